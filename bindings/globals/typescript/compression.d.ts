@@ -0,0 +1,17 @@
+declare type CompressionFormat = "gzip" | "deflate" | "deflate-raw" | "brotli";
+
+declare class CompressionStream {
+	constructor(format: CompressionFormat);
+
+	get readable(): ReadableStream<Uint8Array>;
+
+	get writable(): WritableStream<BufferSource>;
+}
+
+declare class DecompressionStream {
+	constructor(format: CompressionFormat);
+
+	get readable(): ReadableStream<Uint8Array>;
+
+	get writable(): WritableStream<BufferSource>;
+}
@@ -123,6 +123,16 @@ declare class Request {
 	get signal(): AbortSignal;
 
 	get duplex(): RequestDuplex;
+
+	get body(): ReadableStream<Uint8Array> | null;
+
+	get bodyUsed(): boolean;
+
+	arrayBuffer(): Promise<ArrayBuffer>;
+
+	text(): Promise<string>;
+
+	clone(): Request;
 }
 
 declare interface ResponseInit {
@@ -155,6 +165,8 @@ declare class Response {
 	arrayBuffer(): Promise<ArrayBuffer>;
 
 	text(): Promise<string>;
+
+	clone(): Response;
 }
 
 declare function fetch(input: RequestInfo, init?: RequestInit): Promise<Response>;
@@ -6,4 +6,12 @@ declare function clearTimeout(id: number): void;
 
 declare function clearInterval(id: number): void;
 
+declare function setImmediate<T extends any[]>(callback: (...arguments: [...T]) => void, ...arguments: [...T]): number;
+
+declare function clearImmediate(id: number): void;
+
+declare function refTimer(id: number): void;
+
+declare function unrefTimer(id: number): void;
+
 declare function queueMacrotask(callback: () => void): void;
@@ -1,17 +1,51 @@
 declare module "assert" {
+	export type ErrorMatcher = RegExp | Function | ((error: any) => boolean) | object;
+
 	export function ok(assertion?: boolean, message?: string): void;
 
-	export function equals(actual: any, expected: any, message?: string): void;
+	export function equal(actual: any, expected: any, message?: string): void;
+
+	export function notEqual(actual: any, expected: any, message?: string): void;
+
+	export function strictEqual<T>(actual: any, expected: T, message?: string): asserts actual is T;
+
+	export function notStrictEqual(actual: any, expected: any, message?: string): void;
+
+	export function equals<T>(actual: any, expected: T, message?: string): asserts actual is T;
+
+	export function deepEqual<T>(actual: any, expected: T, message?: string): asserts actual is T;
+
+	export function notDeepEqual(actual: any, expected: any, message?: string): void;
 
 	export function throws(func: () => void, message?: string): void;
+	export function throws(func: () => void, expected: ErrorMatcher, message?: string): void;
+
+	export function doesNotThrow(func: () => void, message?: string): void;
+
+	export function rejects(promise: Promise<any> | (() => Promise<any>), message?: string): Promise<void>;
+	export function rejects(promise: Promise<any> | (() => Promise<any>), expected: ErrorMatcher, message?: string): Promise<void>;
+
+	export function match(string: string, regexp: RegExp, message?: string): void;
+
+	export function doesNotMatch(string: string, regexp: RegExp, message?: string): void;
 
-	export function fail(message?: string): void;
+	export function fail(message?: string): never;
 
 	namespace Assert {
 		export {
 			ok,
+			equal,
+			notEqual,
+			strictEqual,
+			notStrictEqual,
 			equals,
+			deepEqual,
+			notDeepEqual,
 			throws,
+			doesNotThrow,
+			rejects,
+			match,
+			doesNotMatch,
 			fail,
 		};
 	}
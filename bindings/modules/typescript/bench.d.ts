@@ -0,0 +1,39 @@
+declare module "bench" {
+	export interface BenchOptions {
+		skip?: boolean;
+		only?: boolean;
+		warmup?: number;
+		iterations?: number;
+		time?: number;
+	}
+
+	export interface RunOptions {
+		filter?: string;
+	}
+
+	export interface BenchResult {
+		name: string;
+		status: "completed" | "failed" | "skipped";
+		iterations?: number;
+		mean?: number;
+		min?: number;
+		max?: number;
+		p75?: number;
+		p99?: number;
+		stddev?: number;
+		error?: string;
+	}
+
+	export function bench(name: string, func: () => void | Promise<void>, options?: BenchOptions): void;
+
+	export function run(options?: RunOptions): Promise<BenchResult[]>;
+
+	namespace Bench {
+		export {
+			bench,
+			run,
+		};
+	}
+
+	export default Bench;
+}
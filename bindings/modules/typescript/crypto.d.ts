@@ -0,0 +1,48 @@
+declare module "crypto" {
+	export type HashAlgorithm =
+		| "md5"
+		| "sha1"
+		| "sha224"
+		| "sha256"
+		| "sha384"
+		| "sha512"
+		| "sha3-224"
+		| "sha3-256"
+		| "sha3-384"
+		| "sha3-512";
+
+	export type HashData = string | ArrayBuffer | ArrayBufferView;
+
+	export type DigestEncoding = "hex" | "base64" | "base64url";
+
+	export class Hash {
+		private constructor();
+
+		get algorithm(): HashAlgorithm;
+
+		update(data: HashData): Hash;
+
+		digest(): Uint8Array;
+		digest(encoding: DigestEncoding): string;
+
+		copy(): Hash;
+	}
+
+	export function createHash(algorithm: HashAlgorithm): Hash;
+
+	export function createHmac(algorithm: HashAlgorithm, key: HashData): Hash;
+
+	export function timingSafeEqual(a: ArrayBufferView, b: ArrayBufferView): boolean;
+
+	namespace Crypto {
+		export {
+			createHash,
+			createHmac,
+			timingSafeEqual,
+
+			Hash,
+		};
+	}
+
+	export default Crypto;
+}
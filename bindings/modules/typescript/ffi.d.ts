@@ -0,0 +1,60 @@
+declare module "ffi" {
+	export type NativeType =
+		| "void"
+		| "bool"
+		| "u8"
+		| "i8"
+		| "u16"
+		| "i16"
+		| "u32"
+		| "i32"
+		| "u64"
+		| "i64"
+		| "usize"
+		| "isize"
+		| "f32"
+		| "f64"
+		| "pointer"
+		| "buffer"
+		| "string";
+
+	export type Pointer = bigint | null;
+
+	export interface ForeignFunction {
+		parameters?: NativeType[];
+		result?: NativeType;
+	}
+
+	export type ForeignLibrary = Record<string, ForeignFunction>;
+
+	export type NativeValue = number | bigint | boolean | string | Pointer | ArrayBuffer | ArrayBufferView | undefined;
+
+	export type Symbols<S extends ForeignLibrary> = {
+		readonly [name in keyof S]: (...args: NativeValue[]) => NativeValue;
+	};
+
+	export class DynamicLibrary<S extends ForeignLibrary = ForeignLibrary> {
+		private constructor();
+
+		readonly symbols: Symbols<S>;
+
+		get path(): string | null;
+
+		close(): void;
+	}
+
+	export const suffix: "so" | "dylib" | "dll";
+
+	export function dlopen<S extends ForeignLibrary>(path: string | null, symbols: S): DynamicLibrary<S>;
+
+	namespace Ffi {
+		export {
+			dlopen,
+			suffix,
+
+			DynamicLibrary,
+		};
+	}
+
+	export default Ffi;
+}
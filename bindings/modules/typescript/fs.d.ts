@@ -1,51 +1,208 @@
 declare module "fs" {
+	export interface FileInfo {
+		isFile: boolean;
+		isDirectory: boolean;
+		isSymlink: boolean;
+		size: number;
+		readonly: boolean;
+		modified: Date | null;
+		accessed: Date | null;
+		created: Date | null;
+		mode?: number;
+	}
+
+	export interface DirEntry {
+		name: string;
+		isFile: boolean;
+		isDirectory: boolean;
+		isSymlink: boolean;
+	}
+
+	export interface FileOptions {
+		read?: boolean;
+		write?: boolean;
+		append?: boolean;
+		truncate?: boolean;
+		create?: boolean;
+		createNew?: boolean;
+		mode?: number;
+	}
+
+	export interface WriteFileOptions {
+		append?: boolean;
+		create?: boolean;
+		createNew?: boolean;
+		mode?: number;
+	}
+
+	export interface RecursiveOptions {
+		recursive?: boolean;
+	}
+
+	export type FileData = string | ArrayBuffer | ArrayBufferView;
+
+	export type SeekMode = "start" | "current" | "end";
+
+	export class FileHandle {
+		private constructor();
+
+		get path(): string;
+
+		read(length?: number): Promise<Uint8Array | null>;
+
+		write(data: FileData): Promise<number>;
+
+		seek(offset: number, mode?: SeekMode): Promise<number>;
+
+		stat(): Promise<FileInfo>;
+
+		sync(): Promise<void>;
+
+		syncData(): Promise<void>;
+
+		truncate(length?: number): Promise<void>;
+
+		close(): Promise<void>;
+	}
+
+	export function readFile(path: string): Promise<Uint8Array>;
+
+	export function readFileSync(path: string): Uint8Array;
+
+	export function readTextFile(path: string): Promise<string>;
+
+	export function readTextFileSync(path: string): string;
+
+	export function writeFile(path: string, data: FileData, options?: WriteFileOptions): Promise<void>;
+
+	export function writeFileSync(path: string, data: FileData, options?: WriteFileOptions): void;
+
+	export function open(path: string, options?: FileOptions): Promise<FileHandle>;
+
+	export function stat(path: string): Promise<FileInfo>;
+
+	export function statSync(path: string): FileInfo;
+
+	export function lstat(path: string): Promise<FileInfo>;
+
+	export function lstatSync(path: string): FileInfo;
+
+	export function readDir(path: string): Promise<AsyncIterableIterator<DirEntry>>;
+
+	export function readDirSync(path: string): DirEntry[];
+
+	export function mkdir(path: string, options?: RecursiveOptions): Promise<void>;
+
+	export function mkdirSync(path: string, options?: RecursiveOptions): void;
+
+	export function rm(path: string, options?: RecursiveOptions): Promise<void>;
+
+	export function rmSync(path: string, options?: RecursiveOptions): void;
+
+	export function rename(from: string, to: string): Promise<void>;
+
+	export function renameSync(from: string, to: string): void;
+
+	export function copyFile(from: string, to: string): Promise<void>;
+
+	export function copyFileSync(from: string, to: string): void;
+
+	export function symlink(original: string, path: string): Promise<void>;
+
+	export function symlinkSync(original: string, path: string): void;
+
+	export function link(original: string, path: string): Promise<void>;
+
+	export function linkSync(original: string, path: string): void;
+
+	export function readlink(path: string): Promise<string>;
+
+	export function readlinkSync(path: string): string;
+
+	/** @deprecated Use the functions above instead. */
 	export function readBinary(path: string): Promise<Uint8Array>;
 
+	/** @deprecated Use the functions above instead. */
 	export function readString(path: string): Promise<string>;
 
-	export function readDir(path: string): Promise<string[]>;
-
+	/** @deprecated Use the functions above instead. */
 	export function write(path: string, contents: string): Promise<boolean>;
 
+	/** @deprecated Use the functions above instead. */
 	export function createDir(path: string): Promise<boolean>;
 
+	/** @deprecated Use the functions above instead. */
 	export function createDirRecursive(path: string): Promise<boolean>;
 
+	/** @deprecated Use the functions above instead. */
 	export function removeFile(path: string): Promise<boolean>;
 
+	/** @deprecated Use the functions above instead. */
 	export function removeDir(path: string): Promise<boolean>;
 
+	/** @deprecated Use the functions above instead. */
 	export function removeDirRecursive(path: string): Promise<boolean>;
 
+	/** @deprecated Use the functions above instead. */
 	export function copy(from: string, to: string): Promise<boolean>;
 
-	export function rename(from: string, to: string): Promise<boolean>;
+	/** @deprecated Use the functions above instead. */
+	export function softLink(original: string, path: string): Promise<boolean>;
 
-	export function softLink(original: string, link: string): Promise<boolean>;
-
-	export function hardLink(original: string, link: string): Promise<boolean>;
+	/** @deprecated Use the functions above instead. */
+	export function hardLink(original: string, path: string): Promise<boolean>;
 
+	/** @deprecated Use the synchronous functions above instead. */
 	export const sync: {
-		readBinary(path: string): Uint8Array,
-		readString(path: string): string,
-		readDir(path: string): string[],
-		write(path: string, contents: string): boolean,
-		createDir(path: string): boolean,
-		createDirRecursive(path: string): boolean,
-		removeFile(path: string): boolean,
-		removeDir(path: string): boolean,
-		removeDirRecursive(path: string): boolean,
-		copy(from: string, to: string): boolean,
-		rename(from: string, to: string): boolean,
-		softLink(original: string, link: string): boolean,
-		hardLink(original: string, link: string): boolean,
+		readBinary(path: string): Uint8Array;
+		readString(path: string): string;
+		readDir(path: string): string[];
+		write(path: string, contents: string): boolean;
+		createDir(path: string): boolean;
+		createDirRecursive(path: string): boolean;
+		removeFile(path: string): boolean;
+		removeDir(path: string): boolean;
+		removeDirRecursive(path: string): boolean;
+		copy(from: string, to: string): boolean;
+		rename(from: string, to: string): boolean;
+		softLink(original: string, path: string): boolean;
+		hardLink(original: string, path: string): boolean;
 	};
 
-	namespace Assert {
+	namespace FileSystem {
 		export {
+			readFile,
+			readFileSync,
+			readTextFile,
+			readTextFileSync,
+			writeFile,
+			writeFileSync,
+			open,
+			stat,
+			statSync,
+			lstat,
+			lstatSync,
+			readDir,
+			readDirSync,
+			mkdir,
+			mkdirSync,
+			rm,
+			rmSync,
+			rename,
+			renameSync,
+			copyFile,
+			copyFileSync,
+			symlink,
+			symlinkSync,
+			link,
+			linkSync,
+			readlink,
+			readlinkSync,
+
+			FileHandle,
+
 			readBinary,
 			readString,
-			readDir,
 			write,
 			createDir,
 			createDirRecursive,
@@ -53,13 +210,11 @@ declare module "fs" {
 			removeDir,
 			removeDirRecursive,
 			copy,
-			rename,
 			softLink,
 			hardLink,
-
 			sync,
 		};
 	}
 
-	export default Assert;
+	export default FileSystem;
 }
@@ -0,0 +1,74 @@
+declare module "http" {
+	import { Address } from "net";
+
+	export interface ServerTlsOptions {
+		cert: string;
+		key: string;
+	}
+
+	export interface ServeOptions {
+		port?: number;
+		hostname?: string;
+		tls?: ServerTlsOptions;
+		signal?: AbortSignal;
+	}
+
+	export interface UpgradeOptions {
+		protocol?: string;
+	}
+
+	export interface WebSocketUpgrade {
+		socket: WebSocket;
+		response: Response;
+	}
+
+	export type Handler = (request: Request) => Response | Promise<Response>;
+
+	export class Server {
+		private constructor();
+
+		get addr(): Address;
+		get finished(): Promise<void>;
+
+		shutdown(): Promise<void>;
+	}
+
+	export class WebSocket extends EventTarget {
+		private constructor();
+
+		get url(): string;
+		get protocol(): string;
+		get readyState(): number;
+		get binaryType(): "arraybuffer";
+
+		onopen: ((event: Event) => void) | null;
+		onmessage: ((event: Event & { data: string | ArrayBuffer }) => void) | null;
+		onerror: ((event: Event) => void) | null;
+		onclose: ((event: Event & { code: number, reason: string, wasClean: boolean }) => void) | null;
+
+		send(data: string | ArrayBuffer | ArrayBufferView): void;
+
+		close(code?: number, reason?: string): void;
+
+		static get CONNECTING(): 0;
+		static get OPEN(): 1;
+		static get CLOSING(): 2;
+		static get CLOSED(): 3;
+	}
+
+	export function serve(handler: Handler, options?: ServeOptions): Server;
+
+	export function upgradeWebSocket(request: Request, options?: UpgradeOptions): WebSocketUpgrade;
+
+	namespace Http {
+		export {
+			serve,
+			upgradeWebSocket,
+
+			Server,
+			WebSocket,
+		};
+	}
+
+	export default Http;
+}
@@ -0,0 +1,68 @@
+declare module "kv" {
+	export type KeyPart = string | number | bigint | boolean | Uint8Array;
+
+	export type Key = KeyPart[];
+
+	export interface Entry<T = unknown> {
+		key: Key;
+		value: T | null;
+		versionstamp: string | null;
+	}
+
+	export type Selector = { prefix: Key; start?: Key; end?: Key } | { start: Key; end: Key };
+
+	export interface ListOptions {
+		limit?: number;
+		reverse?: boolean;
+	}
+
+	export interface Check {
+		key: Key;
+		versionstamp: string | null;
+	}
+
+	export type CommitResult = { ok: true; versionstamp: string } | { ok: false };
+
+	export class Store {
+		private constructor();
+
+		get path(): string;
+
+		get<T = unknown>(key: Key): Promise<Entry<T>>;
+
+		set(key: Key, value: unknown): Promise<{ ok: true; versionstamp: string }>;
+
+		delete(key: Key): Promise<void>;
+
+		list<T = unknown>(selector: Selector, options?: ListOptions): Promise<Entry<T>[]>;
+
+		atomic(): AtomicOperation;
+
+		close(): Promise<void>;
+	}
+
+	export class AtomicOperation {
+		private constructor();
+
+		check(...checks: Check[]): this;
+
+		set(key: Key, value: unknown): this;
+
+		delete(key: Key): this;
+
+		commit(): Promise<CommitResult>;
+	}
+
+	export function open(path?: string): Promise<Store>;
+
+	namespace Kv {
+		export {
+			open,
+
+			Store,
+			AtomicOperation,
+		};
+	}
+
+	export default Kv;
+}
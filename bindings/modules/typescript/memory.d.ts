@@ -0,0 +1,20 @@
+declare module "memory" {
+	export interface MemoryUsage {
+		heapUsed: number;
+		heapTotal: number;
+		rss: number;
+	}
+
+	export function usage(): MemoryUsage;
+
+	export function takeHeapSnapshot(path: string): void;
+
+	namespace Memory {
+		export {
+			usage,
+			takeHeapSnapshot,
+		};
+	}
+
+	export default Memory;
+}
@@ -0,0 +1,113 @@
+declare module "net" {
+	export interface Address {
+		hostname: string;
+		port: number;
+		family: "IPv4" | "IPv6";
+	}
+
+	export interface TlsOptions {
+		hostname?: string;
+		caCerts?: string[];
+		alpnProtocols?: string[];
+		certChain?: string;
+		privateKey?: string;
+	}
+
+	export interface PeerCertificate {
+		subject: string;
+		issuer: string;
+		serialNumber: string;
+		validFrom: Date;
+		validTo: Date;
+		subjectAltNames: string[];
+		raw: Uint8Array;
+	}
+
+	export interface ConnectOptions {
+		tls?: TlsOptions;
+		signal?: AbortSignal;
+	}
+
+	export interface ListenOptions {
+		hostname?: string;
+		signal?: AbortSignal;
+	}
+
+	export interface DatagramOptions {
+		hostname?: string;
+		broadcast?: boolean;
+		multicastLoop?: boolean;
+		signal?: AbortSignal;
+	}
+
+	export interface Datagram {
+		data: Uint8Array;
+		addr: Address;
+	}
+
+	export class Conn {
+		private constructor();
+
+		get readable(): ReadableStream<Uint8Array>;
+		get writable(): WritableStream<Uint8Array>;
+
+		get localAddr(): Address;
+		get remoteAddr(): Address;
+
+		get alpnProtocol(): string | null;
+		peerCertificates(): PeerCertificate[];
+
+		startTls(options?: TlsOptions): Promise<Conn>;
+
+		close(): Promise<void>;
+	}
+
+	export class Listener implements AsyncIterable<Conn> {
+		private constructor();
+
+		get addr(): Address;
+
+		accept(): Promise<Conn | null>;
+
+		close(): void;
+
+		[Symbol.asyncIterator](): AsyncIterableIterator<Conn>;
+	}
+
+	export class DatagramSocket implements AsyncIterable<Datagram> {
+		private constructor();
+
+		get addr(): Address;
+
+		send(data: ArrayBufferView, hostname: string, port: number): Promise<number>;
+		receive(): Promise<Datagram | null>;
+
+		joinMulticast(group: string, iface?: string): void;
+		leaveMulticast(group: string, iface?: string): void;
+		setBroadcast(broadcast: boolean): void;
+
+		close(): void;
+
+		[Symbol.asyncIterator](): AsyncIterableIterator<Datagram>;
+	}
+
+	export function connect(hostname: string, port: number, options?: ConnectOptions): Promise<Conn>;
+
+	export function listen(port: number, options?: ListenOptions): Listener;
+
+	export function listenDatagram(port: number, options?: DatagramOptions): DatagramSocket;
+
+	namespace Net {
+		export {
+			connect,
+			listen,
+			listenDatagram,
+
+			Conn,
+			Listener,
+			DatagramSocket,
+		};
+	}
+
+	export default Net;
+}
@@ -0,0 +1,62 @@
+declare module "os" {
+	export interface CpuTimes {
+		user: number;
+		nice: number;
+		sys: number;
+		idle: number;
+		irq: number;
+	}
+
+	export interface CpuInfo {
+		model: string;
+		speed: number;
+		times: CpuTimes | null;
+	}
+
+	export interface NetworkInterfaceAddress {
+		address: string;
+		netmask: string;
+		family: "IPv4" | "IPv6";
+		internal: boolean;
+		cidr: string;
+	}
+
+	export const platform: string;
+	export const arch: string;
+	export const EOL: string;
+
+	export function hostname(): string | null;
+
+	export function cpus(): CpuInfo[];
+
+	export function totalmem(): number;
+
+	export function freemem(): number;
+
+	export function uptime(): number;
+
+	export function homedir(): string | null;
+
+	export function tmpdir(): string;
+
+	export function networkInterfaces(): Record<string, NetworkInterfaceAddress[]>;
+
+	namespace OperatingSystem {
+		export {
+			platform,
+			arch,
+			EOL,
+
+			hostname,
+			cpus,
+			totalmem,
+			freemem,
+			uptime,
+			homedir,
+			tmpdir,
+			networkInterfaces,
+		};
+	}
+
+	export default OperatingSystem;
+}
@@ -1,6 +1,46 @@
 declare module "path" {
+	export interface ParsedPath {
+		root: string;
+		dir: string;
+		base: string;
+		ext: string;
+		name: string;
+	}
+
+	export interface PathFlavour {
+		join(...segments: string[]): string;
+		resolve(...segments: string[]): string;
+		normalize(path: string): string;
+		relative(from: string, to: string): string;
+		dirname(path: string): string;
+		basename(path: string, extension?: string): string;
+		extname(path: string): string;
+		isAbsolute(path: string): boolean;
+		parse(path: string): ParsedPath;
+		format(path: Partial<ParsedPath>): string;
+
+		readonly separator: string;
+		readonly delimiter: string;
+	}
+
 	export function join(...segments: string[]): string;
 
+	export function resolve(...segments: string[]): string;
+
+	export function normalize(path: string): string;
+
+	export function relative(from: string, to: string): string;
+
+	export function dirname(path: string): string;
+
+	export function basename(path: string, extension?: string): string;
+
+	export function extname(path: string): string;
+
+	export function parse(path: string): ParsedPath;
+
+	export function format(path: Partial<ParsedPath>): string;
+
 	export function stripPrefix(path: string, prefix: string): string;
 
 	export function fileStem(path: string): string | null;
@@ -28,9 +68,20 @@ declare module "path" {
 	export const separator: string;
 	export const delimiter: string;
 
+	export const posix: PathFlavour;
+	export const win32: PathFlavour;
+
 	namespace Path {
 		export {
 			join,
+			resolve,
+			normalize,
+			relative,
+			dirname,
+			basename,
+			extname,
+			parse,
+			format,
 			stripPrefix,
 			fileStem,
 			parent,
@@ -46,6 +97,9 @@ declare module "path" {
 
 			separator,
 			delimiter,
+
+			posix,
+			win32,
 		};
 	}
 
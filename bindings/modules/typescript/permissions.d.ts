@@ -0,0 +1,35 @@
+declare module "permissions" {
+	export type PermissionName = "read" | "write" | "net" | "env" | "run" | "ffi" | "hrtime";
+
+	export type PermissionState = "granted" | "prompt" | "denied";
+
+	export type PermissionDescriptor =
+		| { name: "read"; path?: string }
+		| { name: "write"; path?: string }
+		| { name: "net"; host?: string }
+		| { name: "env"; variable?: string }
+		| { name: "run"; command?: string }
+		| { name: "ffi" }
+		| { name: "hrtime" };
+
+	export interface PermissionStatus {
+		name: PermissionName;
+		state: PermissionState;
+	}
+
+	export function query(descriptor: PermissionDescriptor): Promise<PermissionStatus>;
+
+	export function request(descriptor: PermissionDescriptor): Promise<PermissionStatus>;
+
+	export function revoke(descriptor: PermissionDescriptor): Promise<PermissionStatus>;
+
+	namespace Permissions {
+		export {
+			query,
+			request,
+			revoke,
+		};
+	}
+
+	export default Permissions;
+}
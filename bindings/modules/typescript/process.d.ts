@@ -0,0 +1,45 @@
+declare module "process" {
+	export const args: string[];
+	export const env: Record<string, string | undefined>;
+	export const pid: number;
+	export const platform: string;
+	export const arch: string;
+
+	export const stdin: ReadableStream<Uint8Array>;
+	export const stdout: WritableStream<Uint8Array>;
+	export const stderr: WritableStream<Uint8Array>;
+
+	export function exit(code?: number): never;
+
+	export function cwd(): string;
+
+	export function chdir(path: string): void;
+
+	export type Signal = "SIGINT" | "SIGTERM" | "SIGHUP";
+
+	export function addSignalListener(signal: Signal, listener: (signal: Signal) => void): void;
+
+	export function removeSignalListener(signal: Signal, listener: (signal: Signal) => void): void;
+
+	namespace Process {
+		export let exitCode: number;
+
+		export {
+			args,
+			env,
+			pid,
+			platform,
+			arch,
+			stdin,
+			stdout,
+			stderr,
+			exit,
+			cwd,
+			chdir,
+			addSignalListener,
+			removeSignalListener,
+		};
+	}
+
+	export default Process;
+}
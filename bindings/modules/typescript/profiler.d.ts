@@ -0,0 +1,27 @@
+declare module "profiler" {
+	export type ProfileFormat = "speedscope" | "folded";
+
+	export interface StartOptions {
+		interval?: number;
+	}
+
+	export interface StopOptions {
+		format?: ProfileFormat;
+	}
+
+	export function start(options?: StartOptions): void;
+
+	export function stop(options?: StopOptions): string;
+
+	export function isRunning(): boolean;
+
+	namespace Profiler {
+		export {
+			start,
+			stop,
+			isRunning,
+		};
+	}
+
+	export default Profiler;
+}
@@ -0,0 +1,49 @@
+declare module "runtime" {
+	export type HandleType =
+		| "timer"
+		| "interval"
+		| "immediate"
+		| "macrotask"
+		| "fetch"
+		| "socket"
+		| "server"
+		| "subprocess"
+		| "stream"
+		| "module"
+		| "port"
+		| "promise"
+		| "other";
+
+	export interface ActiveHandle {
+		type: HandleType;
+		description: string;
+		stack?: string;
+	}
+
+	export function activeHandles(): ActiveHandle[];
+
+	export type DiagnosticLevel = "error" | "warn" | "info" | "debug";
+
+	export interface Diagnostic {
+		target: string;
+		level: DiagnosticLevel;
+		message: string;
+		fields: Record<string, string>;
+		timestamp: number;
+	}
+
+	export namespace diagnostics {
+		export function subscribe(callback: (diagnostic: Diagnostic) => void): void;
+
+		export function unsubscribe(callback: (diagnostic: Diagnostic) => void): boolean;
+	}
+
+	namespace Runtime {
+		export {
+			activeHandles,
+			diagnostics,
+		};
+	}
+
+	export default Runtime;
+}
@@ -0,0 +1,56 @@
+declare module "sqlite" {
+	export type SqlValue = null | number | bigint | string | Uint8Array;
+
+	export type BindValue = SqlValue | boolean | undefined | ArrayBuffer | ArrayBufferView;
+
+	export type Parameters = BindValue[] | Record<string, BindValue>;
+
+	export interface OpenOptions {
+		readonly?: boolean;
+		create?: boolean;
+	}
+
+	export interface RunResult {
+		changes: number;
+		lastInsertRowid: number | bigint;
+	}
+
+	export class Database {
+		private constructor();
+
+		get path(): string;
+
+		exec(sql: string): Promise<void>;
+
+		prepare(sql: string): Promise<Statement>;
+
+		transaction<T>(callback: (nested: <U>(callback: () => U | Promise<U>) => Promise<U>) => T | Promise<T>): Promise<T>;
+
+		close(): Promise<void>;
+	}
+
+	export class Statement {
+		private constructor();
+
+		get sql(): string;
+		get columns(): string[];
+
+		all(parameters?: Parameters): Promise<Record<string, SqlValue>[]>;
+		values(parameters?: Parameters): Promise<SqlValue[][]>;
+		get(parameters?: Parameters): Promise<Record<string, SqlValue> | null>;
+		run(parameters?: Parameters): Promise<RunResult>;
+	}
+
+	export function open(path: string, options?: OpenOptions): Promise<Database>;
+
+	namespace Sqlite {
+		export {
+			open,
+
+			Database,
+			Statement,
+		};
+	}
+
+	export default Sqlite;
+}
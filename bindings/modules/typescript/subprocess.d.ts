@@ -0,0 +1,53 @@
+declare module "subprocess" {
+	export type StdioMode = "piped" | "inherit" | "null";
+
+	export type KillSignal = "SIGHUP" | "SIGINT" | "SIGQUIT" | "SIGKILL" | "SIGTERM";
+
+	export interface SpawnOptions {
+		stdio?: StdioMode;
+		env?: Record<string, string>;
+		clearEnv?: boolean;
+		cwd?: string;
+	}
+
+	export interface ChildStatus {
+		success: boolean;
+		code: number | null;
+		signal: number | null;
+	}
+
+	export interface ProcessOutput {
+		status: ChildStatus;
+		stdout: Uint8Array;
+		stderr: Uint8Array;
+	}
+
+	export class Child {
+		private constructor();
+
+		get pid(): number | null;
+
+		get stdin(): WritableStream<Uint8Array> | null;
+		get stdout(): ReadableStream<Uint8Array> | null;
+		get stderr(): ReadableStream<Uint8Array> | null;
+
+		status(): Promise<ChildStatus>;
+
+		kill(signal?: KillSignal): void;
+	}
+
+	export function spawn(program: string, args?: string[], options?: SpawnOptions): Child;
+
+	export function output(program: string, args?: string[], options?: SpawnOptions): Promise<ProcessOutput>;
+
+	namespace Subprocess {
+		export {
+			spawn,
+			output,
+
+			Child,
+		};
+	}
+
+	export default Subprocess;
+}
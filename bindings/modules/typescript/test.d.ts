@@ -0,0 +1,63 @@
+declare module "test" {
+	export interface TestOptions {
+		skip?: boolean;
+		only?: boolean;
+		timeout?: number;
+	}
+
+	export interface GroupOptions {
+		skip?: boolean;
+		only?: boolean;
+	}
+
+	export interface RunOptions {
+		filter?: string;
+	}
+
+	export interface TestResult {
+		name: string;
+		status: "passed" | "failed" | "skipped";
+		duration: number;
+		error?: string;
+	}
+
+	export interface Summary {
+		passed: number;
+		failed: number;
+		skipped: number;
+		results: TestResult[];
+	}
+
+	export type TestFunction = () => void | Promise<void>;
+
+	export function test(name: string, func: TestFunction, options?: TestOptions): void;
+
+	export function it(name: string, func: TestFunction, options?: TestOptions): void;
+
+	export function describe(name: string, func: () => void, options?: GroupOptions): void;
+
+	export function beforeAll(func: TestFunction): void;
+
+	export function afterAll(func: TestFunction): void;
+
+	export function beforeEach(func: TestFunction): void;
+
+	export function afterEach(func: TestFunction): void;
+
+	export function run(options?: RunOptions): Promise<Summary>;
+
+	namespace Test {
+		export {
+			test,
+			it,
+			describe,
+			beforeAll,
+			afterAll,
+			beforeEach,
+			afterEach,
+			run,
+		};
+	}
+
+	export default Test;
+}
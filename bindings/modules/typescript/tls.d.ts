@@ -0,0 +1,17 @@
+declare module "tls" {
+	import { Conn, TlsOptions } from "net";
+
+	export interface ConnectTlsOptions extends TlsOptions {
+		signal?: AbortSignal;
+	}
+
+	export function connectTls(hostname: string, port: number, options?: ConnectTlsOptions): Promise<Conn>;
+
+	namespace Tls {
+		export {
+			connectTls,
+		};
+	}
+
+	export default Tls;
+}
@@ -0,0 +1,28 @@
+declare module "zlib" {
+	export type Format = "gzip" | "deflate" | "deflate-raw" | "brotli";
+
+	export type Data = string | ArrayBuffer | ArrayBufferView;
+
+	export interface CompressOptions {
+		level?: number;
+	}
+
+	export function compress(data: Data, format?: Format, options?: CompressOptions): Promise<Uint8Array>;
+
+	export function compressSync(data: Data, format?: Format, options?: CompressOptions): Uint8Array;
+
+	export function decompress(data: Data, format?: Format): Promise<Uint8Array>;
+
+	export function decompressSync(data: Data, format?: Format): Uint8Array;
+
+	namespace Zlib {
+		export {
+			compress,
+			compressSync,
+			decompress,
+			decompressSync,
+		};
+	}
+
+	export default Zlib;
+}
@@ -0,0 +1,208 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::cell::RefCell;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use colored::Colorize;
+use mozjs::rust::{JSEngine, JSEngineHandle, Runtime as RustRuntime};
+
+use ion::Context;
+use modules::{BenchOutcome, BenchResult, Modules, run_benchmarks, Statistics};
+use runtime::RuntimeBuilder;
+use runtime::modules::Loader;
+use runtime::options::ContextOptions;
+
+use crate::commands::test::{evaluate, find_files, format_report, indent, json_string};
+
+const SUFFIXES: &[&str] = &[".bench", "_bench"];
+
+/// Represents the results of the benchmarks of a file, and the errors which occurred outside of them.
+struct FileReport {
+	path: PathBuf,
+	results: Vec<BenchResult>,
+	errors: Vec<String>,
+}
+
+/// Runs the benchmarks in the files matched by `patterns`, one file at a time so that they do not affect each other's measurements.
+/// Results are printed as a table for each file, or as a single JSON document if `json` is set.
+/// Returns `false` if any benchmark failed, or any file could not be run.
+pub(crate) async fn run(patterns: &[String], filter: Option<String>, json: bool, options: ContextOptions) -> bool {
+	let files = find_files(patterns, SUFFIXES);
+	if files.is_empty() {
+		eprintln!("No benchmark files were found");
+		return false;
+	}
+
+	let engine = JSEngine::init().unwrap();
+	let mut reports = Vec::new();
+	for path in files {
+		let report = run_file(engine.handle(), options, path, filter.clone()).await;
+		if !json {
+			print_table(&report);
+		}
+		reports.push(report);
+	}
+
+	if json {
+		let files: Vec<_> = reports.iter().map(json_file).collect();
+		println!("{{\"files\":[{}]}}", files.join(","));
+	}
+	reports
+		.iter()
+		.all(|report| report.errors.is_empty() && report.results.iter().all(|result| !matches!(result.outcome, BenchOutcome::Failed(_))))
+}
+
+async fn run_file(engine: JSEngineHandle, options: ContextOptions, path: PathBuf, filter: Option<String>) -> FileReport {
+	let rt = RustRuntime::new(engine.clone());
+	let cx = &mut Context::from_runtime(&rt);
+	let rt = RuntimeBuilder::new()
+		.microtask_queue()
+		.macrotask_queue()
+		.modules(Loader::default())
+		.standard_modules(Modules)
+		.workers(engine)
+		.options(options)
+		.build(cx);
+
+	let mut errors = Vec::new();
+	let results = Rc::new(RefCell::new(Vec::new()));
+	if let Err(error) = evaluate(&rt, &path).await {
+		errors.push(error);
+	} else {
+		let collected = Rc::clone(&results);
+		run_benchmarks(rt.cx(), filter, move |result| collected.borrow_mut().push(result.clone()));
+		if let Err(report) = rt.run_event_loop().await {
+			errors.push(format_report(rt.cx(), report));
+		}
+	}
+	rt.shutdown();
+
+	let results = results.take();
+	FileReport { path, results, errors }
+}
+
+/// Formats a duration in milliseconds with the most readable unit.
+fn format_time(milliseconds: f64) -> String {
+	if milliseconds < 0.001 {
+		format!("{:.2} ns", milliseconds * 1_000_000.0)
+	} else if milliseconds < 1.0 {
+		format!("{:.2} µs", milliseconds * 1000.0)
+	} else if milliseconds < 1000.0 {
+		format!("{:.2} ms", milliseconds)
+	} else {
+		format!("{:.2} s", milliseconds / 1000.0)
+	}
+}
+
+/// Prints the results of a file as a table, comparing the mean of each benchmark to the fastest benchmark.
+fn print_table(report: &FileReport) {
+	println!("{}", report.path.display().to_string().bold());
+
+	let fastest = report
+		.results
+		.iter()
+		.filter_map(|result| match &result.outcome {
+			BenchOutcome::Completed(statistics) => Some(statistics.mean),
+			_ => None,
+		})
+		.min_by(f64::total_cmp);
+
+	let header = ["benchmark", "iterations", "mean", "p75", "p99", "stddev", "comparison"].map(String::from);
+	let mut rows = vec![header];
+	for result in &report.results {
+		if let BenchOutcome::Completed(statistics) = &result.outcome {
+			rows.push(row(&result.name, statistics, fastest));
+		}
+	}
+
+	if rows.len() > 1 {
+		let widths: Vec<_> = (0..rows[0].len())
+			.map(|column| rows.iter().map(|row| row[column].chars().count()).max().unwrap_or(0))
+			.collect();
+		for (index, row) in rows.iter().enumerate() {
+			let cells: Vec<_> = row
+				.iter()
+				.zip(&widths)
+				.enumerate()
+				.map(|(column, (cell, width))| {
+					if column == 0 {
+						format!("{:<width$}", cell, width = width)
+					} else {
+						format!("{:>width$}", cell, width = width)
+					}
+				})
+				.collect();
+			let line = format!("  {}", cells.join("  "));
+			if index == 0 {
+				println!("{}", line.dimmed());
+			} else {
+				println!("{}", line);
+			}
+		}
+	}
+
+	for result in &report.results {
+		match &result.outcome {
+			BenchOutcome::Failed(error) => {
+				println!("  {} {}", "✗".red(), result.name);
+				println!("{}", indent(error, 4));
+			}
+			BenchOutcome::Skipped => println!("  {} {} {}", "-".yellow(), result.name, "(skipped)".dimmed()),
+			BenchOutcome::Completed(_) => {}
+		}
+	}
+	for error in &report.errors {
+		println!("  {} {}", "✗".red(), "Failed to run file".red());
+		println!("{}", indent(error, 4));
+	}
+	println!();
+}
+
+fn row(name: &str, statistics: &Statistics, fastest: Option<f64>) -> [String; 7] {
+	let comparison = match fastest {
+		Some(fastest) if statistics.mean > fastest && fastest > 0.0 => format!("{:.2}x slower", statistics.mean / fastest),
+		_ => String::from("fastest"),
+	};
+	[
+		String::from(name),
+		statistics.iterations.to_string(),
+		format_time(statistics.mean),
+		format_time(statistics.p75),
+		format_time(statistics.p99),
+		format!("± {}", format_time(statistics.stddev)),
+		comparison,
+	]
+}
+
+fn json_file(report: &FileReport) -> String {
+	let results: Vec<_> = report.results.iter().map(json_result).collect();
+	let errors: Vec<_> = report.errors.iter().map(|error| json_string(error)).collect();
+	format!(
+		"{{\"path\":{},\"benchmarks\":[{}],\"errors\":[{}]}}",
+		json_string(&report.path.display().to_string()),
+		results.join(","),
+		errors.join(",")
+	)
+}
+
+fn json_result(result: &BenchResult) -> String {
+	let fields = match &result.outcome {
+		BenchOutcome::Completed(statistics) => format!(
+			",\"iterations\":{},\"mean\":{},\"min\":{},\"max\":{},\"p75\":{},\"p99\":{},\"stddev\":{}",
+			statistics.iterations, statistics.mean, statistics.min, statistics.max, statistics.p75, statistics.p99, statistics.stddev
+		),
+		BenchOutcome::Failed(error) => format!(",\"error\":{}", json_string(error)),
+		BenchOutcome::Skipped => String::new(),
+	};
+	format!(
+		"{{\"name\":{},\"status\":\"{}\"{}}}",
+		json_string(&result.name),
+		result.outcome.as_str(),
+		fields
+	)
+}
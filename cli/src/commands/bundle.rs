@@ -0,0 +1,58 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::fs::write;
+use std::path::{Path, PathBuf};
+
+use mozjs::rust::{JSEngine, Runtime};
+
+use ion::Context;
+use runtime::bundler::{Bundle, bundle as bundle_module, BundleOptions};
+use runtime::modules::Loader;
+use runtime::RuntimeBuilder;
+
+/// Bundles the entry module, and writes the bundle to the output file or standard output.
+/// Returns `false` if the bundle could not be created or written.
+pub(crate) fn bundle(entry: &Path, output: Option<PathBuf>, options: BundleOptions) -> bool {
+	let engine = JSEngine::init().unwrap();
+	let rt = Runtime::new(engine.handle());
+
+	let cx = &mut Context::from_runtime(&rt);
+	let rt = RuntimeBuilder::<(), ()>::new().build(cx);
+
+	let Some(bundle) = create_bundle(rt.cx(), entry, options) else {
+		return false;
+	};
+
+	match output {
+		Some(output) => match write(&output, &bundle.code) {
+			Ok(()) => {
+				println!("Bundled {} Modules to {}", bundle.modules.len(), output.display());
+				true
+			}
+			Err(error) => {
+				eprintln!("Failed to Save Bundle: {}", error);
+				false
+			}
+		},
+		None => {
+			print!("{}", bundle.code);
+			true
+		}
+	}
+}
+
+/// Bundles the entry module, printing the error if it could not be bundled.
+pub(crate) fn create_bundle(cx: &Context, entry: &Path, options: BundleOptions) -> Option<Bundle> {
+	match bundle_module(cx, &mut Loader::default(), entry, options) {
+		Ok(bundle) => Some(bundle),
+		Err(error) => {
+			eprintln!("Failed to Bundle {}", entry.display());
+			eprintln!("{}", error);
+			None
+		}
+	}
+}
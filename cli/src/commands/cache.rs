@@ -4,11 +4,65 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/.
  */
 
-use std::fs::{metadata, read_dir};
+use std::collections::HashSet;
+use std::ffi::OsStr;
+use std::fs::{metadata, read_dir, read_to_string};
 use std::io;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
-use runtime::cache::Cache;
+use clap::ValueEnum;
+use dunce::canonicalize;
+use mozjs::rust::{JSEngine, Runtime as RustRuntime};
+use url::Url;
+
+use ion::{Context, Exception};
+use ion::module::Module;
+use ion::stencil::Stencil;
+use runtime::cache::{Cache, locate_in_cache, locate_stencil, remove_entry};
+use runtime::modules::{is_remote, Loader, remote_url};
+use runtime::modules::commonjs::is_commonjs;
+use runtime::modules::remote::fetch_imports;
+use runtime::options::ContextOptions;
+use runtime::RuntimeBuilder;
+use runtime::snapshot::Snapshot;
+use runtime::typescript::is_typescript;
+
+/// Represents the kinds of entries in the cache.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub(crate) enum CacheEntry {
+	All,
+	/// Compiled TypeScript and encoded stencils of scripts and modules.
+	Compiled,
+	Remote,
+	Storage,
+	Snapshot,
+}
+
+impl CacheEntry {
+	const KINDS: [CacheEntry; 4] = [CacheEntry::Compiled, CacheEntry::Remote, CacheEntry::Storage, CacheEntry::Snapshot];
+
+	fn of(cache: &Cache, path: &Path) -> CacheEntry {
+		if path == cache.remote_dir() {
+			CacheEntry::Remote
+		} else if path == cache.storage_dir() {
+			CacheEntry::Storage
+		} else if Snapshot::default_path().is_some_and(|snapshot| path == snapshot) {
+			CacheEntry::Snapshot
+		} else {
+			CacheEntry::Compiled
+		}
+	}
+
+	fn name(&self) -> &'static str {
+		match self {
+			CacheEntry::All => "All",
+			CacheEntry::Compiled => "Compiled Modules",
+			CacheEntry::Remote => "Remote Modules",
+			CacheEntry::Storage => "Storage",
+			CacheEntry::Snapshot => "Snapshot",
+		}
+	}
+}
 
 pub(crate) fn cache_statistics() {
 	if let Some(cache) = Cache::new() {
@@ -17,11 +71,161 @@ pub(crate) fn cache_statistics() {
 			Ok(size) => println!("Size: {}", format_size(size)),
 			Err(err) => eprintln!("Error while Calculating Size: {}", err),
 		}
+
+		match entry_sizes(&cache) {
+			Ok(sizes) => {
+				for (entry, size) in CacheEntry::KINDS.iter().zip(sizes) {
+					println!("  {}: {}", entry.name(), format_size(size));
+				}
+			}
+			Err(err) => eprintln!("Error while Calculating Size: {}", err),
+		}
 	} else {
 		println!("No Cache Found");
 	}
 }
 
+/// Removes the entries of the cache of the given kind.
+/// Returns `false` if they could not be removed.
+pub(crate) fn clear_cache(entry: CacheEntry) -> bool {
+	let Some(cache) = Cache::new() else {
+		eprintln!("No Cache Found");
+		return false;
+	};
+
+	let result = if entry == CacheEntry::All {
+		cache.clear()
+	} else {
+		entries(&cache).and_then(|entries| {
+			entries
+				.into_iter()
+				.filter(|path| CacheEntry::of(&cache, path) == entry)
+				.try_for_each(|path| remove_entry(&path))
+		})
+	};
+	match result {
+		Ok(()) => {
+			println!("Cleared {} from the Cache", entry.name());
+			true
+		}
+		Err(err) => {
+			eprintln!("Error while Clearing the Cache: {}", err);
+			false
+		}
+	}
+}
+
+/// Downloads the remote modules, and compiles the modules in the module graphs of the given files into the cache.
+/// Returns `false` if any module could not be cached.
+pub(crate) async fn cache_modules(paths: &[PathBuf], options: ContextOptions) -> bool {
+	let engine = JSEngine::init().unwrap();
+	let rt = RustRuntime::new(engine.handle());
+
+	let cx = &mut Context::from_runtime(&rt);
+	let rt = RuntimeBuilder::<(), ()>::new().options(options).build(cx);
+
+	let mut loader = Loader::default();
+	let mut cached = HashSet::new();
+	for path in paths {
+		if let Err(error) = cache_graph(rt.cx(), &mut loader, path, &mut cached).await {
+			eprintln!("Failed to Cache {}", path.display());
+			eprintln!("{}", error);
+			return false;
+		}
+	}
+	println!("Cached {} Modules", cached.len());
+	true
+}
+
+/// Caches the module graph of a file, adding the paths of its modules to `cached`.
+/// Modules which are already in `cached` are not cached again.
+async fn cache_graph(cx: &Context, loader: &mut Loader, entry: &Path, cached: &mut HashSet<PathBuf>) -> Result<(), String> {
+	let mut queue = vec![entry.to_path_buf()];
+	while let Some(path) = queue.pop() {
+		let path = canonicalize(&path).map_err(|error| format!("Unable to read module {}: {}", path.display(), error))?;
+		if !cached.insert(path.clone()) {
+			continue;
+		}
+		let Some(stencil) = compile_module(cx, &path)? else {
+			continue;
+		};
+
+		let module = stencil.to_module(cx).map_err(|report| report.format(cx))?;
+		let specifiers = Module(module).requested_specifiers(cx);
+		// Remote imports are fetched, along with their own imports, before they are resolved.
+		// Imports of remote modules are already fetched with them.
+		if remote_url(cx, &path).is_none() {
+			fetch_remote_imports(cx, loader, &path, &specifiers).await?;
+		}
+
+		for specifier in specifiers {
+			match loader.resolve_specifier(cx, &specifier, Some(&path)) {
+				Some(dependency) if dependency.is_file() => queue.push(dependency),
+				// Standard modules are not files, and are compiled when the runtime is built.
+				Some(_) => {}
+				None => return Err(exception_message(cx, &specifier, &path)),
+			}
+		}
+	}
+	Ok(())
+}
+
+/// Fetches the remote modules imported by a local module, including those mapped by the import map, and their imports.
+async fn fetch_remote_imports(cx: &Context, loader: &mut Loader, path: &Path, specifiers: &[String]) -> Result<(), String> {
+	let mut urls = Vec::new();
+	for specifier in specifiers {
+		match loader.resolve_url(cx, specifier, Some(path)) {
+			Some(Some(url)) if is_remote(&url) => urls.push(String::from(url)),
+			Some(_) => {}
+			None => return Err(exception_message(cx, specifier, path)),
+		}
+	}
+	let referrer = Url::from_file_path(path).map_err(|_| format!("Invalid module path: {}", path.display()))?;
+	fetch_imports(cx, &referrer, urls).await.map_err(|error| error.format())
+}
+
+/// Returns the message of the pending exception, which was thrown while resolving `specifier`.
+fn exception_message(cx: &Context, specifier: &str, path: &Path) -> String {
+	let message = Exception::new(cx).map(|exception| exception.format(cx));
+	message.unwrap_or_else(|| format!("Unable to resolve \"{}\", imported by {}", specifier, path.display()))
+}
+
+/// Compiles a module into the cache, returning its stencil.
+/// Returns [None] for JSON and CommonJS modules, which are not compiled into stencils.
+fn compile_module(cx: &Context, path: &Path) -> Result<Option<Stencil>, String> {
+	if path.extension() == Some(OsStr::new("json")) {
+		return Ok(None);
+	}
+	let script = read_to_string(path).map_err(|error| format!("Unable to read module {}: {}", path.display(), error))?;
+	if is_commonjs(cx, path, &script) {
+		return Ok(None);
+	}
+
+	let script = if is_typescript(path) {
+		locate_in_cache(path, &script)
+			.map(|(script, _)| script)
+			.ok_or_else(|| format!("Unable to compile TypeScript module {}", path.display()))?
+	} else {
+		script
+	};
+	locate_stencil(cx, path, &script, true).map(Some).map_err(|report| report.format(cx))
+}
+
+fn entries(cache: &Cache) -> io::Result<Vec<PathBuf>> {
+	read_dir(cache.dir())?.map(|entry| entry.map(|entry| entry.path())).collect()
+}
+
+/// Returns the sizes of each kind of entry in the cache, in the order of [CacheEntry::KINDS].
+fn entry_sizes(cache: &Cache) -> io::Result<[u64; 4]> {
+	let mut sizes = [0; 4];
+	for path in entries(cache)? {
+		let entry = CacheEntry::of(cache, &path);
+		let index = CacheEntry::KINDS.iter().position(|kind| *kind == entry).unwrap();
+		sizes[index] += cache_size(&path)?;
+	}
+	Ok(sizes)
+}
+
 fn cache_size(folder: &Path) -> io::Result<u64> {
 	let mut size = 0;
 	let metadata = metadata(folder)?;
@@ -0,0 +1,116 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::env::{args, current_exe};
+use std::env::consts::EXE_SUFFIX;
+use std::iter::once;
+use std::path::{Path, PathBuf};
+use std::process;
+
+use clap::Parser;
+use mozjs::rust::{JSEngine, Runtime};
+
+use ion::Context;
+use modules::Modules;
+use runtime::bundler::BundleOptions;
+use runtime::config::{Config, CONFIG, LogLevel};
+use runtime::modules::StandardModules;
+use runtime::options::ContextOptions;
+use runtime::RuntimeBuilder;
+use runtime::snapshot::Snapshot;
+use runtime::standalone::Standalone;
+
+use crate::commands::bundle::create_bundle;
+use crate::evaluate::eval_standalone;
+use crate::PermissionArgs;
+
+/// Represents the runtime flags embedded in a standalone executable.
+#[derive(Parser)]
+struct Flags {
+	#[command(flatten)]
+	permissions: PermissionArgs,
+}
+
+/// Bundles the entry module, and embeds it into a copy of the current executable, which runs it when started.
+/// The standard modules and the bundle are compiled into an embedded snapshot if `snapshot` is set.
+/// Returns `false` if the executable could not be created.
+pub(crate) fn compile(entry: &Path, output: Option<PathBuf>, snapshot: bool, flags: Vec<String>) -> bool {
+	let stem = entry
+		.file_stem()
+		.map(|stem| stem.to_string_lossy().into_owned())
+		.unwrap_or_else(|| String::from("main"));
+	let output = output.unwrap_or_else(|| PathBuf::from(format!("{}{}", stem, EXE_SUFFIX)));
+	let name = format!("{}.js", stem);
+
+	let engine = JSEngine::init().unwrap();
+	let rt = Runtime::new(engine.handle());
+
+	let cx = &mut Context::from_runtime(&rt);
+	let rt = RuntimeBuilder::<(), ()>::new().build(cx);
+
+	let Some(bundle) = create_bundle(rt.cx(), entry, BundleOptions::default()) else {
+		return false;
+	};
+
+	let snapshot = if snapshot {
+		let mut snapshot = Snapshot::default();
+		if !Modules.snapshot(rt.cx(), &mut snapshot) {
+			eprintln!("Failed to Compile the Standard Modules");
+			return false;
+		}
+		if let Err(report) = snapshot.add_module(rt.cx(), &name, &bundle.code) {
+			eprintln!("Failed to Compile the Bundle");
+			eprintln!("{}", report.format(rt.cx()));
+			return false;
+		}
+		Some(snapshot.to_bytes())
+	} else {
+		None
+	};
+
+	let app = Standalone {
+		name,
+		source: bundle.code,
+		snapshot,
+		flags,
+	};
+	let result = current_exe().and_then(|binary| app.save(&binary, &output));
+	match result {
+		Ok(()) => {
+			println!("Compiled {} Modules to {}", bundle.modules.len(), output.display());
+			true
+		}
+		Err(error) => {
+			eprintln!("Failed to Save Executable: {}", error);
+			false
+		}
+	}
+}
+
+/// Runs the application embedded in the current executable, passing all arguments to it.
+pub(crate) async fn run_standalone(app: Standalone) {
+	let flags = match Flags::try_parse_from(once(String::from("spiderfire")).chain(app.flags.iter().cloned())) {
+		Ok(flags) => flags,
+		Err(error) => {
+			eprintln!("Invalid Embedded Flags: {}", error);
+			process::exit(1);
+		}
+	};
+
+	CONFIG
+		.set(
+			Config::default()
+				.log_level(LogLevel::Error)
+				.main(current_exe().ok())
+				.permissions(flags.permissions.permissions())
+				.args(args().skip(1).collect()),
+		)
+		.unwrap();
+	if let Some(snapshot) = app.snapshot.as_deref().and_then(Snapshot::from_bytes) {
+		Snapshot::set_global(snapshot);
+	}
+	eval_standalone(&app, ContextOptions::default()).await;
+}
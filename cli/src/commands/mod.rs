@@ -4,34 +4,73 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/.
  */
 
-use runtime::cache::Cache;
-use runtime::config::{Config, CONFIG, LogLevel};
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::process;
+use std::time::Duration;
+
+use runtime::bundler::BundleOptions;
+use runtime::config::{Config, CONFIG, InspectOptions, LogLevel, ProfileOptions};
+use runtime::options::ContextOptions;
+use runtime::permissions::PermissionName;
 
 use crate::Command;
 
-mod cache;
+mod bench;
+mod bundle;
+pub(crate) mod cache;
+pub(crate) mod compile;
 mod eval;
 mod repl;
 mod run;
+mod snapshot;
+pub(crate) mod test;
+mod watch;
 
-pub(crate) async fn handle_command(command: Option<Command>) {
+pub(crate) async fn handle_command(command: Option<Command>, options: ContextOptions) {
 	match command {
-		Some(Command::Cache { clear }) => {
-			if !clear {
-				cache::cache_statistics();
-			} else if let Some(cache) = Cache::new() {
-				if let Err(err) = cache.clear() {
-					eprintln!("{}", err);
+		Some(Command::Cache { paths, clear, import_map, reload }) => {
+			if let Some(entry) = clear {
+				if !cache::clear_cache(entry) {
+					process::exit(1);
 				}
+			} else if !paths.is_empty() {
+				CONFIG.set(Config::default().import_map(import_map).reload(reload)).unwrap();
+				if !cache::cache_modules(&paths, options).await {
+					process::exit(1);
+				}
+			} else {
+				cache::cache_statistics();
 			}
 		}
 
 		Some(Command::Eval { source }) => {
 			CONFIG.set(Config::default().log_level(LogLevel::Debug).script(true)).unwrap();
-			eval::eval_source(&source).await;
+			eval::eval_source(&source, options).await;
 		}
 
-		Some(Command::Run { path, log_level, debug, script }) => {
+		Some(Command::Run {
+			path,
+			log_level,
+			debug,
+			script,
+			no_code_cache,
+			import_map,
+			reload,
+			cached_only,
+			snapshot,
+			location,
+			permissions,
+			watch,
+			inspect,
+			inspect_brk,
+			prof,
+			prof_interval,
+			heap_snapshot_on_exit,
+			trace_exit,
+			timeout,
+			args,
+		}) => {
 			let log_level = if debug {
 				LogLevel::Debug
 			} else {
@@ -45,13 +84,107 @@ pub(crate) async fn handle_command(command: Option<Command>) {
 				}
 			};
 
-			CONFIG.set(Config::default().log_level(log_level).script(script)).unwrap();
-			run::run(&path).await;
+			CONFIG
+				.set(
+					Config::default()
+						.log_level(log_level)
+						.script(script)
+						.code_cache(!no_code_cache)
+						.import_map(import_map)
+						.reload(reload)
+						.cached_only(cached_only)
+						.snapshot(snapshot)
+						.main(Some(PathBuf::from(&path)))
+						.location(location)
+						.inspect(inspect_options(inspect, inspect_brk))
+						.profile(prof.map(|output| ProfileOptions {
+							output,
+							interval: Duration::from_secs_f64(prof_interval / 1000.0),
+						}))
+						.heap_snapshot_on_exit(heap_snapshot_on_exit)
+						.trace_exit(trace_exit)
+						.timeout(timeout.map(Duration::from_millis))
+						.permissions(permissions.permissions())
+						.args(args),
+				)
+				.unwrap();
+			if watch {
+				watch::watch(&path, options).await;
+			} else {
+				run::run(&path, options).await;
+			}
+		}
+
+		Some(Command::Test {
+			paths,
+			filter,
+			reporter,
+			jobs,
+			permissions,
+		}) => {
+			CONFIG
+				.set(Config::default().log_level(LogLevel::Debug).permissions(permissions.permissions()))
+				.unwrap();
+			if !test::run(&paths, filter, reporter, jobs, options) {
+				process::exit(1);
+			}
+		}
+
+		Some(Command::Bench { paths, filter, json, permissions }) => {
+			// Benchmarks are measured with high resolution time, since coarsened times would make their results meaningless.
+			let permissions = permissions.permissions().allow(PermissionName::Hrtime, &[]);
+			CONFIG.set(Config::default().log_level(LogLevel::Debug).permissions(permissions)).unwrap();
+			if !bench::run(&paths, filter, json, options).await {
+				process::exit(1);
+			}
+		}
+
+		Some(Command::Bundle {
+			entry,
+			output,
+			import_map,
+			no_tree_shake,
+			no_source_map,
+		}) => {
+			CONFIG.set(Config::default().import_map(import_map)).unwrap();
+			let bundle_options = BundleOptions {
+				tree_shake: !no_tree_shake,
+				source_map: !no_source_map,
+			};
+			if !bundle::bundle(&entry, output, bundle_options) {
+				process::exit(1);
+			}
+		}
+
+		Some(Command::Compile {
+			entry,
+			output,
+			import_map,
+			snapshot,
+			permissions,
+		}) => {
+			CONFIG.set(Config::default().import_map(import_map)).unwrap();
+			if !compile::compile(&entry, output, snapshot, permissions.to_args()) {
+				process::exit(1);
+			}
+		}
+
+		Some(Command::Snapshot { paths, output, script }) => {
+			CONFIG.set(Config::default().script(script)).unwrap();
+			snapshot::build_snapshot(output, &paths);
 		}
 
 		Some(Command::Repl) | None => {
 			CONFIG.set(Config::default().log_level(LogLevel::Debug).script(true)).unwrap();
-			repl::start_repl().await;
+			repl::start_repl(options).await;
 		}
 	}
 }
+
+fn inspect_options(inspect: Option<SocketAddr>, inspect_brk: Option<SocketAddr>) -> Option<InspectOptions> {
+	match (inspect, inspect_brk) {
+		(_, Some(address)) => Some(InspectOptions { address, wait: true }),
+		(Some(address), None) => Some(InspectOptions { address, wait: false }),
+		(None, None) => None,
+	}
+}
@@ -4,26 +4,35 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/.
  */
 
-use mozjs::rust::{JSEngine, Runtime};
+use std::path::Path;
+
+use mozjs::rust::{JSEngine, Runtime as RustRuntime};
 use rustyline::Editor;
 use rustyline::error::ReadlineError;
 
-use ion::Context;
+use ion::{Context, ErrorReport, Exception, Promise};
+use ion::format::Config as FormatConfig;
+use ion::format::format_value;
+use ion::script::Script;
 use modules::Modules;
-use runtime::RuntimeBuilder;
+use runtime::{Runtime, RuntimeBuilder};
+use runtime::options::ContextOptions;
+
+use crate::evaluate::{exit, run_event_loop};
+use crate::repl::{history_path, is_identifier, ReplHelper, rustyline_config};
 
-use crate::evaluate::eval_inline;
-use crate::repl::{ReplHelper, rustyline_config};
+const FILE_NAME: &str = "repl.js";
 
-pub(crate) async fn start_repl() {
+pub(crate) async fn start_repl(options: ContextOptions) {
 	let engine = JSEngine::init().unwrap();
-	let rt = Runtime::new(engine.handle());
+	let rt = RustRuntime::new(engine.handle());
 
 	let cx = &mut Context::from_runtime(&rt);
 	let rt = RuntimeBuilder::<(), _>::new()
 		.microtask_queue()
 		.macrotask_queue()
 		.standard_modules(Modules)
+		.options(options)
 		.build(cx);
 
 	let mut repl = match Editor::with_config(rustyline_config()) {
@@ -33,7 +42,12 @@ pub(crate) async fn start_repl() {
 			return;
 		}
 	};
-	repl.set_helper(Some(ReplHelper));
+	repl.set_helper(Some(ReplHelper::new(rt.cx())));
+
+	let history = history_path();
+	if let Some(history) = &history {
+		let _ = repl.load_history(history);
+	}
 	let mut terminate: u8 = 0;
 
 	loop {
@@ -44,7 +58,12 @@ pub(crate) async fn start_repl() {
 			Err(error) => terminate += handle_error(error),
 		}
 
-		repl.add_history_entry(&input).unwrap();
+		if !input.is_empty() {
+			repl.add_history_entry(&input).unwrap();
+			if let Some(history) = &history {
+				let _ = repl.append_history(history);
+			}
+		}
 
 		if terminate == 1 && input.is_empty() {
 			println!("Press Ctrl+C again or Ctrl+D to exit.");
@@ -55,13 +74,75 @@ pub(crate) async fn start_repl() {
 
 		if !input.is_empty() && input != "exit" {
 			terminate = 0;
-			eval_inline(&rt, &input).await;
+			evaluate(&rt, &input).await;
 		}
 
-		if terminate > 1 || input == "exit" {
+		if terminate > 1 || input == "exit" || rt.has_exited() {
 			break;
 		}
 	}
+	exit(&rt);
+}
+
+/// Evaluates input of the REPL, and prints its result once the event loop has finished.
+///
+/// Input with top-level `await` is evaluated in an async function, whose result is awaited.
+/// Variables declared in such input are only kept if it is a single declaration, such as `const response = await fetch(url)`.
+async fn evaluate(rt: &Runtime<'_>, input: &str) {
+	let cx = rt.cx();
+	let path = Path::new(FILE_NAME);
+	let (result, awaited) = match Script::compile(cx, path, input) {
+		Ok(script) => (script.evaluate(cx), false),
+		Err(report) => match input.contains("await").then(|| compile_async(cx, path, input)).flatten() {
+			Some(script) => (script.evaluate(cx), true),
+			None => (Err(Some(report)), false),
+		},
+	};
+
+	let result = match result {
+		_ if rt.has_exited() => return,
+		Ok(value) if awaited => {
+			let promise = Promise::from(value.to_object(cx).into_local()).unwrap();
+			run_event_loop(rt).await;
+			match promise.settled_result(cx) {
+				Some(Ok(value)) => Ok(value),
+				Some(Err(reason)) => Err(Some(ErrorReport::from_exception_with_error_stack(cx, Exception::from_value(cx, &reason)))),
+				None if rt.has_exited() => return,
+				None => {
+					eprintln!("Top-level await did not settle before the event loop finished");
+					return;
+				}
+			}
+		}
+		result => result,
+	};
+
+	match result {
+		Ok(value) => println!("{}", format_value(cx, FormatConfig::default().quoted(true), &value)),
+		Err(Some(report)) => eprintln!("{}", report.format(cx)),
+		Err(None) => eprintln!("Uncatchable Error"),
+	}
+	run_event_loop(rt).await;
+}
+
+/// Compiles input with top-level `await` as an async function, which is called immediately.
+/// Expressions are returned from the function, and a single declaration is assigned to the global object instead.
+fn compile_async<'cx>(cx: &'cx Context, path: &Path, input: &str) -> Option<Script<'cx>> {
+	let body = input.trim_end().trim_end_matches(';');
+	let sources = [
+		declaration(body).map(|(name, expression)| format!("(async () => {{ globalThis.{} = (\n{}\n); }})()", name, expression)),
+		Some(format!("(async () => (\n{}\n))()", body)),
+		Some(format!("(async () => {{\n{}\n}})()", input)),
+	];
+	sources.into_iter().flatten().find_map(|source| Script::compile(cx, path, &source).ok())
+}
+
+/// Splits a single declaration, such as `let name = expression`, into its name and expression.
+fn declaration(input: &str) -> Option<(&str, &str)> {
+	let rest = ["const ", "let ", "var "].into_iter().find_map(|keyword| input.strip_prefix(keyword))?;
+	let (name, expression) = rest.split_once('=')?;
+	let name = name.trim();
+	(is_identifier(name) && !expression.starts_with('=')).then_some((name, expression))
 }
 
 fn handle_error(error: ReadlineError) -> u8 {
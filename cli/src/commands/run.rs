@@ -7,13 +7,14 @@
 use std::path::Path;
 
 use runtime::config::Config;
+use runtime::options::ContextOptions;
 
 use crate::evaluate::{eval_module, eval_script};
 
-pub(crate) async fn run(path: &str) {
+pub(crate) async fn run(path: &str, options: ContextOptions) {
 	if Config::global().script {
-		eval_script(Path::new(path)).await;
+		eval_script(Path::new(path), options).await;
 	} else {
-		eval_module(Path::new(path)).await;
+		eval_module(Path::new(path), options).await;
 	}
 }
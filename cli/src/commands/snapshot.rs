@@ -0,0 +1,72 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::fs::read_to_string;
+use std::path::{Path, PathBuf};
+
+use dunce::canonicalize;
+use mozjs::rust::{JSEngine, Runtime};
+
+use ion::Context;
+use modules::Modules;
+use runtime::cache::locate_in_cache;
+use runtime::config::Config;
+use runtime::modules::StandardModules;
+use runtime::RuntimeBuilder;
+use runtime::snapshot::Snapshot;
+use runtime::typescript::is_typescript;
+
+pub(crate) fn build_snapshot(output: Option<PathBuf>, paths: &[PathBuf]) {
+	let Some(output) = output.or_else(Snapshot::default_path) else {
+		eprintln!("No Output Path for the Snapshot");
+		return;
+	};
+
+	let engine = JSEngine::init().unwrap();
+	let rt = Runtime::new(engine.handle());
+
+	let cx = &mut Context::from_runtime(&rt);
+	let rt = RuntimeBuilder::<(), ()>::new().build(cx);
+
+	let mut snapshot = Snapshot::default();
+	if !Modules.snapshot(rt.cx(), &mut snapshot) {
+		eprintln!("Failed to Compile the Standard Modules");
+		return;
+	}
+
+	for path in paths {
+		if let Err(error) = add_file(rt.cx(), &mut snapshot, path) {
+			eprintln!("Failed to Add {} to the Snapshot", path.display());
+			eprintln!("{}", error);
+			return;
+		}
+	}
+
+	match snapshot.save(&output) {
+		Ok(()) => println!("Saved Snapshot of {} Modules to {}", snapshot.len(), output.display()),
+		Err(error) => eprintln!("Failed to Save Snapshot: {}", error),
+	}
+}
+
+fn add_file(cx: &Context, snapshot: &mut Snapshot, path: &Path) -> Result<(), String> {
+	let path = canonicalize(path).map_err(|error| error.to_string())?;
+	let script = read_to_string(&path).map_err(|error| error.to_string())?;
+	let script = if is_typescript(&path) {
+		locate_in_cache(&path, &script)
+			.map(|(script, _)| script)
+			.ok_or("Failed to Compile TypeScript")?
+	} else {
+		script
+	};
+
+	let name = path.to_str().ok_or("Invalid Path")?;
+	let result = if Config::global().script {
+		snapshot.add_script(cx, name, &script)
+	} else {
+		snapshot.add_module(cx, name, &script)
+	};
+	result.map_err(|report| report.format(cx))
+}
@@ -0,0 +1,394 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::collections::{BTreeSet, HashMap, VecDeque};
+use std::fs::{read_dir, read_to_string};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::mpsc::{channel, Sender};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use clap::ValueEnum;
+use colored::Colorize;
+use glob::glob;
+use mozjs::rust::{JSEngine, JSEngineHandle, Runtime as RustRuntime};
+use tokio::task::LocalSet;
+
+use ion::{Context, ErrorReport, Exception};
+use ion::module::Module;
+use modules::{Modules, run_tests, TestOutcome, TestResult};
+use runtime::{Runtime, RuntimeBuilder};
+use runtime::modules::Loader;
+use runtime::options::ContextOptions;
+
+use crate::evaluate::cache;
+
+const EXTENSIONS: [&str; 4] = ["js", "mjs", "ts", "mts"];
+const SUFFIXES: &[&str] = &[".test", "_test", ".spec"];
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub(crate) enum Reporter {
+	Pretty,
+	Tap,
+	Json,
+}
+
+enum Event {
+	Result(PathBuf, TestResult),
+	/// The file could not be loaded, or threw outside of its tests.
+	Error(PathBuf, String),
+	Finished(PathBuf),
+}
+
+/// Runs the tests in the files matched by `patterns`, which can be files, directories or glob patterns.
+/// Each file runs in its own runtime, and up to `jobs` files run in parallel on separate threads.
+/// Returns `false` if any test failed, or any file could not be run.
+pub(crate) fn run(patterns: &[String], filter: Option<String>, reporter: Reporter, jobs: usize, options: ContextOptions) -> bool {
+	let files = find_files(patterns, SUFFIXES);
+	if files.is_empty() {
+		eprintln!("No test files were found");
+		return false;
+	}
+
+	let engine = JSEngine::init().unwrap();
+	let queue = Mutex::new(files.iter().cloned().collect::<VecDeque<_>>());
+	let mut report = Report::new(reporter);
+	let start = Instant::now();
+
+	thread::scope(|scope| {
+		let (sender, receiver) = channel();
+		for _ in 0..jobs.clamp(1, files.len()) {
+			let (engine, sender, filter, queue) = (engine.handle(), sender.clone(), filter.clone(), &queue);
+			scope.spawn(move || {
+				while let Some(path) = queue.lock().unwrap().pop_front() {
+					run_file(engine.clone(), options, &path, filter.clone(), &sender);
+					let _ = sender.send(Event::Finished(path));
+				}
+			});
+		}
+		drop(sender);
+
+		report.start();
+		for event in receiver {
+			report.event(event);
+		}
+	});
+	report.finish(start.elapsed())
+}
+
+fn run_file(engine: JSEngineHandle, options: ContextOptions, path: &Path, filter: Option<String>, sender: &Sender<Event>) {
+	let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+	let local = LocalSet::new();
+
+	local.block_on(&runtime, async {
+		let rt = RustRuntime::new(engine.clone());
+		let cx = &mut Context::from_runtime(&rt);
+		let rt = RuntimeBuilder::new()
+			.microtask_queue()
+			.macrotask_queue()
+			.modules(Loader::default())
+			.standard_modules(Modules)
+			.workers(engine)
+			.options(options)
+			.build(cx);
+
+		if let Err(error) = evaluate(&rt, path).await {
+			let _ = sender.send(Event::Error(path.to_path_buf(), error));
+		} else {
+			let (events, file) = (sender.clone(), path.to_path_buf());
+			run_tests(rt.cx(), filter, move |result| {
+				let _ = events.send(Event::Result(file.clone(), result.clone()));
+			});
+			if let Err(report) = rt.run_event_loop().await {
+				let _ = sender.send(Event::Error(path.to_path_buf(), format_report(rt.cx(), report)));
+			}
+		}
+		rt.shutdown();
+	});
+}
+
+/// Evaluates a test or benchmark file as a module, which declares its tests, and waits for its top-level `await` to settle.
+pub(crate) async fn evaluate(rt: &Runtime<'_>, path: &Path) -> Result<(), String> {
+	let script = read_to_string(path).map_err(|error| format!("Failed to read file: {}", error))?;
+	let (script, _) = cache(path, script);
+	let filename = path.file_name().and_then(|name| name.to_str()).unwrap_or_default();
+
+	let (_, promise) = Module::compile(rt.cx(), filename, Some(path), &script).map_err(|error| error.format(rt.cx()))?;
+	if let Some(promise) = promise {
+		rt.run_event_loop().await.map_err(|report| format_report(rt.cx(), report))?;
+		match promise.settled_result(rt.cx()) {
+			Some(Ok(_)) => {}
+			Some(Err(reason)) => {
+				let report = ErrorReport::from_exception_with_error_stack(rt.cx(), Exception::from_value(rt.cx(), &reason));
+				return Err(report.format(rt.cx()));
+			}
+			None => return Err(String::from("Top-level await did not settle before the event loop finished")),
+		}
+	}
+	Ok(())
+}
+
+pub(crate) fn format_report(cx: &Context, report: Option<ErrorReport>) -> String {
+	report
+		.map(|report| report.format(cx))
+		.unwrap_or_else(|| String::from("Uncatchable Error"))
+}
+
+/// Finds the files matched by the patterns, or within the current directory if there are none.
+/// Files within directories are only matched if their names end with one of the suffixes.
+pub(crate) fn find_files(patterns: &[String], suffixes: &[&str]) -> Vec<PathBuf> {
+	let mut files = BTreeSet::new();
+	if patterns.is_empty() {
+		find_in_dir(Path::new("."), suffixes, &mut files);
+	}
+
+	for pattern in patterns {
+		let path = Path::new(pattern);
+		if path.is_file() {
+			files.insert(path.to_path_buf());
+		} else if path.is_dir() {
+			find_in_dir(path, suffixes, &mut files);
+		} else if let Ok(paths) = glob(pattern) {
+			for path in paths.flatten() {
+				if path.is_dir() {
+					find_in_dir(&path, suffixes, &mut files);
+				} else if has_suffix(&path, suffixes) {
+					files.insert(path);
+				}
+			}
+		}
+	}
+	files.into_iter().collect()
+}
+
+/// Recursively finds matching files within a directory, skipping hidden directories and `node_modules`.
+fn find_in_dir(dir: &Path, suffixes: &[&str], files: &mut BTreeSet<PathBuf>) {
+	let Ok(entries) = read_dir(dir) else {
+		return;
+	};
+	for entry in entries.flatten() {
+		let path = entry.path();
+		let name = entry.file_name();
+		let name = name.to_string_lossy();
+		if path.is_dir() {
+			if !name.starts_with('.') && name != "node_modules" {
+				find_in_dir(&path, suffixes, files);
+			}
+		} else if has_suffix(&path, suffixes) {
+			files.insert(path);
+		}
+	}
+}
+
+/// Checks if a script's name ends with one of the suffixes before its extension, such as `math.test.js` or `math_test.ts`.
+fn has_suffix(path: &Path, suffixes: &[&str]) -> bool {
+	let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+		return false;
+	};
+	match name.rsplit_once('.') {
+		Some((stem, extension)) => EXTENSIONS.contains(&extension) && suffixes.iter().any(|suffix| stem.ends_with(suffix)),
+		None => false,
+	}
+}
+
+#[derive(Default)]
+struct FileReport {
+	results: Vec<TestResult>,
+	errors: Vec<String>,
+}
+
+/// Reports the results of tests as they are received, and counts them.
+struct Report {
+	reporter: Reporter,
+	files: HashMap<PathBuf, FileReport>,
+	finished: Vec<PathBuf>,
+	count: usize,
+	passed: usize,
+	failed: usize,
+	skipped: usize,
+}
+
+impl Report {
+	fn new(reporter: Reporter) -> Report {
+		Report {
+			reporter,
+			files: HashMap::new(),
+			finished: Vec::new(),
+			count: 0,
+			passed: 0,
+			failed: 0,
+			skipped: 0,
+		}
+	}
+
+	fn start(&self) {
+		if let Reporter::Tap = self.reporter {
+			println!("TAP version 13");
+		}
+	}
+
+	fn event(&mut self, event: Event) {
+		match event {
+			Event::Result(path, result) => {
+				self.count += 1;
+				match &result.outcome {
+					TestOutcome::Passed => self.passed += 1,
+					TestOutcome::Failed(_) => self.failed += 1,
+					TestOutcome::Skipped => self.skipped += 1,
+				}
+				if let Reporter::Tap = self.reporter {
+					print_tap(self.count, &format!("{} > {}", path.display(), result.name), &result.outcome);
+				}
+				self.files.entry(path).or_default().results.push(result);
+			}
+			Event::Error(path, error) => {
+				self.count += 1;
+				self.failed += 1;
+				if let Reporter::Tap = self.reporter {
+					print_tap(self.count, &path.display().to_string(), &TestOutcome::Failed(error.clone()));
+				}
+				self.files.entry(path).or_default().errors.push(error);
+			}
+			Event::Finished(path) => {
+				if let Reporter::Pretty = self.reporter {
+					print_pretty(&path, self.files.get(&path).unwrap_or(&FileReport::default()));
+				}
+				self.finished.push(path);
+			}
+		}
+	}
+
+	/// Prints the summary of the run, and returns `false` if any test failed.
+	fn finish(mut self, duration: Duration) -> bool {
+		match self.reporter {
+			Reporter::Pretty => {
+				let summary = format!(
+					"{} passed, {} failed, {} skipped ({}ms)",
+					self.passed,
+					self.failed,
+					self.skipped,
+					duration.as_millis()
+				);
+				if self.failed == 0 {
+					println!("\n{}", summary.green());
+				} else {
+					println!("\n{}", summary.red());
+				}
+			}
+			Reporter::Tap => {
+				println!("1..{}", self.count);
+				println!("# pass {}", self.passed);
+				println!("# fail {}", self.failed);
+				println!("# skip {}", self.skipped);
+			}
+			Reporter::Json => {
+				self.finished.sort();
+				let files: Vec<_> = self
+					.finished
+					.iter()
+					.map(|path| json_file(path, self.files.get(path).unwrap_or(&FileReport::default())))
+					.collect();
+				println!(
+					"{{\"passed\":{},\"failed\":{},\"skipped\":{},\"duration\":{},\"files\":[{}]}}",
+					self.passed,
+					self.failed,
+					self.skipped,
+					duration.as_secs_f64() * 1000.0,
+					files.join(",")
+				);
+			}
+		}
+		self.failed == 0
+	}
+}
+
+fn print_pretty(path: &Path, file: &FileReport) {
+	println!("{}", path.display().to_string().bold());
+	for result in &file.results {
+		let duration = format!("({}ms)", result.duration.as_millis()).dimmed();
+		match &result.outcome {
+			TestOutcome::Passed => println!("  {} {} {}", "✓".green(), result.name, duration),
+			TestOutcome::Failed(error) => {
+				println!("  {} {} {}", "✗".red(), result.name, duration);
+				println!("{}", indent(error, 4));
+			}
+			TestOutcome::Skipped => println!("  {} {} {}", "-".yellow(), result.name, "(skipped)".dimmed()),
+		}
+	}
+	for error in &file.errors {
+		println!("  {} {}", "✗".red(), "Failed to run file".red());
+		println!("{}", indent(error, 4));
+	}
+}
+
+fn print_tap(number: usize, name: &str, outcome: &TestOutcome) {
+	match outcome {
+		TestOutcome::Passed => println!("ok {} - {}", number, name),
+		TestOutcome::Failed(error) => {
+			println!("not ok {} - {}", number, name);
+			println!("  ---");
+			println!("  message: |-");
+			println!("{}", indent(error, 4));
+			println!("  ...");
+		}
+		TestOutcome::Skipped => println!("ok {} - {} # SKIP", number, name),
+	}
+}
+
+pub(crate) fn indent(string: &str, width: usize) -> String {
+	let indentation = " ".repeat(width);
+	string
+		.lines()
+		.map(|line| format!("{}{}", indentation, line))
+		.collect::<Vec<_>>()
+		.join("\n")
+}
+
+fn json_file(path: &Path, file: &FileReport) -> String {
+	let results: Vec<_> = file
+		.results
+		.iter()
+		.map(|result| {
+			let error = match &result.outcome {
+				TestOutcome::Failed(error) => format!(",\"error\":{}", json_string(error)),
+				_ => String::new(),
+			};
+			format!(
+				"{{\"name\":{},\"status\":\"{}\",\"duration\":{}{}}}",
+				json_string(&result.name),
+				result.outcome.as_str(),
+				result.duration.as_secs_f64() * 1000.0,
+				error
+			)
+		})
+		.collect();
+	let errors: Vec<_> = file.errors.iter().map(|error| json_string(error)).collect();
+	format!(
+		"{{\"path\":{},\"results\":[{}],\"errors\":[{}]}}",
+		json_string(&path.display().to_string()),
+		results.join(","),
+		errors.join(",")
+	)
+}
+
+pub(crate) fn json_string(string: &str) -> String {
+	let mut escaped = String::with_capacity(string.len() + 2);
+	escaped.push('"');
+	for char in string.chars() {
+		match char {
+			'"' => escaped.push_str("\\\""),
+			'\\' => escaped.push_str("\\\\"),
+			'\n' => escaped.push_str("\\n"),
+			'\r' => escaped.push_str("\\r"),
+			'\t' => escaped.push_str("\\t"),
+			char if char.is_control() => escaped.push_str(&format!("\\u{:04x}", char as u32)),
+			char => escaped.push(char),
+		}
+	}
+	escaped.push('"');
+	escaped
+}
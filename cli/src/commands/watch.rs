@@ -0,0 +1,160 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::collections::HashSet;
+use std::env::{args_os, current_exe};
+use std::ffi::OsString;
+use std::io;
+use std::io::{stdout, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitStatus;
+use std::time::Duration;
+
+use colored::Colorize;
+use dunce::canonicalize;
+use mozjs::rust::{JSEngine, Runtime as RustRuntime};
+use notify_debouncer_mini::{DebounceEventResult, new_debouncer};
+use notify_debouncer_mini::notify::RecursiveMode;
+use tokio::process::{Child, Command};
+use tokio::select;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver};
+
+use ion::Context;
+use runtime::config::Config;
+use runtime::modules::graph::local_module_graph;
+use runtime::modules::Loader;
+use runtime::options::ContextOptions;
+use runtime::RuntimeBuilder;
+
+/// Time which files must be unchanged for before restarting, so that saving several files at once only restarts once.
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// Runs a script in a child process, and restarts it whenever a file in its module graph changes.
+///
+/// The child process is started with the same arguments, except for `--watch`.
+/// Files are found by walking the static imports of the script, so modules which are only imported dynamically are not watched.
+/// Their directories are watched instead of the files themselves, as editors may save files by replacing them.
+pub(crate) async fn watch(path: &str, options: ContextOptions) {
+	let executable = match current_exe() {
+		Ok(executable) => executable,
+		Err(error) => {
+			eprintln!("Failed to Locate the Executable: {}", error);
+			return;
+		}
+	};
+	let args = child_args();
+
+	let (sender, mut receiver) = unbounded_channel();
+	let mut debouncer = match new_debouncer(DEBOUNCE, move |result: DebounceEventResult| {
+		let _ = sender.send(result);
+	}) {
+		Ok(debouncer) => debouncer,
+		Err(error) => {
+			eprintln!("Failed to Start Watching Files: {}", error);
+			return;
+		}
+	};
+
+	let engine = JSEngine::init().unwrap();
+	let rt = RustRuntime::new(engine.handle());
+
+	let cx = &mut Context::from_runtime(&rt);
+	let rt = RuntimeBuilder::<(), ()>::new().options(options).build(cx);
+
+	loop {
+		clear_terminal();
+		let files = watched_files(rt.cx(), Path::new(path));
+		let directories: HashSet<_> = files.iter().filter_map(|file| file.parent()).collect();
+		for directory in &directories {
+			if let Err(error) = debouncer.watcher().watch(directory, RecursiveMode::NonRecursive) {
+				eprintln!("Failed to Watch {}: {}", directory.display(), error);
+			}
+		}
+		// Changes made while the module graph was being loaded are already included.
+		while receiver.try_recv().is_ok() {}
+		println!("{}", format!("Watching {} Files", files.len()).dimmed());
+
+		let mut child = match Command::new(&executable).args(&args).kill_on_drop(true).spawn() {
+			Ok(child) => Some(child),
+			Err(error) => {
+				eprintln!("Failed to Start {}: {}", path, error);
+				None
+			}
+		};
+
+		wait_for_change(&mut receiver, &files, &mut child).await;
+
+		for directory in &directories {
+			let _ = debouncer.watcher().unwatch(directory);
+		}
+		if let Some(mut child) = child {
+			let _ = child.kill().await;
+		}
+	}
+}
+
+/// Waits until a watched file changes, reporting when the child process exits in the meantime.
+async fn wait_for_change(receiver: &mut UnboundedReceiver<DebounceEventResult>, files: &HashSet<PathBuf>, child: &mut Option<Child>) {
+	loop {
+		let event = match child {
+			Some(process) => select! {
+				status = process.wait() => Err(status),
+				result = receiver.recv() => Ok(result),
+			},
+			None => Ok(receiver.recv().await),
+		};
+
+		match event {
+			Ok(Some(Ok(events))) => {
+				if events.iter().any(|event| files.contains(&event.path)) {
+					return;
+				}
+			}
+			Ok(Some(Err(error))) => eprintln!("Failed to Watch Files: {}", error),
+			Ok(None) => return,
+			Err(status) => {
+				report_exit(status);
+				*child = None;
+			}
+		}
+	}
+}
+
+fn report_exit(status: io::Result<ExitStatus>) {
+	let message = match status {
+		Ok(status) => format!("Process exited with {}. Waiting for Changes...", status),
+		Err(error) => format!("Process failed: {}. Waiting for Changes...", error),
+	};
+	println!("{}", message.dimmed());
+}
+
+/// Returns the arguments the runtime was started with, without the first `--watch`.
+/// Arguments after the script are passed to the script, so the first `--watch` is always an argument of the runtime.
+fn child_args() -> Vec<OsString> {
+	let mut removed = false;
+	args_os()
+		.skip(1)
+		.filter(|arg| {
+			let watch = !removed && arg == "--watch";
+			removed |= watch;
+			!watch
+		})
+		.collect()
+}
+
+/// Returns the local files in the module graph of a script, without fetching or caching any modules.
+/// Errors are left to be reported by the script, and the graph is watched as far as it could be loaded, so fixing them restarts it.
+fn watched_files(cx: &Context, path: &Path) -> HashSet<PathBuf> {
+	if Config::global().script {
+		return HashSet::from([canonicalize(path).unwrap_or_else(|_| path.to_path_buf())]);
+	}
+	local_module_graph(cx, &mut Loader::default(), path)
+}
+
+fn clear_terminal() {
+	print!("\x1B[2J\x1B[3J\x1B[H");
+	let _ = stdout().flush();
+}
@@ -4,38 +4,59 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/.
  */
 
-use std::ffi::OsStr;
 use std::fs::read_to_string;
 use std::io::ErrorKind;
 use std::path::Path;
+use std::process;
+use std::time::Instant;
 
+use dunce::canonicalize;
 use mozjs::rust::JSEngine;
 use mozjs::rust::Runtime as RustRuntime;
 use sourcemap::SourceMap;
+use tokio::time::timeout;
+use url::Url;
 
-use ion::Context;
+use ion::{Context, ErrorReport, Exception, Function, Promise, Value};
 use ion::format::Config as FormatConfig;
 use ion::format::format_value;
-use ion::module::Module;
+use ion::module::{Module, ModuleError, ModuleErrorKind};
 use ion::script::Script;
+use ion::stencil::Stencil;
 use modules::Modules;
 use runtime::{Runtime, RuntimeBuilder};
-use runtime::cache::locate_in_cache;
-use runtime::cache::map::{save_sourcemap, transform_error_report_with_sourcemaps};
+use runtime::cache::{locate_in_cache, locate_module_stencil, locate_stencil};
+use runtime::cache::map::{register_sourcemap_from_source, save_sourcemap, transform_error_report_with_sourcemaps};
 use runtime::config::Config;
-use runtime::modules::Loader;
+use runtime::event_loop::handles::trace_exit;
+use runtime::heap::snapshot_heap_on_shutdown;
+use runtime::inspector::Inspector;
+use runtime::modules::{Loader, StandardModules};
+use runtime::modules::remote::fetch_module_imports;
+use runtime::options::ContextOptions;
+use runtime::profiler::Profiler;
+use runtime::snapshot::Snapshot;
+use runtime::standalone::Standalone;
+use runtime::typescript::is_typescript;
+use runtime::watchdog::{deadline, set_time_limit, take_terminated};
 
 pub(crate) async fn eval_inline(rt: &Runtime<'_>, source: &str) {
 	let result = Script::compile_and_evaluate(rt.cx(), Path::new("inline.js"), source);
 
 	match result {
 		Ok(v) => println!("{}", format_value(rt.cx(), FormatConfig::default().quoted(true), &v)),
-		Err(report) => eprintln!("{}", report.format(rt.cx())),
+		Err(_) if rt.has_exited() => {}
+		Err(Some(report)) => eprintln!("{}", report.format(rt.cx())),
+		Err(None) => {
+			exit_on_timeout(rt);
+			eprintln!("Uncatchable Error");
+		}
 	}
 	run_event_loop(rt).await;
+	exit(rt);
 }
 
-pub(crate) async fn eval_script(path: &Path) {
+pub(crate) async fn eval_script(path: &Path, options: ContextOptions) {
 	let engine = JSEngine::init().unwrap();
 	let rt = RustRuntime::new(engine.handle());
 
@@ -44,27 +65,44 @@ pub(crate) async fn eval_script(path: &Path) {
 		.microtask_queue()
 		.macrotask_queue()
 		.standard_modules(Modules)
+		.workers(engine.handle())
+		.options(options)
 		.build(cx);
+	ensure_default_snapshot(rt.cx());
+	start_inspector(rt.cx(), path);
+	start_profiler(rt.cx());
 
 	if let Some((script, _)) = read_script(path) {
 		let (script, sourcemap) = cache(path, script);
 		if let Some(sourcemap) = sourcemap {
 			save_sourcemap(path, sourcemap);
+		} else {
+			register_sourcemap_from_source(path, &script);
 		}
-		let result = Script::compile_and_evaluate(rt.cx(), path, &script);
+		let result = locate_stencil(rt.cx(), path, &script, false)
+			.and_then(|stencil| stencil.to_script(rt.cx()))
+			.map_err(Some)
+			.and_then(|script| script.evaluate(rt.cx()));
 
 		match result {
 			Ok(v) => println!("{}", format_value(rt.cx(), FormatConfig::default().quoted(true), &v)),
-			Err(mut report) => {
+			Err(_) if rt.has_exited() => {}
+			Err(Some(mut report)) => {
+				exit_on_timeout(&rt);
 				transform_error_report_with_sourcemaps(&mut report);
 				eprintln!("{}", report.format(rt.cx()));
 			}
+			Err(None) => {
+				exit_on_timeout(&rt);
+				eprintln!("Uncatchable Error");
+			}
 		}
 		run_event_loop(&rt).await;
+		exit(&rt);
 	}
 }
 
-pub(crate) async fn eval_module(path: &Path) {
+pub(crate) async fn eval_module(path: &Path, options: ContextOptions) {
 	let engine = JSEngine::init().unwrap();
 	let rt = RustRuntime::new(engine.handle());
 
@@ -74,20 +112,176 @@ pub(crate) async fn eval_module(path: &Path) {
 		.macrotask_queue()
 		.modules(Loader::default())
 		.standard_modules(Modules)
+		.workers(engine.handle())
+		.options(options)
 		.build(cx);
+	ensure_default_snapshot(rt.cx());
+	start_inspector(rt.cx(), path);
+	start_profiler(rt.cx());
 
-	if let Some((script, filename)) = read_script(path) {
+	if let Some((script, _)) = read_script(path) {
 		let (script, sourcemap) = cache(path, script);
 		if let Some(sourcemap) = sourcemap {
 			save_sourcemap(path, sourcemap);
+		} else {
+			register_sourcemap_from_source(path, &script);
 		}
-		let result = Module::compile(rt.cx(), &filename, Some(path), &script);
+		let result = match locate_module_stencil(rt.cx(), path, &script).await {
+			Ok(stencil) => {
+				if let Err(error) = fetch_module_imports(rt.cx(), path, &stencil).await {
+					eprintln!("{}", error.format());
+					rt.shutdown();
+					process::exit(1);
+				}
+				Module::from_stencil(rt.cx(), Some(path), &stencil)
+			}
+			Err(report) => Err(ModuleError {
+				kind: ModuleErrorKind::Compilation,
+				report: Some(report),
+			}),
+		};
+
+		run_module(&rt, path, result).await;
+	}
+}
+
+/// Runs an application embedded in the executable, which consists of a single bundled module.
+/// The bundled module is decoded from the embedded snapshot if possible, instead of being compiled.
+pub(crate) async fn eval_standalone(app: &Standalone, options: ContextOptions) {
+	let engine = JSEngine::init().unwrap();
+	let rt = RustRuntime::new(engine.handle());
+
+	let cx = &mut Context::from_runtime(&rt);
+	let rt = RuntimeBuilder::new()
+		.microtask_queue()
+		.macrotask_queue()
+		.modules(Loader::default())
+		.standard_modules(Modules)
+		.workers(engine.handle())
+		.options(options)
+		.build(cx);
+
+	let path = Path::new(&app.name);
+	register_sourcemap_from_source(path, &app.source);
+	let stencil = match Snapshot::global().and_then(|snapshot| snapshot.stencil(rt.cx(), &app.name, &app.source, true)) {
+		Some(stencil) => Ok(stencil),
+		None => Stencil::compile_module(rt.cx(), path, &app.source),
+	};
+	let result = match stencil {
+		Ok(stencil) => Module::from_stencil(rt.cx(), None, &stencil),
+		Err(report) => Err(ModuleError {
+			kind: ModuleErrorKind::Compilation,
+			report: Some(report),
+		}),
+	};
+	run_module(&rt, path, result).await;
+}
+
+/// Runs the event loop until an evaluated module has finished, reporting its errors, then exits.
+async fn run_module(rt: &Runtime<'_>, path: &Path, result: Result<(Module<'_>, Option<Promise<'_>>), ModuleError>) {
+	match result {
+		Ok((_, Some(promise))) => {
+			let on_rejected = Function::new_closure(rt.cx(), "", |cx, _| Ok(Value::undefined(cx)));
+			promise.add_reactions(rt.cx(), None, Some(on_rejected));
+			run_event_loop(rt).await;
 
-		if let Err(mut error) = result {
-			transform_error_report_with_sourcemaps(&mut error.report);
+			match promise.settled_result(rt.cx()) {
+				Some(Ok(_)) => {}
+				_ if rt.has_exited() => {}
+				Some(Err(reason)) => {
+					let exception = Exception::from_value(rt.cx(), &reason);
+					let mut report = ErrorReport::from_exception_with_error_stack(rt.cx(), exception);
+					transform_error_report_with_sourcemaps(&mut report);
+					eprintln!("{}", report.format(rt.cx()));
+					rt.shutdown();
+					process::exit(1);
+				}
+				None => eprintln!("Top-level await in {} did not settle before the event loop finished", path.display()),
+			}
+		}
+		Ok((_, None)) => run_event_loop(rt).await,
+		Err(_) if rt.has_exited() => {}
+		Err(mut error) => {
+			exit_on_timeout(rt);
+			if let Some(report) = &mut error.report {
+				transform_error_report_with_sourcemaps(report);
+			}
 			eprintln!("{}", error.format(rt.cx()));
+			run_event_loop(rt).await;
 		}
-		run_event_loop(&rt).await;
+	}
+	exit(rt);
+}
+
+/// Starts the profiler if it was enabled, which writes its profile when the runtime shuts down.
+/// A heap snapshot is also written, and active handles are reported, on shutdown, if requested.
+/// The timeout of the script, if set, starts here.
+fn start_profiler(cx: &Context) {
+	let config = Config::global();
+	if let Some(options) = &config.profile {
+		Profiler::start(cx, options.interval, Some(options.output.clone()));
+	}
+	if let Some(path) = &config.heap_snapshot_on_exit {
+		snapshot_heap_on_shutdown(cx, path.clone());
+	}
+	if config.trace_exit {
+		trace_exit(cx);
+	}
+	if let Some(timeout) = config.timeout {
+		if !set_time_limit(cx, timeout) {
+			eprintln!("Failed to Start the Timeout of the Script");
+		}
+	}
+}
+
+/// Exits with an exit code of 1 if the script was terminated for exceeding its timeout.
+fn exit_on_timeout(rt: &Runtime) {
+	if take_terminated(rt.cx()) {
+		exit_for_timeout(rt);
+	}
+}
+
+fn exit_for_timeout(rt: &Runtime) -> ! {
+	eprintln!("Script Terminated after Exceeding the Timeout");
+	rt.shutdown();
+	process::exit(1);
+}
+
+/// Starts the inspector if it was enabled, and waits for a debugger if requested.
+/// The runtime still runs if the inspector cannot be started.
+fn start_inspector(cx: &Context, path: &Path) {
+	let Some(options) = Config::global().inspect else {
+		return;
+	};
+	let url = canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+	let url = Url::from_file_path(&url).map(String::from).unwrap_or_else(|_| path.display().to_string());
+	match Inspector::start(cx, options.address, &path.display().to_string(), &url) {
+		Ok(url) => {
+			eprintln!("Debugger listening on {}", url);
+			if options.wait {
+				eprintln!("Waiting for the Debugger to Connect...");
+				if let Err(Some(report)) = Inspector::wait_for_debugger(cx) {
+					eprintln!("{}", report.format(cx));
+				}
+			}
+		}
+		Err(error) => eprintln!("Failed to Start the Inspector on {}: {}", options.address, error),
+	}
+}
+
+/// Builds the default snapshot of the standard modules if it does not exist, so that subsequent runs can load them from it.
+fn ensure_default_snapshot(cx: &Context) {
+	let config = Config::global();
+	if config.snapshot.is_some() || !config.code_cache {
+		return;
+	}
+	let Some(path) = Snapshot::default_path().filter(|path| !path.exists()) else {
+		return;
+	};
+
+	let mut snapshot = Snapshot::default();
+	if Modules.snapshot(cx, &mut snapshot) {
+		let _ = snapshot.save(&path);
 	}
 }
 
@@ -109,9 +303,22 @@ fn read_script(path: &Path) -> Option<(String, String)> {
 	}
 }
 
-async fn run_event_loop(rt: &Runtime<'_>) {
-	if let Err(err) = rt.run_event_loop().await {
-		if let Some(err) = err {
+/// The timeout of the script, if set, also applies while the event loop is idle, such as while waiting for a timer.
+pub(crate) async fn run_event_loop(rt: &Runtime<'_>) {
+	let result = match deadline(rt.cx()) {
+		Some(deadline) => {
+			let remaining = deadline.saturating_duration_since(Instant::now());
+			match timeout(remaining, rt.run_event_loop()).await {
+				Ok(result) => result,
+				Err(_) => exit_for_timeout(rt),
+			}
+		}
+		None => rt.run_event_loop().await,
+	};
+	if let Err(err) = result {
+		exit_on_timeout(rt);
+		if let Some(mut err) = err {
+			transform_error_report_with_sourcemaps(&mut err);
 			eprintln!("{}", err.format(rt.cx()));
 		} else {
 			eprintln!("Unknown error occurred while executing microtask.");
@@ -119,8 +326,21 @@ async fn run_event_loop(rt: &Runtime<'_>) {
 	}
 }
 
-fn cache(path: &Path, script: String) -> (String, Option<SourceMap>) {
-	let is_typescript = Config::global().typescript && path.extension() == Some(OsStr::new("ts"));
+/// Shuts down the runtime once the event loop has finished, and exits with the exit code set by scripts.
+/// Unhandled promise rejections cause an exit code of 1, unless another exit code has been set.
+pub(crate) fn exit(rt: &Runtime) {
+	rt.shutdown();
+	let code = match rt.exit_code() {
+		0 if rt.has_unhandled_rejections() => 1,
+		code => code,
+	};
+	if code != 0 {
+		process::exit(code);
+	}
+}
+
+pub(crate) fn cache(path: &Path, script: String) -> (String, Option<SourceMap>) {
+	let is_typescript = is_typescript(path);
 	is_typescript
 		.then(|| locate_in_cache(path, &script))
 		.flatten()
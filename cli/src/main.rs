@@ -4,10 +4,21 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/.
  */
 
-use clap::{Parser, Subcommand};
+use std::net::{SocketAddr, ToSocketAddrs};
+use std::path::PathBuf;
+
+use clap::{Args, Parser, Subcommand};
 use tokio::task::LocalSet;
 
+use runtime::diagnostics::{init_tracing, LOG_ENV};
+use runtime::options::ContextOptions;
+use runtime::permissions::{PermissionName, Permissions};
+use runtime::standalone::Standalone;
+
+use crate::commands::cache::CacheEntry;
+use crate::commands::compile::run_standalone;
 use crate::commands::handle_command;
+use crate::commands::test::Reporter;
 
 mod commands;
 mod evaluate;
@@ -18,14 +29,216 @@ mod repl;
 struct Cli {
 	#[command(subcommand)]
 	command: Option<Command>,
+
+	#[command(flatten)]
+	engine: EngineArgs,
+
+	#[arg(
+		help = "Logs Runtime Internals matching the given Filter to stderr, such as 'debug' or 'runtime::modules=trace'",
+		long,
+		value_name = "FILTER",
+		global = true,
+		env = LOG_ENV
+	)]
+	log_filter: Option<String>,
+}
+
+#[derive(Args)]
+struct EngineArgs {
+	#[arg(help = "Disables WeakRef and FinalizationRegistry", long, global = true)]
+	no_weak_refs: bool,
+
+	#[arg(help = "Disables SharedArrayBuffer and Atomics", long, global = true)]
+	no_shared_memory: bool,
+
+	#[arg(help = "Enables Import Assertions", long, global = true)]
+	import_assertions: bool,
+
+	#[arg(help = "Disables WebAssembly", long, global = true)]
+	no_wasm: bool,
+
+	#[arg(help = "Disables the Baseline Interpreter", long, global = true)]
+	no_baseline_interpreter: bool,
+
+	#[arg(help = "Disables the Baseline JIT", long, global = true)]
+	no_baseline_jit: bool,
+
+	#[arg(help = "Disables the Optimizing JIT", long, global = true)]
+	no_optimizing_jit: bool,
+
+	#[arg(help = "Disables all JIT Tiers", long, global = true)]
+	no_jit: bool,
+
+	#[arg(help = "Sets GC Zeal as <level>[,<frequency>] (Debug Builds Only)", long, global = true, value_parser = parse_gc_zeal)]
+	gc_zeal: Option<(u8, u32)>,
+
+	#[arg(help = "Exposes globalThis.gc()", long, global = true)]
+	expose_gc: bool,
+}
+
+impl EngineArgs {
+	fn options(&self) -> ContextOptions {
+		let mut options = ContextOptions::default()
+			.weak_refs(!self.no_weak_refs)
+			.shared_memory(!self.no_shared_memory)
+			.import_assertions(self.import_assertions)
+			.wasm(!self.no_wasm)
+			.baseline_interpreter(!self.no_baseline_interpreter)
+			.baseline_jit(!self.no_baseline_jit)
+			.optimizing_jit(!self.no_optimizing_jit)
+			.expose_gc(self.expose_gc);
+		if self.no_jit {
+			options = options.no_jit();
+		}
+		if let Some((level, frequency)) = self.gc_zeal {
+			options = options.gc_zeal(level, frequency);
+		}
+		options
+	}
+}
+
+#[derive(Args)]
+pub(crate) struct PermissionArgs {
+	#[arg(help = "Allows all Permissions", short = 'A', long)]
+	allow_all: bool,
+
+	#[arg(help = "Allows Reading Files, optionally only within the given Paths", long, value_name = "PATH", num_args = 0.., value_delimiter = ',', require_equals = true)]
+	allow_read: Option<Vec<String>>,
+
+	#[arg(help = "Allows Writing Files, optionally only within the given Paths", long, value_name = "PATH", num_args = 0.., value_delimiter = ',', require_equals = true)]
+	allow_write: Option<Vec<String>>,
+
+	#[arg(help = "Allows Network Access, optionally only to the given Hosts", long, value_name = "HOST", num_args = 0.., value_delimiter = ',', require_equals = true)]
+	allow_net: Option<Vec<String>>,
+
+	#[arg(help = "Allows Access to Environment Variables, optionally only to the given Variables", long, value_name = "NAME", num_args = 0.., value_delimiter = ',', require_equals = true)]
+	allow_env: Option<Vec<String>>,
+
+	#[arg(help = "Allows Running Subprocesses, optionally only of the given Programs", long, value_name = "PROGRAM", num_args = 0.., value_delimiter = ',', require_equals = true)]
+	allow_run: Option<Vec<String>>,
+
+	#[arg(help = "Allows Loading and Calling Native Libraries through the FFI Module", long)]
+	allow_ffi: bool,
+
+	#[arg(help = "Allows High Resolution Time Measurement", long)]
+	allow_hrtime: bool,
+
+	#[arg(help = "Denies Permissions which have not been Allowed instead of Prompting", long)]
+	no_prompt: bool,
+}
+
+impl PermissionArgs {
+	pub(crate) fn permissions(&self) -> Permissions {
+		if self.allow_all {
+			return Permissions::allow_all();
+		}
+
+		let mut permissions = Permissions::none(!self.no_prompt);
+		let resources = [
+			(PermissionName::Read, &self.allow_read),
+			(PermissionName::Write, &self.allow_write),
+			(PermissionName::Net, &self.allow_net),
+			(PermissionName::Env, &self.allow_env),
+			(PermissionName::Run, &self.allow_run),
+		];
+		for (name, resources) in resources {
+			if let Some(resources) = resources {
+				permissions = permissions.allow(name, resources);
+			}
+		}
+		for (name, allowed) in [(PermissionName::Ffi, self.allow_ffi), (PermissionName::Hrtime, self.allow_hrtime)] {
+			if allowed {
+				permissions = permissions.allow(name, &[]);
+			}
+		}
+		permissions
+	}
+
+	/// Returns the arguments which parse into these permissions, so that they can be embedded into a standalone executable.
+	pub(crate) fn to_args(&self) -> Vec<String> {
+		let mut args = Vec::new();
+		let resources = [
+			("--allow-read", &self.allow_read),
+			("--allow-write", &self.allow_write),
+			("--allow-net", &self.allow_net),
+			("--allow-env", &self.allow_env),
+			("--allow-run", &self.allow_run),
+		];
+		for (flag, resources) in resources {
+			match resources {
+				Some(resources) if resources.is_empty() => args.push(String::from(flag)),
+				Some(resources) => args.push(format!("{}={}", flag, resources.join(","))),
+				None => {}
+			}
+		}
+		let flags = [
+			("--allow-all", self.allow_all),
+			("--allow-ffi", self.allow_ffi),
+			("--allow-hrtime", self.allow_hrtime),
+			("--no-prompt", self.no_prompt),
+		];
+		args.extend(flags.into_iter().filter(|(_, set)| *set).map(|(flag, _)| String::from(flag)));
+		args
+	}
+}
+
+fn parse_gc_zeal(zeal: &str) -> Result<(u8, u32), String> {
+	let (level, frequency) = zeal.split_once(',').unwrap_or((zeal, "100"));
+	let level = level.trim().parse().map_err(|_| format!("Invalid GC Zeal Level: {}", level))?;
+	let frequency = frequency
+		.trim()
+		.parse()
+		.map_err(|_| format!("Invalid GC Zeal Frequency: {}", frequency))?;
+	Ok((level, frequency))
+}
+
+const DEFAULT_INSPECT_ADDRESS: &str = "127.0.0.1:9229";
+
+/// Parses the address of the inspector, which can also be only a port on the loopback address.
+fn parse_inspect_address(address: &str) -> Result<SocketAddr, String> {
+	if let Ok(port) = address.parse::<u16>() {
+		return Ok(SocketAddr::from(([127, 0, 0, 1], port)));
+	}
+	address
+		.to_socket_addrs()
+		.ok()
+		.and_then(|mut addresses| addresses.next())
+		.ok_or_else(|| format!("Invalid Inspector Address: {}", address))
+}
+
+const DEFAULT_PROFILE_PATH: &str = "profile.speedscope.json";
+const DEFAULT_HEAP_SNAPSHOT_PATH: &str = "exit.heapsnapshot";
+
+fn parse_prof_interval(interval: &str) -> Result<f64, String> {
+	match interval.parse::<f64>() {
+		Ok(interval) if interval.is_finite() && interval >= 0.1 => Ok(interval),
+		_ => Err(format!("Invalid Profiler Interval: {}, Expected at least 0.1 Milliseconds", interval)),
+	}
 }
 
 #[derive(Subcommand)]
 pub(crate) enum Command {
-	#[command(about = "Prints Cache Statistics")]
+	#[command(about = "Prints Cache Statistics, or Downloads and Compiles the Module Graphs of the given Files into the Cache")]
 	Cache {
-		#[arg(help = "Clears the Cache", short, long)]
-		clear: bool,
+		#[arg(help = "Files whose Module Graphs are Cached")]
+		paths: Vec<PathBuf>,
+
+		#[arg(
+			help = "Clears Entries of the Cache, Default: all",
+			short,
+			long,
+			value_enum,
+			value_name = "ENTRY",
+			num_args = 0..=1,
+			default_missing_value = "all"
+		)]
+		clear: Option<CacheEntry>,
+
+		#[arg(help = "Sets the Import Map used to resolve Module Specifiers", long, value_name = "FILE")]
+		import_map: Option<PathBuf>,
+
+		#[arg(help = "Downloads Remote Modules instead of using the Cache", long)]
+		reload: bool,
 	},
 
 	#[command(about = "Evaluates a line of JavaScript")]
@@ -50,12 +263,192 @@ pub(crate) enum Command {
 
 		#[arg(help = "Disables ES Modules Features", short, long)]
 		script: bool,
+
+		#[arg(help = "Disables Caching of Compiled Code", long)]
+		no_code_cache: bool,
+
+		#[arg(help = "Sets the Import Map used to resolve Module Specifiers", long, value_name = "FILE")]
+		import_map: Option<PathBuf>,
+
+		#[arg(help = "Downloads Remote Modules instead of using the Cache", long)]
+		reload: bool,
+
+		#[arg(help = "Only uses Remote Modules from the Cache, without Network Access", long, conflicts_with = "reload")]
+		cached_only: bool,
+
+		#[arg(help = "Sets the Snapshot of Pre-Compiled Modules loaded on Startup", long, value_name = "FILE")]
+		snapshot: Option<PathBuf>,
+
+		#[arg(help = "Sets the Origin which Storage is Partitioned by, Default: the Script", long, value_name = "URL")]
+		location: Option<String>,
+
+		#[command(flatten)]
+		permissions: PermissionArgs,
+
+		#[arg(help = "Restarts the Script when the Files it imports change", long)]
+		watch: bool,
+
+		#[arg(
+			help = "Starts the Inspector for Debuggers, Default: 127.0.0.1:9229",
+			long,
+			value_name = "HOST:PORT",
+			num_args = 0..=1,
+			require_equals = true,
+			default_missing_value = DEFAULT_INSPECT_ADDRESS,
+			value_parser = parse_inspect_address
+		)]
+		inspect: Option<SocketAddr>,
+
+		#[arg(
+			help = "Starts the Inspector, and Waits for a Debugger before Running the Script",
+			long,
+			value_name = "HOST:PORT",
+			num_args = 0..=1,
+			require_equals = true,
+			default_missing_value = DEFAULT_INSPECT_ADDRESS,
+			value_parser = parse_inspect_address,
+			conflicts_with = "inspect"
+		)]
+		inspect_brk: Option<SocketAddr>,
+
+		#[arg(
+			help = "Profiles the Script, and Writes a Speedscope Profile, or Folded Stacks for '.folded' Files, on Exit",
+			long,
+			value_name = "FILE",
+			num_args = 0..=1,
+			require_equals = true,
+			default_missing_value = DEFAULT_PROFILE_PATH
+		)]
+		prof: Option<PathBuf>,
+
+		#[arg(
+			help = "Sets the Sampling Interval of the Profiler in Milliseconds",
+			long,
+			value_name = "MS",
+			default_value_t = 1.0,
+			value_parser = parse_prof_interval,
+			requires = "prof"
+		)]
+		prof_interval: f64,
+
+		#[arg(
+			help = "Writes a Heap Snapshot, which can be loaded in Chrome DevTools, on Exit",
+			long,
+			value_name = "FILE",
+			num_args = 0..=1,
+			require_equals = true,
+			default_missing_value = DEFAULT_HEAP_SNAPSHOT_PATH
+		)]
+		heap_snapshot_on_exit: Option<PathBuf>,
+
+		#[arg(
+			help = "Prints the Timers, Sockets and other Handles keeping the Event Loop Alive, with their Creation Stacks, on Exit or Ctrl+C",
+			long
+		)]
+		trace_exit: bool,
+
+		#[arg(
+			help = "Terminates the Script if it is still Running after the given Number of Milliseconds",
+			long,
+			value_name = "MS"
+		)]
+		timeout: Option<u64>,
+
+		#[arg(help = "Arguments passed to the Script", trailing_var_arg = true, allow_hyphen_values = true)]
+		args: Vec<String>,
+	},
+
+	#[command(about = "Runs Tests declared with the Test Module")]
+	Test {
+		#[arg(help = "Test Files, Directories or Glob Patterns, Default: the Current Directory")]
+		paths: Vec<String>,
+
+		#[arg(help = "Only runs Tests whose Names contain the Filter", short, long)]
+		filter: Option<String>,
+
+		#[arg(help = "Sets the Format of the Results", short, long, value_enum, default_value = "pretty")]
+		reporter: Reporter,
+
+		#[arg(help = "Sets the Number of Files run in Parallel, Default: 1", short, long, default_value_t = 1)]
+		jobs: usize,
+
+		#[command(flatten)]
+		permissions: PermissionArgs,
+	},
+
+	#[command(about = "Runs Benchmarks declared with the Bench Module")]
+	Bench {
+		#[arg(help = "Benchmark Files, Directories or Glob Patterns, Default: the Current Directory")]
+		paths: Vec<String>,
+
+		#[arg(help = "Only runs Benchmarks whose Names contain the Filter", short, long)]
+		filter: Option<String>,
+
+		#[arg(help = "Prints the Results as JSON", long)]
+		json: bool,
+
+		#[command(flatten)]
+		permissions: PermissionArgs,
+	},
+
+	#[command(about = "Bundles a Module and the Modules it imports into a single ES Module")]
+	Bundle {
+		#[arg(help = "The Entry Module of the Bundle")]
+		entry: PathBuf,
+
+		#[arg(help = "Sets the Output File, Default: Standard Output", short, long, value_name = "FILE")]
+		output: Option<PathBuf>,
+
+		#[arg(help = "Sets the Import Map used to resolve Module Specifiers", long, value_name = "FILE")]
+		import_map: Option<PathBuf>,
+
+		#[arg(help = "Keeps Declarations which are never used", long)]
+		no_tree_shake: bool,
+
+		#[arg(help = "Disables the Inline Source Map", long)]
+		no_source_map: bool,
+	},
+
+	#[command(about = "Compiles a Module and the Modules it imports into a Standalone Executable")]
+	Compile {
+		#[arg(help = "The Entry Module of the Executable")]
+		entry: PathBuf,
+
+		#[arg(help = "Sets the Output File, Default: the Name of the Entry Module", short, long, value_name = "FILE")]
+		output: Option<PathBuf>,
+
+		#[arg(help = "Sets the Import Map used to resolve Module Specifiers", long, value_name = "FILE")]
+		import_map: Option<PathBuf>,
+
+		#[arg(help = "Embeds a Snapshot of the Standard Modules and the Bundle, which is decoded on Startup", long)]
+		snapshot: bool,
+
+		#[command(flatten)]
+		permissions: PermissionArgs,
+	},
+
+	#[command(about = "Builds a Snapshot of the Standard Modules and the given Files")]
+	Snapshot {
+		#[arg(help = "Files to include in the Snapshot")]
+		paths: Vec<PathBuf>,
+
+		#[arg(help = "Sets the Output File, Default: the Snapshot in the Cache", short, long, value_name = "FILE")]
+		output: Option<PathBuf>,
+
+		#[arg(help = "Compiles Files as Scripts instead of ES Modules", short, long)]
+		script: bool,
 	},
 }
 
 #[tokio::main(flavor = "current_thread")]
 async fn main() {
+	if let Some(app) = Standalone::current() {
+		LocalSet::new().run_until(run_standalone(app)).await;
+		return;
+	}
+
 	let args = Cli::parse();
+	init_tracing(args.log_filter.as_deref());
 
 	#[cfg(windows)]
 	{
@@ -63,5 +456,5 @@ async fn main() {
 	}
 
 	let local = LocalSet::new();
-	local.run_until(handle_command(args.command)).await;
+	local.run_until(handle_command(args.command, args.engine.options())).await;
 }
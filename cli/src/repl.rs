@@ -4,21 +4,148 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/.
  */
 
-use rustyline::{Config, Result};
-use rustyline::config::Builder;
-use rustyline::validate::{MatchingBracketValidator, ValidationContext, ValidationResult, Validator};
-use rustyline_derive::{Completer, Helper, Highlighter, Hinter};
+use std::collections::BTreeSet;
+use std::fs::create_dir_all;
+use std::path::PathBuf;
 
-#[derive(Completer, Helper, Hinter, Highlighter)]
-pub(crate) struct ReplHelper;
+use dirs::home_dir;
+use rustyline::{Config, Context as LineContext, Helper, Result};
+use rustyline::completion::{Completer, Pair};
+use rustyline::config::{Builder, CompletionType};
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::validate::{ValidationContext, ValidationResult, Validator};
 
-impl Validator for ReplHelper {
+use ion::{Context, Object, OwnedKey};
+use ion::flags::IteratorFlags;
+use ion::script::Script;
+
+const HISTORY_SIZE: usize = 1000;
+
+/// Provides completion of properties and detection of incomplete input for the REPL, by introspecting the runtime.
+pub(crate) struct ReplHelper<'cx> {
+	cx: &'cx Context,
+}
+
+impl<'cx> ReplHelper<'cx> {
+	pub(crate) fn new(cx: &'cx Context) -> ReplHelper<'cx> {
+		ReplHelper { cx }
+	}
+
+	/// Returns the names of the properties of an object and its prototypes, including those which are not enumerable.
+	fn property_names(&self, object: &Object) -> BTreeSet<String> {
+		object
+			.keys(self.cx, Some(IteratorFlags::HIDDEN))
+			.filter_map(|key| match key.to_owned_key(self.cx) {
+				OwnedKey::String(name) if is_identifier(&name) => Some(name),
+				_ => None,
+			})
+			.collect()
+	}
+
+	/// Resolves an object from a path of properties, starting from the global object.
+	fn resolve(&self, path: &[&str]) -> Option<Object<'cx>> {
+		let mut object = Object::global(self.cx);
+		for property in path {
+			let value = object.get(self.cx, *property)?;
+			if !value.handle().is_object() {
+				return None;
+			}
+			object = value.to_object(self.cx);
+		}
+		Some(object)
+	}
+}
+
+impl Completer for ReplHelper<'_> {
+	type Candidate = Pair;
+
+	/// Completes the properties of the expression before the cursor, such as `console.lo` or `Math.`.
+	/// Global variables are completed if the expression has no object.
+	fn complete(&self, line: &str, pos: usize, _: &LineContext) -> Result<(usize, Vec<Pair>)> {
+		let line = &line[..pos];
+		let start = line
+			.char_indices()
+			.rev()
+			.take_while(|(_, char)| is_identifier_char(*char) || *char == '.')
+			.last()
+			.map(|(index, _)| index)
+			.unwrap_or(pos);
+		let expression = &line[start..];
+
+		let (path, prefix) = match expression.rsplit_once('.') {
+			Some((path, prefix)) => (path.split('.').collect::<Vec<_>>(), prefix),
+			None => (Vec::new(), expression),
+		};
+		if path.iter().any(|property| !is_identifier(property)) {
+			return Ok((pos, Vec::new()));
+		}
+
+		let Some(object) = self.resolve(&path) else {
+			return Ok((pos, Vec::new()));
+		};
+		let candidates = self
+			.property_names(&object)
+			.into_iter()
+			.filter(|name| name.starts_with(prefix))
+			.map(|name| Pair { display: name.clone(), replacement: name })
+			.collect();
+		Ok((pos - prefix.len(), candidates))
+	}
+}
+
+impl Hinter for ReplHelper<'_> {
+	type Hint = String;
+}
+
+impl Highlighter for ReplHelper<'_> {}
+
+impl Validator for ReplHelper<'_> {
+	/// Input is incomplete if it ends before a complete script, or before a complete async function body,
+	/// so that statements with `await` can also span multiple lines.
 	fn validate(&self, ctx: &mut ValidationContext) -> Result<ValidationResult> {
-		MatchingBracketValidator::new().validate(ctx)
+		let input = ctx.input();
+		if input.trim().is_empty() {
+			return Ok(ValidationResult::Valid(None));
+		}
+
+		let wrapped = format!("(async () => {{\n{}\n}})", input);
+		if Script::is_compilable_unit(self.cx, input) && Script::is_compilable_unit(self.cx, &wrapped) {
+			Ok(ValidationResult::Valid(None))
+		} else {
+			Ok(ValidationResult::Incomplete)
+		}
 	}
 }
 
+impl Helper for ReplHelper<'_> {}
+
+fn is_identifier_char(char: char) -> bool {
+	char.is_alphanumeric() || char == '_' || char == '$'
+}
+
+pub(crate) fn is_identifier(string: &str) -> bool {
+	let mut chars = string.chars();
+	chars.next().is_some_and(|char| !char.is_numeric() && is_identifier_char(char)) && chars.all(is_identifier_char)
+}
+
 pub(crate) fn rustyline_config() -> Config {
 	let builder = Builder::new();
-	builder.tab_stop(4).build()
+	builder
+		.tab_stop(4)
+		.completion_type(CompletionType::List)
+		.max_history_size(HISTORY_SIZE)
+		.unwrap()
+		.history_ignore_dups(true)
+		.unwrap()
+		.history_ignore_space(true)
+		.auto_add_history(false)
+		.build()
+}
+
+/// Returns the path of the file which the history of the REPL is persisted in, creating its directory if needed.
+pub(crate) fn history_path() -> Option<PathBuf> {
+	let dir = home_dir()?.join(".spiderfire");
+	create_dir_all(&dir).ok()?;
+	Some(dir.join("repl_history"))
 }
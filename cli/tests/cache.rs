@@ -0,0 +1,140 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+// The cache is located in the home directory, which can only be overridden with an environment variable on Unix.
+#![cfg(unix)]
+
+use std::{env, fs, process};
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::Path;
+use std::process::{Command, Output};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+const FIXTURES: [(&str, &str); 4] = [
+	("entry.js", include_str!("scripts/cache/entry.js")),
+	("dependency.js", include_str!("scripts/cache/dependency.js")),
+	("uncached.js", include_str!("scripts/cache/uncached.js")),
+	("import-map.json", include_str!("scripts/cache/import-map.json")),
+];
+
+#[test]
+fn cache() {
+	let home = env::temp_dir().join(format!("spiderfire-cache-{}", process::id()));
+	let _ = fs::remove_dir_all(&home);
+	fs::create_dir_all(&home).unwrap();
+	let requests = Arc::new(AtomicUsize::new(0));
+	let origin = serve(Arc::clone(&requests));
+	for (name, source) in FIXTURES {
+		fs::write(home.join(name), source.replace("{origin}", &origin)).unwrap();
+	}
+	let cache = home.join(".spiderfire/cache");
+
+	// Remote modules imported by remote modules, and through the import map by local modules, are fetched.
+	let output = spiderfire(&home, &["cache", "entry.js", "--import-map", "import-map.json"]);
+	assert_success(&output);
+	assert!(stdout(&output).contains("Cached 5 Modules"), "{}", stdout(&output));
+	assert_eq!(requests.load(Ordering::SeqCst), 3);
+
+	let output = spiderfire(&home, &["cache"]);
+	assert_success(&output);
+	assert!(stdout(&output).contains(&format!("Location: {}", cache.display())));
+	assert!(!stdout(&output).contains("Remote Modules: 0 B"), "{}", stdout(&output));
+
+	let output = spiderfire(&home, &["run", "-A", "--cached-only", "--import-map", "import-map.json", "entry.js"]);
+	assert_success(&output);
+	assert_eq!(stdout(&output).trim(), "remote mapped");
+	assert_eq!(requests.load(Ordering::SeqCst), 3, "Cached modules should not be fetched again");
+
+	let output = spiderfire(&home, &["run", "-A", "--cached-only", "uncached.js"]);
+	assert!(!output.status.success());
+	assert!(stderr(&output).contains("cannot be fetched with --cached-only"), "{}", stderr(&output));
+	assert_eq!(requests.load(Ordering::SeqCst), 3, "Modules should not be fetched with --cached-only");
+
+	let output = spiderfire(&home, &["cache", "--clear=remote"]);
+	assert_success(&output);
+	assert!(stdout(&output).contains("Cleared Remote Modules from the Cache"));
+	assert!(!cache.join("remote").exists());
+	assert!(entries(&cache) > 0, "Compiled modules should be kept");
+
+	let output = spiderfire(&home, &["cache", "--clear"]);
+	assert_success(&output);
+	assert!(stdout(&output).contains("Cleared All from the Cache"));
+	assert_eq!(entries(&cache), 0);
+
+	fs::remove_dir_all(&home).unwrap();
+}
+
+/// Runs the executable in `home`, which is also used as its home directory, so that it has its own cache.
+fn spiderfire(home: &Path, args: &[&str]) -> Output {
+	Command::new(env!("CARGO_BIN_EXE_cli"))
+		.args(args)
+		.current_dir(home)
+		.env("HOME", home)
+		.output()
+		.unwrap()
+}
+
+fn assert_success(output: &Output) {
+	assert!(output.status.success(), "{}", stderr(output));
+}
+
+fn stdout(output: &Output) -> String {
+	String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+fn stderr(output: &Output) -> String {
+	String::from_utf8_lossy(&output.stderr).into_owned()
+}
+
+fn entries(directory: &Path) -> usize {
+	fs::read_dir(directory).map(|entries| entries.count()).unwrap_or(0)
+}
+
+/// Serves the remote modules of the test from a local server, counting the requests it receives, and returns its origin.
+fn serve(requests: Arc<AtomicUsize>) -> String {
+	let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+	let origin = format!("http://{}", listener.local_addr().unwrap());
+
+	thread::spawn(move || {
+		for stream in listener.incoming().flatten() {
+			requests.fetch_add(1, Ordering::SeqCst);
+			respond(stream);
+		}
+	});
+	origin
+}
+
+fn respond(mut stream: TcpStream) {
+	let mut reader = BufReader::new(&stream);
+	let mut request_line = String::new();
+	if reader.read_line(&mut request_line).is_err() {
+		return;
+	}
+	let mut line = String::new();
+	while reader.read_line(&mut line).is_ok_and(|read| read > 2) {
+		line.clear();
+	}
+
+	let path = request_line.split_whitespace().nth(1).unwrap_or("/");
+	let (status, body) = match path {
+		"/remote.js" => ("200 OK", r#"export {value as remote} from "./nested.js";"#),
+		"/nested.js" => ("200 OK", r#"export const value = "remote";"#),
+		"/mapped.js" => ("200 OK", r#"export const mapped = "mapped";"#),
+		"/uncached.js" => ("200 OK", ""),
+		_ => ("404 Not Found", ""),
+	};
+
+	let response = format!(
+		"HTTP/1.1 {}\r\nContent-Type: text/javascript\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+		status,
+		body.len(),
+		body
+	);
+	let _ = stream.write_all(response.as_bytes());
+}
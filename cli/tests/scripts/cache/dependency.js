@@ -0,0 +1 @@
+export { mapped } from "mapped";
@@ -0,0 +1,4 @@
+import { remote } from "{origin}/remote.js";
+import { mapped } from "./dependency.js";
+
+console.log(remote, mapped);
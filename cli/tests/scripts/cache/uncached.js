@@ -0,0 +1 @@
+import "{origin}/uncached.js";
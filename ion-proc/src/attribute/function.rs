@@ -12,6 +12,7 @@ mod keywords {
 	custom_keyword!(varargs);
 	custom_keyword!(convert);
 	custom_keyword!(strict);
+	custom_keyword!(default);
 }
 
 #[allow(dead_code)]
@@ -37,12 +38,37 @@ impl Parse for ConvertAttribute {
 	}
 }
 
+#[allow(dead_code)]
+#[derive(Debug)]
+pub(crate) struct DefaultAttribute {
+	kw: keywords::default,
+	pub(crate) default: Option<(Token![=], Box<Expr>)>,
+}
+
+impl Parse for DefaultAttribute {
+	fn parse(input: ParseStream) -> Result<DefaultAttribute> {
+		let lookahead = input.lookahead1();
+		if lookahead.peek(keywords::default) {
+			let kw = input.parse()?;
+			let default = if input.peek(Token![=]) {
+				Some((input.parse()?, input.parse()?))
+			} else {
+				None
+			};
+			Ok(DefaultAttribute { kw, default })
+		} else {
+			Err(lookahead.error())
+		}
+	}
+}
+
 #[derive(Debug)]
 pub(crate) enum ParameterAttribute {
 	This(keywords::this),
 	VarArgs(keywords::varargs),
 	Convert(ConvertAttribute),
 	Strict(keywords::strict),
+	Default(DefaultAttribute),
 }
 
 impl Parse for ParameterAttribute {
@@ -58,6 +84,8 @@ impl Parse for ParameterAttribute {
 			Ok(PA::Convert(input.parse()?))
 		} else if lookahead.peek(keywords::strict) {
 			Ok(PA::Strict(input.parse()?))
+		} else if lookahead.peek(keywords::default) {
+			Ok(PA::Default(input.parse()?))
 		} else {
 			Err(lookahead.error())
 		}
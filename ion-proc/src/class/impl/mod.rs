@@ -217,6 +217,10 @@ fn class_definition(
 			Self::__ion_native_class()
 		}
 
+		fn parent_class_info(cx: &#ion::Context) -> ::std::option::Option<(&'static #ion::class::NativeClass, #ion::Local<*mut ::mozjs::jsapi::JSObject>)> {
+			Self::__ion_parent_class_prototype(cx)
+		}
+
 		fn constructor() -> (#ion::functions::NativeFunction, ::core::primitive::u32) {
 			(Self::__ion_bindings_constructor, #constructor_nargs)
 		}
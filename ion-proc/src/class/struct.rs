@@ -116,6 +116,14 @@ fn class_impls(ion: &TokenStream, span: Span, name: &str, r#type: &Type, super_f
 			proto_chain
 		}
 
+		pub fn __ion_class_prototype(cx: &#ion::Context) -> ::std::option::Option<(&'static #ion::class::NativeClass, #ion::Local<*mut ::mozjs::jsapi::JSObject>)> {
+			#ion::class::ClassInfo::of::<#r#type>(cx).map(|info| (info.class(), info.prototype(cx)))
+		}
+
+		pub fn __ion_parent_class_prototype(cx: &#ion::Context) -> ::std::option::Option<(&'static #ion::class::NativeClass, #ion::Local<*mut ::mozjs::jsapi::JSObject>)> {
+			#super_type::__ion_class_prototype(cx)
+		}
+
 		pub const fn __ion_native_class() -> &'static #ion::class::NativeClass {
 			const ION_CLASS_OPERATIONS: ::mozjs::jsapi::JSClassOps = ::mozjs::jsapi::JSClassOps {
 				addProperty: #none,
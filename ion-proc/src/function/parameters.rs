@@ -23,12 +23,14 @@ pub(crate) enum Parameter {
 		conversion: Box<Expr>,
 		strict: bool,
 		option: Option<Box<Type>>,
+		default: Option<Box<Expr>>,
 	},
 	VarArgs {
 		pat: Box<Pat>,
 		ty: Box<Type>,
 		conversion: Box<Expr>,
 		strict: bool,
+		rest: bool,
 	},
 	Context(Box<Pat>, Box<Type>),
 	Arguments(Box<Pat>, Box<Type>),
@@ -76,6 +78,7 @@ impl Parameter {
 
 				let mut conversion = None;
 				let mut strict = false;
+				let mut default = None;
 
 				for attr in &pat_ty.attrs {
 					if attr.path().is_ident("ion") {
@@ -93,6 +96,13 @@ impl Parameter {
 								PA::Strict(_) => {
 									strict = true;
 								}
+								PA::Default(attr) => {
+									default = Some(
+										attr.default
+											.map(|(_, default)| default)
+											.unwrap_or_else(|| parse_quote!(::std::default::Default::default())),
+									);
+								}
 								_ => (),
 							}
 						}
@@ -101,8 +111,12 @@ impl Parameter {
 
 				let conversion = conversion.unwrap_or_else(|| parse_quote!(()));
 
+				let mut rest = false;
 				if let Type::Path(ty) = &*ty {
-					if path_ends_with(&ty.path, "Option") {
+					if path_ends_with(&ty.path, "Rest") {
+						rest = true;
+						vararg = true;
+					} else if path_ends_with(&ty.path, "Option") || path_ends_with(&ty.path, "Opt") {
 						let option_segment = ty.path.segments.last().unwrap();
 						if let PathArguments::AngleBracketed(inner) = &option_segment.arguments {
 							if let GenericArgument::Type(inner) = inner.args.last().unwrap() {
@@ -113,9 +127,19 @@ impl Parameter {
 				}
 
 				if vararg {
-					Ok(Parameter::VarArgs { pat, ty, conversion, strict })
+					if default.is_some() {
+						return Err(Error::new(pat_ty.span(), "Variadic parameters cannot have a default value"));
+					}
+					Ok(Parameter::VarArgs { pat, ty, conversion, strict, rest })
 				} else {
-					Ok(Parameter::Regular { pat, ty, conversion, strict, option })
+					Ok(Parameter::Regular {
+						pat,
+						ty,
+						conversion,
+						strict,
+						option,
+						default,
+					})
 				}
 			}
 			FnArg::Receiver(_) => unreachable!(),
@@ -134,8 +158,13 @@ impl Parameter {
 		use Parameter as P;
 		let ty = self.get_type_without_lifetimes();
 		match self {
-			P::Regular { pat, conversion, strict, option, .. } => regular_param_statement(ion, pat, &ty, option.as_deref(), conversion, *strict),
-			P::VarArgs { pat, conversion, strict, .. } => varargs_param_statement(pat, &ty, conversion, *strict),
+			P::Regular {
+				pat, conversion, strict, option, default, ..
+			} => match default {
+				Some(default) => default_param_statement(pat, &ty, default, conversion, *strict),
+				None => regular_param_statement(ion, pat, &ty, option.as_deref(), conversion, *strict),
+			},
+			P::VarArgs { pat, conversion, strict, rest, .. } => varargs_param_statement(ion, pat, &ty, conversion, *strict, *rest),
 			P::Context(pat, _) => parse2(quote!(let #pat: #ty = __cx;)),
 			P::Arguments(pat, _) => parse2(quote!(let #pat: #ty = __args;)),
 		}
@@ -239,8 +268,15 @@ impl Parameters {
 				}
 				let param = Parameter::from_arg(arg);
 				match param {
-					Ok(Parameter::Regular { pat, ty, conversion, strict, option }) => {
-						if option.is_none() {
+					Ok(Parameter::Regular {
+						pat,
+						ty,
+						conversion,
+						strict,
+						option,
+						default,
+					}) => {
+						if option.is_none() && default.is_none() {
 							nargs.0 += 1;
 						} else {
 							nargs.1 += 1;
@@ -248,13 +284,20 @@ impl Parameters {
 						if let Some(ident) = get_ident(&pat) {
 							idents.push(ident);
 						}
-						Some(Ok(Parameter::Regular { pat, ty, conversion, strict, option }))
+						Some(Ok(Parameter::Regular {
+							pat,
+							ty,
+							conversion,
+							strict,
+							option,
+							default,
+						}))
 					}
-					Ok(Parameter::VarArgs { pat, ty, conversion, strict }) => {
+					Ok(Parameter::VarArgs { pat, ty, conversion, strict, rest }) => {
 						if let Some(ident) = get_ident(&pat) {
 							idents.push(ident);
 						}
-						Some(Ok(Parameter::VarArgs { pat, ty, conversion, strict }))
+						Some(Ok(Parameter::VarArgs { pat, ty, conversion, strict, rest }))
 					}
 					Ok(Parameter::Context(pat, ty)) => {
 						if let Some(ident) = get_ident(&pat) {
@@ -339,7 +382,7 @@ fn regular_param_statement(ion: &TokenStream, pat: &Pat, ty: &Type, option: Opti
 		String::from("Argument at index {{}} was not found.")
 	};
 	let if_none: Expr = if option.is_some() {
-		parse2(quote!(::std::option::Option::None)).unwrap()
+		parse2(quote!(::std::default::Default::default())).unwrap()
 	} else {
 		parse2(quote!(return Err(#ion::Error::new(#not_found_error, #ion::ErrorKind::Type).into()))).unwrap()
 	};
@@ -352,8 +395,21 @@ fn regular_param_statement(ion: &TokenStream, pat: &Pat, ty: &Type, option: Opti
 	))
 }
 
-fn varargs_param_statement(pat: &Pat, ty: &Type, conversion: &Expr, strict: bool) -> Result<Stmt> {
-	parse2(quote!(let #pat: #ty = unsafe { __accessor.args(#strict, #conversion)? };))
+fn default_param_statement(pat: &Pat, ty: &Type, default: &Expr, conversion: &Expr, strict: bool) -> Result<Stmt> {
+	parse2(quote!(
+		let #pat: #ty = match unsafe { __accessor.arg::<::std::option::Option<#ty>>(#strict, #conversion) } {
+			::std::option::Option::Some(value) => value?.unwrap_or_else(|| #default),
+			::std::option::Option::None => #default,
+		};
+	))
+}
+
+fn varargs_param_statement(ion: &TokenStream, pat: &Pat, ty: &Type, conversion: &Expr, strict: bool, rest: bool) -> Result<Stmt> {
+	if rest {
+		parse2(quote!(let #pat: #ty = #ion::functions::Rest(unsafe { __accessor.args(#strict, #conversion)? });))
+	} else {
+		parse2(quote!(let #pat: #ty = unsafe { __accessor.args(#strict, #conversion)? };))
+	}
 }
 
 pub(crate) fn get_ident(pat: &Pat) -> Option<Ident> {
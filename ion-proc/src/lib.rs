@@ -16,7 +16,7 @@ use quote::ToTokens;
 use crate::class::impl_js_class;
 use crate::function::impl_js_fn;
 use crate::trace::impl_trace;
-use crate::value::impl_from_value;
+use crate::value::{impl_from_value, impl_to_value};
 
 pub(crate) mod attribute;
 pub(crate) mod class;
@@ -57,3 +57,11 @@ pub fn from_value(input: TokenStream) -> TokenStream {
 		Err(error) => error.to_compile_error().into(),
 	}
 }
+
+#[proc_macro_derive(ToValue, attributes(ion))]
+pub fn to_value(input: TokenStream) -> TokenStream {
+	match impl_to_value(parse_macro_input!(input)) {
+		Ok(to_value) => to_value.into_token_stream().into(),
+		Err(error) => error.to_compile_error().into(),
+	}
+}
@@ -15,6 +15,7 @@ mod keywords {
 	custom_keyword!(skip);
 
 	custom_keyword!(name);
+	custom_keyword!(rename);
 	custom_keyword!(convert);
 	custom_keyword!(strict);
 	custom_keyword!(parser);
@@ -99,6 +100,8 @@ impl Parse for DataAttribute {
 #[allow(dead_code)]
 #[derive(Debug)]
 pub(crate) enum VariantAttribute {
+	Name { kw: keywords::name, eq: Token![=], name: LitStr },
+	Rename { kw: keywords::rename, eq: Token![=], name: LitStr },
 	Tag(Tag),
 	Inherit(keywords::inherit),
 	Skip(keywords::skip),
@@ -109,7 +112,19 @@ impl Parse for VariantAttribute {
 		use VariantAttribute as VA;
 
 		let lookahead = input.lookahead1();
-		if lookahead.peek(keywords::untagged) || lookahead.peek(keywords::tag) {
+		if lookahead.peek(keywords::name) {
+			Ok(VA::Name {
+				kw: input.parse()?,
+				eq: input.parse()?,
+				name: input.parse()?,
+			})
+		} else if lookahead.peek(keywords::rename) {
+			Ok(VA::Rename {
+				kw: input.parse()?,
+				eq: input.parse()?,
+				name: input.parse()?,
+			})
+		} else if lookahead.peek(keywords::untagged) || lookahead.peek(keywords::tag) {
 			Ok(VA::Tag(input.parse()?))
 		} else if lookahead.peek(keywords::inherit) {
 			Ok(VA::Inherit(input.parse()?))
@@ -129,6 +144,11 @@ pub(crate) enum FieldAttribute {
 		eq: Token![=],
 		name: LitStr,
 	},
+	Rename {
+		kw: keywords::rename,
+		eq: Token![=],
+		name: LitStr,
+	},
 	Inherit(keywords::inherit),
 	Skip(keywords::skip),
 	Convert {
@@ -160,6 +180,12 @@ impl Parse for FieldAttribute {
 				eq: input.parse()?,
 				name: input.parse()?,
 			})
+		} else if lookahead.peek(keywords::rename) {
+			Ok(FA::Rename {
+				kw: input.parse()?,
+				eq: input.parse()?,
+				name: input.parse()?,
+			})
 		} else if lookahead.peek(keywords::inherit) {
 			Ok(FA::Inherit(input.parse()?))
 		} else if lookahead.peek(keywords::skip) {
@@ -131,6 +131,7 @@ fn impl_body(ion: &TokenStream, span: Span, data: &Data, ident: &Ident, tag: Tag
 
 					let mut tag = tag.clone();
 					let mut inherit = inherit;
+					let mut name = None;
 
 					for attr in &variant.attrs {
 						if attr.path().is_ident("ion") {
@@ -141,6 +142,9 @@ fn impl_body(ion: &TokenStream, span: Span, data: &Data, ident: &Ident, tag: Tag
 
 							for arg in args {
 								match arg {
+									VariantAttribute::Name { name: variant_name, .. } | VariantAttribute::Rename { name: variant_name, .. } => {
+										name = Some(variant_name.value());
+									}
 									VariantAttribute::Tag(variant_tag) => {
 										tag = variant_tag;
 									}
@@ -210,6 +214,17 @@ fn impl_body(ion: &TokenStream, span: Span, data: &Data, ident: &Ident, tag: Tag
 									);
 								}
 							}
+							if unit && repr.is_none() {
+								let name = name.unwrap_or_else(|| variant_string.to_case(Case::Kebab));
+								return Some(
+									parse2(quote_spanned!(variant.span() => {
+										if __string == #name {
+											return ::std::result::Result::Ok(Self::#variant_ident);
+										}
+									}))
+									.map(|block| (block, false)),
+								);
+							}
 							Some(parse2(quote!({return ::std::result::Result::Ok(Self::#variant_ident);})).map(|block| (block, false)))
 						}
 					}
@@ -227,6 +242,10 @@ fn impl_body(ion: &TokenStream, span: Span, data: &Data, ident: &Ident, tag: Tag
 					if_unit = Some(
 						quote_spanned!(repr.span() => let discriminant: #repr = #ion::conversions::FromValue::from_value(cx, value, true, #ion::conversions::ConversionBehavior::EnforceRange)?;),
 					);
+				} else {
+					if_unit = Some(
+						quote_spanned!(span => let __string: ::std::string::String = #ion::conversions::FromValue::from_value(cx, value, true, ())?;),
+					);
 				}
 			}
 
@@ -316,14 +335,15 @@ fn map_fields(
 					for arg in args {
 						use FieldAttribute as FA;
 						match arg {
-							FA::Name { name, .. } => {
+							FA::Name { name, .. } | FA::Rename { name, .. } => {
 								key = name.value();
 							}
 							FA::Inherit(_) => {
 								inherit = true;
 							}
 							FA::Skip(_) => {
-								return None;
+								let stmt = quote_spanned!(field.span() => let #ident: #ty = ::std::default::Default::default(););
+								return Some(Ok((ident, stmt)));
 							}
 							FA::Convert { expr, .. } => {
 								convert = Some(expr);
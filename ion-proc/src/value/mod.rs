@@ -5,6 +5,8 @@
  */
 
 pub(crate) use from::*;
+pub(crate) use to::*;
 
 pub(crate) mod attribute;
 pub(crate) mod from;
+pub(crate) mod to;
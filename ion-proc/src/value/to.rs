@@ -0,0 +1,233 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use convert_case::{Case, Casing};
+use proc_macro2::{Ident, Span, TokenStream};
+use syn::{Data, DeriveInput, Error, Field, Fields, GenericParam, ItemImpl, Meta, parse2, Result};
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+
+use crate::attribute::krate::crate_from_attributes;
+use crate::utils::add_trait_bounds;
+use crate::value::attribute::{DataAttribute, FieldAttribute, Tag, VariantAttribute};
+
+pub(crate) fn impl_to_value(input: DeriveInput) -> Result<ItemImpl> {
+	let ion = &crate_from_attributes(&input.attrs);
+
+	let mut impl_generics = input.generics.clone();
+	let has_cx = impl_generics.params.iter().any(|param| {
+		if let GenericParam::Lifetime(lt) = param {
+			lt.lifetime == parse_quote!('cx)
+		} else {
+			false
+		}
+	});
+	if !has_cx {
+		impl_generics.params.push(parse2(quote!('cx))?);
+	}
+	add_trait_bounds(&mut impl_generics, &parse_quote!(#ion::conversions::ToValue<'cx>));
+	let (impl_generics, _, _) = impl_generics.split_for_impl();
+	let (_, ty_generics, where_clause) = input.generics.split_for_impl();
+
+	let mut tag = Tag::default();
+	let mut repr = None;
+	for attr in &input.attrs {
+		if attr.path().is_ident("ion") {
+			let args: Punctuated<DataAttribute, Token![,]> = attr.parse_args_with(Punctuated::parse_terminated)?;
+
+			for arg in args {
+				match arg {
+					DataAttribute::Tag(data_tag) => {
+						tag = data_tag;
+					}
+					DataAttribute::Inherit(kw) => {
+						return Err(Error::new(kw.span(), "#[derive(ToValue)] does not support inherited data"));
+					}
+				}
+			}
+		} else if attr.path().is_ident("repr") {
+			let nested = attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)?;
+			for meta in nested {
+				if let Meta::Path(path) = &meta {
+					let ident = path.get_ident().map(Ident::to_string).unwrap_or_default();
+					if matches!(ident.as_str(), "i8" | "i16" | "i32" | "i64" | "u8" | "u16" | "u32" | "u64") {
+						repr = path.get_ident().cloned();
+					}
+				}
+			}
+		}
+	}
+
+	let name = &input.ident;
+	let body = impl_body(ion, input.span(), &input.data, tag, repr)?;
+
+	parse2(quote_spanned!(input.span() =>
+		#[automatically_derived]
+		impl #impl_generics #ion::conversions::ToValue<'cx> for #name #ty_generics #where_clause {
+			fn to_value(&self, cx: &'cx #ion::Context, value: &mut #ion::Value) {
+				#body
+			}
+		}
+	))
+}
+
+fn impl_body(ion: &TokenStream, span: Span, data: &Data, tag: Tag, repr: Option<Ident>) -> Result<TokenStream> {
+	match data {
+		Data::Struct(data) => {
+			let (pattern, statements) = map_fields(&data.fields)?;
+			let object = object_from_fields(ion, span, statements);
+			Ok(quote_spanned!(span => {
+				let Self #pattern = self;
+				#object
+			}))
+		}
+		Data::Enum(data) => {
+			let unit = data.variants.iter().all(|variant| matches!(variant.fields, Fields::Unit));
+
+			let arms = data
+				.variants
+				.iter()
+				.map(|variant| {
+					let variant_ident = &variant.ident;
+					let variant_string = variant_ident.to_string();
+
+					let mut tag = tag.clone();
+					let mut name = None;
+					for attr in &variant.attrs {
+						if attr.path().is_ident("ion") {
+							let args: Punctuated<VariantAttribute, Token![,]> = attr.parse_args_with(Punctuated::parse_terminated)?;
+							for arg in args {
+								match arg {
+									VariantAttribute::Name { name: variant_name, .. } | VariantAttribute::Rename { name: variant_name, .. } => {
+										name = Some(variant_name.value());
+									}
+									VariantAttribute::Tag(variant_tag) => {
+										tag = variant_tag;
+									}
+									VariantAttribute::Inherit(kw) => {
+										return Err(Error::new(kw.span(), "#[derive(ToValue)] does not support inherited variants"));
+									}
+									VariantAttribute::Skip(_) => {
+										return Ok(quote_spanned!(variant.span() => Self::#variant_ident { .. } => ().to_value(cx, value),));
+									}
+								}
+							}
+						}
+					}
+
+					if unit {
+						return Ok(if let Some(repr) = &repr {
+							quote_spanned!(variant.span() => Self::#variant_ident => (Self::#variant_ident as #repr).to_value(cx, value),)
+						} else {
+							let name = name.unwrap_or_else(|| variant_string.to_case(Case::Kebab));
+							quote_spanned!(variant.span() => Self::#variant_ident => #name.to_value(cx, value),)
+						});
+					}
+
+					let (pattern, mut statements) = map_fields(&variant.fields)?;
+					let arm = match tag {
+						Tag::Untagged(_) => object_from_fields(ion, variant.span(), statements),
+						Tag::External(_) => {
+							let inner = object_from_fields(ion, variant.span(), statements);
+							quote_spanned!(variant.span() => {
+								let mut __inner = #ion::Value::undefined(cx);
+								{
+									let value = &mut __inner;
+									#inner
+								}
+								let mut __object = #ion::Object::new(cx);
+								__object.set(cx, #variant_string, &__inner);
+								__object.to_value(cx, value);
+							})
+						}
+						Tag::Internal { key, .. } => {
+							statements.insert(0, quote_spanned!(key.span() => __object.set_as(cx, #key, #variant_string);));
+							object_from_fields(ion, variant.span(), statements)
+						}
+					};
+					Ok(quote_spanned!(variant.span() => Self::#variant_ident #pattern => #arm,))
+				})
+				.collect::<Result<Vec<_>>>()?;
+
+			Ok(quote_spanned!(span => {
+				use #ion::conversions::ToValue;
+				match self {
+					#(#arms)*
+				}
+			}))
+		}
+		Data::Union(_) => Err(Error::new(span, "#[derive(ToValue)] is not implemented for union types")),
+	}
+}
+
+fn object_from_fields(ion: &TokenStream, span: Span, statements: Vec<TokenStream>) -> TokenStream {
+	quote_spanned!(span => {
+		#[allow(unused_mut)]
+		let mut __object = #ion::Object::new(cx);
+		#(#statements)*
+		#ion::conversions::ToValue::to_value(&__object, cx, value);
+	})
+}
+
+fn map_fields(fields: &Fields) -> Result<(TokenStream, Vec<TokenStream>)> {
+	let fields: Vec<&Field> = match fields {
+		Fields::Named(fields) => fields.named.iter().collect(),
+		Fields::Unnamed(fields) => fields.unnamed.iter().collect(),
+		Fields::Unit => return Ok((quote!(), Vec::new())),
+	};
+
+	let mut patterns = Vec::with_capacity(fields.len());
+	let mut statements = Vec::with_capacity(fields.len());
+
+	for (index, field) in fields.iter().enumerate() {
+		let (binding, mut key) = if let Some(ident) = &field.ident {
+			(ident.clone(), ident.to_string().to_case(Case::Camel))
+		} else {
+			(format_ident!("field{}", index), index.to_string())
+		};
+
+		let mut skip = false;
+		for attr in &field.attrs {
+			if attr.path().is_ident("ion") {
+				let args: Punctuated<FieldAttribute, Token![,]> = attr.parse_args_with(Punctuated::parse_terminated)?;
+				for arg in args {
+					use FieldAttribute as FA;
+					match arg {
+						FA::Name { name, .. } | FA::Rename { name, .. } => {
+							key = name.value();
+						}
+						FA::Skip(_) => {
+							skip = true;
+						}
+						FA::Inherit(kw) => {
+							return Err(Error::new(kw.span(), "#[derive(ToValue)] does not support inherited fields"));
+						}
+						FA::Convert { .. } | FA::Strict(_) | FA::Default { .. } | FA::Parser { .. } => {}
+					}
+				}
+			}
+		}
+
+		if skip {
+			patterns.push(match &field.ident {
+				Some(ident) => quote!(#ident: _),
+				None => quote!(_),
+			});
+		} else {
+			patterns.push(match &field.ident {
+				Some(ident) => quote!(#ident),
+				None => quote!(#binding),
+			});
+			statements.push(quote_spanned!(field.span() => __object.set_as(cx, #key, #binding);));
+		}
+	}
+
+	let pattern = match fields.first().and_then(|field| field.ident.as_ref()) {
+		Some(_) => quote!({ #(#patterns,)* }),
+		None => quote!((#(#patterns,)*)),
+	};
+	Ok((pattern, statements))
+}
@@ -7,3 +7,11 @@ enum Representation {
 	One = 1,
 	Ten = 10,
 }
+
+#[derive(FromValue)]
+enum Redirect {
+	Follow,
+	Error,
+	#[ion(rename = "manual")]
+	Stop,
+}
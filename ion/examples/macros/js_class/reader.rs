@@ -0,0 +1,44 @@
+use std::io::{Cursor, Read};
+
+use ion::{Error, js_class, Result};
+use ion::class::Reflector;
+use ion::conversions::ConversionBehavior;
+
+#[js_class]
+pub struct Reader {
+	reflector: Reflector,
+	#[ion(no_trace)]
+	cursor: Cursor<Vec<u8>>,
+	#[ion(readonly)]
+	pub length: u32,
+}
+
+#[js_class]
+impl Reader {
+	#[ion(constructor)]
+	pub fn constructor(data: String) -> Reader {
+		let data = data.into_bytes();
+		Reader {
+			reflector: Reflector::default(),
+			length: data.len() as u32,
+			cursor: Cursor::new(data),
+		}
+	}
+
+	pub fn read(&mut self, #[ion(convert = ConversionBehavior::EnforceRange)] count: u32) -> Result<String> {
+		let mut buffer = vec![0; count as usize];
+		let read = self.cursor.read(&mut buffer).map_err(|error| Error::new(&error.to_string(), None))?;
+		buffer.truncate(read);
+		String::from_utf8(buffer).map_err(|error| Error::new(&error.to_string(), None))
+	}
+
+	#[ion(get)]
+	pub fn get_position(&self) -> u32 {
+		self.cursor.position() as u32
+	}
+
+	#[ion(name = "isEmpty")]
+	pub fn is_empty(data: String) -> bool {
+		data.is_empty()
+	}
+}
@@ -2,6 +2,7 @@ pub mod complex;
 pub mod context;
 pub mod integer;
 pub mod object;
+pub mod optional;
 pub mod output;
 pub mod regular;
 pub mod this;
@@ -0,0 +1,12 @@
+use ion::{js_fn, Value};
+use ion::conversions::ConversionBehavior;
+use ion::functions::{Opt, Rest};
+
+#[js_fn]
+pub fn optional(_string: Opt<String>) {}
+
+#[js_fn]
+pub fn default(#[ion(default)] _boolean: bool, #[ion(convert = ConversionBehavior::Clamp, default = 10)] _integer: i32) {}
+
+#[js_fn]
+pub fn rest(_first: String, _rest: Rest<Value>) {}
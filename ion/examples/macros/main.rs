@@ -3,3 +3,4 @@
 pub mod from_value;
 pub mod js_class;
 pub mod js_fn;
+pub mod to_value;
@@ -0,0 +1,15 @@
+use ion::ToValue;
+
+#[derive(ToValue)]
+pub enum Mode {
+	SameOrigin,
+	NoCors,
+	Cors,
+}
+
+#[derive(ToValue)]
+#[ion(tag = "type")]
+pub enum Entry {
+	File { name: String, size: u32 },
+	Directory { name: String },
+}
@@ -0,0 +1,2 @@
+pub mod enumeration;
+pub mod structure;
@@ -0,0 +1,11 @@
+use ion::{FromValue, ToValue};
+
+#[derive(FromValue, ToValue)]
+pub struct Stats {
+	pub size: u32,
+	#[ion(rename = "isFile")]
+	pub file: bool,
+	pub modified: Option<f64>,
+	#[ion(skip)]
+	pub inode: u64,
+}
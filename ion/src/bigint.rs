@@ -15,6 +15,7 @@ use mozjs::jsapi::mozilla::{Range, RangedPtr};
 
 use crate::{Context, Exception, Local, String};
 
+/// Represents a JavaScript [BigInt](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/BigInt).
 pub struct BigInt<'b> {
 	bi: Local<'b, *mut JSBigInt>,
 }
@@ -35,6 +36,14 @@ impl<'b> BigInt<'b> {
 		BigInt::from(cx.root_bigint(unsafe { BigIntFromUint64(cx.as_ptr(), number) }))
 	}
 
+	/// Creates a [BigInt] from a 128-bit signed integer.
+	pub fn from_i128(cx: &Context, number: i128) -> BigInt {
+		match i64::try_from(number) {
+			Ok(number) => BigInt::from_i64(cx, number),
+			Err(_) => BigInt::from_string(cx, &number.to_string()).ok().unwrap(),
+		}
+	}
+
 	/// Creates a [BigInt] from a double.
 	/// Returns an error if `number` is `NaN`, `Infinity`, `-Infinity` or contains a fractional component.
 	pub fn from_f64(cx: &Context, number: f64) -> Result<BigInt, Exception> {
@@ -89,6 +98,14 @@ impl<'b> BigInt<'b> {
 		unsafe { BigIntIsUint64(self.get(), &mut result).then_some(result) }
 	}
 
+	/// Converts a [BigInt] to a 128-bit signed integer if possible.
+	pub fn to_i128(&self, cx: &Context) -> Option<i128> {
+		match self.to_i64() {
+			Some(number) => Some(number as i128),
+			None => self.to_string(cx, 10).and_then(|string| string.to_owned(cx).parse().ok()),
+		}
+	}
+
 	/// Converts a [BigInt] to a double.
 	/// Returns `Infinity` or `-Infinity` if it does not fit in a double.
 	pub fn to_f64(&self) -> f64 {
@@ -15,6 +15,7 @@ use mozjs::jsapi::{
 	JSPropertySpec,
 };
 use mozjs::jsval::{PrivateValue, UndefinedValue};
+use mozjs::rust::get_object_class;
 
 use crate::{Arguments, Context, Function, Local, Object};
 pub use crate::class::native::{MAX_PROTO_CHAIN_LENGTH, NativeClass, PrototypeChain, TypeIdWrapper};
@@ -33,6 +34,22 @@ pub struct ClassInfo {
 	prototype: *mut JSObject,
 }
 
+impl ClassInfo {
+	/// Returns the information of a class, if it has been initialised.
+	pub fn of<T: ClassDefinition>(cx: &Context) -> Option<&ClassInfo> {
+		let infos = unsafe { &(*cx.get_inner_data().as_ptr()).class_infos };
+		infos.get(&TypeId::of::<T>())
+	}
+
+	pub fn class(&self) -> &'static NativeClass {
+		self.class
+	}
+
+	pub fn prototype<'cx>(&self, cx: &'cx Context) -> Local<'cx, *mut JSObject> {
+		cx.root_object(self.prototype)
+	}
+}
+
 pub trait ClassDefinition: NativeObject {
 	const NAME: &'static str;
 
@@ -152,4 +169,19 @@ pub trait ClassDefinition: NativeObject {
 			JS_InstanceOf(cx.as_ptr(), object.handle().into(), &Self::class().base, args)
 		}
 	}
+
+	/// Checks if an object is an instance of this class, or of a native class derived from it.
+	fn instance_of_derived(cx: &Context, object: &Object) -> bool {
+		let class = unsafe { get_object_class(object.handle().get()) };
+		let infos = unsafe { &(*cx.get_inner_data().as_ptr()).class_infos };
+		infos.values().any(|info| {
+			ptr::eq(&info.class.base, class)
+				&& info
+					.class
+					.prototype_chain
+					.iter()
+					.flatten()
+					.any(|proto| proto.type_id() == TypeId::of::<Self>())
+		})
+	}
 }
@@ -10,6 +10,7 @@ use mozjs::gc::Traceable;
 use mozjs::jsapi::{Heap, JSObject, JSTracer};
 use mozjs::rust::{get_object_class, Handle};
 
+use crate::{Context, Local};
 use crate::class::{NativeClass, PrototypeChain};
 
 pub trait NativeObject: Traceable + Sized + 'static {
@@ -76,6 +77,11 @@ impl Reflector {
 	pub const fn __ion_native_prototype_chain() -> PrototypeChain {
 		[None; 8]
 	}
+
+	#[doc(hidden)]
+	pub fn __ion_class_prototype(_: &Context) -> Option<(&'static NativeClass, Local<*mut JSObject>)> {
+		None
+	}
 }
 
 unsafe impl Traceable for Reflector {
@@ -23,6 +23,7 @@ use typed_arena::Arena;
 use crate::class::ClassInfo;
 use crate::Local;
 use crate::module::ModuleLoader;
+use crate::objects::FutureSpawner;
 
 /// Represents Types that can be Rooted in SpiderMonkey
 pub enum GCType {
@@ -60,6 +61,7 @@ pub struct Persistent {
 pub struct ContextInner {
 	pub class_infos: HashMap<TypeId, ClassInfo>,
 	pub module_loader: Option<Box<dyn ModuleLoader>>,
+	pub future_spawner: Option<FutureSpawner>,
 	persistent: Persistent,
 	private: *mut c_void,
 }
@@ -69,6 +71,7 @@ impl Default for ContextInner {
 		ContextInner {
 			class_infos: HashMap::new(),
 			module_loader: None,
+			future_spawner: None,
 			persistent: Persistent::default(),
 			private: ptr::null_mut(),
 		}
@@ -27,7 +27,10 @@ macro_rules! impl_to_key_for_integer {
 	($ty:ty) => {
 		impl<'cx> ToPropertyKey<'cx> for $ty {
 			fn to_key(&self, cx: &'cx Context) -> Option<PropertyKey<'cx>> {
-				Some(PropertyKey::with_int(cx, *self as i32))
+				match i32::try_from(*self) {
+					Ok(int) if int >= 0 => Some(PropertyKey::with_int(cx, int)),
+					_ => self.to_string().to_key(cx),
+				}
 			}
 		}
 	};
@@ -36,10 +39,14 @@ macro_rules! impl_to_key_for_integer {
 impl_to_key_for_integer!(i8);
 impl_to_key_for_integer!(i16);
 impl_to_key_for_integer!(i32);
+impl_to_key_for_integer!(i64);
+impl_to_key_for_integer!(isize);
 
 impl_to_key_for_integer!(u8);
 impl_to_key_for_integer!(u16);
 impl_to_key_for_integer!(u32);
+impl_to_key_for_integer!(u64);
+impl_to_key_for_integer!(usize);
 
 impl<'cx> ToPropertyKey<'cx> for *mut JSString {
 	fn to_key(&self, cx: &'cx Context) -> Option<PropertyKey<'cx>> {
@@ -4,6 +4,9 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/.
  */
 
+use std::time::SystemTime;
+
+use chrono::{DateTime, Utc};
 use mozjs::conversions::{ConversionResult, FromJSValConvertible};
 pub use mozjs::conversions::ConversionBehavior;
 use mozjs::jsapi::{
@@ -15,8 +18,10 @@ use mozjs::jsval::JSVal;
 use mozjs::rust::{ToBoolean, ToNumber, ToString};
 use mozjs::typedarray::{JSObjectStorage, TypedArray, TypedArrayElement};
 
-use crate::{Array, Context, Date, Error, ErrorKind, Exception, Function, Object, Promise, Result, StringRef, Symbol, Value};
+use crate::{Array, BigInt, Context, Date, Error, ErrorKind, Exception, Function, Map, Object, Promise, Result, Set, StringRef, Symbol, Value};
+use crate::functions::Opt;
 use crate::objects::RegExp;
+use crate::objects::typedarray::{SharedArrayBuffer, TypedArrayView};
 
 /// Represents types that can be converted to from [JavaScript Values](Value).
 pub trait FromValue<'cx>: Sized {
@@ -69,12 +74,77 @@ macro_rules! impl_from_value_for_integer {
 impl_from_value_for_integer!(u8);
 impl_from_value_for_integer!(u16);
 impl_from_value_for_integer!(u32);
-impl_from_value_for_integer!(u64);
 
 impl_from_value_for_integer!(i8);
 impl_from_value_for_integer!(i16);
 impl_from_value_for_integer!(i32);
-impl_from_value_for_integer!(i64);
+
+macro_rules! impl_from_value_for_bigint_integer {
+	($ty:ty, $convert:ident) => {
+		impl<'cx> FromValue<'cx> for $ty {
+			type Config = ConversionBehavior;
+
+			fn from_value(cx: &'cx Context, value: &Value, strict: bool, config: ConversionBehavior) -> Result<$ty> {
+				let handle = value.handle();
+				if handle.is_bigint() {
+					let bi = BigInt::from(cx.root_bigint(handle.to_bigint()));
+					return bi
+						.$convert()
+						.ok_or_else(|| Error::new(concat!("BigInt is out of range for ", stringify!($ty)), ErrorKind::Range));
+				}
+				if strict && !handle.is_number() {
+					return Err(Error::new("Expected Number or BigInt in Strict Conversion", ErrorKind::Type));
+				}
+				let unsafe_integer = handle.is_number() && handle.to_number().fract() == 0.0 && !is_safe_integer(handle.to_number());
+				if unsafe_integer && matches!(config, ConversionBehavior::Default) {
+					return Err(Error::new(
+						"Number cannot be represented losslessly as an integer",
+						ErrorKind::Range,
+					));
+				}
+
+				match unsafe { <$ty>::from_jsval(cx.as_ptr(), handle, config) } {
+					Ok(ConversionResult::Success(number)) => Ok(number),
+					Err(_) => Err(Exception::new(cx).unwrap().to_error()),
+					_ => unreachable!(),
+				}
+			}
+		}
+	};
+}
+
+impl_from_value_for_bigint_integer!(u64, to_u64);
+impl_from_value_for_bigint_integer!(i64, to_i64);
+
+impl<'cx> FromValue<'cx> for i128 {
+	type Config = ();
+
+	fn from_value(cx: &'cx Context, value: &Value, strict: bool, _: ()) -> Result<i128> {
+		let handle = value.handle();
+		if handle.is_bigint() {
+			let bi = BigInt::from(cx.root_bigint(handle.to_bigint()));
+			return bi
+				.to_i128(cx)
+				.ok_or_else(|| Error::new("BigInt is out of range for i128", ErrorKind::Range));
+		}
+		if strict && !handle.is_number() {
+			return Err(Error::new("Expected Number or BigInt in Strict Conversion", ErrorKind::Type));
+		}
+
+		let number = f64::from_value(cx, value, strict, ())?;
+		if is_safe_integer(number) {
+			Ok(number as i128)
+		} else {
+			Err(Error::new("Number cannot be represented losslessly as an integer", ErrorKind::Range))
+		}
+	}
+}
+
+/// Checks if `number` is an integer that can be represented exactly by a double.
+fn is_safe_integer(number: f64) -> bool {
+	const MAX_SAFE_INTEGER: f64 = ((1_u64 << 53) - 1) as f64;
+	number.fract() == 0.0 && number.abs() <= MAX_SAFE_INTEGER
+}
 
 impl<'cx> FromValue<'cx> for f32 {
 	type Config = ();
@@ -207,6 +277,63 @@ impl<'cx> FromValue<'cx> for Date<'cx> {
 	}
 }
 
+impl<'cx> FromValue<'cx> for DateTime<Utc> {
+	type Config = ();
+
+	fn from_value(cx: &'cx Context, value: &Value, strict: bool, _: ()) -> Result<DateTime<Utc>> {
+		let date = Date::from_value(cx, value, strict, ())?;
+		date.to_date(cx).ok_or_else(|| Error::new("Invalid Date", ErrorKind::Range))
+	}
+}
+
+impl<'cx> FromValue<'cx> for SystemTime {
+	type Config = ();
+
+	fn from_value(cx: &'cx Context, value: &Value, strict: bool, _: ()) -> Result<SystemTime> {
+		DateTime::<Utc>::from_value(cx, value, strict, ()).map(SystemTime::from)
+	}
+}
+
+impl<'cx> FromValue<'cx> for Map<'cx> {
+	type Config = ();
+
+	fn from_value(cx: &'cx Context, value: &Value, _: bool, _: ()) -> Result<Map<'cx>> {
+		if !value.handle().is_object() {
+			return Err(Error::new("Expected Map", ErrorKind::Type));
+		}
+
+		let object = value.to_object(cx).into_local();
+		if let Some(map) = Map::from(cx, object) {
+			unsafe {
+				AssertSameCompartment(cx.as_ptr(), map.get());
+			}
+			Ok(map)
+		} else {
+			Err(Error::new("Expected Map", ErrorKind::Type))
+		}
+	}
+}
+
+impl<'cx> FromValue<'cx> for Set<'cx> {
+	type Config = ();
+
+	fn from_value(cx: &'cx Context, value: &Value, _: bool, _: ()) -> Result<Set<'cx>> {
+		if !value.handle().is_object() {
+			return Err(Error::new("Expected Set", ErrorKind::Type));
+		}
+
+		let object = value.to_object(cx).into_local();
+		if let Some(set) = Set::from(cx, object) {
+			unsafe {
+				AssertSameCompartment(cx.as_ptr(), set.get());
+			}
+			Ok(set)
+		} else {
+			Err(Error::new("Expected Set", ErrorKind::Type))
+		}
+	}
+}
+
 impl<'cx> FromValue<'cx> for Promise<'cx> {
 	type Config = ();
 
@@ -332,6 +459,14 @@ impl<'cx, T: FromValue<'cx>> FromValue<'cx> for Option<T> {
 	}
 }
 
+impl<'cx, T: FromValue<'cx>> FromValue<'cx> for Opt<T> {
+	type Config = T::Config;
+
+	fn from_value(cx: &'cx Context, value: &Value, strict: bool, config: T::Config) -> Result<Opt<T>> {
+		Option::<T>::from_value(cx, value, strict, config).map(Opt)
+	}
+}
+
 // Copied from [rust-mozjs](https://github.com/servo/rust-mozjs/blob/master/src/conversions.rs#L619-L642)
 struct ForOfIteratorGuard<'a> {
 	root: &'a mut ForOfIterator,
@@ -409,3 +544,29 @@ impl<'cx, T: TypedArrayElement, S: JSObjectStorage> FromValue<'cx> for TypedArra
 		}
 	}
 }
+
+impl<'cx, T: TypedArrayElement> FromValue<'cx> for TypedArrayView<'cx, T> {
+	type Config = ();
+
+	fn from_value(cx: &'cx Context, value: &Value, _: bool, _: ()) -> Result<TypedArrayView<'cx, T>> {
+		let value = value.handle();
+		if value.is_object() {
+			TypedArrayView::from(cx.root_object(value.to_object())).ok_or_else(|| Error::new("Expected Typed Array", ErrorKind::Type))
+		} else {
+			Err(Error::new("Expected Object", ErrorKind::Type))
+		}
+	}
+}
+
+impl<'cx> FromValue<'cx> for SharedArrayBuffer<'cx> {
+	type Config = ();
+
+	fn from_value(cx: &'cx Context, value: &Value, _: bool, _: ()) -> Result<SharedArrayBuffer<'cx>> {
+		let value = value.handle();
+		if value.is_object() {
+			SharedArrayBuffer::from(cx.root_object(value.to_object())).ok_or_else(|| Error::new("Expected SharedArrayBuffer", ErrorKind::Type))
+		} else {
+			Err(Error::new("Expected Object", ErrorKind::Type))
+		}
+	}
+}
@@ -7,17 +7,22 @@
 use std::ptr::NonNull;
 use std::rc::Rc;
 use std::string::String as RustString;
+use std::time::SystemTime;
+
+use chrono::{DateTime, Utc};
 
 use mozjs::jsapi::{JS_GetFunctionObject, JS_IdToValue, JS_WrapValue, JSFunction, JSObject, JSString};
 use mozjs::jsapi::PropertyKey as JSPropertyKey;
 use mozjs::jsapi::Symbol as JSSymbol;
 use mozjs::jsval::{
-	BooleanValue, DoubleValue, Int32Value, JSVal, NullValue, ObjectOrNullValue, ObjectValue, StringValue, SymbolValue, UInt32Value, UndefinedValue,
+	BigIntValue, BooleanValue, DoubleValue, Int32Value, JSVal, NullValue, ObjectOrNullValue, ObjectValue, StringValue, SymbolValue, UInt32Value,
+	UndefinedValue,
 };
 use mozjs::rust::{maybe_wrap_object_or_null_value, maybe_wrap_object_value, maybe_wrap_value};
 
-use crate::{Array, Context, Date, Function, Object, Promise, PropertyKey, String, Symbol, Value};
+use crate::{Array, BigInt, Context, Date, Function, Map, Object, Promise, PropertyKey, Proxy, Set, String, Symbol, Value};
 use crate::objects::RegExp;
+use crate::objects::typedarray::SharedArrayBuffer;
 
 /// Represents types that can be converted to JavaScript [Values](Value).
 pub trait ToValue<'cx> {
@@ -79,11 +84,24 @@ macro_rules! impl_to_value_as_double {
 	};
 }
 
-impl_to_value_as_double!(i64);
-impl_to_value_as_double!(u64);
 impl_to_value_as_double!(f32);
 impl_to_value_as_double!(f64);
 
+macro_rules! impl_to_value_as_bigint {
+	($ty:ty, $constructor:ident) => {
+		impl ToValue<'_> for $ty {
+			fn to_value(&self, cx: &Context, value: &mut Value) {
+				let bi = BigInt::$constructor(cx, *self);
+				value.handle_mut().set(BigIntValue(unsafe { &*bi.get() }));
+			}
+		}
+	};
+}
+
+impl_to_value_as_bigint!(i64, from_i64);
+impl_to_value_as_bigint!(u64, from_u64);
+impl_to_value_as_bigint!(i128, from_i128);
+
 impl ToValue<'_> for *mut JSString {
 	fn to_value(&self, cx: &Context, value: &mut Value) {
 		value.handle_mut().set(StringValue(unsafe { &**self }));
@@ -152,12 +170,48 @@ impl<'cx> ToValue<'cx> for Date<'cx> {
 	}
 }
 
+impl<'cx> ToValue<'cx> for DateTime<Utc> {
+	fn to_value(&self, cx: &'cx Context, value: &mut Value) {
+		Date::from_date(cx, *self).to_value(cx, value);
+	}
+}
+
+impl<'cx> ToValue<'cx> for SystemTime {
+	fn to_value(&self, cx: &'cx Context, value: &mut Value) {
+		Date::from_system_time(cx, *self).to_value(cx, value);
+	}
+}
+
+impl<'cx> ToValue<'cx> for Map<'cx> {
+	fn to_value(&self, cx: &'cx Context, value: &mut Value) {
+		self.handle().to_value(cx, value);
+	}
+}
+
+impl<'cx> ToValue<'cx> for Set<'cx> {
+	fn to_value(&self, cx: &'cx Context, value: &mut Value) {
+		self.handle().to_value(cx, value);
+	}
+}
+
 impl<'cx> ToValue<'cx> for Promise<'cx> {
 	fn to_value(&self, cx: &'cx Context, value: &mut Value) {
 		self.handle().to_value(cx, value);
 	}
 }
 
+impl<'cx> ToValue<'cx> for Proxy<'cx> {
+	fn to_value(&self, cx: &'cx Context, value: &mut Value) {
+		self.handle().to_value(cx, value);
+	}
+}
+
+impl<'cx> ToValue<'cx> for SharedArrayBuffer<'cx> {
+	fn to_value(&self, cx: &'cx Context, value: &mut Value) {
+		self.handle().to_value(cx, value);
+	}
+}
+
 impl<'cx> ToValue<'cx> for RegExp<'cx> {
 	fn to_value(&self, cx: &'cx Context, value: &mut Value) {
 		self.handle().to_value(cx, value);
@@ -7,12 +7,12 @@
 use std::{error, fmt, ptr};
 use std::fmt::{Display, Formatter};
 
-use mozjs::error::{throw_internal_error, throw_range_error, throw_type_error};
-use mozjs::jsapi::{CreateError, JS_ReportErrorUTF8, JSExnType, JSObject, JSProtoKey, UndefinedHandleValue};
+use mozjs::jsapi::{CreateError, ExceptionStackBehavior, JS_SetPendingException, JSExnType, JSObject, JSProtoKey, UndefinedHandleValue};
 
-use crate::{Context, Object, Stack, Value};
+use crate::{Array, Context, Exception, Object, Stack, Value};
 use crate::conversions::ToValue;
 use crate::exception::ThrowException;
+use crate::flags::PropertyFlags;
 use crate::stack::Location;
 
 /// Represents the types of errors that can be thrown and are recognised in the JS Runtime.
@@ -94,21 +94,62 @@ impl Display for ErrorKind {
 			EK::Type => "TypeError",
 			EK::WasmCompile => "CompileError",
 			EK::WasmLink => "LinkError",
-			EK::WasmRuntime => "CompileError",
+			EK::WasmRuntime => "RuntimeError",
 			EK::None => "Not an Error",
 		};
 		f.write_str(str)
 	}
 }
 
+/// Represents the `code` property of an error, such as those on a `DOMException` or a system error.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ErrorCode {
+	Number(i32),
+	String(String),
+}
+
+impl From<i32> for ErrorCode {
+	fn from(code: i32) -> ErrorCode {
+		ErrorCode::Number(code)
+	}
+}
+
+impl From<&str> for ErrorCode {
+	fn from(code: &str) -> ErrorCode {
+		ErrorCode::String(String::from(code))
+	}
+}
+
+impl From<String> for ErrorCode {
+	fn from(code: String) -> ErrorCode {
+		ErrorCode::String(code)
+	}
+}
+
+impl Display for ErrorCode {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		match self {
+			ErrorCode::Number(code) => Display::fmt(code, f),
+			ErrorCode::String(code) => f.write_str(code),
+		}
+	}
+}
+
 /// Represents errors in the JS Runtime
 /// Contains information about the type of error, the error message and the error location.
 ///
+/// Errors can have a custom `name`, such as `AbortError`, which replaces the name of the error kind,
+/// and a `code`, both of which are defined as properties on the error object.
+///
 /// If created from an error object, it also contains the error object.
 #[derive(Clone, Debug)]
 pub struct Error {
 	pub kind: ErrorKind,
+	pub name: Option<String>,
+	pub code: Option<ErrorCode>,
 	pub message: String,
+	pub cause: Option<Box<Exception>>,
+	pub errors: Vec<Exception>,
 	pub location: Option<Location>,
 	pub object: Option<*mut JSObject>,
 }
@@ -117,7 +158,11 @@ impl Error {
 	pub fn new<T: Into<Option<ErrorKind>>>(message: &str, kind: T) -> Error {
 		Error {
 			kind: kind.into().unwrap_or(ErrorKind::Normal),
+			name: None,
+			code: None,
 			message: String::from(message),
+			cause: None,
+			errors: Vec::new(),
 			location: None,
 			object: None,
 		}
@@ -126,12 +171,59 @@ impl Error {
 	pub fn none() -> Error {
 		Error {
 			kind: ErrorKind::None,
+			name: None,
+			code: None,
 			message: String::from(""),
+			cause: None,
+			errors: Vec::new(),
 			location: None,
 			object: None,
 		}
 	}
 
+	/// Sets the custom name of the [Error].
+	pub fn with_name(mut self, name: &str) -> Error {
+		self.name = Some(String::from(name));
+		self
+	}
+
+	/// Sets the code of the [Error].
+	pub fn with_code<C: Into<ErrorCode>>(mut self, code: C) -> Error {
+		self.code = Some(code.into());
+		self
+	}
+
+	/// Sets the cause of the [Error], which is defined as the `cause` property of the error object.
+	pub fn with_cause<E: Into<Exception>>(mut self, cause: E) -> Error {
+		self.cause = Some(Box::new(cause.into()));
+		self
+	}
+
+	/// Creates an `AggregateError` containing multiple errors, such as from [Promise::any](crate::Promise::any).
+	pub fn aggregate<I: IntoIterator<Item = E>, E: Into<Exception>>(message: &str, errors: I) -> Error {
+		let mut error = Error::new(message, ErrorKind::Aggregate);
+		error.errors = errors.into_iter().map(Into::into).collect();
+		error
+	}
+
+	/// Returns the causes of the [Error], starting from its direct cause.
+	pub fn causes(&self) -> impl std::iter::Iterator<Item = &Exception> {
+		let mut cause = self.cause.as_deref();
+		std::iter::from_fn(move || {
+			let current = cause?;
+			cause = match current {
+				Exception::Error(error) => error.cause.as_deref(),
+				Exception::Other(_) => None,
+			};
+			Some(current)
+		})
+	}
+
+	/// Returns the name of the [Error], which is either its custom name or the name of its kind.
+	pub fn name(&self) -> String {
+		self.name.clone().unwrap_or_else(|| self.kind.to_string())
+	}
+
 	pub fn to_object<'cx>(&self, cx: &'cx Context) -> Option<Object<'cx>> {
 		if let Some(object) = self.object {
 			return Some(cx.root_object(object).into());
@@ -174,7 +266,28 @@ impl Error {
 					UndefinedHandleValue,
 					error.handle_mut().into(),
 				) {
-					return Some(error.to_object(cx));
+					let mut error = error.to_object(cx);
+					if let Some(name) = &self.name {
+						error.set_as(cx, "name", name);
+					}
+					match &self.code {
+						Some(ErrorCode::Number(code)) => {
+							error.set_as(cx, "code", code);
+						}
+						Some(ErrorCode::String(code)) => {
+							error.set_as(cx, "code", code);
+						}
+						None => {}
+					}
+					if let Some(cause) = &self.cause {
+						error.define(cx, "cause", &cause.as_value(cx), PropertyFlags::empty());
+					}
+					if self.kind == ErrorKind::Aggregate {
+						let errors: Vec<_> = self.errors.iter().map(|error| error.as_value(cx)).collect();
+						let errors: Vec<_> = errors.iter().map(|error| error.get()).collect();
+						error.define(cx, "errors", &Array::from_slice(cx, &errors).as_value(cx), PropertyFlags::empty());
+					}
+					return Some(error);
 				}
 			}
 		}
@@ -182,7 +295,8 @@ impl Error {
 	}
 
 	pub fn format(&self) -> String {
-		let Error { kind, message, location, .. } = self;
+		let Error { message, location, .. } = self;
+		let kind = self.name();
 		let message = (!message.is_empty()).then(|| format!(" - {}", message)).unwrap_or(String::new());
 		if let Some(location) = location {
 			let Location { file, lineno, column } = location;
@@ -214,16 +328,9 @@ impl<E: error::Error> From<E> for Error {
 
 impl ThrowException for Error {
 	fn throw(&self, cx: &Context) {
-		unsafe {
-			use ErrorKind as EK;
-			match self.kind {
-				EK::Normal => JS_ReportErrorUTF8(cx.as_ptr(), format!("{}\0", self.message).as_ptr().cast()),
-				EK::Internal => throw_internal_error(cx.as_ptr(), &self.message),
-				EK::Range => throw_range_error(cx.as_ptr(), &self.message),
-				EK::Type => throw_type_error(cx.as_ptr(), &self.message),
-				EK::None => (),
-				_ => unimplemented!("Throwing Exception for this is not implemented"),
-			}
+		if let Some(error) = self.to_object(cx) {
+			let error = error.as_value(cx);
+			unsafe { JS_SetPendingException(cx.as_ptr(), error.handle().into(), ExceptionStackBehavior::Capture) }
 		}
 	}
 }
@@ -13,7 +13,7 @@ use mozjs::jsval::{JSVal, ObjectValue};
 #[cfg(feature = "sourcemap")]
 use sourcemap::SourceMap;
 
-use crate::{Context, Error, ErrorKind, Object, Stack, Value};
+use crate::{Array, Context, Error, ErrorCode, ErrorKind, Object, Stack, Value};
 use crate::conversions::{FromValue, ToValue};
 use crate::format::{format_value, NEWLINE};
 use crate::stack::Location;
@@ -73,9 +73,36 @@ impl Exception {
 
 				let location = Location { file, lineno, column };
 				let kind = ErrorKind::from_proto_key(IdentifyStandardInstance(handle.get()));
+				let name = exception
+					.get_as::<_, String>(cx, "name", true, ())
+					.filter(|name| *name != kind.to_string());
+				let code = exception.get(cx, "code").and_then(|code| {
+					if code.handle().is_int32() {
+						Some(ErrorCode::Number(code.handle().to_int32()))
+					} else {
+						String::from_value(cx, &code, true, ()).ok().map(ErrorCode::String)
+					}
+				});
+				let cause = exception
+					.has_own(cx, "cause")
+					.then(|| exception.get(cx, "cause"))
+					.flatten()
+					.map(|cause| Box::new(Exception::from_value(cx, &cause)));
+				let errors = if kind == ErrorKind::Aggregate {
+					exception
+						.get_as::<_, Array>(cx, "errors", true, ())
+						.map(|errors| errors.to_vec(cx).iter().map(|error| Exception::from_value(cx, error)).collect())
+						.unwrap_or_default()
+				} else {
+					Vec::new()
+				};
 				let error = Error {
 					kind,
+					name,
+					code,
 					message,
+					cause,
+					errors,
 					location: Some(location),
 					object: Some(handle.get()),
 				};
@@ -236,6 +263,7 @@ impl ErrorReport {
 	}
 
 	/// Formats the [ErrorReport] as a string for printing.
+	/// The causes of the exception and the errors of an `AggregateError` are included after the stack.
 	pub fn format(&self, cx: &Context) -> String {
 		let mut string = self.exception.format(cx);
 		if let Some(stack) = &self.stack {
@@ -244,6 +272,39 @@ impl ErrorReport {
 				string.push_str(&stack.format());
 			}
 		}
+		if let Exception::Error(error) = &self.exception {
+			format_nested(cx, &mut string, error, 1);
+		}
 		string
 	}
 }
+
+fn format_nested(cx: &Context, string: &mut String, error: &Error, depth: usize) {
+	format_aggregated(cx, string, error, depth);
+	let indent = "  ".repeat(depth);
+	for cause in error.causes() {
+		string.push_str(NEWLINE);
+		string.push_str(&format!("{}Caused by: {}", indent, format_exception(cx, cause)));
+		if let Exception::Error(error) = cause {
+			format_aggregated(cx, string, error, depth + 1);
+		}
+	}
+}
+
+fn format_aggregated(cx: &Context, string: &mut String, error: &Error, depth: usize) {
+	let indent = "  ".repeat(depth);
+	for (index, exception) in error.errors.iter().enumerate() {
+		string.push_str(NEWLINE);
+		string.push_str(&format!("{}[{}]: {}", indent, index, format_exception(cx, exception)));
+		if let Exception::Error(error) = exception {
+			format_nested(cx, string, error, depth + 1);
+		}
+	}
+}
+
+fn format_exception(cx: &Context, exception: &Exception) -> String {
+	match exception {
+		Exception::Error(error) => error.format(),
+		Exception::Other(value) => format_value(cx, Default::default(), &cx.root_value(*value).into()),
+	}
+}
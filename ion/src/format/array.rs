@@ -16,7 +16,7 @@ use crate::format::Config;
 #[allow(clippy::unnecessary_to_owned)]
 pub fn format_array(cx: &Context, cfg: Config, array: &Array) -> String {
 	let color = cfg.colours.array;
-	if cfg.depth < 5 {
+	if cfg.depth < cfg.max_depth {
 		let vec = array.to_vec(cx);
 		let length = vec.len();
 
@@ -67,11 +67,11 @@ pub fn format_array(cx: &Context, cfg: Config, array: &Array) -> String {
 
 			let remaining = length - len;
 			match remaining.cmp(&1) {
-				Ordering::Equal => string.push_str(&"... 1 more item ".color(color)),
-				Ordering::Greater => string.push_str(&format!("... {} more items ", remaining).color(color)),
+				Ordering::Equal => string.push_str(&", ... 1 more item".color(color)),
+				Ordering::Greater => string.push_str(&format!(", ... {} more items", remaining).color(color)),
 				_ => (),
 			}
-			string.push_str(&"]".color(color).to_string());
+			string.push_str(&" ]".color(color).to_string());
 
 			string
 		}
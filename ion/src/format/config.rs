@@ -16,6 +16,9 @@ pub struct Config {
 	#[derivative(Default(value = "IteratorFlags::empty()"))]
 	pub iteration: IteratorFlags,
 	pub depth: u16,
+	/// Depth beyond which nested objects and arrays are abbreviated, such as `[Object]`.
+	#[derivative(Default(value = "4"))]
+	pub max_depth: u16,
 	pub indentation: u16,
 	#[derivative(Default(value = "true"))]
 	pub multiline: bool,
@@ -36,6 +39,10 @@ impl Config {
 		Config { depth, ..self }
 	}
 
+	pub fn max_depth(self, max_depth: u16) -> Config {
+		Config { max_depth, ..self }
+	}
+
 	pub fn indentation(self, indentation: u16) -> Config {
 		Config { indentation, ..self }
 	}
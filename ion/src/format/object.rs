@@ -4,12 +4,13 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/.
  */
 
+use std::cell::RefCell;
 use std::cmp::Ordering;
 use std::fmt::Write;
 
 use colored::Colorize;
 use mozjs::conversions::jsstr_to_string;
-use mozjs::jsapi::{ESClass, JS_ValueToSource};
+use mozjs::jsapi::{ESClass, JS_ValueToSource, JSObject};
 
 use crate::{Array, Context, Date, Exception, Function, Object, Promise, RegExp};
 use crate::conversions::ToValue;
@@ -24,9 +25,28 @@ use crate::format::key::format_key;
 use crate::format::promise::format_promise;
 use crate::format::regexp::format_regexp;
 
+thread_local! {
+	/// Objects which are currently being formatted, used to detect circular references.
+	static FORMATTING: RefCell<Vec<*mut JSObject>> = RefCell::new(Vec::new());
+}
+
 /// Formats a [JavaScript Object](Object), depending on its class, as a string using the given [configuration](Config).
 /// The object is passed to more specific formatting functions, such as [format_array] and [format_date].
+///
+/// Objects which contain themselves are formatted as `[Circular]` where they are referenced again.
 pub fn format_object(cx: &Context, cfg: Config, object: Object) -> String {
+	let pointer = object.handle().get();
+	if FORMATTING.with_borrow(|formatting| formatting.contains(&pointer)) {
+		return "[Circular]".color(cfg.colours.object).to_string();
+	}
+
+	FORMATTING.with_borrow_mut(|formatting| formatting.push(pointer));
+	let string = format_object_by_class(cx, cfg, object);
+	FORMATTING.with_borrow_mut(|formatting| formatting.pop());
+	string
+}
+
+fn format_object_by_class(cx: &Context, cfg: Config, object: Object) -> String {
 	unsafe {
 		use ESClass as ESC;
 		let class = object.get_builtin_class(cx);
@@ -61,7 +81,7 @@ pub fn format_object(cx: &Context, cfg: Config, object: Object) -> String {
 #[allow(clippy::unnecessary_to_owned)]
 pub fn format_plain_object(cx: &Context, cfg: Config, object: &Object) -> String {
 	let color = cfg.colours.object;
-	if cfg.depth < 4 {
+	if cfg.depth < cfg.max_depth {
 		let keys = object.keys(cx, Some(cfg.iteration));
 		let length = keys.len();
 
@@ -102,11 +122,11 @@ pub fn format_plain_object(cx: &Context, cfg: Config, object: &Object) -> String
 
 			let remaining = length - len;
 			match remaining.cmp(&1) {
-				Ordering::Equal => string.push_str(&"... 1 more item ".color(color)),
-				Ordering::Greater => string.push_str(&format!("... {} more items ", remaining).color(color)),
+				Ordering::Equal => string.push_str(&", ... 1 more item".color(color)),
+				Ordering::Greater => string.push_str(&format!(", ... {} more items", remaining).color(color)),
 				_ => (),
 			}
-			string.push_str(&"}".color(color).to_string());
+			string.push_str(&" }".color(color).to_string());
 
 			string
 		}
@@ -5,7 +5,6 @@
  */
 
 use colored::Colorize;
-use mozjs::jsapi::PromiseState;
 
 use crate::{Context, Promise};
 use crate::format::{Config, format_value, INDENT};
@@ -17,16 +16,14 @@ use crate::format::{Config, format_value, INDENT};
 /// ```
 #[allow(clippy::unnecessary_to_owned)]
 pub fn format_promise(cx: &Context, cfg: Config, promise: &Promise) -> String {
-	let state = promise.state();
-	let state_string = match state {
-		PromiseState::Pending => return "Promise { <pending> }".color(cfg.colours.promise).to_string(),
-		PromiseState::Fulfilled => "<fulfilled>",
-		PromiseState::Rejected => "<rejected>",
+	let (state_string, result) = match promise.settled_result(cx) {
+		None => return "Promise { <pending> }".color(cfg.colours.promise).to_string(),
+		Some(Ok(value)) => ("<fulfilled>", value),
+		Some(Err(value)) => ("<rejected>", value),
 	};
 	let state_string = state_string.color(cfg.colours.promise);
 
 	let mut base = "Promise {".color(cfg.colours.promise).to_string();
-	let result = promise.result(cx);
 
 	if cfg.multiline {
 		let result_string = format_value(cx, cfg.depth(cfg.depth + 1), &result);
@@ -9,7 +9,7 @@ use std::ops::{Deref, DerefMut};
 use mozjs::jsapi::CallArgs;
 use mozjs::jsval::JSVal;
 
-use crate::{Context, Local, Object, Result, Value};
+use crate::{Context, Error, ErrorKind, Local, Object, Result, Value};
 use crate::conversions::FromValue;
 
 /// Represents Arguments to a [JavaScript Function](crate::Function)
@@ -71,6 +71,57 @@ impl<'cx> Arguments<'cx> {
 		range.filter_map(|index| self.value(index)).collect()
 	}
 
+	/// Converts the argument at the given index to `T`.
+	/// Returns a [TypeError](ErrorKind::Type) if the argument is missing or cannot be converted.
+	pub fn get<T: FromValue<'cx>>(&self, index: usize) -> Result<T>
+	where
+		T::Config: Default,
+	{
+		match self.value(index) {
+			Some(value) => convert_argument(self.cx, value, index),
+			None => Err(Error::new(
+				&format!("Expected at least {} arguments, but received {}", index + 1, self.len()),
+				ErrorKind::Type,
+			)),
+		}
+	}
+
+	/// Converts the argument at the given index to `T`, if it is present and not `undefined`.
+	/// Returns a [TypeError](ErrorKind::Type) if the argument cannot be converted.
+	pub fn get_opt<T: FromValue<'cx>>(&self, index: usize) -> Result<Option<T>>
+	where
+		T::Config: Default,
+	{
+		match self.value(index) {
+			Some(value) if !value.handle().is_undefined() => convert_argument(self.cx, value, index).map(Some),
+			_ => Ok(None),
+		}
+	}
+
+	/// Converts the arguments starting from the given index to `T`.
+	/// Returns a [TypeError](ErrorKind::Type) if any of the arguments cannot be converted.
+	pub fn rest<T: FromValue<'cx>>(&self, start: usize) -> Result<Vec<T>>
+	where
+		T::Config: Default,
+	{
+		self.values
+			.iter()
+			.enumerate()
+			.skip(start)
+			.map(|(index, value)| convert_argument(self.cx, value, index))
+			.collect()
+	}
+
+	/// Converts the `this` value of the function to `T`.
+	/// Returns a [TypeError](ErrorKind::Type) if it cannot be converted.
+	pub fn this_as<T: FromValue<'cx>>(&self) -> Result<T>
+	where
+		T::Config: Default,
+	{
+		T::from_value(self.cx, &self.this, false, T::Config::default())
+			.map_err(|error| Error::new(&format!("Invalid this value: {}", error.message), ErrorKind::Type))
+	}
+
 	pub fn cx(&self) -> &'cx Context {
 		self.cx
 	}
@@ -114,6 +165,14 @@ impl<'cx> Arguments<'cx> {
 	}
 }
 
+fn convert_argument<'cx, T: FromValue<'cx>>(cx: &'cx Context, value: &Value<'cx>, index: usize) -> Result<T>
+where
+	T::Config: Default,
+{
+	T::from_value(cx, value, false, T::Config::default())
+		.map_err(|error| Error::new(&format!("Argument {}: {}", index + 1, error.message), ErrorKind::Type))
+}
+
 pub struct Accessor<'a, 'cx> {
 	args: &'a mut Arguments<'cx>,
 	index: usize,
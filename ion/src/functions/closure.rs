@@ -9,7 +9,7 @@ use std::ptr;
 
 use mozjs::glue::JS_GetReservedSlot;
 use mozjs::jsapi::{
-	GCContext, GetFunctionNativeReserved, JS_NewObject, JS_SetReservedSlot, JSClass, JSCLASS_BACKGROUND_FINALIZE, JSClassOps, JSContext, JSObject,
+	GCContext, GetFunctionNativeReserved, JS_NewObject, JS_SetReservedSlot, JSClass, JSCLASS_FOREGROUND_FINALIZE, JSClassOps, JSContext, JSObject,
 };
 use mozjs::jsval::{JSVal, PrivateValue, UndefinedValue};
 
@@ -22,6 +22,8 @@ const CLOSURE_SLOT: u32 = 0;
 
 pub type Closure = dyn for<'cx> FnMut(&mut Arguments<'cx>) -> ResultExc<Value<'cx>> + 'static;
 
+pub type ClosureOnce = dyn for<'cx> FnOnce(&mut Arguments<'cx>) -> ResultExc<Value<'cx>> + 'static;
+
 pub(crate) fn create_closure_object(cx: &Context, closure: Box<Closure>) -> Object {
 	unsafe {
 		let object = Object::from(cx.root_object(JS_NewObject(cx.as_ptr(), &CLOSURE_CLASS)));
@@ -72,9 +74,10 @@ static CLOSURE_OPS: JSClassOps = JSClassOps {
 	trace: None,
 };
 
+// Closures are not required to be `Send`, so they are finalised on the main thread instead of a background thread.
 static CLOSURE_CLASS: JSClass = JSClass {
 	name: "Closure\0".as_ptr().cast(),
-	flags: JSCLASS_BACKGROUND_FINALIZE | class_reserved_slots(1),
+	flags: JSCLASS_FOREGROUND_FINALIZE | class_reserved_slots(1),
 	cOps: &CLOSURE_OPS,
 	spec: ptr::null_mut(),
 	ext: ptr::null_mut(),
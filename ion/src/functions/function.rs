@@ -15,9 +15,9 @@ use mozjs::jsapi::{
 };
 use mozjs::jsval::{JSVal, ObjectValue};
 
-use crate::{Context, ErrorReport, Local, Object, Value};
+use crate::{Arguments, Context, Error, ErrorKind, ErrorReport, Local, Object, Value};
 use crate::flags::PropertyFlags;
-use crate::functions::closure::{call_closure, Closure, create_closure_object};
+use crate::functions::closure::{call_closure, Closure, ClosureOnce, create_closure_object};
 
 /// Native Function that can be used from JavaScript.
 pub type NativeFunction = unsafe extern "C" fn(*mut JSContext, u32, *mut JSVal) -> bool;
@@ -46,7 +46,10 @@ impl<'f> Function<'f> {
 	}
 
 	/// Creates a new [Function] with a [Closure].
+	///
+	/// The closure is owned by the function and is dropped when the function is garbage collected.
 	pub fn from_closure(cx: &'f Context, name: &str, closure: Box<Closure>, nargs: u32, flags: PropertyFlags) -> Function<'f> {
+		let name = CString::new(name).unwrap();
 		unsafe {
 			let function = Function {
 				function: cx.root_function(NewFunctionWithReserved(
@@ -54,7 +57,7 @@ impl<'f> Function<'f> {
 					Some(call_closure),
 					nargs,
 					flags.bits() as u32,
-					name.as_ptr().cast(),
+					name.as_ptr(),
 				)),
 			};
 			let closure_object = create_closure_object(cx, closure);
@@ -63,6 +66,39 @@ impl<'f> Function<'f> {
 		}
 	}
 
+	/// Creates a new [Function] from a Rust closure, which can capture state.
+	///
+	/// Errors returned by the closure are thrown as exceptions.
+	pub fn new_closure<F>(cx: &'f Context, name: &str, mut closure: F) -> Function<'f>
+	where
+		F: for<'cx> FnMut(&'cx Context, &Arguments<'cx>) -> crate::Result<Value<'cx>> + 'static,
+	{
+		Function::from_closure(
+			cx,
+			name,
+			Box::new(move |args| closure(args.cx(), args).map_err(Into::into)),
+			0,
+			PropertyFlags::empty(),
+		)
+	}
+
+	/// Creates a new [Function] with a [ClosureOnce].
+	///
+	/// Calling the function more than once throws an error.
+	pub fn from_closure_once(cx: &'f Context, name: &str, closure: Box<ClosureOnce>, nargs: u32, flags: PropertyFlags) -> Function<'f> {
+		let mut closure = Some(closure);
+		Function::from_closure(
+			cx,
+			name,
+			Box::new(move |args| match closure.take() {
+				Some(closure) => closure(args),
+				None => Err(Error::new("Function cannot be called more than once", ErrorKind::Internal).into()),
+			}),
+			nargs,
+			flags,
+		)
+	}
+
 	/// Creates a new [Function] from an object.
 	/// Returns [None] if the object is not a function.
 	pub fn from_object(cx: &'f Context, obj: &Local<'_, *mut JSObject>) -> Option<Function<'f>> {
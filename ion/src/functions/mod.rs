@@ -9,8 +9,9 @@ use std::mem::forget;
 use std::thread::Result;
 
 pub use arguments::Arguments;
-pub use closure::Closure;
+pub use closure::{Closure, ClosureOnce};
 pub use function::{Function, NativeFunction};
+pub use parameters::{Opt, Rest};
 
 use crate::{Context, Error, Object, ResultExc, ThrowException, Value};
 use crate::conversions::ToValue;
@@ -18,6 +19,7 @@ use crate::conversions::ToValue;
 mod arguments;
 mod closure;
 mod function;
+mod parameters;
 
 #[doc(hidden)]
 pub fn __handle_native_function_result(cx: &Context, result: Result<ResultExc<()>>) -> bool {
@@ -0,0 +1,69 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::ops::{Deref, DerefMut};
+
+/// Represents an optional parameter of a native function.
+/// Missing, `null` and `undefined` arguments are converted to [None].
+#[derive(Clone, Debug)]
+pub struct Opt<T>(pub Option<T>);
+
+impl<T> Opt<T> {
+	pub fn into_inner(self) -> Option<T> {
+		self.0
+	}
+}
+
+impl<T> Default for Opt<T> {
+	fn default() -> Opt<T> {
+		Opt(None)
+	}
+}
+
+impl<T> Deref for Opt<T> {
+	type Target = Option<T>;
+
+	fn deref(&self) -> &Self::Target {
+		&self.0
+	}
+}
+
+impl<T> DerefMut for Opt<T> {
+	fn deref_mut(&mut self) -> &mut Self::Target {
+		&mut self.0
+	}
+}
+
+/// Represents the rest parameter of a native function, which collects all remaining arguments.
+/// Refer to [MDN](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Functions/rest_parameters) for more details.
+#[derive(Clone, Debug)]
+pub struct Rest<T>(pub Vec<T>);
+
+impl<T> Rest<T> {
+	pub fn into_inner(self) -> Vec<T> {
+		self.0
+	}
+}
+
+impl<T> Default for Rest<T> {
+	fn default() -> Rest<T> {
+		Rest(Vec::new())
+	}
+}
+
+impl<T> Deref for Rest<T> {
+	type Target = Vec<T>;
+
+	fn deref(&self) -> &Self::Target {
+		&self.0
+	}
+}
+
+impl<T> DerefMut for Rest<T> {
+	fn deref_mut(&mut self) -> &mut Self::Target {
+		&mut self.0
+	}
+}
@@ -10,59 +10,80 @@ use std::task;
 use std::task::Poll;
 
 use futures::channel::mpsc;
-use futures::channel::mpsc::Receiver;
+use futures::channel::mpsc::{Receiver, Sender};
 use futures::Stream;
-use mozjs::jsval::JSVal;
+use mozjs::jsval::{JSVal, UndefinedValue};
 
-use crate::{Context, Function, Promise, Value};
+use crate::{Arguments, Context, Function, PersistentRooted, Promise, ResultExc, Value};
 use crate::flags::PropertyFlags;
 
-pub struct PromiseFuture(Receiver<Result<JSVal, JSVal>>);
+type SettledValue = Result<PersistentRooted<JSVal>, PersistentRooted<JSVal>>;
 
-impl PromiseFuture {
-	pub fn new(cx: &Context, promise: &Promise) -> PromiseFuture {
-		let (rx, tx) = mpsc::channel(1);
+/// Represents a [Future] which completes when a [Promise] is settled.
+///
+/// The output of the future is the value the promise was fulfilled with, or the reason it was rejected with.
+pub struct PromiseFuture<'cx> {
+	cx: &'cx Context,
+	receiver: Receiver<SettledValue>,
+}
+
+impl<'cx> PromiseFuture<'cx> {
+	/// Creates a new [PromiseFuture] which completes when the given [Promise] is settled.
+	pub fn new(cx: &'cx Context, promise: &Promise) -> PromiseFuture<'cx> {
+		let (sender, receiver) = mpsc::channel(1);
 
-		let mut rx1 = rx;
-		let mut rx2 = rx1.clone();
+		let mut on_resolved = sender.clone();
+		let mut on_rejected = sender;
 
 		promise.add_reactions(
 			cx,
 			Some(Function::from_closure(
 				cx,
 				"",
-				Box::new(move |args| {
-					let _ = rx1.try_send(Ok(args.value(0).unwrap().get()));
-					Ok(Value::undefined(args.cx()))
-				}),
+				Box::new(move |args| send_settled(args, &mut on_resolved, Ok)),
 				1,
 				PropertyFlags::empty(),
 			)),
 			Some(Function::from_closure(
 				cx,
 				"",
-				Box::new(move |args| {
-					let _ = rx2.try_send(Err(args.value(0).unwrap().get()));
-					Ok(Value::undefined(args.cx()))
-				}),
+				Box::new(move |args| send_settled(args, &mut on_rejected, Err)),
 				1,
 				PropertyFlags::empty(),
 			)),
 		);
 
-		PromiseFuture(tx)
+		PromiseFuture { cx, receiver }
 	}
 }
 
-impl Future for PromiseFuture {
-	type Output = Result<JSVal, JSVal>;
+fn send_settled<'cx>(
+	args: &mut Arguments<'cx>, sender: &mut Sender<SettledValue>, settle: fn(PersistentRooted<JSVal>) -> SettledValue,
+) -> ResultExc<Value<'cx>> {
+	let value = PersistentRooted::new(args.value(0).map(|value| value.get()).unwrap_or_else(UndefinedValue));
+	let _ = sender.try_send(settle(value));
+	Ok(Value::undefined(args.cx()))
+}
+
+impl<'cx> Future for PromiseFuture<'cx> {
+	type Output = Result<Value<'cx>, Value<'cx>>;
+
+	fn poll(mut self: Pin<&mut Self>, wcx: &mut task::Context<'_>) -> Poll<Result<Value<'cx>, Value<'cx>>> {
+		let cx = self.cx;
+		match Pin::new(&mut self.receiver).poll_next(wcx) {
+			Poll::Ready(Some(settled)) => {
+				let is_fulfilled = settled.is_ok();
+				let value = match settled {
+					Ok(value) | Err(value) => Value::from(cx.root_value(value.get())),
+				};
 
-	fn poll(mut self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Result<JSVal, JSVal>> {
-		let result = Pin::new(&mut self.0);
-		if let Poll::Ready(Some(val)) = result.poll_next(cx) {
-			Poll::Ready(val)
-		} else {
-			Poll::Pending
+				if is_fulfilled {
+					Poll::Ready(Ok(value))
+				} else {
+					Poll::Ready(Err(value))
+				}
+			}
+			_ => Poll::Pending,
 		}
 	}
 }
@@ -17,16 +17,21 @@ extern crate mozjs;
 use std::result;
 
 pub use class::ClassDefinition;
+pub use bigint::BigInt;
 pub use context::{Context, ContextInner};
-pub use error::{Error, ErrorKind};
+pub use error::{Error, ErrorCode, ErrorKind};
 pub use exception::{ErrorReport, Exception, ThrowException};
 pub use functions::{Arguments, Function};
 pub use future::PromiseFuture;
 #[cfg(feature = "macros")]
 pub use ion_proc::*;
 pub use local::Local;
-pub use objects::{Array, Date, Iterator, JSIterator, Object, OwnedKey, Promise, PropertyKey, RegExp};
+pub use objects::{
+	Array, AsyncIterator, Date, Iterator, JSIterator, Map, Object, OwnedKey, Promise, PropertyKey, Proxy, RegExp, Set, WasmDescriptor,
+	WasmExternKind, WasmInstance, WasmMemory, WasmModule, Weak,
+};
 pub use objects::typedarray;
+pub use persistent::PersistentRooted;
 pub use stack::{Stack, StackRecord};
 pub use string::{String, StringRef};
 pub use symbol::Symbol;
@@ -45,9 +50,11 @@ mod future;
 pub mod local;
 pub mod module;
 pub mod objects;
+mod persistent;
 pub mod script;
 pub mod spec;
 pub mod stack;
+pub mod stencil;
 mod string;
 pub mod symbol;
 pub mod utils;
@@ -8,14 +8,17 @@ use std::path::Path;
 use std::ptr;
 
 use mozjs::jsapi::{
-	CompileModule, CreateModuleRequest, GetModuleRequestSpecifier, Handle, JS_GetRuntime, JSContext, JSObject, ModuleEvaluate, ModuleLink,
-	SetModuleMetadataHook, SetModulePrivate, SetModuleResolveHook,
+	CompileModule, CreateDefaultExportSyntheticModule, CreateModuleRequest, FinishDynamicModuleImport, GetModuleNamespace, GetModuleRequestSpecifier,
+	GetRequestedModulesCount, GetRequestedModuleSpecifier, Handle, JS_GetReservedSlot, JS_GetRuntime, JS_ParseJSON, JSContext, JSObject,
+	ModuleEvaluate, ModuleLink, SetModuleDynamicImportHook, SetModuleMetadataHook, SetModulePrivate, SetModuleResolveHook,
 };
 use mozjs::jsval::JSVal;
-use mozjs::rust::{CompileOptionsWrapper, transform_u16_to_source_text};
+use mozjs::rust::transform_u16_to_source_text;
 
-use crate::{Context, ErrorReport, Local, Object, Promise, Value};
+use crate::{Array, Context, Error, ErrorKind, ErrorReport, Exception, Local, Object, PersistentRooted, Promise, ThrowException, Value};
 use crate::conversions::{FromValue, ToValue};
+use crate::script::CompileOptions;
+use crate::stencil::Stencil;
 
 /// Represents private module data
 #[derive(Clone, Debug)]
@@ -68,8 +71,28 @@ impl<'r> ModuleRequest<'r> {
 		cx.root_string(unsafe { GetModuleRequestSpecifier(cx.as_ptr(), self.0.handle().into()) })
 			.into()
 	}
+
+	/// Returns the value of an import assertion of the request, such as `type` in `assert { type: "json" }`.
+	///
+	/// Import assertions are not exposed through JSAPI, so they are read from the slot of the request object,
+	/// which holds an array of objects with a single property each.
+	pub fn assertion(&self, cx: &Context, key: &str) -> Option<String> {
+		let mut assertions = Value::undefined(cx);
+		unsafe { JS_GetReservedSlot(self.0.handle().get(), MODULE_REQUEST_ASSERTIONS_SLOT, assertions.handle_mut().into()) };
+		if !assertions.handle().is_object() {
+			return None;
+		}
+
+		let assertions = Array::from(cx, assertions.to_object(cx).into_local())?;
+		(0..assertions.len(cx)).find_map(|index| {
+			let assertion = assertions.get(cx, index).filter(|assertion| assertion.handle().is_object())?;
+			assertion.to_object(cx).get_as::<_, String>(cx, key, true, ())
+		})
+	}
 }
 
+const MODULE_REQUEST_ASSERTIONS_SLOT: u32 = 1;
+
 /// Represents phases of running modules.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum ModuleErrorKind {
@@ -82,18 +105,22 @@ pub enum ModuleErrorKind {
 #[derive(Clone, Debug)]
 pub struct ModuleError {
 	pub kind: ModuleErrorKind,
-	pub report: ErrorReport,
+	/// Report of the error, which is [None] if the error is uncatchable, such as the termination of the module.
+	pub report: Option<ErrorReport>,
 }
 
 impl ModuleError {
 	/// Creates a [ModuleError] with a given report and phase.
-	fn new(report: ErrorReport, kind: ModuleErrorKind) -> ModuleError {
-		ModuleError { kind, report }
+	fn new<R: Into<Option<ErrorReport>>>(report: R, kind: ModuleErrorKind) -> ModuleError {
+		ModuleError { kind, report: report.into() }
 	}
 
 	/// Formats the [ModuleError] for printing.
 	pub fn format(&self, cx: &Context) -> String {
-		self.report.format(cx)
+		match &self.report {
+			Some(report) => report.format(cx),
+			None => String::from("Uncatchable Error"),
+		}
 	}
 }
 
@@ -107,39 +134,117 @@ impl<'cx> Module<'cx> {
 	/// The promise is a byproduct of enabling top-level await.
 	#[allow(clippy::result_large_err)]
 	pub fn compile(cx: &'cx Context, filename: &str, path: Option<&Path>, script: &str) -> Result<(Module<'cx>, Option<Promise<'cx>>), ModuleError> {
+		let filename = path.and_then(Path::to_str).unwrap_or(filename);
+		Module::compile_with_options(cx, &CompileOptions::new(filename), path, script)
+	}
+
+	/// Compiles a [Module] with the given [options](CompileOptions), then links and evaluates it like [Module::compile].
+	/// Imports are resolved relative to `path`, if it is given.
+	#[allow(clippy::result_large_err)]
+	pub fn compile_with_options(
+		cx: &'cx Context, options: &CompileOptions, path: Option<&Path>, script: &str,
+	) -> Result<(Module<'cx>, Option<Promise<'cx>>), ModuleError> {
 		let script: Vec<u16> = script.encode_utf16().collect();
 		let mut source = transform_u16_to_source_text(script.as_slice());
-		let filename = path.and_then(Path::to_str).unwrap_or(filename);
-		let options = unsafe { CompileOptionsWrapper::new(cx.as_ptr(), filename, 1) };
+		let options = options.to_wrapper(cx);
 
 		let module = unsafe { CompileModule(cx.as_ptr(), options.ptr.cast_const().cast(), &mut source) };
 
 		if !module.is_null() {
-			let module = Module(Object::from(cx.root_object(module)));
+			Module::initialise(cx, Object::from(cx.root_object(module)), path)
+		} else {
+			Err(ModuleError::new(ErrorReport::new(cx).unwrap(), ModuleErrorKind::Compilation))
+		}
+	}
 
-			let data = ModuleData {
-				path: path.and_then(Path::to_str).map(String::from),
-			};
+	/// Instantiates a [Module] from a compiled [Stencil], then links and evaluates it like [Module::compile].
+	#[allow(clippy::result_large_err)]
+	pub fn from_stencil(cx: &'cx Context, path: Option<&Path>, stencil: &Stencil) -> Result<(Module<'cx>, Option<Promise<'cx>>), ModuleError> {
+		let module = Module::load(cx, path, stencil)?;
+		module.link_and_evaluate(cx)
+	}
 
-			unsafe {
-				let private = data.to_object(cx).as_value(cx);
-				SetModulePrivate(module.0.handle().get(), &*private.handle());
-			}
+	/// Instantiates a [Module] from a compiled [Stencil] without linking or evaluating it.
+	///
+	/// Module loaders should use this for imported modules, which are linked and evaluated as part of the graph of the importing module.
+	/// This lets the evaluation of a module wait for dependencies that use top-level await.
+	#[allow(clippy::result_large_err)]
+	pub fn load(cx: &'cx Context, path: Option<&Path>, stencil: &Stencil) -> Result<Module<'cx>, ModuleError> {
+		match stencil.to_module(cx) {
+			Ok(module) => Ok(Module::with_private(cx, module, path)),
+			Err(error) => Err(ModuleError::new(error, ModuleErrorKind::Compilation)),
+		}
+	}
 
-			if let Err(error) = module.instantiate(cx) {
-				return Err(ModuleError::new(error, ModuleErrorKind::Instantiation));
+	/// Creates a JSON [Module], whose default export is the value parsed from `json`, without linking or evaluating it.
+	/// Returns [Err] if `json` is not valid JSON.
+	#[allow(clippy::result_large_err)]
+	pub fn load_json(cx: &'cx Context, path: Option<&Path>, json: &str) -> Result<Module<'cx>, ModuleError> {
+		let chars: Vec<u16> = json.encode_utf16().collect();
+		let mut value = Value::undefined(cx);
+		if !unsafe { JS_ParseJSON(cx.as_ptr(), chars.as_ptr(), chars.len() as u32, value.handle_mut().into()) } {
+			return Err(ModuleError::new(
+				ErrorReport::new_with_exception_stack(cx).unwrap(),
+				ModuleErrorKind::Compilation,
+			));
+		}
+
+		// The parsed value is exported by a synthetic module, as evaluating JSON text as source would treat `"__proto__"` keys as prototypes.
+		let module = unsafe { CreateDefaultExportSyntheticModule(cx.as_ptr(), &value.get()) };
+		if module.is_null() {
+			return Err(ModuleError::new(
+				ErrorReport::new_with_exception_stack(cx).unwrap(),
+				ModuleErrorKind::Compilation,
+			));
+		}
+		Ok(Module::with_private(cx, Object::from(cx.root_object(module)), path))
+	}
+
+	#[allow(clippy::result_large_err)]
+	fn initialise(cx: &'cx Context, module: Object<'cx>, path: Option<&Path>) -> Result<(Module<'cx>, Option<Promise<'cx>>), ModuleError> {
+		let module = Module::with_private(cx, module, path);
+
+		// Registers the module before it is linked, so that cyclic imports of it resolve to the same module record.
+		if let Some(path) = path.and_then(Path::to_str) {
+			let loader = unsafe { &mut (*cx.get_inner_data().as_ptr()).module_loader };
+			if let Some(loader) = loader {
+				loader.register(cx, module.0.handle().get(), &ModuleRequest::new(cx, path));
 			}
+		}
 
-			let eval_result = module.evaluate(cx);
-			match eval_result {
-				Ok(val) => {
-					let promise = Promise::from_value(cx, &val, true, ()).ok();
-					Ok((module, promise))
-				}
-				Err(error) => Err(ModuleError::new(error, ModuleErrorKind::Evaluation)),
+		module.link_and_evaluate(cx)
+	}
+
+	fn with_private(cx: &'cx Context, module: Object<'cx>, path: Option<&Path>) -> Module<'cx> {
+		let module = Module(module);
+
+		let data = ModuleData {
+			path: path.and_then(Path::to_str).map(String::from),
+		};
+
+		unsafe {
+			let private = data.to_object(cx).as_value(cx);
+			SetModulePrivate(module.0.handle().get(), &*private.handle());
+		}
+		module
+	}
+
+	/// Links and evaluates a [Module].
+	/// The returned promise settles once the module and its dependencies, including any top-level await, have finished evaluating.
+	#[allow(clippy::result_large_err)]
+	fn link_and_evaluate(self, cx: &'cx Context) -> Result<(Module<'cx>, Option<Promise<'cx>>), ModuleError> {
+		let module = self;
+		if let Err(error) = module.instantiate(cx) {
+			return Err(ModuleError::new(error, ModuleErrorKind::Instantiation));
+		}
+
+		let eval_result = module.evaluate(cx);
+		match eval_result {
+			Ok(val) => {
+				let promise = Promise::from_value(cx, &val, true, ()).ok();
+				Ok((module, promise))
 			}
-		} else {
-			Err(ModuleError::new(ErrorReport::new(cx).unwrap(), ModuleErrorKind::Compilation))
+			Err(error) => Err(ModuleError::new(error, ModuleErrorKind::Evaluation)),
 		}
 	}
 
@@ -152,13 +257,106 @@ impl<'cx> Module<'cx> {
 		}
 	}
 
+	/// Returns the specifiers of the modules requested by the static imports and re-exports of the [Module].
+	pub fn requested_specifiers(&self, cx: &Context) -> Vec<String> {
+		let count = unsafe { GetRequestedModulesCount(cx.as_ptr(), self.0.handle().into()) };
+		(0..count)
+			.map(|index| {
+				let specifier = unsafe { GetRequestedModuleSpecifier(cx.as_ptr(), self.0.handle().into(), index) };
+				crate::String::from(cx.root_string(specifier)).to_owned(cx)
+			})
+			.collect()
+	}
+
 	/// Evaluates a [Module]. Generally called by [Module::compile].
-	pub fn evaluate(&self, cx: &'cx Context) -> Result<Value<'cx>, ErrorReport> {
+	/// Returns [Err] without an [ErrorReport] if the error is uncatchable, such as the termination of the module.
+	pub fn evaluate(&self, cx: &'cx Context) -> Result<Value<'cx>, Option<ErrorReport>> {
 		let mut rval = Value::undefined(cx);
 		if unsafe { ModuleEvaluate(cx.as_ptr(), self.0.handle().into(), rval.handle_mut().into()) } {
 			Ok(rval)
 		} else {
-			Err(ErrorReport::new_with_exception_stack(cx).unwrap())
+			Err(ErrorReport::new_with_exception_stack(cx))
+		}
+	}
+
+	/// Returns the namespace object of a [Module], whose properties are its exports.
+	/// The module must have been linked, and its exports are only initialised once it has been evaluated.
+	pub fn namespace(&self, cx: &'cx Context) -> Object<'cx> {
+		Object::from(cx.root_object(unsafe { GetModuleNamespace(cx.as_ptr(), self.0.handle().into()) }))
+	}
+}
+
+/// Represents a pending dynamic `import()`.
+///
+/// The promise returned by `import()` is settled once the import is finished with [DynamicImport::finish].
+#[derive(Debug)]
+pub struct DynamicImport {
+	private: PersistentRooted<JSVal>,
+	request: PersistentRooted<*mut JSObject>,
+	promise: PersistentRooted<*mut JSObject>,
+}
+
+impl DynamicImport {
+	/// Returns the specifier of the requested module.
+	pub fn specifier(&self, cx: &Context) -> String {
+		let request = ModuleRequest(Object::from(cx.root_object(self.request.get())));
+		request.specifier(cx).to_owned(cx)
+	}
+
+	/// Resolves, links and evaluates the requested module with the current module loader, then settles the promise of the import.
+	/// Failures to resolve or evaluate the module reject the promise.
+	pub fn finish(self, cx: &Context) -> bool {
+		let private = self.private(cx);
+		let request = ModuleRequest(Object::from(cx.root_object(self.request.get())));
+
+		let loader = unsafe { &mut (*cx.get_inner_data().as_ptr()).module_loader };
+		let module = loader
+			.as_mut()
+			.map(|loader| loader.resolve(cx, &private, &request))
+			.unwrap_or_else(ptr::null_mut);
+
+		let mut evaluation = Object::null(cx);
+		if !module.is_null() {
+			let module = cx.root_object(module);
+			let mut rval = Value::undefined(cx);
+			unsafe {
+				if ModuleLink(cx.as_ptr(), module.handle().into())
+					&& ModuleEvaluate(cx.as_ptr(), module.handle().into(), rval.handle_mut().into())
+					&& rval.handle().is_object()
+				{
+					evaluation = rval.to_object(cx);
+				}
+			}
+		} else if !Exception::is_pending(cx) {
+			let specifier = request.specifier(cx).to_owned(cx);
+			Error::new(&format!("Unable to resolve module: {}", specifier), ErrorKind::Type).throw(cx);
+		}
+
+		self.settle(cx, &evaluation)
+	}
+
+	/// Rejects the promise of the import with the pending exception.
+	pub fn reject(self, cx: &Context) -> bool {
+		self.settle(cx, &Object::null(cx))
+	}
+
+	/// Returns the private data of the importing module.
+	pub fn private<'cx>(&self, cx: &'cx Context) -> Value<'cx> {
+		Value::from(cx.root_value(self.private.get()))
+	}
+
+	fn settle(&self, cx: &Context, evaluation: &Object) -> bool {
+		let private = self.private(cx);
+		let request = cx.root_object(self.request.get());
+		let promise = cx.root_object(self.promise.get());
+		unsafe {
+			FinishDynamicModuleImport(
+				cx.as_ptr(),
+				evaluation.handle().into(),
+				private.handle().into(),
+				request.handle().into(),
+				promise.handle().into(),
+			)
 		}
 	}
 }
@@ -174,6 +372,12 @@ pub trait ModuleLoader {
 
 	/// Returns metadata of a module, used to populate `import.meta`.
 	fn metadata(&self, cx: &Context, private: &Value, meta: &mut Object) -> bool;
+
+	/// Handles a dynamic `import()`.
+	/// Returns the import if it should be finished immediately, or [None] if the loader will [finish](DynamicImport::finish) it later.
+	fn dynamic_import(&mut self, _: &Context, import: DynamicImport) -> Option<DynamicImport> {
+		Some(import)
+	}
 }
 
 impl ModuleLoader for () {
@@ -220,11 +424,30 @@ pub fn init_module_loader<ML: ModuleLoader + 'static>(cx: &Context, loader: ML)
 			.unwrap_or_else(|| true)
 	}
 
+	unsafe extern "C" fn dynamic_import(
+		cx: *mut JSContext, private: Handle<JSVal>, request: Handle<*mut JSObject>, promise: Handle<*mut JSObject>,
+	) -> bool {
+		let cx = unsafe { Context::new_unchecked(cx) };
+		let import = DynamicImport {
+			private: PersistentRooted::new(private.get()),
+			request: PersistentRooted::new(request.get()),
+			promise: PersistentRooted::new(promise.get()),
+		};
+
+		let loader = unsafe { &mut (*cx.get_inner_data().as_ptr()).module_loader };
+		let import = match loader.as_mut() {
+			Some(loader) => loader.dynamic_import(&cx, import),
+			None => Some(import),
+		};
+		import.map(|import| import.finish(&cx)).unwrap_or(true)
+	}
+
 	unsafe {
 		(*cx.get_inner_data().as_ptr()).module_loader = Some(Box::new(loader));
 
 		let rt = JS_GetRuntime(cx.as_ptr());
 		SetModuleResolveHook(rt, Some(resolve));
 		SetModuleMetadataHook(rt, Some(metadata));
+		SetModuleDynamicImportHook(rt, Some(dynamic_import));
 	}
 }
@@ -0,0 +1,212 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::cell::RefCell;
+use std::future::poll_fn;
+use std::pin::Pin;
+use std::ptr;
+use std::rc::Rc;
+
+use futures::{Stream, StreamExt};
+use mozjs::glue::JS_GetReservedSlot;
+use mozjs::jsapi::{
+	GCContext, GetRealmAsyncIteratorPrototype, JSClass, JSCLASS_FOREGROUND_FINALIZE, JSClassOps, JSContext, JSFunctionSpec, JSNativeWrapper, JSObject,
+};
+use mozjs::jsval::{JSVal, NullValue};
+
+use crate::{Arguments, ClassDefinition, Context, Error, ErrorKind, Local, Object, Promise, ThrowException, Value};
+use crate::class::{NativeClass, NativeObject, Reflector, TypeIdWrapper};
+use crate::conversions::{BoxedIntoValue, IntoValue, ToValue};
+use crate::flags::PropertyFlags;
+use crate::functions::NativeFunction;
+use crate::objects::class_reserved_slots;
+use crate::spec::{create_function_spec, create_function_spec_symbol};
+use crate::symbol::WellKnownSymbolCode;
+
+type BoxedStream = Pin<Box<dyn Stream<Item = BoxedIntoValue>>>;
+
+struct AsyncIteratorResult(Option<BoxedIntoValue>);
+
+impl<'cx> IntoValue<'cx> for AsyncIteratorResult {
+	fn into_value(self: Box<Self>, cx: &'cx Context, value: &mut Value) {
+		let mut object = Object::new(cx);
+		let done = self.0.is_none();
+
+		let mut next = Value::undefined(cx);
+		if let Some(item) = self.0 {
+			item.into_value(cx, &mut next);
+		}
+		object.set(cx, "value", &next);
+		object.set_as(cx, "done", &done);
+		object.to_value(cx, value);
+	}
+}
+
+/// Represents a native [AsyncIterator](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/AsyncIterator),
+/// which yields the items of a [Stream] to `for await` loops.
+///
+/// Promises returned by `next()` are settled on the current [LocalSet](tokio::task::LocalSet).
+pub struct AsyncIterator {
+	reflector: Reflector,
+	stream: Rc<RefCell<BoxedStream>>,
+}
+
+impl AsyncIterator {
+	/// Creates an [AsyncIterator] which yields the items of a [Stream].
+	pub fn new<T, S>(stream: S) -> AsyncIterator
+	where
+		T: for<'cx> IntoValue<'cx> + 'static,
+		S: Stream<Item = T> + 'static,
+	{
+		let stream = stream.map(|item| Box::new(item) as BoxedIntoValue);
+		AsyncIterator {
+			reflector: Reflector::default(),
+			stream: Rc::new(RefCell::new(Box::pin(stream))),
+		}
+	}
+
+	/// Returns a [Promise] which resolves to the next result of the stream.
+	/// The promise is created with [Promise::from_spawned_future], so that the runtime tracks the stream while it is polled.
+	///
+	/// Returns [None] if the promise could not be created.
+	pub fn next_value<'cx>(&self, cx: &'cx Context) -> Option<Promise<'cx>> {
+		let stream = Rc::clone(&self.stream);
+		Promise::from_spawned_future(cx, async move {
+			let item = poll_fn(|wcx| stream.borrow_mut().as_mut().poll_next(wcx)).await;
+			Ok::<_, ()>(AsyncIteratorResult(item))
+		})
+	}
+}
+
+impl AsyncIterator {
+	unsafe extern "C" fn constructor(cx: *mut JSContext, _: u32, _: *mut JSVal) -> bool {
+		let cx = &unsafe { Context::new_unchecked(cx) };
+		Error::new("Constructor should not be called", ErrorKind::Type).throw(cx);
+		false
+	}
+
+	unsafe extern "C" fn next_raw(cx: *mut JSContext, argc: u32, vp: *mut JSVal) -> bool {
+		let cx = &unsafe { Context::new_unchecked(cx) };
+		let args = &mut unsafe { Arguments::new(cx, argc, vp) };
+
+		let mut this = args.this().to_object(cx);
+		let iterator = AsyncIterator::get_mut_private(&mut this);
+		match iterator.next_value(cx) {
+			Some(promise) => {
+				promise.to_value(cx, args.rval());
+				true
+			}
+			None => {
+				Error::new("Failed to Create Promise", None).throw(cx);
+				false
+			}
+		}
+	}
+
+	unsafe extern "C" fn iterable(cx: *mut JSContext, argc: u32, vp: *mut JSVal) -> bool {
+		let cx = &unsafe { Context::new_unchecked(cx) };
+		let args = &mut unsafe { Arguments::new(cx, argc, vp) };
+
+		let this = args.this().handle().get();
+		args.rval().handle_mut().set(this);
+
+		true
+	}
+
+	unsafe extern "C" fn finalise(_: *mut GCContext, this: *mut JSObject) {
+		let mut value = NullValue();
+		unsafe {
+			JS_GetReservedSlot(this, 0, &mut value);
+		}
+		if value.is_double() && value.asBits_ & 0xFFFF000000000000 == 0 {
+			let _ = unsafe { Box::from_raw(value.to_private() as *mut AsyncIterator) };
+		}
+	}
+}
+
+impl IntoValue<'_> for AsyncIterator {
+	fn into_value(self: Box<Self>, cx: &Context, value: &mut Value) {
+		let object = cx.root_object(AsyncIterator::new_object(cx, self));
+		object.handle().get().to_value(cx, value);
+	}
+}
+
+static ASYNC_ITERATOR_CLASS_OPS: JSClassOps = JSClassOps {
+	addProperty: None,
+	delProperty: None,
+	enumerate: None,
+	newEnumerate: None,
+	resolve: None,
+	mayResolve: None,
+	finalize: Some(AsyncIterator::finalise),
+	call: None,
+	construct: None,
+	trace: None,
+};
+
+// Streams of async iterators are not required to be `Send`, so they are finalised on the main thread instead of a background thread.
+static ASYNC_ITERATOR_CLASS: NativeClass = NativeClass {
+	base: JSClass {
+		name: "NativeAsyncIterator\0".as_ptr().cast(),
+		flags: JSCLASS_FOREGROUND_FINALIZE | class_reserved_slots(1),
+		cOps: &ASYNC_ITERATOR_CLASS_OPS,
+		spec: ptr::null_mut(),
+		ext: ptr::null_mut(),
+		oOps: ptr::null_mut(),
+	},
+	prototype_chain: [Some(&TypeIdWrapper::<AsyncIterator>::new()), None, None, None, None, None, None, None],
+};
+
+static ASYNC_ITERATOR_METHODS: &[JSFunctionSpec] = &[
+	create_function_spec(
+		"next\0",
+		JSNativeWrapper {
+			op: Some(AsyncIterator::next_raw),
+			info: ptr::null_mut(),
+		},
+		0,
+		PropertyFlags::CONSTANT_ENUMERATED,
+	),
+	create_function_spec_symbol(
+		WellKnownSymbolCode::AsyncIterator,
+		JSNativeWrapper {
+			op: Some(AsyncIterator::iterable),
+			info: ptr::null_mut(),
+		},
+		0,
+		PropertyFlags::CONSTANT,
+	),
+	JSFunctionSpec::ZERO,
+];
+
+impl NativeObject for AsyncIterator {
+	fn reflector(&self) -> &Reflector {
+		&self.reflector
+	}
+}
+
+impl ClassDefinition for AsyncIterator {
+	const NAME: &'static str = "";
+
+	fn class() -> &'static NativeClass {
+		&ASYNC_ITERATOR_CLASS
+	}
+
+	fn parent_class_info(cx: &Context) -> Option<(&'static NativeClass, Local<*mut JSObject>)> {
+		Some((
+			&ASYNC_ITERATOR_CLASS,
+			cx.root_object(unsafe { GetRealmAsyncIteratorPrototype(cx.as_ptr()) }),
+		))
+	}
+
+	fn constructor() -> (NativeFunction, u32) {
+		(AsyncIterator::constructor, 0)
+	}
+
+	fn functions() -> &'static [JSFunctionSpec] {
+		ASYNC_ITERATOR_METHODS
+	}
+}
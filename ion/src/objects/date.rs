@@ -5,6 +5,7 @@
  */
 
 use std::ops::{Deref, DerefMut};
+use std::time::SystemTime;
 
 use chrono::{DateTime, TimeZone};
 use chrono::offset::Utc;
@@ -32,6 +33,11 @@ impl<'d> Date<'d> {
 		}
 	}
 
+	/// Creates a new [Date] with the given [SystemTime].
+	pub fn from_system_time(cx: &'d Context, time: SystemTime) -> Date<'d> {
+		Date::from_date(cx, DateTime::from(time))
+	}
+
 	/// Creates a [Date] from an object.
 	/// Returns [None] if it is not a [Date].
 	pub fn from(cx: &Context, object: Local<'d, *mut JSObject>) -> Option<Date<'d>> {
@@ -66,6 +72,11 @@ impl<'d> Date<'d> {
 		}
 	}
 
+	/// Converts the [Date] to a [SystemTime].
+	pub fn to_system_time(&self, cx: &Context) -> Option<SystemTime> {
+		self.to_date(cx).map(SystemTime::from)
+	}
+
 	/// Checks if a [raw object](*mut JSObject) is a date.
 	pub fn is_date_raw(cx: &Context, object: *mut JSObject) -> bool {
 		rooted!(in(cx.as_ptr()) let object = object);
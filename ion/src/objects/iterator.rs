@@ -13,7 +13,7 @@ use mozjs::jsapi::{
 	GCContext, GetRealmIteratorPrototype, Heap, JSClass, JSCLASS_BACKGROUND_FINALIZE, JSClassOps, JSContext, JSFunctionSpec, JSNativeWrapper,
 	JSObject, JSTracer,
 };
-use mozjs::jsval::{JSVal, NullValue};
+use mozjs::jsval::{JSVal, NullValue, UndefinedValue};
 
 use crate::{Arguments, ClassDefinition, Context, Error, ErrorKind, Local, Object, ThrowException, Value};
 use crate::class::{NativeClass, NativeObject, Reflector, TypeIdWrapper};
@@ -70,6 +70,19 @@ impl Iterator {
 		}
 	}
 
+	/// Creates an [Iterator] which lazily yields the items of a Rust [Iterator](iter::Iterator).
+	pub fn new_lazy<T, I>(iter: I) -> Iterator
+	where
+		T: for<'cx> IntoValue<'cx>,
+		I: iter::Iterator<Item = T> + 'static,
+	{
+		Iterator {
+			reflector: Reflector::default(),
+			iter: Box::new(iter),
+			private: Heap::boxed(UndefinedValue()),
+		}
+	}
+
 	pub fn next_value<'cx>(&mut self, cx: &'cx Context) -> IteratorResult<'cx> {
 		let private = Value::from(unsafe { Local::from_heap(&self.private) });
 		let next = self.iter.next_value(cx, &private);
@@ -15,14 +15,22 @@ use mozjs::jsid::{IntId, VoidId};
 use crate::{Context, Local, String, Symbol, Value};
 use crate::conversions::ToPropertyKey;
 
+/// Represents a key on a JavaScript object, which is either an integer, a string or a symbol.
+///
+/// Integer keys are only used for non-negative integers that fit in an [i32]. Other numbers are converted to string keys.
 pub struct PropertyKey<'k> {
 	key: Local<'k, JSPropertyKey>,
 }
 
 impl<'k> PropertyKey<'k> {
 	/// Creates a [PropertyKey] from an integer.
+	/// Negative integers are converted to string keys.
 	pub fn with_int(cx: &'k Context, int: i32) -> PropertyKey<'k> {
-		PropertyKey::from(cx.root_property_key(IntId(int)))
+		if int >= 0 {
+			PropertyKey::from(cx.root_property_key(IntId(int)))
+		} else {
+			PropertyKey::with_string(cx, &int.to_string()).unwrap()
+		}
 	}
 
 	/// Creates a [PropertyKey] from a string.
@@ -31,26 +39,31 @@ impl<'k> PropertyKey<'k> {
 		string.to_key(cx)
 	}
 
+	/// Creates a [PropertyKey] from a symbol.
 	pub fn with_symbol(cx: &'k Context, symbol: &Symbol) -> PropertyKey<'k> {
 		symbol.to_key(cx).unwrap()
 	}
 
+	/// Creates a [PropertyKey] from the name of a standard class.
 	pub fn from_proto_key(cx: &'k Context, proto_key: JSProtoKey) -> PropertyKey<'k> {
 		let mut key = PropertyKey::from(cx.root_property_key(VoidId()));
 		unsafe { ProtoKeyToId(cx.as_ptr(), proto_key, key.handle_mut().into()) }
 		key
 	}
 
+	/// Converts a [Value] into a [PropertyKey], as done in property accesses.
 	pub fn from_value(cx: &'k Context, value: &Value) -> Option<PropertyKey<'k>> {
 		let mut key = PropertyKey::from(cx.root_property_key(VoidId()));
 		(unsafe { JS_ValueToId(cx.as_ptr(), value.handle().into(), key.handle_mut().into()) }).then_some(key)
 	}
 
+	/// Returns the standard class the [PropertyKey] refers to, if any.
 	pub fn to_proto_key(&self, cx: &Context) -> Option<JSProtoKey> {
 		let proto_key = unsafe { JS_IdToProtoKey(cx.as_ptr(), self.handle().into()) };
 		(proto_key != JSProtoKey::JSProto_Null).then_some(proto_key)
 	}
 
+	/// Converts the [PropertyKey] to an [OwnedKey].
 	pub fn to_owned_key<'cx>(&self, cx: &'cx Context) -> OwnedKey<'cx> {
 		if self.handle().is_int() {
 			OwnedKey::Int(self.handle().to_int())
@@ -63,6 +76,21 @@ impl<'k> PropertyKey<'k> {
 		}
 	}
 
+	/// Checks if the [PropertyKey] is an integer key.
+	pub fn is_int(&self) -> bool {
+		self.handle().is_int()
+	}
+
+	/// Checks if the [PropertyKey] is a string key.
+	pub fn is_string(&self) -> bool {
+		self.handle().is_string()
+	}
+
+	/// Checks if the [PropertyKey] is a symbol key.
+	pub fn is_symbol(&self) -> bool {
+		self.handle().is_symbol()
+	}
+
 	pub fn into_local(self) -> Local<'k, JSPropertyKey> {
 		self.key
 	}
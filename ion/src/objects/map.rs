@@ -0,0 +1,151 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::ops::{Deref, DerefMut};
+use std::vec;
+
+use mozjs::jsapi::{IsMapObject, JSObject, MapClear, MapDelete, MapEntries, MapGet, MapHas, MapKeys, MapSet, MapSize, MapValues, NewMapObject};
+
+use crate::{Array, Context, Local, Value};
+use crate::conversions::FromValue;
+
+/// Represents a [Map] in the JavaScript Runtime.
+/// Refer to [MDN](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Map) for more details.
+#[derive(Debug)]
+pub struct Map<'m> {
+	map: Local<'m, *mut JSObject>,
+}
+
+impl<'m> Map<'m> {
+	/// Creates a new empty [Map].
+	pub fn new(cx: &'m Context) -> Map<'m> {
+		Map {
+			map: cx.root_object(unsafe { NewMapObject(cx.as_ptr()) }),
+		}
+	}
+
+	/// Creates a [Map] from an object.
+	/// Returns [None] if it is not a [Map].
+	pub fn from(cx: &Context, object: Local<'m, *mut JSObject>) -> Option<Map<'m>> {
+		if Map::is_map(cx, &object) {
+			Some(Map { map: object })
+		} else {
+			None
+		}
+	}
+
+	/// Creates a [Map] from an object.
+	///
+	/// ### Safety
+	/// Object must be a [Map].
+	pub unsafe fn from_unchecked(object: Local<'m, *mut JSObject>) -> Map<'m> {
+		Map { map: object }
+	}
+
+	/// Returns the number of entries in the [Map].
+	pub fn size(&self, cx: &Context) -> u32 {
+		unsafe { MapSize(cx.as_ptr(), self.handle().into()) }
+	}
+
+	/// Checks if the [Map] has an entry with the given key.
+	pub fn has(&self, cx: &Context, key: &Value) -> bool {
+		let mut has = false;
+		unsafe { MapHas(cx.as_ptr(), self.handle().into(), key.handle().into(), &mut has) && has }
+	}
+
+	/// Gets the value of the entry with the given key.
+	/// Returns [None] if the [Map] does not have an entry with the given key.
+	pub fn get<'cx>(&self, cx: &'cx Context, key: &Value) -> Option<Value<'cx>> {
+		if self.has(cx, key) {
+			let mut value = Value::undefined(cx);
+			unsafe { MapGet(cx.as_ptr(), self.handle().into(), key.handle().into(), value.handle_mut().into()) }.then_some(value)
+		} else {
+			None
+		}
+	}
+
+	/// Sets the value of the entry with the given key.
+	pub fn set(&self, cx: &Context, key: &Value, value: &Value) -> bool {
+		unsafe { MapSet(cx.as_ptr(), self.handle().into(), key.handle().into(), value.handle().into()) }
+	}
+
+	/// Deletes the entry with the given key.
+	/// Returns `true` if an entry was deleted.
+	pub fn delete(&self, cx: &Context, key: &Value) -> bool {
+		let mut deleted = false;
+		unsafe { MapDelete(cx.as_ptr(), self.handle().into(), key.handle().into(), &mut deleted) && deleted }
+	}
+
+	/// Deletes all entries in the [Map].
+	pub fn clear(&self, cx: &Context) -> bool {
+		unsafe { MapClear(cx.as_ptr(), self.handle().into()) }
+	}
+
+	/// Returns an iterator over the keys of the [Map], in insertion order.
+	pub fn keys<'cx>(&self, cx: &'cx Context) -> vec::IntoIter<Value<'cx>> {
+		let mut keys = Value::undefined(cx);
+		unsafe { MapKeys(cx.as_ptr(), self.handle().into(), keys.handle_mut().into()) };
+		collect_iterator(cx, &keys).into_iter()
+	}
+
+	/// Returns an iterator over the values of the [Map], in insertion order.
+	pub fn values<'cx>(&self, cx: &'cx Context) -> vec::IntoIter<Value<'cx>> {
+		let mut values = Value::undefined(cx);
+		unsafe { MapValues(cx.as_ptr(), self.handle().into(), values.handle_mut().into()) };
+		collect_iterator(cx, &values).into_iter()
+	}
+
+	/// Returns an iterator over the entries of the [Map], in insertion order.
+	pub fn iter<'cx>(&self, cx: &'cx Context) -> vec::IntoIter<(Value<'cx>, Value<'cx>)> {
+		let mut entries = Value::undefined(cx);
+		unsafe { MapEntries(cx.as_ptr(), self.handle().into(), entries.handle_mut().into()) };
+		let entries: Vec<_> = collect_iterator(cx, &entries)
+			.into_iter()
+			.filter_map(|entry| {
+				let entry = Array::from(cx, entry.to_object(cx).into_local())?;
+				Some((entry.get(cx, 0)?, entry.get(cx, 1)?))
+			})
+			.collect();
+		entries.into_iter()
+	}
+
+	/// Checks if a [*mut] [JSObject] is a map.
+	pub fn is_map_raw(cx: &Context, object: *mut JSObject) -> bool {
+		rooted!(in(cx.as_ptr()) let object = object);
+		let mut is_map = false;
+		unsafe { IsMapObject(cx.as_ptr(), object.handle().into(), &mut is_map) && is_map }
+	}
+
+	/// Checks if an object is a map.
+	pub fn is_map(cx: &Context, object: &Local<*mut JSObject>) -> bool {
+		let mut is_map = false;
+		unsafe { IsMapObject(cx.as_ptr(), object.handle().into(), &mut is_map) && is_map }
+	}
+
+	pub fn into_local(self) -> Local<'m, *mut JSObject> {
+		self.map
+	}
+}
+
+impl<'m> Deref for Map<'m> {
+	type Target = Local<'m, *mut JSObject>;
+
+	fn deref(&self) -> &Self::Target {
+		&self.map
+	}
+}
+
+impl<'m> DerefMut for Map<'m> {
+	fn deref_mut(&mut self) -> &mut Self::Target {
+		&mut self.map
+	}
+}
+
+/// Collects the values of a JS iterator into a [Vec].
+/// Returns an empty [Vec] if iteration fails.
+pub(crate) fn collect_iterator<'cx>(cx: &'cx Context, iterator: &Value) -> Vec<Value<'cx>> {
+	Vec::from_value(cx, iterator, false, ()).unwrap_or_default()
+}
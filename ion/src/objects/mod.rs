@@ -10,25 +10,37 @@ use mozjs::jsapi::{JS_NewGlobalObject, JSClass, JSCLASS_RESERVED_SLOTS_MASK, JSC
 use mozjs::rust::{RealmOptions, SIMPLE_GLOBAL_CLASS};
 
 pub use array::Array;
+pub use async_iterator::AsyncIterator;
 pub use date::Date;
 pub use descriptor::PropertyDescriptor;
 pub use iterator::{Iterator, JSIterator};
 pub use key::{OwnedKey, PropertyKey};
+pub use map::Map;
 pub use object::Object;
-pub use promise::Promise;
-pub use regexp::RegExp;
+pub use promise::{FutureSpawner, Promise, SpawnedFuture};
+pub use proxy::{Proxy, ProxyBuilder};
+pub use regexp::{RegExp, RegExpMatch};
+pub use set::Set;
+pub use wasm::{WasmDescriptor, WasmExternKind, WasmInstance, WasmMemory, WasmModule};
+pub use weak::Weak;
 
 use crate::Context;
 
 mod array;
+mod async_iterator;
 mod date;
 mod descriptor;
 mod iterator;
 mod key;
+mod map;
 mod object;
 mod promise;
+mod proxy;
 mod regexp;
+mod set;
 pub mod typedarray;
+mod wasm;
+mod weak;
 
 /// Returns the bit-masked representation of reserved slots for a class.
 pub const fn class_reserved_slots(slots: u32) -> u32 {
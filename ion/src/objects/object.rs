@@ -8,12 +8,12 @@ use std::collections::HashMap;
 use std::iter::FusedIterator;
 use std::mem::MaybeUninit;
 use std::ops::{Deref, DerefMut};
-use std::slice;
+use std::{ptr, slice};
 
 use mozjs::jsapi::{
 	CurrentGlobalOrNull, ESClass, GetBuiltinClass, GetPropertyKeys, JS_DefineFunctionById, JS_DefineFunctions, JS_DefineFunctionsWithHelp,
-	JS_DefineProperties, JS_DefinePropertyById2, JS_DeletePropertyById, JS_GetPropertyById, JS_HasOwnPropertyById, JS_HasPropertyById,
-	JS_NewPlainObject, JS_SetPropertyById, JSFunctionSpec, JSFunctionSpecWithHelp, JSObject, JSPropertySpec, Unbox,
+	JS_DefineProperties, JS_DefinePropertyById2, JS_DeletePropertyById, JS_GetPropertyById, JS_GetPrototype, JS_HasOwnPropertyById,
+	JS_HasPropertyById, JS_NewPlainObject, JS_SetPropertyById, JSFunctionSpec, JSFunctionSpecWithHelp, JSObject, JSPropertySpec, Unbox,
 };
 use mozjs::jsapi::PropertyKey as JSPropertyKey;
 use mozjs::jsval::NullValue;
@@ -198,6 +198,13 @@ impl<'o> Object<'o> {
 		class
 	}
 
+	/// Returns the prototype of the object, or [None] if it has none.
+	pub fn get_prototype<'cx>(&self, cx: &'cx Context) -> Option<Object<'cx>> {
+		let mut prototype = Object::from(cx.root_object(ptr::null_mut()));
+		let success = unsafe { JS_GetPrototype(cx.as_ptr(), self.handle().into(), prototype.handle_mut().into()) };
+		(success && !prototype.handle().get().is_null()).then_some(prototype)
+	}
+
 	/// Returns the builtin class of the object if it a wrapper around a primitive.
 	///
 	/// The boxed types are `Boolean`, `Number`, `String` and `BigInt`
@@ -5,24 +5,29 @@
  */
 
 use std::future::Future;
-use std::mem::transmute;
 use std::ops::{Deref, DerefMut};
+use std::pin::Pin;
 
 use futures::executor::block_on;
-use libffi::high::ClosureOnce3;
 use mozjs::glue::JS_GetPromiseResult;
 use mozjs::jsapi::{
-	AddPromiseReactions, GetPromiseID, GetPromiseState, IsPromiseObject, JSContext, JSObject, NewPromiseObject, PromiseState, RejectPromise,
-	ResolvePromise,
+	AddPromiseReactions, GetPromiseConstructor, GetPromiseID, GetPromiseState, IsPromiseObject, JSObject, NewPromiseObject, PromiseState,
+	RejectPromise, ResolvePromise,
 };
-use mozjs::jsval::JSVal;
+use mozjs::jsval::ObjectValue;
 use mozjs::rust::HandleObject;
+use tokio::task::spawn_local;
 
-use crate::{Arguments, Context, Function, Local, Object, Value};
-use crate::conversions::ToValue;
-use crate::exception::ThrowException;
+use crate::{Array, Context, ErrorReport, Function, Local, Object, PersistentRooted, Value};
+use crate::conversions::{BoxedIntoValue, IntoValue, ToValue};
 use crate::flags::PropertyFlags;
-use crate::functions::NativeFunction;
+
+/// Represents a future which settles a promise, with the values it is fulfilled or rejected with.
+pub type SpawnedFuture = Pin<Box<dyn Future<Output = Result<BoxedIntoValue, BoxedIntoValue>>>>;
+
+/// Represents a function which converts a [SpawnedFuture] to a [Promise].
+/// Runtimes set this in [ContextInner](crate::ContextInner), so that they can track the futures of native objects until they complete.
+pub type FutureSpawner = for<'cx> fn(&'cx Context, SpawnedFuture) -> Option<Promise<'cx>>;
 
 /// Represents a [Promise] in the JavaScript Runtime.
 /// Refer to [MDN](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Promise) for more details.
@@ -46,36 +51,29 @@ impl<'p> Promise<'p> {
 	where
 		F: for<'cx> FnOnce(&'cx Context, Function<'cx>, Function<'cx>) -> crate::Result<()> + 'static,
 	{
-		unsafe {
-			let native = move |cx: *mut JSContext, argc: u32, vp: *mut JSVal| {
-				let cx = Context::new_unchecked(cx);
-				let args = Arguments::new(&cx, argc, vp);
-
-				let resolve_obj = args.value(0).unwrap().to_object(&cx).into_local();
-				let reject_obj = args.value(1).unwrap().to_object(&cx).into_local();
-				let resolve = Function::from_object(&cx, &resolve_obj).unwrap();
-				let reject = Function::from_object(&cx, &reject_obj).unwrap();
-
-				match executor(&cx, resolve, reject) {
-					Ok(()) => true as u8,
-					Err(error) => {
-						error.throw(&cx);
-						false as u8
-					}
-				}
-			};
-			let closure = ClosureOnce3::new(native);
-			let fn_ptr: &NativeFunction = transmute(closure.code_ptr());
+		let executor = Function::from_closure_once(
+			cx,
+			"executor",
+			Box::new(move |args| {
+				let cx = args.cx();
+				let resolve_obj = args.value(0).unwrap().to_object(cx).into_local();
+				let reject_obj = args.value(1).unwrap().to_object(cx).into_local();
+				let resolve = Function::from_object(cx, &resolve_obj).unwrap();
+				let reject = Function::from_object(cx, &reject_obj).unwrap();
 
-			let function = Function::new(cx, "executor", Some(*fn_ptr), 2, PropertyFlags::empty());
-			let executor = function.to_object(cx);
-			let promise = NewPromiseObject(cx.as_ptr(), executor.handle().into());
+				executor(cx, resolve, reject)?;
+				Ok(Value::undefined(cx))
+			}),
+			2,
+			PropertyFlags::empty(),
+		);
+		let executor = executor.to_object(cx);
 
-			if !promise.is_null() {
-				Some(Promise { promise: cx.root_object(promise) })
-			} else {
-				None
-			}
+		let promise = unsafe { NewPromiseObject(cx.as_ptr(), executor.handle().into()) };
+		if !promise.is_null() {
+			Some(Promise { promise: cx.root_object(promise) })
+		} else {
+			None
 		}
 	}
 
@@ -111,6 +109,127 @@ impl<'p> Promise<'p> {
 		})
 	}
 
+	/// Creates a new [Promise] with a [Future].
+	/// The future is spawned onto the current [LocalSet](tokio::task::LocalSet) and does not block script execution.
+	///
+	/// The [Result] of the future determines if the promise is resolved or rejected.
+	///
+	/// ### Panics
+	/// Panics if called outside of a [LocalSet](tokio::task::LocalSet).
+	pub fn from_future<F, Output, Error>(cx: &'p Context, future: F) -> Promise<'p>
+	where
+		F: Future<Output = Result<Output, Error>> + 'static,
+		Output: for<'cx> IntoValue<'cx> + 'static,
+		Error: for<'cx> IntoValue<'cx> + 'static,
+	{
+		let promise = Promise::new(cx);
+		let object = PersistentRooted::new(promise.handle().get());
+
+		let raw_cx = cx.as_ptr();
+		spawn_local(async move {
+			let result = future.await;
+
+			let cx = unsafe { Context::new_unchecked(raw_cx) };
+			let promise = Promise { promise: cx.root_object(object.get()) };
+			drop(object);
+
+			let mut value = Value::undefined(&cx);
+			let settled = match result {
+				Ok(output) => {
+					Box::new(output).into_value(&cx, &mut value);
+					promise.resolve(&cx, &value)
+				}
+				Err(error) => {
+					Box::new(error).into_value(&cx, &mut value);
+					promise.reject(&cx, &value)
+				}
+			};
+			if !settled {
+				if let Some(report) = ErrorReport::new_with_exception_stack(&cx) {
+					println!("{}", report.format(&cx));
+				}
+			}
+		});
+
+		promise
+	}
+
+	/// Creates a new [Promise] from a future, with the [FutureSpawner] of the context if one is set,
+	/// and otherwise with [Promise::from_future].
+	///
+	/// Returns [None] if the spawner could not convert the future.
+	pub fn from_spawned_future<F, Output, Error>(cx: &'p Context, future: F) -> Option<Promise<'p>>
+	where
+		F: Future<Output = Result<Output, Error>> + 'static,
+		Output: for<'cx> IntoValue<'cx> + 'static,
+		Error: for<'cx> IntoValue<'cx> + 'static,
+	{
+		let spawner = unsafe { (*cx.get_inner_data().as_ptr()).future_spawner };
+		match spawner {
+			Some(spawner) => {
+				let future = async move {
+					match future.await {
+						Ok(output) => Ok(Box::new(output) as BoxedIntoValue),
+						Err(error) => Err(Box::new(error) as BoxedIntoValue),
+					}
+				};
+				spawner(cx, Box::pin(future))
+			}
+			None => Some(Promise::from_future(cx, future)),
+		}
+	}
+
+	/// Creates a new [Promise] which is fulfilled when all of the given promises are fulfilled, and rejected when any of them is rejected.
+	/// Refer to [MDN](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Promise/all) for more details.
+	pub fn all<'i, I>(cx: &'p Context, promises: I) -> Option<Promise<'p>>
+	where
+		I: IntoIterator<Item = &'i Promise<'i>>,
+	{
+		Promise::combinator(cx, "all", promises)
+	}
+
+	/// Creates a new [Promise] which is fulfilled when all of the given promises are settled.
+	/// Refer to [MDN](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Promise/allSettled) for more details.
+	pub fn all_settled<'i, I>(cx: &'p Context, promises: I) -> Option<Promise<'p>>
+	where
+		I: IntoIterator<Item = &'i Promise<'i>>,
+	{
+		Promise::combinator(cx, "allSettled", promises)
+	}
+
+	/// Creates a new [Promise] which is settled in the same way as the first of the given promises to settle.
+	/// Refer to [MDN](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Promise/race) for more details.
+	pub fn race<'i, I>(cx: &'p Context, promises: I) -> Option<Promise<'p>>
+	where
+		I: IntoIterator<Item = &'i Promise<'i>>,
+	{
+		Promise::combinator(cx, "race", promises)
+	}
+
+	/// Creates a new [Promise] which is fulfilled when any of the given promises are fulfilled, and rejected with an `AggregateError` when all of them are rejected.
+	/// Refer to [MDN](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Promise/any) for more details.
+	pub fn any<'i, I>(cx: &'p Context, promises: I) -> Option<Promise<'p>>
+	where
+		I: IntoIterator<Item = &'i Promise<'i>>,
+	{
+		Promise::combinator(cx, "any", promises)
+	}
+
+	fn combinator<'i, I>(cx: &'p Context, name: &str, promises: I) -> Option<Promise<'p>>
+	where
+		I: IntoIterator<Item = &'i Promise<'i>>,
+	{
+		let constructor = Object::from(cx.root_object(unsafe { GetPromiseConstructor(cx.as_ptr()) }));
+		let combinator = constructor.get(cx, name).filter(|combinator| combinator.handle().is_object())?;
+		let combinator = Function::from_object(cx, &combinator.to_object(cx).into_local())?;
+
+		let promises: Vec<_> = promises.into_iter().map(|promise| ObjectValue(promise.get())).collect();
+		let promises = Array::from_slice(cx, &promises);
+
+		let result = combinator.call(cx, &constructor, &[promises.as_value(cx)]).ok()?;
+		Promise::from(result.to_object(cx).into_local())
+	}
+
 	/// Creates a [Promise] from an object.
 	pub fn from(object: Local<'p, *mut JSObject>) -> Option<Promise<'p>> {
 		if Promise::is_promise(&object) {
@@ -140,14 +259,23 @@ impl<'p> Promise<'p> {
 		unsafe { GetPromiseState(self.handle().into()) }
 	}
 
-	/// Returns the result of the [Promise].
+	/// Returns the result of the [Promise] if it has been settled.
 	///
-	/// ### Note
-	/// Currently leads to a sefault.
-	pub fn result<'cx>(&self, cx: &'cx Context) -> Value<'cx> {
+	/// Returns `Ok` with the fulfillment value if the promise was fulfilled, `Err` with the rejection reason if it was rejected,
+	/// and [None] if it is still pending.
+	pub fn settled_result<'cx>(&self, cx: &'cx Context) -> Option<Result<Value<'cx>, Value<'cx>>> {
+		let state = self.state();
+		if state == PromiseState::Pending {
+			return None;
+		}
+
 		let mut value = Value::undefined(cx);
 		unsafe { JS_GetPromiseResult(self.handle().into(), value.handle_mut().into()) }
-		value
+		match state {
+			PromiseState::Fulfilled => Some(Ok(value)),
+			PromiseState::Rejected => Some(Err(value)),
+			PromiseState::Pending => unreachable!(),
+		}
 	}
 
 	/// Adds Reactions to the [Promise]
@@ -0,0 +1,248 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::ops::{Deref, DerefMut};
+
+use mozjs::jsapi::JSObject;
+use mozjs::jsval::JSVal;
+
+use crate::{Array, Arguments, Context, Error, ErrorKind, Function, Local, Object, PropertyKey, Result, Value};
+use crate::conversions::ToValue;
+use crate::objects::PropertyDescriptor;
+
+/// Trap for `[[Get]]`, receiving the target, key and receiver.
+pub type GetTrap = dyn for<'cx> FnMut(&'cx Context, &Object<'cx>, &PropertyKey<'cx>, &Value<'cx>) -> Result<Value<'cx>> + 'static;
+/// Trap for `[[Set]]`, receiving the target, key, value and receiver.
+pub type SetTrap = dyn for<'cx> FnMut(&'cx Context, &Object<'cx>, &PropertyKey<'cx>, &Value<'cx>, &Value<'cx>) -> Result<bool> + 'static;
+/// Trap for `[[HasProperty]]` and `[[Delete]]`, receiving the target and key.
+pub type KeyTrap = dyn for<'cx> FnMut(&'cx Context, &Object<'cx>, &PropertyKey<'cx>) -> Result<bool> + 'static;
+/// Trap for `[[OwnPropertyKeys]]`, receiving the target.
+pub type OwnKeysTrap = dyn for<'cx> FnMut(&'cx Context, &Object<'cx>) -> Result<Vec<PropertyKey<'cx>>> + 'static;
+/// Trap for `[[GetOwnProperty]]`, receiving the target and key.
+pub type GetOwnPropertyDescriptorTrap =
+	dyn for<'cx> FnMut(&'cx Context, &Object<'cx>, &PropertyKey<'cx>) -> Result<Option<PropertyDescriptor<'cx>>> + 'static;
+
+/// Builder for a [Proxy] whose traps are implemented by Rust closures.
+///
+/// Traps which are not provided fall back to the default behaviour of forwarding to the target.
+#[derive(Default)]
+pub struct ProxyBuilder {
+	get: Option<Box<GetTrap>>,
+	set: Option<Box<SetTrap>>,
+	has: Option<Box<KeyTrap>>,
+	own_keys: Option<Box<OwnKeysTrap>>,
+	delete_property: Option<Box<KeyTrap>>,
+	get_own_property_descriptor: Option<Box<GetOwnPropertyDescriptorTrap>>,
+}
+
+impl ProxyBuilder {
+	pub fn new() -> ProxyBuilder {
+		ProxyBuilder::default()
+	}
+
+	/// Sets the `get` trap.
+	pub fn get<F>(mut self, trap: F) -> ProxyBuilder
+	where
+		F: for<'cx> FnMut(&'cx Context, &Object<'cx>, &PropertyKey<'cx>, &Value<'cx>) -> Result<Value<'cx>> + 'static,
+	{
+		self.get = Some(Box::new(trap));
+		self
+	}
+
+	/// Sets the `set` trap.
+	/// Returning `false` causes a [TypeError](ErrorKind::Type) in strict mode code.
+	pub fn set<F>(mut self, trap: F) -> ProxyBuilder
+	where
+		F: for<'cx> FnMut(&'cx Context, &Object<'cx>, &PropertyKey<'cx>, &Value<'cx>, &Value<'cx>) -> Result<bool> + 'static,
+	{
+		self.set = Some(Box::new(trap));
+		self
+	}
+
+	/// Sets the `has` trap, used by the `in` operator.
+	pub fn has<F>(mut self, trap: F) -> ProxyBuilder
+	where
+		F: for<'cx> FnMut(&'cx Context, &Object<'cx>, &PropertyKey<'cx>) -> Result<bool> + 'static,
+	{
+		self.has = Some(Box::new(trap));
+		self
+	}
+
+	/// Sets the `ownKeys` trap.
+	/// Integer keys are converted to strings before being returned to JavaScript.
+	pub fn own_keys<F>(mut self, trap: F) -> ProxyBuilder
+	where
+		F: for<'cx> FnMut(&'cx Context, &Object<'cx>) -> Result<Vec<PropertyKey<'cx>>> + 'static,
+	{
+		self.own_keys = Some(Box::new(trap));
+		self
+	}
+
+	/// Sets the `deleteProperty` trap.
+	pub fn delete_property<F>(mut self, trap: F) -> ProxyBuilder
+	where
+		F: for<'cx> FnMut(&'cx Context, &Object<'cx>, &PropertyKey<'cx>) -> Result<bool> + 'static,
+	{
+		self.delete_property = Some(Box::new(trap));
+		self
+	}
+
+	/// Sets the `getOwnPropertyDescriptor` trap.
+	pub fn get_own_property_descriptor<F>(mut self, trap: F) -> ProxyBuilder
+	where
+		F: for<'cx> FnMut(&'cx Context, &Object<'cx>, &PropertyKey<'cx>) -> Result<Option<PropertyDescriptor<'cx>>> + 'static,
+	{
+		self.get_own_property_descriptor = Some(Box::new(trap));
+		self
+	}
+
+	/// Creates the [Proxy] around the given target.
+	/// Returns [None] if the proxy could not be created.
+	pub fn build<'cx>(self, cx: &'cx Context, target: &Object) -> Option<Proxy<'cx>> {
+		let handler = self.into_handler(cx);
+
+		let constructor = Object::global(cx).get(cx, "Proxy")?.to_object(cx);
+		let revocable = constructor.get(cx, "revocable").filter(|revocable| revocable.handle().is_object())?;
+		let revocable = Function::from_object(cx, &revocable.to_object(cx).into_local())?;
+
+		let result = revocable
+			.call(cx, &constructor, &[target.as_value(cx), handler.as_value(cx)])
+			.ok()?
+			.to_object(cx);
+		let proxy = result.get(cx, "proxy")?.to_object(cx);
+		let revoke = result.get(cx, "revoke")?.to_object(cx);
+		Some(Proxy {
+			proxy: proxy.into_local(),
+			revoke: Function::from_object(cx, &revoke.into_local())?,
+		})
+	}
+
+	fn into_handler(self, cx: &Context) -> Object {
+		let mut handler = Object::new(cx);
+
+		if let Some(mut trap) = self.get {
+			let function = Function::new_closure(cx, "get", move |cx, args| {
+				let (target, key) = target_and_key(cx, args)?;
+				let receiver = argument(cx, args, 2);
+				trap(cx, &target, &key, &receiver)
+			});
+			handler.set_as(cx, "get", &function);
+		}
+
+		if let Some(mut trap) = self.set {
+			let function = Function::new_closure(cx, "set", move |cx, args| {
+				let (target, key) = target_and_key(cx, args)?;
+				let value = argument(cx, args, 2);
+				let receiver = argument(cx, args, 3);
+				trap(cx, &target, &key, &value, &receiver).map(|result| Value::bool(cx, result))
+			});
+			handler.set_as(cx, "set", &function);
+		}
+
+		if let Some(mut trap) = self.has {
+			let function = Function::new_closure(cx, "has", move |cx, args| {
+				let (target, key) = target_and_key(cx, args)?;
+				trap(cx, &target, &key).map(|result| Value::bool(cx, result))
+			});
+			handler.set_as(cx, "has", &function);
+		}
+
+		if let Some(mut trap) = self.own_keys {
+			let function = Function::new_closure(cx, "ownKeys", move |cx, args| {
+				let target = args.get::<Object>(0)?;
+				let keys = trap(cx, &target)?;
+				let keys: Vec<_> = keys.iter().map(|key| key_to_value(cx, key)).collect();
+				let keys: Vec<JSVal> = keys.iter().map(|key| key.get()).collect();
+				Ok(Array::from_slice(cx, &keys).as_value(cx))
+			});
+			handler.set_as(cx, "ownKeys", &function);
+		}
+
+		if let Some(mut trap) = self.delete_property {
+			let function = Function::new_closure(cx, "deleteProperty", move |cx, args| {
+				let (target, key) = target_and_key(cx, args)?;
+				trap(cx, &target, &key).map(|result| Value::bool(cx, result))
+			});
+			handler.set_as(cx, "deleteProperty", &function);
+		}
+
+		if let Some(mut trap) = self.get_own_property_descriptor {
+			let function = Function::new_closure(cx, "getOwnPropertyDescriptor", move |cx, args| {
+				let (target, key) = target_and_key(cx, args)?;
+				match trap(cx, &target, &key)? {
+					Some(descriptor) => {
+						let descriptor = descriptor
+							.to_object(cx)
+							.ok_or_else(|| Error::new("Invalid Property Descriptor", ErrorKind::Type))?;
+						Ok(descriptor.as_value(cx))
+					}
+					None => Ok(Value::undefined(cx)),
+				}
+			});
+			handler.set_as(cx, "getOwnPropertyDescriptor", &function);
+		}
+
+		handler
+	}
+}
+
+fn argument<'cx>(cx: &'cx Context, args: &Arguments<'cx>, index: usize) -> Value<'cx> {
+	args.value(index)
+		.map(|value| value.get().as_value(cx))
+		.unwrap_or_else(|| Value::undefined(cx))
+}
+
+fn target_and_key<'cx>(cx: &'cx Context, args: &Arguments<'cx>) -> Result<(Object<'cx>, PropertyKey<'cx>)> {
+	let target = args.get::<Object>(0)?;
+	let key = PropertyKey::from_value(cx, &argument(cx, args, 1)).ok_or_else(|| Error::new("Invalid Property Key", ErrorKind::Type))?;
+	Ok((target, key))
+}
+
+fn key_to_value<'cx>(cx: &'cx Context, key: &PropertyKey) -> Value<'cx> {
+	if key.is_int() {
+		Value::string(cx, &key.handle().to_int().to_string())
+	} else {
+		key.handle().get().as_value(cx)
+	}
+}
+
+/// Represents a revocable [Proxy] in the JavaScript Runtime, created with a [ProxyBuilder].
+/// Refer to [MDN](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Proxy) for more details.
+#[derive(Debug)]
+pub struct Proxy<'p> {
+	proxy: Local<'p, *mut JSObject>,
+	revoke: Function<'p>,
+}
+
+impl<'p> Proxy<'p> {
+	/// Creates a [ProxyBuilder] for defining the traps of a [Proxy].
+	pub fn builder() -> ProxyBuilder {
+		ProxyBuilder::new()
+	}
+
+	/// Revokes the [Proxy], after which any operation on it throws a [TypeError](ErrorKind::Type).
+	pub fn revoke(&self, cx: &Context) -> bool {
+		self.revoke.call(cx, &Object::null(cx), &[]).is_ok()
+	}
+
+	pub fn into_local(self) -> Local<'p, *mut JSObject> {
+		self.proxy
+	}
+}
+
+impl<'p> Deref for Proxy<'p> {
+	type Target = Local<'p, *mut JSObject>;
+
+	fn deref(&self) -> &Self::Target {
+		&self.proxy
+	}
+}
+
+impl<'p> DerefMut for Proxy<'p> {
+	fn deref_mut(&mut self) -> &mut Self::Target {
+		&mut self.proxy
+	}
+}
@@ -4,6 +4,7 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/.
  */
 
+use std::collections::HashMap;
 use std::ops::{Deref, DerefMut};
 use std::ptr::NonNull;
 
@@ -11,9 +12,77 @@ use mozjs::glue::JS_GetRegExpFlags;
 use mozjs::jsapi::{CheckRegExpSyntax, ExecuteRegExp, ExecuteRegExpNoStatics, GetRegExpSource, JSObject, NewUCRegExpObject, ObjectIsRegExp};
 use mozjs::jsapi::RegExpFlags as REFlags;
 
-use crate::{Context, Local, Object, Value};
+use crate::{Array, Context, Local, Object, OwnedKey, Value};
+use crate::conversions::{ConversionBehavior, FromValue};
 use crate::flags::RegExpFlags;
 
+/// Represents the result of a successful match of a [RegExp].
+/// Indices are measured in UTF-16 code units, as in JavaScript.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RegExpMatch {
+	/// Index of the start of the match in the input string.
+	pub index: usize,
+	/// Matched substring, followed by each capture group. Groups which did not participate in the match are [None].
+	pub captures: Vec<Option<String>>,
+	/// Named capture groups.
+	pub groups: HashMap<String, Option<String>>,
+	/// Start and end indices of the matched substring and each capture group.
+	/// Only present if the [RegExp] has the `d` flag.
+	pub indices: Option<Vec<Option<(usize, usize)>>>,
+}
+
+impl RegExpMatch {
+	fn from_value(cx: &Context, value: &Value) -> Option<RegExpMatch> {
+		if !value.handle().is_object() {
+			return None;
+		}
+		let array = Array::from(cx, value.to_object(cx).into_local())?;
+		let object = array.to_object(cx);
+
+		let captures = array
+			.to_vec(cx)
+			.iter()
+			.map(|capture| Option::<String>::from_value(cx, capture, false, ()).ok().flatten())
+			.collect();
+		let index = object
+			.get_as::<_, u32>(cx, "index", true, ConversionBehavior::Default)
+			.unwrap_or_default() as usize;
+
+		let groups = object
+			.get(cx, "groups")
+			.filter(|groups| groups.handle().is_object())
+			.map(|groups| {
+				let groups = groups.to_object(cx);
+				groups
+					.to_hashmap(cx, None)
+					.into_iter()
+					.filter_map(|(key, value)| match key {
+						OwnedKey::String(key) => Some((key, Option::<String>::from_value(cx, &value, false, ()).ok().flatten())),
+						_ => None,
+					})
+					.collect()
+			})
+			.unwrap_or_default();
+
+		let indices = object
+			.get(cx, "indices")
+			.and_then(|indices| Vec::<Option<Vec<u32>>>::from_value(cx, &indices, true, ConversionBehavior::Default).ok())
+			.map(|indices| {
+				indices
+					.into_iter()
+					.map(|range| match range.as_deref() {
+						Some(&[start, end]) => Some((start as usize, end as usize)),
+						_ => None,
+					})
+					.collect()
+			});
+
+		Some(RegExpMatch { index, captures, groups, indices })
+	}
+}
+
+/// Represents a [RegExp] in the JavaScript Runtime.
+/// Refer to [MDN](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/RegExp) for more details.
 #[derive(Debug)]
 pub struct RegExp<'r> {
 	re: Local<'r, *mut JSObject>,
@@ -81,6 +150,20 @@ impl<'r> RegExp<'r> {
 		self.execute(cx, string, index, false, &mut rval, false).then_some(rval)
 	}
 
+	/// Checks if the [RegExp] matches the given string, starting from the beginning.
+	/// Unlike `RegExp.prototype.test`, this does not update `lastIndex` or the legacy static properties.
+	pub fn test(&self, cx: &Context, string: &str) -> bool {
+		self.execute_test_no_static(cx, string, &mut 0)
+	}
+
+	/// Executes the [RegExp] on the given string, starting from `index`, and returns the match, if any.
+	/// Unlike `RegExp.prototype.exec`, this does not update `lastIndex` or the legacy static properties.
+	pub fn exec(&self, cx: &Context, string: &str, index: usize) -> Option<RegExpMatch> {
+		let mut index = index;
+		let result = self.execute_match_no_static(cx, string, &mut index)?;
+		RegExpMatch::from_value(cx, &result)
+	}
+
 	fn execute<'cx>(&self, cx: &'cx Context, string: &str, index: &mut usize, test: bool, rval: &mut Value<'cx>, with_static: bool) -> bool {
 		let string: Vec<u16> = string.encode_utf16().collect();
 		if with_static {
@@ -0,0 +1,113 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::ops::{Deref, DerefMut};
+use std::vec;
+
+use mozjs::jsapi::{IsSetObject, JSObject, NewSetObject, SetAdd, SetClear, SetDelete, SetHas, SetSize, SetValues};
+
+use crate::{Context, Local, Value};
+use crate::objects::map::collect_iterator;
+
+/// Represents a [Set] in the JavaScript Runtime.
+/// Refer to [MDN](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Set) for more details.
+#[derive(Debug)]
+pub struct Set<'s> {
+	set: Local<'s, *mut JSObject>,
+}
+
+impl<'s> Set<'s> {
+	/// Creates a new empty [Set].
+	pub fn new(cx: &'s Context) -> Set<'s> {
+		Set {
+			set: cx.root_object(unsafe { NewSetObject(cx.as_ptr()) }),
+		}
+	}
+
+	/// Creates a [Set] from an object.
+	/// Returns [None] if it is not a [Set].
+	pub fn from(cx: &Context, object: Local<'s, *mut JSObject>) -> Option<Set<'s>> {
+		if Set::is_set(cx, &object) {
+			Some(Set { set: object })
+		} else {
+			None
+		}
+	}
+
+	/// Creates a [Set] from an object.
+	///
+	/// ### Safety
+	/// Object must be a [Set].
+	pub unsafe fn from_unchecked(object: Local<'s, *mut JSObject>) -> Set<'s> {
+		Set { set: object }
+	}
+
+	/// Returns the number of values in the [Set].
+	pub fn size(&self, cx: &Context) -> u32 {
+		unsafe { SetSize(cx.as_ptr(), self.handle().into()) }
+	}
+
+	/// Checks if the [Set] contains the given value.
+	pub fn has(&self, cx: &Context, value: &Value) -> bool {
+		let mut has = false;
+		unsafe { SetHas(cx.as_ptr(), self.handle().into(), value.handle().into(), &mut has) && has }
+	}
+
+	/// Adds the given value to the [Set].
+	pub fn add(&self, cx: &Context, value: &Value) -> bool {
+		unsafe { SetAdd(cx.as_ptr(), self.handle().into(), value.handle().into()) }
+	}
+
+	/// Deletes the given value from the [Set].
+	/// Returns `true` if the value was deleted.
+	pub fn delete(&self, cx: &Context, value: &Value) -> bool {
+		let mut deleted = false;
+		unsafe { SetDelete(cx.as_ptr(), self.handle().into(), value.handle().into(), &mut deleted) && deleted }
+	}
+
+	/// Deletes all values in the [Set].
+	pub fn clear(&self, cx: &Context) -> bool {
+		unsafe { SetClear(cx.as_ptr(), self.handle().into()) }
+	}
+
+	/// Returns an iterator over the values of the [Set], in insertion order.
+	pub fn iter<'cx>(&self, cx: &'cx Context) -> vec::IntoIter<Value<'cx>> {
+		let mut values = Value::undefined(cx);
+		unsafe { SetValues(cx.as_ptr(), self.handle().into(), values.handle_mut().into()) };
+		collect_iterator(cx, &values).into_iter()
+	}
+
+	/// Checks if a [*mut] [JSObject] is a set.
+	pub fn is_set_raw(cx: &Context, object: *mut JSObject) -> bool {
+		rooted!(in(cx.as_ptr()) let object = object);
+		let mut is_set = false;
+		unsafe { IsSetObject(cx.as_ptr(), object.handle().into(), &mut is_set) && is_set }
+	}
+
+	/// Checks if an object is a set.
+	pub fn is_set(cx: &Context, object: &Local<*mut JSObject>) -> bool {
+		let mut is_set = false;
+		unsafe { IsSetObject(cx.as_ptr(), object.handle().into(), &mut is_set) && is_set }
+	}
+
+	pub fn into_local(self) -> Local<'s, *mut JSObject> {
+		self.set
+	}
+}
+
+impl<'s> Deref for Set<'s> {
+	type Target = Local<'s, *mut JSObject>;
+
+	fn deref(&self) -> &Self::Target {
+		&self.set
+	}
+}
+
+impl<'s> DerefMut for Set<'s> {
+	fn deref_mut(&mut self) -> &mut Self::Target {
+		&mut self.set
+	}
+}
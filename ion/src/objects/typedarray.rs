@@ -4,11 +4,19 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/.
  */
 
+use std::marker::PhantomData;
 use std::ops::Deref;
+use std::{ptr, slice};
 
-use mozjs::typedarray::CreateWith;
+use mozjs::jsapi::{
+	GetSharedArrayBufferByteLength, GetSharedArrayBufferLengthAndData, IsArrayBufferObject, IsDetachedArrayBufferObject, IsSharedArrayBufferObject,
+	JS_GetArrayBufferViewBuffer, JSObject, NewSharedArrayBuffer,
+};
+use mozjs::typedarray::{
+	ArrayBufferU8, ArrayBufferViewU8, ClampedU8, CreateWith, Float32, Float64, Int16, Int32, Int8, TypedArrayElement, Uint16, Uint32, Uint8,
+};
 
-use crate::{Context, Error, Object, Result, Value};
+use crate::{Context, Error, Local, Object, Result, Value};
 use crate::conversions::ToValue;
 use crate::exception::ThrowException;
 
@@ -64,3 +72,189 @@ impl_typedarray_wrapper!(Float32Array, f32);
 impl_typedarray_wrapper!(Float64Array, f64);
 impl_typedarray_wrapper!(Uint8ClampedArray, u8);
 impl_typedarray_wrapper!(ArrayBuffer, u8);
+
+/// Represents a view over the contents of a typed array or [ArrayBuffer] in the JavaScript Runtime.
+/// Unlike the owned wrappers above, the contents are not copied.
+///
+/// Refer to [MDN](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/TypedArray) for more details.
+#[derive(Debug)]
+pub struct TypedArrayView<'a, T: TypedArrayElement> {
+	object: Local<'a, *mut JSObject>,
+	_element: PhantomData<T>,
+}
+
+impl<'a, T: TypedArrayElement> TypedArrayView<'a, T> {
+	/// Creates a [TypedArrayView] from an object.
+	///
+	/// Returns [None] if the object is not a typed array with elements of type `T`.
+	pub fn from(object: Local<'a, *mut JSObject>) -> Option<TypedArrayView<'a, T>> {
+		if unsafe { T::unwrap_array(object.get()).is_null() } {
+			None
+		} else {
+			Some(TypedArrayView { object, _element: PhantomData })
+		}
+	}
+
+	/// Returns the number of elements in the view.
+	pub fn len(&self) -> usize {
+		unsafe { T::length_and_data(self.object.get()).1 }
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+
+	/// Checks if the underlying [ArrayBuffer] has been detached.
+	/// Detached buffers have no contents and a length of `0`.
+	pub fn is_detached(&self, cx: &Context) -> bool {
+		unsafe {
+			let object = T::unwrap_array(self.object.get());
+			if IsArrayBufferObject(object) {
+				return IsDetachedArrayBufferObject(object);
+			}
+
+			rooted!(in(cx.as_ptr()) let view = object);
+			let mut shared = false;
+			let buffer = JS_GetArrayBufferViewBuffer(cx.as_ptr(), view.handle().into(), &mut shared);
+			!buffer.is_null() && IsDetachedArrayBufferObject(buffer)
+		}
+	}
+
+	/// Returns the contents of the view as a slice.
+	///
+	/// ### Safety
+	/// The slice must not be used after a garbage collection or after any script runs,
+	/// as either may move or detach the underlying buffer.
+	pub unsafe fn as_slice(&self) -> &[T::Element] {
+		let (data, length) = unsafe { T::length_and_data(self.object.get()) };
+		if data.is_null() {
+			&[]
+		} else {
+			unsafe { slice::from_raw_parts(data, length) }
+		}
+	}
+
+	/// Returns the contents of the view as a mutable slice.
+	///
+	/// ### Safety
+	/// See [TypedArrayView::as_slice].
+	pub unsafe fn as_mut_slice(&mut self) -> &mut [T::Element] {
+		let (data, length) = unsafe { T::length_and_data(self.object.get()) };
+		if data.is_null() {
+			&mut []
+		} else {
+			unsafe { slice::from_raw_parts_mut(data, length) }
+		}
+	}
+
+	/// Copies the contents of the view into a [Vec].
+	pub fn to_vec(&self) -> Vec<T::Element>
+	where
+		T::Element: Clone,
+	{
+		unsafe { self.as_slice().to_vec() }
+	}
+
+	pub fn into_local(self) -> Local<'a, *mut JSObject> {
+		self.object
+	}
+}
+
+impl<'a, T: TypedArrayElement> Deref for TypedArrayView<'a, T> {
+	type Target = Local<'a, *mut JSObject>;
+
+	fn deref(&self) -> &Self::Target {
+		&self.object
+	}
+}
+
+pub type Uint8ArrayView<'a> = TypedArrayView<'a, Uint8>;
+pub type Uint16ArrayView<'a> = TypedArrayView<'a, Uint16>;
+pub type Uint32ArrayView<'a> = TypedArrayView<'a, Uint32>;
+pub type Int8ArrayView<'a> = TypedArrayView<'a, Int8>;
+pub type Int16ArrayView<'a> = TypedArrayView<'a, Int16>;
+pub type Int32ArrayView<'a> = TypedArrayView<'a, Int32>;
+pub type Float32ArrayView<'a> = TypedArrayView<'a, Float32>;
+pub type Float64ArrayView<'a> = TypedArrayView<'a, Float64>;
+pub type Uint8ClampedArrayView<'a> = TypedArrayView<'a, ClampedU8>;
+pub type ArrayBufferView<'a> = TypedArrayView<'a, ArrayBufferViewU8>;
+pub type ArrayBufferRef<'a> = TypedArrayView<'a, ArrayBufferU8>;
+
+/// Represents a [SharedArrayBuffer] in the JavaScript Runtime.
+/// The contents of a [SharedArrayBuffer] can be read and written concurrently by other agents through `Atomics`.
+///
+/// Refer to [MDN](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/SharedArrayBuffer) for more details.
+#[derive(Debug)]
+pub struct SharedArrayBuffer<'a> {
+	buffer: Local<'a, *mut JSObject>,
+}
+
+impl<'a> SharedArrayBuffer<'a> {
+	/// Creates a new zero-filled [SharedArrayBuffer] with the given length in bytes.
+	///
+	/// Returns [None] if shared memory is disabled in the current realm, or if allocation fails.
+	pub fn new(cx: &'a Context, length: usize) -> Option<SharedArrayBuffer<'a>> {
+		let buffer = unsafe { NewSharedArrayBuffer(cx.as_ptr(), length) };
+		if buffer.is_null() {
+			None
+		} else {
+			Some(SharedArrayBuffer { buffer: cx.root_object(buffer) })
+		}
+	}
+
+	/// Creates a [SharedArrayBuffer] from an object.
+	///
+	/// Returns [None] if the object is not a shared array buffer.
+	pub fn from(object: Local<'a, *mut JSObject>) -> Option<SharedArrayBuffer<'a>> {
+		if SharedArrayBuffer::is_shared_array_buffer(&object) {
+			Some(SharedArrayBuffer { buffer: object })
+		} else {
+			None
+		}
+	}
+
+	/// Returns the length of the buffer in bytes.
+	pub fn len(&self) -> usize {
+		unsafe { GetSharedArrayBufferByteLength(self.buffer.get()) }
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+
+	/// Returns the contents of the buffer as a slice.
+	///
+	/// ### Safety
+	/// The contents may be modified concurrently by other agents, and must only be accessed with atomic operations
+	/// if the buffer has been shared.
+	pub unsafe fn as_slice(&self) -> &[u8] {
+		let mut length = 0;
+		let mut shared = false;
+		let mut data = ptr::null_mut();
+		unsafe {
+			GetSharedArrayBufferLengthAndData(self.buffer.get(), &mut length, &mut shared, &mut data);
+			if data.is_null() {
+				&[]
+			} else {
+				slice::from_raw_parts(data, length)
+			}
+		}
+	}
+
+	/// Checks if an object is a shared array buffer.
+	pub fn is_shared_array_buffer(object: &Local<*mut JSObject>) -> bool {
+		unsafe { IsSharedArrayBufferObject(object.get()) }
+	}
+
+	pub fn into_local(self) -> Local<'a, *mut JSObject> {
+		self.buffer
+	}
+}
+
+impl<'a> Deref for SharedArrayBuffer<'a> {
+	type Target = Local<'a, *mut JSObject>;
+
+	fn deref(&self) -> &Self::Target {
+		&self.buffer
+	}
+}
@@ -0,0 +1,359 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::ops::Deref;
+
+use mozjs::jsapi::{Construct1, HandleValueArray, JS_HasInstance, JS_NewUint8ArrayWithBuffer, JSObject};
+
+use crate::{Context, ErrorReport, Function, Local, Object, Value};
+use crate::conversions::{ConversionBehavior, FromValue, ToValue};
+use crate::typedarray::{ArrayBufferRef, SharedArrayBuffer, Uint8Array, Uint8ArrayView};
+
+/// Returns a property of the `WebAssembly` namespace, such as a constructor.
+fn namespace_property<'cx>(cx: &'cx Context, name: &str) -> Option<Object<'cx>> {
+	let namespace = Object::global(cx)
+		.get(cx, "WebAssembly")
+		.filter(|namespace| namespace.handle().is_object())?;
+	let property = namespace.to_object(cx).get(cx, name).filter(|property| property.handle().is_object())?;
+	Some(property.to_object(cx))
+}
+
+/// Constructs an object with a constructor of the `WebAssembly` namespace.
+/// Returns [Err] with [None] if `WebAssembly` is unavailable.
+fn construct<'cx>(cx: &'cx Context, name: &str, args: &[Value]) -> Result<Object<'cx>, Option<ErrorReport>> {
+	let constructor = namespace_property(cx, name).ok_or(None)?;
+	let args: Vec<_> = args.iter().map(|arg| arg.get()).collect();
+	let args = unsafe { HandleValueArray::from_rooted_slice(&args) };
+
+	let mut object = Object::null(cx);
+	if unsafe { Construct1(cx.as_ptr(), constructor.as_value(cx).handle().into(), &args, object.handle_mut().into()) } {
+		Ok(object)
+	} else {
+		Err(ErrorReport::new_with_exception_stack(cx))
+	}
+}
+
+fn is_instance(cx: &Context, object: &Local<*mut JSObject>, name: &str) -> bool {
+	let Some(constructor) = namespace_property(cx, name) else {
+		return false;
+	};
+	let value = Object::from(cx.root_object(object.get())).as_value(cx);
+	let mut instance = false;
+	unsafe { JS_HasInstance(cx.as_ptr(), constructor.handle().into(), value.handle().into(), &mut instance) && instance }
+}
+
+/// Represents the kind of an import or export of a [WasmModule].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WasmExternKind {
+	Function,
+	Table,
+	Memory,
+	Global,
+	Tag,
+}
+
+impl WasmExternKind {
+	fn parse(kind: &str) -> Option<WasmExternKind> {
+		match kind {
+			"function" => Some(WasmExternKind::Function),
+			"table" => Some(WasmExternKind::Table),
+			"memory" => Some(WasmExternKind::Memory),
+			"global" => Some(WasmExternKind::Global),
+			"tag" => Some(WasmExternKind::Tag),
+			_ => None,
+		}
+	}
+}
+
+/// Describes an import or export of a [WasmModule].
+/// The `module` is only present for imports.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WasmDescriptor {
+	pub module: Option<String>,
+	pub name: String,
+	pub kind: WasmExternKind,
+}
+
+impl<'cx> FromValue<'cx> for WasmDescriptor {
+	type Config = ();
+
+	fn from_value(cx: &'cx Context, value: &Value, _: bool, _: ()) -> crate::Result<WasmDescriptor> {
+		let object = Object::from_value(cx, value, true, ())?;
+		let module = object.get_as(cx, "module", true, ());
+		let name = object.get_as(cx, "name", true, ()).unwrap_or_default();
+		let kind = object
+			.get_as::<_, String>(cx, "kind", true, ())
+			.and_then(|kind| WasmExternKind::parse(&kind));
+		match kind {
+			Some(kind) => Ok(WasmDescriptor { module, name, kind }),
+			None => Err(crate::Error::new("Invalid WebAssembly Descriptor", crate::ErrorKind::Type)),
+		}
+	}
+}
+
+/// Represents a compiled `WebAssembly.Module` in the JavaScript Runtime.
+/// A [WasmModule] is stateless, and can be instantiated any number of times.
+///
+/// Refer to [MDN](https://developer.mozilla.org/en-US/docs/WebAssembly/JavaScript_interface/Module) for more details.
+#[derive(Debug)]
+pub struct WasmModule<'m> {
+	module: Local<'m, *mut JSObject>,
+}
+
+impl<'m> WasmModule<'m> {
+	/// Synchronously compiles a [WasmModule] from the bytes of a WebAssembly binary.
+	/// Returns [Err] if the binary is invalid, or with [None] if `WebAssembly` is unavailable.
+	pub fn compile(cx: &'m Context, bytes: &[u8]) -> Result<WasmModule<'m>, Option<ErrorReport>> {
+		let bytes = Uint8Array::from(bytes).to_object(cx).map_err(|_| None)?;
+		let module = construct(cx, "Module", &[bytes.as_value(cx)])?;
+		Ok(WasmModule { module: module.into_local() })
+	}
+
+	/// Creates a [WasmModule] from an object.
+	/// Returns [None] if it is not a `WebAssembly.Module`.
+	pub fn from(cx: &Context, object: Local<'m, *mut JSObject>) -> Option<WasmModule<'m>> {
+		is_instance(cx, &object, "Module").then_some(WasmModule { module: object })
+	}
+
+	/// Returns the descriptors of the imports required to instantiate the module.
+	pub fn imports(&self, cx: &Context) -> Vec<WasmDescriptor> {
+		self.descriptors(cx, "imports")
+	}
+
+	/// Returns the descriptors of the exports of each instance of the module.
+	pub fn exports(&self, cx: &Context) -> Vec<WasmDescriptor> {
+		self.descriptors(cx, "exports")
+	}
+
+	fn descriptors(&self, cx: &Context, name: &str) -> Vec<WasmDescriptor> {
+		let describe = namespace_property(cx, "Module")
+			.and_then(|constructor| constructor.get(cx, name))
+			.filter(|describe| describe.handle().is_object())
+			.and_then(|describe| Function::from_object(cx, &describe.to_object(cx).into_local()));
+		let Some(describe) = describe else {
+			return Vec::new();
+		};
+
+		let module = Object::from(cx.root_object(self.module.get())).as_value(cx);
+		describe
+			.call(cx, &Object::null(cx), &[module])
+			.ok()
+			.and_then(|descriptors| Vec::from_value(cx, &descriptors, true, ()).ok())
+			.unwrap_or_default()
+	}
+
+	/// Instantiates the module with an object of imports, keyed by module name and then import name.
+	pub fn instantiate<'cx>(&self, cx: &'cx Context, imports: Option<&Object>) -> Result<WasmInstance<'cx>, Option<ErrorReport>> {
+		WasmInstance::new(cx, self, imports)
+	}
+
+	pub fn into_local(self) -> Local<'m, *mut JSObject> {
+		self.module
+	}
+}
+
+impl<'m> Deref for WasmModule<'m> {
+	type Target = Local<'m, *mut JSObject>;
+
+	fn deref(&self) -> &Self::Target {
+		&self.module
+	}
+}
+
+/// Represents a `WebAssembly.Instance` in the JavaScript Runtime, which holds the state of an instantiated [WasmModule].
+///
+/// Refer to [MDN](https://developer.mozilla.org/en-US/docs/WebAssembly/JavaScript_interface/Instance) for more details.
+#[derive(Debug)]
+pub struct WasmInstance<'i> {
+	instance: Local<'i, *mut JSObject>,
+}
+
+impl<'i> WasmInstance<'i> {
+	/// Synchronously instantiates a [WasmModule].
+	/// Returns [Err] if linking the imports or running the start function fails.
+	pub fn new(cx: &'i Context, module: &WasmModule, imports: Option<&Object>) -> Result<WasmInstance<'i>, Option<ErrorReport>> {
+		let module = Object::from(cx.root_object(module.get())).as_value(cx);
+		let imports = imports.map(|imports| imports.as_value(cx)).unwrap_or_else(|| Value::undefined(cx));
+		let instance = construct(cx, "Instance", &[module, imports])?;
+		Ok(WasmInstance { instance: instance.into_local() })
+	}
+
+	/// Creates a [WasmInstance] from an object.
+	/// Returns [None] if it is not a `WebAssembly.Instance`.
+	pub fn from(cx: &Context, object: Local<'i, *mut JSObject>) -> Option<WasmInstance<'i>> {
+		is_instance(cx, &object, "Instance").then_some(WasmInstance { instance: object })
+	}
+
+	/// Returns the frozen object of the instance's exports.
+	pub fn exports<'cx>(&self, cx: &'cx Context) -> Object<'cx> {
+		let instance = Object::from(cx.root_object(self.instance.get()));
+		instance
+			.get(cx, "exports")
+			.map(|exports| exports.to_object(cx))
+			.unwrap_or_else(|| Object::new(cx))
+	}
+
+	/// Returns an exported function, or [None] if there is no function export with the given name.
+	pub fn function<'cx>(&self, cx: &'cx Context, name: &str) -> Option<Function<'cx>> {
+		let function = self.exports(cx).get(cx, name).filter(|function| function.handle().is_object())?;
+		Function::from_object(cx, &function.to_object(cx).into_local())
+	}
+
+	/// Calls an exported function with the given arguments.
+	/// Returns [Err] with [None] if there is no function export with the given name.
+	pub fn call<'cx>(&self, cx: &'cx Context, name: &str, args: &[Value]) -> Result<Value<'cx>, Option<ErrorReport>> {
+		let function = self.function(cx, name).ok_or(None)?;
+		function.call(cx, &Object::null(cx), args)
+	}
+
+	/// Returns an exported memory, or [None] if there is no memory export with the given name.
+	pub fn memory<'cx>(&self, cx: &'cx Context, name: &str) -> Option<WasmMemory<'cx>> {
+		let memory = self.exports(cx).get(cx, name).filter(|memory| memory.handle().is_object())?;
+		WasmMemory::from(cx, memory.to_object(cx).into_local())
+	}
+
+	pub fn into_local(self) -> Local<'i, *mut JSObject> {
+		self.instance
+	}
+}
+
+impl<'i> Deref for WasmInstance<'i> {
+	type Target = Local<'i, *mut JSObject>;
+
+	fn deref(&self) -> &Self::Target {
+		&self.instance
+	}
+}
+
+/// Represents a `WebAssembly.Memory` in the JavaScript Runtime, whose contents are measured in pages of 64 KiB.
+///
+/// The buffer of a memory is an [ArrayBuffer](crate::typedarray::ArrayBuffer), or a [SharedArrayBuffer] if the memory is shared.
+/// Growing a memory detaches its previous buffer, unless it is shared, so views should not be held across calls into WebAssembly.
+///
+/// Refer to [MDN](https://developer.mozilla.org/en-US/docs/WebAssembly/JavaScript_interface/Memory) for more details.
+#[derive(Debug)]
+pub struct WasmMemory<'m> {
+	memory: Local<'m, *mut JSObject>,
+}
+
+impl<'m> WasmMemory<'m> {
+	/// Size of a page of memory, in bytes.
+	pub const PAGE_SIZE: usize = 65536;
+
+	/// Creates a new [WasmMemory] with the given initial and maximum number of pages.
+	/// Shared memories require a maximum, and shared memory to be enabled in the realm.
+	pub fn new(cx: &'m Context, initial: u32, maximum: Option<u32>, shared: bool) -> Result<WasmMemory<'m>, Option<ErrorReport>> {
+		let mut descriptor = Object::new(cx);
+		descriptor.set_as(cx, "initial", &initial);
+		if let Some(maximum) = maximum {
+			descriptor.set_as(cx, "maximum", &maximum);
+		}
+		descriptor.set_as(cx, "shared", &shared);
+
+		let memory = construct(cx, "Memory", &[descriptor.as_value(cx)])?;
+		Ok(WasmMemory { memory: memory.into_local() })
+	}
+
+	/// Creates a [WasmMemory] from an object.
+	/// Returns [None] if it is not a `WebAssembly.Memory`.
+	pub fn from(cx: &Context, object: Local<'m, *mut JSObject>) -> Option<WasmMemory<'m>> {
+		is_instance(cx, &object, "Memory").then_some(WasmMemory { memory: object })
+	}
+
+	/// Returns the current buffer of the memory.
+	pub fn buffer<'cx>(&self, cx: &'cx Context) -> Object<'cx> {
+		let memory = Object::from(cx.root_object(self.memory.get()));
+		memory
+			.get(cx, "buffer")
+			.map(|buffer| buffer.to_object(cx))
+			.unwrap_or_else(|| Object::null(cx))
+	}
+
+	/// Checks if the memory is shared, in which case its buffer is a [SharedArrayBuffer].
+	pub fn is_shared(&self, cx: &Context) -> bool {
+		SharedArrayBuffer::is_shared_array_buffer(&self.buffer(cx).into_local())
+	}
+
+	/// Returns the current size of the memory, in bytes.
+	pub fn len(&self, cx: &Context) -> usize {
+		let buffer = self.buffer(cx).into_local();
+		if let Some(shared) = SharedArrayBuffer::from(cx.root_object(buffer.get())) {
+			shared.len()
+		} else {
+			ArrayBufferRef::from(buffer).map(|buffer| buffer.len()).unwrap_or_default()
+		}
+	}
+
+	pub fn is_empty(&self, cx: &Context) -> bool {
+		self.len(cx) == 0
+	}
+
+	/// Grows the memory by the given number of pages, and returns the previous number of pages.
+	pub fn grow(&self, cx: &Context, delta: u32) -> Result<u32, Option<ErrorReport>> {
+		let memory = Object::from(cx.root_object(self.memory.get()));
+		let grow = memory
+			.get(cx, "grow")
+			.filter(|grow| grow.handle().is_object())
+			.and_then(|grow| Function::from_object(cx, &grow.to_object(cx).into_local()))
+			.ok_or(None)?;
+
+		let previous = grow.call(cx, &memory, &[Value::u32(cx, delta)])?;
+		u32::from_value(cx, &previous, true, ConversionBehavior::Default).map_err(|_| None)
+	}
+
+	/// Creates a `Uint8Array` over the current buffer of the memory.
+	/// Writes to the view are visible to WebAssembly, and the view is detached if a non-shared memory grows.
+	pub fn view<'cx>(&self, cx: &'cx Context) -> Option<Uint8ArrayView<'cx>> {
+		let buffer = self.buffer(cx);
+		if buffer.handle().get().is_null() {
+			return None;
+		}
+		let view = unsafe { JS_NewUint8ArrayWithBuffer(cx.as_ptr(), buffer.handle().into(), 0, -1) };
+		if view.is_null() {
+			None
+		} else {
+			Uint8ArrayView::from(cx.root_object(view))
+		}
+	}
+
+	/// Copies `length` bytes of the memory, starting at `offset`.
+	/// Returns [None] if the range is out of bounds.
+	pub fn read(&self, cx: &Context, offset: usize, length: usize) -> Option<Vec<u8>> {
+		let view = self.view(cx)?;
+		let end = offset.checked_add(length)?;
+		unsafe { view.as_slice() }.get(offset..end).map(<[u8]>::to_vec)
+	}
+
+	/// Copies bytes into the memory, starting at `offset`.
+	/// Returns `false` if the range is out of bounds.
+	pub fn write(&self, cx: &Context, offset: usize, bytes: &[u8]) -> bool {
+		let Some(mut view) = self.view(cx) else {
+			return false;
+		};
+		let Some(end) = offset.checked_add(bytes.len()) else {
+			return false;
+		};
+		match unsafe { view.as_mut_slice() }.get_mut(offset..end) {
+			Some(slice) => {
+				slice.copy_from_slice(bytes);
+				true
+			}
+			None => false,
+		}
+	}
+
+	pub fn into_local(self) -> Local<'m, *mut JSObject> {
+		self.memory
+	}
+}
+
+impl<'m> Deref for WasmMemory<'m> {
+	type Target = Local<'m, *mut JSObject>;
+
+	fn deref(&self) -> &Self::Target {
+		&self.memory
+	}
+}
@@ -0,0 +1,59 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use mozjs::jsapi::{Construct1, HandleValueArray, JSObject};
+
+use crate::{Context, Function, Object, PersistentRooted};
+
+/// Represents a weak reference to an [Object], backed by a JavaScript `WeakRef`.
+///
+/// Unlike [Local](crate::Local), a [Weak] can be stored beyond a single [Context] borrow without keeping its target alive.
+/// The `WeakRef` itself stays rooted until the [Weak] is dropped.
+///
+/// Requires weak references to be enabled in the realm's creation options.
+/// Refer to [MDN](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/WeakRef) for more details.
+#[derive(Debug)]
+pub struct Weak {
+	weak_ref: PersistentRooted<*mut JSObject>,
+}
+
+impl Weak {
+	/// Creates a [Weak] reference to the given target.
+	/// Returns [None] if `WeakRef` is unavailable or could not be constructed.
+	pub fn new(cx: &Context, target: &Object) -> Option<Weak> {
+		let constructor = Object::global(cx)
+			.get(cx, "WeakRef")
+			.filter(|constructor| constructor.handle().is_object())?;
+
+		let args = [target.as_value(cx).get()];
+		let mut weak_ref = Object::null(cx);
+		let args = unsafe { HandleValueArray::from_rooted_slice(&args) };
+		if !unsafe { Construct1(cx.as_ptr(), constructor.handle().into(), &args, weak_ref.handle_mut().into()) } {
+			return None;
+		}
+
+		Some(Weak {
+			weak_ref: PersistentRooted::from_local(&weak_ref),
+		})
+	}
+
+	/// Returns the target of the [Weak] reference, or [None] if it has been collected.
+	///
+	/// Upgrading keeps the target alive until the end of the current job.
+	pub fn upgrade<'cx>(&self, cx: &'cx Context) -> Option<Object<'cx>> {
+		let weak_ref = Object::from(cx.root_object(self.weak_ref.get()));
+		let deref = weak_ref.get(cx, "deref").filter(|deref| deref.handle().is_object())?;
+		let deref = Function::from_object(cx, &deref.to_object(cx).into_local())?;
+
+		let target = deref.call(cx, &weak_ref, &[]).ok()?;
+		target.handle().is_object().then(|| target.to_object(cx))
+	}
+
+	/// Checks if the target of the [Weak] reference is still alive.
+	pub fn is_alive(&self, cx: &Context) -> bool {
+		self.upgrade(cx).is_some()
+	}
+}
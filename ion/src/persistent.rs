@@ -0,0 +1,86 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::fmt;
+use std::fmt::{Debug, Formatter};
+
+use mozjs::gc::{GCMethods, RootedTraceableSet, Traceable};
+use mozjs::jsapi::Heap;
+use mozjs_sys::jsgc::RootKind;
+
+use crate::Local;
+
+/// Represents a value that is rooted until it is dropped.
+///
+/// Unlike a [Local], a [PersistentRooted] is not tied to a [Context](crate::Context) borrow,
+/// so it can be stored in structures that live across turns of the event loop, such as timers and native callbacks.
+pub struct PersistentRooted<T: Copy + GCMethods + RootKind + 'static>
+where
+	Heap<T>: Default + Traceable,
+{
+	heap: Box<Heap<T>>,
+}
+
+impl<T: Copy + GCMethods + RootKind + 'static> PersistentRooted<T>
+where
+	Heap<T>: Default + Traceable,
+{
+	/// Creates a new [PersistentRooted] and registers it with the Garbage Collector.
+	pub fn new(value: T) -> PersistentRooted<T> {
+		let heap = Heap::boxed(value);
+		unsafe {
+			RootedTraceableSet::add(&*heap);
+		}
+		PersistentRooted { heap }
+	}
+
+	/// Creates a [PersistentRooted] from the current value of a [Local].
+	pub fn from_local(local: &Local<T>) -> PersistentRooted<T> {
+		PersistentRooted::new(local.get())
+	}
+
+	pub fn get(&self) -> T {
+		self.heap.get()
+	}
+
+	pub fn set(&mut self, value: T) {
+		self.heap.set(value);
+	}
+
+	/// Returns a [Local] which borrows from the [PersistentRooted].
+	pub fn local(&self) -> Local<T> {
+		unsafe { Local::from_heap(&self.heap) }
+	}
+}
+
+impl<T: Copy + GCMethods + RootKind + 'static> Drop for PersistentRooted<T>
+where
+	Heap<T>: Default + Traceable,
+{
+	fn drop(&mut self) {
+		unsafe {
+			RootedTraceableSet::remove(&*self.heap);
+		}
+	}
+}
+
+impl<T: Copy + GCMethods + RootKind + 'static> Clone for PersistentRooted<T>
+where
+	Heap<T>: Default + Traceable,
+{
+	fn clone(&self) -> PersistentRooted<T> {
+		PersistentRooted::new(self.get())
+	}
+}
+
+impl<T: Copy + Debug + GCMethods + RootKind + 'static> Debug for PersistentRooted<T>
+where
+	Heap<T>: Default + Traceable,
+{
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		f.debug_tuple("PersistentRooted").field(&self.get()).finish()
+	}
+}
@@ -6,10 +6,47 @@
 
 use std::path::Path;
 
-use mozjs::jsapi::{Compile, JS_ExecuteScript, JSScript};
+use mozjs::jsapi::{Compile, JS_ExecuteScript, JS_Utf8BufferIsCompilableUnit, JSScript};
 use mozjs::rust::{CompileOptionsWrapper, transform_u16_to_source_text};
 
-use crate::{Context, ErrorReport, Local, Value};
+use crate::{Context, ErrorReport, Local, Object, Value};
+
+/// Options used when compiling a [Script] or a [Module](crate::module::Module).
+#[derive(Clone, Debug)]
+pub struct CompileOptions {
+	/// Filename shown in errors and stack traces.
+	pub filename: String,
+	/// Number of lines which precede the source, such as when it is embedded in another file.
+	pub line_offset: u32,
+	/// Compiles the source in strict mode, as if it began with a `"use strict"` directive. Modules are always strict.
+	pub strict: bool,
+}
+
+impl CompileOptions {
+	pub fn new<S: Into<String>>(filename: S) -> CompileOptions {
+		CompileOptions {
+			filename: filename.into(),
+			line_offset: 0,
+			strict: false,
+		}
+	}
+
+	pub fn line_offset(self, line_offset: u32) -> CompileOptions {
+		CompileOptions { line_offset, ..self }
+	}
+
+	pub fn strict(self, strict: bool) -> CompileOptions {
+		CompileOptions { strict, ..self }
+	}
+
+	pub(crate) fn to_wrapper(&self, cx: &Context) -> CompileOptionsWrapper {
+		let options = unsafe { CompileOptionsWrapper::new(cx.as_ptr(), &self.filename, self.line_offset + 1) };
+		unsafe {
+			(*options.ptr)._base.forceStrictMode_ = self.strict;
+		}
+		options
+	}
+}
 
 #[derive(Debug)]
 pub struct Script<'cx> {
@@ -20,9 +57,15 @@ impl<'s> Script<'s> {
 	/// Compiles a script with a given filename and returns the compiled script.
 	/// Returns [Err] when script compilation fails.
 	pub fn compile<'cx>(cx: &'cx Context, path: &Path, script: &str) -> Result<Script<'cx>, ErrorReport> {
+		Script::compile_with_options(cx, &CompileOptions::new(path.to_string_lossy()), script)
+	}
+
+	/// Compiles a script with the given [options](CompileOptions) and returns the compiled script.
+	/// Returns [Err] when script compilation fails.
+	pub fn compile_with_options<'cx>(cx: &'cx Context, options: &CompileOptions, script: &str) -> Result<Script<'cx>, ErrorReport> {
 		let script: Vec<u16> = script.encode_utf16().collect();
 		let mut source = transform_u16_to_source_text(script.as_slice());
-		let options = unsafe { CompileOptionsWrapper::new(cx.as_ptr(), path.to_str().unwrap(), 1) };
+		let options = options.to_wrapper(cx);
 
 		let script = unsafe { Compile(cx.as_ptr(), options.ptr, &mut source) };
 
@@ -33,24 +76,32 @@ impl<'s> Script<'s> {
 		}
 	}
 
+	/// Checks if a script is a compilable unit, which it is not if its source ends before it is complete, such as within a block.
+	/// Scripts with other syntax errors are compilable units, so that their errors are reported when they are compiled.
+	pub fn is_compilable_unit(cx: &Context, script: &str) -> bool {
+		let global = Object::global(cx);
+		unsafe { JS_Utf8BufferIsCompilableUnit(cx.as_ptr(), global.handle().into(), script.as_ptr().cast(), script.len()) }
+	}
+
 	/// Evaluates a script and returns its return value.
-	/// Returns [Err] when an exception occurs during script evaluation.
-	pub fn evaluate<'cx>(&self, cx: &'cx Context) -> Result<Value<'cx>, ErrorReport> {
+	/// Returns [Err] when an exception occurs during script evaluation,
+	/// which has no [ErrorReport] if the error is uncatchable, such as the termination of the script.
+	pub fn evaluate<'cx>(&self, cx: &'cx Context) -> Result<Value<'cx>, Option<ErrorReport>> {
 		let mut rval = Value::undefined(cx);
 
 		if unsafe { JS_ExecuteScript(cx.as_ptr(), self.script.handle().into(), rval.handle_mut().into()) } {
 			Ok(rval)
 		} else {
-			Err(ErrorReport::new_with_exception_stack(cx).unwrap())
+			Err(ErrorReport::new_with_exception_stack(cx))
 		}
 	}
 
 	/// Compiles and evaluates a script with a given filename, and returns its return value.
-	/// Returns [Err] when script compilation fails or an exception occurs during script evaluation.
-	pub fn compile_and_evaluate<'cx>(cx: &'cx Context, path: &Path, script: &str) -> Result<Value<'cx>, ErrorReport> {
+	/// Returns [Err] when script compilation fails or an exception occurs during script evaluation, as with [Script::evaluate].
+	pub fn compile_and_evaluate<'cx>(cx: &'cx Context, path: &Path, script: &str) -> Result<Value<'cx>, Option<ErrorReport>> {
 		match Script::compile(cx, path, script) {
 			Ok(s) => s.evaluate(cx),
-			Err(e) => Err(e),
+			Err(e) => Err(Some(e)),
 		}
 	}
 }
@@ -9,7 +9,10 @@ use std::fmt::{Display, Formatter};
 use std::mem::MaybeUninit;
 
 use mozjs::conversions::jsstr_to_string;
-use mozjs::jsapi::{BuildStackString, CaptureCurrentStack, JS_StackCapture_AllFrames, JS_StackCapture_MaxFrames, JSObject, JSString, StackFormat};
+use mozjs::jsapi::{
+	CaptureCurrentStack, GetSavedFrameColumn, GetSavedFrameFunctionDisplayName, GetSavedFrameLine, GetSavedFrameParent, GetSavedFrameSource,
+	JS_StackCapture_AllFrames, JS_StackCapture_MaxFrames, JSObject, SavedFrameResult, SavedFrameSelfHosted,
+};
 #[cfg(feature = "sourcemap")]
 use sourcemap::SourceMap;
 
@@ -55,6 +58,53 @@ impl Location {
 }
 
 impl StackRecord {
+	/// Creates a [StackRecord] from a saved frame object.
+	/// Returns [None] if the frame cannot be accessed.
+	pub fn from_frame(cx: &Context, frame: &Object) -> Option<StackRecord> {
+		unsafe {
+			let handle = frame.handle();
+
+			let mut source = crate::String::from(cx.root_string(ptr::null_mut()));
+			let result = GetSavedFrameSource(
+				cx.as_ptr(),
+				ptr::null_mut(),
+				handle.into(),
+				source.handle_mut().into(),
+				SavedFrameSelfHosted::Exclude,
+			);
+			if result != SavedFrameResult::Ok {
+				return None;
+			}
+
+			let mut lineno = 0;
+			let mut column = 0;
+			GetSavedFrameLine(cx.as_ptr(), ptr::null_mut(), handle.into(), &mut lineno, SavedFrameSelfHosted::Exclude);
+			GetSavedFrameColumn(cx.as_ptr(), ptr::null_mut(), handle.into(), &mut column, SavedFrameSelfHosted::Exclude);
+
+			let mut function = crate::String::from(cx.root_string(ptr::null_mut()));
+			GetSavedFrameFunctionDisplayName(
+				cx.as_ptr(),
+				ptr::null_mut(),
+				handle.into(),
+				function.handle_mut().into(),
+				SavedFrameSelfHosted::Exclude,
+			);
+
+			let file = if source.handle().is_null() {
+				String::new()
+			} else {
+				let file = jsstr_to_string(cx.as_ptr(), source.handle().get());
+				String::from(normalise_path(&file).to_str().unwrap())
+			};
+			let function = (!function.handle().is_null()).then(|| jsstr_to_string(cx.as_ptr(), function.handle().get()));
+
+			Some(StackRecord {
+				function,
+				location: Location { file, lineno, column },
+			})
+		}
+	}
+
 	/// Transforms a [StackRecord], according to the given [SourceMap].
 	#[cfg(feature = "sourcemap")]
 	pub fn transform_with_sourcemap(&mut self, sourcemap: &SourceMap) {
@@ -97,12 +147,34 @@ impl Stack {
 		Stack { records, object: None }
 	}
 
-	/// Creates a [Stack] from an object.
+	/// Creates a [Stack] from a saved frame object, by walking the chain of its parent frames.
+	/// Self-hosted frames are excluded from the records.
 	pub fn from_object(cx: &Context, stack: *mut JSObject) -> Option<Stack> {
-		stack_to_string(cx, stack).as_deref().map(Stack::from_string).map(|mut s| {
-			s.object = Some(stack);
-			s
-		})
+		if stack.is_null() {
+			return None;
+		}
+
+		let mut records = Vec::new();
+		let mut frame = Object::from(cx.root_object(stack));
+		while let Some(record) = StackRecord::from_frame(cx, &frame) {
+			records.push(record);
+
+			let mut parent = Object::null(cx);
+			let result = unsafe {
+				GetSavedFrameParent(
+					cx.as_ptr(),
+					ptr::null_mut(),
+					frame.handle().into(),
+					parent.handle_mut().into(),
+					SavedFrameSelfHosted::Exclude,
+				)
+			};
+			if result != SavedFrameResult::Ok || parent.handle().is_null() {
+				break;
+			}
+			frame = parent;
+		}
+		Some(Stack { records, object: Some(stack) })
 	}
 
 	/// Captures the [Stack] of the [Context].
@@ -110,6 +182,11 @@ impl Stack {
 		capture_stack(cx, None).and_then(|stack| Stack::from_object(cx, stack))
 	}
 
+	/// Captures the [Stack] of the [Context], with at most `max_frames` [records](StackRecord).
+	pub fn from_capture_with_max_frames(cx: &Context, max_frames: u32) -> Option<Stack> {
+		capture_stack(cx, Some(max_frames)).and_then(|stack| Stack::from_object(cx, stack))
+	}
+
 	/// Returns `true` if the stack contains no [records](StackRecord)
 	pub fn is_empty(&self) -> bool {
 		self.records.is_empty()
@@ -159,23 +236,3 @@ fn capture_stack(cx: &Context, max_frames: Option<u32>) -> Option<*mut JSObject>
 		}
 	}
 }
-
-fn stack_to_string(cx: &Context, stack: *mut JSObject) -> Option<String> {
-	unsafe {
-		rooted!(in(cx.as_ptr()) let stack = stack);
-		rooted!(in(cx.as_ptr()) let mut string: *mut JSString);
-
-		if BuildStackString(
-			cx.as_ptr(),
-			ptr::null_mut(),
-			stack.handle().into(),
-			string.handle_mut().into(),
-			0,
-			StackFormat::SpiderMonkey,
-		) {
-			Some(jsstr_to_string(cx.as_ptr(), string.get()))
-		} else {
-			None
-		}
-	}
-}
@@ -0,0 +1,186 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::ffi::c_void;
+use std::marker::PhantomData;
+use std::path::Path;
+use std::ptr;
+use std::slice;
+
+use futures::channel::oneshot;
+use mozjs::jsapi::{
+	CanCompileOffThread, CompileGlobalScriptToStencil, CompileModuleScriptToStencil, CompileModuleToStencilOffThread, DecodeOptions, DecodeStencil,
+	EncodeStencil, FinishOffThreadStencil, InstantiateGlobalStencil, InstantiateModuleStencil, InstantiateOptions, js_free, OffThreadToken,
+	StencilRelease, TranscodeBuffer, TranscodeRange, TranscodeResult,
+};
+use mozjs::jsapi::mozilla::RangedPtr;
+use mozjs::jsapi::Stencil as JSStencil;
+use mozjs::rust::{CompileOptionsWrapper, transform_u16_to_source_text};
+
+use crate::{Context, ErrorReport, Object};
+use crate::script::Script;
+
+/// Line number of the first line of compiled sources.
+const FIRST_LINE: u32 = 1;
+
+/// Represents the result of parsing a script or module, before it is instantiated in a realm.
+///
+/// Stencils can be encoded into bytes and decoded later, which allows compiled code to be cached without re-parsing its source.
+/// Encoded stencils are only valid for the engine build that produced them.
+#[derive(Debug)]
+pub struct Stencil {
+	stencil: *mut JSStencil,
+}
+
+impl Stencil {
+	/// Describes the compile options of a script or module at `path`, which are applied when it is compiled into a [Stencil].
+	/// Cached stencils are only valid for the same compile options.
+	pub fn options_key(path: &Path) -> String {
+		format!("file={};line={}", path.display(), FIRST_LINE)
+	}
+
+	/// Compiles a script into a [Stencil].
+	/// Returns [Err] when script compilation fails.
+	pub fn compile_script(cx: &Context, path: &Path, script: &str) -> Result<Stencil, ErrorReport> {
+		let script: Vec<u16> = script.encode_utf16().collect();
+		let mut source = transform_u16_to_source_text(script.as_slice());
+		let options = unsafe { CompileOptionsWrapper::new(cx.as_ptr(), &path.to_string_lossy(), FIRST_LINE) };
+
+		let stencil = unsafe { CompileGlobalScriptToStencil(cx.as_ptr(), options.ptr.cast_const().cast(), &mut source) };
+		Stencil::from_raw(cx, stencil.mRawPtr)
+	}
+
+	/// Compiles a module into a [Stencil].
+	/// Returns [Err] when module compilation fails.
+	pub fn compile_module(cx: &Context, path: &Path, script: &str) -> Result<Stencil, ErrorReport> {
+		let script: Vec<u16> = script.encode_utf16().collect();
+		let mut source = transform_u16_to_source_text(script.as_slice());
+		let options = unsafe { CompileOptionsWrapper::new(cx.as_ptr(), &path.to_string_lossy(), FIRST_LINE) };
+
+		let stencil = unsafe { CompileModuleScriptToStencil(cx.as_ptr(), options.ptr.cast_const().cast(), &mut source) };
+		Stencil::from_raw(cx, stencil.mRawPtr)
+	}
+
+	/// Compiles a module into a [Stencil] on a helper thread, allowing other futures to make progress in the meantime.
+	/// Falls back to compiling on the current thread if the engine decides the module is too small to benefit.
+	///
+	/// The source is owned by the helper thread until it finishes, so the future can be dropped before it completes.
+	pub async fn compile_module_off_thread(cx: &Context, path: &Path, script: &str) -> Result<Stencil, ErrorReport> {
+		let (sender, receiver) = oneshot::channel::<()>();
+		let compilation = Box::new(OffThreadCompilation {
+			source: script.encode_utf16().collect(),
+			sender,
+		});
+		let mut source_text = transform_u16_to_source_text(compilation.source.as_slice());
+		let options = unsafe { CompileOptionsWrapper::new(cx.as_ptr(), &path.to_string_lossy(), FIRST_LINE) };
+
+		if !unsafe { CanCompileOffThread(cx.as_ptr(), options.ptr.cast_const().cast(), compilation.source.len()) } {
+			return Stencil::compile_module(cx, path, script);
+		}
+
+		let compilation = Box::into_raw(compilation);
+		let token = unsafe {
+			CompileModuleToStencilOffThread(
+				cx.as_ptr(),
+				options.ptr.cast_const().cast(),
+				&mut source_text,
+				Some(off_thread_callback),
+				compilation.cast(),
+			)
+		};
+		if token.is_null() {
+			let _ = unsafe { Box::from_raw(compilation) };
+			return Stencil::compile_module(cx, path, script);
+		}
+
+		let _ = receiver.await;
+		let stencil = unsafe { FinishOffThreadStencil(cx.as_ptr(), token, ptr::null_mut()) };
+		Stencil::from_raw(cx, stencil.mRawPtr)
+	}
+
+	/// Decodes a [Stencil] previously encoded with [Stencil::encode].
+	/// Returns [None] if the bytes are malformed or were encoded by a different engine build.
+	pub fn decode(cx: &Context, bytes: &[u8]) -> Option<Stencil> {
+		let options = DecodeOptions::default();
+		let range = transcode_range(bytes);
+
+		let mut stencil = ptr::null_mut();
+		let result = unsafe { DecodeStencil(cx.as_ptr(), &options, &range, &mut stencil) };
+		(result == TranscodeResult::Ok && !stencil.is_null()).then_some(Stencil { stencil })
+	}
+
+	/// Encodes the [Stencil] into bytes, which can be stored and later decoded with [Stencil::decode].
+	pub fn encode(&self, cx: &Context) -> Option<Vec<u8>> {
+		let mut buffer = TranscodeBuffer::default();
+		let result = unsafe { EncodeStencil(cx.as_ptr(), self.stencil, &mut buffer) };
+
+		let bytes = (result == TranscodeResult::Ok).then(|| unsafe { slice::from_raw_parts(buffer.mBegin, buffer.mLength).to_vec() });
+		unsafe {
+			js_free(buffer.mBegin.cast());
+		}
+		bytes
+	}
+
+	/// Instantiates the [Stencil] as a [Script] in the current realm.
+	pub fn to_script<'cx>(&self, cx: &'cx Context) -> Result<Script<'cx>, ErrorReport> {
+		let options = InstantiateOptions::default();
+		let script = unsafe { InstantiateGlobalStencil(cx.as_ptr(), &options, self.stencil, ptr::null_mut()) };
+		if !script.is_null() {
+			Ok(Script::from(cx.root_script(script)))
+		} else {
+			Err(ErrorReport::new_with_exception_stack(cx).unwrap())
+		}
+	}
+
+	/// Instantiates the [Stencil] as a module object in the current realm.
+	pub fn to_module<'cx>(&self, cx: &'cx Context) -> Result<Object<'cx>, ErrorReport> {
+		let options = InstantiateOptions::default();
+		let module = unsafe { InstantiateModuleStencil(cx.as_ptr(), &options, self.stencil, ptr::null_mut()) };
+		if !module.is_null() {
+			Ok(Object::from(cx.root_object(module)))
+		} else {
+			Err(ErrorReport::new_with_exception_stack(cx).unwrap())
+		}
+	}
+
+	fn from_raw(cx: &Context, stencil: *mut JSStencil) -> Result<Stencil, ErrorReport> {
+		if !stencil.is_null() {
+			Ok(Stencil { stencil })
+		} else {
+			Err(ErrorReport::new_with_exception_stack(cx).unwrap())
+		}
+	}
+}
+
+/// Holds the source of a module compiled on a helper thread, which is freed by the callback once the helper thread has finished with it.
+struct OffThreadCompilation {
+	source: Vec<u16>,
+	sender: oneshot::Sender<()>,
+}
+
+unsafe extern "C" fn off_thread_callback(_: *mut OffThreadToken, data: *mut c_void) {
+	let compilation = unsafe { Box::from_raw(data.cast::<OffThreadCompilation>()) };
+	let _ = compilation.sender.send(());
+}
+
+fn transcode_range(bytes: &[u8]) -> TranscodeRange {
+	let range = bytes.as_ptr_range();
+	TranscodeRange {
+		mStart: RangedPtr {
+			mPtr: range.start,
+			_phantom_0: PhantomData,
+		},
+		mEnd: RangedPtr { mPtr: range.end, _phantom_0: PhantomData },
+	}
+}
+
+impl Drop for Stencil {
+	fn drop(&mut self) {
+		unsafe {
+			StencilRelease(self.stencil);
+		}
+	}
+}
@@ -152,6 +152,21 @@ impl<'s> Symbol<'s> {
 		Symbol { sym: cx.root_symbol(symbol) }
 	}
 
+	/// Returns `Symbol.iterator`.
+	pub fn iterator(cx: &Context) -> Symbol {
+		Symbol::well_known(cx, WellKnownSymbolCode::Iterator)
+	}
+
+	/// Returns `Symbol.asyncIterator`.
+	pub fn async_iterator(cx: &Context) -> Symbol {
+		Symbol::well_known(cx, WellKnownSymbolCode::AsyncIterator)
+	}
+
+	/// Returns `Symbol.toStringTag`.
+	pub fn to_string_tag(cx: &Context) -> Symbol {
+		Symbol::well_known(cx, WellKnownSymbolCode::ToStringTag)
+	}
+
 	/// Returns the identifying code of a [Symbol].
 	pub fn code(&self) -> SymbolCode {
 		unsafe { GetSymbolCode(self.sym.handle().into()).into() }
@@ -168,6 +183,16 @@ impl<'s> Symbol<'s> {
 			None
 		}
 	}
+
+	/// Returns the key of a [Symbol] in the symbol registry, equivalent to `Symbol.keyFor`.
+	/// Returns [None] if the symbol was not created with [Symbol::for_key].
+	pub fn registry_key(&self, cx: &Context) -> Option<String> {
+		if self.code() == SymbolCode::InSymbolRegistry {
+			self.description(cx)
+		} else {
+			None
+		}
+	}
 }
 
 impl<'o> From<Local<'o, *mut JSSymbol>> for Symbol<'o> {
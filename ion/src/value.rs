@@ -6,7 +6,7 @@
 
 use std::ops::{Deref, DerefMut};
 
-use mozjs::jsapi::SameValue;
+use mozjs::jsapi::{LooselyEqual, SameValue, StrictlyEqual};
 use mozjs::jsval::{BigIntValue, BooleanValue, DoubleValue, Int32Value, JSVal, NullValue, ObjectValue, SymbolValue, UInt32Value, UndefinedValue};
 
 use crate::{Array, Context, Local, Object, Symbol};
@@ -90,6 +90,19 @@ impl<'v> Value<'v> {
 		let mut same = false;
 		unsafe { SameValue(cx.as_ptr(), self.handle().into(), other.handle().into(), &mut same) && same }
 	}
+
+	/// Compares two values for equality using the [IsStrictlyEqual algorithm](https://tc39.es/ecma262/multipage/abstract-operations.html#sec-isstrictlyequal), as with `===`.
+	pub fn is_strictly_equal(&self, cx: &Context, other: &Value) -> bool {
+		let mut equal = false;
+		unsafe { StrictlyEqual(cx.as_ptr(), self.handle().into(), other.handle().into(), &mut equal) && equal }
+	}
+
+	/// Compares two values for equality using the [IsLooselyEqual algorithm](https://tc39.es/ecma262/multipage/abstract-operations.html#sec-islooselyequal), as with `==`.
+	/// Objects may be converted to primitives, which can throw an exception.
+	pub fn is_loosely_equal(&self, cx: &Context, other: &Value) -> bool {
+		let mut equal = false;
+		unsafe { LooselyEqual(cx.as_ptr(), self.handle().into(), other.handle().into(), &mut equal) && equal }
+	}
 }
 
 impl<'v> From<Local<'v, JSVal>> for Value<'v> {
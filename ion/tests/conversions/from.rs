@@ -124,6 +124,20 @@ fn test_integers(cx: &Context) {
 	assert!(result.is_err());
 	let result = u32::from_value(cx, &value, false, ConversionBehavior::EnforceRange);
 	assert!(result.is_err());
+	let value = u64::MAX.as_value(cx);
+	assert!(value.handle().is_bigint());
+	let result = u64::from_value(cx, &value, true, ConversionBehavior::EnforceRange);
+	assert_eq!(result.unwrap(), u64::MAX);
+	let result = i64::from_value(cx, &value, true, ConversionBehavior::EnforceRange);
+	assert!(result.is_err());
+
+	let value = i128::MIN.as_value(cx);
+	let result = i128::from_value(cx, &value, true, ());
+	assert_eq!(result.unwrap(), i128::MIN);
+
+	let value = Value::f64(cx, 2_f64.powi(60));
+	let result = i64::from_value(cx, &value, true, ConversionBehavior::Default);
+	assert!(result.is_err());
 }
 
 fn test_strings(cx: &Context) {
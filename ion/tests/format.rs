@@ -0,0 +1,43 @@
+use std::path::Path;
+
+use mozjs::jsapi::JSAutoRealm;
+use mozjs::rust::{JSEngine, Runtime};
+
+use ion::Context;
+use ion::format::{Config, format_value};
+use ion::objects::default_new_global;
+use ion::script::Script;
+
+#[test]
+fn format() {
+	colored::control::set_override(false);
+
+	let engine = JSEngine::init().unwrap();
+	let runtime = Runtime::new(engine.handle());
+
+	let cx = &Context::from_runtime(&runtime);
+	let global = default_new_global(cx);
+	let _realm = JSAutoRealm::new(runtime.cx(), global.handle().get());
+
+	let circular = Script::compile_and_evaluate(
+		cx,
+		Path::new("circular.js"),
+		"const circular = { b: {} }; circular.b.a = circular; circular",
+	)
+	.unwrap();
+	let string = format_value(cx, Config::default().multiline(false), &circular);
+	assert_eq!(r#"{ "b": { "a": [Circular] } }"#, string);
+
+	let nested = Script::compile_and_evaluate(cx, Path::new("nested.js"), "({ a: { b: { c: [1] } } })").unwrap();
+	assert_eq!(
+		r#"{ "a": { "b": [Object] } }"#,
+		format_value(cx, Config::default().multiline(false).max_depth(2), &nested)
+	);
+	assert_eq!(
+		r#"{ "a": { "b": { "c": [ 1 ] } } }"#,
+		format_value(cx, Config::default().multiline(false), &nested)
+	);
+
+	let repeated = Script::compile_and_evaluate(cx, Path::new("repeated.js"), "const empty = {}; [empty, empty]").unwrap();
+	assert_eq!("[ {}, {} ]", format_value(cx, Config::default().multiline(false), &repeated));
+}
@@ -1,3 +1,5 @@
+use std::time::{Duration, SystemTime};
+
 use chrono::{TimeZone, Utc};
 use mozjs::jsapi::JSAutoRealm;
 use mozjs::rust::{JSEngine, Runtime};
@@ -29,4 +31,9 @@ fn date() {
 	assert_eq!(Some(Utc.timestamp_millis_opt(EPOCH).unwrap()), epoch.to_date(cx));
 	assert_eq!(Some(Utc.timestamp_millis_opt(POST_EPOCH).unwrap()), post_epoch.to_date(cx));
 	assert_eq!(Some(Utc.timestamp_millis_opt(PRE_EPOCH).unwrap()), pre_epoch.to_date(cx));
+
+	let system_time = SystemTime::UNIX_EPOCH + Duration::from_millis(POST_EPOCH as u64);
+	let system_date = Date::from_system_time(cx, system_time);
+	assert!(system_date.is_valid(cx));
+	assert_eq!(Some(system_time), system_date.to_system_time(cx));
 }
@@ -0,0 +1,38 @@
+use mozjs::jsapi::JSAutoRealm;
+use mozjs::rust::{JSEngine, Runtime};
+
+use ion::{ClassDefinition, Context, Function, Iterator, Value};
+use ion::conversions::{ConversionBehavior, IntoValue};
+use ion::objects::default_new_global;
+
+#[test]
+fn iterator() {
+	let engine = JSEngine::init().unwrap();
+	let runtime = Runtime::new(engine.handle());
+
+	let cx = &Context::from_runtime(&runtime);
+	let mut global = default_new_global(cx);
+	let _realm = JSAutoRealm::new(runtime.cx(), global.handle().get());
+	assert!(Iterator::init_class(cx, &mut global).0);
+
+	let iterator = Iterator::new_lazy((1..=3).map(|i: i32| i * 2));
+	let mut value = Value::undefined(cx);
+	Box::new(iterator).into_value(cx, &mut value);
+	let iterator = value.to_object(cx);
+
+	let next = iterator.get(cx, "next").unwrap().to_object(cx).into_local();
+	let next = Function::from_object(cx, &next).unwrap();
+
+	for expected in [2, 4, 6] {
+		let result = next.call(cx, &iterator, &[]).unwrap().to_object(cx);
+		assert_eq!(
+			Some(expected),
+			result.get_as::<_, i32>(cx, "value", true, ConversionBehavior::EnforceRange)
+		);
+		assert_eq!(Some(false), result.get_as::<_, bool>(cx, "done", true, ()));
+	}
+
+	let result = next.call(cx, &iterator, &[]).unwrap().to_object(cx);
+	assert!(result.get(cx, "value").unwrap().handle().is_undefined());
+	assert_eq!(Some(true), result.get_as::<_, bool>(cx, "done", true, ()));
+}
@@ -0,0 +1,47 @@
+use mozjs::jsapi::JSAutoRealm;
+use mozjs::rust::{JSEngine, Runtime};
+
+use ion::{Context, Map, Value};
+use ion::conversions::FromValue;
+use ion::objects::default_new_global;
+
+#[test]
+fn map() {
+	let engine = JSEngine::init().unwrap();
+	let runtime = Runtime::new(engine.handle());
+
+	let cx = &Context::from_runtime(&runtime);
+	let global = default_new_global(cx);
+	let _realm = JSAutoRealm::new(runtime.cx(), global.handle().get());
+
+	let map = Map::new(cx);
+	assert_eq!(0, map.size(cx));
+
+	let key1 = Value::string(cx, "key1");
+	let key2 = Value::i32(cx, 2);
+	assert!(map.set(cx, &key1, &Value::bool(cx, true)));
+	assert!(map.set(cx, &key2, &Value::null(cx)));
+	assert_eq!(2, map.size(cx));
+
+	assert!(map.has(cx, &key1));
+	assert!(!map.has(cx, &Value::undefined(cx)));
+	let value = map.get(cx, &key1).unwrap();
+	assert!(bool::from_value(cx, &value, true, ()).unwrap());
+	assert!(map.get(cx, &Value::undefined(cx)).is_none());
+
+	let keys: Vec<_> = map.keys(cx).collect();
+	assert_eq!(2, keys.len());
+	assert!(keys[0].is_same(cx, &key1));
+	assert!(keys[1].is_same(cx, &key2));
+
+	for (key, value) in map.iter(cx) {
+		assert!(map.get(cx, &key).unwrap().is_same(cx, &value));
+	}
+
+	assert!(map.delete(cx, &key1));
+	assert!(!map.delete(cx, &key1));
+	assert_eq!(1, map.size(cx));
+
+	assert!(map.clear(cx));
+	assert_eq!(0, map.size(cx));
+}
@@ -1,7 +1,7 @@
 use mozjs::jsapi::JSAutoRealm;
 use mozjs::rust::{JSEngine, Runtime};
 
-use ion::{Context, Object, OwnedKey, Value};
+use ion::{Context, Object, OwnedKey, Symbol, Value};
 use ion::conversions::FromValue;
 use ion::flags::PropertyFlags;
 use ion::objects::default_new_global;
@@ -34,4 +34,33 @@ fn object() {
 	assert!(object.delete(cx, "key2"));
 	assert!(object.get(cx, "key1").is_none());
 	assert!(object.get(cx, "key2").is_some());
+
+	let symbol = Symbol::for_key(cx, "key3");
+	object.define(cx, &symbol, &Value::bool(cx, true), PropertyFlags::all());
+	object.define(cx, Symbol::to_string_tag(cx), &Value::string(cx, "Test"), PropertyFlags::all());
+
+	let value3 = object.get(cx, Symbol::for_key(cx, "key3")).unwrap();
+	assert!(bool::from_value(cx, &value3, true, ()).unwrap());
+	assert_eq!(Some(String::from("key3")), symbol.registry_key(cx));
+	assert_eq!(None, Symbol::new(cx, "key3").registry_key(cx));
+
+	let tag = object.get(cx, Symbol::to_string_tag(cx)).unwrap();
+	assert_eq!(String::from("Test"), String::from_value(cx, &tag, true, ()).unwrap());
+	let mut object = Object::new(cx);
+	object.set(cx, 0, &Value::null(cx));
+	object.set(cx, -1, &Value::null(cx));
+	object.set(cx, u32::MAX, &Value::null(cx));
+	assert!(object.has(cx, "0"));
+	assert!(object.has(cx, "-1"));
+	assert!(object.has(cx, "4294967295"));
+
+	let keys: Vec<_> = object.keys(cx, None).map(|key| key.to_owned_key(cx)).collect();
+	assert_eq!(
+		vec![
+			OwnedKey::Int(0),
+			OwnedKey::String(String::from("-1")),
+			OwnedKey::String(String::from("4294967295"))
+		],
+		keys
+	);
 }
@@ -0,0 +1,63 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use mozjs::jsapi::JSAutoRealm;
+use mozjs::rust::{JSEngine, Runtime};
+
+use ion::{Context, Object, OwnedKey, Proxy, Value};
+use ion::conversions::{FromValue, ToValue};
+use ion::objects::default_new_global;
+
+#[test]
+fn proxy() {
+	let engine = JSEngine::init().unwrap();
+	let runtime = Runtime::new(engine.handle());
+
+	let cx = &Context::from_runtime(&runtime);
+	let global = default_new_global(cx);
+	let _realm = JSAutoRealm::new(runtime.cx(), global.handle().get());
+
+	let store = Rc::new(RefCell::new(HashMap::new()));
+	store.borrow_mut().insert(String::from("key"), String::from("value"));
+
+	let (get, set, has, delete) = (Rc::clone(&store), Rc::clone(&store), Rc::clone(&store), Rc::clone(&store));
+	let target = Object::new(cx);
+	let proxy = Proxy::builder()
+		.get(move |cx, _, key, _| match key.to_owned_key(cx) {
+			OwnedKey::String(key) => Ok(get.borrow().get(&key).as_value(cx)),
+			_ => Ok(Value::undefined(cx)),
+		})
+		.set(move |cx, _, key, value, _| match key.to_owned_key(cx) {
+			OwnedKey::String(key) => {
+				set.borrow_mut().insert(key, String::from_value(cx, value, false, ())?);
+				Ok(true)
+			}
+			_ => Ok(false),
+		})
+		.has(move |cx, _, key| Ok(matches!(key.to_owned_key(cx), OwnedKey::String(key) if has.borrow().contains_key(&key))))
+		.delete_property(move |cx, _, key| {
+			if let OwnedKey::String(key) = key.to_owned_key(cx) {
+				delete.borrow_mut().remove(&key);
+			}
+			Ok(true)
+		})
+		.build(cx, &target)
+		.unwrap();
+
+	let mut object = Object::from(cx.root_object(proxy.handle().get()));
+	let value = object.get(cx, "key").unwrap();
+	assert_eq!(String::from("value"), String::from_value(cx, &value, true, ()).unwrap());
+	assert!(object.has(cx, "key"));
+	assert!(!object.has(cx, "missing"));
+
+	assert!(object.set(cx, "other", &Value::string(cx, "set")));
+	assert_eq!(Some(&String::from("set")), store.borrow().get("other"));
+	assert!(!target.has(cx, "other"));
+
+	assert!(object.delete(cx, "key"));
+	assert!(!store.borrow().contains_key("key"));
+
+	assert!(proxy.revoke(cx));
+	assert!(object.get(cx, "other").is_none());
+}
@@ -0,0 +1,31 @@
+use mozjs::jsapi::JSAutoRealm;
+use mozjs::rust::{JSEngine, Runtime};
+
+use ion::{Context, RegExp};
+use ion::flags::RegExpFlags;
+use ion::objects::default_new_global;
+
+#[test]
+fn regexp() {
+	let engine = JSEngine::init().unwrap();
+	let runtime = Runtime::new(engine.handle());
+
+	let cx = &Context::from_runtime(&runtime);
+	let global = default_new_global(cx);
+	let _realm = JSAutoRealm::new(runtime.cx(), global.handle().get());
+
+	let regexp = RegExp::new(cx, r"(?<year>\d{4})-(\d{2})(-x)?", RegExpFlags::HAS_INDICES).unwrap();
+	assert!(regexp.test(cx, "Released 2021-03"));
+	assert!(!regexp.test(cx, "Released in March"));
+
+	let result = regexp.exec(cx, "Released 2021-03", 0).unwrap();
+	assert_eq!(9, result.index);
+	assert_eq!(
+		vec![Some(String::from("2021-03")), Some(String::from("2021")), Some(String::from("03")), None],
+		result.captures
+	);
+	assert_eq!(Some(&Some(String::from("2021"))), result.groups.get("year"));
+	assert_eq!(Some(vec![Some((9, 16)), Some((9, 13)), Some((14, 16)), None]), result.indices);
+
+	assert!(regexp.exec(cx, "Released 2021-03", 10).is_none());
+}
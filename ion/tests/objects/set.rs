@@ -0,0 +1,40 @@
+use mozjs::jsapi::JSAutoRealm;
+use mozjs::rust::{JSEngine, Runtime};
+
+use ion::{Context, Set, Value};
+use ion::objects::default_new_global;
+
+#[test]
+fn set() {
+	let engine = JSEngine::init().unwrap();
+	let runtime = Runtime::new(engine.handle());
+
+	let cx = &Context::from_runtime(&runtime);
+	let global = default_new_global(cx);
+	let _realm = JSAutoRealm::new(runtime.cx(), global.handle().get());
+
+	let set = Set::new(cx);
+	assert_eq!(0, set.size(cx));
+
+	let value1 = Value::string(cx, "value1");
+	let value2 = Value::i32(cx, 2);
+	assert!(set.add(cx, &value1));
+	assert!(set.add(cx, &value2));
+	assert!(set.add(cx, &value1));
+	assert_eq!(2, set.size(cx));
+
+	assert!(set.has(cx, &value1));
+	assert!(!set.has(cx, &Value::undefined(cx)));
+
+	let values: Vec<_> = set.iter(cx).collect();
+	assert_eq!(2, values.len());
+	assert!(values[0].is_same(cx, &value1));
+	assert!(values[1].is_same(cx, &value2));
+
+	assert!(set.delete(cx, &value1));
+	assert!(!set.delete(cx, &value1));
+	assert_eq!(1, set.size(cx));
+
+	assert!(set.clear(cx));
+	assert_eq!(0, set.size(cx));
+}
@@ -0,0 +1,71 @@
+use mozjs::jsapi::JSAutoRealm;
+use mozjs::rust::{JSEngine, Runtime};
+
+use ion::{Context, Value, WasmDescriptor, WasmExternKind, WasmMemory, WasmModule};
+use ion::conversions::{ConversionBehavior, FromValue};
+use ion::objects::default_new_global;
+
+// (module
+//   (memory (export "memory") 1)
+//   (func (export "add") (param i32 i32) (result i32) local.get 0 local.get 1 i32.add))
+const ADD: &[u8] = &[
+	0x00, 0x61, 0x73, 0x6D, 0x01, 0x00, 0x00, 0x00, 0x01, 0x07, 0x01, 0x60, 0x02, 0x7F, 0x7F, 0x01, 0x7F, 0x03, 0x02, 0x01, 0x00, 0x05, 0x03, 0x01,
+	0x00, 0x01, 0x07, 0x10, 0x02, 0x03, 0x61, 0x64, 0x64, 0x00, 0x00, 0x06, 0x6D, 0x65, 0x6D, 0x6F, 0x72, 0x79, 0x02, 0x00, 0x0A, 0x09, 0x01, 0x07,
+	0x00, 0x20, 0x00, 0x20, 0x01, 0x6A, 0x0B,
+];
+
+#[test]
+fn wasm() {
+	let engine = JSEngine::init().unwrap();
+	let runtime = Runtime::new(engine.handle());
+
+	let cx = &Context::from_runtime(&runtime);
+	let global = default_new_global(cx);
+	let _realm = JSAutoRealm::new(runtime.cx(), global.handle().get());
+
+	assert!(WasmModule::compile(cx, &[0x00, 0x61, 0x73, 0x6D]).is_err());
+
+	let module = WasmModule::compile(cx, ADD).unwrap();
+	assert!(module.imports(cx).is_empty());
+	assert_eq!(
+		vec![
+			WasmDescriptor {
+				module: None,
+				name: String::from("memory"),
+				kind: WasmExternKind::Memory,
+			},
+			WasmDescriptor {
+				module: None,
+				name: String::from("add"),
+				kind: WasmExternKind::Function,
+			},
+		],
+		module.exports(cx)
+	);
+
+	let instance = module.instantiate(cx, None).unwrap();
+	let sum = instance.call(cx, "add", &[Value::i32(cx, 2), Value::i32(cx, 3)]).unwrap();
+	assert_eq!(5, i32::from_value(cx, &sum, true, ConversionBehavior::Default).unwrap());
+	assert!(instance.call(cx, "subtract", &[]).is_err());
+
+	let memory = instance.memory(cx, "memory").unwrap();
+	assert!(!memory.is_shared(cx));
+	assert_eq!(WasmMemory::PAGE_SIZE, memory.len(cx));
+
+	assert!(memory.write(cx, 8, &[1, 2, 3]));
+	assert!(!memory.write(cx, WasmMemory::PAGE_SIZE - 1, &[1, 2]));
+	assert_eq!(Some(vec![0, 1, 2, 3]), memory.read(cx, 7, 4));
+	assert_eq!(None, memory.read(cx, WasmMemory::PAGE_SIZE, 1));
+
+	let view = memory.view(cx).unwrap();
+	assert_eq!(WasmMemory::PAGE_SIZE, view.len());
+	assert_eq!(&[1, 2, 3], unsafe { &view.as_slice()[8..11] });
+
+	assert_eq!(1, memory.grow(cx, 1).unwrap());
+	assert_eq!(2 * WasmMemory::PAGE_SIZE, memory.len(cx));
+	assert!(view.is_detached(cx));
+	assert_eq!(Some(vec![1, 2, 3]), memory.read(cx, 8, 3));
+
+	let memory = WasmMemory::new(cx, 1, Some(1), false).unwrap();
+	assert!(memory.grow(cx, 1).is_err());
+}
@@ -0,0 +1,38 @@
+use mozjs::jsapi::{ClearKeptObjects, GCReason, JS_GC, JSAutoRealm, OnNewGlobalHookOption, WeakRefSpecifier};
+use mozjs::rust::{JSEngine, RealmOptions, Runtime, SIMPLE_GLOBAL_CLASS};
+
+use ion::{Context, Object, Weak};
+use ion::objects::new_global;
+
+#[test]
+fn weak() {
+	let engine = JSEngine::init().unwrap();
+	let runtime = Runtime::new(engine.handle());
+
+	let cx = &Context::from_runtime(&runtime);
+	let mut realm_options = RealmOptions::default();
+	realm_options.creationOptions_.weakRefs_ = WeakRefSpecifier::EnabledWithoutCleanupSome;
+	let global = new_global(cx, &SIMPLE_GLOBAL_CLASS, None, OnNewGlobalHookOption::FireOnNewGlobalHook, realm_options);
+	let _realm = JSAutoRealm::new(runtime.cx(), global.handle().get());
+
+	let mut target = Object::new(cx);
+	assert!(target.set_as(cx, "key", "value"));
+	let weak = Weak::new(cx, &target).unwrap();
+
+	let upgraded = weak.upgrade(cx).unwrap();
+	assert_eq!(target.handle().get(), upgraded.handle().get());
+	assert_eq!(Some(String::from("value")), upgraded.get_as(cx, "key", true, ()));
+
+	let collected = {
+		let inner = unsafe { &Context::new_unchecked(runtime.cx()) };
+		Weak::new(inner, &Object::new(inner)).unwrap()
+	};
+
+	unsafe {
+		ClearKeptObjects(runtime.cx());
+		JS_GC(runtime.cx(), GCReason::API);
+	}
+
+	assert!(weak.is_alive(cx));
+	assert!(!collected.is_alive(cx));
+}
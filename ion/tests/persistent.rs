@@ -0,0 +1,34 @@
+use mozjs::jsapi::{GCReason, JS_GC, JSAutoRealm, JSObject};
+use mozjs::rust::{JSEngine, Runtime};
+
+use ion::{Context, Object, PersistentRooted};
+use ion::objects::default_new_global;
+
+#[test]
+fn persistent() {
+	let engine = JSEngine::init().unwrap();
+	let runtime = Runtime::new(engine.handle());
+
+	let cx = &Context::from_runtime(&runtime);
+	let global = default_new_global(cx);
+	let _realm = JSAutoRealm::new(runtime.cx(), global.handle().get());
+
+	let persistent: PersistentRooted<*mut JSObject> = {
+		let inner = unsafe { &Context::new_unchecked(runtime.cx()) };
+		let mut object = Object::new(inner);
+		assert!(object.set_as(inner, "key", "value"));
+		PersistentRooted::from_local(&object)
+	};
+
+	unsafe {
+		JS_GC(runtime.cx(), GCReason::API);
+	}
+
+	let object = Object::from(cx.root_object(persistent.get()));
+	assert_eq!(Some(String::from("value")), object.get_as(cx, "key", true, ()));
+
+	let cloned = persistent.clone();
+	drop(persistent);
+	assert_eq!(object.handle().get(), cloned.get());
+	assert_eq!(object.handle().get(), cloned.local().get());
+}
@@ -5,8 +5,18 @@
  */
 
 export const ok = ______assertInternal______.ok;
+export const equal = ______assertInternal______.equal;
+export const notEqual = ______assertInternal______.notEqual;
+export const strictEqual = ______assertInternal______.strictEqual;
+export const notStrictEqual = ______assertInternal______.notStrictEqual;
 export const equals = ______assertInternal______.equals;
+export const deepEqual = ______assertInternal______.deepEqual;
+export const notDeepEqual = ______assertInternal______.notDeepEqual;
 export const throws = ______assertInternal______.throws;
+export const doesNotThrow = ______assertInternal______.doesNotThrow;
+export const rejects = ______assertInternal______.rejects;
+export const match = ______assertInternal______.match;
+export const doesNotMatch = ______assertInternal______.doesNotMatch;
 export const fail = ______assertInternal______.fail;
 
 export default Object.freeze(______assertInternal______);
@@ -4,17 +4,52 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/.
  */
 
-use mozjs::jsapi::JSFunctionSpec;
+use mozjs::jsapi::{JS_HasInstance, JSFunctionSpec, JSObject};
+use mozjs::jsval::JSVal;
 
-use ion::{Context, Error, Function, Object, Result, Value};
+use ion::{Context, Error, ErrorKind, Function, Object, PersistentRooted, Promise, PromiseFuture, RegExp, Result, Value};
+use ion::conversions::{FromValue, ToValue};
 use runtime::modules::NativeModule;
+use runtime::promise::future_to_promise;
 
-fn assert_internal(message: Option<String>) -> Result<()> {
+use crate::assert::diff::{diff, format_plain};
+use crate::assert::equality::deep_equal;
+
+fn assertion_error(message: Option<String>) -> Error {
 	let error = match message {
 		Some(msg) => format!("Assertion Failed: {}", msg),
 		None => String::from("Assertion Failed"),
 	};
-	Err(Error::new(&error, None))
+	Error::new(&error, None).with_name("AssertionError")
+}
+
+fn assert_internal(message: Option<String>) -> Result<()> {
+	Err(assertion_error(message))
+}
+
+/// Fails with the custom message if it was given, or the generated message otherwise.
+fn assert_with(message: Option<String>, generated: impl FnOnce() -> String) -> Result<()> {
+	assert_internal(Some(message.unwrap_or_else(generated)))
+}
+
+fn assert_equal(cx: &Context, actual: &Value, expected: &Value, equal: bool, comparison: &str, message: Option<String>) -> Result<()> {
+	if equal {
+		Ok(())
+	} else {
+		assert_with(message, || {
+			format!("Expected values to be {}:\n\n{}", comparison, diff(cx, actual, expected))
+		})
+	}
+}
+
+fn assert_not_equal(cx: &Context, actual: &Value, equal: bool, comparison: &str, message: Option<String>) -> Result<()> {
+	if equal {
+		assert_with(message, || {
+			format!("Expected values not to be {}:\n\n{}", comparison, format_plain(cx, actual))
+		})
+	} else {
+		Ok(())
+	}
 }
 
 #[js_fn]
@@ -26,19 +61,217 @@ fn ok(assertion: Option<bool>, message: Option<String>) -> Result<()> {
 	}
 }
 
+/// Asserts that the values are loosely equal, as with `==`.
 #[js_fn]
-fn equals(cx: &Context, actual: Value, expected: Value, message: Option<String>) -> Result<()> {
-	if actual.is_same(cx, &expected) {
+fn equal(cx: &Context, actual: Value, expected: Value, message: Option<String>) -> Result<()> {
+	let equal = actual.is_loosely_equal(cx, &expected);
+	assert_equal(cx, &actual, &expected, equal, "loosely equal", message)
+}
+
+#[js_fn]
+fn notEqual(cx: &Context, actual: Value, expected: Value, message: Option<String>) -> Result<()> {
+	let equal = actual.is_loosely_equal(cx, &expected);
+	assert_not_equal(cx, &actual, equal, "loosely equal", message)
+}
+
+/// Asserts that the values are the same, as with `Object.is`.
+#[js_fn]
+fn strictEqual(cx: &Context, actual: Value, expected: Value, message: Option<String>) -> Result<()> {
+	let equal = actual.is_same(cx, &expected);
+	assert_equal(cx, &actual, &expected, equal, "strictly equal", message)
+}
+
+#[js_fn]
+fn notStrictEqual(cx: &Context, actual: Value, expected: Value, message: Option<String>) -> Result<()> {
+	let equal = actual.is_same(cx, &expected);
+	assert_not_equal(cx, &actual, equal, "strictly equal", message)
+}
+
+/// Asserts that the values are deeply equal, comparing the properties of objects recursively.
+/// Cyclic objects are equal if their structures are equal.
+#[js_fn]
+fn deepEqual(cx: &Context, actual: Value, expected: Value, message: Option<String>) -> Result<()> {
+	let equal = deep_equal(cx, &actual, &expected);
+	assert_equal(cx, &actual, &expected, equal, "deeply equal", message)
+}
+
+#[js_fn]
+fn notDeepEqual(cx: &Context, actual: Value, expected: Value, message: Option<String>) -> Result<()> {
+	let equal = deep_equal(cx, &actual, &expected);
+	assert_not_equal(cx, &actual, equal, "deeply equal", message)
+}
+
+/// Separates the optional matcher of `throws` and `rejects` from the message, as the matcher can be omitted.
+fn matcher_and_message<'cx>(cx: &'cx Context, expected: Option<Value<'cx>>, message: Option<String>) -> (Option<Value<'cx>>, Option<String>) {
+	match expected {
+		Some(expected) if expected.handle().is_string() && message.is_none() => (None, String::from_value(cx, &expected, true, ()).ok()),
+		Some(expected) if expected.handle().is_undefined() => (None, message),
+		expected => (expected, message),
+	}
+}
+
+/// Checks that an error matches the expected error.
+///
+/// Regular expressions are tested against the error converted to a string, and constructors are checked with `instanceof`.
+/// Other functions are validators, which must return `true`.
+/// The properties of other objects must be deeply equal to the properties of the error, or match them if they are regular expressions.
+fn check_error(cx: &Context, error: &Value, expected: &Value, message: Option<String>) -> Result<()> {
+	if !expected.handle().is_object() {
+		return Err(Error::new(
+			"Expected error must be a regular expression, function or object",
+			ErrorKind::Type,
+		));
+	}
+
+	let object = expected.to_object(cx);
+	if let Some(regexp) = RegExp::from(cx, cx.root_object(object.handle().get())) {
+		let string = String::from_value(cx, error, false, ())?;
+		return if regexp.test(cx, &string) {
+			Ok(())
+		} else {
+			assert_with(message, || {
+				format!(
+					"The error did not match the regular expression {}:\n\n{}",
+					regexp.to_string(cx),
+					format_plain(cx, error)
+				)
+			})
+		};
+	}
+
+	if let Some(function) = Function::from_object(cx, &object) {
+		let matches = if function.is_constructor() {
+			let mut instance = false;
+			unsafe { JS_HasInstance(cx.as_ptr(), object.handle().into(), error.handle().into(), &mut instance) && instance }
+		} else {
+			match function.call(cx, &Object::null(cx), &[Value::from(cx.root_value(error.get()))]) {
+				Ok(result) => result.handle().is_true(),
+				Err(report) => return Err(report.map(|report| report.exception.to_error()).unwrap_or_else(Error::none)),
+			}
+		};
+		return if matches {
+			Ok(())
+		} else {
+			let name = function.name(cx).unwrap_or_default();
+			assert_with(message, || format!("The error did not match {}:\n\n{}", name, format_plain(cx, error)))
+		};
+	}
+
+	let matches = error.handle().is_object() && {
+		let error = error.to_object(cx);
+		object.iter(cx, None).all(|(key, expected)| {
+			let Some(actual) = error.get(cx, &key) else {
+				return false;
+			};
+			let regexp = expected
+				.handle()
+				.is_object()
+				.then(|| RegExp::from(cx, cx.root_object(expected.handle().to_object())))
+				.flatten();
+			match regexp {
+				Some(regexp) if actual.handle().is_string() => String::from_value(cx, &actual, true, ()).is_ok_and(|string| regexp.test(cx, &string)),
+				_ => deep_equal(cx, &actual, &expected),
+			}
+		})
+	};
+	if matches {
 		Ok(())
 	} else {
-		assert_internal(message)
+		assert_with(message, || {
+			format!("The error did not match the expected error:\n\n{}", diff(cx, error, expected))
+		})
 	}
 }
 
+/// Asserts that the function throws, and that the error matches `expected` if it is given.
 #[js_fn]
-fn throws(cx: &Context, func: Function, message: Option<String>) -> Result<()> {
-	if func.call(cx, &Object::global(cx), &[]).is_err() {
-		assert_internal(message)
+fn throws<'cx>(cx: &'cx Context, func: Function, expected: Option<Value<'cx>>, message: Option<String>) -> Result<()> {
+	let (expected, message) = matcher_and_message(cx, expected, message);
+	match func.call(cx, &Object::global(cx), &[]) {
+		Ok(_) => assert_with(message, || String::from("Missing expected exception")),
+		Err(Some(report)) => match expected {
+			Some(expected) => check_error(cx, &report.exception.as_value(cx), &expected, message),
+			None => Ok(()),
+		},
+		Err(None) => Err(Error::new("Function threw an uncatchable exception", None)),
+	}
+}
+
+#[js_fn]
+fn doesNotThrow(cx: &Context, func: Function, message: Option<String>) -> Result<()> {
+	match func.call(cx, &Object::global(cx), &[]) {
+		Ok(_) => Ok(()),
+		Err(report) => {
+			let error = report.map(|report| report.exception.as_value(cx)).unwrap_or_else(|| Value::undefined(cx));
+			assert_with(message, || format!("Got unwanted exception:\n\n{}", format_plain(cx, &error)))
+		}
+	}
+}
+
+/// Asserts that the promise, or the promise returned by the function, rejects, and that the reason matches `expected` if it is given.
+#[js_fn]
+fn rejects<'cx>(cx: &'cx Context, promise: Value<'cx>, expected: Option<Value<'cx>>, message: Option<String>) -> Result<Option<Promise<'cx>>> {
+	let (expected, message) = matcher_and_message(cx, expected, message);
+	let function = promise
+		.handle()
+		.is_object()
+		.then(|| Function::from_object(cx, &promise.to_object(cx)))
+		.flatten();
+	let promise = match function {
+		Some(function) => match function.call(cx, &Object::global(cx), &[]) {
+			Ok(value) => value,
+			Err(Some(report)) => return Err(report.exception.to_error()),
+			Err(None) => return Err(Error::new("Function threw an uncatchable exception", None)),
+		},
+		None => promise,
+	};
+	let promise = promise
+		.handle()
+		.is_object()
+		.then(|| Promise::from(promise.to_object(cx).into_local()))
+		.flatten()
+		.ok_or_else(|| Error::new("Expected a promise, or a function which returns a promise", ErrorKind::Type))?;
+
+	let promise: PersistentRooted<*mut JSObject> = PersistentRooted::new(promise.handle().get());
+	let expected: Option<PersistentRooted<JSVal>> = expected.map(|expected| PersistentRooted::new(expected.get()));
+	let cx_ptr = cx.as_ptr();
+	Ok(future_to_promise::<_, _, Error>(cx, async move {
+		let cx = unsafe { Context::new_unchecked(cx_ptr) };
+		let promise = Promise::from(cx.root_object(promise.get())).unwrap();
+		match PromiseFuture::new(&cx, &promise).await {
+			Ok(_) => assert_with(message, || String::from("Missing expected rejection")),
+			Err(reason) => match expected {
+				Some(expected) => check_error(&cx, &reason, &Value::from(cx.root_value(expected.get())), message),
+				None => Ok(()),
+			},
+		}
+	}))
+}
+
+/// Asserts that the string matches the regular expression.
+#[js_fn]
+fn matches(cx: &Context, string: String, regexp: Object, message: Option<String>) -> Result<()> {
+	let regexp = RegExp::from(cx, regexp.into_local()).ok_or_else(|| Error::new("Expected a regular expression", ErrorKind::Type))?;
+	if regexp.test(cx, &string) {
+		Ok(())
+	} else {
+		assert_with(message, || {
+			format!("The input did not match the regular expression {}:\n\n{:?}", regexp.to_string(cx), string)
+		})
+	}
+}
+
+#[js_fn]
+fn doesNotMatch(cx: &Context, string: String, regexp: Object, message: Option<String>) -> Result<()> {
+	let regexp = RegExp::from(cx, regexp.into_local()).ok_or_else(|| Error::new("Expected a regular expression", ErrorKind::Type))?;
+	if regexp.test(cx, &string) {
+		assert_with(message, || {
+			format!(
+				"The input was expected not to match the regular expression {}:\n\n{:?}",
+				regexp.to_string(cx),
+				string
+			)
+		})
 	} else {
 		Ok(())
 	}
@@ -51,8 +284,18 @@ fn fail(message: Option<String>) -> Result<()> {
 
 const FUNCTIONS: &[JSFunctionSpec] = &[
 	function_spec!(ok, 0),
-	function_spec!(equals, 2),
+	function_spec!(equal, 2),
+	function_spec!(notEqual, 2),
+	function_spec!(strictEqual, 2),
+	function_spec!(strictEqual, "equals", 2),
+	function_spec!(notStrictEqual, 2),
+	function_spec!(deepEqual, 2),
+	function_spec!(notDeepEqual, 2),
 	function_spec!(throws, 1),
+	function_spec!(doesNotThrow, 1),
+	function_spec!(rejects, 1),
+	function_spec!(matches, "match", 2),
+	function_spec!(doesNotMatch, 2),
 	function_spec!(fail, 0),
 	JSFunctionSpec::ZERO,
 ];
@@ -0,0 +1,89 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use ion::{Context, Value};
+use ion::format::{Config, format_value};
+
+/// Formats a value for an assertion message, without colours.
+pub(crate) fn format_plain(cx: &Context, value: &Value) -> String {
+	strip_colours(&format_value(cx, Config::default().quoted(true), value))
+}
+
+fn strip_colours(string: &str) -> String {
+	let mut stripped = String::with_capacity(string.len());
+	let mut chars = string.chars();
+	while let Some(char) = chars.next() {
+		if char == '\x1b' {
+			for char in chars.by_ref() {
+				if char.is_ascii_alphabetic() {
+					break;
+				}
+			}
+		} else {
+			stripped.push(char);
+		}
+	}
+	stripped
+}
+
+/// Creates a line-by-line diff of the formatted values, where lines only in `actual` are prefixed by `+`,
+/// and lines only in `expected` are prefixed by `-`.
+pub(crate) fn diff(cx: &Context, actual: &Value, expected: &Value) -> String {
+	let actual = format_plain(cx, actual);
+	let expected = format_plain(cx, expected);
+	diff_lines(&actual.lines().collect::<Vec<_>>(), &expected.lines().collect::<Vec<_>>())
+}
+
+/// Maximum number of cells in the table of common subsequence lengths, beyond which the lines are not diffed.
+const MAX_DIFF_CELLS: usize = 1 << 20;
+
+/// Diffs lines using their longest common subsequence.
+/// If there are too many lines to diff, every line of `actual` is followed by every line of `expected` instead.
+fn diff_lines(actual: &[&str], expected: &[&str]) -> String {
+	let (rows, columns) = (actual.len(), expected.len());
+	if (rows + 1).saturating_mul(columns + 1) > MAX_DIFF_CELLS {
+		let mut diff = String::from("+ actual - expected\n");
+		for line in actual {
+			diff.push_str("\n+ ");
+			diff.push_str(line);
+		}
+		for line in expected {
+			diff.push_str("\n- ");
+			diff.push_str(line);
+		}
+		return diff;
+	}
+
+	let mut lengths = vec![vec![0usize; columns + 1]; rows + 1];
+	for i in (0..rows).rev() {
+		for j in (0..columns).rev() {
+			lengths[i][j] = if actual[i] == expected[j] {
+				lengths[i + 1][j + 1] + 1
+			} else {
+				lengths[i + 1][j].max(lengths[i][j + 1])
+			};
+		}
+	}
+
+	let mut diff = String::from("+ actual - expected\n");
+	let (mut i, mut j) = (0, 0);
+	while i < rows || j < columns {
+		let line = if i < rows && j < columns && actual[i] == expected[j] {
+			i += 1;
+			j += 1;
+			format!("  {}", actual[i - 1])
+		} else if i < rows && (j == columns || lengths[i + 1][j] >= lengths[i][j + 1]) {
+			i += 1;
+			format!("+ {}", actual[i - 1])
+		} else {
+			j += 1;
+			format!("- {}", expected[j - 1])
+		};
+		diff.push('\n');
+		diff.push_str(&line);
+	}
+	diff
+}
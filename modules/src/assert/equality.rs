@@ -0,0 +1,161 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use mozjs::jsapi::{ESClass, JSObject};
+use mozjs::typedarray::{ArrayBuffer, ArrayBufferView};
+
+use ion::{Array, Context, Date, Map, Object, RegExp, Set, Value};
+
+/// Compares two values for deep equality, as with `assert.deepEqual`.
+///
+/// Primitives are compared with SameValue. Objects are equal if they have the same prototype, builtin class,
+/// and own enumerable properties, whose values are deeply equal.
+/// Dates, regular expressions, boxed primitives, maps and sets are compared by their contents,
+/// and array buffers, typed arrays and data views are compared by their bytes.
+pub(crate) fn deep_equal(cx: &Context, actual: &Value, expected: &Value) -> bool {
+	DeepEquality::default().equal(cx, actual, expected)
+}
+
+/// Tracks the pairs of objects being compared, so that cyclic structures terminate.
+/// A pair which is already being compared is assumed to be equal, as any difference is found by the outer comparison.
+#[derive(Default)]
+struct DeepEquality {
+	visiting: Vec<(*mut JSObject, *mut JSObject)>,
+}
+
+impl DeepEquality {
+	fn equal(&mut self, cx: &Context, actual: &Value, expected: &Value) -> bool {
+		if actual.is_same(cx, expected) {
+			return true;
+		}
+		if !actual.handle().is_object() || !expected.handle().is_object() {
+			return false;
+		}
+
+		let actual = actual.to_object(cx);
+		let expected = expected.to_object(cx);
+		let pair = (actual.handle().get(), expected.handle().get());
+		if self.visiting.contains(&pair) {
+			return true;
+		}
+
+		self.visiting.push(pair);
+		let equal = self.equal_objects(cx, &actual, &expected);
+		self.visiting.pop();
+		equal
+	}
+
+	fn equal_objects(&mut self, cx: &Context, actual: &Object, expected: &Object) -> bool {
+		let prototypes = (actual.get_prototype(cx), expected.get_prototype(cx));
+		let same_prototype = match &prototypes {
+			(Some(actual), Some(expected)) => actual.handle().get() == expected.handle().get(),
+			(None, None) => true,
+			_ => false,
+		};
+		let class = actual.get_builtin_class(cx);
+		if !same_prototype || class != expected.get_builtin_class(cx) {
+			return false;
+		}
+
+		let equal_contents = match class {
+			ESClass::Array => Array::is_array(cx, actual) == Array::is_array(cx, expected),
+			ESClass::Date => {
+				let actual = Date::from(cx, cx.root_object(actual.handle().get())).and_then(|date| date.to_date(cx));
+				let expected = Date::from(cx, cx.root_object(expected.handle().get())).and_then(|date| date.to_date(cx));
+				actual == expected
+			}
+			ESClass::RegExp => {
+				let actual = RegExp::from(cx, cx.root_object(actual.handle().get())).map(|regexp| regexp.to_string(cx));
+				let expected = RegExp::from(cx, cx.root_object(expected.handle().get())).map(|regexp| regexp.to_string(cx));
+				actual == expected
+			}
+			ESClass::Boolean | ESClass::Number | ESClass::String | ESClass::BigInt => {
+				match (actual.unbox_primitive(cx), expected.unbox_primitive(cx)) {
+					(Some(actual), Some(expected)) => actual.is_same(cx, &expected),
+					_ => false,
+				}
+			}
+			ESClass::ArrayBuffer => {
+				let actual = ArrayBuffer::from(actual.handle().get());
+				let expected = ArrayBuffer::from(expected.handle().get());
+				match (actual, expected) {
+					(Ok(actual), Ok(expected)) => unsafe { actual.as_slice() == expected.as_slice() },
+					_ => false,
+				}
+			}
+			ESClass::Other => {
+				let actual = ArrayBufferView::from(actual.handle().get());
+				let expected = ArrayBufferView::from(expected.handle().get());
+				match (actual, expected) {
+					(Ok(actual), Ok(expected)) => unsafe { actual.as_slice() == expected.as_slice() },
+					(actual, expected) => actual.is_err() && expected.is_err(),
+				}
+			}
+			ESClass::Map => self.equal_maps(cx, actual, expected),
+			ESClass::Set => self.equal_sets(cx, actual, expected),
+			ESClass::Error => ["name", "message"]
+				.into_iter()
+				.all(|key| match (actual.get(cx, key), expected.get(cx, key)) {
+					(Some(actual), Some(expected)) => actual.is_same(cx, &expected),
+					(actual, expected) => actual.is_none() && expected.is_none(),
+				}),
+			_ => true,
+		};
+		equal_contents && self.equal_properties(cx, actual, expected)
+	}
+
+	fn equal_properties(&mut self, cx: &Context, actual: &Object, expected: &Object) -> bool {
+		let keys: Vec<_> = actual.keys(cx, None).collect();
+		if keys.len() != expected.keys(cx, None).count() {
+			return false;
+		}
+		keys.iter().all(|key| {
+			if !expected.has_own(cx, key) {
+				return false;
+			}
+			match (actual.get(cx, key), expected.get(cx, key)) {
+				(Some(actual), Some(expected)) => self.equal(cx, &actual, &expected),
+				_ => false,
+			}
+		})
+	}
+
+	/// Maps are equal if every key of one map is a key of the other, and their values are deeply equal.
+	fn equal_maps(&mut self, cx: &Context, actual: &Object, expected: &Object) -> bool {
+		let (Some(actual), Some(expected)) = (
+			Map::from(cx, cx.root_object(actual.handle().get())),
+			Map::from(cx, cx.root_object(expected.handle().get())),
+		) else {
+			return false;
+		};
+		if actual.size(cx) != expected.size(cx) {
+			return false;
+		}
+		actual
+			.iter(cx)
+			.all(|(key, value)| expected.has(cx, &key) && expected.get(cx, &key).is_some_and(|other| self.equal(cx, &value, &other)))
+	}
+
+	/// Sets are equal if every value of one set is in the other, or is an object deeply equal to an object in the other.
+	fn equal_sets(&mut self, cx: &Context, actual: &Object, expected: &Object) -> bool {
+		let (Some(actual), Some(expected)) = (
+			Set::from(cx, cx.root_object(actual.handle().get())),
+			Set::from(cx, cx.root_object(expected.handle().get())),
+		) else {
+			return false;
+		};
+		if actual.size(cx) != expected.size(cx) {
+			return false;
+		}
+		actual.iter(cx).all(|value| {
+			expected.has(cx, &value)
+				|| (value.handle().is_object()
+					&& expected
+						.iter(cx)
+						.any(|other| other.handle().is_object() && self.equal(cx, &value, &other)))
+		})
+	}
+}
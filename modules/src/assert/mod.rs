@@ -7,3 +7,5 @@
 pub use assert::*;
 
 mod assert;
+mod diff;
+mod equality;
@@ -0,0 +1,104 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::mem::take;
+
+use mozjs::conversions::ConversionBehavior::EnforceRange;
+use mozjs::jsapi::{JSFunction, JSFunctionSpec};
+
+use ion::{Context, Function, Object, PersistentRooted, Promise};
+use runtime::ContextExt;
+use runtime::modules::NativeModule;
+
+use crate::bench::runner::run_benchmarks;
+
+/// Default time in milliseconds which each benchmark is measured for.
+const DEFAULT_TIME: u32 = 500;
+
+pub(crate) struct Benchmark {
+	pub(crate) name: String,
+	pub(crate) function: PersistentRooted<*mut JSFunction>,
+	pub(crate) skip: bool,
+	pub(crate) only: bool,
+	pub(crate) warmup: Option<usize>,
+	pub(crate) iterations: Option<usize>,
+	pub(crate) time: f64,
+}
+
+/// Holds the benchmarks declared in a runtime, in the order they were declared.
+#[derive(Default)]
+struct Benchmarks(Vec<Benchmark>);
+
+fn with_benchmarks<T, F: FnOnce(&mut Vec<Benchmark>) -> T>(cx: &Context, f: F) -> T {
+	f(unsafe { &mut (*cx.get_private().as_ptr()).extension::<Benchmarks>().0 })
+}
+
+/// Takes the benchmarks declared in the runtime, so that they are only run once.
+pub(crate) fn take_benchmarks(cx: &Context) -> Vec<Benchmark> {
+	with_benchmarks(cx, take)
+}
+
+#[derive(Default, FromValue)]
+pub(crate) struct BenchOptions {
+	#[ion(default)]
+	skip: bool,
+	/// Only runs this benchmark, and other benchmarks declared with `only`.
+	#[ion(default)]
+	only: bool,
+	/// Number of iterations run before measuring. Defaults to running for up to 100 iterations or 100 milliseconds.
+	#[ion(convert = EnforceRange)]
+	warmup: Option<u32>,
+	/// Number of measured iterations. Defaults to as many as run within `time`.
+	#[ion(convert = EnforceRange)]
+	iterations: Option<u32>,
+	/// Time in milliseconds which iterations are measured for, unless `iterations` is given. Defaults to 500 milliseconds.
+	#[ion(convert = EnforceRange)]
+	time: Option<u32>,
+}
+
+#[derive(Default, FromValue)]
+pub(crate) struct RunOptions {
+	/// Only runs benchmarks whose names contain the filter.
+	filter: Option<String>,
+}
+
+/// Declares a benchmark, which measures how long each call to `function` takes, including settling the promise it returns.
+#[js_fn]
+fn bench(cx: &Context, name: String, function: Function, options: Option<BenchOptions>) {
+	let options = options.unwrap_or_default();
+	let benchmark = Benchmark {
+		name,
+		function: PersistentRooted::new(function.get()),
+		skip: options.skip,
+		only: options.only,
+		warmup: options.warmup.map(|warmup| warmup as usize),
+		iterations: options.iterations.map(|iterations| iterations.max(1) as usize),
+		time: options.time.unwrap_or(DEFAULT_TIME) as f64,
+	};
+	with_benchmarks(cx, |benchmarks| benchmarks.push(benchmark));
+}
+
+/// Runs the declared benchmarks one after another, and resolves with their results.
+#[js_fn]
+fn run(cx: &Context, options: Option<RunOptions>) -> Option<Promise> {
+	let options = options.unwrap_or_default();
+	run_benchmarks(cx, options.filter, |_| {})
+}
+
+const FUNCTIONS: &[JSFunctionSpec] = &[function_spec!(bench, 2), function_spec!(run, 0), JSFunctionSpec::ZERO];
+
+#[derive(Default)]
+pub struct Bench;
+
+impl NativeModule for Bench {
+	const NAME: &'static str = "bench";
+	const SOURCE: &'static str = include_str!("bench.js");
+
+	fn module(cx: &Context) -> Option<Object> {
+		let mut bench = Object::new(cx);
+		unsafe { bench.define_methods(cx, FUNCTIONS).then_some(bench) }
+	}
+}
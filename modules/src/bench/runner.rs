@@ -0,0 +1,213 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use mozjs::jsapi::JSFunction;
+
+use ion::{Context, Error, ErrorReport, Exception, Function, Object, PersistentRooted, Promise, PromiseFuture, Value};
+use ion::conversions::ToValue;
+use runtime::ContextExt;
+use runtime::promise::future_to_promise;
+
+use crate::bench::bench::{Benchmark, take_benchmarks};
+
+/// Minimum number of measured iterations, when the number of iterations is not given.
+const MIN_ITERATIONS: usize = 10;
+const WARMUP_ITERATIONS: usize = 100;
+const WARMUP_TIME: f64 = 100.0;
+
+/// Represents statistics of the durations of the measured iterations of a benchmark, in milliseconds.
+#[derive(Clone, Copy, Debug)]
+pub struct Statistics {
+	pub iterations: usize,
+	pub mean: f64,
+	pub min: f64,
+	pub max: f64,
+	pub p75: f64,
+	pub p99: f64,
+	/// Sample standard deviation of the durations.
+	pub stddev: f64,
+}
+
+impl Statistics {
+	/// Computes the statistics of the samples, or returns [None] if there are none.
+	pub fn from_samples(mut samples: Vec<f64>) -> Option<Statistics> {
+		if samples.is_empty() {
+			return None;
+		}
+		samples.sort_by(f64::total_cmp);
+
+		let count = samples.len() as f64;
+		let mean = samples.iter().sum::<f64>() / count;
+		let variance = if samples.len() > 1 {
+			samples.iter().map(|sample| (sample - mean).powi(2)).sum::<f64>() / (count - 1.0)
+		} else {
+			0.0
+		};
+		Some(Statistics {
+			iterations: samples.len(),
+			mean,
+			min: samples[0],
+			max: samples[samples.len() - 1],
+			p75: percentile(&samples, 0.75),
+			p99: percentile(&samples, 0.99),
+			stddev: variance.sqrt(),
+		})
+	}
+}
+
+/// Returns the sample at the percentile of sorted samples, using the nearest rank.
+fn percentile(sorted: &[f64], percentile: f64) -> f64 {
+	let rank = (percentile * sorted.len() as f64).ceil() as usize;
+	sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+#[derive(Clone, Debug)]
+pub enum BenchOutcome {
+	Completed(Statistics),
+	/// The benchmark threw or rejected with the formatted error.
+	Failed(String),
+	Skipped,
+}
+
+impl BenchOutcome {
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			BenchOutcome::Completed(_) => "completed",
+			BenchOutcome::Failed(_) => "failed",
+			BenchOutcome::Skipped => "skipped",
+		}
+	}
+}
+
+#[derive(Clone, Debug)]
+pub struct BenchResult {
+	pub name: String,
+	pub outcome: BenchOutcome,
+}
+
+impl<'cx> ToValue<'cx> for BenchResult {
+	fn to_value(&self, cx: &'cx Context, value: &mut Value) {
+		let mut object = Object::new(cx);
+		object.set_as(cx, "name", &self.name);
+		object.set_as(cx, "status", self.outcome.as_str());
+		match &self.outcome {
+			BenchOutcome::Completed(statistics) => {
+				object.set_as(cx, "iterations", &(statistics.iterations as f64));
+				object.set_as(cx, "mean", &statistics.mean);
+				object.set_as(cx, "min", &statistics.min);
+				object.set_as(cx, "max", &statistics.max);
+				object.set_as(cx, "p75", &statistics.p75);
+				object.set_as(cx, "p99", &statistics.p99);
+				object.set_as(cx, "stddev", &statistics.stddev);
+			}
+			BenchOutcome::Failed(error) => {
+				object.set_as(cx, "error", error);
+			}
+			BenchOutcome::Skipped => {}
+		}
+		object.to_value(cx, value);
+	}
+}
+
+/// Runs the benchmarks declared in the runtime one after another, and resolves with their results once they have all finished.
+/// Only benchmarks whose names contain `filter` are run, and `on_result` is called with the result of each benchmark as it finishes.
+///
+/// Each benchmark is warmed up, and then measured with the clock of `performance.now()`.
+pub fn run_benchmarks<'cx, F>(cx: &'cx Context, filter: Option<String>, mut on_result: F) -> Option<Promise<'cx>>
+where
+	F: FnMut(&BenchResult) + 'static,
+{
+	let benchmarks = take_benchmarks(cx);
+	let cx_ptr = cx.as_ptr();
+	future_to_promise::<_, _, Error>(cx, async move {
+		let cx = unsafe { Context::new_unchecked(cx_ptr) };
+		let focused = benchmarks.iter().any(|benchmark| benchmark.only);
+
+		let mut results = Vec::new();
+		for benchmark in &benchmarks {
+			if focused && !benchmark.only {
+				continue;
+			}
+			if let Some(filter) = &filter {
+				if !benchmark.name.contains(filter.as_str()) {
+					continue;
+				}
+			}
+
+			let outcome = if benchmark.skip {
+				BenchOutcome::Skipped
+			} else {
+				match measure(&cx, benchmark).await {
+					Ok(statistics) => BenchOutcome::Completed(statistics),
+					Err(error) => BenchOutcome::Failed(error),
+				}
+			};
+			let result = BenchResult { name: benchmark.name.clone(), outcome };
+			on_result(&result);
+			results.push(result);
+		}
+		Ok(results)
+	})
+}
+
+fn now(cx: &Context) -> f64 {
+	unsafe { (*cx.get_private().as_ptr()).performance().now() }
+}
+
+async fn measure(cx: &Context, benchmark: &Benchmark) -> Result<Statistics, String> {
+	match benchmark.warmup {
+		Some(warmup) => {
+			for _ in 0..warmup {
+				call(cx, &benchmark.function).await?;
+			}
+		}
+		None => {
+			let start = now(cx);
+			for _ in 0..WARMUP_ITERATIONS {
+				call(cx, &benchmark.function).await?;
+				if now(cx) - start >= WARMUP_TIME {
+					break;
+				}
+			}
+		}
+	}
+
+	let mut samples = Vec::new();
+	let start = now(cx);
+	loop {
+		let before = now(cx);
+		call(cx, &benchmark.function).await?;
+		samples.push(now(cx) - before);
+
+		let finished = match benchmark.iterations {
+			Some(iterations) => samples.len() >= iterations,
+			None => samples.len() >= MIN_ITERATIONS && now(cx) - start >= benchmark.time,
+		};
+		if finished {
+			break;
+		}
+	}
+	Ok(Statistics::from_samples(samples).unwrap())
+}
+
+/// Calls a benchmark, and waits for the promise it returns to settle.
+async fn call(cx: &Context, function: &PersistentRooted<*mut JSFunction>) -> Result<(), String> {
+	let function = Function::from(cx.root_function(function.get()));
+	let value = function.call(cx, &Object::null(cx), &[]).map_err(|report| match report {
+		Some(report) => report.format(cx),
+		None => String::from("Uncatchable exception"),
+	})?;
+
+	if value.handle().is_object() {
+		if let Some(promise) = Promise::from(value.to_object(cx).into_local()) {
+			if let Err(reason) = PromiseFuture::new(cx, &promise).await {
+				let exception = Exception::from_value(cx, &reason);
+				return Err(ErrorReport::from_exception_with_error_stack(cx, exception).format(cx));
+			}
+		}
+	}
+	Ok(())
+}
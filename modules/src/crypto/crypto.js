@@ -0,0 +1,13 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+export const createHash = ______cryptoInternal______.createHash;
+export const createHmac = ______cryptoInternal______.createHmac;
+export const timingSafeEqual = ______cryptoInternal______.timingSafeEqual;
+
+export const Hash = ______cryptoInternal______.Hash;
+
+export default Object.freeze(______cryptoInternal______);
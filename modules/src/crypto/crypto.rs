@@ -0,0 +1,60 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use mozjs::jsapi::{JSFunctionSpec, JSObject};
+use mozjs::typedarray::ArrayBufferView;
+use subtle::ConstantTimeEq;
+
+use ion::{ClassDefinition, Context, Error, ErrorKind, Object, Result};
+use runtime::modules::NativeModule;
+
+use crate::crypto::hash::{Algorithm, Hash};
+use crate::fs::FileData;
+
+/// Creates a hash, which is updated with chunks of data before its digest is computed.
+#[js_fn]
+fn createHash(cx: &Context, algorithm: Algorithm) -> *mut JSObject {
+	Hash::new_object(cx, Box::new(Hash::new(algorithm, algorithm.hasher())))
+}
+
+/// Creates a hash which computes an HMAC with `key`.
+#[js_fn]
+fn createHmac(cx: &Context, algorithm: Algorithm, key: FileData) -> *mut JSObject {
+	Hash::new_object(cx, Box::new(Hash::new(algorithm, algorithm.hmac(&key.0))))
+}
+
+/// Compares two buffers of equal length in constant time, so that the comparison does not reveal where they differ.
+#[js_fn]
+fn timingSafeEqual(a: ArrayBufferView, b: ArrayBufferView) -> Result<bool> {
+	let (a, b) = unsafe { (a.as_slice(), b.as_slice()) };
+	if a.len() != b.len() {
+		return Err(Error::new("Buffers must have the same length", ErrorKind::Range));
+	}
+	Ok(a.ct_eq(b).into())
+}
+
+const FUNCTIONS: &[JSFunctionSpec] = &[
+	function_spec!(createHash, 1),
+	function_spec!(createHmac, 2),
+	function_spec!(timingSafeEqual, 2),
+	JSFunctionSpec::ZERO,
+];
+
+#[derive(Default)]
+pub struct Crypto;
+
+impl NativeModule for Crypto {
+	const NAME: &'static str = "crypto";
+	const SOURCE: &'static str = include_str!("crypto.js");
+
+	fn module(cx: &Context) -> Option<Object> {
+		let mut crypto = Object::new(cx);
+		if unsafe { crypto.define_methods(cx, FUNCTIONS) } && Hash::init_class(cx, &mut crypto).0 {
+			return Some(crypto);
+		}
+		None
+	}
+}
@@ -0,0 +1,248 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use hmac::{Mac, SimpleHmac};
+use hmac::digest::{Digest, KeyInit};
+use hmac::digest::core_api::BlockSizeUser;
+use mozjs::jsapi::JSObject;
+
+use ion::{ClassDefinition, Context, Error, ErrorKind, Object, Result, Value};
+use ion::class::Reflector;
+use ion::conversions::{FromValue, ToValue};
+use ion::typedarray::Uint8Array;
+
+use crate::encoding::Format;
+use crate::fs::FileData;
+
+/// Calls `$body` with `$digest` as the type of the hash function.
+macro_rules! with_digest {
+	($algorithm:expr, $digest:ident => $body:expr) => {
+		match $algorithm {
+			Algorithm::Md5 => {
+				type $digest = ::md5::Md5;
+				$body
+			}
+			Algorithm::Sha1 => {
+				type $digest = ::sha1::Sha1;
+				$body
+			}
+			Algorithm::Sha224 => {
+				type $digest = ::sha2::Sha224;
+				$body
+			}
+			Algorithm::Sha256 => {
+				type $digest = ::sha2::Sha256;
+				$body
+			}
+			Algorithm::Sha384 => {
+				type $digest = ::sha2::Sha384;
+				$body
+			}
+			Algorithm::Sha512 => {
+				type $digest = ::sha2::Sha512;
+				$body
+			}
+			Algorithm::Sha3_224 => {
+				type $digest = ::sha3::Sha3_224;
+				$body
+			}
+			Algorithm::Sha3_256 => {
+				type $digest = ::sha3::Sha3_256;
+				$body
+			}
+			Algorithm::Sha3_384 => {
+				type $digest = ::sha3::Sha3_384;
+				$body
+			}
+			Algorithm::Sha3_512 => {
+				type $digest = ::sha3::Sha3_512;
+				$body
+			}
+		}
+	};
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Algorithm {
+	Md5,
+	Sha1,
+	Sha224,
+	Sha256,
+	Sha384,
+	Sha512,
+	Sha3_224,
+	Sha3_256,
+	Sha3_384,
+	Sha3_512,
+}
+
+impl Algorithm {
+	/// Returns the algorithm with a name, which is matched case-insensitively, with or without a hyphen after `sha`.
+	fn from_name(name: &str) -> Option<Algorithm> {
+		let name = name.to_ascii_lowercase();
+		let name = name.strip_prefix("sha-").map(|rest| format!("sha{}", rest)).unwrap_or(name);
+		match name.as_str() {
+			"md5" => Some(Algorithm::Md5),
+			"sha1" => Some(Algorithm::Sha1),
+			"sha224" => Some(Algorithm::Sha224),
+			"sha256" => Some(Algorithm::Sha256),
+			"sha384" => Some(Algorithm::Sha384),
+			"sha512" => Some(Algorithm::Sha512),
+			"sha3-224" => Some(Algorithm::Sha3_224),
+			"sha3-256" => Some(Algorithm::Sha3_256),
+			"sha3-384" => Some(Algorithm::Sha3_384),
+			"sha3-512" => Some(Algorithm::Sha3_512),
+			_ => None,
+		}
+	}
+
+	fn name(self) -> &'static str {
+		match self {
+			Algorithm::Md5 => "md5",
+			Algorithm::Sha1 => "sha1",
+			Algorithm::Sha224 => "sha224",
+			Algorithm::Sha256 => "sha256",
+			Algorithm::Sha384 => "sha384",
+			Algorithm::Sha512 => "sha512",
+			Algorithm::Sha3_224 => "sha3-224",
+			Algorithm::Sha3_256 => "sha3-256",
+			Algorithm::Sha3_384 => "sha3-384",
+			Algorithm::Sha3_512 => "sha3-512",
+		}
+	}
+
+	pub(crate) fn hasher(self) -> Box<dyn Hasher> {
+		with_digest!(self, D => Box::new(Plain(D::new())))
+	}
+
+	/// Returns a hasher which computes an HMAC with `key`, which may be of any length.
+	pub(crate) fn hmac(self, key: &[u8]) -> Box<dyn Hasher> {
+		with_digest!(self, D => Box::new(SimpleHmac::<D>::new_from_slice(key).unwrap()))
+	}
+}
+
+impl<'cx> FromValue<'cx> for Algorithm {
+	type Config = ();
+
+	fn from_value(cx: &'cx Context, value: &Value, strict: bool, _: ()) -> Result<Algorithm> {
+		let name = String::from_value(cx, value, strict, ())?;
+		Algorithm::from_name(&name).ok_or_else(|| Error::new(&format!("Unsupported hash algorithm: {}", name), ErrorKind::Type))
+	}
+}
+
+/// Represents the incremental state of a hash function or HMAC.
+pub(crate) trait Hasher {
+	fn update(&mut self, data: &[u8]);
+
+	fn finalize(self: Box<Self>) -> Vec<u8>;
+
+	fn boxed_clone(&self) -> Box<dyn Hasher>;
+}
+
+#[derive(Clone)]
+struct Plain<D>(D);
+
+impl<D: Digest + Clone + 'static> Hasher for Plain<D> {
+	fn update(&mut self, data: &[u8]) {
+		Digest::update(&mut self.0, data);
+	}
+
+	fn finalize(self: Box<Self>) -> Vec<u8> {
+		self.0.finalize().to_vec()
+	}
+
+	fn boxed_clone(&self) -> Box<dyn Hasher> {
+		Box::new(self.clone())
+	}
+}
+
+impl<D: Digest + BlockSizeUser + Clone + 'static> Hasher for SimpleHmac<D> {
+	fn update(&mut self, data: &[u8]) {
+		Mac::update(self, data);
+	}
+
+	fn finalize(self: Box<Self>) -> Vec<u8> {
+		Mac::finalize(*self).into_bytes().to_vec()
+	}
+
+	fn boxed_clone(&self) -> Box<dyn Hasher> {
+		Box::new(self.clone())
+	}
+}
+
+/// Represents a digest, which is returned as bytes unless an encoding is given.
+pub(crate) struct Digested {
+	bytes: Vec<u8>,
+	encoding: Option<Format>,
+}
+
+impl<'cx> ToValue<'cx> for Digested {
+	fn to_value(&self, cx: &'cx Context, value: &mut Value) {
+		match self.encoding {
+			Some(encoding) => encoding.encode(&self.bytes).to_value(cx, value),
+			None => Uint8Array::from(self.bytes.clone()).to_value(cx, value),
+		}
+	}
+}
+
+/// Computes a digest incrementally, as returned by `createHash` and `createHmac`.
+/// Once the digest has been computed, the hash can no longer be updated.
+#[js_class]
+pub struct Hash {
+	reflector: Reflector,
+	#[ion(no_trace)]
+	algorithm: Algorithm,
+	#[ion(no_trace)]
+	hasher: Option<Box<dyn Hasher>>,
+}
+
+impl Hash {
+	pub(crate) fn new(algorithm: Algorithm, hasher: Box<dyn Hasher>) -> Hash {
+		Hash {
+			reflector: Reflector::default(),
+			algorithm,
+			hasher: Some(hasher),
+		}
+	}
+
+	fn finished() -> Error {
+		Error::new("Digest has already been computed", None).with_name("InvalidStateError")
+	}
+}
+
+#[js_class]
+impl Hash {
+	#[ion(constructor)]
+	pub fn constructor() -> Result<Hash> {
+		Err(Error::new("Hash has no constructor.", ErrorKind::Type))
+	}
+
+	/// Updates the hash with data, which is UTF-8 encoded if it is a string, and returns the hash for chaining.
+	pub fn update(cx: &Context, #[ion(this)] this: &Object, data: FileData) -> Result<*mut JSObject> {
+		let object = cx.root_object(this.handle().get());
+		let hash = Hash::get_mut_private(&mut Object::from(object));
+		hash.hasher.as_mut().ok_or_else(Hash::finished)?.update(&data.0);
+		Ok(this.handle().get())
+	}
+
+	/// Computes the digest, which is encoded with `hex`, `base64` or `base64url` if an encoding is given.
+	pub fn digest(&mut self, encoding: Option<String>) -> Result<Digested> {
+		let encoding = encoding.as_deref().map(Format::from_name).transpose()?;
+		let hasher = self.hasher.take().ok_or_else(Hash::finished)?;
+		Ok(Digested { bytes: hasher.finalize(), encoding })
+	}
+
+	/// Returns a copy of the hash which can be updated independently, such as to compute digests of successive prefixes.
+	pub fn copy(&self, cx: &Context) -> Result<*mut JSObject> {
+		let hasher = self.hasher.as_ref().ok_or_else(Hash::finished)?.boxed_clone();
+		Ok(Hash::new_object(cx, Box::new(Hash::new(self.algorithm, hasher))))
+	}
+
+	#[ion(get)]
+	pub fn get_algorithm(&self) -> String {
+		String::from(self.algorithm.name())
+	}
+}
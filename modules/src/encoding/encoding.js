@@ -0,0 +1,16 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+export const encodeBase64 = ______encodingInternal______.encodeBase64;
+export const decodeBase64 = ______encodingInternal______.decodeBase64;
+export const encodeBase64Url = ______encodingInternal______.encodeBase64Url;
+export const decodeBase64Url = ______encodingInternal______.decodeBase64Url;
+export const encodeHex = ______encodingInternal______.encodeHex;
+export const decodeHex = ______encodingInternal______.decodeHex;
+export const Encoder = ______encodingInternal______.Encoder;
+export const Decoder = ______encodingInternal______.Decoder;
+
+export default Object.freeze(______encodingInternal______);
@@ -0,0 +1,266 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use base64::{alphabet, Engine};
+use base64::engine::{DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig};
+use mozjs::jsapi::JSFunctionSpec;
+use mozjs::typedarray::ArrayBufferView;
+
+use ion::{ClassDefinition, Context, Error, ErrorKind, Object, Result};
+use ion::class::Reflector;
+use ion::typedarray::Uint8Array;
+use runtime::modules::NativeModule;
+
+const CONFIG: GeneralPurposeConfig = GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent);
+const BASE64: GeneralPurpose = GeneralPurpose::new(&alphabet::STANDARD, CONFIG);
+const BASE64_URL: GeneralPurpose = GeneralPurpose::new(&alphabet::URL_SAFE, CONFIG.with_encode_padding(false));
+
+const HEX_DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Format {
+	Base64,
+	Base64Url,
+	Hex,
+}
+
+impl Format {
+	pub(crate) fn from_name(name: &str) -> Result<Format> {
+		match name {
+			"base64" => Ok(Format::Base64),
+			"base64url" => Ok(Format::Base64Url),
+			"hex" => Ok(Format::Hex),
+			_ => Err(Error::new(&format!("Unsupported encoding: {}", name), ErrorKind::Range)),
+		}
+	}
+
+	fn name(self) -> &'static str {
+		match self {
+			Format::Base64 => "base64",
+			Format::Base64Url => "base64url",
+			Format::Hex => "hex",
+		}
+	}
+
+	/// Returns the number of bytes encoded together, which are never split between chunks when streaming.
+	fn byte_quantum(self) -> usize {
+		match self {
+			Format::Base64 | Format::Base64Url => 3,
+			Format::Hex => 1,
+		}
+	}
+
+	/// Returns the number of characters decoded together.
+	fn char_quantum(self) -> usize {
+		match self {
+			Format::Base64 | Format::Base64Url => 4,
+			Format::Hex => 2,
+		}
+	}
+
+	pub(crate) fn encode(self, bytes: &[u8]) -> String {
+		match self {
+			Format::Base64 => BASE64.encode(bytes),
+			Format::Base64Url => BASE64_URL.encode(bytes),
+			Format::Hex => encode_hex(bytes),
+		}
+	}
+
+	fn decode(self, string: &str) -> Result<Vec<u8>> {
+		let string: String = string.chars().filter(|c| !c.is_ascii_whitespace()).collect();
+		match self {
+			Format::Base64 => BASE64.decode(string).map_err(|_| invalid(self)),
+			Format::Base64Url => BASE64_URL.decode(string).map_err(|_| invalid(self)),
+			Format::Hex => decode_hex(&string),
+		}
+	}
+}
+
+fn invalid(format: Format) -> Error {
+	Error::new(&format!("Invalid {} string", format.name()), ErrorKind::Syntax)
+}
+
+fn encode_hex(bytes: &[u8]) -> String {
+	let mut string = String::with_capacity(bytes.len() * 2);
+	for byte in bytes {
+		string.push(char::from(HEX_DIGITS[(byte >> 4) as usize]));
+		string.push(char::from(HEX_DIGITS[(byte & 0xF) as usize]));
+	}
+	string
+}
+
+fn decode_hex(string: &str) -> Result<Vec<u8>> {
+	if string.len() % 2 != 0 {
+		return Err(invalid(Format::Hex));
+	}
+	string
+		.as_bytes()
+		.chunks_exact(2)
+		.map(|pair| {
+			let high = (pair[0] as char).to_digit(16);
+			let low = (pair[1] as char).to_digit(16);
+			match (high, low) {
+				(Some(high), Some(low)) => Ok((high << 4 | low) as u8),
+				_ => Err(invalid(Format::Hex)),
+			}
+		})
+		.collect()
+}
+
+#[js_fn]
+fn encodeBase64(bytes: ArrayBufferView) -> String {
+	Format::Base64.encode(unsafe { bytes.as_slice() })
+}
+
+#[js_fn]
+fn decodeBase64(string: String) -> Result<Uint8Array> {
+	Format::Base64.decode(&string).map(Uint8Array::from)
+}
+
+#[js_fn]
+fn encodeBase64Url(bytes: ArrayBufferView) -> String {
+	Format::Base64Url.encode(unsafe { bytes.as_slice() })
+}
+
+#[js_fn]
+fn decodeBase64Url(string: String) -> Result<Uint8Array> {
+	Format::Base64Url.decode(&string).map(Uint8Array::from)
+}
+
+#[js_fn]
+fn encodeHex(bytes: ArrayBufferView) -> String {
+	Format::Hex.encode(unsafe { bytes.as_slice() })
+}
+
+#[js_fn]
+fn decodeHex(string: String) -> Result<Uint8Array> {
+	Format::Hex.decode(&string).map(Uint8Array::from)
+}
+
+/// Encodes a sequence of chunks, so that large buffers can be encoded without being held in memory at once.
+/// Bytes which do not fill a complete quantum are held until the next chunk, or until [Encoder::finish] is called.
+#[js_class]
+pub struct Encoder {
+	reflector: Reflector,
+	#[ion(no_trace)]
+	format: Format,
+	pending: Vec<u8>,
+}
+
+#[js_class]
+impl Encoder {
+	#[ion(constructor)]
+	pub fn constructor(format: Option<String>) -> Result<Encoder> {
+		let format = Format::from_name(format.as_deref().unwrap_or("base64"))?;
+		Ok(Encoder {
+			reflector: Reflector::default(),
+			format,
+			pending: Vec::new(),
+		})
+	}
+
+	pub fn encode(&mut self, bytes: ArrayBufferView) -> String {
+		self.pending.extend_from_slice(unsafe { bytes.as_slice() });
+		let complete = self.pending.len() - self.pending.len() % self.format.byte_quantum();
+		let encoded = self.format.encode(&self.pending[..complete]);
+		self.pending.drain(..complete);
+		encoded
+	}
+
+	pub fn finish(&mut self) -> String {
+		let encoded = self.format.encode(&self.pending);
+		self.pending.clear();
+		encoded
+	}
+
+	#[ion(get)]
+	pub fn get_encoding(&self) -> String {
+		String::from(self.format.name())
+	}
+}
+
+/// Decodes a sequence of strings, so that large inputs can be decoded chunk by chunk.
+/// Characters which do not fill a complete quantum are held until the next string, or until [Decoder::finish] is called.
+#[js_class]
+pub struct Decoder {
+	reflector: Reflector,
+	#[ion(no_trace)]
+	format: Format,
+	pending: String,
+	padded: bool,
+}
+
+#[js_class]
+impl Decoder {
+	#[ion(constructor)]
+	pub fn constructor(format: Option<String>) -> Result<Decoder> {
+		let format = Format::from_name(format.as_deref().unwrap_or("base64"))?;
+		Ok(Decoder {
+			reflector: Reflector::default(),
+			format,
+			pending: String::new(),
+			padded: false,
+		})
+	}
+
+	pub fn decode(&mut self, string: String) -> Result<Uint8Array> {
+		self.pending.extend(string.chars().filter(|c| !c.is_ascii_whitespace()));
+		if self.padded && !self.pending.is_empty() {
+			return Err(invalid(self.format));
+		}
+
+		// Quanta are counted in characters rather than bytes, so that non-ASCII characters are reported as invalid instead of being split.
+		let quantum = self.format.char_quantum();
+		let chars = self.pending.chars().count();
+		let complete = self
+			.pending
+			.char_indices()
+			.nth(chars - chars % quantum)
+			.map_or(self.pending.len(), |(index, _)| index);
+		let decoded = self.format.decode(&self.pending[..complete])?;
+		self.padded = self.pending[..complete].ends_with('=');
+		self.pending.drain(..complete);
+		Ok(Uint8Array::from(decoded))
+	}
+
+	pub fn finish(&mut self) -> Result<Uint8Array> {
+		let decoded = self.format.decode(&self.pending);
+		self.pending.clear();
+		self.padded = false;
+		decoded.map(Uint8Array::from)
+	}
+
+	#[ion(get)]
+	pub fn get_encoding(&self) -> String {
+		String::from(self.format.name())
+	}
+}
+
+const FUNCTIONS: &[JSFunctionSpec] = &[
+	function_spec!(encodeBase64, 1),
+	function_spec!(decodeBase64, 1),
+	function_spec!(encodeBase64Url, 1),
+	function_spec!(decodeBase64Url, 1),
+	function_spec!(encodeHex, 1),
+	function_spec!(decodeHex, 1),
+	JSFunctionSpec::ZERO,
+];
+
+#[derive(Default)]
+pub struct EncodingM;
+
+impl NativeModule for EncodingM {
+	const NAME: &'static str = "encoding";
+	const SOURCE: &'static str = include_str!("encoding.js");
+
+	fn module(cx: &Context) -> Option<Object> {
+		let mut encoding = Object::new(cx);
+		if unsafe { encoding.define_methods(cx, FUNCTIONS) } && Encoder::init_class(cx, &mut encoding).0 && Decoder::init_class(cx, &mut encoding).0 {
+			return Some(encoding);
+		}
+		None
+	}
+}
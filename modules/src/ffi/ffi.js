@@ -0,0 +1,12 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+export const dlopen = ______ffiInternal______.dlopen;
+export const suffix = ______ffiInternal______.suffix;
+
+export const DynamicLibrary = ______ffiInternal______.DynamicLibrary;
+
+export default Object.freeze(______ffiInternal______);
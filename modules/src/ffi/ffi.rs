@@ -0,0 +1,68 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use libloading::Library;
+use mozjs::jsapi::{JSFunctionSpec, JSObject};
+
+use ion::{ClassDefinition, Context, Error, Object, Result};
+use ion::flags::PropertyFlags;
+use runtime::modules::NativeModule;
+use runtime::permissions::{check, PermissionName};
+
+use crate::ffi::library::DynamicLibrary;
+
+/// File extension of shared libraries on the current platform.
+const SUFFIX: &str = if cfg!(windows) {
+	"dll"
+} else if cfg!(target_os = "macos") {
+	"dylib"
+} else {
+	"so"
+};
+
+#[cfg(unix)]
+fn current_process() -> std::result::Result<Library, libloading::Error> {
+	Ok(libloading::os::unix::Library::this().into())
+}
+
+#[cfg(windows)]
+fn current_process() -> std::result::Result<Library, libloading::Error> {
+	libloading::os::windows::Library::this().map(Library::from)
+}
+
+/// Opens a shared library, and declares the signatures of its symbols, which are called synchronously.
+/// The symbols of the current process are used if `path` is `null`.
+#[js_fn]
+fn dlopen(cx: &Context, path: Option<String>, symbols: Object) -> Result<*mut JSObject> {
+	check(PermissionName::Ffi, None)?;
+	let library = match &path {
+		Some(path) => unsafe { Library::new(path) },
+		None => current_process(),
+	};
+	let library = library.map_err(|error| Error::new(&format!("Could not open library: {}", error), None))?;
+	DynamicLibrary::new_library(cx, path, library, &symbols).map(|library| library.handle().get())
+}
+
+const FUNCTIONS: &[JSFunctionSpec] = &[function_spec!(dlopen, 2), JSFunctionSpec::ZERO];
+
+#[derive(Default)]
+pub struct Ffi;
+
+impl NativeModule for Ffi {
+	const NAME: &'static str = "ffi";
+	const SOURCE: &'static str = include_str!("ffi.js");
+
+	fn module(cx: &Context) -> Option<Object> {
+		let mut ffi = Object::new(cx);
+		if unsafe { ffi.define_methods(cx, FUNCTIONS) }
+			&& ffi.define_as(cx, "suffix", SUFFIX, PropertyFlags::CONSTANT_ENUMERATED)
+			&& DynamicLibrary::init_class(cx, &mut ffi).0
+		{
+			return Some(ffi);
+		}
+		None
+	}
+}
@@ -0,0 +1,129 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::cell::RefCell;
+use std::ffi::c_void;
+use std::rc::Rc;
+
+use libffi::middle::{Cif, CodePtr};
+use libloading::{Library, Symbol};
+
+use ion::{ClassDefinition, Context, Error, ErrorKind, Function, Object, OwnedKey, Result};
+use ion::class::Reflector;
+use ion::conversions::FromValue;
+use ion::flags::PropertyFlags;
+
+use crate::ffi::types::{NativeArguments, NativeType, Signature};
+
+type SharedLibrary = Rc<RefCell<Option<Library>>>;
+
+fn closed() -> Error {
+	Error::new("Library is closed", None).with_name("InvalidStateError")
+}
+
+/// Represents a symbol of a library, which can be called with the arguments of its signature.
+struct ForeignFunction {
+	library: SharedLibrary,
+	code: CodePtr,
+	cif: Cif,
+	parameters: Vec<NativeType>,
+	result: NativeType,
+}
+
+impl ForeignFunction {
+	fn new(library: &SharedLibrary, name: &str, signature: Signature) -> Result<ForeignFunction> {
+		let cif = signature.cif()?;
+		let code = {
+			let library = library.borrow();
+			let library = library.as_ref().ok_or_else(closed)?;
+			let symbol: Symbol<*mut c_void> = unsafe { library.get(name.as_bytes()) }
+				.map_err(|error| Error::new(&format!("Could not find symbol {}: {}", name, error), ErrorKind::Type))?;
+			CodePtr::from_ptr(*symbol)
+		};
+		Ok(ForeignFunction {
+			library: Rc::clone(library),
+			code,
+			cif,
+			parameters: signature.parameters,
+			result: signature.result,
+		})
+	}
+
+	/// Creates the JavaScript function which calls the symbol.
+	fn into_function<'cx>(self, cx: &'cx Context, name: &str) -> Function<'cx> {
+		Function::new_closure(cx, name, move |cx, args| {
+			if self.library.borrow().is_none() {
+				return Err(closed());
+			}
+			if args.len() < self.parameters.len() {
+				return Err(Error::new(
+					&format!("Expected {} arguments, but received {}", self.parameters.len(), args.len()),
+					ErrorKind::Type,
+				));
+			}
+
+			let mut arguments = NativeArguments::default();
+			for (index, &parameter) in self.parameters.iter().enumerate() {
+				arguments.push(cx, args.value(index).unwrap(), parameter)?;
+			}
+			unsafe { arguments.call(cx, &self.cif, self.code, self.result) }
+		})
+	}
+}
+
+/// Represents a shared library opened with `ffi.dlopen`, whose declared symbols are functions of its `symbols` object.
+#[js_class]
+pub struct DynamicLibrary {
+	reflector: Reflector,
+	#[ion(no_trace)]
+	path: Option<String>,
+	#[ion(no_trace)]
+	library: SharedLibrary,
+}
+
+impl DynamicLibrary {
+	/// Creates a library object, with a `symbols` object of the declared symbols.
+	pub(crate) fn new_library<'cx>(cx: &'cx Context, path: Option<String>, library: Library, symbols: &Object) -> Result<Object<'cx>> {
+		let library = Rc::new(RefCell::new(Some(library)));
+		let mut functions = Object::new(cx);
+		for (name, signature) in symbols.to_hashmap(cx, None) {
+			let OwnedKey::String(name) = name else {
+				continue;
+			};
+			let signature = Signature::from_value(cx, &signature, true, ())?;
+			let function = ForeignFunction::new(&library, &name, signature)?.into_function(cx, &name);
+			functions.set_as(cx, name.as_str(), &function);
+		}
+
+		let object = DynamicLibrary {
+			reflector: Reflector::default(),
+			path,
+			library,
+		};
+		let mut object = Object::from(cx.root_object(DynamicLibrary::new_object(cx, Box::new(object))));
+		object.define_as(cx, "symbols", &functions, PropertyFlags::CONSTANT_ENUMERATED);
+		Ok(object)
+	}
+}
+
+#[js_class]
+impl DynamicLibrary {
+	#[ion(constructor)]
+	pub fn constructor() -> Result<DynamicLibrary> {
+		Err(Error::new("DynamicLibrary has no constructor.", ErrorKind::Type))
+	}
+
+	/// Closes the library. Further calls to its symbols throw an error.
+	pub fn close(&self) {
+		self.library.borrow_mut().take();
+	}
+
+	/// Returns the path the library was opened from, or `null` for the current process.
+	#[ion(get)]
+	pub fn get_path(&self) -> Option<String> {
+		self.path.clone()
+	}
+}
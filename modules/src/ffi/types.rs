@@ -0,0 +1,263 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::ffi::{c_char, c_void, CStr, CString};
+use std::ptr;
+
+use libffi::middle::{Arg, Cif, CodePtr, Type};
+use mozjs::typedarray::{ArrayBuffer, ArrayBufferView};
+
+use ion::{Context, Error, ErrorKind, Result, Value};
+use ion::conversions::{ConversionBehavior, FromValue, ToValue};
+
+/// Represents the type of a parameter or result of a foreign function.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum NativeType {
+	#[default]
+	Void,
+	Bool,
+	U8,
+	I8,
+	U16,
+	I16,
+	U32,
+	I32,
+	U64,
+	I64,
+	Usize,
+	Isize,
+	F32,
+	F64,
+	/// Pointers are represented as BigInts, or `null` for null pointers.
+	Pointer,
+	/// Buffers are passed as pointers to the contents of an ArrayBuffer or typed array.
+	Buffer,
+	/// Strings are passed as pointers to null-terminated UTF-8 strings.
+	String,
+}
+
+impl NativeType {
+	fn ffi_type(self) -> Type {
+		match self {
+			NativeType::Void => Type::void(),
+			NativeType::Bool | NativeType::U8 => Type::u8(),
+			NativeType::I8 => Type::i8(),
+			NativeType::U16 => Type::u16(),
+			NativeType::I16 => Type::i16(),
+			NativeType::U32 => Type::u32(),
+			NativeType::I32 => Type::i32(),
+			NativeType::U64 => Type::u64(),
+			NativeType::I64 => Type::i64(),
+			NativeType::Usize => Type::usize(),
+			NativeType::Isize => Type::isize(),
+			NativeType::F32 => Type::f32(),
+			NativeType::F64 => Type::f64(),
+			NativeType::Pointer | NativeType::Buffer | NativeType::String => Type::pointer(),
+		}
+	}
+}
+
+impl<'cx> FromValue<'cx> for NativeType {
+	type Config = ();
+
+	fn from_value(cx: &'cx Context, value: &Value, strict: bool, _: ()) -> Result<NativeType> {
+		let name = String::from_value(cx, value, strict, ())?;
+		let ty = match name.as_str() {
+			"void" => NativeType::Void,
+			"bool" => NativeType::Bool,
+			"u8" => NativeType::U8,
+			"i8" => NativeType::I8,
+			"u16" => NativeType::U16,
+			"i16" => NativeType::I16,
+			"u32" => NativeType::U32,
+			"i32" => NativeType::I32,
+			"u64" => NativeType::U64,
+			"i64" => NativeType::I64,
+			"usize" => NativeType::Usize,
+			"isize" => NativeType::Isize,
+			"f32" => NativeType::F32,
+			"f64" => NativeType::F64,
+			"pointer" => NativeType::Pointer,
+			"buffer" => NativeType::Buffer,
+			"string" => NativeType::String,
+			_ => return Err(Error::new(&format!("Unknown Native Type: {}", name), ErrorKind::Type)),
+		};
+		Ok(ty)
+	}
+}
+
+/// Represents the signature of a foreign function, as declared when a library is opened.
+#[derive(Debug, FromValue)]
+pub(crate) struct Signature {
+	#[ion(default)]
+	pub(crate) parameters: Vec<NativeType>,
+	#[ion(default)]
+	pub(crate) result: NativeType,
+}
+
+impl Signature {
+	pub(crate) fn cif(&self) -> Result<Cif> {
+		if self.parameters.contains(&NativeType::Void) {
+			return Err(Error::new("Parameters cannot have the type void", ErrorKind::Type));
+		}
+		let parameters = self.parameters.iter().map(|parameter| parameter.ffi_type());
+		Ok(Cif::new(parameters, self.result.ffi_type()))
+	}
+}
+
+/// Represents an argument converted to its native representation, which must outlive the call it is passed to.
+enum NativeArgument {
+	U8(u8),
+	I8(i8),
+	U16(u16),
+	I16(i16),
+	U32(u32),
+	I32(i32),
+	U64(u64),
+	I64(i64),
+	Usize(usize),
+	Isize(isize),
+	F32(f32),
+	F64(f64),
+	Pointer(*mut c_void),
+}
+
+impl NativeArgument {
+	fn as_arg(&self) -> Arg {
+		match self {
+			NativeArgument::U8(argument) => Arg::new(argument),
+			NativeArgument::I8(argument) => Arg::new(argument),
+			NativeArgument::U16(argument) => Arg::new(argument),
+			NativeArgument::I16(argument) => Arg::new(argument),
+			NativeArgument::U32(argument) => Arg::new(argument),
+			NativeArgument::I32(argument) => Arg::new(argument),
+			NativeArgument::U64(argument) => Arg::new(argument),
+			NativeArgument::I64(argument) => Arg::new(argument),
+			NativeArgument::Usize(argument) => Arg::new(argument),
+			NativeArgument::Isize(argument) => Arg::new(argument),
+			NativeArgument::F32(argument) => Arg::new(argument),
+			NativeArgument::F64(argument) => Arg::new(argument),
+			NativeArgument::Pointer(argument) => Arg::new(argument),
+		}
+	}
+}
+
+fn pointer_from_value(cx: &Context, value: &Value) -> Result<*mut c_void> {
+	if value.handle().is_null_or_undefined() {
+		return Ok(ptr::null_mut());
+	}
+	let address = u64::from_value(cx, value, false, ConversionBehavior::EnforceRange)
+		.map_err(|_| Error::new("Expected BigInt, Number or null as Pointer", ErrorKind::Type))?;
+	Ok(address as usize as *mut c_void)
+}
+
+fn buffer_from_value(cx: &Context, value: &Value) -> Result<*mut c_void> {
+	if value.handle().is_null_or_undefined() {
+		Ok(ptr::null_mut())
+	} else if let Ok(mut buffer) = ArrayBuffer::from_value(cx, value, true, ()) {
+		Ok(unsafe { buffer.as_mut_slice() }.as_mut_ptr().cast())
+	} else if let Ok(mut view) = ArrayBufferView::from_value(cx, value, true, ()) {
+		Ok(unsafe { view.as_mut_slice() }.as_mut_ptr().cast())
+	} else {
+		Err(Error::new("Expected ArrayBuffer, TypedArray or null as Buffer", ErrorKind::Type))
+	}
+}
+
+/// Holds the native arguments of a call, along with the strings they point to.
+///
+/// Pointers to buffers are only taken just before the call, as converting later arguments can run scripts which detach them.
+#[derive(Default)]
+pub(crate) struct NativeArguments<'a, 'cx> {
+	arguments: Vec<NativeArgument>,
+	strings: Vec<CString>,
+	buffers: Vec<(usize, &'a Value<'cx>)>,
+}
+
+impl<'a, 'cx> NativeArguments<'a, 'cx> {
+	pub(crate) fn push(&mut self, cx: &Context, value: &'a Value<'cx>, ty: NativeType) -> Result<()> {
+		let argument = match ty {
+			NativeType::Void => unreachable!("Signatures with void parameters are rejected when they are declared"),
+			NativeType::Bool => NativeArgument::U8(bool::from_value(cx, value, false, ())? as u8),
+			NativeType::U8 => NativeArgument::U8(u8::from_value(cx, value, false, ConversionBehavior::EnforceRange)?),
+			NativeType::I8 => NativeArgument::I8(i8::from_value(cx, value, false, ConversionBehavior::EnforceRange)?),
+			NativeType::U16 => NativeArgument::U16(u16::from_value(cx, value, false, ConversionBehavior::EnforceRange)?),
+			NativeType::I16 => NativeArgument::I16(i16::from_value(cx, value, false, ConversionBehavior::EnforceRange)?),
+			NativeType::U32 => NativeArgument::U32(u32::from_value(cx, value, false, ConversionBehavior::EnforceRange)?),
+			NativeType::I32 => NativeArgument::I32(i32::from_value(cx, value, false, ConversionBehavior::EnforceRange)?),
+			NativeType::U64 => NativeArgument::U64(u64::from_value(cx, value, false, ConversionBehavior::EnforceRange)?),
+			NativeType::I64 => NativeArgument::I64(i64::from_value(cx, value, false, ConversionBehavior::EnforceRange)?),
+			NativeType::Usize => NativeArgument::Usize(u64::from_value(cx, value, false, ConversionBehavior::EnforceRange)? as usize),
+			NativeType::Isize => NativeArgument::Isize(i64::from_value(cx, value, false, ConversionBehavior::EnforceRange)? as isize),
+			NativeType::F32 => NativeArgument::F32(f32::from_value(cx, value, false, ())?),
+			NativeType::F64 => NativeArgument::F64(f64::from_value(cx, value, false, ())?),
+			NativeType::Pointer => NativeArgument::Pointer(pointer_from_value(cx, value)?),
+			NativeType::Buffer => {
+				self.buffers.push((self.arguments.len(), value));
+				NativeArgument::Pointer(ptr::null_mut())
+			}
+			NativeType::String => {
+				if value.handle().is_null_or_undefined() {
+					NativeArgument::Pointer(ptr::null_mut())
+				} else {
+					let string = CString::new(String::from_value(cx, value, false, ())?)
+						.map_err(|_| Error::new("String cannot contain null characters", ErrorKind::Type))?;
+					let pointer = string.as_ptr() as *mut c_void;
+					self.strings.push(string);
+					NativeArgument::Pointer(pointer)
+				}
+			}
+		};
+		self.arguments.push(argument);
+		Ok(())
+	}
+
+	/// Calls a foreign function with the arguments, and converts its result to a [Value].
+	///
+	/// Integer results smaller than a register are read as a register and truncated, since libffi widens them.
+	///
+	/// ### Safety
+	/// The [Cif] must match the signature of the function at `code`, and the arguments must match the [Cif].
+	pub(crate) unsafe fn call(&mut self, cx: &'cx Context, cif: &Cif, code: CodePtr, result: NativeType) -> Result<Value<'cx>> {
+		// Buffers are converted strictly, so no scripts run between taking their pointers and the call.
+		for &(index, value) in &self.buffers {
+			self.arguments[index] = NativeArgument::Pointer(buffer_from_value(cx, value)?);
+		}
+
+		let arguments: Vec<_> = self.arguments.iter().map(NativeArgument::as_arg).collect();
+		let mut value = Value::undefined(cx);
+		unsafe {
+			match result {
+				NativeType::Void => cif.call::<()>(code, &arguments),
+				NativeType::Bool => (cif.call::<usize>(code, &arguments) as u8 != 0).to_value(cx, &mut value),
+				NativeType::U8 => (cif.call::<usize>(code, &arguments) as u8).to_value(cx, &mut value),
+				NativeType::I8 => (cif.call::<isize>(code, &arguments) as i8).to_value(cx, &mut value),
+				NativeType::U16 => (cif.call::<usize>(code, &arguments) as u16).to_value(cx, &mut value),
+				NativeType::I16 => (cif.call::<isize>(code, &arguments) as i16).to_value(cx, &mut value),
+				NativeType::U32 => (cif.call::<usize>(code, &arguments) as u32).to_value(cx, &mut value),
+				NativeType::I32 => (cif.call::<isize>(code, &arguments) as i32).to_value(cx, &mut value),
+				NativeType::U64 => cif.call::<u64>(code, &arguments).to_value(cx, &mut value),
+				NativeType::I64 => cif.call::<i64>(code, &arguments).to_value(cx, &mut value),
+				NativeType::Usize => (cif.call::<usize>(code, &arguments) as u64).to_value(cx, &mut value),
+				NativeType::Isize => (cif.call::<isize>(code, &arguments) as i64).to_value(cx, &mut value),
+				NativeType::F32 => cif.call::<f32>(code, &arguments).to_value(cx, &mut value),
+				NativeType::F64 => cif.call::<f64>(code, &arguments).to_value(cx, &mut value),
+				NativeType::Pointer | NativeType::Buffer => {
+					let pointer = cif.call::<*mut c_void>(code, &arguments);
+					(!pointer.is_null()).then_some(pointer as usize as u64).to_value(cx, &mut value);
+				}
+				NativeType::String => {
+					let pointer = cif.call::<*const c_char>(code, &arguments);
+					if !pointer.is_null() {
+						CStr::from_ptr(pointer).to_string_lossy().into_owned().to_value(cx, &mut value);
+					} else {
+						value = Value::null(cx);
+					}
+				}
+			}
+		}
+		Ok(value)
+	}
+}
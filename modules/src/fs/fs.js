@@ -4,20 +4,90 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/.
  */
 
-export const readBinary = ______fsInternal______.readBinary;
-export const readString = ______fsInternal______.readString;
+export const readFile = ______fsInternal______.readFile;
+export const readFileSync = ______fsInternal______.readFileSync;
+export const readTextFile = ______fsInternal______.readTextFile;
+export const readTextFileSync = ______fsInternal______.readTextFileSync;
+export const writeFile = ______fsInternal______.writeFile;
+export const writeFileSync = ______fsInternal______.writeFileSync;
+export const open = ______fsInternal______.open;
+export const stat = ______fsInternal______.stat;
+export const statSync = ______fsInternal______.statSync;
+export const lstat = ______fsInternal______.lstat;
+export const lstatSync = ______fsInternal______.lstatSync;
 export const readDir = ______fsInternal______.readDir;
-export const write = ______fsInternal______.write;
-export const createDir = ______fsInternal______.createDir;
-export const createDirRecursive = ______fsInternal______.createDirRecursive;
-export const removeFile = ______fsInternal______.removeFile;
-export const removeDir = ______fsInternal______.removeDir;
-export const removeDirRecursive = ______fsInternal______.removeDirRecursive;
-export const copy = ______fsInternal______.copy;
+export const readDirSync = ______fsInternal______.readDirSync;
+export const mkdir = ______fsInternal______.mkdir;
+export const mkdirSync = ______fsInternal______.mkdirSync;
+export const rm = ______fsInternal______.rm;
+export const rmSync = ______fsInternal______.rmSync;
 export const rename = ______fsInternal______.rename;
-export const softLink = ______fsInternal______.softLink;
-export const hardLink = ______fsInternal______.hardLink;
+export const renameSync = ______fsInternal______.renameSync;
+export const copyFile = ______fsInternal______.copyFile;
+export const copyFileSync = ______fsInternal______.copyFileSync;
+export const symlink = ______fsInternal______.symlink;
+export const symlinkSync = ______fsInternal______.symlinkSync;
+export const link = ______fsInternal______.link;
+export const linkSync = ______fsInternal______.linkSync;
+export const readlink = ______fsInternal______.readlink;
+export const readlinkSync = ______fsInternal______.readlinkSync;
 
-export const sync = ______fsInternal______.sync;
+export const FileHandle = ______fsInternal______.FileHandle;
+
+// The previous API of the module is kept as aliases, so that existing scripts continue to work.
+// Operations which modify the file system resolve with whether they succeeded, instead of rejecting.
+const succeeded = promise => promise.then(() => true, () => false);
+
+function succeededSync(operation) {
+	try {
+		operation();
+		return true;
+	} catch {
+		return false;
+	}
+}
+
+export const readBinary = path => readFile(path);
+export const readString = path => readTextFile(path);
+export const write = (path, contents) => succeeded(writeFile(path, contents));
+export const createDir = path => succeeded(mkdir(path));
+export const createDirRecursive = path => succeeded(mkdir(path, { recursive: true }));
+export const removeFile = path => succeeded(rm(path));
+export const removeDir = path => succeeded(rm(path));
+export const removeDirRecursive = path => succeeded(rm(path, { recursive: true }));
+export const copy = (from, to) => succeeded(copyFile(from, to));
+export const softLink = (original, path) => succeeded(symlink(original, path));
+export const hardLink = (original, path) => succeeded(link(original, path));
+
+export const sync = Object.freeze({
+	readBinary: path => readFileSync(path),
+	readString: path => readTextFileSync(path),
+	readDir: path => readDirSync(path).map(entry => entry.name).sort(),
+	write: (path, contents) => succeededSync(() => writeFileSync(path, contents)),
+	createDir: path => succeededSync(() => mkdirSync(path)),
+	createDirRecursive: path => succeededSync(() => mkdirSync(path, { recursive: true })),
+	removeFile: path => succeededSync(() => rmSync(path)),
+	removeDir: path => succeededSync(() => rmSync(path)),
+	removeDirRecursive: path => succeededSync(() => rmSync(path, { recursive: true })),
+	copy: (from, to) => succeededSync(() => copyFileSync(from, to)),
+	rename: (from, to) => succeededSync(() => renameSync(from, to)),
+	softLink: (original, path) => succeededSync(() => symlinkSync(original, path)),
+	hardLink: (original, path) => succeededSync(() => linkSync(original, path)),
+});
+
+Object.assign(______fsInternal______, {
+	readBinary,
+	readString,
+	write,
+	createDir,
+	createDirRecursive,
+	removeFile,
+	removeDir,
+	removeDirRecursive,
+	copy,
+	softLink,
+	hardLink,
+	sync,
+});
 
 export default Object.freeze(______fsInternal______);
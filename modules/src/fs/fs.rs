@@ -4,407 +4,359 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/.
  */
 
-use std::{fs, os};
-use std::iter::Iterator;
-use std::path::Path;
+use std::io::Write;
 
 use futures::stream::StreamExt;
 use mozjs::jsapi::JSFunctionSpec;
+use tokio::fs;
+use tokio::io::AsyncWriteExt;
 use tokio_stream::wrappers::ReadDirStream;
 
-use ion::{Context, Error, Object, Promise, Result};
-use ion::flags::PropertyFlags;
+use ion::{AsyncIterator, ClassDefinition, Context, Error, Object, Promise, Result};
 use ion::typedarray::Uint8Array;
 use runtime::modules::NativeModule;
+use runtime::permissions::{check_read, check_write};
 use runtime::promise::future_to_promise;
 
-fn check_exists(path: &Path) -> Result<()> {
-	if path.exists() {
-		Err(Error::new(&format!("Path {} does not exist", path.to_str().unwrap()), None))
-	} else {
-		Ok(())
-	}
-}
-
-fn check_not_exists(path: &Path) -> Result<()> {
-	if !path.exists() {
-		Err(Error::new(&format!("Path {} exist", path.to_str().unwrap()), None))
-	} else {
-		Ok(())
-	}
-}
-
-fn check_is_file(path: &Path) -> Result<()> {
-	check_exists(path)?;
-	if path.is_file() {
-		Err(Error::new(&format!("Path {} is not a file", path.to_str().unwrap()), None))
-	} else {
-		Ok(())
-	}
-}
+use crate::fs::handle::{FileHandle, OpenedFile};
+use crate::fs::options::{DirEntry, FileData, FileInfo, FileOptions, fs_error, RecursiveOptions, WriteFileOptions};
 
-fn check_is_not_file(path: &Path) -> Result<()> {
-	check_exists(path)?;
-	if !path.is_file() {
-		Err(Error::new(&format!("Path {} is a file", path.to_str().unwrap()), None))
-	} else {
-		Ok(())
-	}
-}
-
-fn check_is_dir(path: &Path) -> Result<()> {
-	check_exists(path)?;
-	if path.is_dir() {
-		Err(Error::new(&format!("Path {} is not a directory", path.to_str().unwrap()), None))
-	} else {
-		Ok(())
-	}
+#[js_fn]
+fn readFile(cx: &Context, path: String) -> Option<Promise> {
+	future_to_promise::<_, _, Error>(cx, async move {
+		check_read(&path)?;
+		let bytes = fs::read(&path).await.map_err(|error| fs_error(error, "read", &path))?;
+		Ok(Uint8Array::from(bytes))
+	})
 }
 
-fn check_is_not_dir(path: &Path) -> Result<()> {
-	check_exists(path)?;
-	if !path.is_dir() {
-		Err(Error::new(&format!("Path {} is a directory", path.to_str().unwrap()), None))
-	} else {
-		Ok(())
-	}
+#[js_fn]
+fn readFileSync(path: String) -> Result<Uint8Array> {
+	check_read(&path)?;
+	let bytes = std::fs::read(&path).map_err(|error| fs_error(error, "read", &path))?;
+	Ok(Uint8Array::from(bytes))
 }
 
 #[js_fn]
-fn readBinary(cx: &Context, path_str: String) -> Option<Promise> {
+fn readTextFile(cx: &Context, path: String) -> Option<Promise> {
 	future_to_promise(cx, async move {
-		let path = Path::new(&path_str);
-
-		check_is_file(path)?;
-		if let Ok(bytes) = tokio::fs::read(&path).await {
-			Ok(Uint8Array::from(bytes))
-		} else {
-			Err(Error::new(&format!("Could not read file: {}", path_str), None))
-		}
+		check_read(&path)?;
+		fs::read_to_string(&path).await.map_err(|error| fs_error(error, "read", &path))
 	})
 }
 
 #[js_fn]
-fn readBinarySync(path_str: String) -> Result<Uint8Array> {
-	let path = Path::new(&path_str);
-
-	check_is_file(path)?;
-	if let Ok(bytes) = fs::read(path) {
-		Ok(Uint8Array::from(bytes))
-	} else {
-		Err(Error::new(&format!("Could not read file: {}", path_str), None))
-	}
+fn readTextFileSync(path: String) -> Result<String> {
+	check_read(&path)?;
+	std::fs::read_to_string(&path).map_err(|error| fs_error(error, "read", &path))
 }
 
+/// Writes a string or the bytes of a buffer to a file, replacing its contents unless `append` is set.
 #[js_fn]
-fn readString(cx: &Context, path_str: String) -> Option<Promise> {
+fn writeFile(cx: &Context, path: String, data: FileData, options: Option<WriteFileOptions>) -> Option<Promise> {
+	let options = fs::OpenOptions::from(options.unwrap_or_default().open_options());
 	future_to_promise(cx, async move {
-		let path = Path::new(&path_str);
-
-		check_is_file(path)?;
-		if let Ok(str) = tokio::fs::read_to_string(path).await {
-			Ok(str)
-		} else {
-			Err(Error::new(&format!("Could not read file: {}", path_str), None))
-		}
+		check_write(&path)?;
+		let write = async {
+			let mut file = options.open(&path).await?;
+			file.write_all(&data.0).await?;
+			file.flush().await
+		};
+		write.await.map_err(|error| fs_error(error, "write", &path))
 	})
 }
 
 #[js_fn]
-fn readStringSync(path_str: String) -> Result<String> {
-	let path = Path::new(&path_str);
-
-	check_is_file(path)?;
-	if let Ok(str) = fs::read_to_string(path) {
-		Ok(str)
-	} else {
-		Err(Error::new(&format!("Could not read file: {}", path_str), None))
-	}
+fn writeFileSync(path: String, data: FileData, options: Option<WriteFileOptions>) -> Result<()> {
+	check_write(&path)?;
+	let options = options.unwrap_or_default().open_options();
+	let write = || options.open(&path)?.write_all(&data.0);
+	write().map_err(|error| fs_error(error, "write", &path))
 }
 
+/// Opens a file, and resolves with a `FileHandle` to it.
 #[js_fn]
-fn readDir(cx: &Context, path_str: String) -> Option<Promise> {
+fn open(cx: &Context, path: String, options: Option<FileOptions>) -> Option<Promise> {
+	let options = options.unwrap_or_default();
+	let (read, write) = options.access();
+	let options = fs::OpenOptions::from(options.open_options());
 	future_to_promise::<_, _, Error>(cx, async move {
-		let path = Path::new(&path_str);
-
-		check_is_dir(path)?;
-		if let Ok(dir) = tokio::fs::read_dir(path).await {
-			let entries: Vec<_> = ReadDirStream::new(dir).filter_map(|entry| async move { entry.ok() }).collect().await;
-			let mut str_entries: Vec<String> = entries.iter().map(|entry| entry.file_name().into_string().unwrap()).collect();
-			str_entries.sort();
-
-			Ok(str_entries)
-		} else {
-			Ok(Vec::new())
+		if read {
+			check_read(&path)?;
+		}
+		if write {
+			check_write(&path)?;
 		}
+		let file = options.open(&path).await.map_err(|error| fs_error(error, "open", &path))?;
+		Ok(OpenedFile { path, file })
 	})
 }
 
 #[js_fn]
-fn readDirSync(path_str: String) -> Result<Vec<String>> {
-	let path = Path::new(&path_str);
-
-	check_is_dir(path)?;
-	if let Ok(dir) = fs::read_dir(path) {
-		let entries: Vec<_> = dir.filter_map(|entry| entry.ok()).collect();
-		let mut str_entries: Vec<String> = entries.iter().map(|entry| entry.file_name().into_string().unwrap()).collect();
-		str_entries.sort();
-
-		Ok(str_entries)
-	} else {
-		Ok(Vec::new())
-	}
-}
-
-#[js_fn]
-fn write(cx: &Context, path_str: String, contents: String) -> Option<Promise> {
+fn stat(cx: &Context, path: String) -> Option<Promise> {
 	future_to_promise::<_, _, Error>(cx, async move {
-		let path = Path::new(&path_str);
-
-		check_is_not_dir(path)?;
-		Ok(tokio::fs::write(path, contents).await.is_ok())
+		check_read(&path)?;
+		let metadata = fs::metadata(&path).await.map_err(|error| fs_error(error, "stat", &path))?;
+		Ok(FileInfo(metadata))
 	})
 }
 
 #[js_fn]
-fn writeSync(path_str: String, contents: String) -> Result<bool> {
-	let path = Path::new(&path_str);
-
-	check_is_not_dir(path)?;
-	Ok(fs::write(path, contents).is_ok())
+fn statSync(path: String) -> Result<FileInfo> {
+	check_read(&path)?;
+	let metadata = std::fs::metadata(&path).map_err(|error| fs_error(error, "stat", &path))?;
+	Ok(FileInfo(metadata))
 }
 
+/// Returns the metadata of a path, without following symbolic links.
 #[js_fn]
-fn createDir(cx: &Context, path_str: String) -> Option<Promise> {
+fn lstat(cx: &Context, path: String) -> Option<Promise> {
 	future_to_promise::<_, _, Error>(cx, async move {
-		let path = Path::new(&path_str);
-
-		check_is_not_file(path)?;
-		Ok(tokio::fs::create_dir(path).await.is_ok())
+		check_read(&path)?;
+		let metadata = fs::symlink_metadata(&path).await.map_err(|error| fs_error(error, "stat", &path))?;
+		Ok(FileInfo(metadata))
 	})
 }
 
 #[js_fn]
-fn createDirSync(path_str: String) -> Result<bool> {
-	let path = Path::new(&path_str);
-
-	check_is_not_file(path)?;
-	Ok(fs::create_dir(path).is_ok())
+fn lstatSync(path: String) -> Result<FileInfo> {
+	check_read(&path)?;
+	let metadata = std::fs::symlink_metadata(&path).map_err(|error| fs_error(error, "stat", &path))?;
+	Ok(FileInfo(metadata))
 }
 
+/// Reads the entries of a directory, and resolves with an async iterator over them.
+/// Entries which cannot be read, such as those removed while iterating, are skipped.
 #[js_fn]
-fn createDirRecursive(cx: &Context, path_str: String) -> Option<Promise> {
+fn readDir(cx: &Context, path: String) -> Option<Promise> {
 	future_to_promise::<_, _, Error>(cx, async move {
-		let path = Path::new(&path_str);
-
-		check_is_not_file(path)?;
-		Ok(tokio::fs::create_dir_all(path).await.is_ok())
+		check_read(&path)?;
+		let dir = fs::read_dir(&path).await.map_err(|error| fs_error(error, "read directory", &path))?;
+		let entries = ReadDirStream::new(dir).filter_map(|entry| async move {
+			let entry = entry.ok()?;
+			let file_type = entry.file_type().await.ok()?;
+			Some(DirEntry {
+				name: entry.file_name().to_string_lossy().into_owned(),
+				file_type,
+			})
+		});
+		Ok(AsyncIterator::new(entries))
 	})
 }
 
+/// Reads the entries of a directory into an array, skipping entries which cannot be read.
 #[js_fn]
-fn createDirRecursiveSync(path_str: String) -> Result<bool> {
-	let path = Path::new(&path_str);
-
-	check_is_not_file(path)?;
-	Ok(fs::create_dir_all(path).is_ok())
+fn readDirSync(path: String) -> Result<Vec<DirEntry>> {
+	check_read(&path)?;
+	let dir = std::fs::read_dir(&path).map_err(|error| fs_error(error, "read directory", &path))?;
+	let entries = dir.filter_map(|entry| {
+		let entry = entry.ok()?;
+		let file_type = entry.file_type().ok()?;
+		Some(DirEntry {
+			name: entry.file_name().to_string_lossy().into_owned(),
+			file_type,
+		})
+	});
+	Ok(entries.collect())
 }
 
 #[js_fn]
-fn removeFile(cx: &Context, path_str: String) -> Option<Promise> {
-	future_to_promise::<_, _, Error>(cx, async move {
-		let path = Path::new(&path_str);
-
-		check_is_file(path)?;
-		Ok(tokio::fs::remove_file(path).await.is_ok())
+fn mkdir(cx: &Context, path: String, options: Option<RecursiveOptions>) -> Option<Promise> {
+	let recursive = options.unwrap_or_default().recursive;
+	future_to_promise(cx, async move {
+		check_write(&path)?;
+		let result = if recursive {
+			fs::create_dir_all(&path).await
+		} else {
+			fs::create_dir(&path).await
+		};
+		result.map_err(|error| fs_error(error, "create directory", &path))
 	})
 }
 
 #[js_fn]
-fn removeFileSync(path_str: String) -> Result<bool> {
-	let path = Path::new(&path_str);
-
-	check_is_file(path)?;
-	Ok(fs::remove_file(path).is_ok())
+fn mkdirSync(path: String, options: Option<RecursiveOptions>) -> Result<()> {
+	check_write(&path)?;
+	let result = if options.unwrap_or_default().recursive {
+		std::fs::create_dir_all(&path)
+	} else {
+		std::fs::create_dir(&path)
+	};
+	result.map_err(|error| fs_error(error, "create directory", &path))
 }
 
+/// Removes a file or a directory. Directories which are not empty are only removed if `recursive` is set.
 #[js_fn]
-fn removeDir(cx: &Context, path_str: String) -> Option<Promise> {
-	future_to_promise::<_, _, Error>(cx, async move {
-		let path = Path::new(&path_str);
-
-		check_is_dir(path)?;
-		Ok(tokio::fs::remove_file(path).await.is_ok())
+fn rm(cx: &Context, path: String, options: Option<RecursiveOptions>) -> Option<Promise> {
+	let recursive = options.unwrap_or_default().recursive;
+	future_to_promise(cx, async move {
+		check_write(&path)?;
+		let remove = async {
+			let metadata = fs::symlink_metadata(&path).await?;
+			if !metadata.is_dir() {
+				fs::remove_file(&path).await
+			} else if recursive {
+				fs::remove_dir_all(&path).await
+			} else {
+				fs::remove_dir(&path).await
+			}
+		};
+		remove.await.map_err(|error| fs_error(error, "remove", &path))
 	})
 }
 
 #[js_fn]
-fn removeDirSync(path_str: String) -> Result<bool> {
-	let path = Path::new(&path_str);
-
-	check_is_dir(path)?;
-	Ok(fs::remove_file(path).is_ok())
+fn rmSync(path: String, options: Option<RecursiveOptions>) -> Result<()> {
+	check_write(&path)?;
+	let recursive = options.unwrap_or_default().recursive;
+	let remove = || {
+		let metadata = std::fs::symlink_metadata(&path)?;
+		if !metadata.is_dir() {
+			std::fs::remove_file(&path)
+		} else if recursive {
+			std::fs::remove_dir_all(&path)
+		} else {
+			std::fs::remove_dir(&path)
+		}
+	};
+	remove().map_err(|error| fs_error(error, "remove", &path))
 }
 
 #[js_fn]
-fn removeDirRecursive(cx: &Context, path_str: String) -> Option<Promise> {
-	future_to_promise::<_, _, Error>(cx, async move {
-		let path = Path::new(&path_str);
-
-		check_is_dir(path)?;
-		Ok(tokio::fs::remove_dir_all(path).await.is_ok())
+fn rename(cx: &Context, from: String, to: String) -> Option<Promise> {
+	future_to_promise(cx, async move {
+		check_read(&from)?;
+		check_write(&from)?;
+		check_write(&to)?;
+		let result = fs::rename(&from, &to).await;
+		result.map_err(|error| fs_error(error, "rename", &format!("{} to {}", from, to)))
 	})
 }
 
 #[js_fn]
-fn removeDirRecursiveSync(path_str: String) -> Result<bool> {
-	let path = Path::new(&path_str);
-
-	check_is_dir(path)?;
-	Ok(fs::remove_dir_all(path).is_ok())
+fn renameSync(from: String, to: String) -> Result<()> {
+	check_read(&from)?;
+	check_write(&from)?;
+	check_write(&to)?;
+	std::fs::rename(&from, &to).map_err(|error| fs_error(error, "rename", &format!("{} to {}", from, to)))
 }
 
 #[js_fn]
-fn copy(cx: &Context, from_str: String, to_str: String) -> Option<Promise> {
+fn copyFile(cx: &Context, from: String, to: String) -> Option<Promise> {
 	future_to_promise::<_, _, Error>(cx, async move {
-		let from = Path::new(&from_str);
-		let to = Path::new(&to_str);
-
-		check_is_not_dir(from)?;
-		check_is_not_dir(to)?;
-		Ok(tokio::fs::copy(from, to).await.is_ok())
+		check_read(&from)?;
+		check_write(&to)?;
+		fs::copy(&from, &to)
+			.await
+			.map_err(|error| fs_error(error, "copy", &format!("{} to {}", from, to)))?;
+		Ok(())
 	})
 }
 
 #[js_fn]
-fn copySync(from_str: String, to_str: String) -> Result<bool> {
-	let from = Path::new(&from_str);
-	let to = Path::new(&to_str);
-
-	check_is_not_dir(from)?;
-	check_is_not_dir(to)?;
-	Ok(fs::copy(from, to).is_ok())
+fn copyFileSync(from: String, to: String) -> Result<()> {
+	check_read(&from)?;
+	check_write(&to)?;
+	std::fs::copy(&from, &to).map_err(|error| fs_error(error, "copy", &format!("{} to {}", from, to)))?;
+	Ok(())
 }
 
+/// Creates a symbolic link at `path` which points to `original`.
+/// On Windows, `original` must exist to determine whether a file or directory link is created.
 #[js_fn]
-fn rename(cx: &Context, from_str: String, to_str: String) -> Option<Promise> {
-	future_to_promise::<_, _, Error>(cx, async move {
-		let from = Path::new(&from_str);
-		let to = Path::new(&to_str);
-
-		check_is_not_dir(from)?;
-		check_is_not_dir(to)?;
-		Ok(tokio::fs::rename(from, to).await.is_ok())
+fn symlink(cx: &Context, original: String, path: String) -> Option<Promise> {
+	future_to_promise(cx, async move {
+		check_read(&original)?;
+		check_write(&path)?;
+		#[cfg(unix)]
+		let result = fs::symlink(&original, &path).await;
+		#[cfg(windows)]
+		let result = match fs::metadata(&original).await {
+			Ok(metadata) if metadata.is_dir() => fs::symlink_dir(&original, &path).await,
+			Ok(_) => fs::symlink_file(&original, &path).await,
+			Err(error) => Err(error),
+		};
+		result.map_err(|error| fs_error(error, "link", &format!("{} to {}", path, original)))
 	})
 }
 
 #[js_fn]
-fn renameSync(from_str: String, to_str: String) -> Result<bool> {
-	let from = Path::new(&from_str);
-	let to = Path::new(&to_str);
-
-	check_is_not_dir(from)?;
-	check_is_not_dir(to)?;
-	Ok(fs::rename(from, to).is_ok())
-}
-
+fn symlinkSync(original: String, path: String) -> Result<()> {
+	check_read(&original)?;
+	check_write(&path)?;
+	#[cfg(unix)]
+	let result = std::os::unix::fs::symlink(&original, &path);
+	#[cfg(windows)]
+	let result = match std::fs::metadata(&original) {
+		Ok(metadata) if metadata.is_dir() => std::os::windows::fs::symlink_dir(&original, &path),
+		Ok(_) => std::os::windows::fs::symlink_file(&original, &path),
+		Err(error) => Err(error),
+	};
+	result.map_err(|error| fs_error(error, "link", &format!("{} to {}", path, original)))
+}
+
+/// Creates a hard link at `path` to the file at `original`.
 #[js_fn]
-fn softLink(cx: &Context, original_str: String, link_str: String) -> Option<Promise> {
-	future_to_promise::<_, _, Error>(cx, async move {
-		let original = Path::new(&original_str);
-		let link = Path::new(&link_str);
-
-		check_not_exists(link)?;
-		#[cfg(target_family = "unix")]
-		{
-			Ok(tokio::fs::symlink(original, link).await.is_ok())
-		}
-		#[cfg(target_family = "windows")]
-		{
-			if original.is_file() {
-				Ok(tokio::fs::symlink_file(original, link).await.is_ok())
-			} else if original.is_dir() {
-				Ok(tokio::fs::symlink_dir(original, link).await.is_ok())
-			} else {
-				Ok(false)
-			}
-		}
+fn link(cx: &Context, original: String, path: String) -> Option<Promise> {
+	future_to_promise(cx, async move {
+		check_read(&original)?;
+		check_write(&path)?;
+		let result = fs::hard_link(&original, &path).await;
+		result.map_err(|error| fs_error(error, "link", &format!("{} to {}", path, original)))
 	})
 }
 
 #[js_fn]
-fn softLinkSync(original_str: String, link_str: String) -> Result<bool> {
-	let original = Path::new(&original_str);
-	let link = Path::new(&link_str);
-
-	check_not_exists(link)?;
-	#[cfg(target_family = "unix")]
-	{
-		Ok(os::unix::fs::symlink(original, link).is_ok())
-	}
-	#[cfg(target_family = "windows")]
-	{
-		if original.is_file() {
-			Ok(os::windows::fs::symlink_file(original, link).is_ok())
-		} else if original.is_dir() {
-			Ok(os::windows::fs::symlink_dir(original, link).is_ok())
-		} else {
-			Ok(false)
-		}
-	}
+fn linkSync(original: String, path: String) -> Result<()> {
+	check_read(&original)?;
+	check_write(&path)?;
+	std::fs::hard_link(&original, &path).map_err(|error| fs_error(error, "link", &format!("{} to {}", path, original)))
 }
 
 #[js_fn]
-fn hardLink(cx: &Context, original_str: String, link_str: String) -> Option<Promise> {
+fn readlink(cx: &Context, path: String) -> Option<Promise> {
 	future_to_promise::<_, _, Error>(cx, async move {
-		let original = Path::new(&original_str);
-		let link = Path::new(&link_str);
-
-		check_not_exists(link)?;
-		Ok(tokio::fs::hard_link(original, link).await.is_ok())
+		check_read(&path)?;
+		let target = fs::read_link(&path).await.map_err(|error| fs_error(error, "read link", &path))?;
+		Ok(target.to_string_lossy().into_owned())
 	})
 }
 
 #[js_fn]
-fn hardLinkSync(original_str: String, link_str: String) -> Result<bool> {
-	let original = Path::new(&original_str);
-	let link = Path::new(&link_str);
-
-	check_not_exists(link)?;
-	Ok(fs::hard_link(original, link).is_ok())
-}
-
-const SYNC_FUNCTIONS: &[JSFunctionSpec] = &[
-	function_spec!(readBinarySync, "readBinary", 1),
-	function_spec!(readStringSync, "readString", 1),
-	function_spec!(readDirSync, "readDir", 1),
-	function_spec!(writeSync, "write", 2),
-	function_spec!(createDirSync, "createDir", 1),
-	function_spec!(createDirRecursiveSync, "createDirRecursive", 1),
-	function_spec!(removeFileSync, "removeFile", 1),
-	function_spec!(removeDirSync, "removeDir", 1),
-	function_spec!(removeDirRecursiveSync, "removeDirRecursive", 1),
-	function_spec!(copySync, "copy", 2),
-	function_spec!(renameSync, "rename", 2),
-	function_spec!(softLinkSync, "softLink", 2),
-	function_spec!(hardLinkSync, "hardLink", 2),
-	JSFunctionSpec::ZERO,
-];
-
-const ASYNC_FUNCTIONS: &[JSFunctionSpec] = &[
-	function_spec!(readBinary, 1),
-	function_spec!(readString, 1),
+fn readlinkSync(path: String) -> Result<String> {
+	check_read(&path)?;
+	let target = std::fs::read_link(&path).map_err(|error| fs_error(error, "read link", &path))?;
+	Ok(target.to_string_lossy().into_owned())
+}
+
+const FUNCTIONS: &[JSFunctionSpec] = &[
+	function_spec!(readFile, 1),
+	function_spec!(readFileSync, 1),
+	function_spec!(readTextFile, 1),
+	function_spec!(readTextFileSync, 1),
+	function_spec!(writeFile, 2),
+	function_spec!(writeFileSync, 2),
+	function_spec!(open, 1),
+	function_spec!(stat, 1),
+	function_spec!(statSync, 1),
+	function_spec!(lstat, 1),
+	function_spec!(lstatSync, 1),
 	function_spec!(readDir, 1),
-	function_spec!(write, 2),
-	function_spec!(createDir, 1),
-	function_spec!(createDirRecursive, 1),
-	function_spec!(removeFile, 1),
-	function_spec!(removeDir, 1),
-	function_spec!(removeDirRecursive, 1),
-	function_spec!(copy, 2),
+	function_spec!(readDirSync, 1),
+	function_spec!(mkdir, 1),
+	function_spec!(mkdirSync, 1),
+	function_spec!(rm, 1),
+	function_spec!(rmSync, 1),
 	function_spec!(rename, 2),
-	function_spec!(softLink, 2),
-	function_spec!(hardLink, 2),
+	function_spec!(renameSync, 2),
+	function_spec!(copyFile, 2),
+	function_spec!(copyFileSync, 2),
+	function_spec!(symlink, 2),
+	function_spec!(symlinkSync, 2),
+	function_spec!(link, 2),
+	function_spec!(linkSync, 2),
+	function_spec!(readlink, 1),
+	function_spec!(readlinkSync, 1),
 	JSFunctionSpec::ZERO,
 ];
 
@@ -417,12 +369,7 @@ impl NativeModule for FileSystem {
 
 	fn module(cx: &Context) -> Option<Object> {
 		let mut fs = Object::new(cx);
-		let mut sync = Object::new(cx);
-
-		if unsafe { fs.define_methods(cx, ASYNC_FUNCTIONS) }
-			&& unsafe { sync.define_methods(cx, SYNC_FUNCTIONS) }
-			&& fs.define_as(cx, "sync", &sync, PropertyFlags::CONSTANT_ENUMERATED)
-		{
+		if unsafe { fs.define_methods(cx, FUNCTIONS) } && FileHandle::init_class(cx, &mut fs).0 {
 			return Some(fs);
 		}
 		None
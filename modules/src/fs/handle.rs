@@ -0,0 +1,166 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::io;
+use std::io::SeekFrom;
+use std::rc::Rc;
+
+use futures::future::LocalBoxFuture;
+use futures::lock::Mutex;
+use mozjs::conversions::ConversionBehavior::EnforceRange;
+use tokio::fs::File;
+use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
+
+use ion::{ClassDefinition, Context, Error, ErrorKind, Promise, Result, Value};
+use ion::class::Reflector;
+use ion::conversions::{IntoValue, ToValue};
+use ion::typedarray::Uint8Array;
+use runtime::promise::future_to_promise;
+
+use crate::fs::options::{FileData, FileInfo, fs_error};
+
+const DEFAULT_READ_LENGTH: u32 = 16384;
+
+#[derive(Clone, Copy, Debug, Default, FromValue)]
+pub(crate) enum SeekMode {
+	#[default]
+	Start,
+	Current,
+	End,
+}
+
+/// Represents a file opened with `fs.open`, whose operations are performed in the order they are called.
+#[js_class]
+pub struct FileHandle {
+	reflector: Reflector,
+	#[ion(no_trace)]
+	path: String,
+	#[ion(no_trace)]
+	file: Rc<Mutex<Option<File>>>,
+}
+
+impl FileHandle {
+	/// Runs an operation on the file, once all previous operations have completed.
+	fn operate<F, O>(&self, cx: &Context, operation: &'static str, f: F) -> Option<Promise>
+	where
+		F: for<'f> FnOnce(&'f mut File) -> LocalBoxFuture<'f, io::Result<O>> + 'static,
+		O: for<'cx> IntoValue<'cx> + 'static,
+	{
+		let file = Rc::clone(&self.file);
+		let path = self.path.clone();
+		future_to_promise(cx, async move {
+			let mut file = file.lock().await;
+			let file = file.as_mut().ok_or_else(|| Error::new(&format!("File {} is closed", path), None))?;
+			f(file).await.map_err(|error| fs_error(error, operation, &path))
+		})
+	}
+}
+
+#[js_class]
+impl FileHandle {
+	#[ion(constructor)]
+	pub fn constructor() -> Result<FileHandle> {
+		Err(Error::new("FileHandle has no constructor.", ErrorKind::Type))
+	}
+
+	/// Reads up to `length` bytes from the current position of the file, or `null` once the end of the file is reached.
+	/// The buffer grows as bytes are read, so large lengths do not allocate more than the rest of the file.
+	pub fn read(&self, cx: &Context, #[ion(convert = EnforceRange)] length: Option<u32>) -> Option<Promise> {
+		let length = length.unwrap_or(DEFAULT_READ_LENGTH);
+		self.operate(cx, "read", move |file| {
+			Box::pin(async move {
+				let mut bytes = Vec::new();
+				file.take(u64::from(length)).read_to_end(&mut bytes).await?;
+				if bytes.is_empty() && length != 0 {
+					return Ok(None);
+				}
+				Ok(Some(Uint8Array::from(bytes)))
+			})
+		})
+	}
+
+	/// Writes all the given data at the current position of the file, and resolves with the number of bytes written.
+	pub fn write(&self, cx: &Context, data: FileData) -> Option<Promise> {
+		self.operate(cx, "write", move |file| {
+			Box::pin(async move {
+				file.write_all(&data.0).await?;
+				file.flush().await?;
+				Ok(data.0.len() as f64)
+			})
+		})
+	}
+
+	/// Moves the position of the file, and resolves with the new position from the start of the file.
+	/// Throws a `RangeError` if the offset is not an integer, or is negative when seeking from the start.
+	pub fn seek(&self, cx: &Context, offset: f64, mode: Option<SeekMode>) -> Result<Option<Promise>> {
+		if !offset.is_finite() || offset.fract() != 0.0 {
+			return Err(Error::new("Offset must be an integer", ErrorKind::Range));
+		}
+		let offset = offset as i64;
+		let seek = match mode.unwrap_or_default() {
+			SeekMode::Start if offset < 0 => return Err(Error::new("Offset from the start must not be negative", ErrorKind::Range)),
+			SeekMode::Start => SeekFrom::Start(offset as u64),
+			SeekMode::Current => SeekFrom::Current(offset),
+			SeekMode::End => SeekFrom::End(offset),
+		};
+		Ok(self.operate(cx, "seek", move |file| Box::pin(async move { Ok(file.seek(seek).await? as f64) })))
+	}
+
+	pub fn stat(&self, cx: &Context) -> Option<Promise> {
+		self.operate(cx, "stat", |file| Box::pin(async move { Ok(FileInfo(file.metadata().await?)) }))
+	}
+
+	/// Flushes all data and metadata of the file to the disk.
+	pub fn sync(&self, cx: &Context) -> Option<Promise> {
+		self.operate(cx, "sync", |file| Box::pin(async move { file.sync_all().await }))
+	}
+
+	#[ion(name = "syncData")]
+	pub fn sync_data(&self, cx: &Context) -> Option<Promise> {
+		self.operate(cx, "sync", |file| Box::pin(async move { file.sync_data().await }))
+	}
+
+	pub fn truncate(&self, cx: &Context, #[ion(convert = EnforceRange)] length: Option<u64>) -> Option<Promise> {
+		let length = length.unwrap_or(0);
+		self.operate(cx, "truncate", move |file| Box::pin(async move { file.set_len(length).await }))
+	}
+
+	/// Closes the file, once all previous operations have completed. Further operations on the handle are rejected.
+	pub fn close(&self, cx: &Context) -> Option<Promise> {
+		let file = Rc::clone(&self.file);
+		future_to_promise::<_, _, Error>(cx, async move {
+			if let Some(mut file) = file.lock().await.take() {
+				let _ = file.flush().await;
+			}
+			Ok(())
+		})
+	}
+
+	#[ion(get)]
+	pub fn get_path(&self) -> String {
+		self.path.clone()
+	}
+}
+
+/// Represents a file which has just been opened, which is converted to a [FileHandle] when its promise resolves.
+pub(crate) struct OpenedFile {
+	pub(crate) path: String,
+	pub(crate) file: File,
+}
+
+impl<'cx> IntoValue<'cx> for OpenedFile {
+	fn into_value(self: Box<Self>, cx: &'cx Context, value: &mut Value) {
+		let handle = FileHandle {
+			reflector: Reflector::default(),
+			path: self.path,
+			file: Rc::new(Mutex::new(Some(self.file))),
+		};
+		cx.root_object(FileHandle::new_object(cx, Box::new(handle)))
+			.handle()
+			.get()
+			.to_value(cx, value);
+	}
+}
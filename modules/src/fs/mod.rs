@@ -5,5 +5,8 @@
  */
 
 pub use fs::*;
+pub(crate) use options::FileData;
 
 mod fs;
+mod handle;
+mod options;
@@ -0,0 +1,193 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::fs::{FileType, Metadata, OpenOptions};
+use std::io;
+use std::io::ErrorKind as IoErrorKind;
+
+use mozjs::conversions::ConversionBehavior::EnforceRange;
+use mozjs::typedarray::{ArrayBuffer, ArrayBufferView};
+
+use ion::{Context, Error, ErrorKind, Object, Result, Value};
+use ion::conversions::{FromValue, ToValue};
+
+/// Converts an I/O error from an operation on a path into an [Error].
+/// The `code` of the error is set to the name of the corresponding system error, where one is known.
+pub(crate) fn fs_error(error: io::Error, operation: &str, path: &str) -> Error {
+	let code = match error.kind() {
+		IoErrorKind::NotFound => Some("ENOENT"),
+		IoErrorKind::PermissionDenied => Some("EACCES"),
+		IoErrorKind::AlreadyExists => Some("EEXIST"),
+		IoErrorKind::InvalidInput => Some("EINVAL"),
+		IoErrorKind::Unsupported => Some("ENOTSUP"),
+		_ => None,
+	};
+
+	let error = Error::new(&format!("Could not {} {}: {}", operation, path, error), None);
+	match code {
+		Some(code) => error.with_code(code),
+		None => error,
+	}
+}
+
+/// Represents the contents written to a file, which are either the bytes of a buffer or the UTF-8 encoding of a string.
+pub(crate) struct FileData(pub(crate) Vec<u8>);
+
+impl<'cx> FromValue<'cx> for FileData {
+	type Config = ();
+
+	fn from_value(cx: &'cx Context, value: &Value, strict: bool, _: ()) -> Result<FileData> {
+		if value.handle().is_string() {
+			return String::from_value(cx, value, true, ()).map(|string| FileData(string.into_bytes()));
+		}
+		if let Ok(buffer) = ArrayBuffer::from_value(cx, value, strict, ()) {
+			return Ok(FileData(unsafe { buffer.as_slice() }.to_vec()));
+		}
+		ArrayBufferView::from_value(cx, value, strict, ())
+			.map(|view| FileData(unsafe { view.as_slice() }.to_vec()))
+			.map_err(|_| Error::new("Expected String, ArrayBuffer or ArrayBufferView", ErrorKind::Type))
+	}
+}
+
+#[derive(Default, FromValue)]
+pub(crate) struct FileOptions {
+	#[ion(default)]
+	read: Option<bool>,
+	#[ion(default)]
+	write: bool,
+	#[ion(default)]
+	append: bool,
+	#[ion(default)]
+	truncate: bool,
+	#[ion(default)]
+	create: bool,
+	#[ion(default, name = "createNew")]
+	create_new: bool,
+	#[ion(convert = EnforceRange)]
+	mode: Option<u32>,
+}
+
+impl FileOptions {
+	/// Returns whether the file is opened for reading and for writing.
+	/// Files are opened for reading, unless any other access is requested.
+	pub(crate) fn access(&self) -> (bool, bool) {
+		let write = self.write || self.append || self.truncate || self.create || self.create_new;
+		(self.read.unwrap_or(!write), write)
+	}
+
+	pub(crate) fn open_options(&self) -> OpenOptions {
+		let (read, write) = self.access();
+		let mut options = OpenOptions::new();
+		options
+			.read(read)
+			.write(write)
+			.append(self.append)
+			.truncate(self.truncate)
+			.create(self.create)
+			.create_new(self.create_new);
+		set_mode(&mut options, self.mode);
+		options
+	}
+}
+
+#[derive(FromValue)]
+pub(crate) struct WriteFileOptions {
+	#[ion(default)]
+	append: bool,
+	#[ion(default = true)]
+	create: bool,
+	#[ion(default, name = "createNew")]
+	create_new: bool,
+	#[ion(convert = EnforceRange)]
+	mode: Option<u32>,
+}
+
+impl Default for WriteFileOptions {
+	fn default() -> WriteFileOptions {
+		WriteFileOptions {
+			append: false,
+			create: true,
+			create_new: false,
+			mode: None,
+		}
+	}
+}
+
+impl WriteFileOptions {
+	/// Returns the [OpenOptions] for writing a file, which replace its contents unless `append` is set.
+	pub(crate) fn open_options(&self) -> OpenOptions {
+		let mut options = OpenOptions::new();
+		options
+			.write(true)
+			.append(self.append)
+			.truncate(!self.append)
+			.create(self.create)
+			.create_new(self.create_new);
+		set_mode(&mut options, self.mode);
+		options
+	}
+}
+
+#[cfg(unix)]
+fn set_mode(options: &mut OpenOptions, mode: Option<u32>) {
+	use std::os::unix::fs::OpenOptionsExt;
+
+	if let Some(mode) = mode {
+		options.mode(mode);
+	}
+}
+
+#[cfg(not(unix))]
+fn set_mode(_: &mut OpenOptions, _: Option<u32>) {}
+
+#[derive(Default, FromValue)]
+pub(crate) struct RecursiveOptions {
+	#[ion(default)]
+	pub(crate) recursive: bool,
+}
+
+/// Represents the metadata of a file, returned by `stat`, `lstat` and `FileHandle.stat`.
+pub(crate) struct FileInfo(pub(crate) Metadata);
+
+impl<'cx> ToValue<'cx> for FileInfo {
+	fn to_value(&self, cx: &'cx Context, value: &mut Value) {
+		let metadata = &self.0;
+		let mut object = Object::new(cx);
+		set_file_type(cx, &mut object, metadata.file_type());
+		object.set_as(cx, "size", &(metadata.len() as f64));
+		object.set_as(cx, "readonly", &metadata.permissions().readonly());
+		object.set_as(cx, "modified", &metadata.modified().ok());
+		object.set_as(cx, "accessed", &metadata.accessed().ok());
+		object.set_as(cx, "created", &metadata.created().ok());
+		#[cfg(unix)]
+		{
+			use std::os::unix::fs::MetadataExt;
+			object.set_as(cx, "mode", &metadata.mode());
+		}
+		object.to_value(cx, value);
+	}
+}
+
+/// Represents an entry of a directory, yielded by `readDir`.
+pub(crate) struct DirEntry {
+	pub(crate) name: String,
+	pub(crate) file_type: FileType,
+}
+
+impl<'cx> ToValue<'cx> for DirEntry {
+	fn to_value(&self, cx: &'cx Context, value: &mut Value) {
+		let mut object = Object::new(cx);
+		object.set_as(cx, "name", &self.name);
+		set_file_type(cx, &mut object, self.file_type);
+		object.to_value(cx, value);
+	}
+}
+
+fn set_file_type(cx: &Context, object: &mut Object, file_type: FileType) {
+	object.set_as(cx, "isFile", &file_type.is_file());
+	object.set_as(cx, "isDirectory", &file_type.is_dir());
+	object.set_as(cx, "isSymlink", &file_type.is_symlink());
+}
@@ -0,0 +1,123 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::net::TcpListener as StdTcpListener;
+use std::rc::Rc;
+
+use hyper::{Body, Method, StatusCode};
+use hyper::header::{CONNECTION, SEC_WEBSOCKET_ACCEPT, SEC_WEBSOCKET_KEY, SEC_WEBSOCKET_PROTOCOL, SEC_WEBSOCKET_VERSION, UPGRADE};
+use mozjs::jsapi::{JSFunctionSpec, JSObject};
+use tokio::net::TcpListener;
+use tokio::task::spawn_local;
+use tokio_tungstenite::tungstenite::handshake::derive_accept_key;
+use url::Url;
+
+use ion::{ClassDefinition, Context, Error, ErrorKind, Function, Object, Result};
+use runtime::event_loop::handles::{ActiveHandle, HandleKind};
+use runtime::event_loop::KeepAlive;
+use runtime::globals::fetch::{Request, Response};
+use runtime::modules::NativeModule;
+use runtime::permissions::check_net;
+
+use crate::http::options::{ServeOptions, ServerTlsOptions, UpgradeOptions};
+use crate::http::server::{Handler, run, Server, ServerState};
+use crate::http::websocket::WebSocket;
+use crate::net::net_error;
+
+const DEFAULT_HOSTNAME: &str = "0.0.0.0";
+const DEFAULT_PORT: u16 = 8000;
+
+/// Serves HTTP on a port, calling `handler` with a `Request` for each incoming request.
+/// The handler returns a `Response`, or a promise which resolves with one.
+/// Requests can be upgraded to WebSocket connections with `upgradeWebSocket`.
+/// The server is shut down gracefully when the given `signal` is aborted.
+#[js_fn]
+fn serve(cx: &Context, handler: Function, options: Option<ServeOptions>) -> Result<*mut JSObject> {
+	let options = options.unwrap_or_default();
+	let hostname = options.hostname.as_deref().unwrap_or(DEFAULT_HOSTNAME);
+	let port = options.port.unwrap_or(DEFAULT_PORT);
+	let acceptor = options.tls.as_ref().map(ServerTlsOptions::acceptor).transpose()?;
+
+	let address = format!("{}:{}", hostname, port);
+	let error = |error| net_error(error, "listen on", &address);
+	check_net(hostname, Some(port))?;
+	let listener = StdTcpListener::bind((hostname, port)).map_err(error)?;
+	listener.set_nonblocking(true).map_err(error)?;
+	let listener = TcpListener::from_std(listener).map_err(error)?;
+	let addr = listener.local_addr().map_err(error)?;
+
+	let state = ServerState::new();
+	options.signal.on_abort({
+		let state = Rc::clone(&state);
+		move |_| state.shutdown()
+	});
+	let keep_alive = KeepAlive::for_handle(cx, ActiveHandle::new(cx, HandleKind::Server, format!("HTTP Server on {}", addr)));
+	spawn_local(run(listener, acceptor, Handler::new(cx, &handler), Rc::clone(&state), keep_alive));
+	Ok(Server::new_object(cx, Box::new(Server::new(addr, state))))
+}
+
+/// Upgrades a request received by a server to a WebSocket connection.
+/// Returns the `WebSocket`, and the `Response` which the handler returns to complete the upgrade.
+#[js_fn]
+fn upgradeWebSocket(cx: &Context, request: Object, options: Option<UpgradeOptions>) -> Result<*mut JSObject> {
+	let mut request = request;
+	if !Request::instance_of(cx, &request, None) {
+		return Err(Error::new("Expected Request", ErrorKind::Type));
+	}
+	let request = Request::get_mut_private(&mut request);
+	let mut url = Url::parse(&request.get_url())?;
+	let secure = url.scheme() == "https";
+	let _ = url.set_scheme(if secure { "wss" } else { "ws" });
+
+	let http = request.http_request_mut();
+	let headers = http.headers();
+	let header = |name| headers.get(name).and_then(|value| value.to_str().ok()).unwrap_or_default();
+	let has_token = |name, token: &str| header(name).split(',').any(|value| value.trim().eq_ignore_ascii_case(token));
+	if http.method() != Method::GET || !has_token(UPGRADE, "websocket") || !has_token(CONNECTION, "upgrade") || header(SEC_WEBSOCKET_VERSION) != "13"
+	{
+		return Err(Error::new("Request is not a WebSocket upgrade", ErrorKind::Type));
+	}
+	let key = headers
+		.get(SEC_WEBSOCKET_KEY)
+		.ok_or_else(|| Error::new("Request is missing the Sec-WebSocket-Key header", ErrorKind::Type))?;
+
+	let protocol = options.unwrap_or_default().protocol.unwrap_or_default();
+	let mut response = hyper::Response::builder()
+		.status(StatusCode::SWITCHING_PROTOCOLS)
+		.header(UPGRADE, "websocket")
+		.header(CONNECTION, "Upgrade")
+		.header(SEC_WEBSOCKET_ACCEPT, derive_accept_key(key.as_bytes()));
+	if !protocol.is_empty() {
+		response = response.header(SEC_WEBSOCKET_PROTOCOL, &protocol);
+	}
+	let response = response.body(Body::empty())?;
+
+	let socket = WebSocket::accept(cx, hyper::upgrade::on(http), String::from(url.as_str()), protocol)?;
+	let response = cx.root_object(Response::new_object(cx, Box::new(Response::new_outgoing(cx, response))));
+
+	let mut result = Object::new(cx);
+	result.set_as(cx, "socket", &socket);
+	result.set_as(cx, "response", &Object::from(response));
+	Ok(result.handle().get())
+}
+
+const FUNCTIONS: &[JSFunctionSpec] = &[function_spec!(serve, 1), function_spec!(upgradeWebSocket, 1), JSFunctionSpec::ZERO];
+
+#[derive(Default)]
+pub struct Http;
+
+impl NativeModule for Http {
+	const NAME: &'static str = "http";
+	const SOURCE: &'static str = include_str!("http.js");
+
+	fn module(cx: &Context) -> Option<Object> {
+		let mut http = Object::new(cx);
+		if unsafe { http.define_methods(cx, FUNCTIONS) } && Server::init_class(cx, &mut http).0 && WebSocket::init_class(cx, &mut http).0 {
+			return Some(http);
+		}
+		None
+	}
+}
@@ -0,0 +1,62 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::sync::Arc;
+
+use mozjs::conversions::ConversionBehavior::EnforceRange;
+use rustls_pemfile::Item;
+use tokio_rustls::rustls::{Certificate, PrivateKey, ServerConfig};
+use tokio_rustls::TlsAcceptor;
+
+use ion::{Error, ErrorKind, Result};
+use runtime::globals::abort::Signal;
+
+#[derive(Default, FromValue)]
+pub(crate) struct ServeOptions {
+	#[ion(convert = EnforceRange)]
+	pub(crate) port: Option<u16>,
+	pub(crate) hostname: Option<String>,
+	/// Serves HTTPS instead of HTTP, with HTTP/2 or HTTP/1.1 negotiated with ALPN.
+	pub(crate) tls: Option<ServerTlsOptions>,
+	#[ion(default)]
+	pub(crate) signal: Signal,
+}
+
+#[derive(Default, FromValue)]
+pub(crate) struct UpgradeOptions {
+	/// Subprotocol selected by the server, which is sent in the `Sec-WebSocket-Protocol` header.
+	pub(crate) protocol: Option<String>,
+}
+
+#[derive(FromValue)]
+pub(crate) struct ServerTlsOptions {
+	/// PEM-encoded certificate chain of the server, starting with its own certificate.
+	cert: String,
+	/// PEM-encoded private key of the server, in PKCS #8, PKCS #1 or SEC1 format.
+	key: String,
+}
+
+impl ServerTlsOptions {
+	pub(crate) fn acceptor(&self) -> Result<TlsAcceptor> {
+		let certificates = rustls_pemfile::certs(&mut self.cert.as_bytes()).map_err(|_| Error::new("Invalid PEM Certificate", ErrorKind::Type))?;
+		let items = rustls_pemfile::read_all(&mut self.key.as_bytes()).map_err(|_| Error::new("Invalid PEM Private Key", ErrorKind::Type))?;
+		let key = items
+			.into_iter()
+			.find_map(|item| match item {
+				Item::PKCS8Key(key) | Item::RSAKey(key) | Item::ECKey(key) => Some(PrivateKey(key)),
+				_ => None,
+			})
+			.ok_or_else(|| Error::new("Expected PEM Private Key", ErrorKind::Type))?;
+
+		let mut config = ServerConfig::builder()
+			.with_safe_defaults()
+			.with_no_client_auth()
+			.with_single_cert(certificates.into_iter().map(Certificate).collect(), key)
+			.map_err(|error| Error::new(&format!("Invalid Certificate: {}", error), ErrorKind::Type))?;
+		config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+		Ok(TlsAcceptor::from(Arc::new(config)))
+	}
+}
@@ -0,0 +1,268 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::convert::Infallible;
+use std::future::Future;
+use std::net::SocketAddr;
+use std::pin::pin;
+use std::rc::Rc;
+
+use futures::future::{Either, select};
+use hyper::{Body, StatusCode};
+use hyper::header::HOST;
+use hyper::rt::Executor;
+use hyper::server::conn::Http;
+use hyper::service::service_fn;
+use mozjs::jsapi::{JSContext, JSFunction};
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{mpsc, watch};
+use tokio::task::spawn_local;
+use tokio_rustls::TlsAcceptor;
+use url::Url;
+
+use ion::{ClassDefinition, Context, Error, ErrorKind, Exception, Function, Object, PersistentRooted, Promise, PromiseFuture, Result, ResultExc};
+use ion::class::Reflector;
+use ion::conversions::ToValue;
+use runtime::config::LogLevel;
+use runtime::event_loop::KeepAlive;
+use runtime::globals::console;
+use runtime::globals::fetch::{Request, Response};
+use runtime::promise::future_to_promise;
+
+use crate::net::{Address, Backoff};
+
+/// Runs the tasks spawned by HTTP/2 connections on the current thread, as the futures of handlers are not [Send].
+#[derive(Clone, Copy)]
+struct LocalExecutor;
+
+impl<F: Future + 'static> Executor<F> for LocalExecutor {
+	fn execute(&self, future: F) {
+		spawn_local(future);
+	}
+}
+
+/// Calls the JavaScript handler of a [Server] with each incoming request.
+pub(crate) struct Handler {
+	cx: *mut JSContext,
+	function: PersistentRooted<*mut JSFunction>,
+}
+
+impl Handler {
+	pub(crate) fn new(cx: &Context, function: &Function) -> Handler {
+		Handler {
+			cx: cx.as_ptr(),
+			function: PersistentRooted::new(function.get()),
+		}
+	}
+
+	/// Responds to a request with the [Response] returned by the handler.
+	/// If the handler throws, or does not return a [Response], the error is reported through the console,
+	/// and `500 Internal Server Error` is sent instead.
+	async fn respond(&self, request: hyper::Request<Body>, scheme: &str, local: SocketAddr) -> hyper::Response<Body> {
+		let cx = unsafe { Context::new_unchecked(self.cx) };
+		match self.call(&cx, request, scheme, local).await {
+			Ok(response) => response,
+			Err(exception) => {
+				console::write(&cx, LogLevel::Error, &exception.format(&cx));
+				let mut response = hyper::Response::new(Body::empty());
+				*response.status_mut() = StatusCode::INTERNAL_SERVER_ERROR;
+				response
+			}
+		}
+	}
+
+	async fn call(&self, cx: &Context, request: hyper::Request<Body>, scheme: &str, local: SocketAddr) -> ResultExc<hyper::Response<Body>> {
+		let url = request_url(&request, scheme, local)?;
+		let request = Request::new_incoming(cx, request, url)?;
+		let request = cx.root_object(Request::new_object(cx, Box::new(request)));
+
+		let function = Function::from(cx.root_function(self.function.get()));
+		let mut value = function
+			.call(cx, &Object::null(cx), &[Object::from(request).as_value(cx)])
+			.map_err(|report| {
+				report
+					.map(|report| report.exception)
+					.unwrap_or_else(|| Exception::Error(Error::new("Handler threw an uncatchable exception", None)))
+			})?;
+
+		if value.handle().is_object() {
+			if let Some(promise) = Promise::from(value.to_object(cx).into_local()) {
+				value = PromiseFuture::new(cx, &promise).await.map_err(|reason| Exception::Other(reason.get()))?;
+			}
+		}
+
+		let expected = || Error::new("Expected Handler to return a Response", ErrorKind::Type);
+		if !value.handle().is_object() {
+			return Err(expected().into());
+		}
+		let mut response = value.to_object(cx);
+		if !Response::instance_of(cx, &response, None) {
+			return Err(expected().into());
+		}
+		Ok(Response::get_mut_private(&mut response).take_http_response(cx)?)
+	}
+}
+
+/// Returns the absolute URL of a request, using the authority of the request or its `Host` header, or the local address of the connection.
+fn request_url(request: &hyper::Request<Body>, scheme: &str, local: SocketAddr) -> Result<Url> {
+	let uri = request.uri();
+	let authority = uri.authority().map(|authority| authority.to_string());
+	let authority = authority
+		.or_else(|| request.headers().get(HOST).and_then(|host| host.to_str().ok()).map(String::from))
+		.unwrap_or_else(|| local.to_string());
+	let path = uri.path_and_query().map(|path| path.as_str()).unwrap_or("/");
+	Ok(Url::parse(&format!("{}://{}{}", scheme, authority, path))?)
+}
+
+/// Tracks the lifecycle of a [Server]. Once it is shut down, no further connections are accepted,
+/// and existing connections are closed once their pending responses have been sent.
+pub(crate) struct ServerState {
+	shutdown: watch::Sender<bool>,
+	finished: watch::Sender<bool>,
+}
+
+impl ServerState {
+	pub(crate) fn new() -> Rc<ServerState> {
+		Rc::new(ServerState {
+			shutdown: watch::channel(false).0,
+			finished: watch::channel(false).0,
+		})
+	}
+
+	pub(crate) fn shutdown(&self) {
+		self.shutdown.send_replace(true);
+	}
+
+	async fn finished(&self) {
+		let _ = self.finished.subscribe().wait_for(|finished| *finished).await;
+	}
+}
+
+/// Accepts connections until the server is shut down, then waits for all connections to close.
+pub(crate) async fn run(listener: TcpListener, acceptor: Option<TlsAcceptor>, handler: Handler, state: Rc<ServerState>, _keep_alive: KeepAlive) {
+	let handler = Rc::new(handler);
+	let mut shutdown = state.shutdown.subscribe();
+	// Each connection holds a sender, so the receiver completes once every connection has closed.
+	let (connection, mut closed) = mpsc::channel::<()>(1);
+
+	let mut backoff = Backoff::default();
+	loop {
+		let accepted = {
+			let accept = pin!(listener.accept());
+			let stopped = pin!(shutdown.wait_for(|shutdown| *shutdown));
+			match select(accept, stopped).await {
+				Either::Left((accepted, _)) => accepted,
+				Either::Right(_) => break,
+			}
+		};
+		let stream = match accepted {
+			Ok((stream, _)) => stream,
+			// Accepting can keep failing, such as when the process has run out of file descriptors.
+			Err(_) => {
+				backoff.wait(shutdown.wait_for(|shutdown| *shutdown)).await;
+				continue;
+			}
+		};
+		backoff = Backoff::default();
+
+		let task = serve(
+			stream,
+			acceptor.clone(),
+			Rc::clone(&handler),
+			state.shutdown.subscribe(),
+			connection.clone(),
+		);
+		spawn_local(task);
+	}
+
+	drop(listener);
+	drop(connection);
+	let _ = closed.recv().await;
+	state.finished.send_replace(true);
+}
+
+async fn serve(stream: TcpStream, acceptor: Option<TlsAcceptor>, handler: Rc<Handler>, shutdown: watch::Receiver<bool>, _: mpsc::Sender<()>) {
+	let Ok(local) = stream.local_addr() else {
+		return;
+	};
+	match acceptor {
+		Some(acceptor) => {
+			if let Ok(stream) = acceptor.accept(stream).await {
+				serve_connection(stream, "https", local, handler, shutdown).await;
+			}
+		}
+		None => serve_connection(stream, "http", local, handler, shutdown).await,
+	}
+}
+
+/// Serves HTTP/1.1 or HTTP/2 over a connection, depending on the preface sent by the client.
+/// HTTP/1.1 connections are kept alive between requests, and can be upgraded to other protocols.
+async fn serve_connection<I>(io: I, scheme: &'static str, local: SocketAddr, handler: Rc<Handler>, mut shutdown: watch::Receiver<bool>)
+where
+	I: AsyncRead + AsyncWrite + Unpin + 'static,
+{
+	let service = service_fn(move |request| {
+		let handler = Rc::clone(&handler);
+		async move { Ok::<_, Infallible>(handler.respond(request, scheme, local).await) }
+	});
+
+	let mut connection = pin!(Http::new().with_executor(LocalExecutor).serve_connection(io, service).with_upgrades());
+	let stopped = pin!(shutdown.wait_for(|shutdown| *shutdown));
+	if let Either::Right(_) = select(connection.as_mut(), stopped).await {
+		connection.as_mut().graceful_shutdown();
+		let _ = connection.await;
+	}
+}
+
+/// Represents an HTTP server created with `http.serve`.
+#[js_class]
+pub struct Server {
+	reflector: Reflector,
+	#[ion(no_trace)]
+	addr: SocketAddr,
+	#[ion(no_trace)]
+	state: Rc<ServerState>,
+}
+
+impl Server {
+	pub(crate) fn new(addr: SocketAddr, state: Rc<ServerState>) -> Server {
+		Server {
+			reflector: Reflector::default(),
+			addr,
+			state,
+		}
+	}
+}
+
+#[js_class]
+impl Server {
+	#[ion(constructor)]
+	pub fn constructor() -> Result<Server> {
+		Err(Error::new("Server has no constructor.", ErrorKind::Type))
+	}
+
+	/// Stops accepting connections, and resolves once all pending responses have been sent and every connection has closed.
+	pub fn shutdown(&self, cx: &Context) -> Option<Promise> {
+		self.state.shutdown();
+		self.get_finished(cx)
+	}
+
+	#[ion(get)]
+	pub fn get_addr(&self) -> Address {
+		Address(self.addr)
+	}
+
+	/// Resolves once the server has shut down.
+	#[ion(get)]
+	pub fn get_finished(&self, cx: &Context) -> Option<Promise> {
+		let state = Rc::clone(&self.state);
+		future_to_promise::<_, _, Error>(cx, async move {
+			state.finished().await;
+			Ok(())
+		})
+	}
+}
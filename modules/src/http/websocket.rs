@@ -0,0 +1,283 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::borrow::Cow;
+use std::cell::Cell;
+use std::rc::Rc;
+
+use futures::{SinkExt, StreamExt};
+use futures::future::{Either, select};
+use hyper::upgrade::OnUpgrade;
+use mozjs::conversions::ConversionBehavior::EnforceRange;
+use mozjs::rust::IntoHandle;
+use mozjs::typedarray::{ArrayBuffer as RawArrayBuffer, ArrayBufferView};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::tungstenite::protocol::{CloseFrame, Role};
+use tokio_tungstenite::tungstenite::protocol::frame::coding::CloseCode;
+use tokio_tungstenite::WebSocketStream;
+
+use ion::{ClassDefinition, Context, Error, ErrorKind, Local, Object, Result, Value};
+use ion::conversions::{FromValue, ToValue};
+use ion::flags::PropertyFlags;
+use ion::typedarray::ArrayBuffer;
+use runtime::config::LogLevel;
+use runtime::event_loop::handles::{ActiveHandle, HandleKind};
+use runtime::globals::console;
+use runtime::globals::event::{Event, EventTarget};
+use runtime::promise::future_to_promise_with_handle;
+
+/// Status code of close events for connections which closed without a close frame.
+const ABNORMAL_CLOSURE: u16 = 1006;
+/// Status code of close events for close frames without a status code.
+const NO_STATUS_RECEIVED: u16 = 1005;
+const MAX_REASON_LENGTH: usize = 123;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum ReadyState {
+	#[default]
+	Connecting = 0,
+	Open = 1,
+	Closing = 2,
+	Closed = 3,
+}
+
+/// Represents data sent with `WebSocket.send`, which is sent as a text message if it is a string, and as a binary message otherwise.
+pub(crate) struct MessageData(Message);
+
+impl<'cx> FromValue<'cx> for MessageData {
+	type Config = ();
+
+	fn from_value(cx: &'cx Context, value: &Value, strict: bool, _: ()) -> Result<MessageData> {
+		if value.handle().is_string() {
+			return String::from_value(cx, value, true, ()).map(|string| MessageData(Message::Text(string)));
+		}
+		if let Ok(buffer) = RawArrayBuffer::from_value(cx, value, strict, ()) {
+			return Ok(MessageData(Message::Binary(unsafe { buffer.as_slice() }.to_vec())));
+		}
+		ArrayBufferView::from_value(cx, value, strict, ())
+			.map(|view| MessageData(Message::Binary(unsafe { view.as_slice() }.to_vec())))
+			.map_err(|_| Error::new("Expected String, ArrayBuffer or ArrayBufferView", ErrorKind::Type))
+	}
+}
+
+enum Command {
+	Send(Message),
+	Close(Option<CloseFrame<'static>>),
+}
+
+/// Represents the server side of a WebSocket connection, created with `http.upgradeWebSocket`.
+/// It opens once the response to the upgrade request has been sent.
+#[js_class]
+pub struct WebSocket {
+	event_target: EventTarget,
+	#[ion(no_trace)]
+	url: String,
+	#[ion(no_trace)]
+	protocol: String,
+	#[ion(no_trace)]
+	state: Rc<Cell<ReadyState>>,
+	#[ion(no_trace)]
+	commands: UnboundedSender<Command>,
+}
+
+impl WebSocket {
+	/// Creates a [WebSocket], whose connection runs once the request is upgraded.
+	pub(crate) fn accept(cx: &Context, upgrade: OnUpgrade, url: String, protocol: String) -> Result<Object> {
+		let (commands, receiver) = unbounded_channel();
+		let state = Rc::new(Cell::new(ReadyState::Connecting));
+		let handle = ActiveHandle::new(cx, HandleKind::Socket, format!("WebSocket {}", url));
+		let socket = WebSocket {
+			event_target: EventTarget::default(),
+			url,
+			protocol,
+			state: Rc::clone(&state),
+			commands,
+		};
+		let socket = cx.root_object(WebSocket::new_object(cx, Box::new(socket)));
+
+		let this = cx.root_persistent_object(socket.handle().get());
+		let cx2 = unsafe { Context::new_unchecked(cx.as_ptr()) };
+		let this = this.handle().into_handle();
+		let connection = async move {
+			let target = Object::from(unsafe { Local::from_raw_handle(this) });
+			run(&cx2, &target, upgrade, &state, receiver).await;
+			cx2.unroot_persistent_object(this.get());
+			Ok::<_, Error>(())
+		};
+		future_to_promise_with_handle(cx, handle, connection).ok_or_else(|| Error::new("WebSocket requires a future queue", None))?;
+		Ok(socket.into())
+	}
+}
+
+#[js_class]
+impl WebSocket {
+	#[ion(constructor)]
+	pub fn constructor() -> Result<WebSocket> {
+		Err(Error::new("WebSocket has no constructor.", ErrorKind::Type))
+	}
+
+	#[ion(get)]
+	pub fn get_url(&self) -> String {
+		self.url.clone()
+	}
+
+	#[ion(get)]
+	pub fn get_protocol(&self) -> String {
+		self.protocol.clone()
+	}
+
+	#[ion(get)]
+	pub fn get_ready_state(&self) -> u16 {
+		self.state.get() as u16
+	}
+
+	/// Returns the type of the data of binary messages, which are always received as `ArrayBuffer`s.
+	#[ion(get)]
+	pub fn get_binary_type(&self) -> String {
+		String::from("arraybuffer")
+	}
+
+	/// Sends a message, which is ignored if the connection is closing or closed.
+	pub fn send(&self, data: MessageData) -> Result<()> {
+		match self.state.get() {
+			ReadyState::Connecting => Err(Error::new("WebSocket is still connecting.", None).with_name("InvalidStateError")),
+			ReadyState::Open => {
+				let _ = self.commands.send(Command::Send(data.0));
+				Ok(())
+			}
+			ReadyState::Closing | ReadyState::Closed => Ok(()),
+		}
+	}
+
+	/// Starts the closing handshake. The `close` event is fired once the connection has closed.
+	pub fn close(&self, #[ion(convert = EnforceRange)] code: Option<u16>, reason: Option<String>) -> Result<()> {
+		if let Some(code) = code {
+			if code != 1000 && !(3000..=4999).contains(&code) {
+				return Err(Error::new("Invalid close code", None).with_name("InvalidAccessError"));
+			}
+		}
+		let reason = reason.unwrap_or_default();
+		if reason.len() > MAX_REASON_LENGTH {
+			return Err(Error::new("Close reason is too long", None).with_name("SyntaxError"));
+		}
+
+		if matches!(self.state.get(), ReadyState::Connecting | ReadyState::Open) {
+			self.state.set(ReadyState::Closing);
+			let frame = code.map(|code| CloseFrame {
+				code: CloseCode::from(code),
+				reason: Cow::Owned(reason),
+			});
+			let _ = self.commands.send(Command::Close(frame));
+		}
+		Ok(())
+	}
+
+	#[ion(get, name = "CONNECTING")]
+	pub fn connecting() -> u16 {
+		ReadyState::Connecting as u16
+	}
+
+	#[ion(get, name = "OPEN")]
+	pub fn open() -> u16 {
+		ReadyState::Open as u16
+	}
+
+	#[ion(get, name = "CLOSING")]
+	pub fn closing() -> u16 {
+		ReadyState::Closing as u16
+	}
+
+	#[ion(get, name = "CLOSED")]
+	pub fn closed() -> u16 {
+		ReadyState::Closed as u16
+	}
+}
+
+/// Waits for the upgrade, then sends and receives messages until the connection closes.
+async fn run(cx: &Context, target: &Object, upgrade: OnUpgrade, state: &Cell<ReadyState>, mut commands: UnboundedReceiver<Command>) {
+	let Ok(upgraded) = upgrade.await else {
+		fire(cx, target, Event::new_trusted(cx, "error"));
+		fire_close(cx, target, state, None);
+		return;
+	};
+	let mut socket = WebSocketStream::from_raw_socket(upgraded, Role::Server, None).await;
+	if state.get() == ReadyState::Connecting {
+		state.set(ReadyState::Open);
+		fire(cx, target, Event::new_trusted(cx, "open"));
+	}
+
+	let mut received: Option<CloseFrame<'static>> = None;
+	let clean = loop {
+		let next = match select(socket.next(), Box::pin(commands.recv())).await {
+			Either::Left((message, _)) => Either::Left(message),
+			Either::Right((command, _)) => Either::Right(command),
+		};
+
+		match next {
+			Either::Left(Some(Ok(message))) => match message {
+				Message::Text(text) => fire_message(cx, target, &Value::string(cx, &text)),
+				Message::Binary(bytes) => fire_message(cx, target, &ArrayBuffer::from(bytes).as_value(cx)),
+				Message::Close(frame) => {
+					state.set(ReadyState::Closing);
+					received = Some(frame.unwrap_or(CloseFrame {
+						code: CloseCode::from(NO_STATUS_RECEIVED),
+						reason: Cow::Borrowed(""),
+					}));
+				}
+				_ => {}
+			},
+			Either::Left(Some(Err(_))) => break false,
+			Either::Left(None) => break received.is_some(),
+			Either::Right(Some(Command::Send(message))) => {
+				if socket.send(message).await.is_err() {
+					break false;
+				}
+			}
+			Either::Right(Some(Command::Close(frame))) => {
+				if socket.close(frame).await.is_err() {
+					break false;
+				}
+			}
+			Either::Right(None) => {
+				let _ = socket.close(None).await;
+				break false;
+			}
+		}
+	};
+
+	if !clean {
+		fire(cx, target, Event::new_trusted(cx, "error"));
+	}
+	fire_close(cx, target, state, received.filter(|_| clean));
+}
+
+fn fire_message(cx: &Context, target: &Object, data: &Value) {
+	let mut message = Event::new_trusted(cx, "message");
+	message.define(cx, "data", data, PropertyFlags::ENUMERATE);
+	fire(cx, target, message);
+}
+
+/// Fires the `close` event. Connections which closed without a close frame are not clean.
+fn fire_close(cx: &Context, target: &Object, state: &Cell<ReadyState>, frame: Option<CloseFrame>) {
+	state.set(ReadyState::Closed);
+	let mut close = Event::new_trusted(cx, "close");
+	let (code, reason) = match &frame {
+		Some(frame) => (u16::from(frame.code), frame.reason.as_ref()),
+		None => (ABNORMAL_CLOSURE, ""),
+	};
+	close.define(cx, "code", &code.as_value(cx), PropertyFlags::ENUMERATE);
+	close.define(cx, "reason", &Value::string(cx, reason), PropertyFlags::ENUMERATE);
+	close.define(cx, "wasClean", &Value::bool(cx, frame.is_some()), PropertyFlags::ENUMERATE);
+	fire(cx, target, close);
+}
+
+/// Fires an event at the socket, reporting errors thrown by listeners through the console.
+fn fire(cx: &Context, target: &Object, mut event: Object) {
+	if let Err(error) = EventTarget::fire(cx, target, &mut event) {
+		console::write(cx, LogLevel::Error, &error.format());
+	}
+}
@@ -0,0 +1,123 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::mem::take;
+
+use mozjs::jsapi::JSObject;
+
+use ion::{ClassDefinition, Context, Error, ErrorKind, Object, Promise, Result, ResultExc, Value};
+use ion::class::Reflector;
+use ion::conversions::FromValue;
+
+use crate::kv::key::Key;
+use crate::kv::store::{Check, commit, CommitResult, Mutation, parse_versionstamp, serialise};
+use crate::sqlite::{operate, SharedConnection};
+
+/// Represents a check passed to `AtomicOperation.check`, as `{ key, versionstamp }`.
+/// A `versionstamp` of `null` checks that the key does not have an entry.
+pub(crate) struct KeyCheck(Check);
+
+impl<'cx> FromValue<'cx> for KeyCheck {
+	type Config = ();
+
+	fn from_value(cx: &'cx Context, value: &Value, strict: bool, _: ()) -> Result<KeyCheck> {
+		if !value.handle().is_object() {
+			return Err(Error::new("Expected Object as Check", ErrorKind::Type));
+		}
+		let object = value.to_object(cx);
+		let key: Key = object
+			.get_as(cx, "key", strict, ())
+			.ok_or_else(|| Error::new("Check must have a key", ErrorKind::Type))?;
+		let versionstamp: Option<String> = object.get_as(cx, "versionstamp", strict, ()).flatten();
+		Ok(KeyCheck(Check {
+			key: key.encode_entry()?,
+			version: versionstamp.as_deref().map(parse_versionstamp).transpose()?,
+		}))
+	}
+}
+
+/// Represents an atomic operation created with `Store.atomic`.
+/// Its mutations are applied in a single transaction when it is committed, unless any of its checks fail.
+#[js_class]
+pub struct AtomicOperation {
+	reflector: Reflector,
+	#[ion(no_trace)]
+	connection: SharedConnection,
+	#[ion(no_trace)]
+	checks: Vec<Check>,
+	#[ion(no_trace)]
+	mutations: Vec<Mutation>,
+	#[ion(no_trace)]
+	committed: bool,
+}
+
+impl AtomicOperation {
+	pub(crate) fn new(connection: SharedConnection) -> AtomicOperation {
+		AtomicOperation {
+			reflector: Reflector::default(),
+			connection,
+			checks: Vec::new(),
+			mutations: Vec::new(),
+			committed: false,
+		}
+	}
+
+	fn committed() -> Error {
+		Error::new("Atomic operation has already been committed", None).with_name("InvalidStateError")
+	}
+
+	/// Returns the operation of `this`, unless it has already been committed.
+	fn pending<'cx>(cx: &'cx Context, this: &Object) -> Result<&'cx mut AtomicOperation> {
+		let operation = AtomicOperation::get_mut_private(&mut Object::from(cx.root_object(this.handle().get())));
+		if operation.committed {
+			return Err(AtomicOperation::committed());
+		}
+		Ok(operation)
+	}
+}
+
+#[js_class]
+impl AtomicOperation {
+	#[ion(constructor)]
+	pub fn constructor() -> Result<AtomicOperation> {
+		Err(Error::new("AtomicOperation has no constructor.", ErrorKind::Type))
+	}
+
+	/// Adds checks that keys have the given versionstamps, and returns the operation for chaining.
+	pub fn check(cx: &Context, #[ion(this)] this: &Object, #[ion(varargs)] checks: Vec<KeyCheck>) -> Result<*mut JSObject> {
+		let operation = AtomicOperation::pending(cx, this)?;
+		operation.checks.extend(checks.into_iter().map(|check| check.0));
+		Ok(this.handle().get())
+	}
+
+	/// Sets the value of a key when the operation is committed, and returns the operation for chaining.
+	pub fn set<'cx>(cx: &'cx Context, #[ion(this)] this: &Object, key: Key, value: Value<'cx>) -> ResultExc<*mut JSObject> {
+		let operation = AtomicOperation::pending(cx, this)?;
+		operation.mutations.push(Mutation::Set(key.encode_entry()?, serialise(cx, &value)?));
+		Ok(this.handle().get())
+	}
+
+	/// Deletes a key when the operation is committed, and returns the operation for chaining.
+	pub fn delete(cx: &Context, #[ion(this)] this: &Object, key: Key) -> Result<*mut JSObject> {
+		let operation = AtomicOperation::pending(cx, this)?;
+		operation.mutations.push(Mutation::Delete(key.encode_entry()?));
+		Ok(this.handle().get())
+	}
+
+	/// Commits the operation, and resolves with `{ ok: true, versionstamp }`, or `{ ok: false }` if a check failed.
+	pub fn commit(&mut self, cx: &Context) -> Result<Option<Promise>> {
+		if self.committed {
+			return Err(AtomicOperation::committed());
+		}
+		self.committed = true;
+
+		let checks = take(&mut self.checks);
+		let mutations = take(&mut self.mutations);
+		Ok(operate(cx, &self.connection, move |connection| {
+			commit(connection, &checks, &mutations).map(CommitResult)
+		}))
+	}
+}
@@ -0,0 +1,241 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use mozjs::typedarray::{ArrayBuffer, ArrayBufferView};
+
+use ion::{Context, Error, ErrorKind, Result, Value};
+use ion::conversions::{ConversionBehavior, FromValue, ToValue};
+use ion::typedarray::Uint8Array;
+
+/// Maximum length of an encoded key, in bytes.
+const MAX_KEY_SIZE: usize = 2048;
+
+const BYTES: u8 = 0x01;
+const STRING: u8 = 0x02;
+const BIGINT: u8 = 0x14;
+const NUMBER: u8 = 0x21;
+const FALSE: u8 = 0x26;
+const TRUE: u8 = 0x27;
+
+const SIGN: u64 = 1 << 63;
+
+/// Represents a part of a key, which is a string, number, bigint, boolean or Uint8Array.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum KeyPart {
+	Bytes(Vec<u8>),
+	String(String),
+	BigInt(i64),
+	Number(f64),
+	Boolean(bool),
+}
+
+impl<'cx> FromValue<'cx> for KeyPart {
+	type Config = ();
+
+	fn from_value(cx: &'cx Context, value: &Value, _: bool, _: ()) -> Result<KeyPart> {
+		let handle = value.handle();
+		if handle.is_string() {
+			Ok(KeyPart::String(String::from_value(cx, value, true, ())?))
+		} else if handle.is_number() {
+			Ok(KeyPart::Number(handle.to_number()))
+		} else if handle.is_bigint() {
+			Ok(KeyPart::BigInt(i64::from_value(cx, value, true, ConversionBehavior::EnforceRange)?))
+		} else if handle.is_boolean() {
+			Ok(KeyPart::Boolean(handle.to_boolean()))
+		} else if let Ok(buffer) = ArrayBuffer::from_value(cx, value, true, ()) {
+			Ok(KeyPart::Bytes(unsafe { buffer.as_slice() }.to_vec()))
+		} else if let Ok(view) = ArrayBufferView::from_value(cx, value, true, ()) {
+			Ok(KeyPart::Bytes(unsafe { view.as_slice() }.to_vec()))
+		} else {
+			Err(Error::new(
+				"Expected String, Number, BigInt, Boolean or Uint8Array as Key Part",
+				ErrorKind::Type,
+			))
+		}
+	}
+}
+
+impl<'cx> ToValue<'cx> for KeyPart {
+	fn to_value(&self, cx: &'cx Context, value: &mut Value) {
+		match self {
+			KeyPart::Bytes(bytes) => Uint8Array::from(bytes.clone()).to_value(cx, value),
+			KeyPart::String(string) => string.to_value(cx, value),
+			KeyPart::BigInt(bigint) => bigint.to_value(cx, value),
+			KeyPart::Number(number) => number.to_value(cx, value),
+			KeyPart::Boolean(boolean) => boolean.to_value(cx, value),
+		}
+	}
+}
+
+/// Represents a key, which is an array of parts.
+///
+/// Keys are encoded such that comparing the bytes of two encoded keys orders them by their parts,
+/// and the encoding of a key begins with the encoding of each of its prefixes.
+/// Parts of different types are ordered as Uint8Arrays, strings, bigints, numbers and then booleans.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct Key(pub(crate) Vec<KeyPart>);
+
+impl Key {
+	pub(crate) fn encode(&self) -> Vec<u8> {
+		let mut bytes = Vec::new();
+		for part in &self.0 {
+			match part {
+				KeyPart::Bytes(part) => {
+					bytes.push(BYTES);
+					escape(&mut bytes, part);
+				}
+				KeyPart::String(part) => {
+					bytes.push(STRING);
+					escape(&mut bytes, part.as_bytes());
+				}
+				KeyPart::BigInt(part) => {
+					bytes.push(BIGINT);
+					bytes.extend_from_slice(&(*part as u64 ^ SIGN).to_be_bytes());
+				}
+				KeyPart::Number(part) => {
+					let bits = if part.is_nan() { f64::NAN.to_bits() } else { part.to_bits() };
+					let bits = if bits & SIGN != 0 { !bits } else { bits ^ SIGN };
+					bytes.push(NUMBER);
+					bytes.extend_from_slice(&bits.to_be_bytes());
+				}
+				KeyPart::Boolean(false) => bytes.push(FALSE),
+				KeyPart::Boolean(true) => bytes.push(TRUE),
+			}
+		}
+		bytes
+	}
+
+	/// Encodes the key of an entry, which must have at least one part.
+	pub(crate) fn encode_entry(&self) -> Result<Vec<u8>> {
+		if self.0.is_empty() {
+			return Err(Error::new("Key must have at least one part", ErrorKind::Type));
+		}
+		let bytes = self.encode();
+		if bytes.len() > MAX_KEY_SIZE {
+			return Err(Error::new(&format!("Key is larger than {} bytes", MAX_KEY_SIZE), ErrorKind::Range));
+		}
+		Ok(bytes)
+	}
+
+	pub(crate) fn decode(mut bytes: &[u8]) -> Option<Key> {
+		let mut parts = Vec::new();
+		while let Some((&tag, rest)) = bytes.split_first() {
+			bytes = rest;
+			let part = match tag {
+				BYTES => KeyPart::Bytes(unescape(&mut bytes)?),
+				STRING => KeyPart::String(String::from_utf8(unescape(&mut bytes)?).ok()?),
+				BIGINT => KeyPart::BigInt((read_u64(&mut bytes)? ^ SIGN) as i64),
+				NUMBER => {
+					let bits = read_u64(&mut bytes)?;
+					let bits = if bits & SIGN != 0 { bits ^ SIGN } else { !bits };
+					KeyPart::Number(f64::from_bits(bits))
+				}
+				FALSE => KeyPart::Boolean(false),
+				TRUE => KeyPart::Boolean(true),
+				_ => return None,
+			};
+			parts.push(part);
+		}
+		Some(Key(parts))
+	}
+}
+
+/// Appends bytes terminated by a null byte, where null bytes within them are followed by `0xFF`.
+fn escape(output: &mut Vec<u8>, bytes: &[u8]) {
+	for &byte in bytes {
+		output.push(byte);
+		if byte == 0 {
+			output.push(0xFF);
+		}
+	}
+	output.push(0);
+}
+
+fn unescape(bytes: &mut &[u8]) -> Option<Vec<u8>> {
+	let mut output = Vec::new();
+	let mut index = 0;
+	loop {
+		match bytes.get(index)? {
+			0 if bytes.get(index + 1) == Some(&0xFF) => {
+				output.push(0);
+				index += 2;
+			}
+			0 => break,
+			&byte => {
+				output.push(byte);
+				index += 1;
+			}
+		}
+	}
+	*bytes = &bytes[index + 1..];
+	Some(output)
+}
+
+fn read_u64(bytes: &mut &[u8]) -> Option<u64> {
+	let integer = bytes.get(..8)?.try_into().ok()?;
+	*bytes = &bytes[8..];
+	Some(u64::from_be_bytes(integer))
+}
+
+impl<'cx> FromValue<'cx> for Key {
+	type Config = ();
+
+	fn from_value(cx: &'cx Context, value: &Value, strict: bool, _: ()) -> Result<Key> {
+		if !value.handle().is_object() {
+			return Err(Error::new("Expected Array as Key", ErrorKind::Type));
+		}
+		Vec::<KeyPart>::from_value(cx, value, strict, ()).map(Key)
+	}
+}
+
+impl<'cx> ToValue<'cx> for Key {
+	fn to_value(&self, cx: &'cx Context, value: &mut Value) {
+		self.0.to_value(cx, value);
+	}
+}
+
+/// Represents the range of keys returned by `list`.
+/// Keys are selected by a prefix, optionally starting from or ending before another key with that prefix,
+/// or between a start and an end.
+#[derive(FromValue)]
+pub(crate) struct Selector {
+	prefix: Option<Key>,
+	start: Option<Key>,
+	end: Option<Key>,
+}
+
+/// Represents the bounds of the encoded keys selected by a [Selector].
+/// The lower bound is inclusive, unless it is the prefix itself, and the upper bound is exclusive.
+pub(crate) struct Range {
+	pub(crate) lower: Vec<u8>,
+	pub(crate) inclusive: bool,
+	pub(crate) upper: Vec<u8>,
+}
+
+impl Selector {
+	pub(crate) fn range(&self) -> Result<Range> {
+		match (&self.prefix, &self.start, &self.end) {
+			(Some(prefix), start, end) => {
+				let prefix = prefix.encode();
+				let (lower, inclusive) = match start {
+					Some(start) => (start.encode(), true),
+					None => (prefix.clone(), false),
+				};
+				let upper = match end {
+					Some(end) => end.encode(),
+					None => [prefix.as_slice(), &[0xFF]].concat(),
+				};
+				Ok(Range { lower, inclusive, upper })
+			}
+			(None, Some(start), Some(end)) => Ok(Range {
+				lower: start.encode(),
+				inclusive: true,
+				upper: end.encode(),
+			}),
+			_ => Err(Error::new("Selector must have a prefix, or both a start and an end", ErrorKind::Type)),
+		}
+	}
+}
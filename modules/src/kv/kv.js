@@ -0,0 +1,12 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+export const open = ______kvInternal______.open;
+
+export const Store = ______kvInternal______.Store;
+export const AtomicOperation = ______kvInternal______.AtomicOperation;
+
+export default Object.freeze(______kvInternal______);
@@ -0,0 +1,66 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use mozjs::jsapi::JSFunctionSpec;
+use rusqlite::Connection;
+use tokio::task::spawn_blocking;
+
+use ion::{ClassDefinition, Context, Error, Object, Promise};
+use runtime::globals::storage::storage_file;
+use runtime::modules::NativeModule;
+use runtime::permissions::{check_read, check_write};
+use runtime::promise::future_to_promise;
+
+use crate::kv::atomic::AtomicOperation;
+use crate::kv::store::{initialise, OpenedStore, Store};
+use crate::sqlite::sqlite_error;
+
+/// Opens a key-value store, which is held in memory if `path` is `:memory:`.
+/// If no path is given, the store is persisted in the cache, separately for each origin or script.
+#[js_fn]
+fn open(cx: &Context, path: Option<String>) -> Option<Promise> {
+	future_to_promise::<_, _, Error>(cx, async move {
+		let path = match path {
+			Some(path) => {
+				if path != ":memory:" {
+					check_read(&path)?;
+					check_write(&path)?;
+				}
+				path
+			}
+			None => storage_file("kv")
+				.and_then(|file| file.to_str().map(String::from))
+				.ok_or_else(|| Error::new("Store has no path, and there is no origin or script to persist it for", None))?,
+		};
+
+		let opened = spawn_blocking(move || {
+			let connection = Connection::open(&path)?;
+			initialise(&connection)?;
+			Ok::<_, rusqlite::Error>(OpenedStore { path, connection })
+		})
+		.await
+		.map_err(|error| Error::new(&error.to_string(), None))?;
+		opened.map_err(sqlite_error)
+	})
+}
+
+const FUNCTIONS: &[JSFunctionSpec] = &[function_spec!(open, 0), JSFunctionSpec::ZERO];
+
+#[derive(Default)]
+pub struct Kv;
+
+impl NativeModule for Kv {
+	const NAME: &'static str = "kv";
+	const SOURCE: &'static str = include_str!("kv.js");
+
+	fn module(cx: &Context) -> Option<Object> {
+		let mut kv = Object::new(cx);
+		if unsafe { kv.define_methods(cx, FUNCTIONS) } && Store::init_class(cx, &mut kv).0 && AtomicOperation::init_class(cx, &mut kv).0 {
+			return Some(kv);
+		}
+		None
+	}
+}
@@ -0,0 +1,247 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::sync::{Arc, Mutex};
+
+use mozjs::conversions::ConversionBehavior::EnforceRange;
+use mozjs::jsapi::JSObject;
+use rusqlite::{Connection, OptionalExtension, params, TransactionBehavior};
+
+use ion::{ClassDefinition, Context, Error, ErrorKind, Object, Promise, Result, ResultExc, Value};
+use ion::class::Reflector;
+use ion::conversions::{IntoValue, ToValue};
+use runtime::clone::StructuredClone;
+use runtime::promise::future_to_promise;
+
+use crate::kv::atomic::AtomicOperation;
+use crate::kv::key::{Key, Selector};
+use crate::sqlite::{close, operate, SharedConnection};
+
+const SCHEMA: &str = "
+	CREATE TABLE IF NOT EXISTS entries (key BLOB PRIMARY KEY, value BLOB NOT NULL, version INTEGER NOT NULL) WITHOUT ROWID;
+	CREATE TABLE IF NOT EXISTS versions (id INTEGER PRIMARY KEY CHECK (id = 0), version INTEGER NOT NULL);
+	INSERT OR IGNORE INTO versions (id, version) VALUES (0, 0);
+";
+
+/// Creates the tables of a store, if they do not exist.
+pub(crate) fn initialise(connection: &Connection) -> rusqlite::Result<()> {
+	connection.execute_batch(SCHEMA)
+}
+
+/// Serialises a value with the structured clone algorithm, so that it can be stored.
+pub(crate) fn serialise(cx: &Context, value: &Value) -> ResultExc<Vec<u8>> {
+	StructuredClone::serialise(cx, value, &[]).map(|clone| clone.as_bytes().to_vec())
+}
+
+/// Represents a versionstamp, which identifies the commit which last changed an entry.
+/// Versionstamps are strings of 20 hexadecimal digits, which increase with each commit to a store.
+pub(crate) fn versionstamp(version: u64) -> String {
+	format!("{:020x}", version)
+}
+
+pub(crate) fn parse_versionstamp(versionstamp: &str) -> Result<u64> {
+	if versionstamp.len() == 20 {
+		if let Ok(version) = u64::from_str_radix(versionstamp, 16) {
+			return Ok(version);
+		}
+	}
+	Err(Error::new(&format!("Invalid Versionstamp: {}", versionstamp), ErrorKind::Type))
+}
+
+/// Represents a check of an atomic operation, which fails if the entry of the key does not have the given version.
+/// A version of [None] checks that the entry does not exist.
+pub(crate) struct Check {
+	pub(crate) key: Vec<u8>,
+	pub(crate) version: Option<u64>,
+}
+
+pub(crate) enum Mutation {
+	Set(Vec<u8>, Vec<u8>),
+	Delete(Vec<u8>),
+}
+
+/// Applies mutations in a single transaction, if all the checks pass.
+/// Returns the version of the commit, or [None] if a check failed.
+pub(crate) fn commit(connection: &mut Connection, checks: &[Check], mutations: &[Mutation]) -> rusqlite::Result<Option<u64>> {
+	let transaction = connection.transaction_with_behavior(TransactionBehavior::Immediate)?;
+	for check in checks {
+		let version = transaction
+			.query_row("SELECT version FROM entries WHERE key = ?1", [&check.key], |row| row.get::<_, i64>(0))
+			.optional()?;
+		if version.map(|version| version as u64) != check.version {
+			return Ok(None);
+		}
+	}
+
+	let version: i64 = transaction.query_row("UPDATE versions SET version = version + 1 RETURNING version", [], |row| row.get(0))?;
+	for mutation in mutations {
+		match mutation {
+			Mutation::Set(key, value) => transaction.execute(
+				"INSERT INTO entries (key, value, version) VALUES (?1, ?2, ?3)
+					ON CONFLICT (key) DO UPDATE SET value = excluded.value, version = excluded.version",
+				params![key, value, version],
+			)?,
+			Mutation::Delete(key) => transaction.execute("DELETE FROM entries WHERE key = ?1", [key])?,
+		};
+	}
+	transaction.commit()?;
+	Ok(Some(version as u64))
+}
+
+/// Represents an entry of a store, whose value and versionstamp are `null` if it does not exist.
+pub(crate) struct Entry {
+	key: Key,
+	value: Option<(Vec<u8>, u64)>,
+}
+
+impl<'cx> ToValue<'cx> for Entry {
+	fn to_value(&self, cx: &'cx Context, value: &mut Value) {
+		let mut object = Object::new(cx);
+		object.set_as(cx, "key", &self.key);
+		match &self.value {
+			Some((bytes, version)) => {
+				let deserialised = StructuredClone::from_bytes(bytes.clone()).deserialise(cx);
+				let deserialised = deserialised.unwrap_or_else(|_| Value::null(cx));
+				object.set(cx, "value", &deserialised);
+				object.set_as(cx, "versionstamp", &versionstamp(*version));
+			}
+			None => {
+				object.set(cx, "value", &Value::null(cx));
+				object.set(cx, "versionstamp", &Value::null(cx));
+			}
+		}
+		object.to_value(cx, value);
+	}
+}
+
+/// Represents the result of committing an atomic operation, which is not `ok` if a check failed.
+pub(crate) struct CommitResult(pub(crate) Option<u64>);
+
+impl<'cx> ToValue<'cx> for CommitResult {
+	fn to_value(&self, cx: &'cx Context, value: &mut Value) {
+		let mut object = Object::new(cx);
+		object.set_as(cx, "ok", &self.0.is_some());
+		if let Some(version) = self.0 {
+			object.set_as(cx, "versionstamp", &versionstamp(version));
+		}
+		object.to_value(cx, value);
+	}
+}
+
+#[derive(Default, FromValue)]
+pub(crate) struct ListOptions {
+	#[ion(convert = EnforceRange)]
+	limit: Option<u32>,
+	#[ion(default)]
+	reverse: bool,
+}
+
+/// Represents a key-value store opened with `kv.open`.
+#[js_class]
+pub struct Store {
+	reflector: Reflector,
+	#[ion(no_trace)]
+	path: String,
+	#[ion(no_trace)]
+	connection: SharedConnection,
+}
+
+#[js_class]
+impl Store {
+	#[ion(constructor)]
+	pub fn constructor() -> Result<Store> {
+		Err(Error::new("Store has no constructor.", ErrorKind::Type))
+	}
+
+	/// Reads the entry of a key, whose value is `null` if it does not exist.
+	pub fn get(&self, cx: &Context, key: Key) -> Result<Option<Promise>> {
+		let encoded = key.encode_entry()?;
+		Ok(operate(cx, &self.connection, move |connection| {
+			let value = connection
+				.query_row("SELECT value, version FROM entries WHERE key = ?1", [&encoded], |row| {
+					Ok((row.get(0)?, row.get::<_, i64>(1)? as u64))
+				})
+				.optional()?;
+			Ok(Entry { key, value })
+		}))
+	}
+
+	/// Sets the value of a key, which is serialised with the structured clone algorithm.
+	pub fn set<'cx>(&self, cx: &'cx Context, key: Key, value: Value<'cx>) -> ResultExc<Option<Promise>> {
+		let mutation = Mutation::Set(key.encode_entry()?, serialise(cx, &value)?);
+		Ok(operate(cx, &self.connection, move |connection| {
+			commit(connection, &[], &[mutation]).map(CommitResult)
+		}))
+	}
+
+	pub fn delete(&self, cx: &Context, key: Key) -> Result<Option<Promise>> {
+		let mutation = Mutation::Delete(key.encode_entry()?);
+		Ok(operate(cx, &self.connection, move |connection| {
+			commit(connection, &[], &[mutation]).map(|_| ())
+		}))
+	}
+
+	/// Reads the entries whose keys are selected by `selector`, ordered by their keys.
+	pub fn list(&self, cx: &Context, selector: Selector, options: Option<ListOptions>) -> Result<Option<Promise>> {
+		let range = selector.range()?;
+		let options = options.unwrap_or_default();
+		let limit = options.limit.map(i64::from).unwrap_or(-1);
+
+		let sql = format!(
+			"SELECT key, value, version FROM entries WHERE key {} ?1 AND key < ?2 ORDER BY key {} LIMIT ?3",
+			if range.inclusive { ">=" } else { ">" },
+			if options.reverse { "DESC" } else { "ASC" },
+		);
+		Ok(operate(cx, &self.connection, move |connection| {
+			let mut statement = connection.prepare_cached(&sql)?;
+			let rows = statement.query_map(params![range.lower, range.upper, limit], |row| {
+				Ok((row.get::<_, Vec<u8>>(0)?, row.get(1)?, row.get::<_, i64>(2)? as u64))
+			})?;
+
+			let mut entries = Vec::new();
+			for row in rows {
+				let (key, value, version) = row?;
+				if let Some(key) = Key::decode(&key) {
+					entries.push(Entry { key, value: Some((value, version)) });
+				}
+			}
+			Ok(entries)
+		}))
+	}
+
+	/// Creates an atomic operation, whose mutations are only applied if all of its checks pass.
+	pub fn atomic(&self, cx: &Context) -> *mut JSObject {
+		AtomicOperation::new_object(cx, Box::new(AtomicOperation::new(Arc::clone(&self.connection))))
+	}
+
+	/// Closes the store, once all pending operations have completed. Further operations are rejected.
+	pub fn close(&self, cx: &Context) -> Option<Promise> {
+		let connection = Arc::clone(&self.connection);
+		future_to_promise::<_, _, Error>(cx, async move { close(connection).await })
+	}
+
+	#[ion(get)]
+	pub fn get_path(&self) -> String {
+		self.path.clone()
+	}
+}
+
+/// Represents a store which has just been opened, which is converted to a [Store] when its promise resolves.
+pub(crate) struct OpenedStore {
+	pub(crate) path: String,
+	pub(crate) connection: Connection,
+}
+
+impl<'cx> IntoValue<'cx> for OpenedStore {
+	fn into_value(self: Box<Self>, cx: &'cx Context, value: &mut Value) {
+		let store = Store {
+			reflector: Reflector::default(),
+			path: self.path,
+			connection: Arc::new(Mutex::new(Some(self.connection))),
+		};
+		cx.root_object(Store::new_object(cx, Box::new(store))).handle().get().to_value(cx, value);
+	}
+}
@@ -11,32 +11,137 @@
 extern crate ion;
 
 use ion::{Context, Object};
-use runtime::modules::{init_global_module, init_module, StandardModules};
+use runtime::modules::{init_global_module, init_module, snapshot_module, StandardModules};
+use runtime::snapshot::Snapshot;
 
 pub use crate::assert::Assert;
+pub use crate::bench::{Bench, BenchOutcome, BenchResult, run_benchmarks, Statistics};
+pub use crate::crypto::Crypto;
+pub use crate::encoding::EncodingM;
+pub use crate::ffi::Ffi;
 pub use crate::fs::FileSystem;
+pub use crate::http::Http;
+pub use crate::kv::Kv;
+pub use crate::memory::Memory;
+pub use crate::net::Net;
+pub use crate::os::OperatingSystem;
 pub use crate::path::PathM;
+pub use crate::permissions::PermissionsM;
+pub use crate::process::Process;
+pub use crate::profiler::ProfilerM;
+pub use crate::runtime_m::RuntimeM;
+pub use crate::sqlite::Sqlite;
+pub use crate::subprocess::Subprocess;
+pub use crate::test::{run_tests, Test, TestOutcome, TestResult};
+pub use crate::tls::Tls;
 pub use crate::url::UrlM;
+pub use crate::worker::WorkerM;
+pub use crate::zlib::Zlib;
 
 mod assert;
+mod bench;
+mod crypto;
+mod encoding;
+mod ffi;
 mod fs;
+mod http;
+mod kv;
+mod memory;
+mod net;
+mod os;
 mod path;
+mod permissions;
+mod pipe;
+mod process;
+mod profiler;
+mod runtime_m;
+mod sqlite;
+mod subprocess;
+mod test;
+mod tls;
 mod url;
+mod worker;
+mod zlib;
 
+#[derive(Default)]
 pub struct Modules;
 
 impl StandardModules for Modules {
 	fn init(self, cx: &Context, global: &mut Object) -> bool {
 		init_module::<Assert>(cx, global)
+			&& init_module::<Bench>(cx, global)
+			&& init_module::<Crypto>(cx, global)
+			&& init_module::<EncodingM>(cx, global)
+			&& init_module::<Ffi>(cx, global)
 			&& init_module::<FileSystem>(cx, global)
+			&& init_module::<Http>(cx, global)
+			&& init_module::<Kv>(cx, global)
+			&& init_module::<Memory>(cx, global)
+			&& init_module::<Net>(cx, global)
+			&& init_module::<OperatingSystem>(cx, global)
 			&& init_module::<PathM>(cx, global)
+			&& init_module::<PermissionsM>(cx, global)
+			&& init_module::<Process>(cx, global)
+			&& init_module::<ProfilerM>(cx, global)
+			&& init_module::<RuntimeM>(cx, global)
+			&& init_module::<Sqlite>(cx, global)
+			&& init_module::<Subprocess>(cx, global)
+			&& init_module::<Test>(cx, global)
+			&& init_module::<Tls>(cx, global)
 			&& init_module::<UrlM>(cx, global)
+			&& init_module::<WorkerM>(cx, global)
+			&& init_module::<Zlib>(cx, global)
 	}
 
 	fn init_globals(self, cx: &Context, global: &mut Object) -> bool {
+		// The crypto module is not defined as a global, since `crypto` is already the Web Crypto API.
 		init_global_module::<Assert>(cx, global)
+			&& init_global_module::<Bench>(cx, global)
+			&& init_global_module::<EncodingM>(cx, global)
+			&& init_global_module::<Ffi>(cx, global)
 			&& init_global_module::<FileSystem>(cx, global)
+			&& init_global_module::<Http>(cx, global)
+			&& init_global_module::<Kv>(cx, global)
+			&& init_global_module::<Memory>(cx, global)
+			&& init_global_module::<Net>(cx, global)
+			&& init_global_module::<OperatingSystem>(cx, global)
 			&& init_global_module::<PathM>(cx, global)
+			&& init_global_module::<PermissionsM>(cx, global)
+			&& init_global_module::<Process>(cx, global)
+			&& init_global_module::<ProfilerM>(cx, global)
+			&& init_global_module::<RuntimeM>(cx, global)
+			&& init_global_module::<Sqlite>(cx, global)
+			&& init_global_module::<Subprocess>(cx, global)
+			&& init_global_module::<Test>(cx, global)
+			&& init_global_module::<Tls>(cx, global)
 			&& init_global_module::<UrlM>(cx, global)
+			&& init_global_module::<WorkerM>(cx, global)
+			&& init_global_module::<Zlib>(cx, global)
+	}
+
+	fn snapshot(&self, cx: &Context, snapshot: &mut Snapshot) -> bool {
+		snapshot_module::<Assert>(cx, snapshot)
+			&& snapshot_module::<Bench>(cx, snapshot)
+			&& snapshot_module::<Crypto>(cx, snapshot)
+			&& snapshot_module::<EncodingM>(cx, snapshot)
+			&& snapshot_module::<Ffi>(cx, snapshot)
+			&& snapshot_module::<FileSystem>(cx, snapshot)
+			&& snapshot_module::<Http>(cx, snapshot)
+			&& snapshot_module::<Kv>(cx, snapshot)
+			&& snapshot_module::<Memory>(cx, snapshot)
+			&& snapshot_module::<Net>(cx, snapshot)
+			&& snapshot_module::<OperatingSystem>(cx, snapshot)
+			&& snapshot_module::<PathM>(cx, snapshot)
+			&& snapshot_module::<PermissionsM>(cx, snapshot)
+			&& snapshot_module::<Process>(cx, snapshot)
+			&& snapshot_module::<ProfilerM>(cx, snapshot)
+			&& snapshot_module::<RuntimeM>(cx, snapshot)
+			&& snapshot_module::<Sqlite>(cx, snapshot)
+			&& snapshot_module::<Subprocess>(cx, snapshot)
+			&& snapshot_module::<Test>(cx, snapshot)
+			&& snapshot_module::<Tls>(cx, snapshot)
+			&& snapshot_module::<UrlM>(cx, snapshot)
+			&& snapshot_module::<WorkerM>(cx, snapshot)
+			&& snapshot_module::<Zlib>(cx, snapshot)
 	}
 }
@@ -0,0 +1,81 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::path::Path;
+
+use mozjs::jsapi::JSFunctionSpec;
+use sysinfo::{get_current_pid, ProcessExt, System, SystemExt};
+
+use ion::{Context, Error, Object, Result, Value};
+use ion::conversions::ToValue;
+use runtime::heap::write_heap_snapshot;
+use runtime::memory_usage;
+use runtime::modules::NativeModule;
+use runtime::permissions::check_write;
+
+/// Represents the memory usage of the runtime in bytes, as `{ heapUsed, heapTotal, rss }`.
+struct MemoryUsage {
+	heap_used: u64,
+	heap_total: u64,
+	rss: u64,
+}
+
+impl<'cx> ToValue<'cx> for MemoryUsage {
+	fn to_value(&self, cx: &'cx Context, value: &mut Value) {
+		let mut object = Object::new(cx);
+		object.set_as(cx, "heapUsed", &(self.heap_used as f64));
+		object.set_as(cx, "heapTotal", &(self.heap_total as f64));
+		object.set_as(cx, "rss", &(self.rss as f64));
+		object.to_value(cx, value);
+	}
+}
+
+fn resident_set_size() -> u64 {
+	let Ok(pid) = get_current_pid() else {
+		return 0;
+	};
+	let mut system = System::new();
+	system.refresh_process(pid);
+	system.process(pid).map(|process| process.memory()).unwrap_or(0)
+}
+
+/// Returns the memory usage of the runtime.
+/// `heapUsed` and `heapTotal` are the bytes allocated in and reserved for the GC heap,
+/// and `rss` is the resident set size of the whole process.
+#[js_fn]
+fn usage(cx: &Context) -> MemoryUsage {
+	let memory = memory_usage(cx);
+	MemoryUsage {
+		heap_used: memory.gc_bytes as u64,
+		heap_total: memory.reserved_bytes(),
+		rss: resident_set_size(),
+	}
+}
+
+/// Writes a snapshot of the heap to `path`, which can be loaded in the Memory panel of Chrome DevTools.
+#[js_fn]
+fn takeHeapSnapshot(cx: &Context, path: String) -> Result<()> {
+	check_write(&path)?;
+	write_heap_snapshot(cx, Path::new(&path)).map_err(|error| Error::new(&error.to_string(), None))
+}
+
+const FUNCTIONS: &[JSFunctionSpec] = &[function_spec!(usage, 0), function_spec!(takeHeapSnapshot, 1), JSFunctionSpec::ZERO];
+
+#[derive(Default)]
+pub struct Memory;
+
+impl NativeModule for Memory {
+	const NAME: &'static str = "memory";
+	const SOURCE: &'static str = include_str!("memory.js");
+
+	fn module(cx: &Context) -> Option<Object> {
+		let mut memory = Object::new(cx);
+		if unsafe { memory.define_methods(cx, FUNCTIONS) } {
+			return Some(memory);
+		}
+		None
+	}
+}
@@ -0,0 +1,173 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::net::SocketAddr;
+use std::rc::Rc;
+
+use mozjs::jsapi::{Heap, JSObject};
+use tokio::io::{AsyncRead, AsyncWrite, ReadHalf, split, WriteHalf};
+
+use ion::{ClassDefinition, Context, Error, ErrorKind, Promise, Result, ResultExc, ThrowException, Value};
+use ion::class::Reflector;
+use ion::conversions::{IntoValue, ToValue};
+use runtime::globals::streams::{readable_stream, writable_stream};
+use runtime::promise::future_to_promise;
+
+use crate::net::options::Address;
+use crate::net::tls::{PeerCertificate, TlsInfo, TlsOptions};
+use crate::pipe::{Pipe, PipeSink, PipeSource};
+
+/// Represents a bidirectional byte stream, such as a TCP stream or a TLS stream over it.
+pub(crate) trait Stream: AsyncRead + AsyncWrite + Unpin {}
+
+impl<S: AsyncRead + AsyncWrite + Unpin> Stream for S {}
+
+pub(crate) type BoxedStream = Box<dyn Stream>;
+
+/// Represents a connection which has just been established, which is converted to a [Conn] when its promise resolves.
+pub(crate) struct Connection {
+	pub(crate) stream: BoxedStream,
+	pub(crate) hostname: Option<String>,
+	pub(crate) local: SocketAddr,
+	pub(crate) remote: SocketAddr,
+	pub(crate) tls: Option<TlsInfo>,
+}
+
+impl Connection {
+	fn into_conn(self, cx: &Context) -> ResultExc<Conn> {
+		let (reader, writer) = split(self.stream);
+		let (reader, writer) = (Pipe::new(reader), Pipe::new(writer));
+		let readable = readable_stream(cx, PipeSource::shared(Rc::clone(&reader)))?;
+		let writable = writable_stream(cx, PipeSink::shared(Rc::clone(&writer)))?;
+
+		Ok(Conn {
+			reflector: Reflector::default(),
+			hostname: self.hostname,
+			local: self.local,
+			remote: self.remote,
+			tls: self.tls,
+			reader,
+			writer,
+			readable: Heap::boxed(readable.handle().get()),
+			writable: Heap::boxed(writable.handle().get()),
+		})
+	}
+}
+
+impl<'cx> IntoValue<'cx> for Connection {
+	fn into_value(self: Box<Self>, cx: &'cx Context, value: &mut Value) {
+		match self.into_conn(cx) {
+			Ok(conn) => cx.root_object(Conn::new_object(cx, Box::new(conn))).handle().get().to_value(cx, value),
+			Err(exception) => exception.throw(cx),
+		}
+	}
+}
+
+/// Represents a TCP connection, which is read from and written to with its `readable` and `writable` streams.
+#[js_class]
+pub struct Conn {
+	reflector: Reflector,
+	#[ion(no_trace)]
+	hostname: Option<String>,
+	#[ion(no_trace)]
+	local: SocketAddr,
+	#[ion(no_trace)]
+	remote: SocketAddr,
+	#[ion(no_trace)]
+	tls: Option<TlsInfo>,
+	#[ion(no_trace)]
+	reader: Rc<Pipe<ReadHalf<BoxedStream>>>,
+	#[ion(no_trace)]
+	writer: Rc<Pipe<WriteHalf<BoxedStream>>>,
+	readable: Box<Heap<*mut JSObject>>,
+	writable: Box<Heap<*mut JSObject>>,
+}
+
+impl Conn {
+	/// Takes the stream of the connection from its readable and writable streams, which are ended.
+	async fn take(reader: &Pipe<ReadHalf<BoxedStream>>, writer: &Pipe<WriteHalf<BoxedStream>>) -> Option<BoxedStream> {
+		let reader = reader.take().await;
+		let writer = writer.take().await;
+		Some(reader?.unsplit(writer?))
+	}
+}
+
+#[js_class]
+impl Conn {
+	#[ion(constructor)]
+	pub fn constructor() -> Result<Conn> {
+		Err(Error::new("Conn has no constructor.", ErrorKind::Type))
+	}
+
+	/// Upgrades the connection to TLS, and resolves with the new connection.
+	/// The streams of this connection are ended, and must not be in use.
+	#[ion(name = "startTls")]
+	pub fn start_tls(&self, cx: &Context, options: Option<TlsOptions>) -> Option<Promise> {
+		let options = options.unwrap_or_default();
+		let (reader, writer) = (Rc::clone(&self.reader), Rc::clone(&self.writer));
+		let hostname = self.hostname.clone().unwrap_or_else(|| self.remote.ip().to_string());
+		let (local, remote) = (self.local, self.remote);
+
+		future_to_promise::<_, _, Error>(cx, async move {
+			let stream = Conn::take(&reader, &writer)
+				.await
+				.ok_or_else(|| Error::new("Connection is closed", None))?;
+			let (stream, tls) = options.connect(stream, &hostname).await?;
+			Ok(Connection {
+				stream,
+				hostname: Some(hostname),
+				local,
+				remote,
+				tls: Some(tls),
+			})
+		})
+	}
+
+	/// Closes the connection, ending its streams.
+	pub fn close(&self, cx: &Context) -> Option<Promise> {
+		let (reader, writer) = (Rc::clone(&self.reader), Rc::clone(&self.writer));
+		future_to_promise::<_, _, Error>(cx, async move {
+			Conn::take(&reader, &writer).await;
+			Ok(())
+		})
+	}
+
+	#[ion(get)]
+	pub fn get_readable(&self) -> *mut JSObject {
+		self.readable.get()
+	}
+
+	#[ion(get)]
+	pub fn get_writable(&self) -> *mut JSObject {
+		self.writable.get()
+	}
+
+	#[ion(get)]
+	pub fn get_local_addr(&self) -> Address {
+		Address(self.local)
+	}
+
+	#[ion(get)]
+	pub fn get_remote_addr(&self) -> Address {
+		Address(self.remote)
+	}
+
+	/// Returns the protocol negotiated with ALPN, or `null` if none was negotiated or the connection is not over TLS.
+	#[ion(get)]
+	pub fn get_alpn_protocol(&self) -> Option<String> {
+		self.tls.as_ref().and_then(|tls| tls.alpn_protocol.clone())
+	}
+
+	/// Returns the certificate chain presented by the peer, starting with its own certificate.
+	/// The chain is empty if the connection is not over TLS.
+	#[ion(name = "peerCertificates")]
+	pub fn peer_certificates(&self) -> Vec<PeerCertificate> {
+		self.tls
+			.as_ref()
+			.map(|tls| tls.peer_certificates.iter().cloned().map(PeerCertificate).collect())
+			.unwrap_or_default()
+	}
+}
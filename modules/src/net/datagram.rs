@@ -0,0 +1,217 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::cell::RefCell;
+use std::io;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::pin::pin;
+use std::rc::Rc;
+
+use futures::future::{Either, select};
+use futures::stream;
+use mozjs::conversions::ConversionBehavior::EnforceRange;
+use mozjs::typedarray::ArrayBufferView;
+use tokio::net::UdpSocket;
+use tokio::sync::Notify;
+
+use ion::{AsyncIterator, Context, Error, ErrorKind, Object, Promise, Result, Value};
+use ion::class::Reflector;
+use ion::conversions::ToValue;
+use ion::symbol::WellKnownSymbolCode;
+use ion::typedarray::Uint8Array;
+use runtime::event_loop::handles::{ActiveHandle, HandleKind};
+use runtime::permissions::check_net;
+use runtime::promise::{future_to_promise, future_to_promise_with_handle};
+
+use crate::net::options::{Address, Backoff, net_error};
+
+const MAX_DATAGRAM_SIZE: usize = 65536;
+
+/// Represents a datagram received by a [DatagramSocket], along with the address it was sent from.
+pub(crate) struct Datagram {
+	data: Vec<u8>,
+	addr: SocketAddr,
+}
+
+impl<'cx> ToValue<'cx> for Datagram {
+	fn to_value(&self, cx: &'cx Context, value: &mut Value) {
+		let mut object = Object::new(cx);
+		object.set_as(cx, "data", &Uint8Array::from(self.data.clone()));
+		object.set_as(cx, "addr", &Address(self.addr));
+		object.to_value(cx, value);
+	}
+}
+
+/// Holds the socket of a [DatagramSocket], which is closed once it is taken and all pending operations are interrupted.
+pub(crate) struct DatagramState {
+	socket: RefCell<Option<Rc<UdpSocket>>>,
+	closed: Notify,
+}
+
+impl DatagramState {
+	pub(crate) fn new(socket: UdpSocket) -> Rc<DatagramState> {
+		Rc::new(DatagramState {
+			socket: RefCell::new(Some(Rc::new(socket))),
+			closed: Notify::new(),
+		})
+	}
+
+	fn socket(&self) -> Result<Rc<UdpSocket>> {
+		self.socket.borrow().clone().ok_or_else(|| Error::new("Socket is closed", None))
+	}
+
+	/// Receives the next datagram, or returns [None] once the socket is closed.
+	async fn receive(&self) -> io::Result<Option<Datagram>> {
+		let Some(socket) = self.socket.borrow().clone() else {
+			return Ok(None);
+		};
+
+		let mut data = vec![0; MAX_DATAGRAM_SIZE];
+		let receive = pin!(socket.recv_from(&mut data));
+		let closed = pin!(self.closed.notified());
+		let (length, addr) = match select(receive, closed).await {
+			Either::Left((result, _)) => result?,
+			Either::Right(_) => return Ok(None),
+		};
+		data.truncate(length);
+		Ok(Some(Datagram { data, addr }))
+	}
+
+	pub(crate) fn close(&self) {
+		self.socket.borrow_mut().take();
+		self.closed.notify_waiters();
+	}
+}
+
+fn parse_ip(address: &str) -> Result<IpAddr> {
+	address
+		.parse()
+		.map_err(|_| Error::new(&format!("Invalid IP Address: {}", address), ErrorKind::Type))
+}
+
+/// Represents a UDP socket created with `net.listenDatagram`, which yields received datagrams when iterated with `for await`.
+#[js_class]
+pub struct DatagramSocket {
+	reflector: Reflector,
+	#[ion(no_trace)]
+	addr: SocketAddr,
+	#[ion(no_trace)]
+	state: Rc<DatagramState>,
+}
+
+impl DatagramSocket {
+	pub(crate) fn new(addr: SocketAddr, state: Rc<DatagramState>) -> DatagramSocket {
+		DatagramSocket {
+			reflector: Reflector::default(),
+			addr,
+			state,
+		}
+	}
+
+	/// Joins or leaves a multicast group. IPv4 groups use the given interface address, or any interface if it is not given.
+	fn multicast(&self, group: &str, interface: Option<String>, join: bool) -> Result<()> {
+		let socket = self.state.socket()?;
+		let operation = if join { "join multicast group" } else { "leave multicast group" };
+		let result = match parse_ip(group)? {
+			IpAddr::V4(group) => {
+				let interface = match interface.as_deref().map(parse_ip).transpose()? {
+					Some(IpAddr::V4(interface)) => interface,
+					Some(IpAddr::V6(_)) => return Err(Error::new("Expected IPv4 Interface Address", ErrorKind::Type)),
+					None => Ipv4Addr::UNSPECIFIED,
+				};
+				if join {
+					socket.join_multicast_v4(group, interface)
+				} else {
+					socket.leave_multicast_v4(group, interface)
+				}
+			}
+			IpAddr::V6(group) => {
+				if join {
+					socket.join_multicast_v6(&group, 0)
+				} else {
+					socket.leave_multicast_v6(&group, 0)
+				}
+			}
+		};
+		result.map_err(|error| net_error(error, operation, &group.to_string()))
+	}
+}
+
+#[js_class]
+impl DatagramSocket {
+	#[ion(constructor)]
+	pub fn constructor() -> Result<DatagramSocket> {
+		Err(Error::new("DatagramSocket has no constructor.", ErrorKind::Type))
+	}
+
+	/// Sends a datagram to an address, and resolves with the number of bytes sent.
+	pub fn send(&self, cx: &Context, data: ArrayBufferView, hostname: String, #[ion(convert = EnforceRange)] port: u16) -> Result<Option<Promise>> {
+		let socket = self.state.socket()?;
+		let data = unsafe { data.as_slice() }.to_vec();
+		let address = format!("{}:{}", hostname, port);
+		Ok(future_to_promise(cx, async move {
+			check_net(&hostname, Some(port))?;
+			let sent = socket.send_to(&data, (hostname.as_str(), port)).await;
+			sent.map(|sent| sent as f64)
+				.map_err(|error| net_error(error, "send datagram to", &address))
+		}))
+	}
+
+	/// Resolves with the next datagram received by the socket, or `null` once the socket is closed.
+	pub fn receive(&self, cx: &Context) -> Option<Promise> {
+		let state = Rc::clone(&self.state);
+		let addr = self.addr.to_string();
+		let handle = ActiveHandle::new(cx, HandleKind::Socket, format!("Receiving Datagrams on {}", addr));
+		future_to_promise_with_handle(cx, handle, async move {
+			state.receive().await.map_err(|error| net_error(error, "receive datagram on", &addr))
+		})
+	}
+
+	#[ion(name = "joinMulticast")]
+	pub fn join_multicast(&self, group: String, interface: Option<String>) -> Result<()> {
+		self.multicast(&group, interface, true)
+	}
+
+	#[ion(name = "leaveMulticast")]
+	pub fn leave_multicast(&self, group: String, interface: Option<String>) -> Result<()> {
+		self.multicast(&group, interface, false)
+	}
+
+	#[ion(name = "setBroadcast")]
+	pub fn set_broadcast(&self, broadcast: bool) -> Result<()> {
+		let socket = self.state.socket()?;
+		socket
+			.set_broadcast(broadcast)
+			.map_err(|error| net_error(error, "set broadcast on", &self.addr.to_string()))
+	}
+
+	/// Closes the socket. Pending and future receives resolve with `null`, and iteration ends.
+	pub fn close(&self) {
+		self.state.close();
+	}
+
+	#[ion(get)]
+	pub fn get_addr(&self) -> Address {
+		Address(self.addr)
+	}
+
+	/// Yields received datagrams until the socket is closed.
+	/// Receiving is retried after failures, such as ICMP errors from previous sends, after a delay which grows while it keeps failing.
+	#[ion(name = WellKnownSymbolCode::AsyncIterator)]
+	pub fn iterator(&self) -> AsyncIterator {
+		let datagrams = stream::unfold(Rc::clone(&self.state), |state| async move {
+			let mut backoff = Backoff::default();
+			loop {
+				match state.receive().await {
+					Ok(Some(datagram)) => return Some((datagram, state)),
+					Ok(None) => return None,
+					Err(_) => backoff.wait(state.closed.notified()).await,
+				}
+			}
+		});
+		AsyncIterator::new(datagrams)
+	}
+}
@@ -0,0 +1,149 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::cell::RefCell;
+use std::io;
+use std::net::SocketAddr;
+use std::pin::pin;
+use std::rc::Rc;
+
+use futures::future::{Either, select};
+use futures::stream;
+use mozjs::jsval::NullValue;
+use tokio::net::TcpListener;
+use tokio::sync::Notify;
+
+use ion::{AsyncIterator, Context, Error, ErrorKind, Promise, Result, Value};
+use ion::class::Reflector;
+use ion::conversions::IntoValue;
+use ion::symbol::WellKnownSymbolCode;
+use runtime::event_loop::handles::{ActiveHandle, HandleKind};
+use runtime::promise::future_to_promise_with_handle;
+
+use crate::net::conn::Connection;
+use crate::net::options::{Address, Backoff, net_error};
+
+/// Holds the socket of a [Listener], which is closed once it is taken and all pending accepts are interrupted.
+pub(crate) struct ListenerState {
+	listener: RefCell<Option<Rc<TcpListener>>>,
+	closed: Notify,
+}
+
+impl ListenerState {
+	pub(crate) fn new(listener: TcpListener) -> Rc<ListenerState> {
+		Rc::new(ListenerState {
+			listener: RefCell::new(Some(Rc::new(listener))),
+			closed: Notify::new(),
+		})
+	}
+
+	/// Accepts the next connection, or returns [None] once the listener is closed.
+	async fn accept(&self) -> io::Result<Option<Connection>> {
+		let Some(listener) = self.listener.borrow().clone() else {
+			return Ok(None);
+		};
+
+		let accept = pin!(listener.accept());
+		let closed = pin!(self.closed.notified());
+		match select(accept, closed).await {
+			Either::Left((result, _)) => {
+				let (stream, remote) = result?;
+				let local = stream.local_addr()?;
+				Ok(Some(Connection {
+					stream: Box::new(stream),
+					hostname: None,
+					local,
+					remote,
+					tls: None,
+				}))
+			}
+			Either::Right(_) => Ok(None),
+		}
+	}
+
+	pub(crate) fn close(&self) {
+		self.listener.borrow_mut().take();
+		self.closed.notify_waiters();
+	}
+}
+
+/// Represents the result of accepting a connection, which is `null` once the listener is closed.
+struct Accepted(Option<Connection>);
+
+impl<'cx> IntoValue<'cx> for Accepted {
+	fn into_value(self: Box<Self>, cx: &'cx Context, value: &mut Value) {
+		match self.0 {
+			Some(connection) => Box::new(connection).into_value(cx, value),
+			None => value.handle_mut().set(NullValue()),
+		}
+	}
+}
+
+/// Represents a TCP listener created with `net.listen`, which yields incoming connections when iterated with `for await`.
+#[js_class]
+pub struct Listener {
+	reflector: Reflector,
+	#[ion(no_trace)]
+	addr: SocketAddr,
+	#[ion(no_trace)]
+	state: Rc<ListenerState>,
+}
+
+impl Listener {
+	pub(crate) fn new(addr: SocketAddr, state: Rc<ListenerState>) -> Listener {
+		Listener {
+			reflector: Reflector::default(),
+			addr,
+			state,
+		}
+	}
+}
+
+#[js_class]
+impl Listener {
+	#[ion(constructor)]
+	pub fn constructor() -> Result<Listener> {
+		Err(Error::new("Listener has no constructor.", ErrorKind::Type))
+	}
+
+	/// Resolves with the next incoming connection, or `null` once the listener is closed.
+	pub fn accept(&self, cx: &Context) -> Option<Promise> {
+		let state = Rc::clone(&self.state);
+		let addr = self.addr.to_string();
+		let handle = ActiveHandle::new(cx, HandleKind::Socket, format!("Accepting Connections on {}", addr));
+		future_to_promise_with_handle(cx, handle, async move {
+			let connection = state.accept().await.map_err(|error| net_error(error, "accept connection on", &addr))?;
+			Ok::<_, Error>(Accepted(connection))
+		})
+	}
+
+	/// Closes the listener. Pending and future accepts resolve with `null`, and iteration ends.
+	pub fn close(&self) {
+		self.state.close();
+	}
+
+	#[ion(get)]
+	pub fn get_addr(&self) -> Address {
+		Address(self.addr)
+	}
+
+	/// Yields incoming connections until the listener is closed.
+	/// Connections which fail while being accepted are skipped, and accepting is retried after a delay which grows while it keeps failing.
+	#[ion(name = WellKnownSymbolCode::AsyncIterator)]
+	pub fn iterator(&self) -> AsyncIterator {
+		let connections = stream::unfold(Rc::clone(&self.state), |state| async move {
+			let mut backoff = Backoff::default();
+			loop {
+				match state.accept().await {
+					Ok(Some(connection)) => return Some((connection, state)),
+					Ok(None) => return None,
+					Err(_) => backoff.wait(state.closed.notified()).await,
+				}
+			}
+		});
+		AsyncIterator::new(connections)
+	}
+}
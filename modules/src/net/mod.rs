@@ -0,0 +1,17 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+pub use self::net::*;
+pub(crate) use self::net::establish;
+pub(crate) use self::options::{Address, Backoff, net_error};
+pub(crate) use self::tls::TlsOptions;
+
+mod conn;
+mod datagram;
+mod listener;
+mod net;
+mod options;
+mod tls;
@@ -0,0 +1,15 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+export const connect = ______netInternal______.connect;
+export const listen = ______netInternal______.listen;
+export const listenDatagram = ______netInternal______.listenDatagram;
+
+export const Conn = ______netInternal______.Conn;
+export const Listener = ______netInternal______.Listener;
+export const DatagramSocket = ______netInternal______.DatagramSocket;
+
+export default Object.freeze(______netInternal______);
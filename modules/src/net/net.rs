@@ -0,0 +1,155 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::net::{TcpListener as StdTcpListener, UdpSocket as StdUdpSocket};
+use std::pin::pin;
+use std::rc::Rc;
+
+use futures::future::{Either, select};
+use mozjs::conversions::ConversionBehavior::EnforceRange;
+use mozjs::jsapi::{JSFunctionSpec, JSObject};
+use tokio::net::{TcpListener, TcpStream, UdpSocket};
+
+use ion::{ClassDefinition, Context, Error, Exception, Object, Promise, Result};
+use runtime::event_loop::handles::{ActiveHandle, HandleKind};
+use runtime::globals::abort::Signal;
+use runtime::modules::NativeModule;
+use runtime::permissions::check_net;
+use runtime::promise::future_to_promise_with_handle;
+
+use crate::net::conn::{BoxedStream, Conn, Connection};
+use crate::net::datagram::{DatagramSocket, DatagramState};
+use crate::net::listener::{Listener, ListenerState};
+use crate::net::options::{ConnectOptions, DatagramOptions, ListenOptions, net_error};
+use crate::net::tls::TlsOptions;
+
+const DEFAULT_HOSTNAME: &str = "0.0.0.0";
+
+/// Connects to a TCP server, and resolves with the connection once it is established.
+/// If `tls` is given, the connection is upgraded to TLS before the promise resolves.
+#[js_fn]
+fn connect(cx: &Context, hostname: String, #[ion(convert = EnforceRange)] port: u16, options: Option<ConnectOptions>) -> Option<Promise> {
+	let options = options.unwrap_or_default();
+	establish(cx, hostname, port, options.tls, options.signal)
+}
+
+/// Establishes a TCP connection, which is upgraded to TLS if `tls` is given, unless the `signal` is aborted first.
+pub(crate) fn establish(cx: &Context, hostname: String, port: u16, tls: Option<TlsOptions>, signal: Signal) -> Option<Promise> {
+	let address = format!("{}:{}", hostname, port);
+
+	let handle = ActiveHandle::new(cx, HandleKind::Socket, format!("Connecting to {}", address));
+	future_to_promise_with_handle::<_, _, Exception>(cx, handle, async move {
+		let connect = async {
+			check_net(&hostname, Some(port))?;
+			let error = |error| net_error(error, "connect to", &address);
+			let stream = TcpStream::connect((hostname.as_str(), port)).await.map_err(error)?;
+			let local = stream.local_addr().map_err(error)?;
+			let remote = stream.peer_addr().map_err(error)?;
+
+			let mut stream: BoxedStream = Box::new(stream);
+			let mut info = None;
+			if let Some(tls) = &tls {
+				let (tls_stream, tls_info) = tls.connect(stream, &hostname).await?;
+				stream = tls_stream;
+				info = Some(tls_info);
+			}
+			Ok::<_, Error>(Connection {
+				stream,
+				hostname: Some(hostname.clone()),
+				local,
+				remote,
+				tls: info,
+			})
+		};
+
+		match select(signal.poll(), pin!(connect)).await {
+			Either::Left((reason, _)) => Err(Exception::Other(reason)),
+			Either::Right((connection, _)) => Ok(connection?),
+		}
+	})
+}
+
+/// Listens for TCP connections on a port, which is chosen by the system if it is 0.
+/// The listener is closed when the given `signal` is aborted.
+#[js_fn]
+fn listen(cx: &Context, #[ion(convert = EnforceRange)] port: u16, options: Option<ListenOptions>) -> Result<*mut JSObject> {
+	let options = options.unwrap_or_default();
+	let hostname = options.hostname.as_deref().unwrap_or(DEFAULT_HOSTNAME);
+	let address = format!("{}:{}", hostname, port);
+	let error = |error| net_error(error, "listen on", &address);
+
+	check_net(hostname, Some(port))?;
+	let listener = StdTcpListener::bind((hostname, port)).map_err(error)?;
+	listener.set_nonblocking(true).map_err(error)?;
+	let listener = TcpListener::from_std(listener).map_err(error)?;
+	let addr = listener.local_addr().map_err(error)?;
+
+	let state = ListenerState::new(listener);
+	options.signal.on_abort({
+		let state = Rc::clone(&state);
+		move |_| state.close()
+	});
+	Ok(Listener::new_object(cx, Box::new(Listener::new(addr, state))))
+}
+
+/// Binds a UDP socket to a port, which is chosen by the system if it is 0.
+/// The socket is closed when the given `signal` is aborted.
+#[js_fn]
+fn listenDatagram(cx: &Context, #[ion(convert = EnforceRange)] port: u16, options: Option<DatagramOptions>) -> Result<*mut JSObject> {
+	let options = options.unwrap_or_default();
+	let hostname = options.hostname.as_deref().unwrap_or(DEFAULT_HOSTNAME);
+	let address = format!("{}:{}", hostname, port);
+	let error = |error| net_error(error, "listen on", &address);
+
+	check_net(hostname, Some(port))?;
+	let socket = StdUdpSocket::bind((hostname, port)).map_err(error)?;
+	socket.set_nonblocking(true).map_err(error)?;
+	socket.set_broadcast(options.broadcast).map_err(error)?;
+	let socket = UdpSocket::from_std(socket).map_err(error)?;
+	let addr = socket.local_addr().map_err(error)?;
+	if let Some(multicast_loop) = options.multicast_loop {
+		let result = if addr.is_ipv4() {
+			socket.set_multicast_loop_v4(multicast_loop)
+		} else {
+			socket.set_multicast_loop_v6(multicast_loop)
+		};
+		result.map_err(error)?;
+	}
+
+	let state = DatagramState::new(socket);
+	options.signal.on_abort({
+		let state = Rc::clone(&state);
+		move |_| state.close()
+	});
+	Ok(DatagramSocket::new_object(cx, Box::new(DatagramSocket::new(addr, state))))
+}
+
+const FUNCTIONS: &[JSFunctionSpec] = &[
+	function_spec!(connect, 2),
+	function_spec!(listen, 1),
+	function_spec!(listenDatagram, 1),
+	JSFunctionSpec::ZERO,
+];
+
+#[derive(Default)]
+pub struct Net;
+
+impl NativeModule for Net {
+	const NAME: &'static str = "net";
+	const SOURCE: &'static str = include_str!("net.js");
+
+	fn module(cx: &Context) -> Option<Object> {
+		let mut net = Object::new(cx);
+		if unsafe { net.define_methods(cx, FUNCTIONS) }
+			&& Conn::init_class(cx, &mut net).0
+			&& Listener::init_class(cx, &mut net).0
+			&& DatagramSocket::init_class(cx, &mut net).0
+		{
+			return Some(net);
+		}
+		None
+	}
+}
@@ -0,0 +1,107 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::future::Future;
+use std::io;
+use std::io::ErrorKind as IoErrorKind;
+use std::net::SocketAddr;
+use std::pin::pin;
+use std::time::Duration;
+
+use futures::future::select;
+use tokio::time::sleep;
+
+use ion::{Context, Error, Object, Value};
+use ion::conversions::ToValue;
+use runtime::globals::abort::Signal;
+
+use crate::net::tls::TlsOptions;
+
+/// Converts an I/O error from an operation on an address into an [Error].
+/// The `code` of the error is set to the name of the corresponding system error, where one is known.
+pub(crate) fn net_error(error: io::Error, operation: &str, address: &str) -> Error {
+	let code = match error.kind() {
+		IoErrorKind::ConnectionRefused => Some("ECONNREFUSED"),
+		IoErrorKind::ConnectionReset => Some("ECONNRESET"),
+		IoErrorKind::ConnectionAborted => Some("ECONNABORTED"),
+		IoErrorKind::AddrInUse => Some("EADDRINUSE"),
+		IoErrorKind::AddrNotAvailable => Some("EADDRNOTAVAIL"),
+		IoErrorKind::PermissionDenied => Some("EACCES"),
+		IoErrorKind::TimedOut => Some("ETIMEDOUT"),
+		IoErrorKind::NotFound => Some("ENOTFOUND"),
+		_ => None,
+	};
+
+	let error = Error::new(&format!("Could not {} {}: {}", operation, address, error), None);
+	match code {
+		Some(code) => error.with_code(code),
+		None => error,
+	}
+}
+
+const MIN_BACKOFF: Duration = Duration::from_millis(5);
+const MAX_BACKOFF: Duration = Duration::from_secs(1);
+
+/// Delays retrying an operation of a socket which failed, such as accepting a connection when the process has run out of file descriptors.
+/// The delay doubles after each failure, so that persistent failures are not retried in a busy loop.
+pub(crate) struct Backoff {
+	delay: Duration,
+}
+
+impl Backoff {
+	/// Waits until the operation should be retried, or `closed` completes.
+	pub(crate) async fn wait<F: Future>(&mut self, closed: F) {
+		select(pin!(sleep(self.delay)), pin!(closed)).await;
+		self.delay = (self.delay * 2).min(MAX_BACKOFF);
+	}
+}
+
+impl Default for Backoff {
+	fn default() -> Backoff {
+		Backoff { delay: MIN_BACKOFF }
+	}
+}
+
+#[derive(Default, FromValue)]
+pub(crate) struct ConnectOptions {
+	/// Upgrades the connection to TLS once it is established.
+	pub(crate) tls: Option<TlsOptions>,
+	#[ion(default)]
+	pub(crate) signal: Signal,
+}
+
+#[derive(Default, FromValue)]
+pub(crate) struct ListenOptions {
+	pub(crate) hostname: Option<String>,
+	#[ion(default)]
+	pub(crate) signal: Signal,
+}
+
+#[derive(Default, FromValue)]
+pub(crate) struct DatagramOptions {
+	pub(crate) hostname: Option<String>,
+	/// Allows datagrams to be sent to broadcast addresses.
+	#[ion(default)]
+	pub(crate) broadcast: bool,
+	/// Delivers multicast datagrams sent by the socket back to itself.
+	#[ion(default, name = "multicastLoop")]
+	pub(crate) multicast_loop: Option<bool>,
+	#[ion(default)]
+	pub(crate) signal: Signal,
+}
+
+/// Represents the address of an endpoint of a connection, or of a listener or datagram socket.
+pub(crate) struct Address(pub(crate) SocketAddr);
+
+impl<'cx> ToValue<'cx> for Address {
+	fn to_value(&self, cx: &'cx Context, value: &mut Value) {
+		let mut object = Object::new(cx);
+		object.set_as(cx, "hostname", &self.0.ip().to_string());
+		object.set_as(cx, "port", &self.0.port());
+		object.set_as(cx, "family", if self.0.is_ipv4() { "IPv4" } else { "IPv6" });
+		object.to_value(cx, value);
+	}
+}
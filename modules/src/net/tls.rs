@@ -0,0 +1,162 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::net::IpAddr;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use rustls_pemfile::Item;
+use tokio_rustls::rustls::{Certificate, ClientConfig, OwnedTrustAnchor, PrivateKey, RootCertStore, ServerName};
+use tokio_rustls::TlsConnector;
+use x509_parser::certificate::X509Certificate;
+use x509_parser::extensions::GeneralName;
+use x509_parser::prelude::FromDer;
+use x509_parser::time::ASN1Time;
+
+use ion::{Context, Error, ErrorKind, Object, Result, Value};
+use ion::conversions::ToValue;
+use ion::typedarray::Uint8Array;
+
+use crate::net::conn::BoxedStream;
+use crate::net::options::net_error;
+
+#[derive(Default, FromValue)]
+pub(crate) struct TlsOptions {
+	/// Name of the server, which is verified against its certificate. Defaults to the hostname which was connected to.
+	pub(crate) hostname: Option<String>,
+	/// PEM-encoded certificates which are trusted in addition to the bundled root certificates.
+	#[ion(default, name = "caCerts")]
+	pub(crate) ca_certs: Vec<String>,
+	/// Protocols offered with ALPN, in order of preference.
+	#[ion(default, name = "alpnProtocols")]
+	pub(crate) alpn_protocols: Vec<String>,
+	/// PEM-encoded certificate chain presented to servers which request client authentication.
+	#[ion(name = "certChain")]
+	pub(crate) cert_chain: Option<String>,
+	/// PEM-encoded private key of the client certificate, in PKCS #8, PKCS #1 or SEC1 format.
+	#[ion(name = "privateKey")]
+	pub(crate) private_key: Option<String>,
+}
+
+/// Represents the outcome of a TLS handshake, which is inspected through the resulting connection.
+pub(crate) struct TlsInfo {
+	pub(crate) alpn_protocol: Option<String>,
+	pub(crate) peer_certificates: Vec<Vec<u8>>,
+}
+
+fn pem_certificates(pem: &str) -> Result<Vec<Certificate>> {
+	let certificates = rustls_pemfile::certs(&mut pem.as_bytes()).map_err(|_| Error::new("Invalid PEM Certificate", ErrorKind::Type))?;
+	Ok(certificates.into_iter().map(Certificate).collect())
+}
+
+fn pem_private_key(pem: &str) -> Result<PrivateKey> {
+	let items = rustls_pemfile::read_all(&mut pem.as_bytes()).map_err(|_| Error::new("Invalid PEM Private Key", ErrorKind::Type))?;
+	items
+		.into_iter()
+		.find_map(|item| match item {
+			Item::PKCS8Key(key) | Item::RSAKey(key) | Item::ECKey(key) => Some(PrivateKey(key)),
+			_ => None,
+		})
+		.ok_or_else(|| Error::new("Expected PEM Private Key", ErrorKind::Type))
+}
+
+impl TlsOptions {
+	fn client_config(&self) -> Result<ClientConfig> {
+		let mut roots = RootCertStore::empty();
+		roots.add_trust_anchors(
+			webpki_roots::TLS_SERVER_ROOTS
+				.iter()
+				.map(|anchor| OwnedTrustAnchor::from_subject_spki_name_constraints(anchor.subject, anchor.spki, anchor.name_constraints)),
+		);
+
+		for pem in &self.ca_certs {
+			for certificate in pem_certificates(pem)? {
+				roots
+					.add(&certificate)
+					.map_err(|error| Error::new(&format!("Invalid Certificate: {}", error), ErrorKind::Type))?;
+			}
+		}
+
+		let builder = ClientConfig::builder().with_safe_defaults().with_root_certificates(roots);
+		let mut config = match (&self.cert_chain, &self.private_key) {
+			(Some(chain), Some(key)) => builder
+				.with_client_auth_cert(pem_certificates(chain)?, pem_private_key(key)?)
+				.map_err(|error| Error::new(&format!("Invalid Client Certificate: {}", error), ErrorKind::Type))?,
+			(None, None) => builder.with_no_client_auth(),
+			_ => return Err(Error::new("Client certificates require both certChain and privateKey", ErrorKind::Type)),
+		};
+		config.alpn_protocols = self.alpn_protocols.iter().map(|protocol| protocol.as_bytes().to_vec()).collect();
+		Ok(config)
+	}
+
+	/// Performs a TLS handshake as a client over a stream, verifying the certificate of the server.
+	/// The name sent with SNI is the name which is verified.
+	pub(crate) async fn connect(&self, stream: BoxedStream, hostname: &str) -> Result<(BoxedStream, TlsInfo)> {
+		let hostname = self.hostname.as_deref().unwrap_or(hostname);
+		let name = ServerName::try_from(hostname).map_err(|_| Error::new(&format!("Invalid Server Name: {}", hostname), ErrorKind::Type))?;
+		let connector = TlsConnector::from(Arc::new(self.client_config()?));
+
+		let stream = connector
+			.connect(name, stream)
+			.await
+			.map_err(|error| net_error(error, "negotiate TLS with", hostname))?;
+
+		let (_, connection) = stream.get_ref();
+		let info = TlsInfo {
+			alpn_protocol: connection.alpn_protocol().map(|protocol| String::from_utf8_lossy(protocol).into_owned()),
+			peer_certificates: connection
+				.peer_certificates()
+				.map(|certificates| certificates.iter().map(|certificate| certificate.0.clone()).collect())
+				.unwrap_or_default(),
+		};
+		Ok((Box::new(stream), info))
+	}
+}
+
+/// Represents a DER-encoded certificate presented by a peer, which is converted to an object describing its contents.
+pub(crate) struct PeerCertificate(pub(crate) Vec<u8>);
+
+fn asn1_time(time: ASN1Time) -> SystemTime {
+	let timestamp = time.timestamp();
+	if timestamp >= 0 {
+		UNIX_EPOCH + Duration::from_secs(timestamp as u64)
+	} else {
+		UNIX_EPOCH - Duration::from_secs(timestamp.unsigned_abs())
+	}
+}
+
+fn general_name(name: &GeneralName) -> Option<String> {
+	match name {
+		GeneralName::DNSName(name) | GeneralName::RFC822Name(name) | GeneralName::URI(name) => Some(String::from(*name)),
+		GeneralName::IPAddress(bytes) => match bytes.len() {
+			4 => Some(IpAddr::from(<[u8; 4]>::try_from(*bytes).unwrap()).to_string()),
+			16 => Some(IpAddr::from(<[u8; 16]>::try_from(*bytes).unwrap()).to_string()),
+			_ => None,
+		},
+		_ => None,
+	}
+}
+
+impl<'cx> ToValue<'cx> for PeerCertificate {
+	fn to_value(&self, cx: &'cx Context, value: &mut Value) {
+		let mut object = Object::new(cx);
+		if let Ok((_, certificate)) = X509Certificate::from_der(&self.0) {
+			let names = certificate.subject_alternative_name().ok().flatten();
+			let names: Vec<_> = names
+				.map(|names| names.value.general_names.iter().filter_map(general_name).collect())
+				.unwrap_or_default();
+
+			object.set_as(cx, "subject", &certificate.subject().to_string());
+			object.set_as(cx, "issuer", &certificate.issuer().to_string());
+			object.set_as(cx, "serialNumber", &certificate.raw_serial_as_string());
+			object.set_as(cx, "validFrom", &asn1_time(certificate.validity().not_before));
+			object.set_as(cx, "validTo", &asn1_time(certificate.validity().not_after));
+			object.set_as(cx, "subjectAltNames", &names);
+		}
+		object.set_as(cx, "raw", &Uint8Array::from(self.0.clone()));
+		object.to_value(cx, value);
+	}
+}
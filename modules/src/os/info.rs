@@ -0,0 +1,127 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::collections::BTreeMap;
+
+use if_addrs::{IfAddr, Interface};
+
+use ion::{Context, Object, Value};
+use ion::conversions::ToValue;
+
+/// Represents the time a CPU has spent in each mode, in milliseconds.
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct CpuTimes {
+	pub(crate) user: f64,
+	pub(crate) nice: f64,
+	pub(crate) sys: f64,
+	pub(crate) idle: f64,
+	pub(crate) irq: f64,
+}
+
+impl<'cx> ToValue<'cx> for CpuTimes {
+	fn to_value(&self, cx: &'cx Context, value: &mut Value) {
+		let mut object = Object::new(cx);
+		object.set_as(cx, "user", &self.user);
+		object.set_as(cx, "nice", &self.nice);
+		object.set_as(cx, "sys", &self.sys);
+		object.set_as(cx, "idle", &self.idle);
+		object.set_as(cx, "irq", &self.irq);
+		object.to_value(cx, value);
+	}
+}
+
+/// Represents a logical CPU, returned by `cpus`. The speed of the CPU is in MHz.
+pub(crate) struct CpuInfo {
+	pub(crate) model: String,
+	pub(crate) speed: f64,
+	pub(crate) times: Option<CpuTimes>,
+}
+
+impl<'cx> ToValue<'cx> for CpuInfo {
+	fn to_value(&self, cx: &'cx Context, value: &mut Value) {
+		let mut object = Object::new(cx);
+		object.set_as(cx, "model", &self.model);
+		object.set_as(cx, "speed", &self.speed);
+		object.set_as(cx, "times", &self.times);
+		object.to_value(cx, value);
+	}
+}
+
+/// Reads the times of each logical CPU from `/proc/stat`, which are measured in units of 10 milliseconds.
+#[cfg(target_os = "linux")]
+pub(crate) fn cpu_times() -> Vec<CpuTimes> {
+	const MILLISECONDS_PER_TICK: f64 = 10.0;
+
+	let stat = std::fs::read_to_string("/proc/stat").unwrap_or_default();
+	stat.lines()
+		.filter(|line| line.starts_with("cpu") && line.as_bytes().get(3).is_some_and(u8::is_ascii_digit))
+		.map(|line| {
+			let ticks: Vec<f64> = line.split_whitespace().skip(1).map(|ticks| ticks.parse().unwrap_or(0.0)).collect();
+			let tick = |index: usize| ticks.get(index).copied().unwrap_or(0.0) * MILLISECONDS_PER_TICK;
+			CpuTimes {
+				user: tick(0),
+				nice: tick(1),
+				sys: tick(2),
+				idle: tick(3),
+				irq: tick(5),
+			}
+		})
+		.collect()
+}
+
+#[cfg(not(target_os = "linux"))]
+pub(crate) fn cpu_times() -> Vec<CpuTimes> {
+	Vec::new()
+}
+
+/// Represents an address assigned to a network interface.
+pub(crate) struct InterfaceAddress(Interface);
+
+impl<'cx> ToValue<'cx> for InterfaceAddress {
+	fn to_value(&self, cx: &'cx Context, value: &mut Value) {
+		let (family, netmask) = match &self.0.addr {
+			IfAddr::V4(address) => ("IPv4", address.netmask.to_string()),
+			IfAddr::V6(address) => ("IPv6", address.netmask.to_string()),
+		};
+		let prefix: u32 = match &self.0.addr {
+			IfAddr::V4(address) => address.netmask.octets().iter().map(|octet| octet.count_ones()).sum(),
+			IfAddr::V6(address) => address.netmask.octets().iter().map(|octet| octet.count_ones()).sum(),
+		};
+		let address = self.0.ip().to_string();
+
+		let mut object = Object::new(cx);
+		object.set_as(cx, "address", &address);
+		object.set_as(cx, "netmask", &netmask);
+		object.set_as(cx, "family", family);
+		object.set_as(cx, "internal", &self.0.is_loopback());
+		object.set_as(cx, "cidr", &format!("{}/{}", address, prefix));
+		object.to_value(cx, value);
+	}
+}
+
+/// Represents the addresses of each network interface, keyed by the name of the interface.
+#[derive(Default)]
+pub(crate) struct NetworkInterfaces(BTreeMap<String, Vec<InterfaceAddress>>);
+
+impl FromIterator<Interface> for NetworkInterfaces {
+	fn from_iter<I: IntoIterator<Item = Interface>>(interfaces: I) -> NetworkInterfaces {
+		let mut map: BTreeMap<_, Vec<_>> = BTreeMap::new();
+		for interface in interfaces {
+			map.entry(interface.name.clone()).or_default().push(InterfaceAddress(interface));
+		}
+		NetworkInterfaces(map)
+	}
+}
+
+impl<'cx> ToValue<'cx> for NetworkInterfaces {
+	fn to_value(&self, cx: &'cx Context, value: &mut Value) {
+		let mut object = Object::new(cx);
+		for (name, addresses) in &self.0 {
+			object.set_as(cx, name.as_str(), addresses);
+		}
+		object.to_value(cx, value);
+	}
+}
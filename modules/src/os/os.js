@@ -0,0 +1,20 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+export const platform = ______osInternal______.platform;
+export const arch = ______osInternal______.arch;
+export const EOL = ______osInternal______.EOL;
+
+export const hostname = ______osInternal______.hostname;
+export const cpus = ______osInternal______.cpus;
+export const totalmem = ______osInternal______.totalmem;
+export const freemem = ______osInternal______.freemem;
+export const uptime = ______osInternal______.uptime;
+export const homedir = ______osInternal______.homedir;
+export const tmpdir = ______osInternal______.tmpdir;
+export const networkInterfaces = ______osInternal______.networkInterfaces;
+
+export default Object.freeze(______osInternal______);
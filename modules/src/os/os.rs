@@ -0,0 +1,107 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::env;
+
+use mozjs::jsapi::JSFunctionSpec;
+use sysinfo::{CpuExt, CpuRefreshKind, RefreshKind, System, SystemExt};
+
+use ion::{Context, Error, Object, Result};
+use ion::flags::PropertyFlags;
+use runtime::modules::NativeModule;
+
+use crate::os::info::{cpu_times, CpuInfo, NetworkInterfaces};
+
+#[cfg(windows)]
+const EOL: &str = "\r\n";
+#[cfg(not(windows))]
+const EOL: &str = "\n";
+
+#[js_fn]
+fn hostname() -> Option<String> {
+	System::new().host_name()
+}
+
+/// Returns the model, speed and times of each logical CPU.
+/// The times are `null` on platforms where they are not available.
+#[js_fn]
+fn cpus() -> Vec<CpuInfo> {
+	let system = System::new_with_specifics(RefreshKind::new().with_cpu(CpuRefreshKind::everything()));
+	let mut times = cpu_times().into_iter();
+	system
+		.cpus()
+		.iter()
+		.map(|cpu| CpuInfo {
+			model: String::from(cpu.brand()),
+			speed: cpu.frequency() as f64,
+			times: times.next(),
+		})
+		.collect()
+}
+
+#[js_fn]
+fn totalmem() -> f64 {
+	System::new_with_specifics(RefreshKind::new().with_memory()).total_memory() as f64
+}
+
+#[js_fn]
+fn freemem() -> f64 {
+	System::new_with_specifics(RefreshKind::new().with_memory()).available_memory() as f64
+}
+
+/// Returns the time since the system booted, in seconds.
+#[js_fn]
+fn uptime() -> f64 {
+	System::new().uptime() as f64
+}
+
+#[js_fn]
+fn homedir() -> Option<String> {
+	dirs::home_dir().map(|home| home.to_string_lossy().into_owned())
+}
+
+#[js_fn]
+fn tmpdir() -> String {
+	env::temp_dir().to_string_lossy().into_owned()
+}
+
+#[js_fn]
+fn networkInterfaces() -> Result<NetworkInterfaces> {
+	let interfaces = if_addrs::get_if_addrs().map_err(|error| Error::new(&format!("Could not get network interfaces: {}", error), None))?;
+	Ok(interfaces.into_iter().collect())
+}
+
+const FUNCTIONS: &[JSFunctionSpec] = &[
+	function_spec!(hostname, 0),
+	function_spec!(cpus, 0),
+	function_spec!(totalmem, 0),
+	function_spec!(freemem, 0),
+	function_spec!(uptime, 0),
+	function_spec!(homedir, 0),
+	function_spec!(tmpdir, 0),
+	function_spec!(networkInterfaces, 0),
+	JSFunctionSpec::ZERO,
+];
+
+#[derive(Default)]
+pub struct OperatingSystem;
+
+impl NativeModule for OperatingSystem {
+	const NAME: &'static str = "os";
+	const SOURCE: &'static str = include_str!("os.js");
+
+	fn module(cx: &Context) -> Option<Object> {
+		let mut os = Object::new(cx);
+		if unsafe { os.define_methods(cx, FUNCTIONS) }
+			&& os.define_as(cx, "platform", env::consts::OS, PropertyFlags::CONSTANT_ENUMERATED)
+			&& os.define_as(cx, "arch", env::consts::ARCH, PropertyFlags::CONSTANT_ENUMERATED)
+			&& os.define_as(cx, "EOL", EOL, PropertyFlags::CONSTANT_ENUMERATED)
+		{
+			return Some(os);
+		}
+		None
+	}
+}
@@ -0,0 +1,308 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use ion::{Context, Object, Value};
+use ion::conversions::ToValue;
+
+/// Represents the conventions of paths on a platform, which are applied lexically without accessing the file system.
+///
+/// `..` components are resolved in the same manner as module specifiers, removing the preceding normal component if there is one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Flavour {
+	Posix,
+	Windows,
+}
+
+/// Represents the components of a path, as returned by `parse` and accepted by `format`.
+#[derive(Debug, Default, FromValue)]
+pub(crate) struct ParsedPath {
+	#[ion(default)]
+	pub(crate) root: String,
+	#[ion(default)]
+	pub(crate) dir: String,
+	#[ion(default)]
+	pub(crate) base: String,
+	#[ion(default)]
+	pub(crate) ext: String,
+	#[ion(default)]
+	pub(crate) name: String,
+}
+
+impl<'cx> ToValue<'cx> for ParsedPath {
+	fn to_value(&self, cx: &'cx Context, value: &mut Value) {
+		let mut object = Object::new(cx);
+		object.set_as(cx, "root", &self.root);
+		object.set_as(cx, "dir", &self.dir);
+		object.set_as(cx, "base", &self.base);
+		object.set_as(cx, "ext", &self.ext);
+		object.set_as(cx, "name", &self.name);
+		object.to_value(cx, value);
+	}
+}
+
+impl Flavour {
+	#[cfg(windows)]
+	pub(crate) const NATIVE: Flavour = Flavour::Windows;
+	#[cfg(not(windows))]
+	pub(crate) const NATIVE: Flavour = Flavour::Posix;
+
+	pub(crate) fn separator(self) -> char {
+		match self {
+			Flavour::Posix => '/',
+			Flavour::Windows => '\\',
+		}
+	}
+
+	pub(crate) fn delimiter(self) -> char {
+		match self {
+			Flavour::Posix => ':',
+			Flavour::Windows => ';',
+		}
+	}
+
+	fn is_separator(self, char: char) -> bool {
+		char == '/' || (self == Flavour::Windows && char == '\\')
+	}
+
+	/// Splits a path into its root and the remainder of the path.
+	/// On Windows, the root is either a UNC prefix (`\\server\share\`), a drive (`C:\` or `C:`) or a separator.
+	fn split_root(self, path: &str) -> (&str, &str) {
+		let bytes = path.as_bytes();
+		let separator = |index: usize| bytes.get(index).is_some_and(|&byte| self.is_separator(byte as char));
+
+		let length = match self {
+			Flavour::Posix => usize::from(separator(0)),
+			Flavour::Windows => {
+				if separator(0) && separator(1) && bytes.len() > 2 && !separator(2) {
+					let server = (2..bytes.len()).find(|&index| separator(index));
+					let share = server.and_then(|server| (server + 1..bytes.len()).find(|&index| separator(index)));
+					match (server, share) {
+						(Some(_), Some(share)) => share + 1,
+						(Some(_), None) => bytes.len(),
+						(None, _) => 1,
+					}
+				} else if bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':' {
+					2 + usize::from(separator(2))
+				} else {
+					usize::from(separator(0))
+				}
+			}
+		};
+		path.split_at(length)
+	}
+
+	fn normalise_root(self, root: &str) -> String {
+		root.chars()
+			.map(|char| if self.is_separator(char) { self.separator() } else { char })
+			.collect()
+	}
+
+	fn components(self, path: &str) -> impl DoubleEndedIterator<Item = &str> + '_ {
+		path.split(move |char| self.is_separator(char)).filter(|component| !component.is_empty())
+	}
+
+	pub(crate) fn is_absolute(self, path: &str) -> bool {
+		let (root, _) = self.split_root(path);
+		root.starts_with(|char| self.is_separator(char)) || root.ends_with(|char| self.is_separator(char))
+	}
+
+	/// Checks if a path is absolute and, on Windows, also specifies a drive or UNC prefix.
+	fn is_resolved(self, path: &str) -> bool {
+		let (root, _) = self.split_root(path);
+		self.is_absolute(path) && (self == Flavour::Posix || root.len() > 1)
+	}
+
+	pub(crate) fn normalise(self, path: &str) -> String {
+		if path.is_empty() {
+			return String::from(".");
+		}
+
+		let (root, rest) = self.split_root(path);
+		let absolute = self.is_absolute(path);
+		let mut components = Vec::new();
+		for component in self.components(rest) {
+			match component {
+				"." => {}
+				".." => match components.last() {
+					Some(&last) if last != ".." => {
+						components.pop();
+					}
+					_ if absolute => {}
+					_ => components.push(component),
+				},
+				component => components.push(component),
+			}
+		}
+
+		let mut normalised = self.normalise_root(root);
+		normalised.push_str(&components.join(&self.separator().to_string()));
+		if normalised.is_empty() {
+			normalised.push('.');
+		} else if !components.is_empty() && rest.ends_with(|char| self.is_separator(char)) {
+			normalised.push(self.separator());
+		} else if components.is_empty() && !absolute && !root.is_empty() {
+			normalised.push('.');
+		}
+		normalised
+	}
+
+	pub(crate) fn join(self, segments: &[String]) -> String {
+		let segments: Vec<_> = segments.iter().filter(|segment| !segment.is_empty()).map(String::as_str).collect();
+		if segments.is_empty() {
+			return String::from(".");
+		}
+		self.normalise(&segments.join(&self.separator().to_string()))
+	}
+
+	/// Resolves a sequence of paths into an absolute path, from right to left, until an absolute path is formed.
+	/// If none of the segments are absolute, they are resolved against `cwd`.
+	pub(crate) fn resolve(self, segments: &[String], cwd: &str) -> String {
+		let mut resolved = String::new();
+		for segment in segments.iter().rev().filter(|segment| !segment.is_empty()) {
+			if self.is_absolute(&resolved) {
+				// Paths such as `\dir` are on the drive of the preceding segments, if any of them specify one.
+				let (root, _) = self.split_root(segment);
+				if root.len() > 1 {
+					resolved = format!("{}{}", root.trim_end_matches(|char| self.is_separator(char)), resolved);
+					break;
+				}
+				continue;
+			}
+
+			resolved = if resolved.is_empty() {
+				segment.clone()
+			} else {
+				format!("{}{}{}", segment, self.separator(), resolved)
+			};
+			if self.is_resolved(&resolved) {
+				break;
+			}
+		}
+
+		if !self.is_resolved(&resolved) {
+			let (root, _) = self.split_root(&resolved);
+			let (cwd_root, _) = self.split_root(cwd);
+			resolved = if root.is_empty() {
+				format!("{}{}{}", cwd, self.separator(), resolved)
+			} else if root.len() > 1 && cwd_root.to_ascii_lowercase().starts_with(&root.to_ascii_lowercase()) {
+				// Paths such as `C:dir` are relative to the current directory, if it is on the same drive.
+				format!("{}{}{}", cwd, self.separator(), &resolved[root.len()..])
+			} else {
+				// Paths such as `\dir` are relative to the root of the drive of the current directory, and paths such as `D:dir` to the root of their drive.
+				let drive = if root.len() == 1 { cwd_root } else { root };
+				let drive = drive.trim_end_matches(|char| self.is_separator(char));
+				format!("{}{}{}", drive, self.separator(), &resolved[root.len()..])
+			};
+		}
+
+		let normalised = self.normalise(&resolved);
+		let (root, rest) = self.split_root(&normalised);
+		if rest.is_empty() {
+			normalised
+		} else {
+			format!("{}{}", root, rest.trim_end_matches(|char| self.is_separator(char)))
+		}
+	}
+
+	/// Returns the relative path from `from` to `to`, after resolving both against `cwd`.
+	pub(crate) fn relative(self, from: &str, to: &str, cwd: &str) -> String {
+		let from = self.resolve(&[String::from(from)], cwd);
+		let to = self.resolve(&[String::from(to)], cwd);
+		let equal = |a: &str, b: &str| match self {
+			Flavour::Posix => a == b,
+			Flavour::Windows => a.eq_ignore_ascii_case(b),
+		};
+
+		let (from_root, from_rest) = self.split_root(&from);
+		let (to_root, to_rest) = self.split_root(&to);
+		if !equal(from_root, to_root) {
+			return to;
+		}
+
+		let from: Vec<_> = self.components(from_rest).collect();
+		let to: Vec<_> = self.components(to_rest).collect();
+		let common = from.iter().zip(&to).take_while(|(a, b)| equal(a, b)).count();
+
+		let parents = (common..from.len()).map(|_| "..");
+		let relative: Vec<_> = parents.chain(to[common..].iter().copied()).collect();
+		relative.join(&self.separator().to_string())
+	}
+
+	pub(crate) fn dirname(self, path: &str) -> String {
+		let (root, rest) = self.split_root(path);
+		let rest = rest.trim_end_matches(|char| self.is_separator(char));
+		match rest.rfind(|char| self.is_separator(char)) {
+			Some(index) => {
+				let dir = rest[..index].trim_end_matches(|char| self.is_separator(char));
+				format!("{}{}", root, dir)
+			}
+			None if root.is_empty() => String::from("."),
+			None => String::from(root),
+		}
+	}
+
+	pub(crate) fn basename(self, path: &str, extension: Option<&str>) -> String {
+		let (_, rest) = self.split_root(path);
+		let rest = rest.trim_end_matches(|char| self.is_separator(char));
+		let base = rest.rsplit(|char| self.is_separator(char)).next().unwrap_or_default();
+		match extension {
+			Some(extension) if base != extension => String::from(base.strip_suffix(extension).unwrap_or(base)),
+			_ => String::from(base),
+		}
+	}
+
+	/// Returns the extension of the last component of a path, including the `.`.
+	/// Leading dots, as in `.profile`, do not begin an extension.
+	pub(crate) fn extname(self, path: &str) -> String {
+		let base = self.basename(path, None);
+		let stem = base.trim_start_matches('.');
+		match stem.rfind('.') {
+			Some(index) => String::from(&stem[index..]),
+			None => String::new(),
+		}
+	}
+
+	pub(crate) fn parse(self, path: &str) -> ParsedPath {
+		let (root, rest) = self.split_root(path);
+		let base = self.basename(path, None);
+		let ext = self.extname(path);
+		let name = String::from(&base[..base.len() - ext.len()]);
+		let dir = if rest
+			.trim_end_matches(|char| self.is_separator(char))
+			.contains(|char| self.is_separator(char))
+		{
+			self.dirname(path)
+		} else {
+			String::from(root)
+		};
+		ParsedPath {
+			root: String::from(root),
+			dir,
+			base,
+			ext,
+			name,
+		}
+	}
+
+	pub(crate) fn format(self, path: &ParsedPath) -> String {
+		let dir = if path.dir.is_empty() { &path.root } else { &path.dir };
+		let base = if !path.base.is_empty() {
+			path.base.clone()
+		} else if path.ext.is_empty() || path.ext.starts_with('.') {
+			format!("{}{}", path.name, path.ext)
+		} else {
+			format!("{}.{}", path.name, path.ext)
+		};
+
+		if dir.is_empty() {
+			base
+		} else if dir == &path.root || dir.ends_with(|char| self.is_separator(char)) {
+			format!("{}{}", dir, base)
+		} else {
+			format!("{}{}{}", dir, self.separator(), base)
+		}
+	}
+}
@@ -6,4 +6,5 @@
 
 pub use path::*;
 
+mod flavour;
 mod path;
@@ -5,6 +5,14 @@
  */
 
 export const join = ______pathInternal______.join;
+export const resolve = ______pathInternal______.resolve;
+export const normalize = ______pathInternal______.normalize;
+export const relative = ______pathInternal______.relative;
+export const dirname = ______pathInternal______.dirname;
+export const basename = ______pathInternal______.basename;
+export const extname = ______pathInternal______.extname;
+export const parse = ______pathInternal______.parse;
+export const format = ______pathInternal______.format;
 export const stripPrefix = ______pathInternal______.stripPrefix;
 export const fileStem = ______pathInternal______.fileStem;
 export const parent = ______pathInternal______.parent;
@@ -21,4 +29,7 @@ export const endsWith = ______pathInternal______.endsWith;
 export const separator = ______pathInternal______.separator;
 export const delimiter = ______pathInternal______.delimiter;
 
+export const posix = ______pathInternal______.posix;
+export const win32 = ______pathInternal______.win32;
+
 export default Object.freeze(______pathInternal______);
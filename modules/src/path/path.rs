@@ -4,33 +4,112 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/.
  */
 
-use std::path::{Path, PathBuf};
+use std::env;
+use std::path::Path;
 
-use mozjs::jsapi::{JSFunctionSpec, JSPropertySpec};
+use mozjs::jsapi::JSFunctionSpec;
 
 use ion::{Context, Error, Object, Result};
 use ion::flags::PropertyFlags;
-use ion::spec::create_property_spec_string;
 use runtime::modules::NativeModule;
 
-#[cfg(windows)]
-const SEPARATOR: &str = "\\\0";
-#[cfg(unix)]
-const SEPARATOR: &str = "/\0";
+use crate::path::flavour::Flavour;
 
-#[cfg(windows)]
-const DELIMITER: &str = ";\0";
-#[cfg(unix)]
-const DELIMITER: &str = ":\0";
+fn current_dir() -> Result<String> {
+	let cwd = env::current_dir().map_err(|error| Error::new(&format!("Could not get the current directory: {}", error), None))?;
+	Ok(cwd.to_string_lossy().into_owned())
+}
 
-#[js_fn]
-fn join(#[ion(varargs)] segments: Vec<String>) -> String {
-	let mut path = PathBuf::new();
-	for segment in segments {
-		path.push(segment);
-	}
+/// Defines the functions of the module which follow the conventions of a [Flavour] of paths.
+macro_rules! flavour_functions {
+	($module:ident, $flavour:expr) => {
+		mod $module {
+			use mozjs::jsapi::JSFunctionSpec;
+
+			use ion::Result;
+
+			use crate::path::flavour::{Flavour, ParsedPath};
+			use super::current_dir;
+
+			const FLAVOUR: Flavour = $flavour;
+
+			#[js_fn]
+			fn join(#[ion(varargs)] segments: Vec<String>) -> String {
+				FLAVOUR.join(&segments)
+			}
+
+			#[js_fn]
+			fn resolve(#[ion(varargs)] segments: Vec<String>) -> Result<String> {
+				Ok(FLAVOUR.resolve(&segments, &current_dir()?))
+			}
+
+			#[js_fn]
+			fn normalize(path: String) -> String {
+				FLAVOUR.normalise(&path)
+			}
+
+			#[js_fn]
+			fn relative(from: String, to: String) -> Result<String> {
+				Ok(FLAVOUR.relative(&from, &to, &current_dir()?))
+			}
+
+			#[js_fn]
+			fn dirname(path: String) -> String {
+				FLAVOUR.dirname(&path)
+			}
+
+			#[js_fn]
+			fn basename(path: String, extension: Option<String>) -> String {
+				FLAVOUR.basename(&path, extension.as_deref())
+			}
+
+			#[js_fn]
+			fn extname(path: String) -> String {
+				FLAVOUR.extname(&path)
+			}
+
+			#[js_fn]
+			fn isAbsolute(path: String) -> bool {
+				FLAVOUR.is_absolute(&path)
+			}
+
+			#[js_fn]
+			fn parse(path: String) -> ParsedPath {
+				FLAVOUR.parse(&path)
+			}
+
+			#[js_fn]
+			fn format(path: ParsedPath) -> String {
+				FLAVOUR.format(&path)
+			}
+
+			pub(super) const FUNCTIONS: &[JSFunctionSpec] = &[
+				function_spec!(join, 0),
+				function_spec!(resolve, 0),
+				function_spec!(normalize, 1),
+				function_spec!(relative, 2),
+				function_spec!(dirname, 1),
+				function_spec!(basename, 1),
+				function_spec!(extname, 1),
+				function_spec!(isAbsolute, 1),
+				function_spec!(parse, 1),
+				function_spec!(format, 1),
+				JSFunctionSpec::ZERO,
+			];
+		}
+	};
+}
+
+flavour_functions!(posix, Flavour::Posix);
+flavour_functions!(windows, Flavour::Windows);
 
-	String::from(path.to_str().unwrap())
+/// Creates an object containing the functions and constants of a [Flavour] of paths.
+fn flavour_object(cx: &Context, flavour: Flavour, functions: &'static [JSFunctionSpec]) -> Option<Object> {
+	let mut object = Object::new(cx);
+	let defined = unsafe { object.define_methods(cx, functions) }
+		&& object.define_as(cx, "separator", &flavour.separator().to_string(), PropertyFlags::CONSTANT_ENUMERATED)
+		&& object.define_as(cx, "delimiter", &flavour.delimiter().to_string(), PropertyFlags::CONSTANT_ENUMERATED);
+	defined.then_some(object)
 }
 
 #[js_fn]
@@ -80,11 +159,6 @@ fn withExtension(path: String, extension: String) -> String {
 	String::from(path.with_extension(extension).to_str().unwrap())
 }
 
-#[js_fn]
-fn isAbsolute(path: String) -> bool {
-	Path::new(&path).is_absolute()
-}
-
 #[js_fn]
 fn isRelative(path: String) -> bool {
 	Path::new(&path).is_relative()
@@ -106,7 +180,6 @@ fn endsWith(path: String, prefix: String) -> bool {
 }
 
 const FUNCTIONS: &[JSFunctionSpec] = &[
-	function_spec!(join, 0),
 	function_spec!(stripPrefix, 2),
 	function_spec!(fileStem, 1),
 	function_spec!(parent, 1),
@@ -114,7 +187,6 @@ const FUNCTIONS: &[JSFunctionSpec] = &[
 	function_spec!(extension, 1),
 	function_spec!(withFileName, 2),
 	function_spec!(withExtension, 2),
-	function_spec!(isAbsolute, 1),
 	function_spec!(isRelative, 1),
 	function_spec!(hasRoot, 1),
 	function_spec!(startsWith, 2),
@@ -122,12 +194,6 @@ const FUNCTIONS: &[JSFunctionSpec] = &[
 	JSFunctionSpec::ZERO,
 ];
 
-const PROPERTIES: &[JSPropertySpec] = &[
-	create_property_spec_string("separator", SEPARATOR, PropertyFlags::CONSTANT_ENUMERATED),
-	create_property_spec_string("delimiter", DELIMITER, PropertyFlags::CONSTANT_ENUMERATED),
-	JSPropertySpec::ZERO,
-];
-
 #[derive(Default)]
 pub struct PathM;
 
@@ -136,8 +202,17 @@ impl NativeModule for PathM {
 	const SOURCE: &'static str = include_str!("path.js");
 
 	fn module(cx: &Context) -> Option<Object> {
-		let mut path = Object::new(cx);
-		if unsafe { path.define_methods(cx, FUNCTIONS) && path.define_properties(cx, PROPERTIES) } {
+		let posix = flavour_object(cx, Flavour::Posix, posix::FUNCTIONS)?;
+		let win32 = flavour_object(cx, Flavour::Windows, windows::FUNCTIONS)?;
+		let mut path = match Flavour::NATIVE {
+			Flavour::Posix => flavour_object(cx, Flavour::Posix, posix::FUNCTIONS)?,
+			Flavour::Windows => flavour_object(cx, Flavour::Windows, windows::FUNCTIONS)?,
+		};
+
+		if unsafe { path.define_methods(cx, FUNCTIONS) }
+			&& path.define_as(cx, "posix", &posix, PropertyFlags::CONSTANT_ENUMERATED)
+			&& path.define_as(cx, "win32", &win32, PropertyFlags::CONSTANT_ENUMERATED)
+		{
 			return Some(path);
 		}
 		None
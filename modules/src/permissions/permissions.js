@@ -0,0 +1,11 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+export const query = ______permissionsInternal______.query;
+export const request = ______permissionsInternal______.request;
+export const revoke = ______permissionsInternal______.revoke;
+
+export default Object.freeze(______permissionsInternal______);
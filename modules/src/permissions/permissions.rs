@@ -0,0 +1,113 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use mozjs::jsapi::JSFunctionSpec;
+use tokio::task::spawn_blocking;
+
+use ion::{Context, Error, ErrorKind, Object, Promise, Result, Value};
+use ion::conversions::{FromValue, ToValue};
+use runtime::modules::NativeModule;
+use runtime::permissions::{parse_permission, PermissionName, PermissionState, permissions};
+use runtime::promise::future_to_promise;
+
+/// Represents a permission passed to the functions of the module, as `{ name }` with the resource for its kind,
+/// which is `path` for `read` and `write`, `host` for `net`, `variable` for `env` and `command` for `run`.
+struct PermissionDescriptor {
+	name: PermissionName,
+	resource: Option<String>,
+}
+
+impl<'cx> FromValue<'cx> for PermissionDescriptor {
+	type Config = ();
+
+	fn from_value(cx: &'cx Context, value: &Value, strict: bool, _: ()) -> Result<PermissionDescriptor> {
+		if !value.handle().is_object() {
+			return Err(Error::new("Expected Object as Permission Descriptor", ErrorKind::Type));
+		}
+		let object = value.to_object(cx);
+		let name: String = object
+			.get_as(cx, "name", strict, ())
+			.ok_or_else(|| Error::new("Permission Descriptor must have a name", ErrorKind::Type))?;
+		let name = parse_permission(&name)?;
+
+		let key = match name {
+			PermissionName::Read | PermissionName::Write => Some("path"),
+			PermissionName::Net => Some("host"),
+			PermissionName::Env => Some("variable"),
+			PermissionName::Run => Some("command"),
+			PermissionName::Ffi | PermissionName::Hrtime => None,
+		};
+		let resource = key.and_then(|key| object.get_as::<_, Option<String>>(cx, key, strict, ()).flatten());
+		Ok(PermissionDescriptor { name, resource })
+	}
+}
+
+/// Represents the state of a permission, as `{ name, state }`.
+struct PermissionStatus {
+	name: PermissionName,
+	state: PermissionState,
+}
+
+impl<'cx> ToValue<'cx> for PermissionStatus {
+	fn to_value(&self, cx: &'cx Context, value: &mut Value) {
+		let mut object = Object::new(cx);
+		object.set_as(cx, "name", self.name.as_str());
+		object.set_as(cx, "state", self.state.as_str());
+		object.to_value(cx, value);
+	}
+}
+
+/// Resolves with the state of a permission, without prompting the user.
+#[js_fn]
+fn query(cx: &Context, descriptor: PermissionDescriptor) -> Option<Promise> {
+	let PermissionDescriptor { name, resource } = descriptor;
+	let state = permissions().query(name, resource.as_deref());
+	future_to_promise::<_, _, Error>(cx, async move { Ok(PermissionStatus { name, state }) })
+}
+
+/// Requests a permission, and resolves with its state once it has been granted or denied.
+/// The user is prompted if the permission has neither been granted nor denied.
+#[js_fn]
+fn request(cx: &Context, descriptor: PermissionDescriptor) -> Option<Promise> {
+	let PermissionDescriptor { name, resource } = descriptor;
+	future_to_promise::<_, _, Error>(cx, async move {
+		let state = spawn_blocking(move || runtime::permissions::request(name, resource.as_deref()))
+			.await
+			.map_err(|error| Error::new(&error.to_string(), None))?;
+		Ok(PermissionStatus { name, state })
+	})
+}
+
+/// Revokes a permission, and resolves with its new state.
+#[js_fn]
+fn revoke(cx: &Context, descriptor: PermissionDescriptor) -> Option<Promise> {
+	let PermissionDescriptor { name, resource } = descriptor;
+	let state = permissions().revoke(name, resource.as_deref());
+	future_to_promise::<_, _, Error>(cx, async move { Ok(PermissionStatus { name, state }) })
+}
+
+const FUNCTIONS: &[JSFunctionSpec] = &[
+	function_spec!(query, 1),
+	function_spec!(request, 1),
+	function_spec!(revoke, 1),
+	JSFunctionSpec::ZERO,
+];
+
+#[derive(Default)]
+pub struct PermissionsM;
+
+impl NativeModule for PermissionsM {
+	const NAME: &'static str = "permissions";
+	const SOURCE: &'static str = include_str!("permissions.js");
+
+	fn module(cx: &Context) -> Option<Object> {
+		let mut permissions = Object::new(cx);
+		if unsafe { permissions.define_methods(cx, FUNCTIONS) } {
+			return Some(permissions);
+		}
+		None
+	}
+}
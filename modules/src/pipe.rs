@@ -0,0 +1,148 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::future::Future;
+use std::pin::pin;
+use std::rc::Rc;
+
+use futures::future::{Either, LocalBoxFuture, select};
+use futures::lock::Mutex;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::sync::Notify;
+
+use ion::Error;
+use runtime::globals::streams::{NativeSink, NativeSource};
+
+const CHUNK_SIZE: usize = 8192;
+
+fn pipe_error(error: std::io::Error) -> Error {
+	Error::new(&error.to_string(), None)
+}
+
+fn closed_error() -> Error {
+	Error::new("Pipe is closed", None)
+}
+
+/// Holds a reader or writer which backs a stream, such as the standard output of a child process or a half of a connection.
+/// It can be taken back from the stream at any time, which interrupts any pending operation.
+pub(crate) struct Pipe<T> {
+	inner: Mutex<Option<T>>,
+	taken: Notify,
+}
+
+impl<T> Pipe<T> {
+	pub(crate) fn new(inner: T) -> Rc<Pipe<T>> {
+		Rc::new(Pipe {
+			inner: Mutex::new(Some(inner)),
+			taken: Notify::new(),
+		})
+	}
+
+	/// Takes the reader or writer, once any pending operation has been interrupted.
+	pub(crate) async fn take(&self) -> Option<T> {
+		self.taken.notify_waiters();
+		self.inner.lock().await.take()
+	}
+
+	/// Runs an operation on the reader or writer, unless it is taken before the operation completes.
+	async fn operate<'p, F, Fut, O>(&'p self, f: F) -> Option<O>
+	where
+		F: FnOnce(&'p Mutex<Option<T>>) -> Fut,
+		Fut: Future<Output = O> + 'p,
+	{
+		let operation = pin!(f(&self.inner));
+		let taken = pin!(self.taken.notified());
+		match select(operation, taken).await {
+			Either::Left((output, _)) => Some(output),
+			Either::Right(_) => None,
+		}
+	}
+}
+
+/// Reads chunks from a [Pipe]. The stream ends once the reader is exhausted or taken.
+pub(crate) struct PipeSource<R> {
+	pipe: Rc<Pipe<R>>,
+}
+
+impl<R> PipeSource<R> {
+	pub(crate) fn new(reader: R) -> PipeSource<R> {
+		PipeSource::shared(Pipe::new(reader))
+	}
+
+	pub(crate) fn shared(pipe: Rc<Pipe<R>>) -> PipeSource<R> {
+		PipeSource { pipe }
+	}
+}
+
+impl<R: AsyncRead + Unpin + 'static> NativeSource for PipeSource<R> {
+	fn pull(&mut self) -> LocalBoxFuture<'static, Result<Option<Vec<u8>>, Error>> {
+		let pipe = Rc::clone(&self.pipe);
+		Box::pin(async move {
+			let read = pipe.operate(|reader| async move {
+				let mut reader = reader.lock().await;
+				let Some(reader) = reader.as_mut() else {
+					return Ok(None);
+				};
+
+				let mut chunk = vec![0; CHUNK_SIZE];
+				let read = reader.read(&mut chunk).await.map_err(pipe_error)?;
+				if read == 0 {
+					return Ok(None);
+				}
+				chunk.truncate(read);
+				Ok(Some(chunk))
+			});
+			read.await.unwrap_or(Ok(None))
+		})
+	}
+}
+
+/// Writes chunks to a [Pipe].
+/// Closing the sink shuts down the writer, so that the other end reaches the end of its input.
+pub(crate) struct PipeSink<W> {
+	pipe: Rc<Pipe<W>>,
+}
+
+impl<W> PipeSink<W> {
+	pub(crate) fn new(writer: W) -> PipeSink<W> {
+		PipeSink::shared(Pipe::new(writer))
+	}
+
+	pub(crate) fn shared(pipe: Rc<Pipe<W>>) -> PipeSink<W> {
+		PipeSink { pipe }
+	}
+}
+
+impl<W: AsyncWrite + Unpin + 'static> NativeSink for PipeSink<W> {
+	fn write(&mut self, bytes: Vec<u8>) -> LocalBoxFuture<'static, Result<(), Error>> {
+		let pipe = Rc::clone(&self.pipe);
+		Box::pin(async move {
+			let write = pipe.operate(|writer| async move {
+				let mut writer = writer.lock().await;
+				let writer = writer.as_mut().ok_or_else(closed_error)?;
+				writer.write_all(&bytes).await.map_err(pipe_error)?;
+				writer.flush().await.map_err(pipe_error)
+			});
+			write.await.unwrap_or_else(|| Err(closed_error()))
+		})
+	}
+
+	fn close(&mut self) -> LocalBoxFuture<'static, Result<(), Error>> {
+		let pipe = Rc::clone(&self.pipe);
+		Box::pin(async move {
+			if let Some(mut writer) = pipe.take().await {
+				writer.shutdown().await.map_err(pipe_error)?;
+			}
+			Ok(())
+		})
+	}
+
+	fn abort(&mut self) {
+		if let Some(mut writer) = self.pipe.inner.try_lock() {
+			writer.take();
+		}
+	}
+}
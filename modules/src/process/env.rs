@@ -0,0 +1,150 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::collections::HashMap;
+use std::env;
+
+use tokio::process::Command;
+
+use ion::{Context, Error, ErrorKind, Object, OwnedKey, PropertyKey, Proxy, Result, Value};
+use ion::conversions::{FromValue, ToValue};
+use ion::flags::PropertyFlags;
+use ion::objects::PropertyDescriptor;
+use runtime::ContextExt;
+use runtime::permissions::{check, check_env, PermissionName};
+
+/// Holds the changes which scripts of a runtime have made to its environment variables, over those of the process.
+/// Removed variables are held as [None].
+///
+/// The environment of the process is not modified, as that is unsound while other threads, such as those of workers, read it.
+#[derive(Debug, Default)]
+pub(crate) struct EnvOverlay {
+	variables: HashMap<String, Option<String>>,
+}
+
+impl EnvOverlay {
+	/// Calls a closure with the environment overlay of the runtime.
+	pub(crate) fn with<T, F: FnOnce(&mut EnvOverlay) -> T>(cx: &Context, f: F) -> T {
+		f(unsafe { (*cx.get_private().as_ptr()).extension() })
+	}
+
+	fn get(&self, name: &str) -> Option<String> {
+		match self.variables.get(name) {
+			Some(variable) => variable.clone(),
+			None => env::var(name).ok(),
+		}
+	}
+
+	fn set(&mut self, name: String, value: String) {
+		self.variables.insert(name, Some(value));
+	}
+
+	fn remove(&mut self, name: String) {
+		self.variables.insert(name, None);
+	}
+
+	fn names(&self) -> Vec<String> {
+		let mut names: Vec<_> = env::vars_os()
+			.filter_map(|(name, _)| name.into_string().ok())
+			.filter(|name| !self.variables.contains_key(name))
+			.collect();
+		names.extend(self.variables.iter().filter(|(_, value)| value.is_some()).map(|(name, _)| name.clone()));
+		names
+	}
+
+	/// Applies the changes to the environment of a command, so that child processes inherit them.
+	pub(crate) fn apply(&self, command: &mut Command) {
+		for (name, value) in &self.variables {
+			match value {
+				Some(value) => command.env(name, value),
+				None => command.env_remove(name),
+			};
+		}
+	}
+}
+
+fn variable_name(cx: &Context, key: &PropertyKey) -> Option<String> {
+	match key.to_owned_key(cx) {
+		OwnedKey::Int(int) => Some(int.to_string()),
+		OwnedKey::String(name) => Some(name),
+		_ => None,
+	}
+}
+
+fn check_name(name: &str) -> Result<()> {
+	if name.is_empty() || name.contains(['=', '\0']) {
+		Err(Error::new(&format!("Invalid environment variable name: {}", name), ErrorKind::Type))
+	} else {
+		Ok(())
+	}
+}
+
+/// Creates the `env` object, which reads and writes the environment variables of the runtime as its properties.
+/// As the object is a proxy over the environment, changes made by native code are visible immediately,
+/// unless scripts have changed the same variables.
+/// Changes made by scripts are held in the [EnvOverlay] of the runtime, and are inherited by child processes.
+pub(crate) fn env_proxy(cx: &Context) -> Option<Object> {
+	let proxy = Proxy::builder()
+		.get(|cx, _, key, _| {
+			let variable = match variable_name(cx, key) {
+				Some(name) => {
+					check_env(&name)?;
+					EnvOverlay::with(cx, |overlay| overlay.get(&name))
+				}
+				None => None,
+			};
+			Ok(match variable {
+				Some(variable) => variable.as_value(cx),
+				None => Value::undefined(cx),
+			})
+		})
+		.set(|cx, _, key, value, _| {
+			let Some(name) = variable_name(cx, key) else {
+				return Ok(false);
+			};
+			check_name(&name)?;
+			check_env(&name)?;
+			let value = String::from_value(cx, value, false, ())?;
+			if value.contains('\0') {
+				return Err(Error::new("Environment variables cannot contain null characters", ErrorKind::Type));
+			}
+			EnvOverlay::with(cx, |overlay| overlay.set(name, value));
+			Ok(true)
+		})
+		.has(|cx, _, key| match variable_name(cx, key) {
+			Some(name) => {
+				check_env(&name)?;
+				Ok(EnvOverlay::with(cx, |overlay| overlay.get(&name)).is_some())
+			}
+			None => Ok(false),
+		})
+		.delete_property(|cx, _, key| {
+			if let Some(name) = variable_name(cx, key) {
+				check_name(&name)?;
+				check_env(&name)?;
+				EnvOverlay::with(cx, |overlay| overlay.remove(name));
+			}
+			Ok(true)
+		})
+		.own_keys(|cx, _| {
+			check(PermissionName::Env, None)?;
+			let names = EnvOverlay::with(cx, |overlay| overlay.names());
+			Ok(names.iter().filter_map(|name| PropertyKey::with_string(cx, name)).collect())
+		})
+		.get_own_property_descriptor(|cx, _, key| {
+			let variable = match variable_name(cx, key) {
+				Some(name) => {
+					check_env(&name)?;
+					EnvOverlay::with(cx, |overlay| overlay.get(&name))
+				}
+				None => None,
+			};
+			let flags = PropertyFlags::ENUMERATE;
+			Ok(variable.map(|variable| PropertyDescriptor::new(cx, &variable.as_value(cx), flags)))
+		})
+		.build(cx, &Object::new(cx))?;
+	Some(Object::from(proxy.into_local()))
+}
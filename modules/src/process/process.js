@@ -0,0 +1,22 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+export const args = ______processInternal______.args;
+export const env = ______processInternal______.env;
+export const pid = ______processInternal______.pid;
+export const platform = ______processInternal______.platform;
+export const arch = ______processInternal______.arch;
+export const stdin = ______processInternal______.stdin;
+export const stdout = ______processInternal______.stdout;
+export const stderr = ______processInternal______.stderr;
+
+export const exit = ______processInternal______.exit;
+export const cwd = ______processInternal______.cwd;
+export const chdir = ______processInternal______.chdir;
+export const addSignalListener = ______processInternal______.addSignalListener;
+export const removeSignalListener = ______processInternal______.removeSignalListener;
+
+export default ______processInternal______;
@@ -0,0 +1,123 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::env;
+
+use mozjs::conversions::ConversionBehavior::EnforceRange;
+use mozjs::jsapi::{JSContext, JSFunctionSpec, JSPropertySpec};
+use mozjs::jsval::JSVal;
+
+use ion::{Arguments, Context, Error, Function, Object, Result, ThrowException};
+use ion::conversions::FromValue;
+use ion::flags::PropertyFlags;
+use runtime::ContextExt;
+use runtime::config::CONFIG;
+use runtime::event_loop::signals::{add_signal_listener, parse_signal, remove_signal_listener};
+use runtime::globals::streams::{readable_stream, writable_stream};
+use runtime::modules::NativeModule;
+use runtime::permissions::check_read;
+
+use crate::process::env::env_proxy;
+use crate::process::stdio::{StdinSource, StdioSink};
+
+#[js_fn]
+fn getExitCode(cx: &Context) -> i32 {
+	unsafe { (*cx.get_private().as_ptr()).exit_code() }
+}
+
+#[js_fn]
+fn setExitCode(cx: &Context, #[ion(convert = EnforceRange)] code: i32) {
+	unsafe { (*cx.get_private().as_ptr()).set_exit_code(code) }
+}
+
+/// Sets the exit code and stops the event loop, terminating the running script with an uncatchable exception.
+/// The exit code defaults to `exitCode`, if it is not given.
+/// The embedder shuts down the runtime, which fires `unload` at the global object, and exits the process.
+unsafe extern "C" fn exit(cx: *mut JSContext, argc: u32, vp: *mut JSVal) -> bool {
+	let cx = &unsafe { Context::new_unchecked(cx) };
+	let args = unsafe { Arguments::new(cx, argc, vp) };
+	let private = unsafe { &mut *cx.get_private().as_ptr() };
+
+	let code = match args.value(0).filter(|code| !code.handle().is_undefined()) {
+		Some(code) => match i32::from_value(cx, code, true, EnforceRange) {
+			Ok(code) => code,
+			Err(error) => {
+				error.throw(cx);
+				return false;
+			}
+		},
+		None => private.exit_code(),
+	};
+	private.exit(code);
+	false
+}
+
+#[js_fn]
+fn cwd() -> Result<String> {
+	let cwd = env::current_dir().map_err(|error| Error::new(&format!("Could not get the current directory: {}", error), None))?;
+	Ok(cwd.to_string_lossy().into_owned())
+}
+
+#[js_fn]
+fn chdir(path: String) -> Result<()> {
+	check_read(&path)?;
+	env::set_current_dir(&path).map_err(|error| Error::new(&format!("Could not change directory to {}: {}", path, error), None))
+}
+
+/// Adds a listener for a signal, such as `SIGINT`, which is called on the event loop whenever the process receives it.
+/// Once a signal has had a listener, receiving it without any listeners stops the event loop, as with `exit`.
+#[js_fn]
+fn addSignalListener(cx: &Context, signal: String, listener: Function) -> Result<()> {
+	add_signal_listener(cx, parse_signal(&signal)?, &listener)
+}
+
+#[js_fn]
+fn removeSignalListener(cx: &Context, signal: String, listener: Function) -> Result<()> {
+	remove_signal_listener(cx, parse_signal(&signal)?, &listener);
+	Ok(())
+}
+
+const FUNCTIONS: &[JSFunctionSpec] = &[
+	function_spec!(exit, 0),
+	function_spec!(cwd, 0),
+	function_spec!(chdir, 1),
+	function_spec!(addSignalListener, 2),
+	function_spec!(removeSignalListener, 2),
+	JSFunctionSpec::ZERO,
+];
+
+const PROPERTIES: &[JSPropertySpec] = &[property_spec_getter_setter!(getExitCode, setExitCode, "exitCode"), JSPropertySpec::ZERO];
+
+#[derive(Default)]
+pub struct Process;
+
+impl NativeModule for Process {
+	const NAME: &'static str = "process";
+	const SOURCE: &'static str = include_str!("process.js");
+
+	fn module(cx: &Context) -> Option<Object> {
+		let mut process = Object::new(cx);
+		let args = CONFIG.get().map(|config| config.args.clone()).unwrap_or_default();
+		let variables = env_proxy(cx)?;
+		let stdin = readable_stream(cx, StdinSource).ok()?;
+		let stdout = writable_stream(cx, StdioSink::stdout()).ok()?;
+		let stderr = writable_stream(cx, StdioSink::stderr()).ok()?;
+
+		if unsafe { process.define_methods(cx, FUNCTIONS) && process.define_properties(cx, PROPERTIES) }
+			&& process.define_as(cx, "args", &args, PropertyFlags::CONSTANT_ENUMERATED)
+			&& process.define_as(cx, "env", &variables, PropertyFlags::CONSTANT_ENUMERATED)
+			&& process.define_as(cx, "pid", &std::process::id(), PropertyFlags::CONSTANT_ENUMERATED)
+			&& process.define_as(cx, "platform", env::consts::OS, PropertyFlags::CONSTANT_ENUMERATED)
+			&& process.define_as(cx, "arch", env::consts::ARCH, PropertyFlags::CONSTANT_ENUMERATED)
+			&& process.define_as(cx, "stdin", &stdin, PropertyFlags::CONSTANT_ENUMERATED)
+			&& process.define_as(cx, "stdout", &stdout, PropertyFlags::CONSTANT_ENUMERATED)
+			&& process.define_as(cx, "stderr", &stderr, PropertyFlags::CONSTANT_ENUMERATED)
+		{
+			return Some(process);
+		}
+		None
+	}
+}
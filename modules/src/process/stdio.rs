@@ -0,0 +1,62 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use futures::future::LocalBoxFuture;
+use tokio::io::{AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+use ion::Error;
+use runtime::globals::streams::{NativeSink, NativeSource};
+
+const CHUNK_SIZE: usize = 8192;
+
+fn io_error(error: std::io::Error) -> Error {
+	Error::new(&error.to_string(), None)
+}
+
+/// Reads chunks from the standard input of the process.
+pub(crate) struct StdinSource;
+
+impl NativeSource for StdinSource {
+	fn pull(&mut self) -> LocalBoxFuture<'static, Result<Option<Vec<u8>>, Error>> {
+		Box::pin(async move {
+			let mut stdin = tokio::io::stdin();
+			let mut chunk = vec![0; CHUNK_SIZE];
+			let read = stdin.read(&mut chunk).await.map_err(io_error)?;
+			if read == 0 {
+				return Ok(None);
+			}
+			chunk.truncate(read);
+			Ok(Some(chunk))
+		})
+	}
+}
+
+/// Writes chunks to the standard output or standard error of the process, flushing after each chunk.
+pub(crate) struct StdioSink<W> {
+	writer: fn() -> W,
+}
+
+impl StdioSink<tokio::io::Stdout> {
+	pub(crate) fn stdout() -> StdioSink<tokio::io::Stdout> {
+		StdioSink { writer: tokio::io::stdout }
+	}
+}
+
+impl StdioSink<tokio::io::Stderr> {
+	pub(crate) fn stderr() -> StdioSink<tokio::io::Stderr> {
+		StdioSink { writer: tokio::io::stderr }
+	}
+}
+
+impl<W: AsyncWrite + Unpin + 'static> NativeSink for StdioSink<W> {
+	fn write(&mut self, bytes: Vec<u8>) -> LocalBoxFuture<'static, Result<(), Error>> {
+		let mut writer = (self.writer)();
+		Box::pin(async move {
+			writer.write_all(&bytes).await.map_err(io_error)?;
+			writer.flush().await.map_err(io_error)
+		})
+	}
+}
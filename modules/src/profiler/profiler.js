@@ -0,0 +1,11 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+export const start = ______profilerInternal______.start;
+export const stop = ______profilerInternal______.stop;
+export const isRunning = ______profilerInternal______.isRunning;
+
+export default Object.freeze(______profilerInternal______);
@@ -0,0 +1,81 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::time::Duration;
+
+use mozjs::jsapi::JSFunctionSpec;
+
+use ion::{Context, Error, ErrorKind, Object, Result};
+use runtime::modules::NativeModule;
+use runtime::profiler::{DEFAULT_INTERVAL, ProfileFormat, Profiler};
+
+#[derive(FromValue)]
+pub(crate) struct StartOptions {
+	/// Interval between samples in milliseconds.
+	interval: Option<f64>,
+}
+
+#[derive(FromValue)]
+pub(crate) struct StopOptions {
+	/// Format of the returned profile, which is either `speedscope` or `folded`.
+	format: Option<String>,
+}
+
+/// Starts sampling the stack of the runtime.
+#[js_fn]
+fn start(cx: &Context, options: Option<StartOptions>) -> Result<()> {
+	let interval = match options.and_then(|options| options.interval) {
+		Some(interval) if interval.is_finite() && interval >= 0.1 => Duration::from_secs_f64(interval / 1000.0),
+		Some(_) => return Err(Error::new("Profiler Interval must be at least 0.1 Milliseconds", ErrorKind::Range)),
+		None => DEFAULT_INTERVAL,
+	};
+	if Profiler::start(cx, interval, None) {
+		Ok(())
+	} else {
+		Err(Error::new("Profiler is already running", None))
+	}
+}
+
+/// Stops sampling the stack of the runtime, and returns the profile as a string.
+#[js_fn]
+fn stop(cx: &Context, options: Option<StopOptions>) -> Result<String> {
+	let format = match options.and_then(|options| options.format) {
+		Some(format) => {
+			ProfileFormat::from_name(&format).ok_or_else(|| Error::new(&format!("Invalid Profile Format: {}", format), ErrorKind::Type))?
+		}
+		None => ProfileFormat::Speedscope,
+	};
+	let profile = Profiler::stop(cx).ok_or_else(|| Error::new("Profiler is not running", None))?;
+	Ok(profile.format(format))
+}
+
+#[js_fn]
+fn isRunning(cx: &Context) -> bool {
+	Profiler::is_running(cx)
+}
+
+const FUNCTIONS: &[JSFunctionSpec] = &[
+	function_spec!(start, 0),
+	function_spec!(stop, 0),
+	function_spec!(isRunning, 0),
+	JSFunctionSpec::ZERO,
+];
+
+#[derive(Default)]
+pub struct ProfilerM;
+
+impl NativeModule for ProfilerM {
+	const NAME: &'static str = "profiler";
+	const SOURCE: &'static str = include_str!("profiler.js");
+
+	fn module(cx: &Context) -> Option<Object> {
+		let mut profiler = Object::new(cx);
+		if unsafe { profiler.define_methods(cx, FUNCTIONS) } {
+			return Some(profiler);
+		}
+		None
+	}
+}
@@ -0,0 +1,58 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use mozjs::jsapi::JSFunctionSpec;
+
+use ion::{Context, Function, Object};
+use ion::flags::PropertyFlags;
+use runtime::diagnostics;
+use runtime::event_loop::handles::{active_handles, ActiveHandle};
+use runtime::modules::NativeModule;
+
+/// Lists the timers, sockets and other pending work keeping the event loop alive, as `{ type, description, stack }`.
+/// Creation stacks are only included when running with `--trace-exit`.
+#[js_fn]
+fn activeHandles(cx: &Context) -> Vec<ActiveHandle> {
+	active_handles(cx)
+}
+
+/// Subscribes a callback to events recorded by the runtime, such as modules being loaded and garbage collections.
+/// Each event is passed as `{ target, level, message, fields, timestamp }` between turns of the event loop.
+/// Only events on the thread of the runtime are received, and errors thrown by the callback stop the event loop.
+#[js_fn]
+fn subscribe(cx: &Context, callback: Function) {
+	diagnostics::subscribe(cx, &callback);
+}
+
+/// Unsubscribes a callback from the diagnostics channel, returning `false` if it was not subscribed.
+#[js_fn]
+fn unsubscribe(cx: &Context, callback: Function) -> bool {
+	diagnostics::unsubscribe(cx, &callback)
+}
+
+const FUNCTIONS: &[JSFunctionSpec] = &[function_spec!(activeHandles, 0), JSFunctionSpec::ZERO];
+
+const DIAGNOSTICS_FUNCTIONS: &[JSFunctionSpec] = &[function_spec!(subscribe, 1), function_spec!(unsubscribe, 1), JSFunctionSpec::ZERO];
+
+#[derive(Default)]
+pub struct RuntimeM;
+
+impl NativeModule for RuntimeM {
+	const NAME: &'static str = "runtime";
+	const SOURCE: &'static str = include_str!("runtime.js");
+
+	fn module(cx: &Context) -> Option<Object> {
+		let mut runtime = Object::new(cx);
+		let mut diagnostics = Object::new(cx);
+		if unsafe { runtime.define_methods(cx, FUNCTIONS) }
+			&& unsafe { diagnostics.define_methods(cx, DIAGNOSTICS_FUNCTIONS) }
+			&& runtime.define_as(cx, "diagnostics", &diagnostics, PropertyFlags::CONSTANT_ENUMERATED)
+		{
+			return Some(runtime);
+		}
+		None
+	}
+}
@@ -0,0 +1,230 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::cell::Cell;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex, PoisonError};
+
+use futures::lock::Mutex as AsyncMutex;
+use mozjs::jsapi::JSFunction;
+use rusqlite::Connection;
+use tokio::task::spawn_blocking;
+
+use ion::{ClassDefinition, Context, Error, ErrorKind, Exception, Function, Object, PersistentRooted, Promise, PromiseFuture, Result, Value};
+use ion::class::Reflector;
+use ion::conversions::{IntoValue, ToValue};
+use runtime::promise::future_to_promise;
+
+use crate::sqlite::statement::Prepared;
+use crate::sqlite::value::sqlite_error;
+
+/// Represents a connection which is shared by a database and its statements, and is [None] once the database is closed.
+pub(crate) type SharedConnection = Arc<Mutex<Option<Connection>>>;
+
+fn closed_error() -> Error {
+	Error::new("Database is closed", None)
+}
+
+/// Runs an operation on the connection on the blocking thread pool, so that queries do not stall the event loop.
+/// Operations are performed one at a time, in the order they acquire the connection.
+pub(crate) async fn run<F, O>(connection: &SharedConnection, f: F) -> Result<O>
+where
+	F: FnOnce(&mut Connection) -> rusqlite::Result<O> + Send + 'static,
+	O: Send + 'static,
+{
+	let connection = Arc::clone(connection);
+	let result = spawn_blocking(move || {
+		let mut connection = connection.lock().unwrap_or_else(PoisonError::into_inner);
+		connection.as_mut().map(f)
+	})
+	.await
+	.map_err(|error| Error::new(&error.to_string(), None))?;
+	result.ok_or_else(closed_error)?.map_err(sqlite_error)
+}
+
+/// Runs an operation on the connection, and returns a promise which resolves with its output.
+pub(crate) fn operate<F, O>(cx: &Context, connection: &SharedConnection, f: F) -> Option<Promise>
+where
+	F: FnOnce(&mut Connection) -> rusqlite::Result<O> + Send + 'static,
+	O: for<'cx> IntoValue<'cx> + Send + 'static,
+{
+	let connection = Arc::clone(connection);
+	future_to_promise::<_, _, Error>(cx, async move { run(&connection, f).await })
+}
+
+/// Closes the connection, once all pending operations have completed.
+pub(crate) async fn close(connection: SharedConnection) -> Result<()> {
+	let closed = spawn_blocking(move || {
+		let connection = connection.lock().unwrap_or_else(PoisonError::into_inner).take();
+		connection.map(Connection::close)
+	})
+	.await
+	.map_err(|error| Error::new(&error.to_string(), None))?;
+	if let Some(Err((_, error))) = closed {
+		return Err(sqlite_error(error));
+	}
+	Ok(())
+}
+
+/// Represents a SQLite database opened with `sqlite.open`.
+#[js_class]
+pub struct Database {
+	reflector: Reflector,
+	#[ion(no_trace)]
+	path: String,
+	#[ion(no_trace)]
+	connection: SharedConnection,
+	#[ion(no_trace)]
+	lock: Rc<AsyncMutex<()>>,
+}
+
+#[js_class]
+impl Database {
+	#[ion(constructor)]
+	pub fn constructor() -> Result<Database> {
+		Err(Error::new("Database has no constructor.", ErrorKind::Type))
+	}
+
+	/// Executes one or more statements separated by semicolons, which cannot have parameters.
+	pub fn exec(&self, cx: &Context, sql: String) -> Option<Promise> {
+		operate(cx, &self.connection, move |connection| connection.execute_batch(&sql))
+	}
+
+	/// Compiles a statement, and resolves with a `Statement` which can be run repeatedly with different parameters.
+	pub fn prepare(&self, cx: &Context, sql: String) -> Option<Promise> {
+		let shared = Arc::clone(&self.connection);
+		operate(cx, &self.connection, move |connection| {
+			let statement = connection.prepare_cached(&sql)?;
+			let columns = statement.column_names().into_iter().map(String::from).collect();
+			Ok(Prepared { sql, columns, connection: shared })
+		})
+	}
+
+	/// Calls `callback` within a transaction, which is committed once the callback returns or its promise resolves.
+	/// The transaction is rolled back if the callback throws or its promise rejects.
+	/// Operations from outside the callback which run while it is pending are also part of the transaction.
+	///
+	/// Transactions run one at a time, so a transaction waits for pending transactions to complete before it begins.
+	/// `callback` is called with a function, which runs a nested transaction within a savepoint of this transaction.
+	/// Rolling back a nested transaction only discards the changes made within it.
+	pub fn transaction(&self, cx: &Context, callback: Function) -> Option<Promise> {
+		transaction(
+			cx,
+			Arc::clone(&self.connection),
+			Some(Rc::clone(&self.lock)),
+			PersistentRooted::new(callback.get()),
+			0,
+		)
+	}
+
+	/// Closes the database, once all pending operations have completed. Further operations are rejected.
+	pub fn close(&self, cx: &Context) -> Option<Promise> {
+		let connection = Arc::clone(&self.connection);
+		future_to_promise::<_, _, Error>(cx, async move { close(connection).await })
+	}
+
+	#[ion(get)]
+	pub fn get_path(&self) -> String {
+		self.path.clone()
+	}
+}
+
+/// Runs a transaction at the given depth, where transactions with a depth greater than zero are nested within a savepoint.
+/// Top-level transactions hold `lock` until they complete.
+fn transaction(
+	cx: &Context, connection: SharedConnection, lock: Option<Rc<AsyncMutex<()>>>, callback: PersistentRooted<*mut JSFunction>, depth: u32,
+) -> Option<Promise> {
+	let cx_ptr = cx.as_ptr();
+
+	future_to_promise::<_, _, Exception>(cx, async move {
+		let cx = unsafe { Context::new_unchecked(cx_ptr) };
+		let _guard = match &lock {
+			Some(lock) => Some(lock.lock().await),
+			None => None,
+		};
+
+		let (begin, commit, rollback) = if depth == 0 {
+			(String::from("BEGIN"), String::from("COMMIT"), String::from("ROLLBACK"))
+		} else {
+			let savepoint = format!("nested_{}", depth);
+			(
+				format!("SAVEPOINT {}", savepoint),
+				format!("RELEASE {}", savepoint),
+				format!("ROLLBACK TO {0}; RELEASE {0}", savepoint),
+			)
+		};
+		run(&connection, move |connection| connection.execute_batch(&begin)).await?;
+
+		let active = Rc::new(Cell::new(true));
+		let nested = {
+			let connection = Arc::clone(&connection);
+			let active = Rc::clone(&active);
+			Function::new_closure(&cx, "transaction", move |cx, args| {
+				if !active.get() {
+					return Err(Error::new("Transaction has already completed", None));
+				}
+				let callback: Function = args.get(0)?;
+				let promise = transaction(cx, Arc::clone(&connection), None, PersistentRooted::new(callback.get()), depth + 1)
+					.ok_or_else(|| Error::new("Failed to Create Promise", None))?;
+				Ok(promise.as_value(cx))
+			})
+		};
+
+		let callback = Function::from(cx.root_function(callback.get()));
+		let result = match callback.call(&cx, &Object::null(&cx), &[nested.as_value(&cx)]) {
+			Ok(value) => settle(&cx, value).await,
+			Err(report) => Err(report
+				.map(|report| report.exception)
+				.unwrap_or_else(|| Error::new("Transaction threw an uncatchable exception", None).into())),
+		};
+		active.set(false);
+
+		match result {
+			Ok(value) => match run(&connection, move |connection| connection.execute_batch(&commit)).await {
+				Ok(()) => Ok(value.get()),
+				Err(error) => {
+					let _ = run(&connection, move |connection| connection.execute_batch(&rollback)).await;
+					Err(error.into())
+				}
+			},
+			Err(exception) => {
+				run(&connection, move |connection| connection.execute_batch(&rollback)).await?;
+				Err(exception)
+			}
+		}
+	})
+}
+
+/// Waits for a value to settle if it is a promise.
+async fn settle<'cx>(cx: &'cx Context, value: Value<'cx>) -> std::result::Result<Value<'cx>, Exception> {
+	if value.handle().is_object() {
+		if let Some(promise) = Promise::from(value.to_object(cx).into_local()) {
+			return PromiseFuture::new(cx, &promise).await.map_err(|reason| Exception::Other(reason.get()));
+		}
+	}
+	Ok(value)
+}
+
+/// Represents a database which has just been opened, which is converted to a [Database] when its promise resolves.
+pub(crate) struct OpenedDatabase {
+	pub(crate) path: String,
+	pub(crate) connection: Connection,
+}
+
+impl<'cx> IntoValue<'cx> for OpenedDatabase {
+	fn into_value(self: Box<Self>, cx: &'cx Context, value: &mut Value) {
+		let database = Database {
+			reflector: Reflector::default(),
+			path: self.path,
+			connection: Arc::new(Mutex::new(Some(self.connection))),
+			lock: Rc::default(),
+		};
+		cx.root_object(Database::new_object(cx, Box::new(database)))
+			.handle()
+			.get()
+			.to_value(cx, value);
+	}
+}
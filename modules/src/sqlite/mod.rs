@@ -0,0 +1,14 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+pub use self::sqlite::*;
+pub(crate) use database::{close, operate, run, SharedConnection};
+pub(crate) use value::sqlite_error;
+
+mod database;
+mod sqlite;
+mod statement;
+mod value;
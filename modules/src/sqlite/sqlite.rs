@@ -0,0 +1,101 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::path::{Path, PathBuf};
+
+use mozjs::jsapi::JSFunctionSpec;
+use rusqlite::{Connection, OpenFlags};
+use tokio::task::spawn_blocking;
+
+use ion::{ClassDefinition, Context, Error, Object, Promise};
+use runtime::modules::NativeModule;
+use runtime::permissions::{check_read, check_write};
+use runtime::promise::future_to_promise;
+
+use crate::sqlite::database::{Database, OpenedDatabase};
+use crate::sqlite::statement::Statement;
+use crate::sqlite::value::sqlite_error;
+
+#[derive(FromValue)]
+pub(crate) struct OpenOptions {
+	#[ion(default)]
+	readonly: bool,
+	/// Creates the database if it does not exist. Ignored if `readonly` is set.
+	#[ion(default = true)]
+	create: bool,
+}
+
+impl Default for OpenOptions {
+	fn default() -> OpenOptions {
+		OpenOptions { readonly: false, create: true }
+	}
+}
+
+impl OpenOptions {
+	fn flags(&self) -> OpenFlags {
+		let mut flags = OpenFlags::SQLITE_OPEN_NO_MUTEX;
+		if self.readonly {
+			flags |= OpenFlags::SQLITE_OPEN_READ_ONLY;
+		} else {
+			flags |= OpenFlags::SQLITE_OPEN_READ_WRITE;
+			if self.create {
+				flags |= OpenFlags::SQLITE_OPEN_CREATE;
+			}
+		}
+		flags
+	}
+}
+
+/// Returns the filename which SQLite opens for `path`, which is always interpreted as a filesystem path.
+///
+/// Paths beginning with `file:` are prefixed with the current directory, so that they are not parsed as URIs
+/// if SQLite was built with URI filenames enabled, and open the same file which permissions were checked for.
+fn filename(path: &str) -> PathBuf {
+	if path.get(..5).is_some_and(|scheme| scheme.eq_ignore_ascii_case("file:")) {
+		Path::new(".").join(path)
+	} else {
+		PathBuf::from(path)
+	}
+}
+
+/// Opens a SQLite database, which is held in memory if `path` is `:memory:`.
+#[js_fn]
+fn open(cx: &Context, path: String, options: Option<OpenOptions>) -> Option<Promise> {
+	let options = options.unwrap_or_default();
+	let flags = options.flags();
+	let readonly = options.readonly;
+	future_to_promise::<_, _, Error>(cx, async move {
+		if path != ":memory:" {
+			check_read(&path)?;
+			if !readonly {
+				check_write(&path)?;
+			}
+		}
+		let opened =
+			spawn_blocking(move || Connection::open_with_flags(filename(&path), flags).map(|connection| OpenedDatabase { path, connection }))
+				.await
+				.map_err(|error| Error::new(&error.to_string(), None))?;
+		opened.map_err(sqlite_error)
+	})
+}
+
+const FUNCTIONS: &[JSFunctionSpec] = &[function_spec!(open, 1), JSFunctionSpec::ZERO];
+
+#[derive(Default)]
+pub struct Sqlite;
+
+impl NativeModule for Sqlite {
+	const NAME: &'static str = "sqlite";
+	const SOURCE: &'static str = include_str!("sqlite.js");
+
+	fn module(cx: &Context) -> Option<Object> {
+		let mut sqlite = Object::new(cx);
+		if unsafe { sqlite.define_methods(cx, FUNCTIONS) } && Database::init_class(cx, &mut sqlite).0 && Statement::init_class(cx, &mut sqlite).0 {
+			return Some(sqlite);
+		}
+		None
+	}
+}
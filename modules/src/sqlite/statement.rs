@@ -0,0 +1,132 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use rusqlite::types::Value as SqlValue;
+
+use ion::{ClassDefinition, Context, Error, ErrorKind, Promise, Result, Value};
+use ion::class::Reflector;
+use ion::conversions::{IntoValue, ToValue};
+
+use crate::sqlite::database::{operate, SharedConnection};
+use crate::sqlite::value::{Parameters, Row, Rows, RunResult};
+
+/// Binds the parameters to a statement, and reads up to `limit` of the rows it returns.
+fn query(statement: &mut rusqlite::Statement, parameters: &Parameters, limit: Option<usize>) -> rusqlite::Result<Vec<Vec<SqlValue>>> {
+	parameters.bind(statement)?;
+	let count = statement.column_count();
+	let mut rows = statement.raw_query();
+	let mut result = Vec::new();
+	while limit.map_or(true, |limit| result.len() < limit) {
+		let Some(row) = rows.next()? else {
+			break;
+		};
+		result.push((0..count).map(|index| row.get(index)).collect::<rusqlite::Result<_>>()?);
+	}
+	Ok(result)
+}
+
+/// Represents a statement compiled with `Database.prepare`.
+/// Compiled statements are cached by the database, so running a statement does not compile it again.
+#[js_class]
+pub struct Statement {
+	reflector: Reflector,
+	#[ion(no_trace)]
+	sql: String,
+	#[ion(no_trace)]
+	columns: Vec<String>,
+	#[ion(no_trace)]
+	connection: SharedConnection,
+}
+
+impl Statement {
+	fn rows(&self, cx: &Context, parameters: Option<Parameters>, arrays: bool, limit: Option<usize>) -> Option<Promise> {
+		let sql = self.sql.clone();
+		let columns = self.columns.clone();
+		let parameters = parameters.unwrap_or_default();
+		operate(cx, &self.connection, move |connection| {
+			let mut statement = connection.prepare_cached(&sql)?;
+			let rows = query(&mut statement, &parameters, limit)?;
+			Ok(Rows { columns, rows, arrays })
+		})
+	}
+}
+
+#[js_class]
+impl Statement {
+	#[ion(constructor)]
+	pub fn constructor() -> Result<Statement> {
+		Err(Error::new("Statement has no constructor.", ErrorKind::Type))
+	}
+
+	/// Runs the statement, and resolves with all of its rows as objects keyed by column name.
+	pub fn all(&self, cx: &Context, parameters: Option<Parameters>) -> Option<Promise> {
+		self.rows(cx, parameters, false, None)
+	}
+
+	/// Runs the statement, and resolves with all of its rows as arrays of columns.
+	pub fn values(&self, cx: &Context, parameters: Option<Parameters>) -> Option<Promise> {
+		self.rows(cx, parameters, true, None)
+	}
+
+	/// Runs the statement, and resolves with its first row as an object, or `null` if it returns no rows.
+	pub fn get(&self, cx: &Context, parameters: Option<Parameters>) -> Option<Promise> {
+		let sql = self.sql.clone();
+		let columns = self.columns.clone();
+		let parameters = parameters.unwrap_or_default();
+		operate(cx, &self.connection, move |connection| {
+			let mut statement = connection.prepare_cached(&sql)?;
+			let rows = query(&mut statement, &parameters, Some(1))?;
+			Ok(Row(Rows { columns, rows, arrays: false }))
+		})
+	}
+
+	/// Runs a statement which does not return rows, and resolves with the number of rows changed and the last inserted row ID.
+	pub fn run(&self, cx: &Context, parameters: Option<Parameters>) -> Option<Promise> {
+		let sql = self.sql.clone();
+		let parameters = parameters.unwrap_or_default();
+		operate(cx, &self.connection, move |connection| {
+			let mut statement = connection.prepare_cached(&sql)?;
+			parameters.bind(&mut statement)?;
+			let changes = statement.raw_execute()?;
+			Ok(RunResult {
+				changes,
+				last_insert_rowid: connection.last_insert_rowid(),
+			})
+		})
+	}
+
+	#[ion(get)]
+	pub fn get_sql(&self) -> String {
+		self.sql.clone()
+	}
+
+	#[ion(get)]
+	pub fn get_columns(&self) -> Vec<String> {
+		self.columns.clone()
+	}
+}
+
+/// Represents a statement which has just been compiled, which is converted to a [Statement] when its promise resolves.
+pub(crate) struct Prepared {
+	pub(crate) sql: String,
+	pub(crate) columns: Vec<String>,
+	pub(crate) connection: SharedConnection,
+}
+
+impl<'cx> IntoValue<'cx> for Prepared {
+	fn into_value(self: Box<Self>, cx: &'cx Context, value: &mut Value) {
+		let statement = Statement {
+			reflector: Reflector::default(),
+			sql: self.sql,
+			columns: self.columns,
+			connection: self.connection,
+		};
+		cx.root_object(Statement::new_object(cx, Box::new(statement)))
+			.handle()
+			.get()
+			.to_value(cx, value);
+	}
+}
@@ -0,0 +1,224 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use mozjs::jsval::NullValue;
+use mozjs::typedarray::{ArrayBuffer, ArrayBufferView};
+use rusqlite::{ErrorCode, Statement};
+use rusqlite::types::Value as SqlValue;
+
+use ion::{Array, Context, Error, ErrorKind, Object, OwnedKey, Result, Value};
+use ion::conversions::{ConversionBehavior, FromValue, ToValue};
+use ion::typedarray::Uint8Array;
+
+const MAX_SAFE_INTEGER: i64 = (1 << 53) - 1;
+
+/// Converts an error from SQLite into an [Error].
+/// The `code` of the error is set to the name of the primary result code, where one is known.
+pub(crate) fn sqlite_error(error: rusqlite::Error) -> Error {
+	let code = match error.sqlite_error_code() {
+		Some(ErrorCode::ConstraintViolation) => Some("SQLITE_CONSTRAINT"),
+		Some(ErrorCode::DatabaseBusy) => Some("SQLITE_BUSY"),
+		Some(ErrorCode::DatabaseLocked) => Some("SQLITE_LOCKED"),
+		Some(ErrorCode::ReadOnly) => Some("SQLITE_READONLY"),
+		Some(ErrorCode::CannotOpen) => Some("SQLITE_CANTOPEN"),
+		Some(ErrorCode::NotADatabase) => Some("SQLITE_NOTADB"),
+		Some(ErrorCode::TypeMismatch) => Some("SQLITE_MISMATCH"),
+		_ => None,
+	};
+
+	let error = Error::new(&error.to_string(), None);
+	match code {
+		Some(code) => error.with_code(code),
+		None => error,
+	}
+}
+
+/// Represents a value which is bound to a parameter of a statement, or read from a column of a row.
+/// Numbers which are integers are bound as integers, and integers outside the safe range are read as BigInts.
+pub(crate) struct Column(pub(crate) SqlValue);
+
+impl<'cx> FromValue<'cx> for Column {
+	type Config = ();
+
+	fn from_value(cx: &'cx Context, value: &Value, _: bool, _: ()) -> Result<Column> {
+		let handle = value.handle();
+		let value = if handle.is_null_or_undefined() {
+			SqlValue::Null
+		} else if handle.is_boolean() {
+			SqlValue::Integer(i64::from(handle.to_boolean()))
+		} else if handle.is_number() {
+			let number = handle.to_number();
+			if number.fract() == 0.0 && number.abs() <= MAX_SAFE_INTEGER as f64 {
+				SqlValue::Integer(number as i64)
+			} else {
+				SqlValue::Real(number)
+			}
+		} else if handle.is_bigint() {
+			SqlValue::Integer(i64::from_value(cx, value, true, ConversionBehavior::EnforceRange)?)
+		} else if handle.is_string() {
+			SqlValue::Text(String::from_value(cx, value, true, ())?)
+		} else if let Ok(buffer) = ArrayBuffer::from_value(cx, value, true, ()) {
+			SqlValue::Blob(unsafe { buffer.as_slice() }.to_vec())
+		} else if let Ok(view) = ArrayBufferView::from_value(cx, value, true, ()) {
+			SqlValue::Blob(unsafe { view.as_slice() }.to_vec())
+		} else {
+			return Err(Error::new(
+				"Expected null, Boolean, Number, BigInt, String, ArrayBuffer or ArrayBufferView",
+				ErrorKind::Type,
+			));
+		};
+		Ok(Column(value))
+	}
+}
+
+impl<'cx> ToValue<'cx> for Column {
+	fn to_value(&self, cx: &'cx Context, value: &mut Value) {
+		match &self.0 {
+			SqlValue::Null => value.handle_mut().set(NullValue()),
+			SqlValue::Integer(integer) if integer.abs() <= MAX_SAFE_INTEGER => (*integer as f64).to_value(cx, value),
+			SqlValue::Integer(integer) => integer.to_value(cx, value),
+			SqlValue::Real(real) => real.to_value(cx, value),
+			SqlValue::Text(text) => text.to_value(cx, value),
+			SqlValue::Blob(blob) => Uint8Array::from(blob.clone()).to_value(cx, value),
+		}
+	}
+}
+
+/// Represents the parameters bound to a statement, which are either positional, given as an array,
+/// or named, given as an object whose keys are the names of the parameters with or without their prefix.
+#[derive(Default)]
+pub(crate) enum Parameters {
+	#[default]
+	None,
+	Positional(Vec<SqlValue>),
+	Named(Vec<(String, SqlValue)>),
+}
+
+impl Parameters {
+	pub(crate) fn bind(&self, statement: &mut Statement) -> rusqlite::Result<()> {
+		match self {
+			Parameters::None => Ok(()),
+			Parameters::Positional(values) => {
+				let expected = statement.parameter_count();
+				if values.len() != expected {
+					return Err(rusqlite::Error::InvalidParameterCount(values.len(), expected));
+				}
+				for (index, value) in values.iter().enumerate() {
+					statement.raw_bind_parameter(index + 1, value)?;
+				}
+				Ok(())
+			}
+			Parameters::Named(values) => {
+				for (name, value) in values {
+					let index = if name.starts_with([':', '@', '$']) {
+						statement.parameter_index(name)?
+					} else {
+						[':', '@', '$']
+							.into_iter()
+							.map(|prefix| statement.parameter_index(&format!("{}{}", prefix, name)))
+							.find_map(|index| index.transpose())
+							.transpose()?
+					};
+					let index = index.ok_or_else(|| rusqlite::Error::InvalidParameterName(name.clone()))?;
+					statement.raw_bind_parameter(index, value)?;
+				}
+				Ok(())
+			}
+		}
+	}
+}
+
+impl<'cx> FromValue<'cx> for Parameters {
+	type Config = ();
+
+	fn from_value(cx: &'cx Context, value: &Value, strict: bool, _: ()) -> Result<Parameters> {
+		if value.handle().is_null_or_undefined() {
+			return Ok(Parameters::None);
+		}
+		if !value.handle().is_object() {
+			return Err(Error::new("Expected Array or Object of Parameters", ErrorKind::Type));
+		}
+
+		let object = value.to_object(cx);
+		if Array::is_array_raw(cx, object.handle().get()) {
+			let values = Vec::<Column>::from_value(cx, value, strict, ())?;
+			return Ok(Parameters::Positional(values.into_iter().map(|column| column.0).collect()));
+		}
+
+		let mut values = Vec::new();
+		for key in object.keys(cx, None) {
+			let name = match key.to_owned_key(cx) {
+				OwnedKey::String(name) => name,
+				OwnedKey::Int(index) => index.to_string(),
+				_ => continue,
+			};
+			let value = object.get(cx, name.as_str()).unwrap_or_else(|| Value::undefined(cx));
+			values.push((name, Column::from_value(cx, &value, strict, ())?.0));
+		}
+		Ok(Parameters::Named(values))
+	}
+}
+
+/// Represents the rows returned by a query, which are converted to objects keyed by column name, or to arrays.
+pub(crate) struct Rows {
+	pub(crate) columns: Vec<String>,
+	pub(crate) rows: Vec<Vec<SqlValue>>,
+	pub(crate) arrays: bool,
+}
+
+impl Rows {
+	fn row_to_value(&self, cx: &Context, row: &[SqlValue], value: &mut Value) {
+		if self.arrays {
+			let row: Vec<_> = row.iter().map(|column| Column(column.clone())).collect();
+			row.to_value(cx, value);
+		} else {
+			let mut object = Object::new(cx);
+			for (name, column) in self.columns.iter().zip(row) {
+				object.set_as(cx, name.as_str(), &Column(column.clone()));
+			}
+			object.to_value(cx, value);
+		}
+	}
+}
+
+impl<'cx> ToValue<'cx> for Rows {
+	fn to_value(&self, cx: &'cx Context, value: &mut Value) {
+		let mut array = Array::new(cx);
+		for (index, row) in self.rows.iter().enumerate() {
+			let mut row_value = Value::undefined(cx);
+			self.row_to_value(cx, row, &mut row_value);
+			array.set(cx, index as u32, &row_value);
+		}
+		array.to_value(cx, value);
+	}
+}
+
+/// Represents the first row returned by a query, or `null` if there are none.
+pub(crate) struct Row(pub(crate) Rows);
+
+impl<'cx> ToValue<'cx> for Row {
+	fn to_value(&self, cx: &'cx Context, value: &mut Value) {
+		match self.0.rows.first() {
+			Some(row) => self.0.row_to_value(cx, row, value),
+			None => value.handle_mut().set(NullValue()),
+		}
+	}
+}
+
+/// Represents the result of executing a statement which does not return rows.
+pub(crate) struct RunResult {
+	pub(crate) changes: usize,
+	pub(crate) last_insert_rowid: i64,
+}
+
+impl<'cx> ToValue<'cx> for RunResult {
+	fn to_value(&self, cx: &'cx Context, value: &mut Value) {
+		let mut object = Object::new(cx);
+		object.set_as(cx, "changes", &(self.changes as f64));
+		object.set_as(cx, "lastInsertRowid", &Column(SqlValue::Integer(self.last_insert_rowid)));
+		object.to_value(cx, value);
+	}
+}
@@ -0,0 +1,180 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::pin::pin;
+use std::process::ExitStatus;
+use std::ptr;
+
+use futures::channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender};
+use futures::future::{Either, LocalBoxFuture, select, Shared};
+use futures::{FutureExt, StreamExt};
+use mozjs::jsapi::{Heap, JSObject};
+use tokio::process::{Child as TokioChild, Command};
+use tokio::task::spawn_local;
+
+use ion::{ClassDefinition, Context, Error, ErrorKind, Object, Promise, Result, ResultExc};
+use ion::class::Reflector;
+use runtime::event_loop::handles::{ActiveHandle, HandleKind};
+use runtime::event_loop::KeepAlive;
+use runtime::globals::streams::{readable_stream, writable_stream};
+use runtime::promise::future_to_promise;
+
+use crate::pipe::{PipeSink, PipeSource};
+use crate::subprocess::options::{ChildStatus, spawn_error};
+
+type StatusFuture = Shared<LocalBoxFuture<'static, std::result::Result<ExitStatus, String>>>;
+
+/// Returns the number of a signal which can be sent to a child process.
+fn signal_number(name: &str) -> Result<i32> {
+	match name {
+		"SIGHUP" => Ok(1),
+		"SIGINT" => Ok(2),
+		"SIGQUIT" => Ok(3),
+		"SIGKILL" => Ok(9),
+		"SIGTERM" => Ok(15),
+		_ => Err(Error::new(&format!("Unknown signal: {}", name), ErrorKind::Type)),
+	}
+}
+
+#[cfg(unix)]
+fn send_signal(child: &mut TokioChild, signal: i32) {
+	if let Some(pid) = child.id() {
+		unsafe {
+			libc::kill(pid as libc::pid_t, signal);
+		}
+	}
+}
+
+/// Windows has no signals, so the process is always terminated.
+#[cfg(not(unix))]
+fn send_signal(child: &mut TokioChild, _: i32) {
+	let _ = child.start_kill();
+}
+
+/// Waits for a child process to exit, sending it the signals received in the meantime.
+/// The event loop is kept alive until the process exits.
+async fn wait(mut child: TokioChild, mut signals: UnboundedReceiver<i32>, _keep_alive: KeepAlive) -> std::io::Result<ExitStatus> {
+	loop {
+		let signal = {
+			let status = pin!(child.wait());
+			match select(status, signals.next()).await {
+				Either::Left((status, _)) => return status,
+				Either::Right((Some(signal), _)) => signal,
+				Either::Right((None, status)) => return status.await,
+			}
+		};
+		send_signal(&mut child, signal);
+	}
+}
+
+/// Represents a process spawned with `subprocess.spawn`.
+#[js_class]
+pub struct Child {
+	reflector: Reflector,
+	#[ion(no_trace)]
+	pid: Option<u32>,
+	stdin: Box<Heap<*mut JSObject>>,
+	stdout: Box<Heap<*mut JSObject>>,
+	stderr: Box<Heap<*mut JSObject>>,
+	#[ion(no_trace)]
+	status: StatusFuture,
+	#[ion(no_trace)]
+	signals: UnboundedSender<i32>,
+}
+
+impl Child {
+	/// Spawns a command, and creates a [Child] object for the process.
+	/// Standard streams which are piped are exposed as streams on the object.
+	pub(crate) fn spawn<'cx>(cx: &'cx Context, mut command: Command, program: &str) -> ResultExc<Object<'cx>> {
+		let mut child = command.spawn().map_err(|error| spawn_error(error, program))?;
+
+		let stdin = child.stdin.take().map(|stdin| writable_stream(cx, PipeSink::new(stdin))).transpose()?;
+		let stdout = child
+			.stdout
+			.take()
+			.map(|stdout| readable_stream(cx, PipeSource::new(stdout)))
+			.transpose()?;
+		let stderr = child
+			.stderr
+			.take()
+			.map(|stderr| readable_stream(cx, PipeSource::new(stderr)))
+			.transpose()?;
+		let object = |stream: &Option<Object>| stream.as_ref().map_or_else(ptr::null_mut, |stream| stream.handle().get());
+
+		let pid = child.id();
+		let (sender, receiver) = unbounded();
+		let description = match pid {
+			Some(pid) => format!("{} (PID {})", program, pid),
+			None => String::from(program),
+		};
+		let keep_alive = KeepAlive::for_handle(cx, ActiveHandle::new(cx, HandleKind::Subprocess, description));
+		let task = spawn_local(wait(child, receiver, keep_alive));
+		let status = async move {
+			match task.await {
+				Ok(status) => status.map_err(|error| error.to_string()),
+				Err(error) => Err(error.to_string()),
+			}
+		};
+
+		let child = Child {
+			reflector: Reflector::default(),
+			pid,
+			stdin: Heap::boxed(object(&stdin)),
+			stdout: Heap::boxed(object(&stdout)),
+			stderr: Heap::boxed(object(&stderr)),
+			status: status.boxed_local().shared(),
+			signals: sender,
+		};
+		Ok(cx.root_object(Child::new_object(cx, Box::new(child))).into())
+	}
+}
+
+#[js_class]
+impl Child {
+	#[ion(constructor)]
+	pub fn constructor() -> Result<Child> {
+		Err(Error::new("Child has no constructor.", ErrorKind::Type))
+	}
+
+	/// Resolves with the exit status of the process, once it exits.
+	pub fn status(&self, cx: &Context) -> Option<Promise> {
+		let status = self.status.clone();
+		future_to_promise(cx, async move {
+			let status = status
+				.await
+				.map_err(|error| Error::new(&format!("Could not wait for process: {}", error), None))?;
+			Ok::<_, Error>(ChildStatus(status))
+		})
+	}
+
+	/// Sends a signal to the process, which defaults to `SIGTERM`. On Windows, the process is always terminated.
+	/// Nothing is sent if the process has already exited.
+	pub fn kill(&self, signal: Option<String>) -> Result<()> {
+		let signal = signal_number(signal.as_deref().unwrap_or("SIGTERM"))?;
+		let _ = self.signals.unbounded_send(signal);
+		Ok(())
+	}
+
+	#[ion(get)]
+	pub fn get_pid(&self) -> Option<u32> {
+		self.pid
+	}
+
+	#[ion(get)]
+	pub fn get_stdin(&self) -> *mut JSObject {
+		self.stdin.get()
+	}
+
+	#[ion(get)]
+	pub fn get_stdout(&self) -> *mut JSObject {
+		self.stdout.get()
+	}
+
+	#[ion(get)]
+	pub fn get_stderr(&self) -> *mut JSObject {
+		self.stderr.get()
+	}
+}
@@ -0,0 +1,130 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::io;
+use std::io::ErrorKind as IoErrorKind;
+use std::process::{ExitStatus, Stdio};
+
+use tokio::process::Command;
+
+use ion::{Context, Error, ErrorKind, Object, OwnedKey, Result, Value};
+use ion::conversions::{FromValue, ToValue};
+
+use crate::process::EnvOverlay;
+
+/// Converts an I/O error from spawning a program into an [Error], with the `code` of the corresponding system error.
+pub(crate) fn spawn_error(error: io::Error, program: &str) -> Error {
+	let code = match error.kind() {
+		IoErrorKind::NotFound => Some("ENOENT"),
+		IoErrorKind::PermissionDenied => Some("EACCES"),
+		_ => None,
+	};
+
+	let error = Error::new(&format!("Could not spawn {}: {}", program, error), None);
+	match code {
+		Some(code) => error.with_code(code),
+		None => error,
+	}
+}
+
+/// Represents how the standard streams of a child process are connected.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, FromValue)]
+pub(crate) enum StdioMode {
+	/// The streams are available on the child as `stdin`, `stdout` and `stderr`.
+	#[default]
+	Piped,
+	/// The streams are shared with the current process.
+	Inherit,
+	/// The streams are connected to the null device.
+	Null,
+}
+
+impl StdioMode {
+	pub(crate) fn stdio(self) -> Stdio {
+		match self {
+			StdioMode::Piped => Stdio::piped(),
+			StdioMode::Inherit => Stdio::inherit(),
+			StdioMode::Null => Stdio::null(),
+		}
+	}
+}
+
+/// Represents the environment variables of a child process, given as an object of names to values.
+pub(crate) struct Environment(Vec<(String, String)>);
+
+impl<'cx> FromValue<'cx> for Environment {
+	type Config = ();
+
+	fn from_value(cx: &'cx Context, value: &Value, strict: bool, _: ()) -> Result<Environment> {
+		let object = Object::from_value(cx, value, strict, ())?;
+		let variables = object
+			.iter(cx, None)
+			.map(|(key, value)| {
+				let value = String::from_value(cx, &value, false, ())?;
+				match key.to_owned_key(cx) {
+					OwnedKey::Int(i) => Ok((i.to_string(), value)),
+					OwnedKey::String(key) => Ok((key, value)),
+					_ => Err(Error::new("Expected String Environment Variable Name", ErrorKind::Type)),
+				}
+			})
+			.collect::<Result<_>>()?;
+		Ok(Environment(variables))
+	}
+}
+
+#[derive(Default, FromValue)]
+pub(crate) struct SpawnOptions {
+	#[ion(default)]
+	pub(crate) stdio: StdioMode,
+	env: Option<Environment>,
+	#[ion(default, name = "clearEnv")]
+	clear_env: bool,
+	cwd: Option<String>,
+}
+
+impl SpawnOptions {
+	/// Returns the [Command] for spawning a program with the given arguments and these options.
+	/// The program inherits the environment variables of the runtime, unless `clearEnv` is set.
+	pub(crate) fn command(&self, cx: &Context, program: &str, args: &[String]) -> Command {
+		let mut command = Command::new(program);
+		command
+			.args(args)
+			.stdin(self.stdio.stdio())
+			.stdout(self.stdio.stdio())
+			.stderr(self.stdio.stdio());
+		if self.clear_env {
+			command.env_clear();
+		} else {
+			EnvOverlay::with(cx, |overlay| overlay.apply(&mut command));
+		}
+		if let Some(env) = &self.env {
+			command.envs(env.0.iter().map(|(name, value)| (name, value)));
+		}
+		if let Some(cwd) = &self.cwd {
+			command.current_dir(cwd);
+		}
+		command
+	}
+}
+
+/// Represents the exit status of a child process.
+/// `signal` is the number of the signal which terminated the process, and is always `null` on Windows.
+pub(crate) struct ChildStatus(pub(crate) ExitStatus);
+
+impl<'cx> ToValue<'cx> for ChildStatus {
+	fn to_value(&self, cx: &'cx Context, value: &mut Value) {
+		#[cfg(unix)]
+		let signal = std::os::unix::process::ExitStatusExt::signal(&self.0);
+		#[cfg(not(unix))]
+		let signal: Option<i32> = None;
+
+		let mut object = Object::new(cx);
+		object.set_as(cx, "success", &self.0.success());
+		object.set_as(cx, "code", &self.0.code());
+		object.set_as(cx, "signal", &signal);
+		object.to_value(cx, value);
+	}
+}
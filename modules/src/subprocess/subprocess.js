@@ -0,0 +1,12 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+export const spawn = ______subprocessInternal______.spawn;
+export const output = ______subprocessInternal______.output;
+
+export const Child = ______subprocessInternal______.Child;
+
+export default Object.freeze(______subprocessInternal______);
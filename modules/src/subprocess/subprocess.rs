@@ -0,0 +1,77 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::process::{Output, Stdio};
+
+use mozjs::jsapi::JSFunctionSpec;
+
+use ion::{ClassDefinition, Context, Error, Object, Promise, ResultExc, Value};
+use ion::conversions::ToValue;
+use ion::typedarray::Uint8Array;
+use runtime::modules::NativeModule;
+use runtime::permissions::check_run;
+use runtime::promise::future_to_promise;
+
+use crate::subprocess::child::Child;
+use crate::subprocess::options::{ChildStatus, spawn_error, SpawnOptions, StdioMode};
+
+/// Represents the exit status and collected output of a child process, returned by `output`.
+struct ProcessOutput(Output);
+
+impl<'cx> ToValue<'cx> for ProcessOutput {
+	fn to_value(&self, cx: &'cx Context, value: &mut Value) {
+		let mut object = Object::new(cx);
+		object.set_as(cx, "status", &ChildStatus(self.0.status));
+		object.set_as(cx, "stdout", &Uint8Array::from(self.0.stdout.clone()));
+		object.set_as(cx, "stderr", &Uint8Array::from(self.0.stderr.clone()));
+		object.to_value(cx, value);
+	}
+}
+
+#[js_fn]
+fn spawn<'cx>(cx: &'cx Context, program: String, args: Option<Vec<String>>, options: Option<SpawnOptions>) -> ResultExc<Object<'cx>> {
+	check_run(&program)?;
+	let options = options.unwrap_or_default();
+	let command = options.command(cx, &program, &args.unwrap_or_default());
+	Child::spawn(cx, command, &program)
+}
+
+/// Runs a program to completion, and resolves with its exit status and everything it wrote to its standard output and error.
+/// The standard input of the program is inherited if `stdio` is `inherit`, and is connected to the null device otherwise.
+#[js_fn]
+fn output(cx: &Context, program: String, args: Option<Vec<String>>, options: Option<SpawnOptions>) -> Option<Promise> {
+	let options = options.unwrap_or_default();
+	let mut command = options.command(cx, &program, &args.unwrap_or_default());
+	let stdin = match options.stdio {
+		StdioMode::Inherit => Stdio::inherit(),
+		_ => Stdio::null(),
+	};
+	command.stdin(stdin).stdout(Stdio::piped()).stderr(Stdio::piped());
+
+	future_to_promise::<_, _, Error>(cx, async move {
+		check_run(&program)?;
+		let output = command.output().await.map_err(|error| spawn_error(error, &program))?;
+		Ok(ProcessOutput(output))
+	})
+}
+
+const FUNCTIONS: &[JSFunctionSpec] = &[function_spec!(spawn, 1), function_spec!(output, 1), JSFunctionSpec::ZERO];
+
+#[derive(Default)]
+pub struct Subprocess;
+
+impl NativeModule for Subprocess {
+	const NAME: &'static str = "subprocess";
+	const SOURCE: &'static str = include_str!("subprocess.js");
+
+	fn module(cx: &Context) -> Option<Object> {
+		let mut subprocess = Object::new(cx);
+		if unsafe { subprocess.define_methods(cx, FUNCTIONS) } && Child::init_class(cx, &mut subprocess).0 {
+			return Some(subprocess);
+		}
+		None
+	}
+}
@@ -0,0 +1,176 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::mem::take;
+use std::time::Duration;
+
+use mozjs::jsapi::JSFunction;
+
+use ion::{Context, PersistentRooted};
+use runtime::ContextExt;
+
+pub(crate) type Callback = PersistentRooted<*mut JSFunction>;
+
+/// Default time which tests and hooks must settle within.
+pub(crate) const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum HookKind {
+	BeforeAll,
+	AfterAll,
+	BeforeEach,
+	AfterEach,
+}
+
+#[derive(Default)]
+pub(crate) struct Hooks {
+	pub(crate) before_all: Vec<Callback>,
+	pub(crate) after_all: Vec<Callback>,
+	pub(crate) before_each: Vec<Callback>,
+	pub(crate) after_each: Vec<Callback>,
+}
+
+impl Hooks {
+	pub(crate) fn get(&self, kind: HookKind) -> &[Callback] {
+		match kind {
+			HookKind::BeforeAll => &self.before_all,
+			HookKind::AfterAll => &self.after_all,
+			HookKind::BeforeEach => &self.before_each,
+			HookKind::AfterEach => &self.after_each,
+		}
+	}
+
+	fn get_mut(&mut self, kind: HookKind) -> &mut Vec<Callback> {
+		match kind {
+			HookKind::BeforeAll => &mut self.before_all,
+			HookKind::AfterAll => &mut self.after_all,
+			HookKind::BeforeEach => &mut self.before_each,
+			HookKind::AfterEach => &mut self.after_each,
+		}
+	}
+}
+
+/// Represents a group of tests declared with `describe`, whose hooks apply to every test within it.
+/// The root group contains tests declared outside of any group.
+pub(crate) struct Group {
+	pub(crate) name: String,
+	pub(crate) parent: Option<usize>,
+	pub(crate) hooks: Hooks,
+	pub(crate) skip: bool,
+	pub(crate) only: bool,
+}
+
+pub(crate) struct TestCase {
+	pub(crate) name: String,
+	pub(crate) function: Callback,
+	pub(crate) group: usize,
+	pub(crate) skip: bool,
+	pub(crate) only: bool,
+	pub(crate) timeout: Duration,
+}
+
+/// Holds the groups and tests declared in a runtime, in the order they were declared.
+pub(crate) struct Registry {
+	pub(crate) groups: Vec<Group>,
+	pub(crate) tests: Vec<TestCase>,
+	current: usize,
+}
+
+impl Registry {
+	/// Calls a closure with the registry of the runtime.
+	pub(crate) fn with<T, F: FnOnce(&mut Registry) -> T>(cx: &Context, f: F) -> T {
+		f(unsafe { (*cx.get_private().as_ptr()).extension() })
+	}
+
+	/// Takes the declared groups and tests, so that they are only run once.
+	pub(crate) fn take(cx: &Context) -> Registry {
+		Registry::with(cx, take)
+	}
+
+	/// Starts a group within the current group, and returns the previous group which is restored with [Registry::end_group].
+	pub(crate) fn start_group(&mut self, name: String, skip: bool, only: bool) -> usize {
+		self.groups.push(Group {
+			name,
+			parent: Some(self.current),
+			hooks: Hooks::default(),
+			skip,
+			only,
+		});
+		let previous = self.current;
+		self.current = self.groups.len() - 1;
+		previous
+	}
+
+	pub(crate) fn end_group(&mut self, previous: usize) {
+		self.current = previous;
+	}
+
+	pub(crate) fn add_test(&mut self, name: String, function: Callback, skip: bool, only: bool, timeout: Duration) {
+		self.tests.push(TestCase {
+			name,
+			function,
+			group: self.current,
+			skip,
+			only,
+			timeout,
+		});
+	}
+
+	pub(crate) fn add_hook(&mut self, kind: HookKind, function: Callback) {
+		self.groups[self.current].hooks.get_mut(kind).push(function);
+	}
+
+	/// Returns the groups which contain a group, from the root group to the group itself.
+	pub(crate) fn ancestors(&self, group: usize) -> Vec<usize> {
+		let mut ancestors = vec![group];
+		let mut current = group;
+		while let Some(parent) = self.groups[current].parent {
+			ancestors.push(parent);
+			current = parent;
+		}
+		ancestors.reverse();
+		ancestors
+	}
+
+	/// Returns the name of a test, prefixed by the names of the groups it is within.
+	pub(crate) fn full_name(&self, test: &TestCase) -> String {
+		let mut names: Vec<_> = self
+			.ancestors(test.group)
+			.into_iter()
+			.map(|group| self.groups[group].name.as_str())
+			.collect();
+		names.push(&test.name);
+		names.retain(|name| !name.is_empty());
+		names.join(" > ")
+	}
+
+	/// Checks if a test is skipped, either by itself or by a group it is within.
+	pub(crate) fn is_skipped(&self, test: &TestCase) -> bool {
+		test.skip || self.ancestors(test.group).into_iter().any(|group| self.groups[group].skip)
+	}
+
+	/// Checks if a test is focused with `only`, either by itself or by a group it is within.
+	pub(crate) fn is_only(&self, test: &TestCase) -> bool {
+		test.only || self.ancestors(test.group).into_iter().any(|group| self.groups[group].only)
+	}
+}
+
+impl Default for Registry {
+	fn default() -> Registry {
+		let root = Group {
+			name: String::new(),
+			parent: None,
+			hooks: Hooks::default(),
+			skip: false,
+			only: false,
+		};
+		Registry {
+			groups: vec![root],
+			tests: Vec::new(),
+			current: 0,
+		}
+	}
+}
@@ -0,0 +1,227 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::pin::pin;
+use std::time::{Duration, Instant};
+
+use futures::future::{Either, select};
+use tokio::time::sleep;
+
+use ion::{Context, Error, ErrorReport, Exception, Function, Object, Promise, PromiseFuture, Value};
+use ion::conversions::ToValue;
+use runtime::promise::future_to_promise;
+
+use crate::test::registry::{Callback, DEFAULT_TIMEOUT, HookKind, Registry, TestCase};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TestOutcome {
+	Passed,
+	/// The test, or one of its hooks, threw or rejected with the formatted error.
+	Failed(String),
+	Skipped,
+}
+
+impl TestOutcome {
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			TestOutcome::Passed => "passed",
+			TestOutcome::Failed(_) => "failed",
+			TestOutcome::Skipped => "skipped",
+		}
+	}
+}
+
+/// Represents the result of a test, whose name is prefixed by the names of the groups it is within.
+#[derive(Clone, Debug)]
+pub struct TestResult {
+	pub name: String,
+	pub outcome: TestOutcome,
+	pub duration: Duration,
+}
+
+impl<'cx> ToValue<'cx> for TestResult {
+	fn to_value(&self, cx: &'cx Context, value: &mut Value) {
+		let mut object = Object::new(cx);
+		object.set_as(cx, "name", &self.name);
+		object.set_as(cx, "status", self.outcome.as_str());
+		object.set_as(cx, "duration", &(self.duration.as_secs_f64() * 1000.0));
+		if let TestOutcome::Failed(error) = &self.outcome {
+			object.set_as(cx, "error", error);
+		}
+		object.to_value(cx, value);
+	}
+}
+
+/// Represents the results of a run, as `{ passed, failed, skipped, results }`.
+struct Summary(Vec<TestResult>);
+
+impl<'cx> ToValue<'cx> for Summary {
+	fn to_value(&self, cx: &'cx Context, value: &mut Value) {
+		let count = |outcome: &str| self.0.iter().filter(|result| result.outcome.as_str() == outcome).count() as u32;
+		let mut object = Object::new(cx);
+		object.set_as(cx, "passed", &count("passed"));
+		object.set_as(cx, "failed", &count("failed"));
+		object.set_as(cx, "skipped", &count("skipped"));
+		object.set_as(cx, "results", &self.0);
+		object.to_value(cx, value);
+	}
+}
+
+/// Runs the tests declared in the runtime, and resolves with a summary of their results once they have all finished.
+/// Only tests whose names contain `filter` are run, and `on_result` is called with the result of each test as it finishes.
+///
+/// Tests run in the order they were declared, with the hooks of the groups they are within.
+/// If any test or group is declared with `only`, other tests are not run.
+pub fn run_tests<'cx, F>(cx: &'cx Context, filter: Option<String>, mut on_result: F) -> Option<Promise<'cx>>
+where
+	F: FnMut(&TestResult) + 'static,
+{
+	let registry = Registry::take(cx);
+	let cx_ptr = cx.as_ptr();
+	future_to_promise::<_, _, Error>(cx, async move {
+		let cx = unsafe { Context::new_unchecked(cx_ptr) };
+		let results = run(&cx, &registry, filter.as_deref(), &mut on_result).await;
+		Ok(Summary(results))
+	})
+}
+
+async fn run(cx: &Context, registry: &Registry, filter: Option<&str>, on_result: &mut dyn FnMut(&TestResult)) -> Vec<TestResult> {
+	let focused = registry.tests.iter().any(|test| registry.is_only(test));
+	let tests = registry
+		.tests
+		.iter()
+		.filter(|test| !focused || registry.is_only(test))
+		.filter(|test| match filter {
+			Some(filter) => registry.full_name(test).contains(filter),
+			None => true,
+		});
+
+	let mut results = Vec::new();
+	let mut report = |result: TestResult| {
+		on_result(&result);
+		results.push(result);
+	};
+
+	// Groups whose `beforeAll` hooks have run, from the root group, with the error of the hook if it failed.
+	let mut entered: Vec<(usize, Option<String>)> = Vec::new();
+	for test in tests {
+		let name = registry.full_name(test);
+		if registry.is_skipped(test) {
+			report(TestResult {
+				name,
+				outcome: TestOutcome::Skipped,
+				duration: Duration::ZERO,
+			});
+			continue;
+		}
+
+		let ancestors = registry.ancestors(test.group);
+		while let Some(&(group, _)) = entered.last() {
+			if ancestors.contains(&group) {
+				break;
+			}
+			entered.pop();
+			if let Some(result) = run_after_all(cx, registry, group).await {
+				report(result);
+			}
+		}
+		for &group in &ancestors[entered.len()..] {
+			let failure = run_hooks(cx, registry, &[group], HookKind::BeforeAll).await.err();
+			entered.push((group, failure));
+		}
+
+		let start = Instant::now();
+		let outcome = match entered.iter().find_map(|(_, failure)| failure.clone()) {
+			Some(failure) => TestOutcome::Failed(failure),
+			None => run_test(cx, registry, test, &ancestors).await,
+		};
+		report(TestResult { name, outcome, duration: start.elapsed() });
+	}
+
+	while let Some((group, _)) = entered.pop() {
+		if let Some(result) = run_after_all(cx, registry, group).await {
+			report(result);
+		}
+	}
+	results
+}
+
+/// Runs a test with the `beforeEach` and `afterEach` hooks of the groups it is within.
+/// The `afterEach` hooks run even if the test fails, and the first failure is reported.
+async fn run_test(cx: &Context, registry: &Registry, test: &TestCase, ancestors: &[usize]) -> TestOutcome {
+	let mut failure = run_hooks(cx, registry, ancestors, HookKind::BeforeEach).await.err();
+	if failure.is_none() {
+		failure = call(cx, &test.function, test.timeout).await.err();
+	}
+
+	let descendants: Vec<_> = ancestors.iter().rev().copied().collect();
+	let after = run_hooks(cx, registry, &descendants, HookKind::AfterEach).await.err();
+	match failure.or(after) {
+		Some(failure) => TestOutcome::Failed(failure),
+		None => TestOutcome::Passed,
+	}
+}
+
+/// Runs the `afterAll` hooks of a group, and returns a failed result for the group if any of them fail.
+async fn run_after_all(cx: &Context, registry: &Registry, group: usize) -> Option<TestResult> {
+	let start = Instant::now();
+	let failure = run_hooks(cx, registry, &[group], HookKind::AfterAll).await.err()?;
+
+	let mut names: Vec<_> = registry
+		.ancestors(group)
+		.into_iter()
+		.map(|group| registry.groups[group].name.as_str())
+		.collect();
+	names.push("afterAll");
+	names.retain(|name| !name.is_empty());
+	Some(TestResult {
+		name: names.join(" > "),
+		outcome: TestOutcome::Failed(failure),
+		duration: start.elapsed(),
+	})
+}
+
+/// Runs the hooks of a kind for each group in order, stopping at the first failure.
+async fn run_hooks(cx: &Context, registry: &Registry, groups: &[usize], kind: HookKind) -> Result<(), String> {
+	for &group in groups {
+		for hook in registry.groups[group].hooks.get(kind) {
+			call(cx, hook, DEFAULT_TIMEOUT).await?;
+		}
+	}
+	Ok(())
+}
+
+/// Calls a test or hook, and waits for the promise it returns to settle, unless it does not settle within `timeout`.
+async fn call(cx: &Context, function: &Callback, timeout: Duration) -> Result<(), String> {
+	let function = Function::from(cx.root_function(function.get()));
+	let value = function.call(cx, &Object::null(cx), &[]).map_err(|report| match report {
+		Some(report) => format_failure(cx, report),
+		None => String::from("Uncatchable exception"),
+	})?;
+
+	if value.handle().is_object() {
+		if let Some(promise) = Promise::from(value.to_object(cx).into_local()) {
+			let settled = PromiseFuture::new(cx, &promise);
+			return match select(pin!(settled), pin!(sleep(timeout))).await {
+				Either::Left((Ok(_), _)) => Ok(()),
+				Either::Left((Err(reason), _)) => {
+					let exception = Exception::from_value(cx, &reason);
+					Err(format_failure(cx, ErrorReport::from_exception_with_error_stack(cx, exception)))
+				}
+				Either::Right(_) => Err(format!("Timed out after {}ms", timeout.as_millis())),
+			};
+		}
+	}
+	Ok(())
+}
+
+fn format_failure(cx: &Context, report: ErrorReport) -> String {
+	let message = report.format(cx);
+	match message.strip_prefix("Uncaught ") {
+		Some(message) => String::from(message),
+		None => message,
+	}
+}
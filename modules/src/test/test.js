@@ -0,0 +1,18 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+export const test = ______testInternal______.test;
+export const it = ______testInternal______.it;
+export const describe = ______testInternal______.describe;
+
+export const beforeAll = ______testInternal______.beforeAll;
+export const afterAll = ______testInternal______.afterAll;
+export const beforeEach = ______testInternal______.beforeEach;
+export const afterEach = ______testInternal______.afterEach;
+
+export const run = ______testInternal______.run;
+
+export default Object.freeze(______testInternal______);
@@ -0,0 +1,130 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::time::Duration;
+
+use mozjs::conversions::ConversionBehavior::EnforceRange;
+use mozjs::jsapi::JSFunctionSpec;
+
+use ion::{Context, Error, Function, Object, PersistentRooted, Promise, ResultExc};
+use runtime::modules::NativeModule;
+
+use crate::test::registry::{DEFAULT_TIMEOUT, HookKind, Registry};
+use crate::test::runner::run_tests;
+
+#[derive(Default, FromValue)]
+pub(crate) struct TestOptions {
+	#[ion(default)]
+	skip: bool,
+	/// Only runs this test, and other tests declared with `only`.
+	#[ion(default)]
+	only: bool,
+	/// Time in milliseconds which the test must settle within. Defaults to 5 seconds.
+	#[ion(convert = EnforceRange)]
+	timeout: Option<u32>,
+}
+
+#[derive(Default, FromValue)]
+pub(crate) struct GroupOptions {
+	#[ion(default)]
+	skip: bool,
+	#[ion(default)]
+	only: bool,
+}
+
+#[derive(Default, FromValue)]
+pub(crate) struct RunOptions {
+	/// Only runs tests whose names, including the names of their groups, contain the filter.
+	filter: Option<String>,
+}
+
+/// Declares a test, which passes unless `function` throws or the promise it returns rejects.
+#[js_fn]
+fn test(cx: &Context, name: String, function: Function, options: Option<TestOptions>) {
+	let options = options.unwrap_or_default();
+	let timeout = options
+		.timeout
+		.map(|timeout| Duration::from_millis(timeout as u64))
+		.unwrap_or(DEFAULT_TIMEOUT);
+	let function = PersistentRooted::new(function.get());
+	Registry::with(cx, |registry| registry.add_test(name, function, options.skip, options.only, timeout));
+}
+
+/// Declares a group of tests, by calling `function` which declares the tests and hooks within it.
+#[js_fn]
+fn describe(cx: &Context, name: String, function: Function, options: Option<GroupOptions>) -> ResultExc<()> {
+	let options = options.unwrap_or_default();
+	let previous = Registry::with(cx, |registry| registry.start_group(name, options.skip, options.only));
+	let result = function.call(cx, &Object::null(cx), &[]);
+	Registry::with(cx, |registry| registry.end_group(previous));
+	match result {
+		Ok(_) => Ok(()),
+		Err(Some(report)) => Err(report.exception),
+		Err(None) => Err(Error::new("Group threw an uncatchable exception", None).into()),
+	}
+}
+
+fn add_hook(cx: &Context, kind: HookKind, function: Function) {
+	let function = PersistentRooted::new(function.get());
+	Registry::with(cx, |registry| registry.add_hook(kind, function));
+}
+
+/// Declares a hook which runs before the first test of the current group.
+#[js_fn]
+fn beforeAll(cx: &Context, function: Function) {
+	add_hook(cx, HookKind::BeforeAll, function);
+}
+
+/// Declares a hook which runs after the last test of the current group.
+#[js_fn]
+fn afterAll(cx: &Context, function: Function) {
+	add_hook(cx, HookKind::AfterAll, function);
+}
+
+/// Declares a hook which runs before each test within the current group.
+#[js_fn]
+fn beforeEach(cx: &Context, function: Function) {
+	add_hook(cx, HookKind::BeforeEach, function);
+}
+
+/// Declares a hook which runs after each test within the current group, even if it fails.
+#[js_fn]
+fn afterEach(cx: &Context, function: Function) {
+	add_hook(cx, HookKind::AfterEach, function);
+}
+
+/// Runs the declared tests, and resolves with a summary of their results.
+/// Tests which are declared afterwards are run by subsequent calls.
+#[js_fn]
+fn run(cx: &Context, options: Option<RunOptions>) -> Option<Promise> {
+	let options = options.unwrap_or_default();
+	run_tests(cx, options.filter, |_| {})
+}
+
+const FUNCTIONS: &[JSFunctionSpec] = &[
+	function_spec!(test, 2),
+	function_spec!(test, "it", 2),
+	function_spec!(describe, 2),
+	function_spec!(beforeAll, 1),
+	function_spec!(afterAll, 1),
+	function_spec!(beforeEach, 1),
+	function_spec!(afterEach, 1),
+	function_spec!(run, 0),
+	JSFunctionSpec::ZERO,
+];
+
+#[derive(Default)]
+pub struct Test;
+
+impl NativeModule for Test {
+	const NAME: &'static str = "test";
+	const SOURCE: &'static str = include_str!("test.js");
+
+	fn module(cx: &Context) -> Option<Object> {
+		let mut test = Object::new(cx);
+		unsafe { test.define_methods(cx, FUNCTIONS).then_some(test) }
+	}
+}
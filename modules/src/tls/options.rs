@@ -0,0 +1,34 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use ion::{Context, Result, Value};
+use ion::conversions::FromValue;
+use runtime::globals::abort::Signal;
+
+use crate::net::TlsOptions;
+
+#[derive(Default, FromValue)]
+struct SignalOptions {
+	#[ion(default)]
+	signal: Signal,
+}
+
+/// Represents the options of `connectTls`, which are those of the TLS handshake along with a `signal` which aborts connecting.
+#[derive(Default)]
+pub(crate) struct ConnectTlsOptions {
+	pub(crate) tls: TlsOptions,
+	pub(crate) signal: Signal,
+}
+
+impl<'cx> FromValue<'cx> for ConnectTlsOptions {
+	type Config = ();
+
+	fn from_value(cx: &'cx Context, value: &Value, strict: bool, _: ()) -> Result<ConnectTlsOptions> {
+		let tls = TlsOptions::from_value(cx, value, strict, ())?;
+		let SignalOptions { signal } = SignalOptions::from_value(cx, value, strict, ())?;
+		Ok(ConnectTlsOptions { tls, signal })
+	}
+}
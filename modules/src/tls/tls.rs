@@ -0,0 +1,40 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use mozjs::conversions::ConversionBehavior::EnforceRange;
+use mozjs::jsapi::JSFunctionSpec;
+
+use ion::{Context, Object, Promise};
+use runtime::modules::NativeModule;
+
+use crate::net::establish;
+use crate::tls::options::ConnectTlsOptions;
+
+/// Connects to a TLS server, and resolves with the connection once the handshake has completed.
+/// The certificate of the server is verified against `hostname`, which is also sent with SNI, unless overridden in the options.
+#[js_fn]
+fn connectTls(cx: &Context, hostname: String, #[ion(convert = EnforceRange)] port: u16, options: Option<ConnectTlsOptions>) -> Option<Promise> {
+	let options = options.unwrap_or_default();
+	establish(cx, hostname, port, Some(options.tls), options.signal)
+}
+
+const FUNCTIONS: &[JSFunctionSpec] = &[function_spec!(connectTls, 2), JSFunctionSpec::ZERO];
+
+#[derive(Default)]
+pub struct Tls;
+
+impl NativeModule for Tls {
+	const NAME: &'static str = "tls";
+	const SOURCE: &'static str = include_str!("tls.js");
+
+	fn module(cx: &Context) -> Option<Object> {
+		let mut tls = Object::new(cx);
+		if unsafe { tls.define_methods(cx, FUNCTIONS) } {
+			return Some(tls);
+		}
+		None
+	}
+}
@@ -0,0 +1,12 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+export const Worker = ______workerInternal______.Worker;
+export const isMainThread = ______workerInternal______.isMainThread;
+export const postMessage = ______workerInternal______.postMessage;
+export const close = ______workerInternal______.close;
+
+export default Object.freeze(______workerInternal______);
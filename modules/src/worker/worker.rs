@@ -0,0 +1,37 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use ion::{ClassDefinition, Context, Object};
+use runtime::globals::worker::{is_worker, Worker};
+use runtime::modules::NativeModule;
+
+#[derive(Default)]
+pub struct WorkerM;
+
+impl NativeModule for WorkerM {
+	const NAME: &'static str = "worker";
+	const SOURCE: &'static str = include_str!("worker.js");
+
+	fn module(cx: &Context) -> Option<Object> {
+		let mut worker = Object::new(cx);
+		let global = Object::global(cx);
+
+		if let Some(global_worker) = global.get(cx, stringify!(Worker)) {
+			worker.set(cx, stringify!(Worker), &global_worker);
+		} else if !Worker::init_class(cx, &mut worker).0 {
+			return None;
+		}
+
+		// Only the globals of workers have functions to communicate with their parent.
+		for name in ["postMessage", "close"] {
+			if let Some(function) = global.get(cx, name).filter(|_| is_worker()) {
+				worker.set(cx, name, &function);
+			}
+		}
+
+		worker.set_as(cx, "isMainThread", &!is_worker()).then_some(worker)
+	}
+}
@@ -0,0 +1,12 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+export const compress = ______zlibInternal______.compress;
+export const compressSync = ______zlibInternal______.compressSync;
+export const decompress = ______zlibInternal______.decompress;
+export const decompressSync = ______zlibInternal______.decompressSync;
+
+export default Object.freeze(______zlibInternal______);
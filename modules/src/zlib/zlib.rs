@@ -0,0 +1,89 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use mozjs::conversions::ConversionBehavior::EnforceRange;
+use mozjs::jsapi::JSFunctionSpec;
+use tokio::task::spawn_blocking;
+
+use ion::{Context, Error, ErrorKind, Object, Promise, Result};
+use ion::typedarray::Uint8Array;
+use runtime::globals::compression;
+use runtime::globals::compression::Format;
+use runtime::modules::NativeModule;
+use runtime::promise::future_to_promise;
+
+use crate::fs::FileData;
+
+#[derive(Default, FromValue)]
+struct CompressOptions {
+	/// Ranges from 0 to 9 for gzip and DEFLATE, and from 0 to 11 for brotli.
+	#[ion(convert = EnforceRange)]
+	level: Option<u32>,
+}
+
+/// Runs a compression or decompression on the blocking thread pool, so that large data does not stall the event loop.
+fn spawn<F>(cx: &Context, f: F) -> Option<Promise>
+where
+	F: FnOnce() -> Result<Vec<u8>> + Send + 'static,
+{
+	let f = move || f().map_err(|error| error.message);
+	future_to_promise::<_, _, Error>(cx, async move {
+		let bytes = spawn_blocking(f).await.map_err(|error| Error::new(&error.to_string(), None))?;
+		bytes.map(Uint8Array::from).map_err(|message| Error::new(&message, ErrorKind::Type))
+	})
+}
+
+/// Compresses data in a format, which defaults to gzip, and resolves with the compressed bytes.
+#[js_fn]
+fn compress(cx: &Context, data: FileData, format: Option<Format>, options: Option<CompressOptions>) -> Option<Promise> {
+	let format = format.unwrap_or(Format::Gzip);
+	let level = options.unwrap_or_default().level;
+	spawn(cx, move || compression::compress(format, level, &data.0))
+}
+
+#[js_fn]
+fn compressSync(data: FileData, format: Option<Format>, options: Option<CompressOptions>) -> Result<Uint8Array> {
+	let format = format.unwrap_or(Format::Gzip);
+	let level = options.unwrap_or_default().level;
+	compression::compress(format, level, &data.0).map(Uint8Array::from)
+}
+
+/// Decompresses data in a format, which defaults to gzip, and resolves with the decompressed bytes.
+/// The promise is rejected if the data is invalid or truncated.
+#[js_fn]
+fn decompress(cx: &Context, data: FileData, format: Option<Format>) -> Option<Promise> {
+	let format = format.unwrap_or(Format::Gzip);
+	spawn(cx, move || compression::decompress(format, &data.0))
+}
+
+#[js_fn]
+fn decompressSync(data: FileData, format: Option<Format>) -> Result<Uint8Array> {
+	compression::decompress(format.unwrap_or(Format::Gzip), &data.0).map(Uint8Array::from)
+}
+
+const FUNCTIONS: &[JSFunctionSpec] = &[
+	function_spec!(compress, 1),
+	function_spec!(compressSync, 1),
+	function_spec!(decompress, 1),
+	function_spec!(decompressSync, 1),
+	JSFunctionSpec::ZERO,
+];
+
+#[derive(Default)]
+pub struct Zlib;
+
+impl NativeModule for Zlib {
+	const NAME: &'static str = "zlib";
+	const SOURCE: &'static str = include_str!("zlib.js");
+
+	fn module(cx: &Context) -> Option<Object> {
+		let mut zlib = Object::new(cx);
+		if unsafe { zlib.define_methods(cx, FUNCTIONS) } {
+			return Some(zlib);
+		}
+		None
+	}
+}
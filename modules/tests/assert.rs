@@ -9,6 +9,7 @@ use std::path::Path;
 use mozjs::jsapi::JSFunctionSpec;
 use mozjs::rust::JSEngine;
 use mozjs::rust::Runtime as RustRuntime;
+use tokio::task::LocalSet;
 
 use ion::{Context, Error, Exception, Function, Object, Value};
 use ion::module::Module;
@@ -19,13 +20,22 @@ use runtime::modules::Loader;
 
 const OK: (&str, &str) = ("ok", include_str!("scripts/assert/ok.js"));
 const EQUALS: (&str, &str) = ("equals", include_str!("scripts/assert/equals.js"));
+const EQUAL: (&str, &str) = ("equal", include_str!("scripts/assert/equal.js"));
+const DEEP_EQUAL: (&str, &str) = ("deepEqual", include_str!("scripts/assert/deepEqual.js"));
 const THROWS: (&str, &str) = ("throws", include_str!("scripts/assert/throws.js"));
+const REJECTS: (&str, &str) = ("rejects", include_str!("scripts/assert/rejects.js"));
+const MATCH: (&str, &str) = ("match", include_str!("scripts/assert/match.js"));
 const FAIL: (&str, &str) = ("fail", include_str!("scripts/assert/fail.js"));
 
 const EXCEPTION_STRING: &str = "_spidermonkey_exception_";
 
 #[tokio::test]
 async fn assert() {
+	let local = LocalSet::new();
+	local.run_until(run()).await;
+}
+
+async fn run() {
 	CONFIG.set(Config::default().log_level(LogLevel::Debug)).unwrap();
 
 	let engine = JSEngine::init().unwrap();
@@ -40,7 +50,11 @@ async fn assert() {
 
 	eval_module(&rt, rt.cx(), OK).await;
 	eval_module(&rt, rt.cx(), EQUALS).await;
+	eval_module(&rt, rt.cx(), EQUAL).await;
+	eval_module(&rt, rt.cx(), DEEP_EQUAL).await;
 	eval_module(&rt, rt.cx(), THROWS).await;
+	eval_module(&rt, rt.cx(), REJECTS).await;
+	eval_module(&rt, rt.cx(), MATCH).await;
 	eval_module(&rt, rt.cx(), FAIL).await;
 }
 
@@ -0,0 +1,48 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::path::Path;
+
+use mozjs::jsapi::PromiseState;
+use mozjs::rust::JSEngine;
+use mozjs::rust::Runtime as RustRuntime;
+use tokio::task::LocalSet;
+
+use ion::Context;
+use ion::module::Module;
+use modules::Bench;
+use runtime::config::{Config, CONFIG, LogLevel};
+use runtime::modules::Loader;
+use runtime::RuntimeBuilder;
+
+const FILE_NAME: &str = "bench.js";
+const SCRIPT: &str = include_str!("scripts/bench/bench.js");
+
+#[tokio::test]
+async fn bench() {
+	let local = LocalSet::new();
+	local.run_until(run()).await;
+}
+
+async fn run() {
+	CONFIG.set(Config::default().log_level(LogLevel::Debug)).unwrap();
+
+	let engine = JSEngine::init().unwrap();
+	let rt = RustRuntime::new(engine.handle());
+
+	let cx = &mut Context::from_runtime(&rt);
+	let rt = RuntimeBuilder::new()
+		.modules(Loader::default())
+		.standard_modules(Bench)
+		.microtask_queue()
+		.macrotask_queue()
+		.build(cx);
+
+	let path = format!("./tests/scripts/bench/{}", FILE_NAME);
+	let (_, promise) = Module::compile(rt.cx(), FILE_NAME, Some(Path::new(&path)), SCRIPT).unwrap();
+	assert!(rt.run_event_loop().await.is_ok());
+	assert_eq!(PromiseState::Fulfilled, promise.unwrap().state());
+}
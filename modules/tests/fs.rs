@@ -0,0 +1,59 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::path::Path;
+use std::{env, fs, process};
+
+use mozjs::jsapi::PromiseState;
+use mozjs::rust::JSEngine;
+use mozjs::rust::Runtime as RustRuntime;
+use tokio::task::LocalSet;
+
+use ion::{Context, Object};
+use ion::module::Module;
+use modules::FileSystem;
+use runtime::config::{Config, CONFIG, LogLevel};
+use runtime::modules::Loader;
+use runtime::RuntimeBuilder;
+
+const FILE_NAME: &str = "fs.js";
+const SCRIPT: &str = include_str!("scripts/fs/fs.js");
+
+#[tokio::test]
+async fn fs() {
+	let local = LocalSet::new();
+	local.run_until(run()).await;
+}
+
+async fn run() {
+	CONFIG.set(Config::default().log_level(LogLevel::Debug)).unwrap();
+
+	let directory = env::temp_dir().join(format!("spiderfire-fs-{}", process::id()));
+	fs::create_dir_all(&directory).unwrap();
+
+	let engine = JSEngine::init().unwrap();
+	let rt = RustRuntime::new(engine.handle());
+
+	let cx = &mut Context::from_runtime(&rt);
+	let rt = RuntimeBuilder::new()
+		.modules(Loader::default())
+		.standard_modules(FileSystem)
+		.microtask_queue()
+		.macrotask_queue()
+		.build(cx);
+	let mut global = Object::global(rt.cx());
+	global.set_as(rt.cx(), "directory", directory.to_str().unwrap());
+	global.set_as(rt.cx(), "windows", &cfg!(windows));
+
+	let path = format!("./tests/scripts/fs/{}", FILE_NAME);
+	let (_, promise) = Module::compile(rt.cx(), FILE_NAME, Some(Path::new(&path)), SCRIPT).unwrap();
+	let result = rt.run_event_loop().await;
+	let state = promise.unwrap().state();
+
+	fs::remove_dir_all(&directory).unwrap();
+	assert!(result.is_ok());
+	assert_eq!(PromiseState::Fulfilled, state);
+}
@@ -0,0 +1,54 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::fs::{read_to_string, remove_file};
+use std::path::Path;
+
+use mozjs::jsapi::PromiseState;
+use mozjs::rust::JSEngine;
+use mozjs::rust::Runtime as RustRuntime;
+
+use ion::Context;
+use ion::module::Module;
+use modules::Memory;
+use runtime::config::{Config, CONFIG, LogLevel};
+use runtime::modules::Loader;
+use runtime::RuntimeBuilder;
+
+const FILE_NAME: &str = "memory.js";
+const SCRIPT: &str = include_str!("scripts/memory/memory.js");
+const SNAPSHOT: &str = "./tests/scripts/memory/memory.heapsnapshot";
+
+#[tokio::test]
+async fn memory() {
+	CONFIG.set(Config::default().log_level(LogLevel::Debug)).unwrap();
+
+	let engine = JSEngine::init().unwrap();
+	let rt = RustRuntime::new(engine.handle());
+
+	let cx = &mut Context::from_runtime(&rt);
+	let rt = RuntimeBuilder::new()
+		.modules(Loader::default())
+		.standard_modules(Memory)
+		.microtask_queue()
+		.macrotask_queue()
+		.build(cx);
+
+	let path = format!("./tests/scripts/memory/{}", FILE_NAME);
+	let (_, promise) = Module::compile(rt.cx(), FILE_NAME, Some(Path::new(&path)), SCRIPT).unwrap();
+	assert!(rt.run_event_loop().await.is_ok());
+	assert_eq!(PromiseState::Fulfilled, promise.unwrap().state());
+
+	let snapshot = read_to_string(SNAPSHOT).unwrap();
+	remove_file(SNAPSHOT).unwrap();
+	assert!(snapshot.starts_with(r#"{"snapshot":{"meta":{"node_fields":["type","name","id","self_size","edge_count""#));
+	assert!(snapshot.contains(r#""Leaky""#), "Objects should be named by their constructor");
+	assert!(snapshot.contains(r#""leaks""#), "Properties of the global should be edges");
+	assert!(
+		snapshot.contains(r#""CapturedByClosure""#),
+		"Variables captured by closures should be reachable"
+	);
+}
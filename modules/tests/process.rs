@@ -0,0 +1,110 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::env;
+use std::path::Path;
+
+use mozjs::jsapi::PromiseState;
+use mozjs::rust::{JSEngine, JSEngineHandle};
+use mozjs::rust::Runtime as RustRuntime;
+
+use ion::Context;
+use ion::module::Module;
+use modules::Process;
+use runtime::config::{Config, CONFIG, LogLevel};
+use runtime::modules::Loader;
+use runtime::RuntimeBuilder;
+
+const FILE_NAME: &str = "process.js";
+const SCRIPT: &str = include_str!("scripts/process/process.js");
+
+const EXIT_FILE_NAME: &str = "exit.js";
+const EXIT_SCRIPT: &str = include_str!("scripts/process/exit.js");
+
+#[cfg(unix)]
+const SIGNAL_FILE_NAME: &str = "signal.js";
+#[cfg(unix)]
+const SIGNAL_SCRIPT: &str = include_str!("scripts/process/signal.js");
+
+#[tokio::test]
+async fn process() {
+	let args = vec![String::from("--flag"), String::from("value")];
+	CONFIG.set(Config::default().log_level(LogLevel::Debug).args(args)).unwrap();
+	env::set_var("SPIDERFIRE_PROCESS_TEST", "native");
+	env::set_var("SPIDERFIRE_PROCESS_NATIVE", "native");
+
+	let engine = JSEngine::init().unwrap();
+	run(engine.handle()).await;
+	exit(engine.handle()).await;
+	#[cfg(unix)]
+	signal(engine.handle()).await;
+}
+
+async fn run(engine: JSEngineHandle) {
+	let rt = RustRuntime::new(engine);
+
+	let cx = &mut Context::from_runtime(&rt);
+	let rt = RuntimeBuilder::new()
+		.modules(Loader::default())
+		.standard_modules(Process)
+		.microtask_queue()
+		.macrotask_queue()
+		.build(cx);
+
+	let path = format!("./tests/scripts/process/{}", FILE_NAME);
+	let (_, promise) = Module::compile(rt.cx(), FILE_NAME, Some(Path::new(&path)), SCRIPT).unwrap();
+	assert!(rt.run_event_loop().await.is_ok());
+	assert_eq!(PromiseState::Fulfilled, promise.unwrap().state());
+
+	// Changes made by scripts are held by the runtime, instead of modifying the environment of the process.
+	assert_eq!(env::var("SPIDERFIRE_PROCESS_TEST").ok().as_deref(), Some("native"));
+	assert_eq!(env::var("SPIDERFIRE_PROCESS_NATIVE").ok().as_deref(), Some("native"));
+	assert!(env::var_os("SPIDERFIRE_PROCESS_REMOVED").is_none());
+	rt.shutdown();
+}
+
+async fn exit(engine: JSEngineHandle) {
+	let rt = RustRuntime::new(engine);
+
+	let cx = &mut Context::from_runtime(&rt);
+	let rt = RuntimeBuilder::new()
+		.modules(Loader::default())
+		.standard_modules(Process)
+		.microtask_queue()
+		.macrotask_queue()
+		.build(cx);
+
+	let path = format!("./tests/scripts/process/{}", EXIT_FILE_NAME);
+	Module::compile(rt.cx(), EXIT_FILE_NAME, Some(Path::new(&path)), EXIT_SCRIPT).unwrap();
+	assert!(rt.run_event_loop().await.is_ok());
+	assert!(rt.has_exited());
+	assert_eq!(rt.exit_code(), 3);
+	rt.shutdown();
+}
+
+/// Signals without listeners stop the event loop with the exit code the process would have exited with by default.
+#[cfg(unix)]
+async fn signal(engine: JSEngineHandle) {
+	let rt = RustRuntime::new(engine);
+
+	let cx = &mut Context::from_runtime(&rt);
+	let rt = RuntimeBuilder::new()
+		.modules(Loader::default())
+		.standard_modules(Process)
+		.microtask_queue()
+		.macrotask_queue()
+		.build(cx);
+
+	let path = format!("./tests/scripts/process/{}", SIGNAL_FILE_NAME);
+	Module::compile(rt.cx(), SIGNAL_FILE_NAME, Some(Path::new(&path)), SIGNAL_SCRIPT).unwrap();
+	unsafe {
+		libc::kill(libc::getpid(), libc::SIGHUP);
+	}
+	assert!(rt.run_event_loop().await.is_ok());
+	assert!(rt.has_exited());
+	assert_eq!(rt.exit_code(), 128 + libc::SIGHUP);
+	rt.shutdown();
+}
@@ -0,0 +1,46 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::path::Path;
+
+use mozjs::jsapi::PromiseState;
+use mozjs::rust::JSEngine;
+use mozjs::rust::Runtime as RustRuntime;
+
+use ion::Context;
+use ion::module::Module;
+use modules::RuntimeM;
+use runtime::config::{Config, CONFIG, LogLevel};
+use runtime::diagnostics::init_tracing;
+use runtime::event_loop::handles::capture_handle_stacks;
+use runtime::modules::Loader;
+use runtime::RuntimeBuilder;
+
+const FILE_NAME: &str = "runtime.js";
+const SCRIPT: &str = include_str!("scripts/runtime/runtime.js");
+
+#[tokio::test]
+async fn runtime() {
+	CONFIG.set(Config::default().log_level(LogLevel::Debug)).unwrap();
+	init_tracing(None);
+
+	let engine = JSEngine::init().unwrap();
+	let rt = RustRuntime::new(engine.handle());
+
+	let cx = &mut Context::from_runtime(&rt);
+	let rt = RuntimeBuilder::new()
+		.modules(Loader::default())
+		.standard_modules(RuntimeM)
+		.microtask_queue()
+		.macrotask_queue()
+		.build(cx);
+	capture_handle_stacks(rt.cx(), true);
+
+	let path = format!("./tests/scripts/runtime/{}", FILE_NAME);
+	let (_, promise) = Module::compile(rt.cx(), FILE_NAME, Some(Path::new(&path)), SCRIPT).unwrap();
+	assert!(rt.run_event_loop().await.is_ok());
+	assert_eq!(PromiseState::Fulfilled, promise.unwrap().state());
+}
@@ -0,0 +1,39 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+import {deepEqual, notDeepEqual, ok} from "assert";
+
+deepEqual({ a: 1, b: [1, 2, { c: "3" }] }, { b: [1, 2, { c: "3" }], a: 1 });
+deepEqual(new Map([["a", { b: 1 }]]), new Map([["a", { b: 1 }]]));
+deepEqual(new Set([1, { a: 2 }]), new Set([{ a: 2 }, 1]));
+deepEqual(new Date(0), new Date(0));
+deepEqual(/a/g, /a/g);
+notDeepEqual({ a: 1 }, { a: "1" });
+notDeepEqual([1, 2], [1, 2, 3]);
+notDeepEqual(new Date(0), new Date(1));
+notDeepEqual({}, []);
+
+deepEqual(new Uint8Array([1, 2]), new Uint8Array([1, 2]));
+deepEqual(new DataView(new ArrayBuffer(2)), new DataView(new ArrayBuffer(2)));
+notDeepEqual(new ArrayBuffer(1), new ArrayBuffer(2));
+notDeepEqual(new Uint8Array([1]).buffer, new Uint8Array([2]).buffer);
+notDeepEqual(new DataView(new Uint8Array([1]).buffer), new DataView(new Uint8Array([2]).buffer));
+
+const first = { name: "first" };
+first.self = first;
+const second = { name: "first" };
+second.self = second;
+deepEqual(first, second);
+
+let message = "";
+try {
+	deepEqual({ a: 1, b: 2 }, { a: 1, b: 3 });
+} catch (error) {
+	message = error.message;
+}
+ok(message.includes("+ actual - expected") && message.includes("+   b: 2") && message.includes("-   b: 3"), "deepEqual should include a diff");
+
+deepEqual({ a: 1 }, { a: 2 }, "assert.deepEqual");
@@ -0,0 +1,24 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+import {equal, notEqual, notStrictEqual, ok, strictEqual} from "assert";
+
+equal(1, "1");
+equal(null, undefined);
+notEqual(1, 2);
+strictEqual(NaN, NaN);
+notStrictEqual(0, -0);
+notStrictEqual(1, "1");
+
+let message = "";
+try {
+	strictEqual(1, 2);
+} catch (error) {
+	message = error.message;
+}
+ok(message.includes("Expected values to be strictly equal") && message.includes("+ 1") && message.includes("- 2"), "strictEqual should include a diff");
+
+equal(1, 2, "assert.equal");
@@ -0,0 +1,23 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+import {ok, rejects} from "assert";
+
+await rejects(Promise.reject(new TypeError("Rejected")));
+await rejects(async () => {
+	throw new RangeError("Out of range");
+}, RangeError);
+await rejects(Promise.reject(new Error("Rejected")), /Rejected/);
+
+let failed = false;
+try {
+	await rejects(Promise.reject(new Error("Rejected")), TypeError);
+} catch (error) {
+	failed = error.name === "AssertionError";
+}
+ok(failed, "rejects should fail if the reason does not match");
+
+await rejects(Promise.resolve(), "assert.rejects");
@@ -4,10 +4,24 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/.
  */
 
-import {throws} from "assert";
+import {ok, throws} from "assert";
 
 function thrower() {
-	throw new Error();
+	throw new TypeError("Invalid value");
 }
 
-throws(thrower, "assert.throws");
+throws(thrower);
+throws(thrower, TypeError);
+throws(thrower, /Invalid value/);
+throws(thrower, { name: "TypeError", message: /value/ });
+throws(thrower, error => error.message === "Invalid value");
+
+let mismatched = false;
+try {
+	throws(thrower, RangeError);
+} catch (error) {
+	mismatched = error.name === "AssertionError";
+}
+ok(mismatched, "throws should fail if the error does not match");
+
+throws(() => {}, "assert.throws");
@@ -0,0 +1,47 @@
+import benchModule, { bench, run } from "bench";
+
+function check(condition, message) {
+	if (!condition) {
+		throw new Error(message);
+	}
+}
+
+check(benchModule.bench === bench && benchModule.run === run, "Default export should contain bench and run");
+
+let calls = 0;
+bench("sum", () => {
+	calls++;
+	let sum = 0;
+	for (let i = 0; i < 1000; i++) {
+		sum += i;
+	}
+	return sum;
+}, { warmup: 2, iterations: 20 });
+
+bench("async", async () => {
+	await Promise.resolve();
+}, { warmup: 0, iterations: 5 });
+
+bench("throws", () => {
+	throw new Error("Expected failure");
+}, { warmup: 0 });
+
+bench("skipped", () => {}, { skip: true });
+
+const results = await run();
+check(results.length === 4, "Every benchmark should have a result");
+
+const [sum, async, throws, skipped] = results;
+check(calls === 22, "Benchmarks should run their warmup and measured iterations");
+check(sum.status === "completed" && sum.iterations === 20, "Completed benchmarks should report their iterations");
+check(sum.min <= sum.p75 && sum.p75 <= sum.p99 && sum.p99 <= sum.max, "Percentiles should be ordered");
+check(sum.mean >= sum.min && sum.mean <= sum.max && sum.stddev >= 0, "Mean should be within the range of samples");
+check(async.status === "completed" && async.iterations === 5, "Async benchmarks should be awaited");
+check(throws.status === "failed" && throws.error.includes("Expected failure"), "Failed benchmarks should report their errors");
+check(skipped.status === "skipped", "Skipped benchmarks should not run");
+
+bench("filtered in", () => {}, { iterations: 1 });
+bench("filtered out", () => {}, { iterations: 1 });
+
+const filtered = await run({ filter: "in" });
+check(filtered.length === 1 && filtered[0].name === "filtered in", "Filters should select benchmarks by name");
@@ -0,0 +1,61 @@
+import crypto, { createHash, createHmac, timingSafeEqual, Hash } from "crypto";
+
+function check(condition, message) {
+	if (!condition) {
+		throw new Error(message);
+	}
+}
+
+check(crypto.createHash === createHash && crypto.Hash === Hash, "Default export should contain createHash and Hash");
+
+const digests = {
+	md5: "900150983cd24fb0d6963f7d28e17f72",
+	sha1: "a9993e364706816aba3e25717850c26c9cd0d89d",
+	sha256: "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+	"sha3-256": "3a985da74fe225b2045c172d6bd390bd855f086e3e9d525b46bfe24511431532",
+	sha512:
+		"ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f",
+};
+for (const [algorithm, digest] of Object.entries(digests)) {
+	check(createHash(algorithm).update("abc").digest("hex") === digest, `${algorithm} digest of "abc" is incorrect`);
+}
+
+const hash = createHash("SHA-256");
+check(hash instanceof Hash && hash.algorithm === "sha256", "Algorithm names should be normalised");
+check(hash.update("a").update(new TextEncoder().encode("b")) === hash, "update should return the hash");
+const copy = hash.copy();
+hash.update(new TextEncoder().encode("c").buffer);
+check(hash.digest("base64") === "ungWv48Bz+pBQUDeXa4iI7ADYaOWF3qctBD/YfIAFa0=", "Chunks should be hashed incrementally");
+check(copy.update("c").digest("hex") === digests.sha256, "Copies should be updated independently");
+
+const bytes = createHash("sha1").update("abc").digest();
+check(bytes instanceof Uint8Array && bytes.length === 20, "Digests without an encoding should be bytes");
+
+let finished = false;
+try {
+	hash.update("d");
+} catch (error) {
+	finished = error.name === "InvalidStateError";
+}
+check(finished, "Hashes should not be updated after their digest is computed");
+
+const hmac = createHmac("sha256", "key").update("The quick brown fox jumps over the lazy dog").digest("hex");
+check(hmac === "f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8", "HMAC digest is incorrect");
+
+let unsupported = false;
+try {
+	createHash("whirlpool");
+} catch (error) {
+	unsupported = error instanceof TypeError;
+}
+check(unsupported, "Unsupported algorithms should throw a TypeError");
+
+check(timingSafeEqual(new Uint8Array([1, 2, 3]), new Uint8Array([1, 2, 3])), "Equal buffers should be equal");
+check(!timingSafeEqual(new Uint8Array([1, 2, 3]), new Uint8Array([1, 2, 4])), "Different buffers should not be equal");
+let lengths = false;
+try {
+	timingSafeEqual(new Uint8Array(1), new Uint8Array(2));
+} catch (error) {
+	lengths = error instanceof RangeError;
+}
+check(lengths, "Buffers of different lengths should throw a RangeError");
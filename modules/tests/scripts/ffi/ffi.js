@@ -0,0 +1,52 @@
+import ffi, { dlopen, DynamicLibrary } from "ffi";
+
+function check(condition, message) {
+	if (!condition) {
+		throw new Error(message);
+	}
+}
+
+function throws(callback, name) {
+	try {
+		callback();
+	} catch (error) {
+		return error.name === name;
+	}
+	return false;
+}
+
+check(ffi.dlopen === dlopen && ffi.DynamicLibrary === DynamicLibrary, "Default export should contain dlopen and DynamicLibrary");
+check(["so", "dylib"].includes(ffi.suffix), "suffix should be the extension of shared libraries");
+
+const libc = dlopen(null, {
+	abs: { parameters: ["i32"], result: "i32" },
+	strlen: { parameters: ["string"], result: "usize" },
+	memcpy: { parameters: ["buffer", "buffer", "usize"], result: "pointer" },
+	getenv: { parameters: ["string"], result: "string" },
+	setenv: { parameters: ["string", "string", "i32"], result: "i32" },
+});
+check(libc instanceof DynamicLibrary && libc.path === null, "dlopen should return a DynamicLibrary");
+
+const { abs, strlen, memcpy, getenv, setenv } = libc.symbols;
+check(abs(-42) === 42, "Numbers should be converted to integers");
+check(strlen("spiderfire") === 10n, "Strings should be passed as C strings, and usize returned as BigInt");
+check(throws(() => strlen("a\0b"), "TypeError"), "Strings with null characters should be rejected");
+check(throws(() => abs(), "TypeError"), "Missing arguments should be rejected");
+check(throws(() => abs(2 ** 32), "TypeError"), "Integers out of range should be rejected");
+
+const source = new Uint8Array([1, 2, 3, 4]);
+const destination = new Uint8Array(4);
+const pointer = memcpy(destination, source, 4);
+check(typeof pointer === "bigint" && pointer !== 0n, "Pointers should be returned as BigInt");
+check(destination.join() === "1,2,3,4", "Buffers should be passed as pointers to their contents");
+
+check(setenv("SPIDERFIRE_FFI", "native", 1) === 0, "setenv should succeed");
+check(getenv("SPIDERFIRE_FFI") === "native", "String results should be converted to strings");
+check(getenv("SPIDERFIRE_FFI_MISSING") === null, "Null string results should be converted to null");
+
+check(throws(() => dlopen(null, { missing_symbol_for_ffi: {} }), "TypeError"), "Missing symbols should be rejected");
+check(throws(() => dlopen(null, { abs: { parameters: ["i128"] } }), "TypeError"), "Unknown types should be rejected");
+check(throws(() => dlopen(`missing.${ffi.suffix}`, {}), "Error"), "Missing libraries should be rejected");
+
+libc.close();
+check(throws(() => abs(-1), "InvalidStateError"), "Symbols of closed libraries should throw");
@@ -0,0 +1,115 @@
+import fs, { readFile, readTextFile, writeFile, open, stat, readDir, mkdir, rm, rename, copyFile, symlink, readlink } from "fs";
+
+function check(condition, message) {
+	if (!condition) {
+		throw new Error(message);
+	}
+}
+
+async function rejects(promise, code, message) {
+	try {
+		await promise;
+	} catch (error) {
+		check(error.code === code, `${message}: expected ${code}, found ${error.code}`);
+		return;
+	}
+	throw new Error(`${message}: expected rejection`);
+}
+
+const file = `${directory}/file.txt`;
+
+await writeFile(file, "Hello");
+await writeFile(file, new Uint8Array([44, 32]), { append: true });
+await writeFile(file, new TextEncoder().encode("World"), { append: true });
+check(await readTextFile(file) === "Hello, World", "writeFile should write strings and buffers");
+
+const bytes = await readFile(file);
+check(bytes instanceof Uint8Array && bytes.length === 12, "readFile should resolve with a Uint8Array");
+
+await rejects(writeFile(file, "", { createNew: true }), "EEXIST", "createNew should fail for existing files");
+await rejects(readFile(`${directory}/missing.txt`), "ENOENT", "Reading missing files should fail");
+
+const info = await stat(file);
+check(info.isFile && !info.isDirectory && info.size === 12, "stat should describe files");
+check(info.modified instanceof Date, "stat should include the modification time");
+
+const handle = await open(file, { read: true, write: true });
+check(handle instanceof fs.FileHandle, "open should resolve with a FileHandle");
+check(handle.path === file, "FileHandle should expose its path");
+
+const start = await handle.read(5);
+check(new TextDecoder().decode(start) === "Hello", "FileHandle.read should read from the start");
+check(await handle.seek(-5, "end") === 7, "FileHandle.seek should seek from the end");
+check(await handle.write("Earth") === 5, "FileHandle.write should resolve with the bytes written");
+check(await handle.read() === null, "FileHandle.read should resolve with null at the end of the file");
+await handle.sync();
+check((await handle.stat()).size === 12, "FileHandle.stat should describe the file");
+await handle.truncate(5);
+await handle.close();
+await rejects(handle.read(), undefined, "Closed handles should reject");
+check(await readTextFile(file) === "Hello", "FileHandle.truncate should truncate the file");
+
+await mkdir(`${directory}/nested/deeper`, { recursive: true });
+await rejects(mkdir(`${directory}/nested`), "EEXIST", "mkdir should fail for existing directories");
+await copyFile(file, `${directory}/nested/copy.txt`);
+await rename(`${directory}/nested/copy.txt`, `${directory}/nested/moved.txt`);
+
+const names = [];
+for await (const entry of await readDir(`${directory}/nested`)) {
+	names.push(`${entry.name}:${entry.isDirectory ? "directory" : "file"}`);
+}
+names.sort();
+check(names.join() === "deeper:directory,moved.txt:file", `readDir should iterate over entries: ${names.join()}`);
+
+if (!windows) {
+	await symlink(file, `${directory}/link.txt`);
+	check(await readlink(`${directory}/link.txt`) === file, "readlink should read symbolic links");
+	check((await fs.lstat(`${directory}/link.txt`)).isSymlink, "lstat should not follow symbolic links");
+}
+
+await rm(`${directory}/nested`).then(() => {
+	throw new Error("rm should not remove directories which are not empty");
+}, () => {});
+await rm(`${directory}/nested`, { recursive: true });
+await rejects(stat(`${directory}/nested`), "ENOENT", "rm should remove directories recursively");
+
+const syncDirectory = `${directory}/sync`;
+fs.mkdirSync(`${syncDirectory}/nested`, { recursive: true });
+fs.writeFileSync(`${syncDirectory}/file.txt`, "Sync");
+fs.writeFileSync(`${syncDirectory}/file.txt`, new Uint8Array([33]), { append: true });
+check(fs.readTextFileSync(`${syncDirectory}/file.txt`) === "Sync!", "writeFileSync should write strings and buffers");
+check(fs.readFileSync(`${syncDirectory}/file.txt`).length === 5, "readFileSync should return a Uint8Array");
+check(fs.statSync(`${syncDirectory}/nested`).isDirectory, "statSync should describe directories");
+
+const syncNames = fs.readDirSync(syncDirectory).map(entry => entry.name).sort();
+check(syncNames.join() === "file.txt,nested", `readDirSync should return entries: ${syncNames.join()}`);
+
+let code;
+try {
+	fs.readFileSync(`${syncDirectory}/missing.txt`);
+} catch (error) {
+	code = error.code;
+}
+check(code === "ENOENT", "Sync functions should throw the same errors as async functions");
+
+fs.rmSync(syncDirectory, { recursive: true });
+check(!fs.readDirSync(directory).some(entry => entry.name === "sync"), "rmSync should remove directories recursively");
+
+const large = await open(file);
+const contents = await large.read(0xFFFFFFFF);
+check(contents.length === 5, "FileHandle.read should only read the rest of the file");
+let seekError;
+try {
+	await large.seek(-1);
+} catch (error) {
+	seekError = error;
+}
+check(seekError instanceof RangeError, "FileHandle.seek should throw a RangeError for negative offsets from the start");
+await large.close();
+
+const legacy = `${directory}/legacy.txt`;
+check(await fs.write(legacy, "Legacy") === true, "write should resolve with true when it succeeds");
+check(await fs.readString(legacy) === "Legacy", "readString should read text files");
+check(await fs.createDir(`${directory}/missing/legacy`) === false, "createDir should resolve with false when it fails");
+check(fs.sync.readString(legacy) === "Legacy", "sync.readString should read text files");
+check(fs.sync.removeFile(legacy) && !fs.sync.removeFile(legacy), "sync.removeFile should return whether it succeeded");
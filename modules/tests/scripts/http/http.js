@@ -0,0 +1,143 @@
+import http, { serve, upgradeWebSocket, Server, WebSocket } from "http";
+import { connect } from "net";
+
+function check(condition, message) {
+	if (!condition) {
+		throw new Error(message);
+	}
+}
+
+check(http.Server === Server && http.WebSocket === WebSocket, "Default export should contain Server and WebSocket");
+
+const encoder = new TextEncoder();
+const decoder = new TextDecoder();
+
+const server = serve(async request => {
+	const url = new URL(request.url);
+	switch (url.pathname) {
+		case "/echo":
+			return new Response(`${request.method} ${url.searchParams.get("name")}: ${await request.text()}`, {
+				headers: { "X-Echo": request.headers.get("X-Request") ?? "" },
+			});
+		case "/stream": {
+			const chunks = ["first ", "second"];
+			const body = new ReadableStream({
+				pull(controller) {
+					const chunk = chunks.shift();
+					if (chunk === undefined) {
+						controller.close();
+					} else {
+						controller.enqueue(encoder.encode(chunk));
+					}
+				},
+			});
+			return new Response(body);
+		}
+		case "/throw":
+			throw new Error("Handler Error");
+		default:
+			return new Response(null, { status: 404 });
+	}
+}, { port: 0, hostname: "127.0.0.1" });
+
+check(server instanceof Server, "serve should return a Server");
+check(server.addr.hostname === "127.0.0.1" && server.addr.port > 0, "Server should be bound to a port");
+
+const base = `http://127.0.0.1:${server.addr.port}`;
+
+const echo = await fetch(`${base}/echo?name=test`, {
+	method: "POST",
+	body: "hello",
+	headers: { "X-Request": "header" },
+});
+check(echo.status === 200, "Response should have a status of 200");
+check(echo.headers.get("X-Echo") === "header", "Request headers should be received by the handler");
+check((await echo.text()) === "POST test: hello", "Request body should be received by the handler");
+
+const stream = await fetch(`${base}/stream`);
+check((await stream.text()) === "first second", "Streamed response body should be received");
+
+const original = await fetch(`${base}/stream`);
+const clone = original.clone();
+check(clone.status === original.status && clone !== original, "Responses should be cloned");
+const [originalText, cloneText] = await Promise.all([original.text(), clone.text()]);
+check(originalText === "first second" && cloneText === "first second", "Cloned responses should both read the body");
+
+const request = new Request(`${base}/echo?name=clone`, { method: "POST", body: "cloned" });
+const requestClone = request.clone();
+check((await request.text()) === "cloned" && (await requestClone.text()) === "cloned", "Cloned requests should both read the body");
+
+const missing = await fetch(`${base}/missing`);
+check(missing.status === 404, "Response status should be sent");
+
+const error = await fetch(`${base}/throw`);
+check(error.status === 500, "Handler errors should respond with a status of 500");
+
+await server.shutdown();
+
+let refused = false;
+try {
+	await fetch(`${base}/echo`);
+} catch {
+	refused = true;
+}
+check(refused, "Server should not accept connections after shutting down");
+
+const controller = new AbortController();
+const aborted = serve(() => new Response("aborted"), { port: 0, hostname: "127.0.0.1", signal: controller.signal });
+controller.abort();
+await aborted.finished;
+
+let closed;
+const socketClosed = new Promise(resolve => (closed = resolve));
+const sockets = serve(request => {
+	const { socket, response } = upgradeWebSocket(request, { protocol: "echo" });
+	check(socket instanceof WebSocket, "upgradeWebSocket should return a WebSocket");
+	check(socket.readyState === WebSocket.CONNECTING, "WebSocket should be connecting until the upgrade completes");
+	socket.onmessage = event => socket.send(`echo: ${event.data}`);
+	socket.onclose = closed;
+	return response;
+}, { port: 0, hostname: "127.0.0.1" });
+
+const conn = await connect("127.0.0.1", sockets.addr.port);
+const reader = conn.readable.getReader();
+const writer = conn.writable.getWriter();
+
+await writer.write(encoder.encode([
+	"GET /socket HTTP/1.1",
+	`Host: 127.0.0.1:${sockets.addr.port}`,
+	"Upgrade: websocket",
+	"Connection: Upgrade",
+	"Sec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==",
+	"Sec-WebSocket-Version: 13",
+	"",
+	"",
+].join("\r\n")));
+
+let handshake = "";
+while (!handshake.includes("\r\n\r\n")) {
+	handshake += decoder.decode((await reader.read()).value);
+}
+check(handshake.startsWith("HTTP/1.1 101"), "Server should switch protocols");
+check(handshake.includes("s3pPLMBiTxaQ9kYGzzhZRbK+xOo="), "Server should send the accept key");
+check(handshake.toLowerCase().includes("sec-websocket-protocol: echo"), "Server should send the selected protocol");
+
+function frame(opcode, payload) {
+	const mask = [1, 2, 3, 4];
+	const bytes = new Uint8Array(6 + payload.length);
+	bytes.set([0x80 | opcode, 0x80 | payload.length, ...mask]);
+	payload.forEach((byte, i) => (bytes[6 + i] = byte ^ mask[i % 4]));
+	return bytes;
+}
+
+await writer.write(frame(0x1, [...encoder.encode("hello")]));
+const { value: message } = await reader.read();
+check(message[0] === 0x81, "Server should send a text frame");
+check(decoder.decode(message.subarray(2, 2 + message[1])) === "echo: hello", "Messages should be received and sent");
+
+await writer.write(frame(0x8, [0x03, 0xE8]));
+const close = await socketClosed;
+check(close.wasClean && close.code === 1000, "Close event should be fired with the status code of the client");
+
+await conn.close();
+await sockets.shutdown();
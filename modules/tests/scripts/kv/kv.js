@@ -0,0 +1,118 @@
+import kv, { open, Store, AtomicOperation } from "kv";
+
+function check(condition, message) {
+	if (!condition) {
+		throw new Error(message);
+	}
+}
+
+async function rejects(promise) {
+	try {
+		await promise;
+	} catch (error) {
+		return error;
+	}
+	return null;
+}
+
+check(kv.open === open && kv.Store === Store, "Default export should contain open and Store");
+
+const store = await open(":memory:");
+check(store instanceof Store && store.path === ":memory:", "open should resolve with a Store");
+
+const missing = await store.get(["users", "missing"]);
+check(missing.value === null && missing.versionstamp === null, "Missing entries should have null values and versionstamps");
+check(missing.key[0] === "users" && missing.key[1] === "missing", "Entries should have their keys");
+
+const result = await store.set(["users", "alice"], { name: "Alice", tags: new Set(["admin"]), joined: new Date(0) });
+check(result.ok && /^[0-9a-f]{20}$/.test(result.versionstamp), "set should resolve with a versionstamp");
+
+const alice = await store.get(["users", "alice"]);
+check(alice.value.name === "Alice" && alice.value.tags.has("admin"), "Values should be structured clones");
+check(alice.value.joined instanceof Date && alice.value.joined.getTime() === 0, "Dates should be preserved");
+check(alice.versionstamp === result.versionstamp, "Entries should have the versionstamp of their last commit");
+
+await store.set(["users", "bob"], "Bob");
+await store.set(["users", "carol"], "Carol");
+await store.set(["users", 1n], "BigInt");
+await store.set(["users", 2], "Number");
+await store.set(["usersx"], "Outside");
+await store.set(["bytes", new Uint8Array([0, 1])], new Uint8Array([1, 2, 3]));
+
+const users = await store.list({ prefix: ["users"] });
+check(
+	users.map(entry => entry.value instanceof Object ? entry.value.name : entry.value).join() === "Alice,Bob,Carol,BigInt,Number",
+	"list should return the entries with the prefix, ordered by strings, bigints and then numbers",
+);
+check(users[3].key[1] === 1n && users[4].key[1] === 2, "Listed keys should be decoded");
+
+const reversed = await store.list({ prefix: ["users"] }, { reverse: true, limit: 2 });
+check(reversed.length === 2 && reversed[0].value === "Number" && reversed[1].value === "BigInt", "list should support reverse and limit");
+
+const range = await store.list({ start: ["users", "bob"], end: ["users", "carol"] });
+check(range.length === 1 && range[0].value === "Bob", "list should select keys from start until end");
+
+const fromBob = await store.list({ prefix: ["users"], start: ["users", "bob"] });
+check(fromBob.length === 4 && fromBob[0].value === "Bob", "list should start from the start key within the prefix");
+
+const bytes = await store.get(["bytes", new Uint8Array([0, 1])]);
+check(bytes.value instanceof Uint8Array && bytes.value[2] === 3, "Uint8Arrays should be valid key parts and values");
+
+await store.delete(["users", "bob"]);
+check((await store.get(["users", "bob"])).value === null, "delete should remove an entry");
+
+const bob = await store.get(["users", "bob"]);
+const committed = await store
+	.atomic()
+	.check({ key: ["users", "bob"], versionstamp: null })
+	.check(alice)
+	.set(["users", "bob"], "Bob")
+	.delete(["users", "carol"])
+	.commit();
+check(committed.ok, "Atomic operations should commit when their checks pass");
+check(bob.versionstamp === null && (await store.get(["users", "bob"])).value === "Bob", "Atomic operations should apply sets");
+check((await store.get(["users", "carol"])).value === null, "Atomic operations should apply deletes");
+
+const stale = await store.atomic().check(bob).set(["users", "bob"], "Robert").commit();
+check(!stale.ok && stale.versionstamp === undefined, "Atomic operations should fail when a check fails");
+check((await store.get(["users", "bob"])).value === "Bob", "Failed atomic operations should not apply mutations");
+
+const operation = store.atomic();
+await operation.commit();
+let committedTwice = false;
+try {
+	operation.commit();
+} catch (error) {
+	committedTwice = error.name === "InvalidStateError";
+}
+check(committedTwice, "Atomic operations cannot be committed twice");
+
+let empty = false;
+try {
+	store.set([], 1);
+} catch (error) {
+	empty = error instanceof TypeError;
+}
+check(empty, "Entries cannot have empty keys");
+
+let uncloneable = false;
+try {
+	store.set(["function"], () => {});
+} catch {
+	uncloneable = true;
+}
+check(uncloneable, "Values which cannot be cloned should be rejected");
+
+let selector = false;
+try {
+	store.list({ start: ["users"] });
+} catch (error) {
+	selector = error instanceof TypeError;
+}
+check(selector, "Selectors need a prefix, or a start and an end");
+
+check(AtomicOperation !== undefined, "AtomicOperation should be exported");
+
+await store.close();
+const closed = await rejects(store.get(["users", "alice"]));
+check(closed !== null && closed.message === "Database is closed", "Operations should reject once the store is closed");
@@ -0,0 +1,36 @@
+import memory, { takeHeapSnapshot, usage } from "memory";
+
+function check(condition, message) {
+	if (!condition) {
+		throw new Error(message);
+	}
+}
+
+check(memory.usage === usage && memory.takeHeapSnapshot === takeHeapSnapshot, "Default export should contain usage and takeHeapSnapshot");
+
+const before = usage();
+for (const key of ["heapUsed", "heapTotal", "rss"]) {
+	check(typeof before[key] === "number" && before[key] >= 0, `${key} should be a number of bytes`);
+}
+check(before.heapUsed > 0 && before.heapUsed <= before.heapTotal, "heapUsed should be positive and at most heapTotal");
+
+class Leaky {
+	constructor(id) {
+		this.id = id;
+		this.payload = new Array(64).fill(id);
+	}
+}
+
+globalThis.leaks = new Map();
+for (let i = 0; i < 10000; i++) {
+	leaks.set(i, new Leaky(i));
+}
+check(usage().heapUsed > before.heapUsed, "heapUsed should grow with allocations");
+
+function retain() {
+	const captured = "CapturedByClosure";
+	return () => captured;
+}
+globalThis.retained = retain();
+
+takeHeapSnapshot("./tests/scripts/memory/memory.heapsnapshot");
@@ -0,0 +1,101 @@
+import net, { connect, listen, listenDatagram, Conn, DatagramSocket, Listener } from "net";
+
+function check(condition, message) {
+	if (!condition) {
+		throw new Error(message);
+	}
+}
+
+const encoder = new TextEncoder();
+const decoder = new TextDecoder();
+
+async function read(conn) {
+	const reader = conn.readable.getReader();
+	const { value } = await reader.read();
+	reader.releaseLock();
+	return decoder.decode(value);
+}
+
+async function write(conn, text) {
+	const writer = conn.writable.getWriter();
+	await writer.write(encoder.encode(text));
+	writer.releaseLock();
+}
+
+check(net.Conn === Conn && net.Listener === Listener, "Default export should contain Conn and Listener");
+
+const listener = listen(0, { hostname: "127.0.0.1" });
+check(listener instanceof Listener, "listen should return a Listener");
+check(listener.addr.hostname === "127.0.0.1" && listener.addr.port > 0, "Listener should be bound to a port");
+
+const server = (async () => {
+	for await (const conn of listener) {
+		await write(conn, `echo: ${await read(conn)}`);
+		await conn.close();
+	}
+})();
+
+const client = await connect("127.0.0.1", listener.addr.port);
+check(client instanceof Conn, "connect should resolve with a Conn");
+check(client.remoteAddr.port === listener.addr.port, "remoteAddr should be the address of the listener");
+check(client.localAddr.family === "IPv4", "localAddr should be an IPv4 address");
+
+await write(client, "hello");
+check((await read(client)) === "echo: hello", "Data should be echoed by the server");
+const reader = client.readable.getReader();
+check((await reader.read()).done, "Stream should end when the server closes the connection");
+await client.close();
+
+listener.close();
+await server;
+check((await listener.accept()) === null, "accept should resolve with null once the listener is closed");
+
+let refused = false;
+try {
+	await connect("127.0.0.1", listener.addr.port);
+} catch (error) {
+	refused = error.code === "ECONNREFUSED";
+}
+check(refused, "Connecting to a closed listener should be refused");
+
+const controller = new AbortController();
+const aborted = listen(0, { hostname: "127.0.0.1", signal: controller.signal });
+const accept = aborted.accept();
+controller.abort();
+check((await accept) === null, "Aborting the signal should close the listener");
+
+const reason = new Error("Aborted");
+try {
+	await connect("127.0.0.1", 9, { signal: AbortSignal.abort(reason) });
+	check(false, "connect should reject with an aborted signal");
+} catch (error) {
+	check(error === reason, "connect should reject with the reason of the signal");
+}
+
+const receiver = listenDatagram(0, { hostname: "127.0.0.1" });
+const sender = listenDatagram(0, { hostname: "127.0.0.1", broadcast: true });
+check(receiver instanceof DatagramSocket, "listenDatagram should return a DatagramSocket");
+
+const sent = await sender.send(encoder.encode("ping"), "127.0.0.1", receiver.addr.port);
+check(sent === 4, "send should resolve with the number of bytes sent");
+const datagram = await receiver.receive();
+check(decoder.decode(datagram.data) === "ping", "Datagram should be received");
+check(datagram.addr.port === sender.addr.port, "Datagram should be from the address of the sender");
+
+const iterated = (async () => {
+	for await (const { data } of receiver) {
+		return decoder.decode(data);
+	}
+})();
+await sender.send(encoder.encode("pong"), "127.0.0.1", receiver.addr.port);
+check((await iterated) === "pong", "Datagrams should be yielded when iterating");
+
+sender.close();
+receiver.close();
+check((await receiver.receive()) === null, "receive should resolve with null once the socket is closed");
+
+const datagramController = new AbortController();
+const abortedSocket = listenDatagram(0, { hostname: "127.0.0.1", signal: datagramController.signal });
+const receive = abortedSocket.receive();
+datagramController.abort();
+check((await receive) === null, "Aborting the signal should close the socket");
@@ -0,0 +1,37 @@
+import os, { cpus, EOL, freemem, networkInterfaces, tmpdir, totalmem, uptime } from "os";
+
+function check(condition, message) {
+	if (!condition) {
+		throw new Error(message);
+	}
+}
+
+check(typeof os.platform === "string" && os.platform.length > 0, "platform should be a string");
+check(typeof os.arch === "string" && os.arch.length > 0, "arch should be a string");
+check(EOL === "\n" || EOL === "\r\n", "EOL should be a line ending");
+
+const hostname = os.hostname();
+check(hostname === null || typeof hostname === "string", "hostname should be a string or null");
+
+const processors = cpus();
+check(Array.isArray(processors), "cpus should return an array");
+for (const cpu of processors) {
+	check(typeof cpu.model === "string", "cpu.model should be a string");
+	check(typeof cpu.speed === "number", "cpu.speed should be a number");
+	if (cpu.times !== null) {
+		check(cpu.times.idle >= 0 && cpu.times.user >= 0, "cpu.times should not be negative");
+	}
+}
+
+check(totalmem() > 0, "totalmem should be positive");
+check(freemem() >= 0 && freemem() <= totalmem(), "freemem should be at most totalmem");
+check(uptime() >= 0, "uptime should not be negative");
+check(typeof tmpdir() === "string" && tmpdir().length > 0, "tmpdir should be a path");
+
+const interfaces = networkInterfaces();
+for (const name in interfaces) {
+	for (const address of interfaces[name]) {
+		check(address.family === "IPv4" || address.family === "IPv6", "family should be IPv4 or IPv6");
+		check(address.cidr.startsWith(address.address), "cidr should contain the address");
+	}
+}
@@ -0,0 +1,77 @@
+import path, { posix, win32 } from "path";
+
+function check(actual, expected, message) {
+	if (actual !== expected) {
+		throw new Error(`${message}: expected ${JSON.stringify(expected)}, received ${JSON.stringify(actual)}`);
+	}
+}
+
+check(posix.separator, "/", "posix.separator");
+check(posix.delimiter, ":", "posix.delimiter");
+check(win32.separator, "\\", "win32.separator");
+check(win32.delimiter, ";", "win32.delimiter");
+check(path.separator === posix.separator || path.separator === win32.separator, true, "separator should match a flavour");
+
+check(posix.join("/foo", "bar", "baz/asdf", "quux", ".."), "/foo/bar/baz/asdf", "posix.join");
+check(posix.join("", ""), ".", "posix.join with empty segments");
+check(posix.join("foo/", "bar/"), "foo/bar/", "posix.join with trailing separator");
+check(win32.join("C:\\foo", "bar", "..\\baz"), "C:\\foo\\baz", "win32.join");
+check(win32.join("C:", "foo"), "C:\\foo", "win32.join with drive");
+
+check(posix.normalize("/foo/bar//baz/asdf/quux/.."), "/foo/bar/baz/asdf", "posix.normalize");
+check(posix.normalize("../foo/./../../bar"), "../../bar", "posix.normalize with leading parents");
+check(posix.normalize("/.."), "/", "posix.normalize above root");
+check(posix.normalize(""), ".", "posix.normalize with empty path");
+check(win32.normalize("C:/temp\\\\foo\\bar\\..\\"), "C:\\temp\\foo\\", "win32.normalize");
+check(win32.normalize("\\\\server\\share\\dir\\..\\file"), "\\\\server\\share\\file", "win32.normalize with UNC path");
+
+check(posix.resolve("/foo/bar", "./baz"), "/foo/bar/baz", "posix.resolve");
+check(posix.resolve("/foo/bar", "/tmp/file/"), "/tmp/file", "posix.resolve with absolute segment");
+check(path.isAbsolute(path.resolve("relative")), true, "resolve should resolve relative paths against the current directory");
+check(win32.resolve("C:\\foo", "bar", "D:\\baz"), "D:\\baz", "win32.resolve with drive");
+check(win32.resolve("C:\\foo\\", "..\\bar"), "C:\\bar", "win32.resolve");
+check(win32.resolve("D:\\foo", "\\bar"), "D:\\bar", "win32.resolve with rooted path");
+
+check(posix.relative("/data/orandea/test/aaa", "/data/orandea/impl/bbb"), "../../impl/bbb", "posix.relative");
+check(posix.relative("/data", "/data"), "", "posix.relative with equal paths");
+check(win32.relative("C:\\orandea\\test\\aaa", "C:\\orandea\\impl\\bbb"), "..\\..\\impl\\bbb", "win32.relative");
+check(win32.relative("C:\\Foo\\Bar", "c:\\foo\\bar\\baz"), "baz", "win32.relative should ignore case");
+
+check(posix.dirname("/foo/bar/baz/asdf/quux"), "/foo/bar/baz/asdf", "posix.dirname");
+check(posix.dirname("/foo"), "/", "posix.dirname of root child");
+check(posix.dirname("foo"), ".", "posix.dirname of relative path");
+check(win32.dirname("C:\\foo\\bar"), "C:\\foo", "win32.dirname");
+
+check(posix.basename("/foo/bar/baz/asdf/quux.html"), "quux.html", "posix.basename");
+check(posix.basename("/foo/bar/baz/asdf/quux.html", ".html"), "quux", "posix.basename with extension");
+check(posix.basename("/foo/bar/"), "bar", "posix.basename with trailing separator");
+check(win32.basename("C:\\foo.html", ".html"), "foo", "win32.basename");
+
+check(posix.extname("index.html"), ".html", "posix.extname");
+check(posix.extname("index.coffee.md"), ".md", "posix.extname with multiple extensions");
+check(posix.extname("index."), ".", "posix.extname with trailing dot");
+check(posix.extname("index"), "", "posix.extname without extension");
+check(posix.extname(".index"), "", "posix.extname with leading dot");
+
+check(posix.isAbsolute("/foo/bar"), true, "posix.isAbsolute");
+check(posix.isAbsolute("qux/"), false, "posix.isAbsolute with relative path");
+check(win32.isAbsolute("C:\\foo"), true, "win32.isAbsolute");
+check(win32.isAbsolute("C:foo"), false, "win32.isAbsolute with drive relative path");
+check(win32.isAbsolute("//server/share"), true, "win32.isAbsolute with UNC path");
+
+const parsed = posix.parse("/home/user/dir/file.txt");
+check(parsed.root, "/", "posix.parse root");
+check(parsed.dir, "/home/user/dir", "posix.parse dir");
+check(parsed.base, "file.txt", "posix.parse base");
+check(parsed.ext, ".txt", "posix.parse ext");
+check(parsed.name, "file", "posix.parse name");
+
+const parsedWindows = win32.parse("C:\\path\\dir\\file.txt");
+check(parsedWindows.root, "C:\\", "win32.parse root");
+check(parsedWindows.dir, "C:\\path\\dir", "win32.parse dir");
+check(parsedWindows.name, "file", "win32.parse name");
+
+check(posix.format(parsed), "/home/user/dir/file.txt", "posix.format should reverse parse");
+check(posix.format({ root: "/", base: "file.txt" }), "/file.txt", "posix.format with root");
+check(posix.format({ dir: "/home/user", name: "file", ext: "txt" }), "/home/user/file.txt", "posix.format with name and ext");
+check(win32.format(parsedWindows), "C:\\path\\dir\\file.txt", "win32.format should reverse parse");
@@ -0,0 +1,53 @@
+import permissions, { query, request, revoke } from "permissions";
+import { readTextFile } from "fs";
+import { listenDatagram } from "net";
+
+function check(condition, message) {
+	if (!condition) {
+		throw new Error(message);
+	}
+}
+
+async function rejects(promise, name) {
+	try {
+		await promise;
+	} catch (error) {
+		return error.name === name;
+	}
+	return false;
+}
+
+const directory = "./tests/scripts/permissions";
+const file = `${directory}/permissions.js`;
+
+check(permissions.query === query && permissions.revoke === revoke, "Default export should contain query and revoke");
+
+const granted = await query({ name: "read", path: file });
+check(granted.name === "read" && granted.state === "granted", "Paths within a granted directory should be granted");
+check((await query({ name: "read", path: "/" })).state === "denied", "Paths outside granted directories should be denied");
+check((await query({ name: "write", path: file })).state === "denied", "Permissions which were not granted should be denied");
+check((await query({ name: "hrtime" })).state === "denied", "hrtime should be denied unless it was granted");
+
+const source = await readTextFile(file);
+check(source.includes("permissions"), "Granted files should be readable");
+check(await rejects(readTextFile("/etc/hostname"), "PermissionDenied"), "Reading denied files should reject with PermissionDenied");
+
+check((await request({ name: "net", host: "localhost" })).state === "denied", "Requests should be denied without a prompt");
+
+const socket = listenDatagram(0, { hostname: "127.0.0.1" });
+const data = new Uint8Array([1]);
+check(await socket.send(data, "127.0.0.1", socket.addr.port) === 1, "Datagrams to granted hosts should be sent");
+check(await rejects(socket.send(data, "127.0.0.2", 9), "PermissionDenied"), "Datagrams to denied hosts should reject with PermissionDenied");
+socket.close();
+
+const revoked = await revoke({ name: "read", path: directory });
+check(revoked.state === "denied", "Revoked permissions should be denied without a prompt");
+check(await rejects(readTextFile(file), "PermissionDenied"), "Reading revoked files should reject with PermissionDenied");
+
+let threw = false;
+try {
+	await query({ name: "unknown" });
+} catch (error) {
+	threw = error instanceof TypeError;
+}
+check(threw, "Unknown permissions should throw a TypeError");
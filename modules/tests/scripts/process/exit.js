@@ -0,0 +1,16 @@
+import {exit} from "process";
+
+let count = 0;
+setInterval(() => {
+	count++;
+	if (count === 3) {
+		try {
+			exit(3);
+		} finally {
+			throw new Error("Scripts should not continue after exit");
+		}
+	}
+	if (count > 3) {
+		throw new Error("The event loop should stop after exit");
+	}
+}, 1);
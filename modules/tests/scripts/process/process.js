@@ -0,0 +1,53 @@
+import process, { args, env, cwd, chdir } from "process";
+
+function check(condition, message) {
+	if (!condition) {
+		throw new Error(message);
+	}
+}
+
+check(args.length === 2 && args[0] === "--flag" && args[1] === "value", "args should contain the arguments after the script");
+check(process.args === args, "Default export should contain args");
+
+check(env.SPIDERFIRE_PROCESS_TEST === "native", "env should read variables set natively");
+check("SPIDERFIRE_PROCESS_TEST" in env, "in should find existing variables");
+check(Object.keys(env).includes("SPIDERFIRE_PROCESS_TEST"), "Object.keys should list variables");
+check(env.SPIDERFIRE_PROCESS_MISSING === undefined, "Missing variables should be undefined");
+
+env.SPIDERFIRE_PROCESS_TEST = "script";
+env.SPIDERFIRE_PROCESS_REMOVED = "removed";
+check(env.SPIDERFIRE_PROCESS_REMOVED === "removed", "env should write variables");
+delete env.SPIDERFIRE_PROCESS_REMOVED;
+check(!("SPIDERFIRE_PROCESS_REMOVED" in env), "delete should remove variables");
+check(env.SPIDERFIRE_PROCESS_TEST === "script", "env should read variables written by scripts");
+check(Object.keys(env).filter(name => name === "SPIDERFIRE_PROCESS_TEST").length === 1, "Object.keys should list overwritten variables once");
+
+delete env.SPIDERFIRE_PROCESS_NATIVE;
+check(!("SPIDERFIRE_PROCESS_NATIVE" in env), "delete should hide variables set natively");
+check(!Object.keys(env).includes("SPIDERFIRE_PROCESS_NATIVE"), "Object.keys should not list deleted variables");
+
+let threw = false;
+try {
+	env["INVALID=NAME"] = "value";
+} catch (error) {
+	threw = error instanceof TypeError;
+}
+check(threw, "Invalid variable names should throw a TypeError");
+
+check(typeof process.pid === "number" && process.pid > 0, "pid should be a positive number");
+check(typeof process.platform === "string" && process.platform.length > 0, "platform should be a string");
+check(typeof process.arch === "string" && process.arch.length > 0, "arch should be a string");
+
+const directory = cwd();
+chdir("..");
+check(cwd() !== directory, "chdir should change the current directory");
+chdir(directory);
+check(cwd() === directory, "chdir should restore the current directory");
+
+check(process.stdin instanceof ReadableStream, "stdin should be a ReadableStream");
+check(process.stdout instanceof WritableStream, "stdout should be a WritableStream");
+check(process.stderr instanceof WritableStream, "stderr should be a WritableStream");
+
+const writer = process.stdout.getWriter();
+await writer.write(new TextEncoder().encode("Written to stdout\n"));
+writer.releaseLock();
@@ -0,0 +1,9 @@
+import {addSignalListener, removeSignalListener} from "process";
+
+const listener = () => {
+	throw new Error("Removed signal listeners should not be called");
+};
+addSignalListener("SIGHUP", listener);
+removeSignalListener("SIGHUP", listener);
+
+setInterval(() => {}, 10);
@@ -0,0 +1,57 @@
+import profiler, { isRunning, start, stop } from "profiler";
+
+function check(condition, message) {
+	if (!condition) {
+		throw new Error(message);
+	}
+}
+
+function throws(callback, name) {
+	try {
+		callback();
+	} catch (error) {
+		return error.name === name;
+	}
+	return false;
+}
+
+function fibonacci(n) {
+	return n < 2 ? n : fibonacci(n - 1) + fibonacci(n - 2);
+}
+
+function busy(duration) {
+	const end = Date.now() + duration;
+	let result = 0;
+	while (Date.now() < end) {
+		result += fibonacci(15);
+	}
+	return result;
+}
+
+check(profiler.start === start && profiler.stop === stop, "Default export should contain start and stop");
+check(!isRunning(), "Profiler should not be running before it is started");
+check(throws(() => stop(), "Error"), "Stopping a profiler which is not running should throw");
+check(throws(() => start({ interval: 0 }), "RangeError"), "Intervals below 0.1ms should throw");
+
+start({ interval: 1 });
+check(isRunning(), "Profiler should be running once it is started");
+check(throws(() => start(), "Error"), "Starting a profiler which is already running should throw");
+busy(100);
+
+const profile = JSON.parse(stop());
+check(!isRunning(), "Profiler should not be running once it is stopped");
+check(profile.profiles[0].type === "sampled", "Profile should be a sampled speedscope profile");
+check(profile.profiles[0].samples.length > 0, "Profile should have samples");
+check(profile.profiles[0].samples.length === profile.profiles[0].weights.length, "Every sample should have a weight");
+
+const frames = profile.shared.frames;
+const fibonacciFrame = frames.findIndex(frame => frame.name === "fibonacci");
+check(fibonacciFrame !== -1, "Frames should contain sampled functions");
+check(frames[fibonacciFrame].file.endsWith("profiler.js") && frames[fibonacciFrame].line === 18, "Frames should have locations");
+check(profile.profiles[0].samples.some(sample => sample.includes(fibonacciFrame)), "Samples should contain the frames of their stacks");
+
+start();
+busy(50);
+const folded = stop({ format: "folded" });
+check(folded.split("\n").some(line => /busy \(.*profiler\.js:\d+\).* \d+$/.test(line)), "Folded stacks should contain sampled functions and counts");
+check(throws(() => (start(), stop({ format: "pprof" })), "TypeError"), "Invalid formats should throw");
@@ -0,0 +1 @@
+export const imported = true;
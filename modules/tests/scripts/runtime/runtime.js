@@ -0,0 +1,53 @@
+import runtime, { activeHandles, diagnostics } from "runtime";
+
+function check(condition, message) {
+	if (!condition) {
+		throw new Error(message);
+	}
+}
+
+check(
+	runtime.activeHandles === activeHandles && runtime.diagnostics === diagnostics,
+	"Default export should contain activeHandles and diagnostics",
+);
+
+check(activeHandles().length === 0, "There should be no active handles initially");
+
+const timeout = setTimeout(function pending() {}, 60000);
+const interval = setInterval(function repeating() {}, 30000);
+const unrefed = setTimeout(function unreferenced() {}, 60000);
+unrefTimer(unrefed);
+
+const handles = activeHandles();
+check(handles.length === 2, "Unreferenced timers should not be active handles");
+check(handles[0].type === "timer" && handles[0].description.startsWith("pending after 60000 ms"), "Timeouts should be described");
+check(handles[1].type === "interval" && handles[1].description.startsWith("repeating every 30000 ms"), "Intervals should be described");
+check(handles[0].stack.includes("runtime.js"), "Creation stacks should be captured when enabled");
+
+clearTimeout(timeout);
+clearInterval(interval);
+clearTimeout(unrefed);
+check(activeHandles().length === 0, "Cleared timers should not be active handles");
+
+const received = [];
+function subscriber(diagnostic) {
+	received.push(diagnostic);
+}
+diagnostics.subscribe(subscriber);
+
+const { imported } = await import("./imported.js");
+check(imported, "Dynamic import should succeed");
+
+await new Promise(resolve => setTimeout(resolve, 0));
+
+const loaded = received.find(diagnostic => diagnostic.message === "Loaded module");
+check(loaded !== undefined, "Loading a module should be recorded");
+check(loaded.target === "runtime::modules::loader", "Target should be the module which recorded the event");
+check(loaded.level === "debug", "Level should be lowercase");
+check(loaded.fields.specifier === "./imported.js", "Fields should contain the specifier");
+check(Number(loaded.fields.elapsed_ms) >= 0, "Fields should contain the load time");
+check(typeof loaded.timestamp === "number" && loaded.timestamp > 0, "Timestamp should be in milliseconds");
+check(received.every(diagnostic => diagnostic.level !== "trace"), "Trace events should not be delivered");
+
+check(diagnostics.unsubscribe(subscriber), "Unsubscribing should succeed");
+check(!diagnostics.unsubscribe(subscriber), "Unsubscribing twice should fail");
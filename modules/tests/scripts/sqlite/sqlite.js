@@ -0,0 +1,130 @@
+import sqlite, { open, Database, Statement } from "sqlite";
+
+function check(condition, message) {
+	if (!condition) {
+		throw new Error(message);
+	}
+}
+
+check(sqlite.open === open && sqlite.Database === Database, "Default export should contain open and Database");
+
+const db = await open(":memory:");
+check(db instanceof Database && db.path === ":memory:", "open should resolve with a Database");
+
+await db.exec("CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT NOT NULL UNIQUE, score REAL, avatar BLOB)");
+
+const insert = await db.prepare("INSERT INTO users (name, score, avatar) VALUES (?, ?, ?)");
+check(insert instanceof Statement, "prepare should resolve with a Statement");
+const first = await insert.run(["alice", 1.5, new Uint8Array([1, 2, 3])]);
+check(first.changes === 1 && first.lastInsertRowid === 1, "run should report the changes and the inserted row");
+await insert.run(["bob", null, null]);
+
+const named = await db.prepare("INSERT INTO users (name, score) VALUES (:name, @score)");
+await named.run({ name: "carol", score: 3 });
+await named.run({ ":name": "dave", "@score": 4 });
+
+const select = await db.prepare("SELECT id, name, score, avatar FROM users ORDER BY id");
+check(select.columns.join() === "id,name,score,avatar", "columns should be the names of the result columns");
+
+const rows = await select.all();
+check(rows.length === 4, "all should return every row");
+check(rows[0].name === "alice" && rows[0].score === 1.5, "Rows should be objects keyed by column name");
+check(rows[0].avatar instanceof Uint8Array && rows[0].avatar[2] === 3, "Blobs should be read as Uint8Arrays");
+check(rows[1].score === null, "NULL should be read as null");
+check(rows[3].name === "dave" && rows[3].score === 4, "Named parameters should be bound with or without their prefix");
+
+const values = await select.values();
+check(Array.isArray(values[0]) && values[0][1] === "alice", "values should return rows as arrays");
+
+const byName = await db.prepare("SELECT name FROM users WHERE name = ?");
+check((await byName.get(["bob"])).name === "bob", "get should return the first row");
+check((await byName.get(["nobody"])) === null, "get should return null when there are no rows");
+
+const big = await db.prepare("SELECT ? AS value");
+check((await big.get([2n ** 62n])).value === 2n ** 62n, "Integers outside the safe range should be read as BigInts");
+check((await big.get([true])).value === 1, "Booleans should be bound as integers");
+
+let constraint = false;
+try {
+	await insert.run(["alice", 0, null]);
+} catch (error) {
+	constraint = error.code === "SQLITE_CONSTRAINT";
+}
+check(constraint, "Constraint violations should reject with SQLITE_CONSTRAINT");
+
+let count = false;
+try {
+	await insert.run(["eve"]);
+} catch {
+	count = true;
+}
+check(count, "Binding the wrong number of parameters should reject");
+
+let syntax = false;
+try {
+	await db.prepare("SELEC 1");
+} catch {
+	syntax = true;
+}
+check(syntax, "Invalid SQL should reject when prepared");
+
+const total = await db.prepare("SELECT COUNT(*) AS count FROM users");
+const result = await db.transaction(async () => {
+	await insert.run(["frank", 6, null]);
+	return "committed";
+});
+check(result === "committed", "transaction should resolve with the result of the callback");
+check((await total.get()).count === 5, "Committed transactions should keep their changes");
+
+let rolledBack = false;
+try {
+	await db.transaction(async () => {
+		await insert.run(["grace", 7, null]);
+		throw new Error("abort");
+	});
+} catch (error) {
+	rolledBack = error.message === "abort";
+}
+check(rolledBack, "transaction should reject with the error of the callback");
+check((await total.get()).count === 5, "Rolled back transactions should discard their changes");
+
+const order = [];
+await Promise.all([
+	db.transaction(async () => {
+		order.push("first");
+		await insert.run(["heidi", 8, null]);
+		order.push("first");
+	}),
+	db.transaction(async () => {
+		order.push("second");
+		await insert.run(["ivan", 9, null]);
+		order.push("second");
+	}),
+]);
+check(order.join() === "first,first,second,second", "Transactions should run one at a time");
+check((await total.get()).count === 7, "Serialised transactions should keep their changes");
+
+await db.transaction(async nested => {
+	await insert.run(["judy", 10, null]);
+	let nestedRolledBack = false;
+	try {
+		await nested(async () => {
+			await insert.run(["mallory", 11, null]);
+			throw new Error("nested");
+		});
+	} catch (error) {
+		nestedRolledBack = error.message === "nested";
+	}
+	check(nestedRolledBack, "Nested transactions should reject with the error of the callback");
+	await nested(() => insert.run(["niaj", 12, null]));
+});
+check((await total.get()).count === 9, "Rolled back nested transactions should only discard their own changes");
+
+await db.close();
+let closed = false;
+try {
+	await select.all();
+} catch (error) {
+	closed = error.message === "Database is closed";
+}
+check(closed, "Statements should reject once the database is closed");
@@ -0,0 +1,80 @@
+import subprocess, { spawn, output, Child } from "subprocess";
+
+function check(condition, message) {
+	if (!condition) {
+		throw new Error(message);
+	}
+}
+
+const decoder = new TextDecoder();
+
+function shell(script) {
+	return windows ? ["cmd", ["/C", script]] : ["sh", ["-c", script]];
+}
+
+async function readAll(stream) {
+	const reader = stream.getReader();
+	let text = "";
+	while (true) {
+		const { done, value } = await reader.read();
+		if (done) {
+			return text;
+		}
+		text += decoder.decode(value);
+	}
+}
+
+check(subprocess.Child === Child, "Default export should contain Child");
+
+let threw = false;
+try {
+	new Child();
+} catch (error) {
+	threw = error instanceof TypeError;
+}
+check(threw, "Child should have no constructor");
+
+const echoed = await output(...shell("echo hello"));
+check(echoed.status.success && echoed.status.code === 0, "output should resolve with a successful status");
+check(decoder.decode(echoed.stdout).trim() === "hello", "output should collect stdout");
+
+const failed = await output(...shell("exit 3"));
+check(!failed.status.success && failed.status.code === 3, "output should resolve with the exit code");
+
+const variable = await output(...shell(windows ? "echo %SPIDERFIRE_SUBPROCESS%" : "echo $SPIDERFIRE_SUBPROCESS"), { env: { SPIDERFIRE_SUBPROCESS: "variable" } });
+check(decoder.decode(variable.stdout).trim() === "variable", "env should set environment variables");
+
+try {
+	await output("spiderfire-missing-program");
+	threw = false;
+} catch (error) {
+	threw = error.code === "ENOENT";
+}
+check(threw, "output should reject with ENOENT for missing programs");
+
+const child = spawn(...shell(windows ? "more" : "cat"));
+check(typeof child.pid === "number", "Child should have a pid");
+const writer = child.stdin.getWriter();
+await writer.write(new TextEncoder().encode("piped\n"));
+await writer.close();
+check((await readAll(child.stdout)).trim() === "piped", "stdin should be piped to stdout");
+check((await child.status()).success, "Child should exit successfully once stdin is closed");
+
+const inherited = spawn(...shell("exit 0"), { stdio: "null" });
+check(inherited.stdin === null && inherited.stdout === null && inherited.stderr === null, "Streams should be null unless piped");
+await inherited.status();
+
+if (!windows) {
+	const sleeping = spawn("sleep", ["10"]);
+	sleeping.kill("SIGKILL");
+	const status = await sleeping.status();
+	check(!status.success && status.signal === 9, "kill should send the signal to the child");
+
+	threw = false;
+	try {
+		sleeping.kill("SIGUNKNOWN");
+	} catch (error) {
+		threw = error instanceof TypeError;
+	}
+	check(threw, "kill should reject unknown signals");
+}
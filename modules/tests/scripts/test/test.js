@@ -0,0 +1,72 @@
+import test, { describe, it, beforeAll, afterAll, beforeEach, afterEach, run } from "test";
+
+function check(condition, message) {
+	if (!condition) {
+		throw new Error(message);
+	}
+}
+
+check(test.test === test.it && test.describe === describe, "Default export should contain test, it and describe");
+
+const order = [];
+
+test("passes", () => {
+	order.push("passes");
+});
+
+test("rejects", async () => {
+	await Promise.resolve();
+	throw new Error("Expected failure");
+});
+
+test("is skipped", () => {
+	order.push("skipped");
+}, { skip: true });
+
+test("times out", () => new Promise(() => {}), { timeout: 10 });
+
+describe("group", () => {
+	beforeAll(() => order.push("beforeAll"));
+	afterAll(() => order.push("afterAll"));
+	beforeEach(() => order.push("beforeEach"));
+	afterEach(() => order.push("afterEach"));
+
+	it("runs hooks", () => {
+		order.push("group test");
+	});
+
+	describe("nested", () => {
+		it("inherits hooks", async () => {
+			order.push("nested test");
+		});
+	});
+});
+
+const summary = await run();
+check(summary.passed === 3, "3 tests should pass");
+check(summary.failed === 2, "2 tests should fail");
+check(summary.skipped === 1, "1 test should be skipped");
+
+const results = Object.fromEntries(summary.results.map(result => [result.name, result]));
+check(results["rejects"].error.includes("Expected failure"), "Rejected tests should report their errors");
+check(results["times out"].error.includes("Timed out"), "Tests should time out");
+check(results["group > nested > inherits hooks"].status === "passed", "Tests should be named by their groups");
+
+const expected = [
+	"passes",
+	"beforeAll",
+	"beforeEach",
+	"group test",
+	"afterEach",
+	"beforeEach",
+	"nested test",
+	"afterEach",
+	"afterAll",
+];
+check(order.join() === expected.join(), `Hooks should run in order, but ran: ${order.join()}`);
+
+test("filtered in", () => {});
+test("filtered out", () => {});
+
+const filtered = await run({ filter: "in" });
+check(filtered.results.length === 1 && filtered.passed === 1, "Filters should select tests by name");
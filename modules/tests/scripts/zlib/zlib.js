@@ -0,0 +1,54 @@
+import zlib, { compress, compressSync, decompress, decompressSync } from "zlib";
+
+function check(condition, message) {
+	if (!condition) {
+		throw new Error(message);
+	}
+}
+
+const decoder = new TextDecoder();
+const text = "spiderfire ".repeat(512);
+
+check(zlib.compress === compress && zlib.decompressSync === decompressSync, "Default export should contain the functions");
+
+for (const format of ["gzip", "deflate", "deflate-raw", "brotli"]) {
+	const compressed = await compress(text, format);
+	check(compressed instanceof Uint8Array && compressed.length < text.length, `${format} should compress the data`);
+	check(decoder.decode(await decompress(compressed, format)) === text, `${format} should round-trip asynchronously`);
+
+	const compressedSync = compressSync(text, format);
+	check(decoder.decode(decompressSync(compressedSync, format)) === text, `${format} should round-trip synchronously`);
+}
+
+const gzip = compressSync(new TextEncoder().encode("hello"));
+check(gzip[0] === 0x1f && gzip[1] === 0x8b, "Data should be compressed with gzip by default");
+check(decoder.decode(decompressSync(gzip.buffer)) === "hello", "ArrayBuffers should be decompressed");
+
+const fast = compressSync(text, "deflate", { level: 1 });
+const small = compressSync(text, "deflate", { level: 9 });
+check(small.length <= fast.length, "Higher levels should not compress worse");
+check(compressSync(text, "deflate", { level: 0 }).length > text.length, "Level 0 should store the data");
+
+let invalid = false;
+try {
+	await decompress(new Uint8Array([1, 2, 3, 4]));
+} catch (error) {
+	invalid = error instanceof TypeError;
+}
+check(invalid, "Invalid data should be rejected with a TypeError");
+
+let truncated = false;
+try {
+	decompressSync(compressSync(text, "brotli").slice(0, 10), "brotli");
+} catch (error) {
+	truncated = error instanceof TypeError;
+}
+check(truncated, "Truncated data should throw a TypeError");
+
+let format = false;
+try {
+	compressSync(text, "zip");
+} catch (error) {
+	format = error instanceof TypeError;
+}
+check(format, "Unknown formats should throw a TypeError");
@@ -0,0 +1,29 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+// Measures the overhead of scheduling with many pending timers, which should not grow with the number of timers.
+// Run with `just bench`, which runs `spiderfire bench runtime/benches` with a release build.
+
+import { bench } from "bench";
+
+const PENDING = 100_000;
+
+// Pending timers are unreferenced, so that they do not keep the event loop alive once the benchmarks have finished.
+for (let i = 0; i < PENDING; i++) {
+	unrefTimer(setTimeout(() => {}, 3_600_000 + i));
+}
+
+bench(`setTimeout(0) with ${PENDING} pending timers`, () => new Promise(resolve => setTimeout(resolve, 0)), { iterations: 1000 });
+
+bench(`setImmediate with ${PENDING} pending timers`, () => new Promise(resolve => setImmediate(resolve)), { iterations: 1000 });
+
+bench(`setTimeout and clearTimeout with ${PENDING} pending timers`, () => {
+	clearTimeout(setTimeout(() => {}, 1000));
+}, { iterations: 10_000 });
+
+bench(`setInterval and clearInterval with ${PENDING} pending timers`, () => {
+	clearInterval(setInterval(() => {}, 1000));
+}, { iterations: 10_000 });
@@ -0,0 +1,194 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::collections::HashMap;
+use std::ffi::OsStr;
+use std::fs::read_to_string;
+use std::path::{Path, PathBuf};
+
+use dunce::canonicalize;
+use swc_core::common::{FileName, Mark, SourceMap as SwcSourceMap};
+use swc_core::common::comments::{Comments, SingleThreadedComments};
+use swc_core::common::errors::{ColorConfig, Handler};
+use swc_core::common::input::StringInput;
+use swc_core::common::sync::Lrc;
+use swc_core::ecma::ast::{EsVersion, Module, ModuleDecl, ModuleItem, NamedExport, Str};
+use swc_core::ecma::parser::{EsConfig, Parser, Syntax, TsConfig};
+use swc_core::ecma::parser::lexer::Lexer;
+use swc_core::ecma::transforms::base::resolver;
+use swc_core::ecma::transforms::react::{Options as ReactOptions, react};
+use swc_core::ecma::transforms::typescript::strip;
+use swc_core::ecma::visit::FoldWith;
+
+use ion::{Context, Exception};
+
+use crate::bundler::Error;
+use crate::modules::{is_bare, Loader};
+use crate::modules::commonjs::is_commonjs;
+use crate::typescript::is_typescript;
+
+/// Represents where an import of a module is resolved from.
+#[derive(Clone, Debug)]
+pub(crate) enum Dependency {
+	/// Module within the bundle, by its index in the graph.
+	Internal(usize),
+	/// Module which is imported when the bundle is run, such as a standard module.
+	External(String),
+}
+
+pub(crate) struct ModuleRecord {
+	pub(crate) path: PathBuf,
+	pub(crate) module: Module,
+	pub(crate) dependencies: HashMap<String, Dependency>,
+}
+
+impl ModuleRecord {
+	pub(crate) fn dependency(&self, specifier: &Str) -> &Dependency {
+		&self.dependencies[&*specifier.value]
+	}
+}
+
+/// Represents the graph of modules imported by the entry module, parsed into a single source map.
+pub(crate) struct Graph {
+	pub(crate) modules: Vec<ModuleRecord>,
+	/// Indices of modules in the order they are evaluated.
+	pub(crate) order: Vec<usize>,
+	pub(crate) source_map: Lrc<SwcSourceMap>,
+	pub(crate) comments: SingleThreadedComments,
+	handler: Handler,
+	indices: HashMap<PathBuf, usize>,
+	unresolved_mark: Mark,
+}
+
+impl Graph {
+	pub(crate) fn new() -> Graph {
+		let source_map: Lrc<SwcSourceMap> = Default::default();
+		let handler = Handler::with_tty_emitter(ColorConfig::Auto, true, false, Some(source_map.clone()));
+		Graph {
+			modules: Vec::new(),
+			order: Vec::new(),
+			source_map,
+			comments: SingleThreadedComments::default(),
+			handler,
+			indices: HashMap::new(),
+			unresolved_mark: Mark::new(),
+		}
+	}
+
+	/// Loads a module and the modules it imports, returning its index.
+	/// Modules are ordered after the modules they import, unless they are imported cyclically.
+	pub(crate) fn load(&mut self, cx: &Context, loader: &mut Loader, path: &Path) -> Result<usize, Error> {
+		let path = canonicalize(path).map_err(|error| Error::Read(path.to_path_buf(), error))?;
+		if let Some(index) = self.indices.get(&path) {
+			return Ok(*index);
+		}
+
+		let module = self.parse(cx, &path)?;
+		let specifiers = requested_specifiers(&module);
+
+		let index = self.modules.len();
+		self.indices.insert(path.clone(), index);
+		self.modules.push(ModuleRecord {
+			path: path.clone(),
+			module,
+			dependencies: HashMap::new(),
+		});
+
+		for specifier in specifiers {
+			let dependency = self.resolve(cx, loader, &specifier, &path)?;
+			self.modules[index].dependencies.insert(specifier, dependency);
+		}
+		self.order.push(index);
+		Ok(index)
+	}
+
+	fn resolve(&mut self, cx: &Context, loader: &mut Loader, specifier: &str, importer: &Path) -> Result<Dependency, Error> {
+		match loader.resolve_specifier(cx, specifier, Some(importer)) {
+			Some(path) if path.is_file() => self.load(cx, loader, &path).map(Dependency::Internal),
+			// Bare specifiers which are not files, such as standard modules, are resolved when the bundle is run.
+			Some(_) if is_bare(specifier) => Ok(Dependency::External(String::from(specifier))),
+			Some(path) => Err(Error::Resolution(format!(
+				"Unable to find module {}, imported by {}",
+				path.display(),
+				importer.display()
+			))),
+			None => {
+				let message = Exception::new(cx).map(|exception| exception.format(cx));
+				Err(Error::Resolution(message.unwrap_or_else(|| {
+					format!("Unable to resolve \"{}\", imported by {}", specifier, importer.display())
+				})))
+			}
+		}
+	}
+
+	/// Parses a module, resolving its identifiers and stripping its types.
+	/// JSON modules are parsed as a module with the JSON as its default export.
+	fn parse(&mut self, cx: &Context, path: &Path) -> Result<Module, Error> {
+		let source = read_to_string(path).map_err(|error| Error::Read(path.to_path_buf(), error))?;
+		let is_json = path.extension() == Some(OsStr::new("json"));
+		if !is_json && is_commonjs(cx, path, &source) {
+			return Err(Error::Unsupported(format!("CommonJS module {} cannot be bundled", path.display())));
+		}
+
+		let source = if is_json { format!("export default {};", source.trim()) } else { source };
+		let typescript = !is_json && is_typescript(path);
+		let tsx = typescript && path.extension() == Some(OsStr::new("tsx"));
+		let syntax = if typescript {
+			Syntax::Typescript(TsConfig { tsx, ..TsConfig::default() })
+		} else {
+			Syntax::Es(EsConfig::default())
+		};
+
+		let file = self.source_map.new_source_file(FileName::Real(path.to_path_buf()), source);
+		let comments: &dyn Comments = &self.comments;
+		let lexer = Lexer::new(syntax, EsVersion::Es2022, StringInput::from(&*file), Some(comments));
+		let mut parser = Parser::new_from(lexer);
+		let module = parser.parse_module().map_err(|error| {
+			error.into_diagnostic(&self.handler).emit();
+			Error::Parse(path.to_path_buf())
+		})?;
+		for error in parser.take_errors() {
+			error.into_diagnostic(&self.handler).emit();
+		}
+
+		let top_level_mark = Mark::new();
+		let module = module.fold_with(&mut resolver(self.unresolved_mark, top_level_mark, typescript));
+		if !typescript {
+			return Ok(module);
+		}
+
+		let module = module.fold_with(&mut strip(top_level_mark));
+		if tsx {
+			Ok(module.fold_with(&mut react(
+				self.source_map.clone(),
+				Some(comments),
+				ReactOptions::default(),
+				top_level_mark,
+				self.unresolved_mark,
+			)))
+		} else {
+			Ok(module)
+		}
+	}
+}
+
+/// Returns the specifiers requested by the imports and re-exports of a module, in the order they are evaluated.
+fn requested_specifiers(module: &Module) -> Vec<String> {
+	let mut specifiers: Vec<String> = Vec::new();
+	for item in &module.body {
+		let src = match item {
+			ModuleItem::ModuleDecl(ModuleDecl::Import(import)) => &import.src,
+			ModuleItem::ModuleDecl(ModuleDecl::ExportNamed(NamedExport { src: Some(src), .. })) => src,
+			ModuleItem::ModuleDecl(ModuleDecl::ExportAll(export)) => &export.src,
+			_ => continue,
+		};
+		let specifier = src.value.to_string();
+		if !specifiers.contains(&specifier) {
+			specifiers.push(specifier);
+		}
+	}
+	specifiers
+}
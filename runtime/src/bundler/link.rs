@@ -0,0 +1,472 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::mem::take;
+
+use swc_core::common::{DUMMY_SP, FileName, Mark, SourceMap as SwcSourceMap, SyntaxContext};
+use swc_core::common::input::StringInput;
+use swc_core::common::sync::Lrc;
+use swc_core::ecma::ast::{
+	ClassDecl, Decl, DefaultDecl, EsVersion, Expr, ExportSpecifier, FnDecl, Id, Ident, ImportSpecifier, KeyValueProp, ModuleDecl, ModuleExportName,
+	ModuleItem, ObjectPatProp, Pat, Prop, PropName, Stmt, VarDecl, VarDeclarator, VarDeclKind,
+};
+use swc_core::ecma::parser::{EsConfig, Parser, Syntax};
+use swc_core::ecma::parser::lexer::Lexer;
+use swc_core::ecma::visit::{VisitMut, VisitMutWith};
+
+use crate::bundler::Error;
+use crate::bundler::graph::{Dependency, Graph, ModuleRecord};
+
+#[derive(Clone)]
+enum Import {
+	Named(usize, String),
+	Namespace(usize),
+}
+
+enum Export {
+	Local(Id),
+	Reexport(usize, String),
+	Namespace(usize),
+	/// Binding of an external module, imported by the bundle.
+	Binding(Ident),
+}
+
+/// Represents what an import or export refers to, once re-exports have been followed.
+enum Target {
+	Binding(Ident),
+	Namespace(usize),
+}
+
+/// Represents a module with its import and export declarations removed, and the bindings they declared.
+#[derive(Default)]
+struct Linked {
+	items: Vec<ModuleItem>,
+	imports: HashMap<Id, Import>,
+	exports: HashMap<String, Export>,
+	stars: Vec<usize>,
+}
+
+struct Linker<'g> {
+	graph: &'g Graph,
+	modules: Vec<Linked>,
+	/// Imports of external modules, which are hoisted to the start of the bundle.
+	externals: Vec<ModuleItem>,
+	/// Re-exports of all exports of external modules by the entry module.
+	external_stars: Vec<ModuleItem>,
+	namespaces: HashMap<usize, Ident>,
+	pending_namespaces: Vec<usize>,
+}
+
+/// Links the modules of the graph into the body of a single module.
+///
+/// Imports of modules within the bundle are replaced by the bindings they refer to, and export declarations are removed,
+/// except for the exports of the entry module. Namespace objects are created for modules which are imported as namespaces.
+pub(crate) fn link(graph: &mut Graph, entry: usize) -> Result<Vec<ModuleItem>, Error> {
+	let mut records = take(&mut graph.modules);
+	let mut linker = Linker {
+		graph: &*graph,
+		modules: Vec::new(),
+		externals: Vec::new(),
+		external_stars: Vec::new(),
+		namespaces: HashMap::new(),
+		pending_namespaces: Vec::new(),
+	};
+	let result = linker.link(&mut records, entry);
+	graph.modules = records;
+	result
+}
+
+impl Linker<'_> {
+	fn link(&mut self, records: &mut [ModuleRecord], entry: usize) -> Result<Vec<ModuleItem>, Error> {
+		for (index, record) in records.iter_mut().enumerate() {
+			let linked = self.split(record, index == entry)?;
+			self.modules.push(linked);
+		}
+
+		for index in 0..self.modules.len() {
+			let imports: Vec<_> = self.modules[index]
+				.imports
+				.iter()
+				.map(|(id, import)| (id.clone(), import.clone()))
+				.collect();
+			let mut replacements = HashMap::new();
+			for (id, import) in imports {
+				let target = match import {
+					Import::Named(module, name) => self.resolve_export(module, &name, &mut HashSet::new()).ok_or_else(|| {
+						Error::Link(format!(
+							"Module {} does not provide an export named \"{}\", imported by {}",
+							records[module].path.display(),
+							name,
+							records[index].path.display()
+						))
+					})?,
+					Import::Namespace(module) => Target::Namespace(module),
+				};
+				replacements.insert(id, self.ident(target));
+			}
+			self.modules[index].items.visit_mut_with(&mut Renamer { replacements: &replacements });
+		}
+
+		let mut exports = Vec::new();
+		let mut specifiers = Vec::new();
+		for name in self.export_names(entry, &mut HashSet::new()) {
+			let target = self
+				.resolve_export(entry, &name, &mut HashSet::new())
+				.ok_or_else(|| Error::Link(format!("Export \"{}\" of {} cannot be resolved", name, records[entry].path.display())))?;
+			exports.push(self.ident(target));
+			specifiers.push(format!("${} as {}", exports.len() - 1, name_literal(&name)));
+		}
+
+		let mut namespaces = HashMap::new();
+		while let Some(module) = self.pending_namespaces.pop() {
+			let items = self.namespace_object(module);
+			namespaces.insert(module, items);
+		}
+
+		let mut body = take(&mut self.externals);
+		for index in &self.graph.order {
+			body.append(&mut self.modules[*index].items);
+			if let Some(mut items) = namespaces.remove(index) {
+				body.append(&mut items);
+			}
+		}
+		if !specifiers.is_empty() {
+			let source = format!("export {{ {} }};", specifiers.join(", "));
+			body.extend(generate(&self.graph.source_map, source, &exports));
+		}
+		body.append(&mut self.external_stars);
+		Ok(body)
+	}
+
+	/// Removes the import and export declarations of a module, recording the bindings they declare.
+	fn split(&mut self, record: &mut ModuleRecord, entry: bool) -> Result<Linked, Error> {
+		let mut linked = Linked::default();
+		for item in take(&mut record.module.body) {
+			let decl = match item {
+				ModuleItem::Stmt(stmt) => {
+					linked.items.push(ModuleItem::Stmt(stmt));
+					continue;
+				}
+				ModuleItem::ModuleDecl(decl) => decl,
+			};
+
+			match decl {
+				ModuleDecl::Import(import) => match record.dependency(&import.src) {
+					Dependency::Internal(module) => {
+						for specifier in &import.specifiers {
+							let (local, import) = match specifier {
+								ImportSpecifier::Named(named) => {
+									let imported = named.imported.as_ref().map_or_else(|| named.local.sym.to_string(), export_name);
+									(&named.local, Import::Named(*module, imported))
+								}
+								ImportSpecifier::Default(default) => (&default.local, Import::Named(*module, String::from("default"))),
+								ImportSpecifier::Namespace(namespace) => (&namespace.local, Import::Namespace(*module)),
+							};
+							linked.imports.insert(local.to_id(), import);
+						}
+					}
+					Dependency::External(_) => self.externals.push(ModuleItem::ModuleDecl(ModuleDecl::Import(import))),
+				},
+				ModuleDecl::ExportDecl(export) => {
+					for id in declared_ids(&export.decl) {
+						linked.exports.insert(id.0.to_string(), Export::Local(id));
+					}
+					linked.items.push(ModuleItem::Stmt(Stmt::Decl(export.decl)));
+				}
+				ModuleDecl::ExportNamed(export) => {
+					let dependency = export.src.as_ref().map(|src| record.dependency(src).clone());
+					for specifier in &export.specifiers {
+						let (exported, export) = match specifier {
+							ExportSpecifier::Named(named) => {
+								let orig = export_name(&named.orig);
+								let exported = named.exported.as_ref().map_or_else(|| orig.clone(), export_name);
+								let export = match (&dependency, &named.orig) {
+									(None, ModuleExportName::Ident(ident)) => Export::Local(ident.to_id()),
+									(None, ModuleExportName::Str(_)) => continue,
+									(Some(Dependency::Internal(module)), _) => Export::Reexport(*module, orig),
+									(Some(Dependency::External(specifier)), _) => {
+										let source = format!("import {{ {} as $0 }} from {};", name_literal(&orig), string_literal(specifier));
+										Export::Binding(self.import_external(source, &orig))
+									}
+								};
+								(exported, export)
+							}
+							ExportSpecifier::Namespace(namespace) => {
+								let export = match &dependency {
+									Some(Dependency::Internal(module)) => Export::Namespace(*module),
+									Some(Dependency::External(specifier)) => {
+										let source = format!("import * as $0 from {};", string_literal(specifier));
+										Export::Binding(self.import_external(source, "namespace"))
+									}
+									None => continue,
+								};
+								(export_name(&namespace.name), export)
+							}
+							ExportSpecifier::Default(_) => {
+								return Err(Error::Unsupported(format!(
+									"Default export specifiers in {} cannot be bundled",
+									record.path.display()
+								)));
+							}
+						};
+						linked.exports.insert(exported, export);
+					}
+				}
+				ModuleDecl::ExportDefaultDecl(export) => {
+					let (ident, decl) = match export.decl {
+						DefaultDecl::Class(class) => {
+							let ident = class.ident.unwrap_or_else(|| fresh("_default"));
+							let decl = Decl::Class(ClassDecl {
+								ident: ident.clone(),
+								declare: false,
+								class: class.class,
+							});
+							(ident, decl)
+						}
+						DefaultDecl::Fn(function) => {
+							let ident = function.ident.unwrap_or_else(|| fresh("_default"));
+							let decl = Decl::Fn(FnDecl {
+								ident: ident.clone(),
+								declare: false,
+								function: function.function,
+							});
+							(ident, decl)
+						}
+						DefaultDecl::TsInterfaceDecl(_) => continue,
+					};
+					linked.exports.insert(String::from("default"), Export::Local(ident.to_id()));
+					linked.items.push(ModuleItem::Stmt(Stmt::Decl(decl)));
+				}
+				ModuleDecl::ExportDefaultExpr(export) => {
+					let ident = fresh("_default");
+					linked.exports.insert(String::from("default"), Export::Local(ident.to_id()));
+					let decl = VarDecl {
+						span: export.span,
+						kind: VarDeclKind::Const,
+						declare: false,
+						decls: vec![VarDeclarator {
+							span: export.span,
+							name: Pat::Ident(ident.into()),
+							init: Some(export.expr),
+							definite: false,
+						}],
+					};
+					linked.items.push(ModuleItem::Stmt(Stmt::Decl(Decl::Var(Box::new(decl)))));
+				}
+				ModuleDecl::ExportAll(export) => match record.dependency(&export.src) {
+					Dependency::Internal(module) => linked.stars.push(*module),
+					// The names exported by external modules are unknown until the bundle is run.
+					Dependency::External(_) if entry => self.external_stars.push(ModuleItem::ModuleDecl(ModuleDecl::ExportAll(export))),
+					Dependency::External(specifier) => {
+						return Err(Error::Unsupported(format!(
+							"Re-exporting all exports of external module \"{}\" from {} cannot be bundled",
+							specifier,
+							record.path.display()
+						)));
+					}
+				},
+				_ => {
+					return Err(Error::Unsupported(format!(
+						"TypeScript module declarations in {} cannot be bundled",
+						record.path.display()
+					)));
+				}
+			}
+		}
+		Ok(linked)
+	}
+
+	/// Adds an import of an external module, which binds `$0` to a new identifier.
+	fn import_external(&mut self, source: String, name: &str) -> Ident {
+		let ident = fresh(&format!(
+			"_{}",
+			name.chars().filter(|c| c.is_ascii_alphanumeric() || *c == '_').collect::<String>()
+		));
+		self.externals.extend(generate(&self.graph.source_map, source, &[ident.clone()]));
+		ident
+	}
+
+	fn resolve_export(&self, module: usize, name: &str, visited: &mut HashSet<(usize, String)>) -> Option<Target> {
+		if !visited.insert((module, String::from(name))) {
+			return None;
+		}
+
+		let linked = &self.modules[module];
+		match linked.exports.get(name) {
+			Some(Export::Local(id)) => match linked.imports.get(id) {
+				Some(Import::Named(module, name)) => self.resolve_export(*module, name, visited),
+				Some(Import::Namespace(module)) => Some(Target::Namespace(*module)),
+				None => Some(Target::Binding(Ident::new(id.0.clone(), DUMMY_SP.with_ctxt(id.1)))),
+			},
+			Some(Export::Reexport(module, name)) => self.resolve_export(*module, name, visited),
+			Some(Export::Namespace(module)) => Some(Target::Namespace(*module)),
+			Some(Export::Binding(ident)) => Some(Target::Binding(ident.clone())),
+			None if name != "default" => linked.stars.iter().find_map(|module| self.resolve_export(*module, name, visited)),
+			None => None,
+		}
+	}
+
+	/// Returns the names exported by a module, including the names exported by the modules it re-exports all exports of.
+	fn export_names(&self, module: usize, visited: &mut HashSet<usize>) -> BTreeSet<String> {
+		if !visited.insert(module) {
+			return BTreeSet::new();
+		}
+
+		let linked = &self.modules[module];
+		let mut names: BTreeSet<_> = linked.exports.keys().cloned().collect();
+		for star in &linked.stars {
+			names.extend(self.export_names(*star, visited).into_iter().filter(|name| name != "default"));
+		}
+		names
+	}
+
+	/// Returns the identifier of a target, creating the namespace object of a module when it is first needed.
+	fn ident(&mut self, target: Target) -> Ident {
+		match target {
+			Target::Binding(ident) => ident,
+			Target::Namespace(module) => {
+				let pending = &mut self.pending_namespaces;
+				let ident = self.namespaces.entry(module).or_insert_with(|| {
+					pending.push(module);
+					fresh("_namespace")
+				});
+				ident.clone()
+			}
+		}
+	}
+
+	/// Creates the namespace object of a module, with a getter for each export so that it reflects live bindings.
+	fn namespace_object(&mut self, module: usize) -> Vec<ModuleItem> {
+		let mut idents = vec![self.namespaces[&module].clone()];
+		let mut properties = vec![String::from("__proto__: null"), String::from("[Symbol.toStringTag]: \"Module\"")];
+		for name in self.export_names(module, &mut HashSet::new()) {
+			if let Some(target) = self.resolve_export(module, &name, &mut HashSet::new()) {
+				idents.push(self.ident(target));
+				properties.push(format!("get {}() {{ return ${}; }}", string_literal(&name), idents.len() - 1));
+			}
+		}
+
+		let source = format!("const $0 = Object.freeze({{ {} }});", properties.join(", "));
+		generate(&self.graph.source_map, source, &idents)
+	}
+}
+
+/// Replaces identifiers with the bindings they refer to.
+struct Renamer<'r> {
+	replacements: &'r HashMap<Id, Ident>,
+}
+
+impl VisitMut for Renamer<'_> {
+	fn visit_mut_ident(&mut self, ident: &mut Ident) {
+		if let Some(replacement) = self.replacements.get(&ident.to_id()) {
+			*ident = Ident::new(replacement.sym.clone(), ident.span.with_ctxt(replacement.span.ctxt));
+		}
+	}
+
+	fn visit_mut_prop(&mut self, prop: &mut Prop) {
+		if let Prop::Shorthand(ident) = prop {
+			if let Some(replacement) = self.replacements.get(&ident.to_id()) {
+				let value = Ident::new(replacement.sym.clone(), ident.span.with_ctxt(replacement.span.ctxt));
+				*prop = Prop::KeyValue(KeyValueProp {
+					key: PropName::Ident(Ident::new(ident.sym.clone(), ident.span.with_ctxt(SyntaxContext::empty()))),
+					value: Box::new(Expr::Ident(value)),
+				});
+				return;
+			}
+		}
+		prop.visit_mut_children_with(self);
+	}
+}
+
+/// Parses generated code, replacing the placeholder identifiers `$0`, `$1`, ... with the given identifiers.
+fn generate(source_map: &Lrc<SwcSourceMap>, source: String, idents: &[Ident]) -> Vec<ModuleItem> {
+	let file = source_map.new_source_file(FileName::Anon, source);
+	let lexer = Lexer::new(Syntax::Es(EsConfig::default()), EsVersion::Es2022, StringInput::from(&*file), None);
+	let mut module = Parser::new_from(lexer).parse_module().expect("Generated code should be valid");
+
+	let replacements: HashMap<Id, Ident> = idents
+		.iter()
+		.enumerate()
+		.map(|(index, ident)| ((format!("${}", index).into(), SyntaxContext::empty()), ident.clone()))
+		.collect();
+	module.body.visit_mut_with(&mut Renamer { replacements: &replacements });
+	module.body
+}
+
+/// Creates an identifier which cannot conflict with other identifiers, as it is renamed if necessary.
+fn fresh(name: &str) -> Ident {
+	Ident::new(name.into(), DUMMY_SP.apply_mark(Mark::new()))
+}
+
+fn export_name(name: &ModuleExportName) -> String {
+	match name {
+		ModuleExportName::Ident(ident) => ident.sym.to_string(),
+		ModuleExportName::Str(string) => string.value.to_string(),
+	}
+}
+
+/// Returns the identifiers declared by a declaration.
+fn declared_ids(decl: &Decl) -> Vec<Id> {
+	let mut ids = Vec::new();
+	match decl {
+		Decl::Class(class) => ids.push(class.ident.to_id()),
+		Decl::Fn(function) => ids.push(function.ident.to_id()),
+		Decl::Var(var) => {
+			for declarator in &var.decls {
+				pattern_ids(&declarator.name, &mut ids);
+			}
+		}
+		_ => {}
+	}
+	ids
+}
+
+fn pattern_ids(pattern: &Pat, ids: &mut Vec<Id>) {
+	match pattern {
+		Pat::Ident(ident) => ids.push(ident.id.to_id()),
+		Pat::Array(array) => array.elems.iter().flatten().for_each(|element| pattern_ids(element, ids)),
+		Pat::Rest(rest) => pattern_ids(&rest.arg, ids),
+		Pat::Object(object) => {
+			for prop in &object.props {
+				match prop {
+					ObjectPatProp::KeyValue(key_value) => pattern_ids(&key_value.value, ids),
+					ObjectPatProp::Assign(assign) => ids.push(assign.key.to_id()),
+					ObjectPatProp::Rest(rest) => pattern_ids(&rest.arg, ids),
+				}
+			}
+		}
+		Pat::Assign(assign) => pattern_ids(&assign.left, ids),
+		Pat::Invalid(_) | Pat::Expr(_) => {}
+	}
+}
+
+/// Returns an export name as an identifier if it is a valid identifier name, or as a string literal otherwise.
+fn name_literal(name: &str) -> String {
+	let mut chars = name.chars();
+	let is_identifier =
+		chars.next().is_some_and(|c| c.is_alphabetic() || c == '_' || c == '$') && chars.all(|c| c.is_alphanumeric() || c == '_' || c == '$');
+	if is_identifier {
+		String::from(name)
+	} else {
+		string_literal(name)
+	}
+}
+
+fn string_literal(string: &str) -> String {
+	let mut literal = String::with_capacity(string.len() + 2);
+	literal.push('"');
+	for c in string.chars() {
+		match c {
+			'"' => literal.push_str("\\\""),
+			'\\' => literal.push_str("\\\\"),
+			c if c.is_control() || c == '\u{2028}' || c == '\u{2029}' => literal.push_str(&format!("\\u{{{:x}}}", c as u32)),
+			c => literal.push(c),
+		}
+	}
+	literal.push('"');
+	literal
+}
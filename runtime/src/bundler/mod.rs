@@ -0,0 +1,130 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::{fmt, io};
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+use std::string::FromUtf8Error;
+
+use base64::Engine;
+use base64::prelude::BASE64_STANDARD;
+use swc_core::common::{DUMMY_SP, Globals, GLOBALS};
+use swc_core::common::comments::Comments;
+use swc_core::ecma::ast::{EsVersion, Module};
+use swc_core::ecma::codegen::{Config as CodegenConfig, Emitter};
+use swc_core::ecma::codegen::text_writer::JsWriter;
+use swc_core::ecma::transforms::base::fixer::fixer;
+use swc_core::ecma::transforms::base::hygiene::hygiene;
+use swc_core::ecma::visit::FoldWith;
+
+use ion::Context;
+
+use crate::bundler::graph::Graph;
+use crate::bundler::link::link;
+use crate::bundler::shake::tree_shake;
+use crate::modules::Loader;
+
+mod graph;
+mod link;
+mod shake;
+
+#[derive(Clone, Copy, Debug)]
+pub struct BundleOptions {
+	/// Removes top-level declarations which are never used.
+	pub tree_shake: bool,
+	/// Appends an inline source map, which maps the bundle to the original modules.
+	pub source_map: bool,
+}
+
+impl Default for BundleOptions {
+	fn default() -> BundleOptions {
+		BundleOptions { tree_shake: true, source_map: true }
+	}
+}
+
+#[derive(Clone, Debug)]
+pub struct Bundle {
+	pub code: String,
+	/// Paths of the bundled modules, in the order they are evaluated.
+	pub modules: Vec<PathBuf>,
+}
+
+/// Bundles a module and the modules it imports into a single ES module.
+///
+/// Modules are resolved with the loader, in the same way as when they are run. TypeScript is compiled, and JSON modules are inlined.
+/// Bare specifiers which do not resolve to files, such as standard modules, are left as imports of the bundle.
+///
+/// CommonJS modules cannot be bundled, and dynamic imports are left unchanged.
+pub fn bundle(cx: &Context, loader: &mut Loader, entry: &Path, options: BundleOptions) -> Result<Bundle, Error> {
+	let globals = Globals::default();
+	GLOBALS.set(&globals, || {
+		let mut graph = Graph::new();
+		let entry = graph.load(cx, loader, entry)?;
+		let mut body = link(&mut graph, entry)?;
+		if options.tree_shake {
+			tree_shake(&mut body);
+		}
+
+		let comments: &dyn Comments = &graph.comments;
+		let module = Module { span: DUMMY_SP, body, shebang: None };
+		let module = module.fold_with(&mut hygiene()).fold_with(&mut fixer(Some(comments)));
+
+		let mut buffer = Vec::new();
+		let mut mappings = Vec::new();
+		let mut emitter = Emitter {
+			cfg: CodegenConfig::default().with_target(EsVersion::Es2022),
+			cm: graph.source_map.clone(),
+			comments: Some(comments),
+			wr: JsWriter::new(graph.source_map.clone(), "\n", &mut buffer, options.source_map.then_some(&mut mappings)),
+		};
+		emitter.emit_module(&module).map_err(|_| Error::Emission)?;
+
+		let mut code = String::from_utf8(buffer)?;
+		if options.source_map {
+			let mut map = Vec::new();
+			graph
+				.source_map
+				.build_source_map(&mappings)
+				.to_writer(&mut map)
+				.map_err(|_| Error::Emission)?;
+			code.push_str("//# sourceMappingURL=data:application/json;base64,");
+			code.push_str(&BASE64_STANDARD.encode(map));
+			code.push('\n');
+		}
+
+		let modules = graph.order.iter().map(|index| graph.modules[*index].path.clone()).collect();
+		Ok(Bundle { code, modules })
+	})
+}
+
+#[derive(Debug)]
+pub enum Error {
+	Read(PathBuf, io::Error),
+	Resolution(String),
+	Parse(PathBuf),
+	Link(String),
+	Unsupported(String),
+	Emission,
+	FromUtf8(FromUtf8Error),
+}
+
+impl From<FromUtf8Error> for Error {
+	fn from(err: FromUtf8Error) -> Error {
+		Error::FromUtf8(err)
+	}
+}
+
+impl Display for Error {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		match self {
+			Error::Read(path, err) => write!(f, "Unable to read module {}: {}", path.display(), err),
+			Error::Parse(path) => write!(f, "Unable to parse module {}", path.display()),
+			Error::Resolution(message) | Error::Link(message) | Error::Unsupported(message) => f.write_str(message),
+			Error::Emission => f.write_str("Unable to emit the bundle"),
+			Error::FromUtf8(err) => f.write_str(&err.to_string()),
+		}
+	}
+}
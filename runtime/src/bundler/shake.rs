@@ -0,0 +1,115 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::collections::HashMap;
+
+use swc_core::ecma::ast::{Class, ClassMember, Decl, Expr, Id, Ident, ModuleItem, Pat, Prop, PropName, PropOrSpread, Stmt, UnaryOp, VarDeclarator};
+use swc_core::ecma::visit::{Visit, VisitWith};
+
+/// Counts the occurrences of each identifier, including the ones which declare it.
+#[derive(Default)]
+struct References(HashMap<Id, usize>);
+
+impl References {
+	fn is_unused(&self, ident: &Ident) -> bool {
+		self.0.get(&ident.to_id()).map_or(true, |count| *count <= 1)
+	}
+}
+
+impl Visit for References {
+	fn visit_ident(&mut self, ident: &Ident) {
+		*self.0.entry(ident.to_id()).or_default() += 1;
+	}
+}
+
+/// Removes top-level declarations which are never referenced, and whose evaluation has no side effects.
+/// Declarations are removed repeatedly, as removing a declaration can leave the declarations it referenced unused.
+pub(crate) fn tree_shake(body: &mut Vec<ModuleItem>) {
+	loop {
+		let mut references = References::default();
+		body.visit_with(&mut references);
+
+		let mut removed = false;
+		body.retain_mut(|item| {
+			let ModuleItem::Stmt(Stmt::Decl(decl)) = item else {
+				return true;
+			};
+			let unused = match decl {
+				Decl::Class(class) => references.is_unused(&class.ident) && is_pure_class(&class.class),
+				Decl::Fn(function) => references.is_unused(&function.ident),
+				Decl::Var(var) => {
+					let length = var.decls.len();
+					var.decls.retain(|declarator| !is_unused_declarator(declarator, &references));
+					removed |= var.decls.len() != length;
+					var.decls.is_empty()
+				}
+				_ => false,
+			};
+			removed |= unused;
+			!unused
+		});
+
+		if !removed {
+			break;
+		}
+	}
+}
+
+fn is_unused_declarator(declarator: &VarDeclarator, references: &References) -> bool {
+	let Pat::Ident(ident) = &declarator.name else {
+		return false;
+	};
+	references.is_unused(&ident.id) && declarator.init.as_deref().map_or(true, is_pure)
+}
+
+/// Checks if evaluating an expression cannot have side effects.
+/// Reading an identifier is assumed to be pure, although it can throw if the binding is uninitialised.
+fn is_pure(expr: &Expr) -> bool {
+	match expr {
+		Expr::Lit(_) | Expr::Ident(_) | Expr::Arrow(_) | Expr::Fn(_) => true,
+		Expr::Paren(paren) => is_pure(&paren.expr),
+		Expr::Unary(unary) => unary.op != UnaryOp::Delete && is_pure(&unary.arg),
+		Expr::Tpl(template) => template.exprs.iter().all(|expr| is_pure(expr)),
+		Expr::Array(array) => array
+			.elems
+			.iter()
+			.flatten()
+			.all(|element| element.spread.is_none() && is_pure(&element.expr)),
+		Expr::Object(object) => object.props.iter().all(|prop| match prop {
+			PropOrSpread::Prop(prop) => match &**prop {
+				Prop::Shorthand(_) => true,
+				Prop::KeyValue(key_value) => is_pure_key(&key_value.key) && is_pure(&key_value.value),
+				Prop::Getter(getter) => is_pure_key(&getter.key),
+				Prop::Setter(setter) => is_pure_key(&setter.key),
+				Prop::Method(method) => is_pure_key(&method.key),
+				Prop::Assign(_) => false,
+			},
+			PropOrSpread::Spread(_) => false,
+		}),
+		Expr::Class(class) => is_pure_class(&class.class),
+		_ => false,
+	}
+}
+
+fn is_pure_key(key: &PropName) -> bool {
+	!matches!(key, PropName::Computed(_))
+}
+
+/// Checks if defining a class cannot have side effects, which is the case if it has no decorators, computed keys or static initialisers.
+fn is_pure_class(class: &Class) -> bool {
+	class.decorators.is_empty()
+		&& class
+			.super_class
+			.as_deref()
+			.map_or(true, |super_class| matches!(super_class, Expr::Ident(_)))
+		&& class.body.iter().all(|member| match member {
+			ClassMember::Method(method) => is_pure_key(&method.key),
+			ClassMember::ClassProp(prop) => is_pure_key(&prop.key) && (!prop.is_static || prop.value.as_deref().map_or(true, is_pure)),
+			ClassMember::PrivateProp(prop) => !prop.is_static || prop.value.as_deref().map_or(true, is_pure),
+			ClassMember::StaticBlock(_) | ClassMember::AutoAccessor(_) => false,
+			_ => true,
+		})
+}
@@ -7,7 +7,7 @@
 use std::{fmt, io};
 use std::ffi::OsStr;
 use std::fmt::{Display, Formatter};
-use std::fs::{create_dir_all, metadata, read_dir, read_to_string, remove_dir_all, write};
+use std::fs::{create_dir_all, metadata, read, read_dir, read_to_string, remove_dir_all, remove_file, write};
 use std::path::{Path, PathBuf};
 use std::str::{from_utf8, Utf8Error};
 
@@ -17,8 +17,8 @@ use dirs::home_dir;
 use dunce::canonicalize;
 use sha3::{Digest, Sha3_512};
 use sourcemap::SourceMap;
+use url::Url;
 
-use crate::config::Config;
 use crate::typescript;
 use crate::typescript::compile_typescript;
 
@@ -39,9 +39,19 @@ impl Cache {
 		self.dir.as_path()
 	}
 
+	/// Returns the folder of downloaded remote modules.
+	pub fn remote_dir(&self) -> PathBuf {
+		self.dir.join("remote")
+	}
+
+	/// Returns the folder of persistent storage databases.
+	pub fn storage_dir(&self) -> PathBuf {
+		self.dir.join("storage")
+	}
+
 	pub fn clear(&self) -> io::Result<()> {
 		for entry in read_dir(&self.dir)? {
-			remove_dir_all(entry?.path())?;
+			remove_entry(&entry?.path())?;
 		}
 		create_dir_all(&self.dir)?;
 		Ok(())
@@ -100,7 +110,7 @@ impl Cache {
 		&self, path: P, folder: &Path, source: &str, source_hash: Option<&str>,
 	) -> Result<(String, SourceMap), Error> {
 		let path = path.as_ref();
-		if Config::global().typescript && path.extension() == Some(OsStr::new("ts")) {
+		if typescript::is_typescript(path) {
 			let source_name = path.file_name().and_then(OsStr::to_str).ok_or(Error::Other)?;
 			let source_file = path.file_stem().and_then(OsStr::to_str).ok_or(Error::Other)?;
 			let extension = path.extension().and_then(OsStr::to_str).ok_or(Error::Other)?;
@@ -133,6 +143,124 @@ impl Cache {
 			Err(Error::Other)
 		}
 	}
+
+	/// Returns the encoded stencil of a script or module if it was cached from the same source and compile options.
+	pub fn check_stencil<P: AsRef<Path>>(&self, path: P, folder: &Path, source: &str, module: bool, options: &str) -> Result<Vec<u8>, Error> {
+		let (stencil_file, hash_file) = stencil_files(path.as_ref(), folder, module)?;
+
+		if is_file(&stencil_file) && is_file(&hash_file) {
+			let cached_hash = read_to_string(&hash_file)?;
+			if cached_hash.trim() == stencil_hash(source, options) {
+				return Ok(read(&stencil_file)?);
+			}
+		}
+		Err(Error::Other)
+	}
+
+	/// Saves the encoded stencil of a script or module, keyed by whether it is a module, and the hash of its source and compile options.
+	pub fn save_stencil<P: AsRef<Path>>(
+		&self, path: P, folder: &Path, source: &str, module: bool, options: &str, stencil: &[u8],
+	) -> Result<(), Error> {
+		let (stencil_file, hash_file) = stencil_files(path.as_ref(), folder, module)?;
+
+		if !folder.exists() || !metadata(folder)?.is_dir() {
+			create_dir_all(folder)?;
+		}
+		write(stencil_file, stencil)?;
+		write(hash_file, stencil_hash(source, options))?;
+		Ok(())
+	}
+
+	/// Returns the URL after redirects and the path of the downloaded source of a remote module, if it is cached and unmodified.
+	pub fn check_remote(&self, url: &Url) -> Option<(Url, PathBuf)> {
+		let redirect_file = self.redirect_file(url);
+		let url = if is_file(&redirect_file) {
+			Url::parse(read_to_string(&redirect_file).ok()?.trim()).ok()?
+		} else {
+			url.clone()
+		};
+
+		let (source_file, hash_file) = self.remote_files(&url);
+		if is_file(&source_file) && is_file(&hash_file) {
+			let source = read_to_string(&source_file).ok()?;
+			let cached_hash = read_to_string(&hash_file).ok()?;
+			if cached_hash.trim() == hash(&source, None) {
+				return Some((url, source_file));
+			}
+		}
+		None
+	}
+
+	/// Saves the downloaded source of a remote module, which was redirected to `response_url`, returning the path it was saved to.
+	/// The source is stored under the URL after redirects, and the requested URL records the redirect.
+	pub fn save_remote(&self, url: &Url, response_url: &Url, source: &str) -> Result<PathBuf, Error> {
+		let (source_file, hash_file) = self.remote_files(response_url);
+		create_dir_all(source_file.parent().ok_or(Error::Other)?)?;
+		write(&source_file, source)?;
+		write(hash_file, hash(source, None))?;
+
+		let redirect_file = self.redirect_file(url);
+		if url != response_url {
+			create_dir_all(redirect_file.parent().ok_or(Error::Other)?)?;
+			write(redirect_file, response_url.as_str())?;
+		} else if is_file(&redirect_file) {
+			remove_file(redirect_file)?;
+		}
+		Ok(source_file)
+	}
+
+	/// Returns the path of a database of persistent storage, such as `localStorage`, which belongs to an origin or script.
+	/// Databases are stored in a folder keyed by the hash of the origin, so that each origin has separate storage.
+	pub fn storage_file(&self, origin: &str, name: &str) -> Result<PathBuf, Error> {
+		let folder = self.storage_dir().join(hash(origin, Some(16)));
+		create_dir_all(&folder)?;
+		Ok(folder.join(format!("{}.sqlite", name)))
+	}
+
+	/// Remote modules are stored in a folder keyed by the hash of their URL, keeping the file name so its extension is preserved.
+	fn remote_files(&self, url: &Url) -> (PathBuf, PathBuf) {
+		let folder = self.remote_folder(url);
+
+		let file_name = url
+			.path_segments()
+			.and_then(|mut segments| segments.next_back())
+			.filter(|name| Path::new(name).extension().is_some())
+			.unwrap_or("module.js");
+		let source_file = folder.join(file_name);
+		let hash_file = folder.join(format!("{}.sha512", file_name));
+		(source_file, hash_file)
+	}
+
+	fn redirect_file(&self, url: &Url) -> PathBuf {
+		self.remote_folder(url).join("redirect")
+	}
+
+	fn remote_folder(&self, url: &Url) -> PathBuf {
+		let host = url.host_str().unwrap_or("remote");
+		self.remote_dir().join(format!("{}-{}", host, hash(url.as_str(), Some(16))))
+	}
+}
+
+/// Removes a file or folder of the cache, such as the snapshot or the folder of remote modules.
+pub fn remove_entry(path: &Path) -> io::Result<()> {
+	if metadata(path)?.is_dir() {
+		remove_dir_all(path)
+	} else {
+		remove_file(path)
+	}
+}
+
+/// Scripts and modules compiled from the same file are stored separately, as their stencils are not interchangeable.
+fn stencil_files(path: &Path, folder: &Path, module: bool) -> Result<(PathBuf, PathBuf), Error> {
+	let source_name = path.file_name().and_then(OsStr::to_str).ok_or(Error::Other)?;
+	let kind = if module { "module" } else { "script" };
+	let stencil_file = folder.join(format!("{}.{}.stencil", source_name, kind));
+	let hash_file = folder.join(format!("{}.{}.stencil.sha512", source_name, kind));
+	Ok((stencil_file, hash_file))
+}
+
+fn stencil_hash(source: &str, options: &str) -> String {
+	hash(&format!("{}\0{}", options, source), None)
 }
 
 #[derive(Debug)]
@@ -173,7 +301,7 @@ impl Display for Error {
 	}
 }
 
-fn hash<T: AsRef<[u8]>>(bytes: T, len: Option<usize>) -> String {
+pub(crate) fn hash<T: AsRef<[u8]>>(bytes: T, len: Option<usize>) -> String {
 	let hash = BASE64_URL_SAFE.encode(Sha3_512::new().chain_update(bytes).finalize());
 	len.map_or(hash.clone(), |len| String::from(&hash[0..len]))
 }
@@ -7,18 +7,24 @@
 use std::cell::RefCell;
 use std::collections::hash_map::Entry;
 use std::collections::HashMap;
+use std::fs::read;
 use std::path::{Path, PathBuf};
 
+use base64::Engine;
+use base64::prelude::BASE64_STANDARD;
 use sourcemap::SourceMap;
 
-use ion::{Error, ErrorReport, Exception};
+use ion::{Error, ErrorReport, Exception, Stack};
 use ion::utils::normalise_path;
 
+const SOURCE_MAPPING_URL: &str = "sourceMappingURL=";
+const INLINE_SOURCE_MAP_PREFIX: &str = "data:application/json;base64,";
+
 thread_local!(static SOURCEMAP_CACHE: RefCell<HashMap<PathBuf, SourceMap>> = RefCell::new(HashMap::new()));
 
 pub fn find_sourcemap<P: AsRef<Path>>(path: P) -> Option<SourceMap> {
 	SOURCEMAP_CACHE.with_borrow_mut(|cache| {
-		let path = path.as_ref().to_path_buf();
+		let path = normalise_path(path);
 		match cache.entry(path) {
 			Entry::Occupied(o) => Some(o.get().clone()),
 			Entry::Vacant(_) => None,
@@ -39,6 +45,44 @@ pub fn save_sourcemap<P: AsRef<Path>>(path: P, sourcemap: SourceMap) -> bool {
 	})
 }
 
+/// Registers the source map referenced by the `//# sourceMappingURL=` comment of a script, if it has one.
+///
+/// Both inline base64 source maps and external `.map` files, relative to the script, are supported.
+/// Returns `true` if a source map was registered.
+pub fn register_sourcemap_from_source<P: AsRef<Path>>(path: P, source: &str) -> bool {
+	let path = path.as_ref();
+	let bytes = source_mapping_url(source).and_then(|url| {
+		if let Some(data) = url.strip_prefix(INLINE_SOURCE_MAP_PREFIX) {
+			BASE64_STANDARD.decode(data).ok()
+		} else if !url.contains("://") {
+			let map = path.parent().map(|parent| parent.join(url)).unwrap_or_else(|| PathBuf::from(url));
+			read(map).ok()
+		} else {
+			None
+		}
+	});
+
+	match bytes.and_then(|bytes| SourceMap::from_slice(&bytes).ok()) {
+		Some(sourcemap) => save_sourcemap(path, sourcemap),
+		None => false,
+	}
+}
+
+fn source_mapping_url(source: &str) -> Option<&str> {
+	source.lines().rev().map(str::trim).find_map(|line| {
+		let comment = line.strip_prefix("//# ").or_else(|| line.strip_prefix("//@ "))?;
+		comment.strip_prefix(SOURCE_MAPPING_URL).map(str::trim).filter(|url| !url.is_empty())
+	})
+}
+
+pub fn transform_stack_with_sourcemaps(stack: &mut Stack) {
+	for record in &mut stack.records {
+		if let Some(sourcemap) = find_sourcemap(&record.location.file) {
+			record.transform_with_sourcemap(&sourcemap);
+		}
+	}
+}
+
 pub fn transform_error_report_with_sourcemaps(report: &mut ErrorReport) {
 	if let Exception::Error(Error { location: Some(location), .. }) = &mut report.exception {
 		if let Some(sourcemap) = find_sourcemap(&location.file) {
@@ -46,10 +90,6 @@ pub fn transform_error_report_with_sourcemaps(report: &mut ErrorReport) {
 		}
 	}
 	if let Some(stack) = &mut report.stack {
-		for record in &mut stack.records {
-			if let Some(sourcemap) = find_sourcemap(&record.location.file) {
-				record.transform_with_sourcemap(&sourcemap);
-			}
-		}
+		transform_stack_with_sourcemaps(stack);
 	}
 }
@@ -4,10 +4,17 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/.
  */
 
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
+use dunce::canonicalize;
 use sourcemap::SourceMap;
 
+use ion::{Context, ErrorReport};
+use ion::stencil::Stencil;
+
+use crate::config::Config;
+use crate::snapshot::Snapshot;
+
 pub use cache::*;
 
 #[allow(clippy::module_inception)]
@@ -37,3 +44,75 @@ pub fn locate_in_cache<P: AsRef<Path>>(path: P, script: &str) -> Option<(String,
 		None => None,
 	}
 }
+
+/// Modules at least this large (in bytes) are compiled on a helper thread by [locate_module_stencil].
+pub const OFF_THREAD_COMPILE_THRESHOLD: usize = 128 * 1024;
+
+/// Compiles a script or module into a [Stencil], reusing the encoded stencil in the cache if the source is unchanged.
+/// Freshly compiled stencils are saved to the cache unless the code cache is disabled.
+pub fn locate_stencil<P: AsRef<Path>>(cx: &Context, path: P, script: &str, module: bool) -> Result<Stencil, ErrorReport> {
+	let path = path.as_ref();
+	if let Some(stencil) = check_snapshot(cx, path, script, module) {
+		return Ok(stencil);
+	}
+
+	let cache = stencil_cache(path);
+	if let Some(stencil) = check_stencil_cache(cx, cache.as_ref(), path, script, module) {
+		return Ok(stencil);
+	}
+
+	let stencil = if module {
+		Stencil::compile_module(cx, path, script)?
+	} else {
+		Stencil::compile_script(cx, path, script)?
+	};
+	save_stencil_cache(cx, cache.as_ref(), path, script, module, &stencil);
+	Ok(stencil)
+}
+
+/// Compiles a module like [locate_stencil], but compiles it on a helper thread if it is larger than [OFF_THREAD_COMPILE_THRESHOLD].
+pub async fn locate_module_stencil<P: AsRef<Path>>(cx: &Context, path: P, script: &str) -> Result<Stencil, ErrorReport> {
+	let path = path.as_ref();
+	if script.len() < OFF_THREAD_COMPILE_THRESHOLD {
+		return locate_stencil(cx, path, script, true);
+	}
+	if let Some(stencil) = check_snapshot(cx, path, script, true) {
+		return Ok(stencil);
+	}
+
+	let cache = stencil_cache(path);
+	if let Some(stencil) = check_stencil_cache(cx, cache.as_ref(), path, script, true) {
+		return Ok(stencil);
+	}
+
+	let stencil = Stencil::compile_module_off_thread(cx, path, script).await?;
+	save_stencil_cache(cx, cache.as_ref(), path, script, true, &stencil);
+	Ok(stencil)
+}
+
+/// Returns the stencil of a file from the [Snapshot], which is keyed by its canonical path.
+fn check_snapshot(cx: &Context, path: &Path, script: &str, module: bool) -> Option<Stencil> {
+	let snapshot = Snapshot::global()?;
+	let path = canonicalize(path).ok()?;
+	snapshot.stencil(cx, path.to_str()?, script, module)
+}
+
+fn stencil_cache(path: &Path) -> Option<(Cache, PathBuf)> {
+	let cache = Config::global().code_cache.then(Cache::new).flatten()?;
+	let folder = cache.find_folder(path).ok()?;
+	Some((cache, folder))
+}
+
+fn check_stencil_cache(cx: &Context, cache: Option<&(Cache, PathBuf)>, path: &Path, script: &str, module: bool) -> Option<Stencil> {
+	let (cache, folder) = cache?;
+	let bytes = cache.check_stencil(path, folder, script, module, &Stencil::options_key(path)).ok()?;
+	Stencil::decode(cx, &bytes)
+}
+
+fn save_stencil_cache(cx: &Context, cache: Option<&(Cache, PathBuf)>, path: &Path, script: &str, module: bool, stencil: &Stencil) {
+	if let Some((cache, folder)) = cache {
+		if let Some(bytes) = stencil.encode(cx) {
+			let _ = cache.save_stencil(path, folder, script, module, &Stencil::options_key(path), &bytes);
+		}
+	}
+}
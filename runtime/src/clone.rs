@@ -0,0 +1,138 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::ptr;
+
+use mozjs::glue::{
+	CopyJSStructuredCloneData, DeleteJSAutoStructuredCloneBuffer, GetLengthOfJSStructuredCloneData, NewJSAutoStructuredCloneBuffer,
+	WriteBytesToJSStructuredCloneData,
+};
+use mozjs::jsapi::{
+	CloneDataPolicy, JS_ReadStructuredClone, JS_STRUCTURED_CLONE_VERSION, JS_WriteStructuredClone, JSAutoStructuredCloneBuffer, StructuredCloneScope,
+};
+
+use ion::{Array, Context, Error, Exception, Object, ResultExc, Value};
+
+/// Represents a value serialised with the [structured clone algorithm](https://html.spec.whatwg.org/multipage/structured-data.html),
+/// which can be sent to another thread and deserialised in another runtime.
+///
+/// Serialisation is performed by the engine, which supports primitives, plain objects, arrays,
+/// [Map], [Set], [Date], [RegExp], [ArrayBuffer], typed arrays and circular references.
+/// Transferred [ArrayBuffer]s are detached, and their contents are moved into the serialised data.
+///
+/// [Map]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Map
+/// [Set]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Set
+/// [Date]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Date
+/// [RegExp]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/RegExp
+/// [ArrayBuffer]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/ArrayBuffer
+#[derive(Clone, Debug)]
+pub struct StructuredClone {
+	data: Vec<u8>,
+}
+
+impl StructuredClone {
+	/// Serialises a value, transferring the objects in `transfer`.
+	/// Returns [Err] with a `DataCloneError` if the value cannot be cloned or an object cannot be transferred.
+	pub fn serialise(cx: &Context, value: &Value, transfer: &[Object]) -> ResultExc<StructuredClone> {
+		let transfer = if transfer.is_empty() {
+			Value::undefined(cx)
+		} else {
+			let transfer: Vec<_> = transfer.iter().map(|object| object.as_value(cx).get()).collect();
+			Array::from_slice(cx, &transfer).as_value(cx)
+		};
+
+		let policy = clone_data_policy();
+		unsafe {
+			let buffer = Buffer::new();
+			let data = &mut (*buffer.0).data_;
+			let result = JS_WriteStructuredClone(
+				cx.as_ptr(),
+				value.handle().into(),
+				data,
+				StructuredCloneScope::DifferentProcess,
+				&policy,
+				ptr::null(),
+				ptr::null_mut(),
+				transfer.handle().into(),
+			);
+			if !result {
+				return Err(data_clone_error(cx));
+			}
+
+			let length = GetLengthOfJSStructuredCloneData(data);
+			let mut bytes = Vec::with_capacity(length);
+			CopyJSStructuredCloneData(data, bytes.as_mut_ptr());
+			bytes.set_len(length);
+			Ok(StructuredClone { data: bytes })
+		}
+	}
+
+	/// Creates a [StructuredClone] from bytes previously returned by [StructuredClone::as_bytes].
+	/// The bytes are only valid for the engine build that produced them, and must not contain transferred objects.
+	pub fn from_bytes(data: Vec<u8>) -> StructuredClone {
+		StructuredClone { data }
+	}
+
+	/// Returns the serialised bytes, which can be stored and deserialised later.
+	pub fn as_bytes(&self) -> &[u8] {
+		&self.data
+	}
+
+	/// Deserialises the value in the current realm.
+	pub fn deserialise<'cx>(&self, cx: &'cx Context) -> ResultExc<Value<'cx>> {
+		let policy = clone_data_policy();
+		let mut value = Value::undefined(cx);
+		unsafe {
+			let buffer = Buffer::new();
+			let data = &mut (*buffer.0).data_;
+			if !WriteBytesToJSStructuredCloneData(self.data.as_ptr(), self.data.len(), data) {
+				return Err(Error::new("Failed to allocate structured clone data", None).into());
+			}
+
+			let result = JS_ReadStructuredClone(
+				cx.as_ptr(),
+				data,
+				JS_STRUCTURED_CLONE_VERSION,
+				StructuredCloneScope::DifferentProcess,
+				value.handle_mut().into(),
+				&policy,
+				ptr::null(),
+				ptr::null_mut(),
+			);
+			if !result {
+				return Err(data_clone_error(cx));
+			}
+		}
+		Ok(value)
+	}
+}
+
+struct Buffer(*mut JSAutoStructuredCloneBuffer);
+
+impl Buffer {
+	fn new() -> Buffer {
+		Buffer(unsafe { NewJSAutoStructuredCloneBuffer(StructuredCloneScope::DifferentProcess, ptr::null()) })
+	}
+}
+
+impl Drop for Buffer {
+	fn drop(&mut self) {
+		unsafe {
+			DeleteJSAutoStructuredCloneBuffer(self.0);
+		}
+	}
+}
+
+fn clone_data_policy() -> CloneDataPolicy {
+	CloneDataPolicy {
+		allowIntraClusterClonableSharedObjects_: false,
+		allowSharedMemoryObjects_: false,
+	}
+}
+
+fn data_clone_error(cx: &Context) -> Exception {
+	Exception::new(cx).unwrap_or_else(|| Error::new("Value could not be cloned", None).with_name("DataCloneError").into())
+}
@@ -4,7 +4,12 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/.
  */
 
+use std::net::SocketAddr;
+use std::path::PathBuf;
 use std::sync::OnceLock;
+use std::time::Duration;
+
+use crate::permissions::Permissions;
 
 pub static CONFIG: OnceLock<Config> = OnceLock::new();
 
@@ -30,11 +35,41 @@ impl LogLevel {
 	}
 }
 
+/// Options of the inspector, which debuggers connect to with the Chrome DevTools Protocol.
 #[derive(Clone, Copy, Debug)]
+pub struct InspectOptions {
+	pub address: SocketAddr,
+	/// Waits for a debugger to connect, and pauses before the main script runs.
+	pub wait: bool,
+}
+
+/// Options of the profiler, which samples the main runtime from before the main script runs until it shuts down.
+#[derive(Clone, Debug)]
+pub struct ProfileOptions {
+	/// Path which the profile is written to, whose extension selects its [format](crate::profiler::ProfileFormat).
+	pub output: PathBuf,
+	pub interval: Duration,
+}
+
+#[derive(Clone, Debug)]
 pub struct Config {
 	pub log_level: LogLevel,
 	pub script: bool,
 	pub typescript: bool,
+	pub code_cache: bool,
+	pub import_map: Option<PathBuf>,
+	pub reload: bool,
+	pub cached_only: bool,
+	pub snapshot: Option<PathBuf>,
+	pub main: Option<PathBuf>,
+	pub location: Option<String>,
+	pub inspect: Option<InspectOptions>,
+	pub profile: Option<ProfileOptions>,
+	pub heap_snapshot_on_exit: Option<PathBuf>,
+	pub trace_exit: bool,
+	pub timeout: Option<Duration>,
+	pub permissions: Permissions,
+	pub args: Vec<String>,
 }
 
 impl Config {
@@ -50,6 +85,66 @@ impl Config {
 		Config { typescript, ..self }
 	}
 
+	pub fn code_cache(self, code_cache: bool) -> Config {
+		Config { code_cache, ..self }
+	}
+
+	pub fn import_map(self, import_map: Option<PathBuf>) -> Config {
+		Config { import_map, ..self }
+	}
+
+	pub fn reload(self, reload: bool) -> Config {
+		Config { reload, ..self }
+	}
+
+	/// Only uses remote modules from the cache, instead of fetching them.
+	pub fn cached_only(self, cached_only: bool) -> Config {
+		Config { cached_only, ..self }
+	}
+
+	pub fn snapshot(self, snapshot: Option<PathBuf>) -> Config {
+		Config { snapshot, ..self }
+	}
+
+	pub fn main(self, main: Option<PathBuf>) -> Config {
+		Config { main, ..self }
+	}
+
+	pub fn location(self, location: Option<String>) -> Config {
+		Config { location, ..self }
+	}
+
+	pub fn inspect(self, inspect: Option<InspectOptions>) -> Config {
+		Config { inspect, ..self }
+	}
+
+	pub fn profile(self, profile: Option<ProfileOptions>) -> Config {
+		Config { profile, ..self }
+	}
+
+	/// Writes a heap snapshot to the path when the main runtime shuts down.
+	pub fn heap_snapshot_on_exit(self, heap_snapshot_on_exit: Option<PathBuf>) -> Config {
+		Config { heap_snapshot_on_exit, ..self }
+	}
+
+	/// Reports the [active handles](crate::event_loop::handles::active_handles) of the main runtime if it exits while they are pending.
+	pub fn trace_exit(self, trace_exit: bool) -> Config {
+		Config { trace_exit, ..self }
+	}
+
+	/// Terminates the script running in the main runtime once the timeout has passed since it started.
+	pub fn timeout(self, timeout: Option<Duration>) -> Config {
+		Config { timeout, ..self }
+	}
+
+	pub fn permissions(self, permissions: Permissions) -> Config {
+		Config { permissions, ..self }
+	}
+
+	pub fn args(self, args: Vec<String>) -> Config {
+		Config { args, ..self }
+	}
+
 	pub fn global() -> &'static Config {
 		CONFIG.get().expect("Configuration not initialised")
 	}
@@ -61,6 +156,20 @@ impl Default for Config {
 			log_level: LogLevel::Error,
 			script: false,
 			typescript: true,
+			code_cache: true,
+			import_map: None,
+			reload: false,
+			cached_only: false,
+			snapshot: None,
+			main: None,
+			location: None,
+			inspect: None,
+			profile: None,
+			heap_snapshot_on_exit: None,
+			trace_exit: false,
+			timeout: None,
+			permissions: Permissions::default(),
+			args: Vec::new(),
 		}
 	}
 }
@@ -0,0 +1,221 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::cell::RefCell;
+use std::ffi::c_void;
+use std::fmt::Debug;
+use std::io::stderr;
+use std::mem;
+use std::rc::{Rc, Weak};
+use std::slice;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use mozjs::jsapi::{GCReason, JSContext, JSGCStatus, JSObject};
+use tracing::{debug, Event, Level, Subscriber};
+use tracing::field::{Field, Visit};
+use tracing_subscriber::{EnvFilter, fmt, Layer};
+use tracing_subscriber::filter::filter_fn;
+use tracing_subscriber::layer::{Context as LayerContext, SubscriberExt};
+use tracing_subscriber::util::SubscriberInitExt;
+
+use ion::{Context, ErrorReport, Function, Object, PersistentRooted, Value};
+use ion::conversions::ToValue;
+
+use crate::ContextExt;
+
+/// Environment variable which holds the filter of logged runtime internals, such as `runtime::modules=debug`.
+pub const LOG_ENV: &str = "SPIDERFIRE_LOG";
+/// Maximum number of diagnostics which are held between turns of the event loop. Later diagnostics are dropped.
+const MAX_PENDING: usize = 4096;
+
+type Pending = RefCell<Vec<Diagnostic>>;
+
+thread_local! {
+	/// Pending events of each runtime on this thread whose diagnostics channel has subscribers.
+	/// Events cannot be attributed to a runtime, so they are recorded for every such runtime on the thread they occur on.
+	static SINKS: RefCell<Vec<Weak<Pending>>> = RefCell::new(Vec::new());
+}
+
+/// Represents an event recorded by the runtime, as received by subscribers of the diagnostics channel.
+#[derive(Clone, Debug)]
+pub struct Diagnostic {
+	/// Module of the runtime which recorded the event, such as `runtime::event_loop`.
+	pub target: String,
+	pub level: Level,
+	pub message: String,
+	pub fields: Vec<(String, String)>,
+	/// Milliseconds since the UNIX epoch.
+	pub timestamp: f64,
+}
+
+impl<'cx> ToValue<'cx> for Diagnostic {
+	fn to_value(&self, cx: &'cx Context, value: &mut Value) {
+		let mut object = Object::new(cx);
+		object.set_as(cx, "target", &self.target);
+		object.set_as(cx, "level", &self.level.as_str().to_ascii_lowercase());
+		object.set_as(cx, "message", &self.message);
+		let mut fields = Object::new(cx);
+		for (name, value) in &self.fields {
+			fields.set_as(cx, name.as_str(), value);
+		}
+		object.set_as(cx, "fields", &fields);
+		object.set_as(cx, "timestamp", &self.timestamp);
+		object.to_value(cx, value);
+	}
+}
+
+#[derive(Default)]
+struct DiagnosticVisitor {
+	message: String,
+	fields: Vec<(String, String)>,
+}
+
+impl Visit for DiagnosticVisitor {
+	fn record_str(&mut self, field: &Field, value: &str) {
+		if field.name() == "message" {
+			self.message = String::from(value);
+		} else {
+			self.fields.push((String::from(field.name()), String::from(value)));
+		}
+	}
+
+	fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+		if field.name() == "message" {
+			self.message = format!("{:?}", value);
+		} else {
+			self.fields.push((String::from(field.name()), format!("{:?}", value)));
+		}
+	}
+}
+
+/// Records events for the diagnostics channel of the thread they occur on, while it has subscribers.
+struct DiagnosticsLayer;
+
+impl<S: Subscriber> Layer<S> for DiagnosticsLayer {
+	fn on_event(&self, event: &Event<'_>, _: LayerContext<'_, S>) {
+		let mut visitor = DiagnosticVisitor::default();
+		event.record(&mut visitor);
+		let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs_f64() * 1000.0;
+		let diagnostic = Diagnostic {
+			target: String::from(event.metadata().target()),
+			level: *event.metadata().level(),
+			message: visitor.message,
+			fields: visitor.fields,
+			timestamp,
+		};
+		SINKS.with_borrow_mut(|sinks| {
+			sinks.retain(|sink| {
+				let Some(pending) = sink.upgrade() else {
+					return false;
+				};
+				let mut pending = pending.borrow_mut();
+				if pending.len() < MAX_PENDING {
+					pending.push(diagnostic.clone());
+				}
+				true
+			})
+		});
+	}
+}
+
+/// Installs the subscriber of runtime events for the process.
+///
+/// Events matching `filter`, which uses the syntax of [EnvFilter], are logged to stderr.
+/// Events at the debug level and above are also recorded for the diagnostics channel, while it has subscribers.
+pub fn init_tracing(filter: Option<&str>) {
+	let log = filter.map(|filter| fmt::layer().with_writer(stderr).with_filter(EnvFilter::new(filter)));
+	let diagnostics = DiagnosticsLayer.with_filter(filter_fn(|metadata| {
+		*metadata.level() <= Level::DEBUG && SINKS.with_borrow(|sinks| !sinks.is_empty())
+	}));
+	let _ = tracing_subscriber::registry().with(log).with(diagnostics).try_init();
+}
+
+/// Holds the subscribers of the diagnostics channel of a runtime, and the events which have not been delivered to them.
+#[derive(Default)]
+pub(crate) struct Diagnostics {
+	subscribers: Vec<PersistentRooted<*mut JSObject>>,
+	pending: Rc<Pending>,
+	gc_start: Option<Instant>,
+}
+
+impl Drop for Diagnostics {
+	fn drop(&mut self) {
+		let pending = Rc::downgrade(&self.pending);
+		let _ = SINKS.try_with(|sinks| sinks.borrow_mut().retain(|sink| !sink.ptr_eq(&pending)));
+	}
+}
+
+/// Subscribes a function to the diagnostics channel of the runtime, which is called with each event recorded by the runtime.
+/// Events are delivered between turns of the event loop, and require [init_tracing] to have been called.
+///
+/// Only events which occur on the thread of the runtime are recorded.
+/// Events from other threads, such as those of blocking tasks or workers, are dropped.
+pub fn subscribe(cx: &Context, callback: &Function) {
+	let diagnostics = unsafe { &mut (*cx.get_private().as_ptr()).diagnostics };
+	if diagnostics.subscribers.is_empty() {
+		SINKS.with_borrow_mut(|sinks| sinks.push(Rc::downgrade(&diagnostics.pending)));
+	}
+	diagnostics.subscribers.push(PersistentRooted::new(callback.to_object(cx).handle().get()));
+}
+
+/// Unsubscribes a function from the diagnostics channel. Returns `false` if it was not subscribed.
+pub fn unsubscribe(cx: &Context, callback: &Function) -> bool {
+	let diagnostics = unsafe { &mut (*cx.get_private().as_ptr()).diagnostics };
+	let callback = callback.to_object(cx).handle().get();
+	let Some(index) = diagnostics.subscribers.iter().position(|subscriber| subscriber.get() == callback) else {
+		return false;
+	};
+	diagnostics.subscribers.remove(index);
+	if diagnostics.subscribers.is_empty() {
+		let pending = Rc::downgrade(&diagnostics.pending);
+		SINKS.with_borrow_mut(|sinks| sinks.retain(|sink| !sink.ptr_eq(&pending)));
+		diagnostics.pending.borrow_mut().clear();
+	}
+	true
+}
+
+/// Delivers the events recorded since the last turn of the event loop to the subscribers of the diagnostics channel.
+/// Errors thrown by subscribers are returned, as with other callbacks of the event loop, and the remaining events are dropped.
+pub(crate) fn dispatch(cx: &Context) -> Result<(), Option<ErrorReport>> {
+	let diagnostics = unsafe { &(*cx.get_private().as_ptr()).diagnostics };
+	if diagnostics.subscribers.is_empty() {
+		return Ok(());
+	}
+
+	let pending = mem::take(&mut *diagnostics.pending.borrow_mut());
+	let subscribers: Vec<_> = diagnostics
+		.subscribers
+		.iter()
+		.map(|subscriber| cx.root_object(subscriber.get()))
+		.collect();
+	for diagnostic in pending {
+		let diagnostic = diagnostic.as_value(cx);
+		for subscriber in &subscribers {
+			let Some(function) = Function::from_object(cx, subscriber) else {
+				continue;
+			};
+			function.call(cx, &Object::global(cx), slice::from_ref(&diagnostic))?;
+		}
+	}
+	Ok(())
+}
+
+/// Records the start and end of each major garbage collection.
+/// The callback is removed before the runtime is dropped, as it holds its start time in the private data of the context.
+pub(crate) unsafe extern "C" fn gc_callback(cx: *mut JSContext, status: JSGCStatus, reason: GCReason, _: *mut c_void) {
+	let cx = unsafe { Context::new_unchecked(cx) };
+	let diagnostics = unsafe { &mut (*cx.get_private().as_ptr()).diagnostics };
+	match status {
+		JSGCStatus::JSGC_BEGIN => {
+			diagnostics.gc_start = Some(Instant::now());
+			debug!(reason = ?reason, "Garbage collection started");
+		}
+		JSGCStatus::JSGC_END => {
+			let elapsed = diagnostics.gc_start.take().map(|start| start.elapsed().as_secs_f64() * 1000.0);
+			debug!(reason = ?reason, elapsed_ms = elapsed, "Garbage collection finished");
+		}
+	}
+}
@@ -4,29 +4,58 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/.
  */
 
+use std::cell::{Cell, RefCell};
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::pin::Pin;
 use std::task;
 use std::task::Poll;
 
 use futures::stream::FuturesUnordered;
 use futures::StreamExt;
 use mozjs::jsapi::JSObject;
-use tokio::task::JoinHandle;
+use tokio::task::{JoinError, JoinHandle};
 
 use ion::{Context, Error, ErrorKind, ErrorReport, Promise, ThrowException, Value};
 use ion::conversions::BoxedIntoValue;
 
+use crate::event_loop::handles::ActiveHandle;
+use crate::event_loop::microtasks::MicrotaskQueue;
+
 type FutureOutput = (Result<BoxedIntoValue, BoxedIntoValue>, *mut JSObject);
 
+/// Future in the queue, which is identified so that its [handle](ActiveHandle) can be removed once it completes.
+struct QueuedFuture {
+	id: u64,
+	handle: JoinHandle<FutureOutput>,
+}
+
+impl Future for QueuedFuture {
+	type Output = (u64, Result<FutureOutput, JoinError>);
+
+	fn poll(mut self: Pin<&mut Self>, wcx: &mut task::Context) -> Poll<Self::Output> {
+		let id = self.id;
+		Pin::new(&mut self.handle).poll(wcx).map(|output| (id, output))
+	}
+}
+
 #[derive(Default)]
 pub struct FutureQueue {
-	queue: FuturesUnordered<JoinHandle<FutureOutput>>,
+	queue: FuturesUnordered<QueuedFuture>,
+	handles: RefCell<BTreeMap<u64, ActiveHandle>>,
+	next: Cell<u64>,
+	/// Whether futures have been queued since the queue was last polled, which have not registered the event loop's waker yet.
+	queued: Cell<bool>,
 }
 
 impl FutureQueue {
-	pub fn run_futures(&mut self, cx: &Context, wcx: &mut task::Context) -> Result<(), Option<ErrorReport>> {
+	/// Settles the promises of completed futures, draining microtasks after each promise is settled.
+	pub fn run_futures(&mut self, cx: &Context, wcx: &mut task::Context, microtasks: Option<&MicrotaskQueue>) -> Result<(), Option<ErrorReport>> {
 		let mut results = Vec::new();
+		self.queued.set(false);
 
-		while let Poll::Ready(Some(item)) = self.queue.poll_next_unpin(wcx) {
+		while let Poll::Ready(Some((id, item))) = self.queue.poll_next_unpin(wcx) {
+			self.handles.borrow_mut().remove(&id);
 			match item {
 				Ok(item) => results.push(item),
 				Err(error) => {
@@ -54,13 +83,41 @@ impl FutureQueue {
 			if !result {
 				return Err(ErrorReport::new_with_exception_stack(cx));
 			}
+
+			if let Some(microtasks) = microtasks {
+				microtasks.run_jobs(cx)?;
+			}
 		}
 
 		Ok(())
 	}
 
-	pub fn enqueue(&self, handle: JoinHandle<FutureOutput>) {
-		self.queue.push(handle);
+	/// Queues a future, which is described by `description` in the active handles of the runtime until it completes.
+	pub fn enqueue(&self, handle: JoinHandle<FutureOutput>, description: ActiveHandle) {
+		let id = self.next.get();
+		self.next.set(id + 1);
+		self.handles.borrow_mut().insert(id, description);
+		self.queue.push(QueuedFuture { id, handle });
+		self.queued.set(true);
+	}
+
+	/// Aborts all pending futures, whose promises are never settled.
+	pub fn clear(&mut self) {
+		for future in self.queue.iter() {
+			future.handle.abort();
+		}
+		self.queue.clear();
+		self.handles.borrow_mut().clear();
+		self.queued.set(false);
+	}
+
+	/// Adds the descriptions of the pending futures to `handles`, in the order they were queued in.
+	pub fn handles(&self, handles: &mut Vec<ActiveHandle>) {
+		handles.extend(self.handles.borrow().values().cloned());
+	}
+
+	pub fn is_queued(&self) -> bool {
+		self.queued.get()
 	}
 
 	pub fn is_empty(&self) -> bool {
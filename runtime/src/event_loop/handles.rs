@@ -0,0 +1,175 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::rc::Rc;
+
+use ion::{Context, Object, Value};
+use ion::conversions::ToValue;
+use ion::stack::Stack;
+
+use crate::cache::map::transform_stack_with_sourcemaps;
+use crate::ContextExt;
+use crate::event_loop::signals::Signal;
+
+const MAX_FRAMES: u32 = 32;
+
+/// Kinds of pending work which keep the event loop alive.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum HandleKind {
+	Timer,
+	Interval,
+	Immediate,
+	/// Macrotask queued with `queueMacrotask`, or by the runtime, such as for `AbortSignal.timeout`.
+	Macrotask,
+	Fetch,
+	Socket,
+	Server,
+	Subprocess,
+	/// Read from, or write to, a stream backed by the runtime, such as standard input or a connection.
+	Stream,
+	/// Remote module graph, which is being fetched for a dynamic import.
+	Module,
+	/// Port which receives messages from a worker or channel.
+	Port,
+	/// Promise which is settled by the runtime, for any other operation.
+	Promise,
+	Other,
+}
+
+impl HandleKind {
+	pub fn name(self) -> &'static str {
+		match self {
+			HandleKind::Timer => "timer",
+			HandleKind::Interval => "interval",
+			HandleKind::Immediate => "immediate",
+			HandleKind::Macrotask => "macrotask",
+			HandleKind::Fetch => "fetch",
+			HandleKind::Socket => "socket",
+			HandleKind::Server => "server",
+			HandleKind::Subprocess => "subprocess",
+			HandleKind::Stream => "stream",
+			HandleKind::Module => "module",
+			HandleKind::Port => "port",
+			HandleKind::Promise => "promise",
+			HandleKind::Other => "other",
+		}
+	}
+}
+
+impl Display for HandleKind {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		f.write_str(self.name())
+	}
+}
+
+/// Describes pending work which keeps the event loop alive, as listed by [active_handles].
+#[derive(Clone, Debug)]
+pub struct ActiveHandle {
+	pub kind: HandleKind,
+	pub description: String,
+	/// Stack at which the work was created, which is only captured while [creation stacks](capture_handle_stacks) are enabled.
+	pub stack: Option<Rc<str>>,
+}
+
+impl ActiveHandle {
+	/// Creates a description of pending work, capturing the current stack if creation stacks are enabled.
+	pub fn new<D: Into<String>>(cx: &Context, kind: HandleKind, description: D) -> ActiveHandle {
+		ActiveHandle {
+			kind,
+			description: description.into(),
+			stack: creation_stack(cx),
+		}
+	}
+}
+
+impl Display for ActiveHandle {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		write!(f, "{}: {}", self.kind, self.description)?;
+		if let Some(stack) = &self.stack {
+			write!(f, "\n{}", stack)?;
+		}
+		Ok(())
+	}
+}
+
+impl<'cx> ToValue<'cx> for ActiveHandle {
+	fn to_value(&self, cx: &'cx Context, value: &mut Value) {
+		let mut object = Object::new(cx);
+		object.set_as(cx, "type", self.kind.name());
+		object.set_as(cx, "description", &self.description);
+		if let Some(stack) = &self.stack {
+			object.set_as(cx, "stack", &**stack);
+		}
+		object.to_value(cx, value);
+	}
+}
+
+/// Captures the current stack, mapped to the original sources, if creation stacks are enabled.
+pub(crate) fn creation_stack(cx: &Context) -> Option<Rc<str>> {
+	let event_loop = unsafe { &(*cx.get_private().as_ptr()).event_loop };
+	if !event_loop.handle_stacks {
+		return None;
+	}
+	let mut stack = Stack::from_capture_with_max_frames(cx, MAX_FRAMES).filter(|stack| !stack.is_empty())?;
+	transform_stack_with_sourcemaps(&mut stack);
+	Some(Rc::from(stack.format()))
+}
+
+/// Sets whether the stacks at which timers, futures and other pending work are created are captured.
+/// This is disabled by default, as capturing a stack for each timer and promise is costly.
+pub fn capture_handle_stacks(cx: &Context, enabled: bool) {
+	unsafe {
+		(*cx.get_private().as_ptr()).event_loop.handle_stacks = enabled;
+	}
+}
+
+/// Lists the pending work which keeps the event loop of the runtime alive.
+/// Timers which have been unreferenced, and ports without listeners, are excluded as they do not keep it alive.
+pub fn active_handles(cx: &Context) -> Vec<ActiveHandle> {
+	let event_loop = unsafe { &(*cx.get_private().as_ptr()).event_loop };
+	let mut handles = Vec::new();
+	if let Some(macrotasks) = &event_loop.macrotasks {
+		macrotasks.handles(cx, &mut handles);
+	}
+	if let Some(futures) = &event_loop.futures {
+		futures.handles(&mut handles);
+	}
+	event_loop.messages.handles(&mut handles);
+	event_loop.keep_alive_handles(&mut handles);
+	handles
+}
+
+/// Reports the pending work which was still keeping the event loop alive when the runtime exits,
+/// such as when `process.exit()` is called or the process is interrupted.
+///
+/// Creation stacks are captured from this point onwards, and the process is interrupted by `SIGINT` instead of terminated,
+/// so that the report can be printed.
+pub fn trace_exit(cx: &Context) {
+	capture_handle_stacks(cx, true);
+	let event_loop = unsafe { &mut (*cx.get_private().as_ptr()).event_loop };
+	event_loop.trace_exit = true;
+	if let Err(error) = event_loop.signals.register(Signal::Interrupt) {
+		eprintln!("{}", error.format());
+	}
+}
+
+pub(crate) fn report_on_exit(cx: &Context) {
+	let event_loop = unsafe { &(*cx.get_private().as_ptr()).event_loop };
+	if !event_loop.trace_exit {
+		return;
+	}
+	let handles = active_handles(cx);
+	if handles.is_empty() {
+		return;
+	}
+
+	eprintln!("Exiting with {} Active Handle(s) keeping the Event Loop Alive:", handles.len());
+	for handle in handles {
+		eprintln!("{}", handle);
+	}
+}
@@ -4,29 +4,40 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/.
  */
 
-use std::collections::HashMap;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
 use std::fmt;
+use std::mem;
 use std::fmt::{Debug, Formatter};
-use std::sync::Arc;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::rc::Rc;
 
 use chrono::{DateTime, Duration, Utc};
 use mozjs::jsapi::JSFunction;
 use mozjs::jsval::JSVal;
+use tracing::trace;
 
-use ion::{Context, ErrorReport, Function, Object, Value};
+use ion::{Context, ErrorReport, Function, Object, PersistentRooted, Value};
+
+use crate::event_loop::handles::{ActiveHandle, creation_stack, HandleKind};
+use crate::event_loop::microtasks::MicrotaskQueue;
+
+/// Timers nested deeper than this are clamped to [MINIMUM_DELAY_NESTED].
+const MAXIMUM_NESTING: u8 = 5;
+const MINIMUM_DELAY_NESTED: i64 = 4;
+/// Number of immediates which run in a turn of the event loop before expired timers take precedence.
+const IMMEDIATE_BUDGET: usize = 1024;
+/// Minimum number of entries in the heap of deadlines before it is compacted.
+const COMPACTION_THRESHOLD: usize = 1024;
 
 pub struct SignalMacrotask {
-	callback: Box<dyn FnOnce()>,
-	terminate: Arc<AtomicBool>,
+	callback: Box<dyn FnOnce(&Context)>,
 	scheduled: DateTime<Utc>,
 }
 
 impl SignalMacrotask {
-	pub fn new(callback: Box<dyn FnOnce()>, terminate: Arc<AtomicBool>, duration: Duration) -> SignalMacrotask {
+	pub fn new(callback: Box<dyn FnOnce(&Context)>, duration: Duration) -> SignalMacrotask {
 		SignalMacrotask {
 			callback,
-			terminate,
 			scheduled: Utc::now() + duration,
 		}
 	}
@@ -34,32 +45,31 @@ impl SignalMacrotask {
 
 impl Debug for SignalMacrotask {
 	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-		f.debug_struct("SignalMacrotask")
-			.field("terminate", &self.terminate.as_ref())
-			.field("scheduled", &self.scheduled)
-			.finish()
+		f.debug_struct("SignalMacrotask").field("scheduled", &self.scheduled).finish()
 	}
 }
 
 #[derive(Debug)]
 pub struct TimerMacrotask {
-	callback: *mut JSFunction,
-	arguments: Vec<JSVal>,
+	callback: PersistentRooted<*mut JSFunction>,
+	arguments: Vec<PersistentRooted<JSVal>>,
 	repeat: bool,
 	scheduled: DateTime<Utc>,
 	duration: Duration,
 	nesting: u8,
+	stack: Option<Rc<str>>,
 }
 
 impl TimerMacrotask {
-	pub fn new(callback: Function, arguments: Vec<JSVal>, repeat: bool, duration: Duration) -> TimerMacrotask {
+	pub fn new(cx: &Context, callback: Function, arguments: Vec<JSVal>, repeat: bool, duration: Duration) -> TimerMacrotask {
 		TimerMacrotask {
-			callback: callback.get(),
-			arguments,
+			callback: PersistentRooted::new(callback.get()),
+			arguments: arguments.into_iter().map(PersistentRooted::new).collect(),
 			repeat,
 			duration,
 			scheduled: Utc::now(),
 			nesting: 0,
+			stack: creation_stack(cx),
 		}
 	}
 
@@ -69,19 +79,56 @@ impl TimerMacrotask {
 		}
 		self.repeat
 	}
+
+	/// Sets the nesting level of the timer, which is one more than that of the timer it was created in.
+	/// Deeply nested timers are clamped to a minimum delay, as in browsers.
+	fn nest(&mut self, parent: u8) {
+		if parent > MAXIMUM_NESTING {
+			self.duration = self.duration.max(Duration::milliseconds(MINIMUM_DELAY_NESTED));
+		}
+		self.nesting = parent.saturating_add(1);
+	}
 }
 
 #[derive(Debug)]
 pub struct UserMacrotask {
-	callback: *mut JSFunction,
+	callback: PersistentRooted<*mut JSFunction>,
 	scheduled: DateTime<Utc>,
+	stack: Option<Rc<str>>,
 }
 
 impl UserMacrotask {
-	pub fn new(callback: Function) -> UserMacrotask {
+	pub fn new(cx: &Context, callback: Function) -> UserMacrotask {
 		UserMacrotask {
-			callback: callback.get(),
+			callback: PersistentRooted::new(callback.get()),
 			scheduled: Utc::now(),
+			stack: creation_stack(cx),
+		}
+	}
+}
+
+/// Callback queued with `setImmediate`, which runs once the expired timers of the current turn have run.
+#[derive(Debug)]
+pub struct ImmediateMacrotask {
+	callback: PersistentRooted<*mut JSFunction>,
+	arguments: Vec<PersistentRooted<JSVal>>,
+	stack: Option<Rc<str>>,
+}
+
+impl ImmediateMacrotask {
+	pub fn new(cx: &Context, callback: Function, arguments: Vec<JSVal>) -> ImmediateMacrotask {
+		ImmediateMacrotask {
+			callback: PersistentRooted::new(callback.get()),
+			arguments: arguments.into_iter().map(PersistentRooted::new).collect(),
+			stack: creation_stack(cx),
+		}
+	}
+
+	fn handle(&self, cx: &Context) -> ActiveHandle {
+		ActiveHandle {
+			kind: HandleKind::Immediate,
+			description: callback_name(cx, &self.callback),
+			stack: self.stack.clone(),
 		}
 	}
 }
@@ -91,133 +138,279 @@ pub enum Macrotask {
 	Signal(SignalMacrotask),
 	Timer(TimerMacrotask),
 	User(UserMacrotask),
+	Immediate(ImmediateMacrotask),
 }
 
+/// Queue of the macrotasks of the event loop.
+///
+/// Timers and other macrotasks with deadlines are kept in a binary heap, ordered by their deadlines, then their IDs.
+/// Removing a macrotask, or rescheduling a repeating timer, leaves its previous entry in the heap, which is skipped once it is reached.
+/// Immediates are kept in a FIFO queue, and cleared immediates are skipped once they are reached.
 #[derive(Debug, Default)]
 pub struct MacrotaskQueue {
-	pub(crate) map: HashMap<u32, Macrotask>,
-	pub(crate) nesting: u8,
-	next: Option<u32>,
+	tasks: HashMap<u32, Macrotask>,
+	deadlines: BinaryHeap<Reverse<(DateTime<Utc>, u32)>>,
+	/// Nesting level of the timer which is currently running, or 0 if no timer is running.
+	nesting: u8,
+	/// Timers which do not keep the event loop alive.
+	/// Outside of the timer phase, this only contains timers which are pending.
+	unrefed: HashSet<u32>,
+	running: Option<u32>,
+	immediates: VecDeque<u32>,
+	pending_immediates: HashMap<u32, ImmediateMacrotask>,
 	latest: Option<u32>,
 }
 
 impl Macrotask {
 	pub fn run(self, cx: &Context) -> Result<Option<Macrotask>, Option<ErrorReport>> {
 		if let Macrotask::Signal(signal) = self {
-			(signal.callback)();
+			(signal.callback)(cx);
 			return Ok(None);
 		}
 		let (callback, args) = match &self {
-			Macrotask::Timer(timer) => (timer.callback, timer.arguments.clone()),
-			Macrotask::User(user) => (user.callback, Vec::new()),
+			Macrotask::Timer(timer) => (&timer.callback, timer.arguments.as_slice()),
+			Macrotask::User(user) => (&user.callback, &[][..]),
+			Macrotask::Immediate(immediate) => (&immediate.callback, immediate.arguments.as_slice()),
 			_ => unreachable!(),
 		};
 
-		let callback = Function::from(cx.root_function(callback));
-		let args: Vec<_> = args.into_iter().map(|value| Value::from(cx.root_value(value))).collect();
+		let callback = Function::from(cx.root_function(callback.get()));
+		let args: Vec<_> = args.iter().map(|value| Value::from(cx.root_value(value.get()))).collect();
 
 		callback.call(cx, &Object::global(cx), args.as_slice()).map(|_| (Some(self)))
 	}
 
-	fn terminate(&self) -> bool {
+	/// Returns the time at which the macrotask is due to run.
+	fn deadline(&self) -> DateTime<Utc> {
 		match self {
-			Macrotask::Signal(signal) => signal.terminate.load(Ordering::SeqCst),
-			_ => false,
+			Macrotask::Signal(signal) => signal.scheduled,
+			Macrotask::Timer(timer) => timer.scheduled + timer.duration,
+			Macrotask::User(user) => user.scheduled,
+			Macrotask::Immediate(_) => DateTime::<Utc>::MIN_UTC,
 		}
 	}
 
 	fn remaining(&self) -> Duration {
-		match self {
-			Macrotask::Signal(signal) => signal.scheduled - Utc::now(),
-			Macrotask::Timer(timer) => timer.scheduled + timer.duration - Utc::now(),
-			Macrotask::User(user) => user.scheduled - Utc::now(),
-		}
+		self.deadline() - Utc::now()
+	}
+
+	/// Describes the macrotask as an [ActiveHandle], naming its callback and when it is due.
+	fn handle(&self, cx: &Context) -> ActiveHandle {
+		let (kind, description, stack) = match self {
+			Macrotask::Signal(_) => (HandleKind::Macrotask, String::from("Internal Timer"), None),
+			Macrotask::Timer(timer) => {
+				let (kind, preposition) = if timer.repeat {
+					(HandleKind::Interval, "every")
+				} else {
+					(HandleKind::Timer, "after")
+				};
+				let description = format!(
+					"{} {} {} ms, due in {} ms",
+					callback_name(cx, &timer.callback),
+					preposition,
+					timer.duration.num_milliseconds(),
+					self.remaining().num_milliseconds().max(0)
+				);
+				(kind, description, timer.stack.clone())
+			}
+			Macrotask::User(user) => (HandleKind::Macrotask, callback_name(cx, &user.callback), user.stack.clone()),
+			Macrotask::Immediate(immediate) => return immediate.handle(cx),
+		};
+		ActiveHandle { kind, description, stack }
 	}
 }
 
+fn callback_name(cx: &Context, callback: &PersistentRooted<*mut JSFunction>) -> String {
+	let callback = Function::from(cx.root_function(callback.get()));
+	callback
+		.name(cx)
+		.filter(|name| !name.is_empty())
+		.unwrap_or_else(|| String::from("(anonymous)"))
+}
+
 impl MacrotaskQueue {
-	pub fn run_jobs(&mut self, cx: &Context) -> Result<(), Option<ErrorReport>> {
-		self.find_next();
-		while let Some(next) = self.next {
-			let macrotask = { self.map.remove_entry(&next) };
-			if let Some((id, macrotask)) = macrotask {
-				let macrotask = macrotask.run(cx)?;
-
-				if let Some(Macrotask::Timer(mut timer)) = macrotask {
-					if timer.reset() {
-						self.map.insert(id, Macrotask::Timer(timer));
-					}
+	/// Runs the timer phase, then the immediate phase of a turn of the event loop.
+	///
+	/// Macrotasks which expired before the timer phase run in order of their deadlines, with ties broken by the order they were queued in.
+	/// Repeating timers are rescheduled after they run, so they run at most once per turn.
+	///
+	/// Immediates run in the order they were queued in, excluding those queued during the immediate phase, which run in the next turn.
+	/// Once [IMMEDIATE_BUDGET] immediates have run, the rest are left for the next turn if a timer has expired, so that timers are not starved.
+	/// Microtasks are drained after each callback.
+	pub fn run_jobs(&mut self, cx: &Context, microtasks: Option<&MicrotaskQueue>) -> Result<(), Option<ErrorReport>> {
+		let now = Utc::now();
+		while let Some(id) = self.pop_expired(now) {
+			let Some(macrotask) = self.tasks.remove(&id) else {
+				continue;
+			};
+			if let Macrotask::Timer(timer) = &macrotask {
+				self.nesting = timer.nesting;
+			}
+			trace!(id, "Running macrotask");
+			self.running = Some(id);
+			let result = macrotask.run(cx);
+			let cleared = self.running.take().is_none();
+			let parent = mem::take(&mut self.nesting);
+
+			match result? {
+				Some(Macrotask::Timer(mut timer)) if !cleared && timer.reset() => {
+					timer.nest(parent);
+					self.schedule(id, Macrotask::Timer(timer));
 				}
+				_ => {
+					self.unrefed.remove(&id);
+				}
+			}
+
+			if let Some(microtasks) = microtasks {
+				microtasks.run_jobs(cx)?;
+			}
+		}
+
+		let Some(&last) = self.immediates.back() else {
+			return Ok(());
+		};
+		let mut ran = 0;
+		while self.immediates.front().is_some_and(|id| *id <= last) {
+			if ran >= IMMEDIATE_BUDGET && self.next_deadline().is_some_and(|deadline| deadline <= Utc::now()) {
+				trace!(remaining = self.pending_immediates.len(), "Deferring immediates to run expired timers");
+				break;
+			}
+			let id = self.immediates.pop_front().unwrap();
+			let Some(immediate) = self.pending_immediates.remove(&id) else {
+				continue;
+			};
+			trace!(id, "Running immediate");
+			ran += 1;
+			Macrotask::Immediate(immediate).run(cx)?;
+
+			if let Some(microtasks) = microtasks {
+				microtasks.run_jobs(cx)?;
 			}
-			self.find_next();
 		}
 
 		Ok(())
 	}
 
 	pub fn enqueue(&mut self, mut macrotask: Macrotask, id: Option<u32>) -> u32 {
-		let index = id.unwrap_or_else(|| self.latest.map(|l| l + 1).unwrap_or(0));
-
-		let next = self.next.and_then(|next| self.map.get(&next));
-		if let Some(next) = next {
-			if macrotask.remaining() < next.remaining() {
-				self.set_next(index, &macrotask);
-			}
-		} else {
-			self.set_next(index, &macrotask);
-		}
+		let index = id.unwrap_or_else(|| self.latest.map(|l| l + 1).unwrap_or(1));
 
 		if let Macrotask::Timer(timer) = &mut macrotask {
-			self.nesting += 1;
-			timer.nesting = self.nesting;
+			timer.nest(self.nesting);
 		}
 
 		self.latest = Some(index);
-		self.map.insert(index, macrotask);
+		self.schedule(index, macrotask);
 
 		index
 	}
 
 	pub fn remove(&mut self, id: u32) {
-		if self.map.remove(&id).is_some() {
-			if let Some(next) = self.next {
-				if next == id {
-					self.next = None;
-				}
-			}
+		if self.tasks.remove(&id).is_some() {
+			self.unrefed.remove(&id);
+			self.compact();
 		}
 	}
 
-	pub fn find_next(&mut self) {
-		let mut next: Option<(u32, &Macrotask)> = None;
-		let mut to_remove = Vec::new();
-		for (id, macrotask) in &self.map {
-			if macrotask.terminate() {
-				to_remove.push(*id);
-				continue;
-			}
-			if let Some((_, next_macrotask)) = next {
-				if macrotask.remaining() < next_macrotask.remaining() {
-					next = Some((*id, macrotask));
-				}
-			} else if macrotask.remaining() <= Duration::zero() {
-				next = Some((*id, macrotask));
-			}
+	/// Queues an immediate, which runs in the immediate phase of the next turn of the event loop.
+	pub fn enqueue_immediate(&mut self, immediate: ImmediateMacrotask) -> u32 {
+		let index = self.latest.map(|l| l + 1).unwrap_or(1);
+		self.latest = Some(index);
+		self.immediates.push_back(index);
+		self.pending_immediates.insert(index, immediate);
+		index
+	}
+
+	pub fn clear_immediate(&mut self, id: u32) {
+		self.pending_immediates.remove(&id);
+		if self.pending_immediates.is_empty() {
+			self.immediates.clear();
 		}
-		let next = next.map(|(id, _)| id);
-		for id in to_remove.iter_mut() {
-			self.map.remove(id);
+	}
+
+	/// Removes a timer, including one which is currently running, so that it does not repeat.
+	/// Other macrotasks with the same ID are left untouched.
+	pub fn clear_timer(&mut self, id: u32) {
+		if matches!(self.tasks.get(&id), Some(Macrotask::Timer(_))) {
+			self.remove(id);
+		} else if self.running == Some(id) {
+			self.running = None;
 		}
-		self.next = next;
+		self.unrefed.remove(&id);
 	}
 
-	pub fn set_next(&mut self, index: u32, macrotask: &Macrotask) {
-		if macrotask.remaining() < Duration::zero() {
-			self.next = Some(index);
+	/// Sets whether a timer keeps the event loop alive while it is pending.
+	pub fn ref_timer(&mut self, id: u32, refed: bool) {
+		if refed {
+			self.unrefed.remove(&id);
+		} else if self.running == Some(id) || matches!(self.tasks.get(&id), Some(Macrotask::Timer(_))) {
+			self.unrefed.insert(id);
 		}
 	}
 
+	/// Removes all pending macrotasks, including timers which are currently running.
+	pub fn clear(&mut self) {
+		self.tasks.clear();
+		self.deadlines.clear();
+		self.immediates.clear();
+		self.pending_immediates.clear();
+		self.unrefed.clear();
+		self.running = None;
+	}
+
+	pub fn has_immediates(&self) -> bool {
+		!self.pending_immediates.is_empty()
+	}
+
+	/// Returns the earliest deadline of the pending macrotasks, including those which do not keep the event loop alive.
+	pub fn next_deadline(&mut self) -> Option<DateTime<Utc>> {
+		self.peek().map(|(deadline, _)| deadline)
+	}
+
+	/// Adds the macrotasks which keep the event loop alive to `handles`, in order of their IDs.
+	pub fn handles(&self, cx: &Context, handles: &mut Vec<ActiveHandle>) {
+		let mut ids: Vec<_> = self.tasks.keys().filter(|id| !self.unrefed.contains(id)).copied().collect();
+		ids.sort_unstable();
+		handles.extend(ids.into_iter().map(|id| self.tasks[&id].handle(cx)));
+		let immediates = self.immediates.iter().filter_map(|id| self.pending_immediates.get(id));
+		handles.extend(immediates.map(|immediate| immediate.handle(cx)));
+	}
+
+	/// Checks if there are no macrotasks which keep the event loop alive.
 	pub fn is_empty(&self) -> bool {
-		self.map.is_empty()
+		self.pending_immediates.is_empty() && self.tasks.len() == self.unrefed.len()
+	}
+
+	fn schedule(&mut self, id: u32, macrotask: Macrotask) {
+		self.deadlines.push(Reverse((macrotask.deadline(), id)));
+		self.tasks.insert(id, macrotask);
+	}
+
+	/// Returns the earliest deadline and the ID of its macrotask, discarding entries of macrotasks which were removed or rescheduled.
+	fn peek(&mut self) -> Option<(DateTime<Utc>, u32)> {
+		while let Some(&Reverse((deadline, id))) = self.deadlines.peek() {
+			if self.tasks.get(&id).is_some_and(|macrotask| macrotask.deadline() == deadline) {
+				return Some((deadline, id));
+			}
+			self.deadlines.pop();
+		}
+		None
+	}
+
+	fn pop_expired(&mut self, now: DateTime<Utc>) -> Option<u32> {
+		let (deadline, id) = self.peek()?;
+		if deadline > now {
+			return None;
+		}
+		self.deadlines.pop();
+		Some(id)
+	}
+
+	/// Rebuilds the heap of deadlines once most of its entries belong to removed macrotasks,
+	/// so that clearing many timers does not grow it indefinitely.
+	fn compact(&mut self) {
+		if self.deadlines.len() > COMPACTION_THRESHOLD && self.deadlines.len() > 2 * self.tasks.len() {
+			self.deadlines = self.tasks.iter().map(|(id, macrotask)| Reverse((macrotask.deadline(), *id))).collect();
+		}
 	}
 }
@@ -0,0 +1,181 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::task;
+use std::task::Poll;
+
+use mozjs::jsapi::JSObject;
+use tokio::sync::mpsc::UnboundedReceiver;
+
+use ion::{Context, ErrorReport, Exception, Object, PersistentRooted, Value, Weak};
+use ion::flags::PropertyFlags;
+
+use crate::clone::StructuredClone;
+use crate::event_loop::handles::{ActiveHandle, HandleKind};
+use crate::event_loop::microtasks::MicrotaskQueue;
+use crate::globals::event::{Event, EventTarget};
+
+/// Represents a message sent between runtimes on different threads.
+#[derive(Debug)]
+pub enum Message {
+	Data(StructuredClone),
+	/// An uncaught error, formatted by the runtime it occurred in.
+	Error(String),
+}
+
+/// Receives the messages sent to an object, and dispatches them as `message` and `error` events.
+#[derive(Debug)]
+pub struct Port {
+	/// Keeps the target alive, which weak ports only do while it is listening for messages.
+	target: Option<PersistentRooted<*mut JSObject>>,
+	weak_target: Option<Weak>,
+	receiver: UnboundedReceiver<Message>,
+	closed: Arc<AtomicBool>,
+	weak: bool,
+}
+
+impl Port {
+	/// Creates a [Port] which is removed once all senders have been dropped.
+	/// Messages received after `closed` is set are discarded.
+	/// Weak ports only keep the event loop alive while their target has an `onmessage` handler or `message` listeners.
+	/// They also only keep their target alive while it is listening, so that it can be collected, and are removed once it is.
+	pub fn new(cx: &Context, target: &Object, receiver: UnboundedReceiver<Message>, closed: Arc<AtomicBool>, weak: bool) -> Port {
+		Port {
+			target: Some(PersistentRooted::new(target.handle().get())),
+			weak_target: weak.then(|| Weak::new(cx, target)).flatten(),
+			receiver,
+			closed,
+			weak,
+		}
+	}
+
+	fn target<'cx>(&self, cx: &'cx Context) -> Option<Object<'cx>> {
+		match &self.target {
+			Some(target) => Some(Object::from(cx.root_object(target.get()))),
+			None => self.weak_target.as_ref().and_then(|target| target.upgrade(cx)),
+		}
+	}
+
+	/// Roots the target of a weak port while it is listening for messages, and unroots it otherwise.
+	/// Returns whether the port is listening.
+	fn update_root(&mut self, cx: &Context) -> bool {
+		let Some(target) = self.target(cx) else {
+			return false;
+		};
+		let listening = EventTarget::is_listening(cx, &target, "message");
+		if self.weak_target.is_some() {
+			match (&self.target, listening) {
+				(None, true) => self.target = Some(PersistentRooted::new(target.handle().get())),
+				(Some(_), false) => self.target = None,
+				_ => {}
+			}
+		}
+		listening
+	}
+}
+
+#[derive(Debug, Default)]
+pub struct MessageQueue {
+	ports: Vec<Port>,
+	active: bool,
+}
+
+impl MessageQueue {
+	/// Dispatches the messages received by each port, draining microtasks after each message.
+	/// Ports which have not received a message wake the event loop once they do.
+	pub fn run_messages(&mut self, cx: &Context, wcx: &mut task::Context, microtasks: Option<&MicrotaskQueue>) -> Result<(), Option<ErrorReport>> {
+		let mut messages = Vec::new();
+		self.ports.retain_mut(|port| {
+			// Weak ports whose targets have been collected cannot dispatch messages.
+			let Some(target) = port.target(cx) else {
+				return false;
+			};
+			let closed = port.closed.load(Ordering::SeqCst);
+			loop {
+				match port.receiver.poll_recv(wcx) {
+					Poll::Ready(Some(_)) if closed => {}
+					Poll::Ready(Some(message)) => messages.push((PersistentRooted::new(target.handle().get()), message)),
+					Poll::Ready(None) => return false,
+					Poll::Pending => return true,
+				}
+			}
+		});
+
+		for (target, message) in messages {
+			let target = Object::from(cx.root_object(target.get()));
+			dispatch(cx, &target, message)?;
+
+			if let Some(microtasks) = microtasks {
+				microtasks.run_jobs(cx)?;
+			}
+		}
+
+		let mut active = false;
+		for port in &mut self.ports {
+			let listening = port.update_root(cx);
+			active |= !port.weak || listening;
+		}
+		self.active = active;
+		Ok(())
+	}
+
+	pub fn enqueue(&mut self, port: Port) {
+		self.ports.push(port);
+		self.active = true;
+	}
+
+	/// Removes all ports, discarding messages which have not been dispatched.
+	pub fn clear(&mut self) {
+		self.ports.clear();
+		self.active = false;
+	}
+
+	/// Adds the ports which keep the event loop alive to `handles`.
+	pub fn handles(&self, handles: &mut Vec<ActiveHandle>) {
+		if !self.active {
+			return;
+		}
+		let ports = self.ports.iter().filter(|port| !port.closed.load(Ordering::SeqCst));
+		handles.extend(ports.map(|port| ActiveHandle {
+			kind: HandleKind::Port,
+			description: String::from(if port.weak { "Message Port" } else { "Worker" }),
+			stack: None,
+		}));
+	}
+
+	pub fn is_empty(&self) -> bool {
+		!self.active
+	}
+}
+
+fn dispatch(cx: &Context, target: &Object, message: Message) -> Result<(), Option<ErrorReport>> {
+	let (kind, key, value) = match message {
+		Message::Data(data) => {
+			if !EventTarget::is_listening(cx, target, "message") {
+				return Ok(());
+			}
+			let data = data
+				.deserialise(cx)
+				.map_err(|exception| Some(ErrorReport::from_exception_with_error_stack(cx, exception)))?;
+			("message", "data", data)
+		}
+		Message::Error(error) => {
+			if !EventTarget::is_listening(cx, target, "error") {
+				eprintln!("Uncaught Error in Worker: {}", error);
+				return Ok(());
+			}
+			("error", "message", Value::string(cx, &error))
+		}
+	};
+
+	let mut event = Event::new_trusted(cx, kind);
+	event.define(cx, key, &value, PropertyFlags::ENUMERATE);
+	EventTarget::fire(cx, target, &mut event)
+		.map(|_| ())
+		.map_err(|error| Some(ErrorReport::from(Exception::from(error), None)))
+}
@@ -4,40 +4,45 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/.
  */
 
+use std::cell::{Cell, RefCell};
 use std::collections::vec_deque::VecDeque;
 use std::ffi::c_void;
 
 use mozjs::glue::JobQueueTraps;
-use mozjs::jsapi::{CurrentGlobalOrNull, Handle, JobQueueIsEmpty, JobQueueMayNotBeEmpty, JSContext, JSFunction, JSObject};
+use mozjs::jsapi::{ClearKeptObjects, CurrentGlobalOrNull, Handle, JobQueueIsEmpty, JobQueueMayNotBeEmpty, JSContext, JSFunction, JSObject};
 
-use ion::{Context, ErrorReport, Function, Object};
+use ion::{Context, Error, ErrorReport, Function, Object, PersistentRooted, ThrowException};
 
 use crate::ContextExt;
 
 #[derive(Clone, Debug)]
 pub enum Microtask {
-	Promise(*mut JSObject),
-	User(*mut JSFunction),
+	Promise(PersistentRooted<*mut JSObject>),
+	User(PersistentRooted<*mut JSFunction>),
 	None,
 }
 
+/// Queue of microtasks, which is shared with the engine as its job queue.
+///
+/// The queue is borrowed only to push or pop a microtask, so microtasks can queue more microtasks while it is being drained.
 #[derive(Clone, Debug, Default)]
 pub struct MicrotaskQueue {
-	queue: VecDeque<Microtask>,
-	draining: bool,
+	queue: RefCell<VecDeque<Microtask>>,
+	draining: Cell<bool>,
+	closed: Cell<bool>,
 }
 
 impl Microtask {
 	pub fn run(&self, cx: &Context) -> Result<(), Option<ErrorReport>> {
 		match self {
 			Microtask::Promise(job) => {
-				let object = cx.root_object(*job);
+				let object = cx.root_object(job.get());
 				let function = Function::from_object(cx, &object).unwrap();
 
 				function.call(cx, &Object::null(cx), &[]).map(|_| ())
 			}
 			Microtask::User(callback) => {
-				let callback = Function::from(cx.root_function(*callback));
+				let callback = Function::from(cx.root_function(callback.get()));
 				callback.call(cx, &Object::global(cx), &[]).map(|_| ())
 			}
 			Microtask::None => Ok(()),
@@ -46,30 +51,61 @@ impl Microtask {
 }
 
 impl MicrotaskQueue {
-	pub fn enqueue(&mut self, cx: &Context, microtask: Microtask) {
-		self.queue.push_back(microtask);
+	/// Queues a microtask, which runs at the next microtask checkpoint.
+	/// Returns an error if the runtime has shut down, as the microtask would never run.
+	pub fn enqueue(&self, cx: &Context, microtask: Microtask) -> Result<(), Error> {
+		if self.closed.get() {
+			return Err(Error::new("Microtask Queue has been Shut Down", None));
+		}
+		self.queue.borrow_mut().push_back(microtask);
 		unsafe { JobQueueMayNotBeEmpty(cx.as_ptr()) }
+		Ok(())
 	}
 
-	pub fn run_jobs(&mut self, cx: &Context) -> Result<(), Option<ErrorReport>> {
-		if self.draining {
+	/// Performs a microtask checkpoint, running microtasks until the queue is empty,
+	/// including those queued by the microtasks which are run.
+	///
+	/// Checkpoints do not nest. If a microtask causes another checkpoint, it returns immediately,
+	/// and the microtasks it would have run are run by the outer checkpoint instead.
+	/// If a microtask throws, the checkpoint stops and the error is returned, leaving the rest of the queue for the next checkpoint.
+	///
+	/// Once the queue is empty, the objects kept alive by `WeakRef`s are cleared, so that they can be collected.
+	pub fn run_jobs(&self, cx: &Context) -> Result<(), Option<ErrorReport>> {
+		if self.draining.replace(true) {
 			return Ok(());
 		}
 
-		self.draining = true;
+		let result = self.drain(cx);
+		self.draining.set(false);
 
-		while let Some(microtask) = self.queue.pop_front() {
-			microtask.run(cx)?;
+		// Targets of WeakRefs created or dereferenced during the checkpoint are kept alive until it completes.
+		if self.is_empty() {
+			unsafe {
+				JobQueueIsEmpty(cx.as_ptr());
+				ClearKeptObjects(cx.as_ptr());
+			}
 		}
+		result
+	}
 
-		self.draining = false;
-		unsafe { JobQueueIsEmpty(cx.as_ptr()) };
+	fn drain(&self, cx: &Context) -> Result<(), Option<ErrorReport>> {
+		loop {
+			let microtask = self.queue.borrow_mut().pop_front();
+			match microtask {
+				Some(microtask) => microtask.run(cx)?,
+				None => return Ok(()),
+			}
+		}
+	}
 
-		Ok(())
+	/// Discards all queued microtasks, and rejects microtasks queued afterwards.
+	pub fn close(&self) {
+		self.closed.set(true);
+		self.queue.borrow_mut().clear();
 	}
 
 	pub fn is_empty(&self) -> bool {
-		self.queue.is_empty()
+		self.queue.borrow().is_empty()
 	}
 }
 
@@ -81,19 +117,25 @@ unsafe extern "C" fn enqueue_promise_job(
 	_: *const c_void, cx: *mut JSContext, _: Handle<*mut JSObject>, job: Handle<*mut JSObject>, _: Handle<*mut JSObject>, _: Handle<*mut JSObject>,
 ) -> bool {
 	let cx = unsafe { &Context::new_unchecked(cx) };
-	let event_loop = unsafe { &mut (*cx.get_private().as_ptr()).event_loop };
-	let microtasks = event_loop.microtasks.as_mut().unwrap();
-	if !job.is_null() {
-		microtasks.enqueue(cx, Microtask::Promise(job.get()))
+	let event_loop = unsafe { &(*cx.get_private().as_ptr()).event_loop };
+	let microtasks = event_loop.microtasks.as_ref().unwrap();
+	let microtask = if !job.is_null() {
+		Microtask::Promise(PersistentRooted::new(job.get()))
 	} else {
-		microtasks.enqueue(cx, Microtask::None)
+		Microtask::None
 	};
-	true
+	match microtasks.enqueue(cx, microtask) {
+		Ok(()) => true,
+		Err(error) => {
+			error.throw(cx);
+			false
+		}
+	}
 }
 
 unsafe extern "C" fn empty(extra: *const c_void) -> bool {
 	let queue: &MicrotaskQueue = unsafe { &*extra.cast() };
-	queue.queue.is_empty()
+	queue.is_empty()
 }
 
 pub(crate) static JOB_QUEUE_TRAPS: JobQueueTraps = JobQueueTraps {
@@ -4,79 +4,400 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/.
  */
 
+use std::cell::RefCell;
 use std::collections::VecDeque;
 use std::ffi::c_void;
+use std::future::Future;
+use std::mem;
+use std::pin::Pin;
+use std::rc::{Rc, Weak};
 use std::task;
-use std::task::Poll;
+use std::task::{Poll, Waker};
 
+use chrono::Utc;
 use futures::future::poll_fn;
-use mozjs::jsapi::{Handle, Heap, JSContext, JSObject, PromiseRejectionHandlingState};
+use mozjs::jsapi::{
+	GetWeakMapEntry, Handle, JS_MaybeGC, JSContext, JSFunction, JSObject, NewWeakMapObject, PromiseRejectionHandlingState, SetWeakMapEntry,
+};
+use tokio::runtime::Handle as TokioHandle;
+use tokio::time::{Instant, Sleep, sleep_until};
+use tracing::{debug, trace};
 
-use ion::{Context, ErrorReport, Local, Promise};
+use ion::{Context, ErrorReport, Function, Local, Object, PersistentRooted, Promise, Value};
 use ion::format::{Config, format_value};
+use ion::module::DynamicImport;
 
 use crate::ContextExt;
+use crate::diagnostics;
 use crate::event_loop::future::FutureQueue;
+use crate::event_loop::handles::{ActiveHandle, HandleKind};
 use crate::event_loop::macrotasks::MacrotaskQueue;
+use crate::event_loop::messages::MessageQueue;
 use crate::event_loop::microtasks::MicrotaskQueue;
+use crate::event_loop::signals::SignalQueue;
+use crate::globals::event::{Event, EventTarget, PromiseRejectionEvent};
+#[cfg(feature = "inspector")]
+use crate::inspector::Inspector;
 
 pub(crate) mod future;
+pub mod handles;
 pub(crate) mod macrotasks;
+pub(crate) mod messages;
 pub(crate) mod microtasks;
+pub mod signals;
 
+/// Called with the promise and reason of each unhandled rejection which was not cancelled by an `unhandledrejection` listener.
+pub type RejectionCallback = Rc<dyn Fn(&Context, &Promise, &Value)>;
+
+#[derive(Debug, Default)]
+struct KeepAliveState {
+	waker: RefCell<Option<Waker>>,
+	handles: RefCell<Vec<Weak<ActiveHandle>>>,
+}
+
+/// Keeps the event loop of a runtime alive until it is dropped, for work which the event loop does not track itself.
+/// Dropping the handle wakes the event loop, so that it can exit if there is no other pending work.
+#[derive(Clone, Debug)]
+pub struct KeepAlive {
+	state: Rc<KeepAliveState>,
+	_handle: Rc<ActiveHandle>,
+}
+
+impl KeepAlive {
+	pub fn new(cx: &Context) -> KeepAlive {
+		KeepAlive::for_handle(cx, ActiveHandle::new(cx, HandleKind::Other, "KeepAlive"))
+	}
+
+	/// Creates a [KeepAlive], which is described by `handle` in the [active handles](handles::active_handles) of the runtime.
+	pub fn for_handle(cx: &Context, handle: ActiveHandle) -> KeepAlive {
+		let event_loop = unsafe { &(*cx.get_private().as_ptr()).event_loop };
+		let handle = Rc::new(handle);
+		event_loop.keep_alive.handles.borrow_mut().push(Rc::downgrade(&handle));
+		KeepAlive {
+			state: Rc::clone(&event_loop.keep_alive),
+			_handle: handle,
+		}
+	}
+}
+
+impl Drop for KeepAlive {
+	fn drop(&mut self) {
+		if let Some(waker) = self.state.waker.borrow_mut().take() {
+			waker.wake();
+		}
+	}
+}
+
+/// Event loop of a runtime, which is driven by the tokio runtime it runs in.
+///
+/// Between turns, the event loop sleeps until a future completes, a message or signal is received, the next timer expires, or a [KeepAlive] is dropped.
+/// It exits once there is no pending work and no [KeepAlive] handles remain.
 #[derive(Default)]
 pub struct EventLoop {
 	pub(crate) futures: Option<FutureQueue>,
 	pub(crate) microtasks: Option<MicrotaskQueue>,
 	pub(crate) macrotasks: Option<MacrotaskQueue>,
-	pub(crate) unhandled_rejections: VecDeque<Box<Heap<*mut JSObject>>>,
+	pub(crate) unhandled_rejections: VecDeque<PersistentRooted<*mut JSObject>>,
+	/// Rejected promises which `unhandledrejection` has been fired for, which fire `rejectionhandled` if they are handled later.
+	notified_rejections: NotifiedRejections,
+	handled_rejections: VecDeque<PersistentRooted<*mut JSObject>>,
+	pub(crate) rejection_callback: Option<RejectionCallback>,
+	pub(crate) rejected: bool,
+	pub(crate) finalization_cleanups: VecDeque<PersistentRooted<*mut JSFunction>>,
+	pub(crate) dynamic_imports: VecDeque<DynamicImport>,
+	pub(crate) messages: MessageQueue,
+	pub(crate) signals: SignalQueue,
+	#[cfg(feature = "inspector")]
+	pub(crate) inspector: Option<Inspector>,
+	keep_alive: Rc<KeepAliveState>,
+	timer: Option<Pin<Box<Sleep>>>,
+	unloaded: bool,
+	/// Whether creation stacks are captured for [active handles](handles::active_handles).
+	pub(crate) handle_stacks: bool,
+	/// Whether the active handles are reported when the runtime exits.
+	pub(crate) trace_exit: bool,
 }
 
 impl EventLoop {
 	pub async fn run_event_loop(&mut self, cx: &Context) -> Result<(), Option<ErrorReport>> {
+		debug!("Event loop started");
 		let mut complete = false;
-		poll_fn(|wcx| self.poll_event_loop(cx, wcx, &mut complete)).await
+		let result = poll_fn(|wcx| self.poll_event_loop(cx, wcx, &mut complete)).await;
+		// Scripts which request to exit are terminated, which is not an error.
+		let result = result.or_else(|error| if has_exited(cx) { Ok(()) } else { Err(error) });
+		debug!(error = result.is_err(), "Event loop finished");
+		result
 	}
 
+	/// Runs a single turn of the event loop, which consists of the following phases, in order:
+	/// 1. Completed futures, such as I/O, resolve their promises. Microtasks are drained after each promise is settled.
+	/// 2. Microtasks are drained.
+	/// 3. Timers which expired before this phase run, in order of their deadlines. Microtasks are drained after each timer.
+	/// 4. Immediates queued before this phase run, in order, unless many have run and a timer has expired since.
+	///    Microtasks are drained after each immediate.
+	/// 5. Messages from workers and channels are dispatched, then messages from the inspector, then signals received by the process.
+	/// 6. Dynamic imports are finished, finalization registries are cleaned up, and unhandled rejections are reported.
+	///    Microtasks are drained after each dynamic import and cleanup.
+	/// 7. Events recorded by the runtime are delivered to subscribers of the diagnostics channel.
+	///
+	/// The event loop stops before the next turn once a script has requested the runtime to exit.
 	fn poll_event_loop(&mut self, cx: &Context, wcx: &mut task::Context, complete: &mut bool) -> Poll<Result<(), Option<ErrorReport>>> {
+		if has_exited(cx) {
+			debug!("Event loop stopped by exit");
+			return Poll::Ready(Ok(()));
+		}
+
 		if let Some(futures) = &mut self.futures {
 			if !futures.is_empty() {
-				futures.run_futures(cx, wcx)?;
+				futures.run_futures(cx, wcx, self.microtasks.as_ref())?;
 			}
 		}
 
-		if let Some(microtasks) = &mut self.microtasks {
-			if !microtasks.is_empty() {
-				microtasks.run_jobs(cx)?;
-			}
-		}
+		self.run_microtasks(cx)?;
 
+		// Timers which do not keep the event loop alive still run while it is alive.
 		if let Some(macrotasks) = &mut self.macrotasks {
-			if !macrotasks.is_empty() {
-				macrotasks.run_jobs(cx)?;
+			macrotasks.run_jobs(cx, self.microtasks.as_ref())?;
+		}
+
+		self.messages.run_messages(cx, wcx, self.microtasks.as_ref())?;
+		#[cfg(feature = "inspector")]
+		if let Some(inspector) = &self.inspector {
+			inspector.poll(cx, wcx)?;
+		}
+		self.signals.run_signals(cx, wcx, self.microtasks.as_ref())?;
+
+		while let Some(import) = self.dynamic_imports.pop_front() {
+			if !import.finish(cx) {
+				return Poll::Ready(Err(ErrorReport::new_with_exception_stack(cx)));
 			}
+			self.run_microtasks(cx)?;
 		}
 
-		while let Some(promise) = self.unhandled_rejections.pop_front() {
-			let promise = Promise::from(unsafe { Local::from_heap(&promise) }).unwrap();
-			let result = promise.result(cx);
-			eprintln!("Unhandled Promise Rejection: {}", format_value(cx, Config::default(), &result));
+		while let Some(cleanup) = self.finalization_cleanups.pop_front() {
+			let function = Function::from(cx.root_function(cleanup.get()));
+			function.call(cx, &Object::global(cx), &[])?;
+			self.run_microtasks(cx)?;
+		}
+
+		self.notify_rejections(cx);
+		diagnostics::dispatch(cx)?;
+
+		// Idle time between turns is used to let the engine run incremental GC slices.
+		if self.microtasks.as_ref().map(|m| m.is_empty()).unwrap_or(true) {
+			unsafe {
+				JS_MaybeGC(cx.as_ptr());
+			}
 		}
 
-		let empty = self.is_empty();
+		let mut empty = self.is_empty();
 		if empty && *complete {
-			Poll::Ready(Ok(()))
+			// Listeners of `beforeunload` can queue more work, which keeps the event loop alive.
+			debug!("Firing beforeunload");
+			fire_global_event(cx, "beforeunload");
+			if self.is_empty() {
+				return Poll::Ready(Ok(()));
+			}
+			empty = false;
+		}
+		*complete = empty;
+
+		if empty || self.has_ready_work() || !self.schedule_timer(wcx) {
+			wcx.waker().wake_by_ref();
 		} else {
+			trace!(
+				futures = self.futures.as_ref().map(|f| !f.is_empty()).unwrap_or(false),
+				timers = self.macrotasks.as_ref().map(|m| !m.is_empty()).unwrap_or(false),
+				messages = !self.messages.is_empty(),
+				keep_alive = Rc::strong_count(&self.keep_alive) - 1,
+				"Event loop is waiting"
+			);
+			*self.keep_alive.waker.borrow_mut() = Some(wcx.waker().clone());
+		}
+		Poll::Pending
+	}
+
+	/// Fires `unload` at the global object, then cancels all pending work, so that the event loop finishes.
+	/// Microtasks queued by listeners of `unload` are drained first.
+	/// Futures are aborted, and timers, immediates and messages are discarded.
+	/// Microtasks queued afterwards are rejected with an error, instead of being silently dropped.
+	pub(crate) fn shutdown(&mut self, cx: &Context) {
+		debug!("Event loop shutting down");
+		if !mem::replace(&mut self.unloaded, true) {
+			fire_global_event(cx, "unload");
+			if let Err(Some(report)) = self.run_microtasks(cx) {
+				eprintln!("{}", report.format(cx));
+			}
+		}
+
+		if let Some(futures) = &mut self.futures {
+			futures.clear();
+		}
+		if let Some(microtasks) = &self.microtasks {
+			microtasks.close();
+		}
+		if let Some(macrotasks) = &mut self.macrotasks {
+			macrotasks.clear();
+		}
+		self.messages.clear();
+		self.unhandled_rejections.clear();
+		self.handled_rejections.clear();
+		self.timer = None;
+	}
+
+	/// Fires `unhandledrejection` at the global object for promises which were rejected without a handler,
+	/// and `rejectionhandled` for those which have been handled since.
+	/// Rejections which are not cancelled are reported, and cause the runtime to exit with an error by default.
+	fn notify_rejections(&mut self, cx: &Context) {
+		let global = Object::global(cx);
+		while let Some(promise) = self.unhandled_rejections.pop_front() {
+			let promise = Promise::from(cx.root_object(promise.get())).unwrap();
+			let Some(Err(reason)) = promise.settled_result(cx) else {
+				continue;
+			};
+
+			let mut event = PromiseRejectionEvent::new_trusted(cx, "unhandledrejection", &promise, &reason);
+			let reported = match EventTarget::fire(cx, &global, &mut event) {
+				Ok(not_cancelled) => not_cancelled,
+				Err(error) => {
+					eprintln!("{}", error.format());
+					true
+				}
+			};
+			self.notified_rejections.insert(cx, &promise);
+
+			if reported {
+				debug!("Unhandled promise rejection");
+				self.rejected = true;
+				match &self.rejection_callback {
+					Some(callback) => callback(cx, &promise, &reason),
+					None => eprintln!("Unhandled Promise Rejection: {}", format_value(cx, Config::default(), &reason)),
+				}
+			}
+		}
+
+		while let Some(promise) = self.handled_rejections.pop_front() {
+			let promise = Promise::from(cx.root_object(promise.get())).unwrap();
+			let reason = match promise.settled_result(cx) {
+				Some(Err(reason)) => reason,
+				_ => Value::undefined(cx),
+			};
+			let mut event = PromiseRejectionEvent::new_trusted(cx, "rejectionhandled", &promise, &reason);
+			if let Err(error) = EventTarget::fire(cx, &global, &mut event) {
+				eprintln!("{}", error.format());
+			}
+		}
+	}
+
+	/// Performs a microtask checkpoint, if the microtask queue is enabled.
+	fn run_microtasks(&self, cx: &Context) -> Result<(), Option<ErrorReport>> {
+		match &self.microtasks {
+			Some(microtasks) if !microtasks.is_empty() => microtasks.run_jobs(cx),
+			_ => Ok(()),
+		}
+	}
+
+	/// Checks if there is work which can run immediately, without waiting for a waker.
+	fn has_ready_work(&self) -> bool {
+		!self.microtasks.as_ref().map(|m| m.is_empty()).unwrap_or(true)
+			|| self.futures.as_ref().map(|f| f.is_queued()).unwrap_or(false)
+			|| self.macrotasks.as_ref().map(|m| m.has_immediates()).unwrap_or(false)
+			|| !self.finalization_cleanups.is_empty()
+			|| !self.dynamic_imports.is_empty()
+			|| !self.unhandled_rejections.is_empty()
+			|| !self.handled_rejections.is_empty()
+	}
+
+	/// Registers the waker with a sleep until the next timer expires.
+	/// Returns false if the waker could not be registered, as the event loop is not running in a tokio runtime.
+	fn schedule_timer(&mut self, wcx: &mut task::Context) -> bool {
+		let Some(deadline) = self.macrotasks.as_mut().and_then(|m| m.next_deadline()) else {
+			self.timer = None;
+			return true;
+		};
+		if TokioHandle::try_current().is_err() {
+			return false;
+		}
+
+		let deadline = Instant::now() + (deadline - Utc::now()).to_std().unwrap_or_default();
+		let timer = self.timer.get_or_insert_with(|| Box::pin(sleep_until(deadline)));
+		timer.as_mut().reset(deadline);
+		if timer.as_mut().poll(wcx).is_ready() {
 			wcx.waker().wake_by_ref();
-			*complete = empty;
-			Poll::Pending
 		}
+		true
+	}
+
+	fn keep_alive_handles(&self, handles: &mut Vec<ActiveHandle>) {
+		let mut keep_alive = self.keep_alive.handles.borrow_mut();
+		keep_alive.retain(|handle| handle.strong_count() > 0);
+		handles.extend(keep_alive.iter().filter_map(Weak::upgrade).map(|handle| (*handle).clone()));
 	}
 
 	fn is_empty(&self) -> bool {
 		self.microtasks.as_ref().map(|m| m.is_empty()).unwrap_or(true)
 			&& self.futures.as_ref().map(|f| f.is_empty()).unwrap_or(true)
 			&& self.macrotasks.as_ref().map(|m| m.is_empty()).unwrap_or(true)
+			&& self.finalization_cleanups.is_empty()
+			&& self.dynamic_imports.is_empty()
+			&& self.messages.is_empty()
+			&& Rc::strong_count(&self.keep_alive) == 1
+	}
+}
+
+/// Holds the rejected promises which `unhandledrejection` has been fired for as keys of a `WeakMap`,
+/// so that promises which are never handled can still be collected.
+#[derive(Default)]
+struct NotifiedRejections {
+	map: Option<PersistentRooted<*mut JSObject>>,
+}
+
+impl NotifiedRejections {
+	fn insert(&mut self, cx: &Context, promise: &Promise) {
+		if self.map.is_none() {
+			let map = unsafe { NewWeakMapObject(cx.as_ptr()) };
+			if map.is_null() {
+				return;
+			}
+			self.map = Some(PersistentRooted::new(map));
+		}
+		if let Some(map) = &self.map {
+			let map = cx.root_object(map.get());
+			let notified = Value::bool(cx, true);
+			unsafe {
+				SetWeakMapEntry(cx.as_ptr(), map.handle().into(), promise.handle().into(), notified.handle().into());
+			}
+		}
+	}
+
+	/// Removes a promise, returning `true` if `unhandledrejection` had been fired for it.
+	fn remove(&mut self, cx: &Context, promise: &Promise) -> bool {
+		let Some(map) = &self.map else {
+			return false;
+		};
+		let map = cx.root_object(map.get());
+		let mut notified = Value::undefined(cx);
+		let found = unsafe { GetWeakMapEntry(cx.as_ptr(), map.handle().into(), promise.handle().into(), notified.handle_mut().into()) }
+			&& notified.handle().is_boolean()
+			&& notified.handle().to_boolean();
+		if found {
+			let removed = Value::undefined(cx);
+			unsafe {
+				SetWeakMapEntry(cx.as_ptr(), map.handle().into(), promise.handle().into(), removed.handle().into());
+			}
+		}
+		found
+	}
+}
+
+fn has_exited(cx: &Context) -> bool {
+	unsafe { (*cx.get_private().as_ptr()).exited }
+}
+
+fn fire_global_event(cx: &Context, kind: &str) {
+	let mut event = Event::new_trusted(cx, kind);
+	if let Err(error) = EventTarget::fire(cx, &Object::global(cx), &mut event) {
+		eprintln!("{}", error.format());
 	}
 }
 
@@ -85,14 +406,25 @@ pub(crate) unsafe extern "C" fn promise_rejection_tracker_callback(
 ) {
 	let cx = unsafe { &Context::new_unchecked(cx) };
 	let promise = Promise::from(unsafe { Local::from_raw_handle(promise) }).unwrap();
-	let unhandled = unsafe { &mut (*cx.get_private().as_ptr()).event_loop.unhandled_rejections };
+	let event_loop = unsafe { &mut (*cx.get_private().as_ptr()).event_loop };
 	match state {
-		PromiseRejectionHandlingState::Unhandled => unhandled.push_back(Heap::boxed(promise.get())),
+		PromiseRejectionHandlingState::Unhandled => event_loop.unhandled_rejections.push_back(PersistentRooted::new(promise.get())),
 		PromiseRejectionHandlingState::Handled => {
-			let idx = unhandled.iter().position(|unhandled| unhandled.get() == promise.get());
-			if let Some(idx) = idx {
+			let unhandled = &mut event_loop.unhandled_rejections;
+			if let Some(idx) = unhandled.iter().position(|unhandled| unhandled.get() == promise.get()) {
 				unhandled.swap_remove_back(idx);
+				return;
+			}
+
+			if event_loop.notified_rejections.remove(cx, &promise) {
+				event_loop.handled_rejections.push_back(PersistentRooted::new(promise.get()));
 			}
 		}
 	}
 }
+
+pub(crate) unsafe extern "C" fn cleanup_finalization_registry_callback(cleanup: *mut JSFunction, _: *mut JSObject, data: *mut c_void) {
+	let cx = unsafe { &Context::new_unchecked(data.cast()) };
+	let cleanups = unsafe { &mut (*cx.get_private().as_ptr()).event_loop.finalization_cleanups };
+	cleanups.push_back(PersistentRooted::new(cleanup));
+}
@@ -0,0 +1,203 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::collections::HashSet;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::io;
+use std::task;
+use std::task::Poll;
+
+use mozjs::jsapi::JSFunction;
+use tokio::runtime::Handle as TokioHandle;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+
+use ion::{Context, Error, ErrorKind, ErrorReport, Function, Object, PersistentRooted, Result, Value};
+
+use crate::ContextExt;
+use crate::event_loop::microtasks::MicrotaskQueue;
+
+/// Process signals which scripts can listen for.
+/// Only [Signal::Interrupt], which is sent by Ctrl+C, is supported on Windows.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Signal {
+	Interrupt,
+	Terminate,
+	Hangup,
+}
+
+impl Signal {
+	pub fn from_name(name: &str) -> Option<Signal> {
+		match name {
+			"SIGINT" => Some(Signal::Interrupt),
+			"SIGTERM" => Some(Signal::Terminate),
+			"SIGHUP" => Some(Signal::Hangup),
+			_ => None,
+		}
+	}
+
+	pub fn name(self) -> &'static str {
+		match self {
+			Signal::Interrupt => "SIGINT",
+			Signal::Terminate => "SIGTERM",
+			Signal::Hangup => "SIGHUP",
+		}
+	}
+
+	fn number(self) -> i32 {
+		match self {
+			Signal::Interrupt => 2,
+			Signal::Terminate => 15,
+			Signal::Hangup => 1,
+		}
+	}
+}
+
+impl Display for Signal {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		f.write_str(self.name())
+	}
+}
+
+/// Receives process signals, and dispatches them to the listeners registered by scripts.
+///
+/// Signals are only intercepted once a listener has been added for them.
+/// As the default behaviour of a signal cannot be restored afterwards, signals without listeners stop the event loop,
+/// with the exit code of `128` plus the signal number which the process would have exited with by default.
+/// Listeners do not keep the event loop alive.
+#[derive(Debug, Default)]
+pub struct SignalQueue {
+	listeners: Vec<(Signal, PersistentRooted<*mut JSFunction>)>,
+	registered: HashSet<Signal>,
+	sender: Option<UnboundedSender<Signal>>,
+	receiver: Option<UnboundedReceiver<Signal>>,
+}
+
+impl SignalQueue {
+	pub fn run_signals(
+		&mut self, cx: &Context, wcx: &mut task::Context, microtasks: Option<&MicrotaskQueue>,
+	) -> std::result::Result<(), Option<ErrorReport>> {
+		let Some(receiver) = &mut self.receiver else {
+			return Ok(());
+		};
+		let mut signals = Vec::new();
+		while let Poll::Ready(Some(signal)) = receiver.poll_recv(wcx) {
+			signals.push(signal);
+		}
+
+		for signal in signals {
+			let listeners: Vec<_> = self
+				.listeners
+				.iter()
+				.filter(|(kind, _)| *kind == signal)
+				.map(|(_, listener)| listener.get())
+				.collect();
+			if listeners.is_empty() {
+				let private = unsafe { &mut *cx.get_private().as_ptr() };
+				private.exit(128 + signal.number());
+				// The event loop treats errors after an exit as stopping, rather than as failing.
+				return Err(None);
+			}
+
+			for listener in listeners {
+				let listener = Function::from(cx.root_function(listener));
+				listener.call(cx, &Object::global(cx), &[Value::string(cx, signal.name())])?;
+
+				if let Some(microtasks) = microtasks {
+					microtasks.run_jobs(cx)?;
+				}
+			}
+		}
+		Ok(())
+	}
+
+	pub(crate) fn register(&mut self, signal: Signal) -> Result<()> {
+		if self.registered.contains(&signal) {
+			return Ok(());
+		}
+		if TokioHandle::try_current().is_err() {
+			return Err(Error::new("Signals can only be listened for within a tokio runtime.", None));
+		}
+
+		let sender = match &self.sender {
+			Some(sender) => sender.clone(),
+			None => {
+				let (sender, receiver) = unbounded_channel();
+				self.sender = Some(sender.clone());
+				self.receiver = Some(receiver);
+				sender
+			}
+		};
+		listen(signal, sender).map_err(|error| Error::new(&format!("Failed to listen for {}: {}", signal, error), None))?;
+		self.registered.insert(signal);
+		Ok(())
+	}
+}
+
+#[cfg(unix)]
+fn listen(signal: Signal, sender: UnboundedSender<Signal>) -> io::Result<()> {
+	use tokio::signal::unix::{signal as unix_signal, SignalKind};
+
+	let kind = match signal {
+		Signal::Interrupt => SignalKind::interrupt(),
+		Signal::Terminate => SignalKind::terminate(),
+		Signal::Hangup => SignalKind::hangup(),
+	};
+	let mut stream = unix_signal(kind)?;
+	tokio::spawn(async move {
+		while stream.recv().await.is_some() {
+			if sender.send(signal).is_err() {
+				break;
+			}
+		}
+	});
+	Ok(())
+}
+
+#[cfg(windows)]
+fn listen(signal: Signal, sender: UnboundedSender<Signal>) -> io::Result<()> {
+	use tokio::signal::windows::ctrl_c;
+
+	if signal != Signal::Interrupt {
+		return Err(io::Error::new(io::ErrorKind::Unsupported, "Only SIGINT is supported on Windows"));
+	}
+	let mut stream = ctrl_c()?;
+	tokio::spawn(async move {
+		while stream.recv().await.is_some() {
+			if sender.send(signal).is_err() {
+				break;
+			}
+		}
+	});
+	Ok(())
+}
+
+/// Adds a listener for a signal, which is called with the name of the signal whenever the process receives it.
+pub fn add_signal_listener(cx: &Context, signal: Signal, listener: &Function) -> Result<()> {
+	let signals = unsafe { &mut (*cx.get_private().as_ptr()).event_loop.signals };
+	signals.register(signal)?;
+
+	let listener = listener.get();
+	if !signals
+		.listeners
+		.iter()
+		.any(|(kind, existing)| *kind == signal && existing.get() == listener)
+	{
+		signals.listeners.push((signal, PersistentRooted::new(listener)));
+	}
+	Ok(())
+}
+
+pub fn remove_signal_listener(cx: &Context, signal: Signal, listener: &Function) {
+	let signals = unsafe { &mut (*cx.get_private().as_ptr()).event_loop.signals };
+	let listener = listener.get();
+	signals.listeners.retain(|(kind, existing)| *kind != signal || existing.get() != listener);
+}
+
+/// Parses the name of a signal, such as `SIGINT`.
+pub fn parse_signal(name: &str) -> Result<Signal> {
+	Signal::from_name(name).ok_or_else(|| Error::new(&format!("Unknown signal: {}", name), ErrorKind::Type))
+}
@@ -4,38 +4,68 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/.
  */
 
-use std::{ptr, task};
 use std::future::Future;
 use std::pin::{Pin, pin};
-use std::sync::Arc;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::rc::Rc;
+use std::task;
 use std::task::Poll;
 
 use chrono::Duration;
-use mozjs::jsapi::JSObject;
-use mozjs::jsval::JSVal;
+use mozjs::jsapi::{Heap, JSObject};
+use mozjs::jsval::{JSVal, UndefinedValue};
 use tokio::sync::watch::{channel, Receiver, Sender};
+use tokio::task::spawn_local;
 
-use ion::{ClassDefinition, Context, Error, ErrorKind, Exception, Object, Result, ResultExc, Value};
+use ion::{ClassDefinition, Context, Error, ErrorKind, Exception, Local, Object, PersistentRooted, Result, ResultExc, Value, Weak};
 use ion::class::Reflector;
 use ion::conversions::{ConversionBehavior, FromValue, ToValue};
 
 use crate::ContextExt;
 use crate::event_loop::macrotasks::{Macrotask, SignalMacrotask};
+use crate::globals::event::{Event, EventTarget};
 
+/// Represents the state of an [AbortSignal], which can be observed by native operations.
 #[derive(Clone, Debug, Default)]
 pub enum Signal {
 	#[default]
 	None,
 	Abort(JSVal),
 	Receiver(Receiver<Option<JSVal>>),
-	Timeout(Receiver<Option<JSVal>>, Arc<AtomicBool>),
 }
 
 impl Signal {
 	pub fn poll(&self) -> SignalFuture {
 		SignalFuture { inner: self.clone() }
 	}
+
+	/// Calls `callback` with the reason of the signal once it is aborted.
+	/// If the signal has already been aborted, `callback` is called immediately.
+	///
+	/// The callback is run by the current [LocalSet](tokio::task::LocalSet), and is not called if the signal is never aborted.
+	pub fn on_abort<F: FnOnce(JSVal) + 'static>(&self, callback: F) {
+		match self {
+			Signal::None => {}
+			Signal::Abort(reason) => callback(*reason),
+			Signal::Receiver(_) => {
+				let future = self.poll();
+				spawn_local(async move { callback(future.await) });
+			}
+		}
+	}
+}
+
+impl<'cx> FromValue<'cx> for Signal {
+	type Config = ();
+
+	/// Converts an [AbortSignal] object into its [Signal], for native operations which accept a `signal` option.
+	fn from_value(cx: &'cx Context, value: &Value, strict: bool, _: ()) -> Result<Signal> {
+		let object = Object::from_value(cx, value, strict, ())?;
+		if AbortSignal::instance_of(cx, &object, None) {
+			Ok(AbortSignal::get_private(&object).signal())
+		} else {
+			Err(Error::new("Expected AbortSignal", ErrorKind::Type))
+		}
+	}
 }
 
 pub struct SignalFuture {
@@ -49,7 +79,7 @@ impl Future for SignalFuture {
 		match &mut self.inner {
 			Signal::None => Poll::Pending,
 			Signal::Abort(abort) => Poll::Ready(*abort),
-			Signal::Receiver(receiver) | Signal::Timeout(receiver, _) => {
+			Signal::Receiver(receiver) => {
 				if let Some(abort) = *receiver.borrow() {
 					return Poll::Ready(abort);
 				}
@@ -66,55 +96,116 @@ impl Future for SignalFuture {
 	}
 }
 
-impl Drop for SignalFuture {
-	fn drop(&mut self) {
-		if let Signal::Timeout(receiver, terminate) = &self.inner {
-			if receiver.borrow().is_none() {
-				terminate.store(true, Ordering::SeqCst);
-			}
-		}
-	}
-}
-
 #[js_class]
 pub struct AbortController {
 	reflector: Reflector,
-	#[ion(no_trace)]
-	sender: Sender<Option<JSVal>>,
+	signal: Box<Heap<*mut JSObject>>,
 }
 
 #[js_class]
 impl AbortController {
 	#[ion(constructor)]
-	pub fn constructor() -> AbortController {
-		let (sender, _) = channel(None);
-		AbortController { reflector: Reflector::default(), sender }
+	pub fn constructor(cx: &Context) -> AbortController {
+		AbortController {
+			reflector: Reflector::default(),
+			signal: Heap::boxed(AbortSignal::new_object(cx, Box::default())),
+		}
 	}
 
-	// TODO: Return the same signal object
 	#[ion(get)]
-	pub fn get_signal(&self, cx: &Context) -> *mut JSObject {
-		AbortSignal::new_object(
-			cx,
-			Box::new(AbortSignal {
-				reflector: Reflector::default(),
-				signal: Signal::Receiver(self.sender.subscribe()),
-			}),
-		)
+	pub fn get_signal(&self) -> *mut JSObject {
+		self.signal.get()
 	}
 
 	pub fn abort<'cx>(&self, cx: &'cx Context, reason: Option<Value<'cx>>) {
-		let reason = reason.unwrap_or_else(|| Error::new("AbortError", None).as_value(cx));
-		self.sender.send_replace(Some(reason.get()));
+		let reason = reason.unwrap_or_else(|| abort_error(cx));
+		let mut signal = Object::from(unsafe { Local::from_heap(&self.signal) });
+		AbortSignal::signal_abort(cx, &mut signal, &reason);
 	}
 }
 
 #[js_class]
-#[derive(Default)]
 pub struct AbortSignal {
-	reflector: Reflector,
+	event_target: EventTarget,
+	#[ion(no_trace)]
+	sender: Rc<Sender<Option<JSVal>>>,
+	reason: Box<Heap<JSVal>>,
+	/// Signals created by [AbortSignal.any](AbortSignal::any), which are aborted when this signal is aborted.
+	///
+	/// Dependent signals are held weakly, so that they can be collected before this signal is aborted.
+	/// Their senders are kept, so that native operations observing collected signals are still aborted.
 	#[ion(no_trace)]
-	pub(crate) signal: Signal,
+	dependents: Vec<(Weak, Rc<Sender<Option<JSVal>>>)>,
+	/// Dependent signals which are held strongly, as weak references are disabled.
+	strong_dependents: Vec<Box<Heap<*mut JSObject>>>,
+}
+
+impl Default for AbortSignal {
+	fn default() -> AbortSignal {
+		let (sender, _) = channel(None);
+		AbortSignal {
+			event_target: EventTarget::default(),
+			sender: Rc::new(sender),
+			reason: Heap::boxed(UndefinedValue()),
+			dependents: Vec::new(),
+			strong_dependents: Vec::new(),
+		}
+	}
+}
+
+impl AbortSignal {
+	/// Returns the [Signal] of this signal, which can be observed after the signal object is no longer accessible.
+	pub fn signal(&self) -> Signal {
+		match self.get_reason() {
+			Some(reason) => Signal::Abort(reason),
+			None => Signal::Receiver(self.sender.subscribe()),
+		}
+	}
+
+	/// Aborts a signal, notifying native operations, firing an `abort` event, and aborting its dependent signals.
+	/// Signals which have already been aborted are not affected.
+	pub fn signal_abort(cx: &Context, object: &mut Object, reason: &Value) {
+		let signal = AbortSignal::get_mut_private(object);
+		if signal.get_aborted() {
+			return;
+		}
+		signal.reason.set(reason.get());
+		signal.sender.send_replace(Some(reason.get()));
+		let dependents: Vec<_> = signal.dependents.drain(..).collect();
+		let strong_dependents: Vec<_> = signal.strong_dependents.drain(..).map(|dependent| dependent.get()).collect();
+
+		let mut event = Event::new_trusted(cx, "abort");
+		if let Err(error) = EventTarget::fire(cx, object, &mut event) {
+			eprintln!("{}", error.format());
+		}
+
+		for (dependent, sender) in dependents {
+			match dependent.upgrade(cx) {
+				Some(mut dependent) => AbortSignal::signal_abort(cx, &mut dependent, reason),
+				None => {
+					sender.send_replace(Some(reason.get()));
+				}
+			}
+		}
+		for dependent in strong_dependents {
+			let mut dependent = Object::from(cx.root_object(dependent));
+			AbortSignal::signal_abort(cx, &mut dependent, reason);
+		}
+	}
+
+	/// Adds a dependent signal, which is aborted when this signal is aborted.
+	/// Collected dependents which are no longer observed by native operations are removed.
+	fn add_dependent(&mut self, cx: &Context, dependent: &Object) {
+		self.dependents
+			.retain(|(dependent, sender)| sender.receiver_count() > 0 || dependent.is_alive(cx));
+		match Weak::new(cx, dependent) {
+			Some(weak) => {
+				let sender = Rc::clone(&AbortSignal::get_private(dependent).sender);
+				self.dependents.push((weak, sender));
+			}
+			None => self.strong_dependents.push(Heap::boxed(dependent.handle().get())),
+		}
+	}
 }
 
 #[js_class]
@@ -131,11 +222,8 @@ impl AbortSignal {
 
 	#[ion(get)]
 	pub fn get_reason(&self) -> Option<JSVal> {
-		match &self.signal {
-			Signal::None => None,
-			Signal::Abort(abort) => Some(*abort),
-			Signal::Receiver(receiver) | Signal::Timeout(receiver, _) => *receiver.borrow(),
-		}
+		let reason = self.reason.get();
+		(!reason.is_undefined()).then_some(reason)
 	}
 
 	#[ion(name = "throwIfAborted")]
@@ -148,58 +236,65 @@ impl AbortSignal {
 	}
 
 	pub fn abort<'cx>(cx: &'cx Context, reason: Option<Value<'cx>>) -> *mut JSObject {
-		let reason = reason.unwrap_or_else(|| Error::new("AbortError", None).as_value(cx));
-		AbortSignal::new_object(
-			cx,
-			Box::new(AbortSignal {
-				reflector: Reflector::default(),
-				signal: Signal::Abort(reason.get()),
-			}),
-		)
-	}
-
-	pub fn timeout(cx: &Context, #[ion(convert = ConversionBehavior::EnforceRange)] time: u64) -> *mut JSObject {
-		let (sender, receiver) = channel(None);
-		let terminate = Arc::new(AtomicBool::new(false));
-		let terminate2 = terminate.clone();
-
-		let error = Error::new(&format!("Timeout Error: {}ms", time), None).as_value(cx).get();
-		let callback = Box::new(move || {
-			sender.send_replace(Some(error));
+		let reason = reason.unwrap_or_else(|| abort_error(cx));
+		let signal = AbortSignal::default();
+		signal.reason.set(reason.get());
+		signal.sender.send_replace(Some(reason.get()));
+		AbortSignal::new_object(cx, Box::new(signal))
+	}
+
+	/// Returns a signal which is aborted after `time` milliseconds, with a `TimeoutError`.
+	/// The timeout is scheduled as a macrotask, so it only elapses while the event loop is running.
+	/// The timeout does not keep the event loop running.
+	pub fn timeout(cx: &Context, #[ion(convert = ConversionBehavior::EnforceRange)] time: u64) -> Result<*mut JSObject> {
+		let event_loop = unsafe { &mut (*cx.get_private().as_ptr()).event_loop };
+		let Some(queue) = &mut event_loop.macrotasks else {
+			return Err(Error::new("Macrotask Queue has not been initialised.", None));
+		};
+
+		let object = AbortSignal::new_object(cx, Box::default());
+		let signal = PersistentRooted::new(object);
+		let callback = Box::new(move |cx: &Context| {
+			let reason = Error::new(&format!("The operation timed out after {}ms.", time), None)
+				.with_name("TimeoutError")
+				.as_value(cx);
+			let mut signal = Object::from(cx.root_object(signal.get()));
+			AbortSignal::signal_abort(cx, &mut signal, &reason);
 		});
 
 		let duration = Duration::milliseconds(time as i64);
-		let event_loop = unsafe { &mut (*cx.get_private().as_ptr()).event_loop };
-		if let Some(queue) = &mut event_loop.macrotasks {
-			queue.enqueue(Macrotask::Signal(SignalMacrotask::new(callback, terminate, duration)), None);
-			AbortSignal::new_object(
-				cx,
-				Box::new(AbortSignal {
-					reflector: Reflector::default(),
-					signal: Signal::Timeout(receiver, terminate2),
-				}),
-			)
-		} else {
-			ptr::null_mut()
-		}
+		let id = queue.enqueue(Macrotask::Signal(SignalMacrotask::new(callback, duration)), None);
+		queue.ref_timer(id, false);
+		Ok(object)
 	}
-}
 
-impl<'cx> FromValue<'cx> for AbortSignal {
-	type Config = ();
-	fn from_value(cx: &'cx Context, value: &Value, strict: bool, _: ()) -> Result<AbortSignal> {
-		let object = Object::from_value(cx, value, strict, ())?;
-		if AbortSignal::instance_of(cx, &object, None) {
-			Ok(AbortSignal {
-				reflector: Reflector::default(),
-				signal: AbortSignal::get_private(&object).signal.clone(),
-			})
-		} else {
-			Err(Error::new("Expected AbortSignal", ErrorKind::Type))
+	/// Returns a signal which is aborted when any of the given signals are aborted, with the same reason.
+	pub fn any(cx: &Context, signals: Vec<Object>) -> Result<*mut JSObject> {
+		if signals.iter().any(|signal| !AbortSignal::instance_of(cx, signal, None)) {
+			return Err(Error::new("Expected AbortSignal", ErrorKind::Type));
+		}
+
+		let object = AbortSignal::new_object(cx, Box::default());
+		let aborted = signals.iter().find_map(|signal| AbortSignal::get_private(signal).get_reason());
+		if let Some(reason) = aborted {
+			let signal = AbortSignal::get_mut_private(&mut Object::from(cx.root_object(object)));
+			signal.reason.set(reason);
+			signal.sender.send_replace(Some(reason));
+			return Ok(object);
 		}
+
+		let dependent = Object::from(cx.root_object(object));
+		for mut signal in signals {
+			AbortSignal::get_mut_private(&mut signal).add_dependent(cx, &dependent);
+		}
+		Ok(object)
 	}
 }
 
+fn abort_error(cx: &Context) -> Value {
+	Error::new("The operation was aborted.", None).with_name("AbortError").as_value(cx)
+}
+
 pub fn define(cx: &Context, global: &mut Object) -> bool {
 	AbortController::init_class(cx, global).0 && AbortSignal::init_class(cx, global).0
 }
@@ -0,0 +1,103 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+use tokio::sync::mpsc::{unbounded_channel, UnboundedSender};
+
+use ion::{ClassDefinition, Context, Error, Object, ResultExc, Value};
+
+use crate::ContextExt;
+use crate::clone::StructuredClone;
+use crate::event_loop::messages::{Message, Port};
+use crate::globals::event::EventTarget;
+
+/// Channels subscribed to the broker, which is shared by all runtimes in the process.
+static BROKER: Mutex<Vec<Subscriber>> = Mutex::new(Vec::new());
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+struct Subscriber {
+	id: u64,
+	name: String,
+	sender: UnboundedSender<Message>,
+}
+
+#[js_class]
+pub struct BroadcastChannel {
+	event_target: EventTarget,
+	#[ion(no_trace)]
+	id: u64,
+	#[ion(no_trace)]
+	name: String,
+	#[ion(no_trace)]
+	closed: Arc<AtomicBool>,
+}
+
+#[js_class]
+impl BroadcastChannel {
+	#[ion(constructor)]
+	pub fn constructor(#[ion(this)] this: &Object, cx: &Context, name: String) -> BroadcastChannel {
+		let id = NEXT_ID.fetch_add(1, Ordering::SeqCst);
+		let (sender, receiver) = unbounded_channel();
+		let closed = Arc::new(AtomicBool::new(false));
+
+		BROKER.lock().unwrap().push(Subscriber { id, name: name.clone(), sender });
+		let event_loop = unsafe { &mut (*cx.get_private().as_ptr()).event_loop };
+		event_loop.messages.enqueue(Port::new(cx, this, receiver, closed.clone(), true));
+
+		BroadcastChannel {
+			event_target: EventTarget::default(),
+			id,
+			name,
+			closed,
+		}
+	}
+
+	#[ion(get)]
+	pub fn get_name(&self) -> String {
+		self.name.clone()
+	}
+
+	/// Sends a message to all other channels with the same name, in any runtime.
+	#[ion(name = "postMessage")]
+	pub fn post_message(&self, cx: &Context, message: Value) -> ResultExc<()> {
+		if self.closed.load(Ordering::SeqCst) {
+			return Err(Error::new("BroadcastChannel is closed.", None).with_name("InvalidStateError").into());
+		}
+
+		let data = StructuredClone::serialise(cx, &message, &[])?;
+		let mut broker = BROKER.lock().unwrap();
+		// Removes subscribers whose ports have been removed, such as those of collected channels.
+		broker.retain(|subscriber| !subscriber.sender.is_closed());
+		for subscriber in broker
+			.iter()
+			.filter(|subscriber| subscriber.name == self.name && subscriber.id != self.id)
+		{
+			let _ = subscriber.sender.send(Message::Data(data.clone()));
+		}
+		Ok(())
+	}
+
+	pub fn close(&self) {
+		self.closed.store(true, Ordering::SeqCst);
+		unsubscribe(self.id);
+	}
+}
+
+impl Drop for BroadcastChannel {
+	fn drop(&mut self) {
+		unsubscribe(self.id);
+	}
+}
+
+fn unsubscribe(id: u64) {
+	BROKER.lock().unwrap().retain(|subscriber| subscriber.id != id);
+}
+
+pub fn define(cx: &Context, global: &mut Object) -> bool {
+	BroadcastChannel::init_class(cx, global).0
+}
@@ -0,0 +1,31 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use mozjs::jsapi::JSFunctionSpec;
+use mozjs::jsval::JSVal;
+
+use ion::{Context, Object, ResultExc, Value};
+
+use crate::clone::StructuredClone;
+
+#[derive(Default, FromValue)]
+pub struct StructuredSerializeOptions<'cx> {
+	#[ion(default)]
+	transfer: Vec<Object<'cx>>,
+}
+
+#[js_fn]
+fn structuredClone<'cx>(cx: &'cx Context, value: Value<'cx>, options: Option<StructuredSerializeOptions<'cx>>) -> ResultExc<JSVal> {
+	let options = options.unwrap_or_default();
+	let data = StructuredClone::serialise(cx, &value, &options.transfer)?;
+	Ok(data.deserialise(cx)?.get())
+}
+
+const FUNCTIONS: &[JSFunctionSpec] = &[function_spec!(structuredClone, 1), JSFunctionSpec::ZERO];
+
+pub fn define(cx: &Context, global: &mut Object) -> bool {
+	unsafe { global.define_methods(cx, FUNCTIONS) }
+}
@@ -0,0 +1,186 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::cell::RefCell;
+use std::io;
+use std::io::Write;
+use std::mem::take;
+use std::rc::Rc;
+
+use brotli::{CompressorWriter, DecompressorWriter};
+use flate2::Compression;
+use flate2::write::{DeflateDecoder, DeflateEncoder, GzDecoder, GzEncoder, ZlibDecoder, ZlibEncoder};
+
+use ion::{Error, ErrorKind};
+
+use crate::globals::streams::NativeTransform;
+
+const BROTLI_BUFFER_SIZE: usize = 4096;
+const BROTLI_WINDOW_SIZE: u32 = 22;
+const BROTLI_DEFAULT_QUALITY: u32 = 11;
+
+/// Represents the formats of compressed data.
+/// `deflate` is the zlib format, and `deflate-raw` is the DEFLATE format without a header or trailer.
+#[derive(Clone, Copy, Debug, FromValue)]
+pub enum Format {
+	Gzip,
+	Deflate,
+	DeflateRaw,
+	Brotli,
+}
+
+/// Collects the output of an encoder or decoder, so that it can be taken after each chunk is written.
+#[derive(Clone, Default)]
+struct Output(Rc<RefCell<Vec<u8>>>);
+
+impl Output {
+	fn take(&self) -> Vec<u8> {
+		take(&mut *self.0.borrow_mut())
+	}
+}
+
+impl Write for Output {
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+		self.0.borrow_mut().extend_from_slice(buf);
+		Ok(buf.len())
+	}
+
+	fn flush(&mut self) -> io::Result<()> {
+		Ok(())
+	}
+}
+
+enum Writer {
+	GzipEncoder(GzEncoder<Output>),
+	ZlibEncoder(ZlibEncoder<Output>),
+	DeflateEncoder(DeflateEncoder<Output>),
+	BrotliEncoder(Box<CompressorWriter<Output>>),
+	GzipDecoder(GzDecoder<Output>),
+	ZlibDecoder(ZlibDecoder<Output>),
+	DeflateDecoder(DeflateDecoder<Output>),
+	BrotliDecoder(Box<DecompressorWriter<Output>>),
+}
+
+impl Writer {
+	fn get_mut(&mut self) -> &mut dyn Write {
+		match self {
+			Writer::GzipEncoder(writer) => writer,
+			Writer::ZlibEncoder(writer) => writer,
+			Writer::DeflateEncoder(writer) => writer,
+			Writer::BrotliEncoder(writer) => writer.as_mut(),
+			Writer::GzipDecoder(writer) => writer,
+			Writer::ZlibDecoder(writer) => writer,
+			Writer::DeflateDecoder(writer) => writer,
+			Writer::BrotliDecoder(writer) => writer.as_mut(),
+		}
+	}
+
+	fn finish(self) -> io::Result<()> {
+		match self {
+			Writer::GzipEncoder(mut writer) => writer.try_finish(),
+			Writer::ZlibEncoder(mut writer) => writer.try_finish(),
+			Writer::DeflateEncoder(mut writer) => writer.try_finish(),
+			Writer::BrotliEncoder(writer) => {
+				writer.into_inner();
+				Ok(())
+			}
+			Writer::GzipDecoder(mut writer) => writer.try_finish(),
+			Writer::ZlibDecoder(mut writer) => writer.try_finish(),
+			Writer::DeflateDecoder(mut writer) => writer.try_finish(),
+			Writer::BrotliDecoder(writer) => match writer.into_inner() {
+				Ok(_) => Ok(()),
+				Err(_) => Err(io::Error::new(io::ErrorKind::UnexpectedEof, "Unexpected end of compressed data")),
+			},
+		}
+	}
+}
+
+/// Compresses or decompresses data incrementally, returning the output which is produced after each chunk.
+pub struct Codec {
+	writer: Option<Writer>,
+	output: Output,
+}
+
+impl Codec {
+	/// Creates a compressor for a format.
+	/// The `level` ranges from 0 to 9 for gzip and DEFLATE, and 0 to 11 for brotli, and defaults to 6 and 11 respectively.
+	pub fn compressor(format: Format, level: Option<u32>) -> Codec {
+		let output = Output::default();
+		let compression = || Compression::new(level.unwrap_or(Compression::default().level()).min(9));
+		let writer = match format {
+			Format::Gzip => Writer::GzipEncoder(GzEncoder::new(output.clone(), compression())),
+			Format::Deflate => Writer::ZlibEncoder(ZlibEncoder::new(output.clone(), compression())),
+			Format::DeflateRaw => Writer::DeflateEncoder(DeflateEncoder::new(output.clone(), compression())),
+			Format::Brotli => {
+				let quality = level.unwrap_or(BROTLI_DEFAULT_QUALITY).min(11);
+				Writer::BrotliEncoder(Box::new(CompressorWriter::new(
+					output.clone(),
+					BROTLI_BUFFER_SIZE,
+					quality,
+					BROTLI_WINDOW_SIZE,
+				)))
+			}
+		};
+		Codec { writer: Some(writer), output }
+	}
+
+	pub fn decompressor(format: Format) -> Codec {
+		let output = Output::default();
+		let writer = match format {
+			Format::Gzip => Writer::GzipDecoder(GzDecoder::new(output.clone())),
+			Format::Deflate => Writer::ZlibDecoder(ZlibDecoder::new(output.clone())),
+			Format::DeflateRaw => Writer::DeflateDecoder(DeflateDecoder::new(output.clone())),
+			Format::Brotli => Writer::BrotliDecoder(Box::new(DecompressorWriter::new(output.clone(), BROTLI_BUFFER_SIZE))),
+		};
+		Codec { writer: Some(writer), output }
+	}
+
+	/// Writes a chunk of data, and returns the output which has been produced since the previous chunk.
+	pub fn write(&mut self, bytes: &[u8]) -> Result<Vec<u8>, Error> {
+		let writer = self.writer.as_mut().ok_or_else(|| Error::new("Codec has already finished", None))?;
+		writer.get_mut().write_all(bytes).map_err(codec_error)?;
+		Ok(self.output.take())
+	}
+
+	/// Finishes the data, and returns the remaining output.
+	/// Decompression fails if the data is truncated.
+	pub fn finish(&mut self) -> Result<Vec<u8>, Error> {
+		if let Some(writer) = self.writer.take() {
+			writer.finish().map_err(codec_error)?;
+		}
+		Ok(self.output.take())
+	}
+}
+
+impl NativeTransform for Codec {
+	fn transform(&mut self, bytes: Vec<u8>) -> Result<Vec<u8>, Error> {
+		self.write(&bytes)
+	}
+
+	fn flush(&mut self) -> Result<Vec<u8>, Error> {
+		self.finish()
+	}
+}
+
+fn codec_error(error: io::Error) -> Error {
+	Error::new(&format!("Invalid compressed data: {}", error), ErrorKind::Type)
+}
+
+/// Compresses all of `bytes` at once.
+pub fn compress(format: Format, level: Option<u32>, bytes: &[u8]) -> Result<Vec<u8>, Error> {
+	let mut codec = Codec::compressor(format, level);
+	let mut output = codec.write(bytes)?;
+	output.extend(codec.finish()?);
+	Ok(output)
+}
+
+/// Decompresses all of `bytes` at once.
+pub fn decompress(format: Format, bytes: &[u8]) -> Result<Vec<u8>, Error> {
+	let mut codec = Codec::decompressor(format);
+	let mut output = codec.write(bytes)?;
+	output.extend(codec.finish()?);
+	Ok(output)
+}
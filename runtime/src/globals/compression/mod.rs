@@ -0,0 +1,16 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+pub use codec::{Codec, compress, decompress, Format};
+pub use stream::{CompressionStream, DecompressionStream};
+use ion::{ClassDefinition, Context, Object};
+
+mod codec;
+mod stream;
+
+pub fn define(cx: &Context, global: &mut Object) -> bool {
+	CompressionStream::init_class(cx, global).0 && DecompressionStream::init_class(cx, global).0
+}
@@ -0,0 +1,89 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use mozjs::jsapi::{Heap, JSObject};
+
+use ion::{Context, Error, ResultExc};
+use ion::class::Reflector;
+
+use crate::globals::compression::codec::{Codec, Format};
+use crate::globals::streams::transform_stream;
+
+/// Returns the readable and writable sides of a transform stream which is backed by `codec`.
+fn codec_stream(cx: &Context, codec: Codec) -> ResultExc<(Box<Heap<*mut JSObject>>, Box<Heap<*mut JSObject>>)> {
+	let stream = transform_stream(cx, codec)?;
+	let side = |name: &str| {
+		stream
+			.get(cx, name)
+			.filter(|side| side.handle().is_object())
+			.map(|side| Heap::boxed(side.handle().to_object()))
+			.ok_or_else(|| Error::new("Transform stream has no readable or writable side", None))
+	};
+	Ok((side("readable")?, side("writable")?))
+}
+
+/// Compresses the chunks written to its `writable` side, which are read from its `readable` side.
+#[js_class]
+pub struct CompressionStream {
+	reflector: Reflector,
+	readable: Box<Heap<*mut JSObject>>,
+	writable: Box<Heap<*mut JSObject>>,
+}
+
+#[js_class]
+impl CompressionStream {
+	#[ion(constructor)]
+	pub fn constructor(cx: &Context, format: Format) -> ResultExc<CompressionStream> {
+		let (readable, writable) = codec_stream(cx, Codec::compressor(format, None))?;
+		Ok(CompressionStream {
+			reflector: Reflector::default(),
+			readable,
+			writable,
+		})
+	}
+
+	#[ion(get)]
+	pub fn get_readable(&self) -> *mut JSObject {
+		self.readable.get()
+	}
+
+	#[ion(get)]
+	pub fn get_writable(&self) -> *mut JSObject {
+		self.writable.get()
+	}
+}
+
+/// Decompresses the chunks written to its `writable` side, which are read from its `readable` side.
+/// The stream errors if the data is invalid or truncated.
+#[js_class]
+pub struct DecompressionStream {
+	reflector: Reflector,
+	readable: Box<Heap<*mut JSObject>>,
+	writable: Box<Heap<*mut JSObject>>,
+}
+
+#[js_class]
+impl DecompressionStream {
+	#[ion(constructor)]
+	pub fn constructor(cx: &Context, format: Format) -> ResultExc<DecompressionStream> {
+		let (readable, writable) = codec_stream(cx, Codec::decompressor(format))?;
+		Ok(DecompressionStream {
+			reflector: Reflector::default(),
+			readable,
+			writable,
+		})
+	}
+
+	#[ion(get)]
+	pub fn get_readable(&self) -> *mut JSObject {
+		self.readable.get()
+	}
+
+	#[ion(get)]
+	pub fn get_writable(&self) -> *mut JSObject {
+		self.writable.get()
+	}
+}
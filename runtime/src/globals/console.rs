@@ -4,10 +4,10 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/.
  */
 
-use std::cell::{Cell, RefCell};
 use std::collections::hash_map::{Entry, HashMap};
+use std::rc::Rc;
+use std::time::Instant;
 
-use chrono::{DateTime, offset::Utc};
 use indent::indent_all_by;
 use indexmap::IndexSet;
 use mozjs::jsapi::JSFunctionSpec;
@@ -17,13 +17,14 @@ use term_table::table_cell::{Alignment, TableCell};
 
 use ion::{Context, Object, OwnedKey, Stack, Value};
 use ion::conversions::FromValue;
-use ion::flags::PropertyFlags;
+use ion::flags::{IteratorFlags, PropertyFlags};
 use ion::format::{format_value, INDENT};
 use ion::format::Config as FormatConfig;
 use ion::format::key::format_key;
 use ion::format::primitive::format_primitive;
 
-use crate::cache::map::find_sourcemap;
+use crate::cache::map::transform_stack_with_sourcemaps;
+use crate::ContextExt;
 use crate::config::{Config, LogLevel};
 
 const ANSI_CLEAR: &str = "\x1b[1;1H";
@@ -31,31 +32,186 @@ const ANSI_CLEAR_SCREEN_DOWN: &str = "\x1b[0J";
 
 const DEFAULT_LABEL: &str = "default";
 
-thread_local! {
-	static COUNT_MAP: RefCell<HashMap<String, u32>> = RefCell::new(HashMap::new());
-	static TIMER_MAP: RefCell<HashMap<String, DateTime<Utc>>> = RefCell::new(HashMap::new());
+/// Holds the counters, timers and group indentation of the `console` global of a runtime, and the backend it writes to.
+pub(crate) struct ConsoleState {
+	counts: HashMap<String, u32>,
+	timers: HashMap<String, Instant>,
+	indents: u16,
+	backend: Rc<dyn ConsoleBackend>,
+}
 
-	static INDENTS: Cell<u16> = Cell::new(0);
+impl Default for ConsoleState {
+	fn default() -> ConsoleState {
+		ConsoleState {
+			counts: HashMap::new(),
+			timers: HashMap::new(),
+			indents: 0,
+			backend: Rc::new(StdioBackend),
+		}
+	}
 }
 
-fn print_indent(is_stderr: bool) {
-	let indents = INDENTS.get();
-	if !is_stderr {
-		print!("{}", INDENT.repeat(indents as usize));
-	} else {
-		eprint!("{}", INDENT.repeat(indents as usize));
+fn with_state<R, F: FnOnce(&mut ConsoleState) -> R>(cx: &Context, f: F) -> R {
+	f(unsafe { &mut (*cx.get_private().as_ptr()).console })
+}
+
+/// Receives the output of the `console` global.
+/// Embedders can implement this to capture console output instead of printing it, with [set_backend].
+pub trait ConsoleBackend {
+	/// Writes a message, which has already been formatted and indented, at the given level.
+	fn write(&self, level: LogLevel, message: &str);
+
+	/// Clears the console, if supported.
+	fn clear(&self) {}
+}
+
+/// Writes messages at [LogLevel::Warn] and [LogLevel::Error] to stderr, and all other messages to stdout.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StdioBackend;
+
+impl ConsoleBackend for StdioBackend {
+	fn write(&self, level: LogLevel, message: &str) {
+		if level.is_stderr() {
+			eprintln!("{}", message);
+		} else {
+			println!("{}", message);
+		}
+	}
+
+	fn clear(&self) {
+		println!("{}", ANSI_CLEAR);
+		println!("{}", ANSI_CLEAR_SCREEN_DOWN);
 	}
 }
 
-fn print_args(cx: &Context, args: &[Value], stderr: bool) {
-	for value in args.iter() {
-		let string = format_value(cx, FormatConfig::default().indentation(INDENTS.get()), value);
-		if !stderr {
-			print!("{} ", string);
+/// Sets the backend of the `console` global of the runtime.
+pub fn set_backend(cx: &Context, backend: Rc<dyn ConsoleBackend>) {
+	with_state(cx, |state| state.backend = backend);
+}
+
+/// Writes a message through the console backend of the runtime, such as an error reported by native code on behalf of scripts.
+pub fn write(cx: &Context, level: LogLevel, message: &str) {
+	let backend = with_state(cx, |state| Rc::clone(&state.backend));
+	backend.write(level, message);
+}
+
+fn format_config() -> FormatConfig {
+	FormatConfig::default()
+}
+
+/// Writes a message to the backend at the current group indentation, indenting each of its lines.
+fn print(cx: &Context, level: LogLevel, message: &str) {
+	let (indents, backend) = with_state(cx, |state| (state.indents, Rc::clone(&state.backend)));
+	let message = indent_all_by(INDENT.len() * indents as usize, message);
+	backend.write(level, &message);
+}
+
+/// Removes the ANSI escape sequences used for colours from a string.
+fn strip_colours(string: &str) -> String {
+	let mut stripped = String::with_capacity(string.len());
+	let mut chars = string.chars().peekable();
+	while let Some(char) = chars.next() {
+		if char == '\x1b' && chars.peek() == Some(&'[') {
+			for char in chars.by_ref() {
+				if ('@'..='~').contains(&char) && char != '[' {
+					break;
+				}
+			}
 		} else {
-			eprint!("{} ", string);
+			stripped.push(char);
 		}
 	}
+	stripped
+}
+
+/// Formats a value for the `%s` specifier, which converts primitives to strings without quotes or colours.
+fn format_string(cx: &Context, value: &Value, cfg: FormatConfig) -> String {
+	let handle = value.handle();
+	if handle.is_object() {
+		format_value(cx, cfg.multiline(false), value)
+	} else if handle.is_symbol() || handle.is_bigint() {
+		strip_colours(&format_primitive(cx, cfg, value))
+	} else {
+		String::from_value(cx, value, false, ()).unwrap_or_default()
+	}
+}
+
+/// Formats a value for the numeric specifiers, truncating it to an integer if `integer` is set.
+fn format_number(cx: &Context, value: &Value, cfg: FormatConfig, integer: bool) -> String {
+	let handle = value.handle();
+	if handle.is_bigint() {
+		return format_primitive(cx, cfg, value);
+	}
+
+	let number = if handle.is_symbol() {
+		f64::NAN
+	} else {
+		f64::from_value(cx, value, false, ()).unwrap_or(f64::NAN)
+	};
+	let number = if integer { number.trunc() } else { number };
+	format_primitive(cx, cfg, &Value::f64(cx, number))
+}
+
+/// Formats the arguments of a logging method, separated by spaces.
+///
+/// If the first argument is a string, it is treated as a format string, where each specifier is replaced with the next argument.
+/// `%s` formats a string, `%d` and `%i` an integer, `%f` a floating-point number, and `%o` and `%O` an object.
+/// `%c` applies CSS styles, which are ignored, and `%%` is replaced with `%`.
+fn format_args(cx: &Context, args: &[Value], cfg: FormatConfig) -> String {
+	let mut strings = Vec::with_capacity(args.len());
+	let mut args = args.iter();
+
+	if let Some(first) = args.as_slice().first().filter(|first| first.handle().is_string()) {
+		args.next();
+		let format = String::from_value(cx, first, true, ()).unwrap();
+		let mut string = String::with_capacity(format.len());
+		let mut chars = format.chars().peekable();
+
+		while let Some(char) = chars.next() {
+			if char != '%' {
+				string.push(char);
+				continue;
+			}
+
+			let specifier = chars.peek().copied();
+			let formatted = match specifier {
+				Some('%') => Some(String::from("%")),
+				Some('s' | 'd' | 'i' | 'f' | 'o' | 'O' | 'c') => args.next().map(|arg| match specifier {
+					Some('s') => format_string(cx, arg, cfg),
+					Some('d' | 'i') => format_number(cx, arg, cfg, true),
+					Some('f') => format_number(cx, arg, cfg, false),
+					Some('o') => format_value(cx, cfg.iteration(IteratorFlags::HIDDEN | IteratorFlags::SYMBOLS).quoted(true), arg),
+					Some('O') => format_value(cx, cfg.quoted(true), arg),
+					_ => String::new(),
+				}),
+				_ => None,
+			};
+
+			match formatted {
+				Some(formatted) => {
+					chars.next();
+					string.push_str(&formatted);
+				}
+				None => string.push(char),
+			}
+		}
+		strings.push(string);
+	}
+
+	strings.extend(args.map(|arg| format_value(cx, cfg, arg)));
+	strings.join(" ")
+}
+
+fn log_with(cx: &Context, level: LogLevel, prefix: Option<&str>, values: &[Value]) {
+	if Config::global().log_level >= level {
+		let message = format_args(cx, values, format_config());
+		let message = match prefix {
+			Some(prefix) if message.is_empty() => String::from(prefix),
+			Some(prefix) => format!("{}: {}", prefix, message),
+			None => message,
+		};
+		print(cx, level, &message);
+	}
 }
 
 // TODO: Convert to Undefinable<String> as null is a valid label
@@ -67,217 +223,205 @@ fn get_label(label: Option<String>) -> String {
 	}
 }
 
+/// Formats the time elapsed since a timer was started.
+fn format_elapsed(start: Instant) -> String {
+	format!("{:.3}ms", start.elapsed().as_secs_f64() * 1000.0)
+}
+
 #[js_fn]
 fn log(cx: &Context, #[ion(varargs)] values: Vec<Value>) {
-	if Config::global().log_level >= LogLevel::Info {
-		print_indent(false);
-		print_args(cx, values.as_slice(), false);
-		println!();
-	}
+	log_with(cx, LogLevel::Info, None, &values);
 }
 
 #[js_fn]
 fn warn(cx: &Context, #[ion(varargs)] values: Vec<Value>) {
-	if Config::global().log_level >= LogLevel::Warn {
-		print_indent(true);
-		print_args(cx, values.as_slice(), true);
-		println!();
-	}
+	log_with(cx, LogLevel::Warn, None, &values);
 }
 
 #[js_fn]
 fn error(cx: &Context, #[ion(varargs)] values: Vec<Value>) {
-	if Config::global().log_level >= LogLevel::Error {
-		print_indent(true);
-		print_args(cx, values.as_slice(), true);
-		println!();
-	}
+	log_with(cx, LogLevel::Error, None, &values);
 }
 
 #[js_fn]
 fn debug(cx: &Context, #[ion(varargs)] values: Vec<Value>) {
-	if Config::global().log_level == LogLevel::Debug {
-		print_indent(false);
-		print_args(cx, values.as_slice(), false);
-		println!();
-	}
+	log_with(cx, LogLevel::Debug, None, &values);
 }
 
 #[js_fn]
 fn assert(cx: &Context, assertion: Option<bool>, #[ion(varargs)] values: Vec<Value>) {
-	if Config::global().log_level >= LogLevel::Error {
-		if let Some(assertion) = assertion {
-			if assertion {
-				return;
-			}
+	if !assertion.unwrap_or_default() {
+		log_with(cx, LogLevel::Error, Some("Assertion Failed"), &values);
+	}
+}
 
-			if values.is_empty() {
-				print_indent(true);
-				eprintln!("Assertion Failed");
-				return;
-			}
+/// Options for [console.dir](dir), which override how the object is formatted.
+#[derive(Default)]
+struct DirOptions {
+	/// Number of levels of nested objects to show, where `null` shows all levels.
+	depth: Option<u16>,
+	colours: Option<bool>,
+	show_hidden: bool,
+}
+
+impl DirOptions {
+	fn from_object(cx: &Context, options: Option<Object>) -> DirOptions {
+		let Some(options) = options else {
+			return DirOptions::default();
+		};
 
-			if values[0].handle().is_string() {
-				print_indent(true);
-				eprint!("Assertion Failed: {} ", format_primitive(cx, FormatConfig::default(), &values[0]));
-				print_args(cx, &values[2..], true);
-				eprintln!();
-				return;
+		let depth = options.get(cx, "depth").filter(|depth| !depth.handle().is_undefined()).map(|depth| {
+			if depth.handle().is_null() {
+				u16::MAX
+			} else {
+				f64::from_value(cx, &depth, false, ()).map_or(u16::MAX, |depth| depth.clamp(0.0, (u16::MAX - 1) as f64) as u16 + 1)
 			}
+		});
+		DirOptions {
+			depth,
+			colours: options.get_as(cx, "colors", false, ()),
+			show_hidden: options.get_as(cx, "showHidden", false, ()).unwrap_or_default(),
+		}
+	}
+}
 
-			print_indent(true);
-			eprint!("Assertion Failed: ");
-			print_args(cx, values.as_slice(), true);
-			println!();
+/// Prints an object with the given options.
+/// The `depth` option sets the number of nested levels which are shown, and `colors` can disable colours.
+#[js_fn]
+fn dir(cx: &Context, item: Option<Value>, options: Option<Object>) {
+	if Config::global().log_level >= LogLevel::Info {
+		let options = DirOptions::from_object(cx, options);
+		let mut cfg = format_config().quoted(true);
+		if let Some(depth) = options.depth {
+			cfg = cfg.max_depth(depth);
+		}
+		if options.show_hidden {
+			cfg = cfg.iteration(IteratorFlags::HIDDEN | IteratorFlags::SYMBOLS);
+		}
+
+		let item = item.unwrap_or_else(|| Value::undefined(cx));
+		let string = format_value(cx, cfg, &item);
+		if options.colours == Some(false) {
+			print(cx, LogLevel::Info, &strip_colours(&string));
 		} else {
-			eprintln!("Assertion Failed:");
+			print(cx, LogLevel::Info, &string);
 		}
 	}
 }
 
 #[js_fn]
-fn clear() {
-	INDENTS.set(0);
-
-	println!("{}", ANSI_CLEAR);
-	println!("{}", ANSI_CLEAR_SCREEN_DOWN);
+fn clear(cx: &Context) {
+	let backend = with_state(cx, |state| {
+		state.indents = 0;
+		Rc::clone(&state.backend)
+	});
+	backend.clear();
 }
 
 #[js_fn]
 fn trace(cx: &Context, #[ion(varargs)] values: Vec<Value>) {
 	if Config::global().log_level == LogLevel::Debug {
-		print_indent(false);
-		print!("Trace: ");
-		print_args(cx, values.as_slice(), false);
-		println!();
+		log_with(cx, LogLevel::Debug, Some("Trace"), &values);
 
 		let mut stack = Stack::from_capture(cx);
-		let indents = ((INDENTS.get() + 1) * 2) as usize;
-
 		if let Some(stack) = &mut stack {
-			for record in &mut stack.records {
-				if let Some(sourcemap) = find_sourcemap(&record.location.file) {
-					record.transform_with_sourcemap(&sourcemap);
-				}
-			}
-
-			println!("{}", &indent_all_by(indents, stack.format()));
+			transform_stack_with_sourcemaps(stack);
+			print(cx, LogLevel::Debug, &indent_all_by(INDENT.len(), stack.format()));
 		} else {
-			eprintln!("Current Stack could not be captured.");
+			print(cx, LogLevel::Error, "Current Stack could not be captured.");
 		}
 	}
 }
 
+/// Prints the label of a group, if it has one, and indents later messages until [console.groupEnd](groupEnd) is called.
 #[js_fn]
 fn group(cx: &Context, #[ion(varargs)] values: Vec<Value>) {
-	INDENTS.set(INDENTS.get().min(u16::MAX - 1) + 1);
-
-	if Config::global().log_level >= LogLevel::Info {
-		print_args(cx, values.as_slice(), false);
-		println!();
+	if !values.is_empty() {
+		log_with(cx, LogLevel::Info, None, &values);
 	}
+	with_state(cx, |state| state.indents = state.indents.min(u16::MAX - 1) + 1);
 }
 
 #[js_fn]
-fn groupEnd() {
-	INDENTS.set(INDENTS.get().max(1) - 1);
+fn groupEnd(cx: &Context) {
+	with_state(cx, |state| state.indents = state.indents.max(1) - 1);
 }
 
 #[js_fn]
-fn count(label: Option<String>) {
+fn count(cx: &Context, label: Option<String>) {
 	let label = get_label(label);
-	COUNT_MAP.with_borrow_mut(|counts| match counts.entry(label.clone()) {
-		Entry::Vacant(v) => {
-			let val = v.insert(1);
-			if Config::global().log_level >= LogLevel::Info {
-				print_indent(false);
-				println!("{}: {}", label, val);
-			}
-		}
-		Entry::Occupied(mut o) => {
-			let val = o.insert(o.get() + 1);
-			if Config::global().log_level >= LogLevel::Info {
-				print_indent(false);
-				println!("{}: {}", label, val);
-			}
-		}
+	let count = with_state(cx, |state| {
+		let count = state.counts.entry(label.clone()).or_insert(0);
+		*count += 1;
+		*count
 	});
+	if Config::global().log_level >= LogLevel::Info {
+		print(cx, LogLevel::Info, &format!("{}: {}", label, count));
+	}
 }
 
 #[js_fn]
-fn countReset(label: Option<String>) {
+fn countReset(cx: &Context, label: Option<String>) {
 	let label = get_label(label);
-	COUNT_MAP.with_borrow_mut(|counts| match counts.entry(label.clone()) {
-		Entry::Vacant(_) => {
-			if Config::global().log_level >= LogLevel::Error {
-				print_indent(true);
-				eprintln!("Count for {} does not exist", label);
-			}
-		}
-		Entry::Occupied(mut o) => {
-			o.insert(0);
-		}
-	});
+	let exists = with_state(cx, |state| state.counts.get_mut(&label).map(|count| *count = 0).is_some());
+	if !exists && Config::global().log_level >= LogLevel::Warn {
+		print(cx, LogLevel::Warn, &format!("Count for {} does not exist", label));
+	}
 }
 
 #[js_fn]
-fn time(label: Option<String>) {
+fn time(cx: &Context, label: Option<String>) {
 	let label = get_label(label);
-	TIMER_MAP.with_borrow_mut(|timers| match timers.entry(label.clone()) {
-		Entry::Vacant(v) => {
-			v.insert(Utc::now());
-		}
-		Entry::Occupied(_) => {
-			if Config::global().log_level >= LogLevel::Error {
-				print_indent(true);
-				eprintln!("Timer {} already exists", label);
-			}
+	let started = with_state(cx, |state| match state.timers.entry(label.clone()) {
+		Entry::Vacant(entry) => {
+			entry.insert(Instant::now());
+			true
 		}
+		Entry::Occupied(_) => false,
 	});
+	if !started && Config::global().log_level >= LogLevel::Warn {
+		print(cx, LogLevel::Warn, &format!("Timer {} already exists", label));
+	}
 }
 
 #[js_fn]
 fn timeLog(cx: &Context, label: Option<String>, #[ion(varargs)] values: Vec<Value>) {
 	let label = get_label(label);
-	TIMER_MAP.with_borrow(|timers| match timers.get(&label) {
+	let start = with_state(cx, |state| state.timers.get(&label).copied());
+	match start {
 		Some(start) => {
 			if Config::global().log_level >= LogLevel::Info {
-				let duration = Utc::now().timestamp_millis() - start.timestamp_millis();
-				print_indent(false);
-				print!("{}: {}ms ", label, duration);
-				print_args(cx, values.as_slice(), false);
-				println!();
+				let mut message = format!("{}: {}", label, format_elapsed(start));
+				if !values.is_empty() {
+					message.push(' ');
+					message.push_str(&format_args(cx, &values, format_config()));
+				}
+				print(cx, LogLevel::Info, &message);
 			}
 		}
 		None => {
-			if Config::global().log_level >= LogLevel::Error {
-				print_indent(true);
-				eprintln!("Timer {} does not exist", label);
+			if Config::global().log_level >= LogLevel::Warn {
+				print(cx, LogLevel::Warn, &format!("Timer {} does not exist", label));
 			}
 		}
-	});
+	}
 }
 
 #[js_fn]
-fn timeEnd(label: Option<String>) {
+fn timeEnd(cx: &Context, label: Option<String>) {
 	let label = get_label(label);
-	TIMER_MAP.with_borrow_mut(|timers| match timers.entry(label.clone()) {
-		Entry::Vacant(_) => {
-			if Config::global().log_level >= LogLevel::Error {
-				print_indent(true);
-				eprintln!("Timer {} does not exist", label);
+	match with_state(cx, |state| state.timers.remove(&label)) {
+		Some(start) => {
+			if Config::global().log_level >= LogLevel::Info {
+				print(cx, LogLevel::Info, &format!("{}: {} - Timer Ended", label, format_elapsed(start)));
 			}
 		}
-		Entry::Occupied(o) => {
-			if Config::global().log_level >= LogLevel::Info {
-				let (_, start_time) = o.remove_entry();
-				let duration = Utc::now().timestamp_millis() - start_time.timestamp_millis();
-				print_indent(false);
-				print!("{}: {}ms - Timer Ended", label, duration);
-				println!();
+		None => {
+			if Config::global().log_level >= LogLevel::Warn {
+				print(cx, LogLevel::Warn, &format!("Timer {} does not exist", label));
 			}
 		}
-	});
+	}
 }
 
 #[js_fn]
@@ -306,7 +450,6 @@ fn table(cx: &Context, data: Value, columns: Option<Vec<String>>) {
 		keys
 	}
 
-	let indents = INDENTS.get();
 	if let Ok(object) = Object::from_value(cx, &data, true, ()) {
 		let (rows, columns, has_values) = if let Some(columns) = columns {
 			let rows = object.keys(cx, None).map(|key| key.to_owned_key(cx));
@@ -386,17 +529,18 @@ fn table(cx: &Context, data: Value, columns: Option<Vec<String>>) {
 			table.add_row(Row::new(table_row));
 		}
 
-		println!("{}", indent_all_by((indents * 2) as usize, table.render()))
+		if Config::global().log_level >= LogLevel::Info {
+			print(cx, LogLevel::Info, &table.render());
+		}
 	} else if Config::global().log_level >= LogLevel::Info {
-		print_indent(true);
-		println!("{}", format_value(cx, FormatConfig::default().indentation(indents), &data));
+		print(cx, LogLevel::Info, &format_args(cx, &[data], format_config()));
 	}
 }
 
 const METHODS: &[JSFunctionSpec] = &[
 	function_spec!(log, 0),
 	function_spec!(log, "info", 0),
-	function_spec!(log, "dir", 0),
+	function_spec!(dir, 0),
 	function_spec!(log, "dirxml", 0),
 	function_spec!(warn, 0),
 	function_spec!(error, 0),
@@ -407,11 +551,11 @@ const METHODS: &[JSFunctionSpec] = &[
 	function_spec!(group, 0),
 	function_spec!(group, "groupCollapsed", 0),
 	function_spec!(groupEnd, 0),
-	function_spec!(count, 1),
-	function_spec!(countReset, 1),
-	function_spec!(time, 1),
-	function_spec!(timeLog, 1),
-	function_spec!(timeEnd, 1),
+	function_spec!(count, 0),
+	function_spec!(countReset, 0),
+	function_spec!(time, 0),
+	function_spec!(timeLog, 0),
+	function_spec!(timeEnd, 0),
 	function_spec!(table, 1),
 	JSFunctionSpec::ZERO,
 ];
@@ -0,0 +1,401 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use sha1::Sha1;
+use sha2::{Digest, Sha256, Sha384, Sha512};
+
+use ion::{Context, Error, ErrorKind, Object, Result, Value};
+use ion::conversions::{ConversionBehavior, FromValue};
+
+use crate::globals::crypto::{BufferSource, not_supported, syntax_error};
+
+/// Calls `$body` with `$digest` as the type of the hash function, which is needed by generic hash-based primitives.
+macro_rules! with_hash {
+	($hash:expr, $digest:ident => $body:expr) => {
+		match $hash {
+			$crate::globals::crypto::algorithm::Hash::Sha1 => {
+				type $digest = ::sha1::Sha1;
+				$body
+			}
+			$crate::globals::crypto::algorithm::Hash::Sha256 => {
+				type $digest = ::sha2::Sha256;
+				$body
+			}
+			$crate::globals::crypto::algorithm::Hash::Sha384 => {
+				type $digest = ::sha2::Sha384;
+				$body
+			}
+			$crate::globals::crypto::algorithm::Hash::Sha512 => {
+				type $digest = ::sha2::Sha512;
+				$body
+			}
+		}
+	};
+}
+
+pub(crate) use with_hash;
+
+/// Represents the algorithms supported by [SubtleCrypto](https://w3c.github.io/webcrypto/#subtlecrypto-interface).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AlgorithmName {
+	RsaOaep,
+	RsaPss,
+	Ecdsa,
+	Ecdh,
+	AesGcm,
+	AesCbc,
+	Hmac,
+	Pbkdf2,
+	Hkdf,
+	Sha1,
+	Sha256,
+	Sha384,
+	Sha512,
+}
+
+const ALGORITHMS: [AlgorithmName; 13] = [
+	AlgorithmName::RsaOaep,
+	AlgorithmName::RsaPss,
+	AlgorithmName::Ecdsa,
+	AlgorithmName::Ecdh,
+	AlgorithmName::AesGcm,
+	AlgorithmName::AesCbc,
+	AlgorithmName::Hmac,
+	AlgorithmName::Pbkdf2,
+	AlgorithmName::Hkdf,
+	AlgorithmName::Sha1,
+	AlgorithmName::Sha256,
+	AlgorithmName::Sha384,
+	AlgorithmName::Sha512,
+];
+
+impl AlgorithmName {
+	pub fn name(self) -> &'static str {
+		match self {
+			AlgorithmName::RsaOaep => "RSA-OAEP",
+			AlgorithmName::RsaPss => "RSA-PSS",
+			AlgorithmName::Ecdsa => "ECDSA",
+			AlgorithmName::Ecdh => "ECDH",
+			AlgorithmName::AesGcm => "AES-GCM",
+			AlgorithmName::AesCbc => "AES-CBC",
+			AlgorithmName::Hmac => "HMAC",
+			AlgorithmName::Pbkdf2 => "PBKDF2",
+			AlgorithmName::Hkdf => "HKDF",
+			AlgorithmName::Sha1 => "SHA-1",
+			AlgorithmName::Sha256 => "SHA-256",
+			AlgorithmName::Sha384 => "SHA-384",
+			AlgorithmName::Sha512 => "SHA-512",
+		}
+	}
+
+	/// Reads the name of an algorithm identifier, which is either a string or an object with a `name` property.
+	/// Names are matched case-insensitively.
+	pub fn from_identifier(cx: &Context, algorithm: &Value) -> Result<AlgorithmName> {
+		let name = if algorithm.handle().is_object() {
+			let algorithm = algorithm.to_object(cx);
+			algorithm
+				.get_as::<_, String>(cx, "name", true, ())
+				.ok_or_else(|| Error::new("Algorithm is missing a name", ErrorKind::Type))?
+		} else {
+			String::from_value(cx, algorithm, false, ())?
+		};
+
+		ALGORITHMS
+			.into_iter()
+			.find(|algorithm| algorithm.name().eq_ignore_ascii_case(&name))
+			.ok_or_else(|| not_supported(&format!("Unrecognised algorithm: {}", name)))
+	}
+}
+
+impl Display for AlgorithmName {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		f.write_str(self.name())
+	}
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Hash {
+	Sha1,
+	Sha256,
+	Sha384,
+	Sha512,
+}
+
+impl Hash {
+	pub fn digest(self, data: &[u8]) -> Vec<u8> {
+		match self {
+			Hash::Sha1 => Sha1::digest(data).to_vec(),
+			Hash::Sha256 => Sha256::digest(data).to_vec(),
+			Hash::Sha384 => Sha384::digest(data).to_vec(),
+			Hash::Sha512 => Sha512::digest(data).to_vec(),
+		}
+	}
+
+	/// Returns the block size of the hash function in bits, which is the default length of HMAC keys.
+	pub fn block_size(self) -> u32 {
+		match self {
+			Hash::Sha1 | Hash::Sha256 => 512,
+			Hash::Sha384 | Hash::Sha512 => 1024,
+		}
+	}
+
+	pub fn from_algorithm(algorithm: AlgorithmName) -> Result<Hash> {
+		match algorithm {
+			AlgorithmName::Sha1 => Ok(Hash::Sha1),
+			AlgorithmName::Sha256 => Ok(Hash::Sha256),
+			AlgorithmName::Sha384 => Ok(Hash::Sha384),
+			AlgorithmName::Sha512 => Ok(Hash::Sha512),
+			_ => Err(not_supported(&format!("{} is not a hash function", algorithm))),
+		}
+	}
+
+	pub fn algorithm(self) -> AlgorithmName {
+		match self {
+			Hash::Sha1 => AlgorithmName::Sha1,
+			Hash::Sha256 => AlgorithmName::Sha256,
+			Hash::Sha384 => AlgorithmName::Sha384,
+			Hash::Sha512 => AlgorithmName::Sha512,
+		}
+	}
+}
+
+impl<'cx> FromValue<'cx> for Hash {
+	type Config = ();
+
+	fn from_value(cx: &'cx Context, value: &Value, _: bool, _: ()) -> Result<Hash> {
+		Hash::from_algorithm(AlgorithmName::from_identifier(cx, value)?)
+	}
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NamedCurve {
+	P256,
+	P384,
+}
+
+impl NamedCurve {
+	/// Returns the size of the field elements of the curve in bytes.
+	pub fn field_size(self) -> usize {
+		match self {
+			NamedCurve::P256 => 32,
+			NamedCurve::P384 => 48,
+		}
+	}
+}
+
+impl FromStr for NamedCurve {
+	type Err = Error;
+
+	fn from_str(curve: &str) -> Result<NamedCurve> {
+		match curve {
+			"P-256" => Ok(NamedCurve::P256),
+			"P-384" => Ok(NamedCurve::P384),
+			_ => Err(not_supported(&format!("Unsupported named curve: {}", curve))),
+		}
+	}
+}
+
+impl Display for NamedCurve {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		match self {
+			NamedCurve::P256 => f.write_str("P-256"),
+			NamedCurve::P384 => f.write_str("P-384"),
+		}
+	}
+}
+
+impl<'cx> FromValue<'cx> for NamedCurve {
+	type Config = ();
+
+	fn from_value(cx: &'cx Context, value: &Value, _: bool, _: ()) -> Result<NamedCurve> {
+		let curve = String::from_value(cx, value, true, ())?;
+		NamedCurve::from_str(&curve)
+	}
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeyFormat {
+	Raw,
+	Pkcs8,
+	Spki,
+	Jwk,
+}
+
+impl FromStr for KeyFormat {
+	type Err = Error;
+
+	fn from_str(format: &str) -> Result<KeyFormat> {
+		match format {
+			"raw" => Ok(KeyFormat::Raw),
+			"pkcs8" => Ok(KeyFormat::Pkcs8),
+			"spki" => Ok(KeyFormat::Spki),
+			"jwk" => Ok(KeyFormat::Jwk),
+			_ => Err(Error::new("Invalid value for Enumeration KeyFormat", ErrorKind::Type)),
+		}
+	}
+}
+
+impl<'cx> FromValue<'cx> for KeyFormat {
+	type Config = ();
+
+	fn from_value(cx: &'cx Context, value: &Value, _: bool, _: ()) -> Result<KeyFormat> {
+		let format = String::from_value(cx, value, true, ())?;
+		KeyFormat::from_str(&format)
+	}
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeyUsage {
+	Encrypt,
+	Decrypt,
+	Sign,
+	Verify,
+	DeriveKey,
+	DeriveBits,
+	WrapKey,
+	UnwrapKey,
+}
+
+impl FromStr for KeyUsage {
+	type Err = Error;
+
+	fn from_str(usage: &str) -> Result<KeyUsage> {
+		match usage {
+			"encrypt" => Ok(KeyUsage::Encrypt),
+			"decrypt" => Ok(KeyUsage::Decrypt),
+			"sign" => Ok(KeyUsage::Sign),
+			"verify" => Ok(KeyUsage::Verify),
+			"deriveKey" => Ok(KeyUsage::DeriveKey),
+			"deriveBits" => Ok(KeyUsage::DeriveBits),
+			"wrapKey" => Ok(KeyUsage::WrapKey),
+			"unwrapKey" => Ok(KeyUsage::UnwrapKey),
+			_ => Err(Error::new("Invalid value for Enumeration KeyUsage", ErrorKind::Type)),
+		}
+	}
+}
+
+impl Display for KeyUsage {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		let str = match self {
+			KeyUsage::Encrypt => "encrypt",
+			KeyUsage::Decrypt => "decrypt",
+			KeyUsage::Sign => "sign",
+			KeyUsage::Verify => "verify",
+			KeyUsage::DeriveKey => "deriveKey",
+			KeyUsage::DeriveBits => "deriveBits",
+			KeyUsage::WrapKey => "wrapKey",
+			KeyUsage::UnwrapKey => "unwrapKey",
+		};
+		f.write_str(str)
+	}
+}
+
+impl<'cx> FromValue<'cx> for KeyUsage {
+	type Config = ();
+
+	fn from_value(cx: &'cx Context, value: &Value, _: bool, _: ()) -> Result<KeyUsage> {
+		let usage = String::from_value(cx, value, true, ())?;
+		KeyUsage::from_str(&usage)
+	}
+}
+
+/// Checks that all `usages` are permitted by an algorithm, and returns them without duplicates.
+pub fn check_usages(usages: &[KeyUsage], permitted: &[KeyUsage]) -> Result<Vec<KeyUsage>> {
+	let mut checked = Vec::with_capacity(usages.len());
+	for usage in usages {
+		if !permitted.contains(usage) {
+			return Err(syntax_error(&format!("Usage {} is not permitted for this key", usage)));
+		}
+		if !checked.contains(usage) {
+			checked.push(*usage);
+		}
+	}
+	Ok(checked)
+}
+
+#[derive(FromValue)]
+pub struct RsaHashedKeyGenParams {
+	#[ion(convert = ConversionBehavior::EnforceRange)]
+	pub modulus_length: u32,
+	pub public_exponent: BufferSource,
+	pub hash: Hash,
+}
+
+#[derive(FromValue)]
+pub struct RsaHashedImportParams {
+	pub hash: Hash,
+}
+
+#[derive(FromValue)]
+pub struct RsaOaepParams {
+	pub label: Option<BufferSource>,
+}
+
+#[derive(FromValue)]
+pub struct RsaPssParams {
+	#[ion(convert = ConversionBehavior::EnforceRange)]
+	pub salt_length: u32,
+}
+
+#[derive(FromValue)]
+pub struct EcKeyParams {
+	pub named_curve: NamedCurve,
+}
+
+#[derive(FromValue)]
+pub struct EcdsaParams {
+	pub hash: Hash,
+}
+
+#[derive(FromValue)]
+pub struct EcdhKeyDeriveParams<'cx> {
+	pub public: Object<'cx>,
+}
+
+#[derive(FromValue)]
+pub struct AesKeyParams {
+	#[ion(convert = ConversionBehavior::EnforceRange)]
+	pub length: u16,
+}
+
+#[derive(FromValue)]
+pub struct AesGcmParams {
+	pub iv: BufferSource,
+	pub additional_data: Option<BufferSource>,
+	#[ion(convert = ConversionBehavior::EnforceRange)]
+	pub tag_length: Option<u8>,
+}
+
+#[derive(FromValue)]
+pub struct AesCbcParams {
+	pub iv: BufferSource,
+}
+
+#[derive(FromValue)]
+pub struct HmacKeyParams {
+	pub hash: Hash,
+	#[ion(convert = ConversionBehavior::EnforceRange)]
+	pub length: Option<u32>,
+}
+
+#[derive(FromValue)]
+pub struct Pbkdf2Params {
+	pub salt: BufferSource,
+	#[ion(convert = ConversionBehavior::EnforceRange)]
+	pub iterations: u32,
+	pub hash: Hash,
+}
+
+#[derive(FromValue)]
+pub struct HkdfParams {
+	pub hash: Hash,
+	pub salt: BufferSource,
+	pub info: BufferSource,
+}
@@ -0,0 +1,115 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use base64::Engine;
+use base64::prelude::BASE64_URL_SAFE_NO_PAD;
+
+use ion::{Context, Object, Result};
+
+use crate::globals::crypto::algorithm::KeyUsage;
+use crate::globals::crypto::data_error;
+
+/// Represents a [JSON Web Key](https://www.rfc-editor.org/rfc/rfc7517), which keys are imported from and exported to with the `jwk` format.
+#[derive(Debug, Default, FromValue)]
+pub struct JsonWebKey {
+	pub kty: Option<String>,
+	pub alg: Option<String>,
+	#[ion(name = "key_ops")]
+	pub key_ops: Option<Vec<String>>,
+	pub ext: Option<bool>,
+
+	pub k: Option<String>,
+
+	pub crv: Option<String>,
+	pub x: Option<String>,
+	pub y: Option<String>,
+
+	pub n: Option<String>,
+	pub e: Option<String>,
+	pub p: Option<String>,
+	pub q: Option<String>,
+	pub dp: Option<String>,
+	pub dq: Option<String>,
+	pub qi: Option<String>,
+
+	pub d: Option<String>,
+}
+
+impl JsonWebKey {
+	pub fn new(kty: &str) -> JsonWebKey {
+		JsonWebKey {
+			kty: Some(String::from(kty)),
+			..JsonWebKey::default()
+		}
+	}
+
+	/// Checks the members common to all keys, before the key material is read.
+	pub fn check(&self, kty: &str, alg: Option<&str>, extractable: bool, usages: &[KeyUsage]) -> Result<()> {
+		if self.kty.as_deref() != Some(kty) {
+			return Err(data_error(&format!("Expected JSON Web Key of type {}", kty)));
+		}
+		if let (Some(expected), Some(alg)) = (alg, &self.alg) {
+			if expected != alg {
+				return Err(data_error(&format!("JSON Web Key algorithm {} does not match {}", alg, expected)));
+			}
+		}
+		if self.ext == Some(false) && extractable {
+			return Err(data_error("JSON Web Key is not extractable"));
+		}
+		if let Some(key_ops) = &self.key_ops {
+			if let Some(usage) = usages.iter().find(|usage| !key_ops.contains(&usage.to_string())) {
+				return Err(data_error(&format!("JSON Web Key does not permit the {} operation", usage)));
+			}
+		}
+		Ok(())
+	}
+
+	/// Decodes a base64url-encoded member of the key.
+	pub fn decode(member: &Option<String>, name: &str) -> Result<Vec<u8>> {
+		let member = member
+			.as_deref()
+			.ok_or_else(|| data_error(&format!("JSON Web Key is missing {}", name)))?;
+		BASE64_URL_SAFE_NO_PAD
+			.decode(member)
+			.map_err(|_| data_error(&format!("JSON Web Key has an invalid {}", name)))
+	}
+
+	pub fn encode(bytes: &[u8]) -> Option<String> {
+		Some(BASE64_URL_SAFE_NO_PAD.encode(bytes))
+	}
+
+	pub fn to_object<'cx>(&self, cx: &'cx Context) -> Object<'cx> {
+		let mut object = Object::new(cx);
+		let members = [
+			("kty", &self.kty),
+			("alg", &self.alg),
+			("k", &self.k),
+			("crv", &self.crv),
+			("x", &self.x),
+			("y", &self.y),
+			("n", &self.n),
+			("e", &self.e),
+			("d", &self.d),
+			("p", &self.p),
+			("q", &self.q),
+			("dp", &self.dp),
+			("dq", &self.dq),
+			("qi", &self.qi),
+		];
+		for (name, member) in members {
+			if let Some(member) = member {
+				object.set_as(cx, name, member);
+			}
+		}
+		if let Some(key_ops) = &self.key_ops {
+			object.set_as(cx, "key_ops", key_ops);
+		}
+		if let Some(ext) = self.ext {
+			object.set_as(cx, "ext", &ext);
+		}
+		object
+	}
+}
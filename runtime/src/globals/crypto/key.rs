@@ -0,0 +1,202 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use mozjs::jsapi::JSObject;
+use rsa::{RsaPrivateKey, RsaPublicKey};
+
+use ion::{ClassDefinition, Context, Error, ErrorKind, Object, Result};
+use ion::class::Reflector;
+use ion::typedarray::Uint8Array;
+
+use crate::globals::crypto::algorithm::{AlgorithmName, Hash, KeyUsage, NamedCurve};
+use crate::globals::crypto::invalid_access;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeyType {
+	Secret,
+	Public,
+	Private,
+}
+
+impl KeyType {
+	fn name(self) -> &'static str {
+		match self {
+			KeyType::Secret => "secret",
+			KeyType::Public => "public",
+			KeyType::Private => "private",
+		}
+	}
+}
+
+/// Describes the algorithm a key is used with, which is exposed as [CryptoKey.algorithm](CryptoKey::get_algorithm).
+#[derive(Clone, Debug)]
+pub enum KeyAlgorithm {
+	Rsa {
+		name: AlgorithmName,
+		modulus_length: u32,
+		public_exponent: Vec<u8>,
+		hash: Hash,
+	},
+	Ec {
+		name: AlgorithmName,
+		curve: NamedCurve,
+	},
+	Aes {
+		name: AlgorithmName,
+		length: u16,
+	},
+	Hmac {
+		hash: Hash,
+		length: u32,
+	},
+	Kdf {
+		name: AlgorithmName,
+	},
+}
+
+impl KeyAlgorithm {
+	pub fn name(&self) -> AlgorithmName {
+		match self {
+			KeyAlgorithm::Rsa { name, .. } | KeyAlgorithm::Ec { name, .. } | KeyAlgorithm::Aes { name, .. } | KeyAlgorithm::Kdf { name } => *name,
+			KeyAlgorithm::Hmac { .. } => AlgorithmName::Hmac,
+		}
+	}
+
+	fn to_object<'cx>(&self, cx: &'cx Context) -> Object<'cx> {
+		let mut object = Object::new(cx);
+		object.set_as(cx, "name", self.name().name());
+		match self {
+			KeyAlgorithm::Rsa {
+				modulus_length, public_exponent, hash, ..
+			} => {
+				object.set_as(cx, "modulusLength", modulus_length);
+				object.set_as(cx, "publicExponent", &Uint8Array::from(public_exponent.clone()));
+				object.set_as(cx, "hash", &hash_object(cx, *hash));
+			}
+			KeyAlgorithm::Ec { curve, .. } => {
+				object.set_as(cx, "namedCurve", &curve.to_string());
+			}
+			KeyAlgorithm::Aes { length, .. } => {
+				object.set_as(cx, "length", length);
+			}
+			KeyAlgorithm::Hmac { hash, length } => {
+				object.set_as(cx, "hash", &hash_object(cx, *hash));
+				object.set_as(cx, "length", length);
+			}
+			KeyAlgorithm::Kdf { .. } => {}
+		}
+		object
+	}
+}
+
+fn hash_object(cx: &Context, hash: Hash) -> Object {
+	let mut object = Object::new(cx);
+	object.set_as(cx, "name", hash.algorithm().name());
+	object
+}
+
+#[derive(Clone, Debug)]
+pub enum EcPublicKey {
+	P256(p256::PublicKey),
+	P384(p384::PublicKey),
+}
+
+#[derive(Clone, Debug)]
+pub enum EcPrivateKey {
+	P256(p256::SecretKey),
+	P384(p384::SecretKey),
+}
+
+/// Holds the key material of a [CryptoKey], which is never exposed to scripts unless the key is exported.
+#[derive(Clone, Debug)]
+pub enum KeyMaterial {
+	Secret(Vec<u8>),
+	RsaPublic(RsaPublicKey),
+	RsaPrivate(RsaPrivateKey),
+	EcPublic(EcPublicKey),
+	EcPrivate(EcPrivateKey),
+}
+
+#[js_class]
+pub struct CryptoKey {
+	reflector: Reflector,
+	#[ion(no_trace)]
+	pub(crate) kind: KeyType,
+	#[ion(no_trace)]
+	pub(crate) extractable: bool,
+	#[ion(no_trace)]
+	pub(crate) algorithm: KeyAlgorithm,
+	#[ion(no_trace)]
+	pub(crate) usages: Vec<KeyUsage>,
+	#[ion(no_trace)]
+	pub(crate) material: KeyMaterial,
+}
+
+impl CryptoKey {
+	pub(crate) fn new(algorithm: KeyAlgorithm, material: KeyMaterial, extractable: bool, usages: Vec<KeyUsage>) -> CryptoKey {
+		let kind = match material {
+			KeyMaterial::Secret(_) => KeyType::Secret,
+			KeyMaterial::RsaPublic(_) | KeyMaterial::EcPublic(_) => KeyType::Public,
+			KeyMaterial::RsaPrivate(_) | KeyMaterial::EcPrivate(_) => KeyType::Private,
+		};
+		CryptoKey {
+			reflector: Reflector::default(),
+			kind,
+			extractable,
+			algorithm,
+			usages,
+			material,
+		}
+	}
+
+	/// Returns the [CryptoKey] of an object, if it is one.
+	pub(crate) fn from_object<'a>(cx: &Context, object: &Object<'a>) -> Result<&'a CryptoKey> {
+		if CryptoKey::instance_of(cx, object, None) {
+			Ok(CryptoKey::get_private(object))
+		} else {
+			Err(Error::new("Expected CryptoKey", ErrorKind::Type))
+		}
+	}
+
+	/// Checks that the key can be used for an operation of an algorithm.
+	pub(crate) fn check(&self, algorithm: AlgorithmName, usage: KeyUsage) -> Result<()> {
+		if self.algorithm.name() != algorithm {
+			return Err(invalid_access(&format!("Key cannot be used with {}", algorithm)));
+		}
+		if !self.usages.contains(&usage) {
+			return Err(invalid_access(&format!("Key does not support the {} operation", usage)));
+		}
+		Ok(())
+	}
+}
+
+#[js_class]
+impl CryptoKey {
+	#[ion(constructor)]
+	pub fn constructor() -> Result<CryptoKey> {
+		Err(Error::new("CryptoKey has no constructor.", ErrorKind::Type))
+	}
+
+	#[ion(get)]
+	pub fn get_type(&self) -> String {
+		String::from(self.kind.name())
+	}
+
+	#[ion(get)]
+	pub fn get_extractable(&self) -> bool {
+		self.extractable
+	}
+
+	#[ion(get)]
+	pub fn get_algorithm(&self, cx: &Context) -> *mut JSObject {
+		self.algorithm.to_object(cx).handle().get()
+	}
+
+	#[ion(get)]
+	pub fn get_usages(&self) -> Vec<String> {
+		self.usages.iter().map(KeyUsage::to_string).collect()
+	}
+}
@@ -0,0 +1,115 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use mozjs::jsapi::{JSFunctionSpec, JSObject, Type};
+use mozjs::typedarray::{ArrayBuffer, ArrayBufferView};
+use rand::RngCore;
+use rand::rngs::OsRng;
+
+use ion::{ClassDefinition, Context, Error, ErrorKind, Object, Result, Value};
+use ion::conversions::FromValue;
+use ion::flags::PropertyFlags;
+pub use key::CryptoKey;
+
+mod algorithm;
+mod jwk;
+mod key;
+mod subtle;
+
+/// Maximum number of bytes which can be filled by a single call to `getRandomValues`.
+const MAX_RANDOM_BYTES: usize = 65536;
+
+/// Represents an [ArrayBuffer](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/ArrayBuffer) or a view over one,
+/// whose bytes are copied when it is converted.
+#[derive(Clone, Debug, Default)]
+pub struct BufferSource(pub Vec<u8>);
+
+impl<'cx> FromValue<'cx> for BufferSource {
+	type Config = ();
+
+	fn from_value(cx: &'cx Context, value: &Value, strict: bool, _: ()) -> Result<BufferSource> {
+		if let Ok(buffer) = ArrayBuffer::from_value(cx, value, strict, ()) {
+			return Ok(BufferSource(unsafe { buffer.as_slice() }.to_vec()));
+		}
+		ArrayBufferView::from_value(cx, value, strict, ())
+			.map(|view| BufferSource(unsafe { view.as_slice() }.to_vec()))
+			.map_err(|_| Error::new("Expected ArrayBuffer or ArrayBufferView", ErrorKind::Type))
+	}
+}
+
+fn not_supported(message: &str) -> Error {
+	Error::new(message, None).with_name("NotSupportedError")
+}
+
+fn syntax_error(message: &str) -> Error {
+	Error::new(message, None).with_name("SyntaxError")
+}
+
+fn invalid_access(message: &str) -> Error {
+	Error::new(message, None).with_name("InvalidAccessError")
+}
+
+fn data_error(message: &str) -> Error {
+	Error::new(message, None).with_name("DataError")
+}
+
+fn operation_error(message: &str) -> Error {
+	Error::new(message, None).with_name("OperationError")
+}
+
+/// Fills an integer typed array with cryptographically secure random values.
+#[js_fn]
+fn getRandomValues(array: Object) -> Result<*mut JSObject> {
+	let mut view = ArrayBufferView::from(array.handle().get()).map_err(|_| Error::new("Expected Integer TypedArray", ErrorKind::Type))?;
+	match view.get_array_type() {
+		Type::Int8
+		| Type::Uint8
+		| Type::Uint8Clamped
+		| Type::Int16
+		| Type::Uint16
+		| Type::Int32
+		| Type::Uint32
+		| Type::BigInt64
+		| Type::BigUint64 => {}
+		_ => {
+			return Err(Error::new("Expected Integer TypedArray", None).with_name("TypeMismatchError"));
+		}
+	}
+
+	let bytes = unsafe { view.as_mut_slice() };
+	if bytes.len() > MAX_RANDOM_BYTES {
+		return Err(Error::new(
+			&format!("TypedArray of {} bytes exceeds the maximum of {} bytes", bytes.len(), MAX_RANDOM_BYTES),
+			None,
+		)
+		.with_name("QuotaExceededError"));
+	}
+	OsRng.fill_bytes(bytes);
+	Ok(array.handle().get())
+}
+
+/// Generates a random version 4 [UUID](https://www.rfc-editor.org/rfc/rfc4122).
+#[js_fn]
+fn randomUUID() -> String {
+	let mut bytes = [0; 16];
+	OsRng.fill_bytes(&mut bytes);
+	bytes[6] = (bytes[6] & 0x0F) | 0x40;
+	bytes[8] = (bytes[8] & 0x3F) | 0x80;
+
+	let hex: String = bytes.iter().map(|byte| format!("{:02x}", byte)).collect();
+	format!("{}-{}-{}-{}-{}", &hex[0..8], &hex[8..12], &hex[12..16], &hex[16..20], &hex[20..32])
+}
+
+const FUNCTIONS: &[JSFunctionSpec] = &[function_spec!(getRandomValues, 1), function_spec!(randomUUID, 0), JSFunctionSpec::ZERO];
+
+pub fn define(cx: &Context, global: &mut Object) -> bool {
+	let mut crypto = Object::new(cx);
+	let mut subtle = Object::new(cx);
+	unsafe { crypto.define_methods(cx, FUNCTIONS) && subtle.define_methods(cx, subtle::FUNCTIONS) }
+	&&crypto.define_as(cx, "subtle", &subtle, PropertyFlags::CONSTANT_ENUMERATED)
+		&& CryptoKey::init_class(cx, global).0
+		&& global.define_as(cx, "crypto", &crypto, PropertyFlags::CONSTANT_ENUMERATED)
+}
@@ -0,0 +1,151 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use aes::{Aes128, Aes192, Aes256};
+use aes_gcm::{Aes128Gcm, Aes256Gcm, AesGcm};
+use aes_gcm::aead::{Aead, KeyInit, Nonce, Payload};
+use aes_gcm::aead::consts::U12;
+use cbc::{Decryptor, Encryptor};
+use cbc::cipher::{BlockCipher, BlockDecryptMut, BlockEncryptMut, KeyIvInit};
+use cbc::cipher::block_padding::Pkcs7;
+use rand::RngCore;
+use rand::rngs::OsRng;
+
+use ion::{Context, Result, Value};
+use ion::conversions::FromValue;
+
+use crate::globals::crypto::{data_error, not_supported, operation_error};
+use crate::globals::crypto::algorithm::{AesCbcParams, AesGcmParams, AesKeyParams, AlgorithmName, check_usages, KeyFormat, KeyUsage};
+use crate::globals::crypto::jwk::JsonWebKey;
+use crate::globals::crypto::key::{CryptoKey, KeyAlgorithm, KeyMaterial};
+use crate::globals::crypto::subtle::{ExportedKey, GeneratedKey, KeyData, secret};
+
+type Aes192Gcm = AesGcm<Aes192, U12>;
+
+const PERMITTED_USAGES: &[KeyUsage] = &[KeyUsage::Encrypt, KeyUsage::Decrypt, KeyUsage::WrapKey, KeyUsage::UnwrapKey];
+
+fn jwk_algorithm(name: AlgorithmName, length: u16) -> String {
+	match name {
+		AlgorithmName::AesGcm => format!("A{}GCM", length),
+		_ => format!("A{}CBC", length),
+	}
+}
+
+fn check_length(length: u16) -> Result<u16> {
+	match length {
+		128 | 192 | 256 => Ok(length),
+		_ => Err(operation_error("AES key length must be 128, 192 or 256 bits")),
+	}
+}
+
+pub(super) fn generate_key(cx: &Context, algorithm: &Value, name: AlgorithmName, extractable: bool, usages: &[KeyUsage]) -> Result<GeneratedKey> {
+	let length = check_length(AesKeyParams::from_value(cx, algorithm, false, ())?.length)?;
+	let usages = check_usages(usages, PERMITTED_USAGES)?;
+
+	let mut bytes = vec![0; length as usize / 8];
+	OsRng.fill_bytes(&mut bytes);
+	let algorithm = KeyAlgorithm::Aes { name, length };
+	Ok(GeneratedKey::Key(CryptoKey::new(
+		algorithm,
+		KeyMaterial::Secret(bytes),
+		extractable,
+		usages,
+	)))
+}
+
+pub(super) fn import_key(data: KeyData, name: AlgorithmName, extractable: bool, usages: &[KeyUsage]) -> Result<CryptoKey> {
+	let usages = check_usages(usages, PERMITTED_USAGES)?;
+	let bytes = match data {
+		KeyData::Raw(bytes) => bytes,
+		KeyData::Jwk(jwk) => {
+			let bytes = JsonWebKey::decode(&jwk.k, "k")?;
+			let alg = jwk_algorithm(name, (bytes.len() * 8) as u16);
+			jwk.check("oct", Some(&alg), extractable, &usages)?;
+			bytes
+		}
+		_ => return Err(not_supported("AES keys can only be imported in the raw or jwk formats")),
+	};
+
+	let length = check_length((bytes.len() * 8) as u16).map_err(|_| data_error("AES key length must be 128, 192 or 256 bits"))?;
+	let algorithm = KeyAlgorithm::Aes { name, length };
+	Ok(CryptoKey::new(algorithm, KeyMaterial::Secret(bytes), extractable, usages))
+}
+
+pub(super) fn export_key(format: KeyFormat, key: &CryptoKey) -> Result<ExportedKey> {
+	let bytes = secret(key)?;
+	match format {
+		KeyFormat::Raw => Ok(ExportedKey::Bytes(bytes.to_vec())),
+		KeyFormat::Jwk => {
+			let mut jwk = JsonWebKey::new("oct");
+			jwk.alg = Some(jwk_algorithm(key.algorithm.name(), (bytes.len() * 8) as u16));
+			jwk.k = JsonWebKey::encode(bytes);
+			jwk.key_ops = Some(key.usages.iter().map(KeyUsage::to_string).collect());
+			jwk.ext = Some(key.extractable);
+			Ok(ExportedKey::Jwk(jwk))
+		}
+		_ => Err(not_supported("AES keys can only be exported in the raw or jwk formats")),
+	}
+}
+
+pub(super) fn crypt_gcm(cx: &Context, algorithm: &Value, key: &CryptoKey, data: &[u8], encrypt: bool) -> Result<Vec<u8>> {
+	let params = AesGcmParams::from_value(cx, algorithm, false, ())?;
+	if params.iv.0.len() != 12 {
+		return Err(not_supported("AES-GCM only supports initialisation vectors of 12 bytes"));
+	}
+	if params.tag_length.is_some_and(|length| length != 128) {
+		return Err(not_supported("AES-GCM only supports tags of 128 bits"));
+	}
+
+	let bytes = secret(key)?;
+	let aad = params.additional_data.as_ref().map(|data| &*data.0).unwrap_or_default();
+	let payload = Payload { msg: data, aad };
+	match bytes.len() {
+		16 => gcm_crypt::<Aes128Gcm>(bytes, &params.iv.0, payload, encrypt),
+		24 => gcm_crypt::<Aes192Gcm>(bytes, &params.iv.0, payload, encrypt),
+		_ => gcm_crypt::<Aes256Gcm>(bytes, &params.iv.0, payload, encrypt),
+	}
+}
+
+fn gcm_crypt<C: Aead + KeyInit>(key: &[u8], iv: &[u8], payload: Payload, encrypt: bool) -> Result<Vec<u8>> {
+	let cipher = C::new_from_slice(key).map_err(|_| data_error("Invalid AES key"))?;
+	let nonce = Nonce::<C>::from_slice(iv);
+	if encrypt {
+		cipher.encrypt(nonce, payload).map_err(|_| operation_error("Encryption failed"))
+	} else {
+		cipher.decrypt(nonce, payload).map_err(|_| operation_error("Decryption failed"))
+	}
+}
+
+pub(super) fn crypt_cbc(cx: &Context, algorithm: &Value, key: &CryptoKey, data: &[u8], encrypt: bool) -> Result<Vec<u8>> {
+	let iv = AesCbcParams::from_value(cx, algorithm, false, ())?.iv;
+	if iv.0.len() != 16 {
+		return Err(operation_error("AES-CBC initialisation vectors must be 16 bytes"));
+	}
+
+	let bytes = secret(key)?;
+	match bytes.len() {
+		16 => cbc_crypt::<Aes128>(bytes, &iv.0, data, encrypt),
+		24 => cbc_crypt::<Aes192>(bytes, &iv.0, data, encrypt),
+		_ => cbc_crypt::<Aes256>(bytes, &iv.0, data, encrypt),
+	}
+}
+
+fn cbc_crypt<C>(key: &[u8], iv: &[u8], data: &[u8], encrypt: bool) -> Result<Vec<u8>>
+where
+	C: BlockCipher + BlockEncryptMut + BlockDecryptMut + KeyInit,
+	Encryptor<C>: KeyIvInit,
+	Decryptor<C>: KeyIvInit,
+{
+	if encrypt {
+		let encryptor = Encryptor::<C>::new_from_slices(key, iv).map_err(|_| data_error("Invalid AES key"))?;
+		Ok(encryptor.encrypt_padded_vec_mut::<Pkcs7>(data))
+	} else {
+		let decryptor = Decryptor::<C>::new_from_slices(key, iv).map_err(|_| data_error("Invalid AES key"))?;
+		decryptor
+			.decrypt_padded_vec_mut::<Pkcs7>(data)
+			.map_err(|_| operation_error("Decryption failed"))
+	}
+}
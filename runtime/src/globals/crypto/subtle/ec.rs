@@ -0,0 +1,268 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use p256::ecdsa::signature::hazmat::{PrehashSigner, PrehashVerifier};
+use p256::elliptic_curve::sec1::ToEncodedPoint;
+use p256::pkcs8::{DecodePrivateKey, DecodePublicKey, EncodePrivateKey, EncodePublicKey};
+use rand::rngs::OsRng;
+
+use ion::{Context, Result, Value};
+use ion::conversions::FromValue;
+
+use crate::globals::crypto::{data_error, invalid_access, not_supported, operation_error};
+use crate::globals::crypto::algorithm::{AlgorithmName, check_usages, EcdhKeyDeriveParams, EcdsaParams, EcKeyParams, KeyFormat, KeyUsage, NamedCurve};
+use crate::globals::crypto::jwk::JsonWebKey;
+use crate::globals::crypto::key::{CryptoKey, EcPrivateKey, EcPublicKey, KeyAlgorithm, KeyMaterial};
+use crate::globals::crypto::subtle::{ExportedKey, GeneratedKey, KeyData};
+
+/// Evaluates `$body` with `$module` as the crate implementing the curve of `$key`, which is bound to `$inner`.
+macro_rules! with_curve {
+	($key:expr, $kind:ident, $inner:ident, $module:ident => $body:expr) => {
+		match $key {
+			$kind::P256($inner) => {
+				#[allow(unused_imports)]
+				use p256 as $module;
+				$body
+			}
+			$kind::P384($inner) => {
+				#[allow(unused_imports)]
+				use p384 as $module;
+				$body
+			}
+		}
+	};
+}
+
+fn permitted_usages(name: AlgorithmName) -> (&'static [KeyUsage], &'static [KeyUsage]) {
+	match name {
+		AlgorithmName::Ecdsa => (&[KeyUsage::Verify], &[KeyUsage::Sign]),
+		_ => (&[], &[KeyUsage::DeriveKey, KeyUsage::DeriveBits]),
+	}
+}
+
+fn jwk_algorithm(name: AlgorithmName, curve: NamedCurve) -> Option<&'static str> {
+	match (name, curve) {
+		(AlgorithmName::Ecdsa, NamedCurve::P256) => Some("ES256"),
+		(AlgorithmName::Ecdsa, NamedCurve::P384) => Some("ES384"),
+		_ => None,
+	}
+}
+
+fn key_curve(key: &CryptoKey) -> NamedCurve {
+	match &key.algorithm {
+		KeyAlgorithm::Ec { curve, .. } => *curve,
+		_ => unreachable!(),
+	}
+}
+
+fn public_key(private: &EcPrivateKey) -> EcPublicKey {
+	match private {
+		EcPrivateKey::P256(secret) => EcPublicKey::P256(secret.public_key()),
+		EcPrivateKey::P384(secret) => EcPublicKey::P384(secret.public_key()),
+	}
+}
+
+/// Returns the uncompressed point of a public key.
+fn encoded_point(public: &EcPublicKey) -> Vec<u8> {
+	with_curve!(public, EcPublicKey, public, _module => public.to_encoded_point(false).as_bytes().to_vec())
+}
+
+fn public_from_sec1(curve: NamedCurve, bytes: &[u8]) -> Result<EcPublicKey> {
+	let public = match curve {
+		NamedCurve::P256 => p256::PublicKey::from_sec1_bytes(bytes).map(EcPublicKey::P256),
+		NamedCurve::P384 => p384::PublicKey::from_sec1_bytes(bytes).map(EcPublicKey::P384),
+	};
+	public.map_err(|_| data_error("Invalid elliptic curve public key"))
+}
+
+fn public_from_spki(curve: NamedCurve, bytes: &[u8]) -> Result<EcPublicKey> {
+	let public = match curve {
+		NamedCurve::P256 => p256::PublicKey::from_public_key_der(bytes).map(EcPublicKey::P256),
+		NamedCurve::P384 => p384::PublicKey::from_public_key_der(bytes).map(EcPublicKey::P384),
+	};
+	public.map_err(|_| data_error("Invalid SPKI key"))
+}
+
+fn private_from_pkcs8(curve: NamedCurve, bytes: &[u8]) -> Result<EcPrivateKey> {
+	let private = match curve {
+		NamedCurve::P256 => p256::SecretKey::from_pkcs8_der(bytes).map(EcPrivateKey::P256),
+		NamedCurve::P384 => p384::SecretKey::from_pkcs8_der(bytes).map(EcPrivateKey::P384),
+	};
+	private.map_err(|_| data_error("Invalid PKCS #8 key"))
+}
+
+fn private_from_scalar(curve: NamedCurve, bytes: &[u8]) -> Result<EcPrivateKey> {
+	let private = match curve {
+		NamedCurve::P256 => p256::SecretKey::from_slice(bytes).map(EcPrivateKey::P256),
+		NamedCurve::P384 => p384::SecretKey::from_slice(bytes).map(EcPrivateKey::P384),
+	};
+	private.map_err(|_| data_error("Invalid elliptic curve private key"))
+}
+
+fn import_jwk(jwk: JsonWebKey, name: AlgorithmName, curve: NamedCurve, extractable: bool, usages: &[KeyUsage]) -> Result<KeyMaterial> {
+	jwk.check("EC", jwk_algorithm(name, curve), extractable, usages)?;
+	if jwk.crv.as_deref() != Some(&curve.to_string()) {
+		return Err(data_error(&format!("Expected JSON Web Key with curve {}", curve)));
+	}
+
+	let x = JsonWebKey::decode(&jwk.x, "x")?;
+	let y = JsonWebKey::decode(&jwk.y, "y")?;
+	if x.len() != curve.field_size() || y.len() != curve.field_size() {
+		return Err(data_error("Invalid elliptic curve coordinates"));
+	}
+	let point: Vec<u8> = [&[0x04], &*x, &*y].concat();
+	let public = public_from_sec1(curve, &point)?;
+
+	if jwk.d.is_some() {
+		let private = private_from_scalar(curve, &JsonWebKey::decode(&jwk.d, "d")?)?;
+		if encoded_point(&public_key(&private)) != point {
+			return Err(data_error("JSON Web Key private key does not match its public key"));
+		}
+		Ok(KeyMaterial::EcPrivate(private))
+	} else {
+		Ok(KeyMaterial::EcPublic(public))
+	}
+}
+
+pub(super) fn generate_key(cx: &Context, algorithm: &Value, name: AlgorithmName, extractable: bool, usages: &[KeyUsage]) -> Result<GeneratedKey> {
+	let curve = EcKeyParams::from_value(cx, algorithm, false, ())?.named_curve;
+	let (public_usages, private_usages) = permitted_usages(name);
+	let permitted: Vec<_> = public_usages.iter().chain(private_usages).copied().collect();
+	let usages = check_usages(usages, &permitted)?;
+
+	let private = match curve {
+		NamedCurve::P256 => EcPrivateKey::P256(p256::SecretKey::random(&mut OsRng)),
+		NamedCurve::P384 => EcPrivateKey::P384(p384::SecretKey::random(&mut OsRng)),
+	};
+	let public = public_key(&private);
+	let algorithm = KeyAlgorithm::Ec { name, curve };
+
+	let (public_key_usages, private_key_usages): (Vec<_>, Vec<_>) = usages.into_iter().partition(|usage| public_usages.contains(usage));
+	Ok(GeneratedKey::Pair {
+		public: CryptoKey::new(algorithm.clone(), KeyMaterial::EcPublic(public), true, public_key_usages),
+		private: CryptoKey::new(algorithm, KeyMaterial::EcPrivate(private), extractable, private_key_usages),
+	})
+}
+
+pub(super) fn import_key(
+	cx: &Context, data: KeyData, algorithm: &Value, name: AlgorithmName, extractable: bool, usages: &[KeyUsage],
+) -> Result<CryptoKey> {
+	let curve = EcKeyParams::from_value(cx, algorithm, false, ())?.named_curve;
+	let (public_usages, private_usages) = permitted_usages(name);
+
+	let material = match data {
+		KeyData::Raw(bytes) => KeyMaterial::EcPublic(public_from_sec1(curve, &bytes)?),
+		KeyData::Spki(bytes) => KeyMaterial::EcPublic(public_from_spki(curve, &bytes)?),
+		KeyData::Pkcs8(bytes) => KeyMaterial::EcPrivate(private_from_pkcs8(curve, &bytes)?),
+		KeyData::Jwk(jwk) => import_jwk(jwk, name, curve, extractable, usages)?,
+	};
+
+	let permitted = match material {
+		KeyMaterial::EcPublic(_) => public_usages,
+		_ => private_usages,
+	};
+	let usages = check_usages(usages, permitted)?;
+	Ok(CryptoKey::new(KeyAlgorithm::Ec { name, curve }, material, extractable, usages))
+}
+
+pub(super) fn export_key(format: KeyFormat, key: &CryptoKey) -> Result<ExportedKey> {
+	match (format, &key.material) {
+		(KeyFormat::Raw, KeyMaterial::EcPublic(public)) => Ok(ExportedKey::Bytes(encoded_point(public))),
+		(KeyFormat::Spki, KeyMaterial::EcPublic(public)) => {
+			let der = with_curve!(public, EcPublicKey, public, _module => public.to_public_key_der());
+			let der = der.map_err(|error| operation_error(&error.to_string()))?;
+			Ok(ExportedKey::Bytes(der.as_bytes().to_vec()))
+		}
+		(KeyFormat::Pkcs8, KeyMaterial::EcPrivate(private)) => {
+			let der = with_curve!(private, EcPrivateKey, private, _module => private.to_pkcs8_der());
+			let der = der.map_err(|error| operation_error(&error.to_string()))?;
+			Ok(ExportedKey::Bytes(der.as_bytes().to_vec()))
+		}
+		(KeyFormat::Jwk, material) => {
+			let curve = key_curve(key);
+			let mut jwk = JsonWebKey::new("EC");
+			jwk.alg = jwk_algorithm(key.algorithm.name(), curve).map(String::from);
+			jwk.crv = Some(curve.to_string());
+			jwk.key_ops = Some(key.usages.iter().map(KeyUsage::to_string).collect());
+			jwk.ext = Some(key.extractable);
+
+			let public = match material {
+				KeyMaterial::EcPublic(public) => public.clone(),
+				KeyMaterial::EcPrivate(private) => {
+					let scalar = with_curve!(private, EcPrivateKey, private, _module => private.to_bytes().to_vec());
+					jwk.d = JsonWebKey::encode(&scalar);
+					public_key(private)
+				}
+				_ => unreachable!(),
+			};
+			let point = encoded_point(&public);
+			let (x, y) = point[1..].split_at(curve.field_size());
+			jwk.x = JsonWebKey::encode(x);
+			jwk.y = JsonWebKey::encode(y);
+			Ok(ExportedKey::Jwk(jwk))
+		}
+		_ => Err(not_supported(&format!("{} keys cannot be exported in this format", key.algorithm.name()))),
+	}
+}
+
+pub(super) fn sign(cx: &Context, algorithm: &Value, key: &CryptoKey, data: &[u8]) -> Result<Vec<u8>> {
+	let hash = EcdsaParams::from_value(cx, algorithm, false, ())?.hash;
+	let KeyMaterial::EcPrivate(private) = &key.material else {
+		return Err(invalid_access("Expected private key"));
+	};
+
+	let digest = hash.digest(data);
+	with_curve!(private, EcPrivateKey, private, module => {
+		let signing = module::ecdsa::SigningKey::from(private);
+		let signature: module::ecdsa::Signature = signing.sign_prehash(&digest).map_err(|error| operation_error(&error.to_string()))?;
+		Ok(signature.to_bytes().to_vec())
+	})
+}
+
+pub(super) fn verify(cx: &Context, algorithm: &Value, key: &CryptoKey, signature: &[u8], data: &[u8]) -> Result<bool> {
+	let hash = EcdsaParams::from_value(cx, algorithm, false, ())?.hash;
+	let KeyMaterial::EcPublic(public) = &key.material else {
+		return Err(invalid_access("Expected public key"));
+	};
+
+	let digest = hash.digest(data);
+	with_curve!(public, EcPublicKey, public, module => {
+		let verifying = module::ecdsa::VerifyingKey::from(public);
+		let Ok(signature) = module::ecdsa::Signature::from_slice(signature) else {
+			return Ok(false);
+		};
+		Ok(verifying.verify_prehash(&digest, &signature).is_ok())
+	})
+}
+
+pub(super) fn derive_bits(cx: &Context, algorithm: &Value, key: &CryptoKey, length: Option<u32>) -> Result<Vec<u8>> {
+	let public = EcdhKeyDeriveParams::from_value(cx, algorithm, false, ())?.public;
+	let public = CryptoKey::from_object(cx, &public)?;
+	if public.algorithm.name() != AlgorithmName::Ecdh {
+		return Err(invalid_access("Public key must be an ECDH key"));
+	}
+
+	let shared = match (&key.material, &public.material) {
+		(KeyMaterial::EcPrivate(EcPrivateKey::P256(secret)), KeyMaterial::EcPublic(EcPublicKey::P256(public))) => {
+			p256::ecdh::diffie_hellman(secret.to_nonzero_scalar(), public.as_affine())
+				.raw_secret_bytes()
+				.to_vec()
+		}
+		(KeyMaterial::EcPrivate(EcPrivateKey::P384(secret)), KeyMaterial::EcPublic(EcPublicKey::P384(public))) => {
+			p384::ecdh::diffie_hellman(secret.to_nonzero_scalar(), public.as_affine())
+				.raw_secret_bytes()
+				.to_vec()
+		}
+		(KeyMaterial::EcPrivate(_), KeyMaterial::EcPublic(_)) => return Err(invalid_access("Keys must use the same named curve")),
+		_ => return Err(invalid_access("Expected private key and public key")),
+	};
+
+	match length {
+		Some(length) if length as usize / 8 > shared.len() => Err(operation_error("Length is larger than the derived secret")),
+		Some(length) => Ok(shared[..length as usize / 8].to_vec()),
+		None => Ok(shared),
+	}
+}
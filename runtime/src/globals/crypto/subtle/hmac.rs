@@ -0,0 +1,121 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use rand::rngs::OsRng;
+
+use ion::{Context, Result, Value};
+use ion::conversions::FromValue;
+
+use crate::globals::crypto::{data_error, not_supported, operation_error};
+use crate::globals::crypto::algorithm::{check_usages, Hash, HmacKeyParams, KeyFormat, KeyUsage, with_hash};
+use crate::globals::crypto::jwk::JsonWebKey;
+use crate::globals::crypto::key::{CryptoKey, KeyAlgorithm, KeyMaterial};
+use crate::globals::crypto::subtle::{ExportedKey, GeneratedKey, KeyData, secret};
+
+const PERMITTED_USAGES: &[KeyUsage] = &[KeyUsage::Sign, KeyUsage::Verify];
+
+fn jwk_algorithm(hash: Hash) -> &'static str {
+	match hash {
+		Hash::Sha1 => "HS1",
+		Hash::Sha256 => "HS256",
+		Hash::Sha384 => "HS384",
+		Hash::Sha512 => "HS512",
+	}
+}
+
+fn key_hash(key: &CryptoKey) -> Hash {
+	match &key.algorithm {
+		KeyAlgorithm::Hmac { hash, .. } => *hash,
+		_ => unreachable!(),
+	}
+}
+
+/// Returns the length of a key in bits, which must be within the last byte of the key material.
+fn key_length(length: Option<u32>, bytes: usize) -> Result<u32> {
+	let bits = (bytes * 8) as u32;
+	match length {
+		None => Ok(bits),
+		Some(0) => Err(data_error("HMAC key length cannot be 0")),
+		Some(length) if length > bits || length <= bits.saturating_sub(8) => Err(data_error("HMAC key length does not match the key data")),
+		Some(length) => Ok(length),
+	}
+}
+
+pub(super) fn generate_key(cx: &Context, algorithm: &Value, extractable: bool, usages: &[KeyUsage]) -> Result<GeneratedKey> {
+	let params = HmacKeyParams::from_value(cx, algorithm, false, ())?;
+	let usages = check_usages(usages, PERMITTED_USAGES)?;
+	let length = params.length.unwrap_or_else(|| params.hash.block_size());
+	if length == 0 {
+		return Err(operation_error("HMAC key length cannot be 0"));
+	}
+
+	let mut bytes = vec![0; length.div_ceil(8) as usize];
+	OsRng.fill_bytes(&mut bytes);
+	let algorithm = KeyAlgorithm::Hmac { hash: params.hash, length };
+	Ok(GeneratedKey::Key(CryptoKey::new(
+		algorithm,
+		KeyMaterial::Secret(bytes),
+		extractable,
+		usages,
+	)))
+}
+
+pub(super) fn import_key(cx: &Context, data: KeyData, algorithm: &Value, extractable: bool, usages: &[KeyUsage]) -> Result<CryptoKey> {
+	let HmacKeyParams { hash, length } = HmacKeyParams::from_value(cx, algorithm, false, ())?;
+	let usages = check_usages(usages, PERMITTED_USAGES)?;
+
+	let bytes = match data {
+		KeyData::Raw(bytes) => bytes,
+		KeyData::Jwk(jwk) => {
+			jwk.check("oct", Some(jwk_algorithm(hash)), extractable, &usages)?;
+			JsonWebKey::decode(&jwk.k, "k")?
+		}
+		_ => return Err(not_supported("HMAC keys can only be imported in the raw or jwk formats")),
+	};
+	if bytes.is_empty() {
+		return Err(data_error("HMAC key cannot be empty"));
+	}
+
+	let length = key_length(length, bytes.len())?;
+	let algorithm = KeyAlgorithm::Hmac { hash, length };
+	Ok(CryptoKey::new(algorithm, KeyMaterial::Secret(bytes), extractable, usages))
+}
+
+pub(super) fn export_key(format: KeyFormat, key: &CryptoKey) -> Result<ExportedKey> {
+	let bytes = secret(key)?;
+	match format {
+		KeyFormat::Raw => Ok(ExportedKey::Bytes(bytes.to_vec())),
+		KeyFormat::Jwk => {
+			let mut jwk = JsonWebKey::new("oct");
+			jwk.alg = Some(String::from(jwk_algorithm(key_hash(key))));
+			jwk.k = JsonWebKey::encode(bytes);
+			jwk.key_ops = Some(key.usages.iter().map(KeyUsage::to_string).collect());
+			jwk.ext = Some(key.extractable);
+			Ok(ExportedKey::Jwk(jwk))
+		}
+		_ => Err(not_supported("HMAC keys can only be exported in the raw or jwk formats")),
+	}
+}
+
+pub(super) fn sign(key: &CryptoKey, data: &[u8]) -> Result<Vec<u8>> {
+	let bytes = secret(key)?;
+	with_hash!(key_hash(key), D => {
+		let mut mac = Hmac::<D>::new_from_slice(bytes).map_err(|error| operation_error(&error.to_string()))?;
+		mac.update(data);
+		Ok(mac.finalize().into_bytes().to_vec())
+	})
+}
+
+pub(super) fn verify(key: &CryptoKey, signature: &[u8], data: &[u8]) -> Result<bool> {
+	let bytes = secret(key)?;
+	with_hash!(key_hash(key), D => {
+		let mut mac = Hmac::<D>::new_from_slice(bytes).map_err(|error| operation_error(&error.to_string()))?;
+		mac.update(data);
+		Ok(mac.verify_slice(signature).is_ok())
+	})
+}
@@ -0,0 +1,51 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use hkdf::Hkdf;
+use pbkdf2::pbkdf2_hmac;
+
+use ion::{Context, Result, Value};
+use ion::conversions::FromValue;
+
+use crate::globals::crypto::{not_supported, operation_error, syntax_error};
+use crate::globals::crypto::algorithm::{AlgorithmName, check_usages, HkdfParams, KeyUsage, Pbkdf2Params, with_hash};
+use crate::globals::crypto::key::{CryptoKey, KeyAlgorithm, KeyMaterial};
+use crate::globals::crypto::subtle::{KeyData, secret};
+
+const PERMITTED_USAGES: &[KeyUsage] = &[KeyUsage::DeriveKey, KeyUsage::DeriveBits];
+
+pub(super) fn import_key(data: KeyData, name: AlgorithmName, extractable: bool, usages: &[KeyUsage]) -> Result<CryptoKey> {
+	let KeyData::Raw(bytes) = data else {
+		return Err(not_supported(&format!("{} keys can only be imported in the raw format", name)));
+	};
+	if extractable {
+		return Err(syntax_error(&format!("{} keys cannot be extractable", name)));
+	}
+	let usages = check_usages(usages, PERMITTED_USAGES)?;
+	Ok(CryptoKey::new(KeyAlgorithm::Kdf { name }, KeyMaterial::Secret(bytes), false, usages))
+}
+
+pub(super) fn derive_bits(cx: &Context, algorithm: &Value, key: &CryptoKey, bytes: usize) -> Result<Vec<u8>> {
+	let secret = secret(key)?;
+	let mut derived = vec![0; bytes];
+
+	match key.algorithm.name() {
+		AlgorithmName::Pbkdf2 => {
+			let params = Pbkdf2Params::from_value(cx, algorithm, false, ())?;
+			if params.iterations == 0 {
+				return Err(operation_error("PBKDF2 iterations cannot be 0"));
+			}
+			with_hash!(params.hash, D => pbkdf2_hmac::<D>(secret, &params.salt.0, params.iterations, &mut derived));
+		}
+		AlgorithmName::Hkdf => {
+			let params = HkdfParams::from_value(cx, algorithm, false, ())?;
+			with_hash!(params.hash, D => Hkdf::<D>::new(Some(&params.salt.0), secret).expand(&params.info.0, &mut derived))
+				.map_err(|_| operation_error("HKDF length is too large"))?;
+		}
+		_ => unreachable!(),
+	}
+	Ok(derived)
+}
@@ -0,0 +1,292 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use mozjs::jsapi::{JSFunctionSpec, JSObject};
+
+use ion::{ClassDefinition, Context, Object, Promise, Result, Value};
+use ion::conversions::{ConversionBehavior, FromValue, ToValue};
+use ion::typedarray::ArrayBuffer;
+
+use crate::globals::crypto::{BufferSource, CryptoKey, data_error, invalid_access, not_supported, operation_error, syntax_error};
+use crate::globals::crypto::algorithm::{AesKeyParams, AlgorithmName, Hash, HmacKeyParams, KeyFormat, KeyUsage};
+use crate::globals::crypto::jwk::JsonWebKey;
+use crate::globals::crypto::key::{KeyMaterial, KeyType};
+
+mod aes;
+mod ec;
+mod hmac;
+mod kdf;
+mod rsa;
+
+/// Represents a key in the format it was exported in.
+enum ExportedKey {
+	Bytes(Vec<u8>),
+	Jwk(JsonWebKey),
+}
+
+impl ExportedKey {
+	fn to_value<'cx>(&self, cx: &'cx Context) -> Value<'cx> {
+		match self {
+			ExportedKey::Bytes(bytes) => ArrayBuffer::from(bytes.clone()).as_value(cx),
+			ExportedKey::Jwk(jwk) => jwk.to_object(cx).as_value(cx),
+		}
+	}
+}
+
+/// Represents the data a key is imported from, which depends on the format.
+enum KeyData {
+	Raw(Vec<u8>),
+	Pkcs8(Vec<u8>),
+	Spki(Vec<u8>),
+	Jwk(JsonWebKey),
+}
+
+impl KeyData {
+	fn from_value(cx: &Context, format: KeyFormat, data: &Value) -> Result<KeyData> {
+		match format {
+			KeyFormat::Raw => BufferSource::from_value(cx, data, false, ()).map(|data| KeyData::Raw(data.0)),
+			KeyFormat::Pkcs8 => BufferSource::from_value(cx, data, false, ()).map(|data| KeyData::Pkcs8(data.0)),
+			KeyFormat::Spki => BufferSource::from_value(cx, data, false, ()).map(|data| KeyData::Spki(data.0)),
+			KeyFormat::Jwk => JsonWebKey::from_value(cx, data, false, ()).map(KeyData::Jwk),
+		}
+	}
+}
+
+/// Represents the result of generating a key, which is a single key for symmetric algorithms, or a key pair.
+enum GeneratedKey {
+	Key(CryptoKey),
+	Pair { public: CryptoKey, private: CryptoKey },
+}
+
+fn settle<'cx, T: ToValue<'cx>>(cx: &'cx Context, result: Result<T>) -> Promise<'cx> {
+	let promise = Promise::new(cx);
+	match result {
+		Ok(value) => promise.resolve(cx, &value.as_value(cx)),
+		Err(error) => promise.reject(cx, &error.as_value(cx)),
+	};
+	promise
+}
+
+fn new_key(cx: &Context, key: CryptoKey) -> *mut JSObject {
+	CryptoKey::new_object(cx, Box::new(key))
+}
+
+/// Checks that a secret or private key has at least one usage, which is required when it is created.
+fn require_usages(key: CryptoKey) -> Result<CryptoKey> {
+	if key.kind != KeyType::Public && key.usages.is_empty() {
+		return Err(syntax_error("Key must have at least one usage"));
+	}
+	Ok(key)
+}
+
+#[js_fn]
+fn digest<'cx>(cx: &'cx Context, algorithm: Value<'cx>, data: BufferSource) -> Promise<'cx> {
+	let digest = AlgorithmName::from_identifier(cx, &algorithm)
+		.and_then(Hash::from_algorithm)
+		.map(|hash| ArrayBuffer::from(hash.digest(&data.0)));
+	settle(cx, digest)
+}
+
+#[js_fn]
+fn encrypt<'cx>(cx: &'cx Context, algorithm: Value<'cx>, key: Object<'cx>, data: BufferSource) -> Promise<'cx> {
+	settle(cx, crypt(cx, &algorithm, &key, &data.0, KeyUsage::Encrypt).map(ArrayBuffer::from))
+}
+
+#[js_fn]
+fn decrypt<'cx>(cx: &'cx Context, algorithm: Value<'cx>, key: Object<'cx>, data: BufferSource) -> Promise<'cx> {
+	settle(cx, crypt(cx, &algorithm, &key, &data.0, KeyUsage::Decrypt).map(ArrayBuffer::from))
+}
+
+fn crypt(cx: &Context, algorithm: &Value, key: &Object, data: &[u8], usage: KeyUsage) -> Result<Vec<u8>> {
+	let name = AlgorithmName::from_identifier(cx, algorithm)?;
+	let key = CryptoKey::from_object(cx, key)?;
+	key.check(name, usage)?;
+
+	let encrypt = usage == KeyUsage::Encrypt;
+	match name {
+		AlgorithmName::RsaOaep => rsa::crypt(cx, algorithm, key, data, encrypt),
+		AlgorithmName::AesGcm => aes::crypt_gcm(cx, algorithm, key, data, encrypt),
+		AlgorithmName::AesCbc => aes::crypt_cbc(cx, algorithm, key, data, encrypt),
+		_ => Err(not_supported(&format!("{} does not support encryption", name))),
+	}
+}
+
+#[js_fn]
+fn sign<'cx>(cx: &'cx Context, algorithm: Value<'cx>, key: Object<'cx>, data: BufferSource) -> Promise<'cx> {
+	settle(cx, sign_data(cx, &algorithm, &key, &data.0).map(ArrayBuffer::from))
+}
+
+fn sign_data(cx: &Context, algorithm: &Value, key: &Object, data: &[u8]) -> Result<Vec<u8>> {
+	let name = AlgorithmName::from_identifier(cx, algorithm)?;
+	let key = CryptoKey::from_object(cx, key)?;
+	key.check(name, KeyUsage::Sign)?;
+
+	match name {
+		AlgorithmName::RsaPss => rsa::sign(cx, algorithm, key, data),
+		AlgorithmName::Ecdsa => ec::sign(cx, algorithm, key, data),
+		AlgorithmName::Hmac => hmac::sign(key, data),
+		_ => Err(not_supported(&format!("{} does not support signatures", name))),
+	}
+}
+
+#[js_fn]
+fn verify<'cx>(cx: &'cx Context, algorithm: Value<'cx>, key: Object<'cx>, signature: BufferSource, data: BufferSource) -> Promise<'cx> {
+	settle(cx, verify_data(cx, &algorithm, &key, &signature.0, &data.0))
+}
+
+fn verify_data(cx: &Context, algorithm: &Value, key: &Object, signature: &[u8], data: &[u8]) -> Result<bool> {
+	let name = AlgorithmName::from_identifier(cx, algorithm)?;
+	let key = CryptoKey::from_object(cx, key)?;
+	key.check(name, KeyUsage::Verify)?;
+
+	match name {
+		AlgorithmName::RsaPss => rsa::verify(cx, algorithm, key, signature, data),
+		AlgorithmName::Ecdsa => ec::verify(cx, algorithm, key, signature, data),
+		AlgorithmName::Hmac => hmac::verify(key, signature, data),
+		_ => Err(not_supported(&format!("{} does not support signatures", name))),
+	}
+}
+
+#[js_fn]
+fn generateKey<'cx>(cx: &'cx Context, algorithm: Value<'cx>, extractable: bool, usages: Vec<KeyUsage>) -> Promise<'cx> {
+	settle(cx, generate(cx, &algorithm, extractable, &usages))
+}
+
+fn generate(cx: &Context, algorithm: &Value, extractable: bool, usages: &[KeyUsage]) -> Result<*mut JSObject> {
+	let name = AlgorithmName::from_identifier(cx, algorithm)?;
+	let key = match name {
+		AlgorithmName::RsaOaep | AlgorithmName::RsaPss => rsa::generate_key(cx, algorithm, name, extractable, usages)?,
+		AlgorithmName::Ecdsa | AlgorithmName::Ecdh => ec::generate_key(cx, algorithm, name, extractable, usages)?,
+		AlgorithmName::AesGcm | AlgorithmName::AesCbc => aes::generate_key(cx, algorithm, name, extractable, usages)?,
+		AlgorithmName::Hmac => hmac::generate_key(cx, algorithm, extractable, usages)?,
+		_ => return Err(not_supported(&format!("{} does not support key generation", name))),
+	};
+
+	match key {
+		GeneratedKey::Key(key) => Ok(new_key(cx, require_usages(key)?)),
+		GeneratedKey::Pair { public, private } => {
+			let private = require_usages(private)?;
+			let mut pair = Object::new(cx);
+			pair.set_as(cx, "publicKey", &new_key(cx, public));
+			pair.set_as(cx, "privateKey", &new_key(cx, private));
+			Ok(pair.handle().get())
+		}
+	}
+}
+
+#[js_fn]
+fn importKey<'cx>(
+	cx: &'cx Context, format: KeyFormat, data: Value<'cx>, algorithm: Value<'cx>, extractable: bool, usages: Vec<KeyUsage>,
+) -> Promise<'cx> {
+	let key = KeyData::from_value(cx, format, &data).and_then(|data| {
+		let name = AlgorithmName::from_identifier(cx, &algorithm)?;
+		let key = import(cx, data, &algorithm, name, extractable, &usages)?;
+		Ok(new_key(cx, require_usages(key)?))
+	});
+	settle(cx, key)
+}
+
+fn import(cx: &Context, data: KeyData, algorithm: &Value, name: AlgorithmName, extractable: bool, usages: &[KeyUsage]) -> Result<CryptoKey> {
+	match name {
+		AlgorithmName::RsaOaep | AlgorithmName::RsaPss => rsa::import_key(cx, data, algorithm, name, extractable, usages),
+		AlgorithmName::Ecdsa | AlgorithmName::Ecdh => ec::import_key(cx, data, algorithm, name, extractable, usages),
+		AlgorithmName::AesGcm | AlgorithmName::AesCbc => aes::import_key(data, name, extractable, usages),
+		AlgorithmName::Hmac => hmac::import_key(cx, data, algorithm, extractable, usages),
+		AlgorithmName::Pbkdf2 | AlgorithmName::Hkdf => kdf::import_key(data, name, extractable, usages),
+		_ => Err(not_supported(&format!("{} does not support key import", name))),
+	}
+}
+
+#[js_fn]
+fn exportKey<'cx>(cx: &'cx Context, format: KeyFormat, key: Object<'cx>) -> Promise<'cx> {
+	let exported = CryptoKey::from_object(cx, &key).and_then(|key| export(format, key));
+	settle(cx, exported.map(|exported| exported.to_value(cx)))
+}
+
+fn export(format: KeyFormat, key: &CryptoKey) -> Result<ExportedKey> {
+	if !key.extractable {
+		return Err(invalid_access("Key is not extractable"));
+	}
+
+	match key.algorithm.name() {
+		AlgorithmName::RsaOaep | AlgorithmName::RsaPss => rsa::export_key(format, key),
+		AlgorithmName::Ecdsa | AlgorithmName::Ecdh => ec::export_key(format, key),
+		AlgorithmName::AesGcm | AlgorithmName::AesCbc => aes::export_key(format, key),
+		AlgorithmName::Hmac => hmac::export_key(format, key),
+		name => Err(not_supported(&format!("{} does not support key export", name))),
+	}
+}
+
+#[js_fn]
+fn deriveBits<'cx>(
+	cx: &'cx Context, algorithm: Value<'cx>, key: Object<'cx>, #[ion(convert = ConversionBehavior::EnforceRange)] length: Option<u32>,
+) -> Promise<'cx> {
+	let bits = CryptoKey::from_object(cx, &key).and_then(|key| derive(cx, &algorithm, key, length, KeyUsage::DeriveBits));
+	settle(cx, bits.map(ArrayBuffer::from))
+}
+
+#[js_fn]
+fn deriveKey<'cx>(
+	cx: &'cx Context, algorithm: Value<'cx>, key: Object<'cx>, derived: Value<'cx>, extractable: bool, usages: Vec<KeyUsage>,
+) -> Promise<'cx> {
+	let key = CryptoKey::from_object(cx, &key).and_then(|key| derive_key(cx, &algorithm, key, &derived, extractable, &usages));
+	settle(cx, key)
+}
+
+fn derive_key(cx: &Context, algorithm: &Value, key: &CryptoKey, derived: &Value, extractable: bool, usages: &[KeyUsage]) -> Result<*mut JSObject> {
+	let name = AlgorithmName::from_identifier(cx, derived)?;
+	let length = match name {
+		AlgorithmName::AesGcm | AlgorithmName::AesCbc => AesKeyParams::from_value(cx, derived, false, ())?.length as u32,
+		AlgorithmName::Hmac => {
+			let params = HmacKeyParams::from_value(cx, derived, false, ())?;
+			params.length.unwrap_or_else(|| params.hash.block_size())
+		}
+		_ => return Err(not_supported(&format!("{} keys cannot be derived", name))),
+	};
+
+	let bits = derive(cx, algorithm, key, Some(length), KeyUsage::DeriveKey)?;
+	let key = import(cx, KeyData::Raw(bits), derived, name, extractable, usages)?;
+	Ok(new_key(cx, require_usages(key)?))
+}
+
+fn derive(cx: &Context, algorithm: &Value, key: &CryptoKey, length: Option<u32>, usage: KeyUsage) -> Result<Vec<u8>> {
+	let name = AlgorithmName::from_identifier(cx, algorithm)?;
+	key.check(name, usage)?;
+	if length.is_some_and(|length| length % 8 != 0) {
+		return Err(operation_error("Length must be a multiple of 8"));
+	}
+
+	match name {
+		AlgorithmName::Ecdh => ec::derive_bits(cx, algorithm, key, length),
+		AlgorithmName::Pbkdf2 | AlgorithmName::Hkdf => {
+			let length = length.ok_or_else(|| operation_error("Length is required"))?;
+			kdf::derive_bits(cx, algorithm, key, length as usize / 8)
+		}
+		_ => Err(not_supported(&format!("{} does not support key derivation", name))),
+	}
+}
+
+/// Returns the secret bytes of a key.
+fn secret(key: &CryptoKey) -> Result<&[u8]> {
+	match &key.material {
+		KeyMaterial::Secret(secret) => Ok(secret),
+		_ => Err(data_error("Expected secret key")),
+	}
+}
+
+pub(super) const FUNCTIONS: &[JSFunctionSpec] = &[
+	function_spec!(digest, 2),
+	function_spec!(encrypt, 3),
+	function_spec!(decrypt, 3),
+	function_spec!(sign, 3),
+	function_spec!(verify, 4),
+	function_spec!(generateKey, 3),
+	function_spec!(importKey, 5),
+	function_spec!(exportKey, 2),
+	function_spec!(deriveBits, 3),
+	function_spec!(deriveKey, 5),
+	JSFunctionSpec::ZERO,
+];
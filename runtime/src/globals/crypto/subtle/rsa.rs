@@ -0,0 +1,204 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use rand::rngs::OsRng;
+use rsa::{BigUint, Oaep, Pss, RsaPrivateKey, RsaPublicKey};
+use rsa::pkcs8::{DecodePrivateKey, DecodePublicKey, EncodePrivateKey, EncodePublicKey};
+use rsa::traits::{PrivateKeyParts, PublicKeyParts};
+
+use ion::{Context, Result, Value};
+use ion::conversions::FromValue;
+
+use crate::globals::crypto::{data_error, invalid_access, not_supported, operation_error};
+use crate::globals::crypto::algorithm::{
+	AlgorithmName, check_usages, Hash, KeyFormat, KeyUsage, RsaHashedImportParams, RsaHashedKeyGenParams, RsaOaepParams, RsaPssParams, with_hash,
+};
+use crate::globals::crypto::jwk::JsonWebKey;
+use crate::globals::crypto::key::{CryptoKey, KeyAlgorithm, KeyMaterial};
+use crate::globals::crypto::subtle::{ExportedKey, GeneratedKey, KeyData};
+
+fn permitted_usages(name: AlgorithmName) -> (&'static [KeyUsage], &'static [KeyUsage]) {
+	match name {
+		AlgorithmName::RsaOaep => (&[KeyUsage::Encrypt, KeyUsage::WrapKey], &[KeyUsage::Decrypt, KeyUsage::UnwrapKey]),
+		_ => (&[KeyUsage::Verify], &[KeyUsage::Sign]),
+	}
+}
+
+fn key_algorithm(name: AlgorithmName, public: &RsaPublicKey, hash: Hash) -> KeyAlgorithm {
+	KeyAlgorithm::Rsa {
+		name,
+		modulus_length: (public.size() * 8) as u32,
+		public_exponent: public.e().to_bytes_be(),
+		hash,
+	}
+}
+
+fn key_hash(key: &CryptoKey) -> Hash {
+	match &key.algorithm {
+		KeyAlgorithm::Rsa { hash, .. } => *hash,
+		_ => unreachable!(),
+	}
+}
+
+/// Returns the JSON Web Key algorithm of a key, which is not defined for RSA-PSS with SHA-1.
+fn jwk_algorithm(name: AlgorithmName, hash: Hash) -> Option<&'static str> {
+	match (name, hash) {
+		(AlgorithmName::RsaOaep, Hash::Sha1) => Some("RSA-OAEP"),
+		(AlgorithmName::RsaOaep, Hash::Sha256) => Some("RSA-OAEP-256"),
+		(AlgorithmName::RsaOaep, Hash::Sha384) => Some("RSA-OAEP-384"),
+		(AlgorithmName::RsaOaep, Hash::Sha512) => Some("RSA-OAEP-512"),
+		(_, Hash::Sha256) => Some("PS256"),
+		(_, Hash::Sha384) => Some("PS384"),
+		(_, Hash::Sha512) => Some("PS512"),
+		(_, Hash::Sha1) => None,
+	}
+}
+
+pub(super) fn generate_key(cx: &Context, algorithm: &Value, name: AlgorithmName, extractable: bool, usages: &[KeyUsage]) -> Result<GeneratedKey> {
+	let params = RsaHashedKeyGenParams::from_value(cx, algorithm, false, ())?;
+	let (public_usages, private_usages) = permitted_usages(name);
+	let permitted: Vec<_> = public_usages.iter().chain(private_usages).copied().collect();
+	let usages = check_usages(usages, &permitted)?;
+
+	let exponent = BigUint::from_bytes_be(&params.public_exponent.0);
+	let private =
+		RsaPrivateKey::new_with_exp(&mut OsRng, params.modulus_length as usize, &exponent).map_err(|error| operation_error(&error.to_string()))?;
+	let public = private.to_public_key();
+	let algorithm = key_algorithm(name, &public, params.hash);
+
+	let (public_key_usages, private_key_usages): (Vec<_>, Vec<_>) = usages.into_iter().partition(|usage| public_usages.contains(usage));
+	Ok(GeneratedKey::Pair {
+		public: CryptoKey::new(algorithm.clone(), KeyMaterial::RsaPublic(public), true, public_key_usages),
+		private: CryptoKey::new(algorithm, KeyMaterial::RsaPrivate(private), extractable, private_key_usages),
+	})
+}
+
+pub(super) fn import_key(
+	cx: &Context, data: KeyData, algorithm: &Value, name: AlgorithmName, extractable: bool, usages: &[KeyUsage],
+) -> Result<CryptoKey> {
+	let hash = RsaHashedImportParams::from_value(cx, algorithm, false, ())?.hash;
+	let (public_usages, private_usages) = permitted_usages(name);
+
+	let material = match data {
+		KeyData::Spki(bytes) => KeyMaterial::RsaPublic(RsaPublicKey::from_public_key_der(&bytes).map_err(|_| data_error("Invalid SPKI key"))?),
+		KeyData::Pkcs8(bytes) => KeyMaterial::RsaPrivate(RsaPrivateKey::from_pkcs8_der(&bytes).map_err(|_| data_error("Invalid PKCS #8 key"))?),
+		KeyData::Raw(_) => return Err(not_supported("RSA keys cannot be imported in the raw format")),
+		KeyData::Jwk(jwk) => {
+			jwk.check("RSA", jwk_algorithm(name, hash), extractable, usages)?;
+			let n = BigUint::from_bytes_be(&JsonWebKey::decode(&jwk.n, "n")?);
+			let e = BigUint::from_bytes_be(&JsonWebKey::decode(&jwk.e, "e")?);
+			if jwk.d.is_some() {
+				let d = BigUint::from_bytes_be(&JsonWebKey::decode(&jwk.d, "d")?);
+				let p = BigUint::from_bytes_be(&JsonWebKey::decode(&jwk.p, "p")?);
+				let q = BigUint::from_bytes_be(&JsonWebKey::decode(&jwk.q, "q")?);
+				let private = RsaPrivateKey::from_components(n, e, d, vec![p, q]).map_err(|_| data_error("Invalid RSA private key"))?;
+				private.validate().map_err(|_| data_error("Invalid RSA private key"))?;
+				KeyMaterial::RsaPrivate(private)
+			} else {
+				KeyMaterial::RsaPublic(RsaPublicKey::new(n, e).map_err(|_| data_error("Invalid RSA public key"))?)
+			}
+		}
+	};
+
+	let (public, permitted) = match &material {
+		KeyMaterial::RsaPublic(public) => (public.clone(), public_usages),
+		KeyMaterial::RsaPrivate(private) => (private.to_public_key(), private_usages),
+		_ => unreachable!(),
+	};
+	let usages = check_usages(usages, permitted)?;
+	Ok(CryptoKey::new(key_algorithm(name, &public, hash), material, extractable, usages))
+}
+
+pub(super) fn export_key(format: KeyFormat, key: &CryptoKey) -> Result<ExportedKey> {
+	match (format, &key.material) {
+		(KeyFormat::Spki, KeyMaterial::RsaPublic(public)) => {
+			let der = public.to_public_key_der().map_err(|error| operation_error(&error.to_string()))?;
+			Ok(ExportedKey::Bytes(der.as_bytes().to_vec()))
+		}
+		(KeyFormat::Pkcs8, KeyMaterial::RsaPrivate(private)) => {
+			let der = private.to_pkcs8_der().map_err(|error| operation_error(&error.to_string()))?;
+			Ok(ExportedKey::Bytes(der.as_bytes().to_vec()))
+		}
+		(KeyFormat::Jwk, material) => {
+			let mut jwk = JsonWebKey::new("RSA");
+			jwk.alg = jwk_algorithm(key.algorithm.name(), key_hash(key)).map(String::from);
+			jwk.key_ops = Some(key.usages.iter().map(KeyUsage::to_string).collect());
+			jwk.ext = Some(key.extractable);
+
+			match material {
+				KeyMaterial::RsaPublic(public) => {
+					jwk.n = JsonWebKey::encode(&public.n().to_bytes_be());
+					jwk.e = JsonWebKey::encode(&public.e().to_bytes_be());
+				}
+				KeyMaterial::RsaPrivate(private) => {
+					let [p, q] = private.primes() else {
+						return Err(operation_error("RSA keys with more than two primes cannot be exported"));
+					};
+					let one = BigUint::from(1u8);
+					jwk.n = JsonWebKey::encode(&private.n().to_bytes_be());
+					jwk.e = JsonWebKey::encode(&private.e().to_bytes_be());
+					jwk.d = JsonWebKey::encode(&private.d().to_bytes_be());
+					jwk.p = JsonWebKey::encode(&p.to_bytes_be());
+					jwk.q = JsonWebKey::encode(&q.to_bytes_be());
+					jwk.dp = JsonWebKey::encode(&(private.d() % (p - &one)).to_bytes_be());
+					jwk.dq = JsonWebKey::encode(&(private.d() % (q - &one)).to_bytes_be());
+					// As p is prime, the inverse of q modulo p is q^(p - 2) mod p.
+					jwk.qi = JsonWebKey::encode(&q.modpow(&(p - BigUint::from(2u8)), p).to_bytes_be());
+				}
+				_ => unreachable!(),
+			}
+			Ok(ExportedKey::Jwk(jwk))
+		}
+		_ => Err(not_supported(&format!("{} keys cannot be exported in this format", key.algorithm.name()))),
+	}
+}
+
+pub(super) fn crypt(cx: &Context, algorithm: &Value, key: &CryptoKey, data: &[u8], encrypt: bool) -> Result<Vec<u8>> {
+	let params = RsaOaepParams::from_value(cx, algorithm, false, ())?;
+	let label = params
+		.label
+		.map(|label| String::from_utf8(label.0))
+		.transpose()
+		.map_err(|_| not_supported("RSA-OAEP labels must be valid UTF-8"))?;
+
+	let padding = with_hash!(key_hash(key), D => match label {
+		Some(label) => Oaep::new_with_label::<D, _>(label),
+		None => Oaep::new::<D>(),
+	});
+	match (&key.material, encrypt) {
+		(KeyMaterial::RsaPublic(public), true) => public
+			.encrypt(&mut OsRng, padding, data)
+			.map_err(|error| operation_error(&error.to_string())),
+		(KeyMaterial::RsaPrivate(private), false) => private.decrypt(padding, data).map_err(|error| operation_error(&error.to_string())),
+		_ => Err(invalid_access("Key is of the wrong type")),
+	}
+}
+
+pub(super) fn sign(cx: &Context, algorithm: &Value, key: &CryptoKey, data: &[u8]) -> Result<Vec<u8>> {
+	let salt_length = RsaPssParams::from_value(cx, algorithm, false, ())?.salt_length as usize;
+	let KeyMaterial::RsaPrivate(private) = &key.material else {
+		return Err(invalid_access("Expected private key"));
+	};
+
+	let hash = key_hash(key);
+	let hashed = hash.digest(data);
+	let padding = with_hash!(hash, D => Pss::new_with_salt::<D>(salt_length));
+	private
+		.sign_with_rng(&mut OsRng, padding, &hashed)
+		.map_err(|error| operation_error(&error.to_string()))
+}
+
+pub(super) fn verify(cx: &Context, algorithm: &Value, key: &CryptoKey, signature: &[u8], data: &[u8]) -> Result<bool> {
+	let salt_length = RsaPssParams::from_value(cx, algorithm, false, ())?.salt_length as usize;
+	let KeyMaterial::RsaPublic(public) = &key.material else {
+		return Err(invalid_access("Expected public key"));
+	};
+
+	let hash = key_hash(key);
+	let hashed = hash.digest(data);
+	let padding = with_hash!(hash, D => Pss::new_with_salt::<D>(salt_length));
+	Ok(public.verify(padding, &hashed, signature).is_ok())
+}
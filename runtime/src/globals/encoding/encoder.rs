@@ -12,8 +12,8 @@ use ion::conversions::ToValue;
 use ion::typedarray::Uint8Array;
 
 pub struct EncodeResult {
-	read: u64,
-	written: u64,
+	read: u32,
+	written: u32,
 }
 
 impl<'cx> ToValue<'cx> for EncodeResult {
@@ -54,8 +54,8 @@ impl TextEncoder {
 		let mut destination = destination;
 		let (_, read, written, _) = self.encoder.encode_from_utf8(&input, unsafe { destination.as_mut_slice() }, true);
 		EncodeResult {
-			read: read as u64,
-			written: written as u64,
+			read: read as u32,
+			written: written as u32,
 		}
 	}
 
@@ -0,0 +1,44 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use mozjs::jsapi::Heap;
+use mozjs::jsval::JSVal;
+
+use ion::{Context, Value};
+
+use crate::globals::event::{Event, EventInit};
+
+#[derive(Default, FromValue)]
+pub struct CustomEventInit {
+	#[ion(inherit)]
+	event: EventInit,
+	#[ion(default)]
+	detail: Option<JSVal>,
+}
+
+#[js_class]
+pub struct CustomEvent {
+	event: Event,
+	detail: Box<Heap<JSVal>>,
+}
+
+#[js_class]
+impl CustomEvent {
+	#[ion(constructor)]
+	pub fn constructor(cx: &Context, kind: String, init: Option<CustomEventInit>) -> CustomEvent {
+		let init = init.unwrap_or_default();
+		let detail = init.detail.unwrap_or_else(|| Value::null(cx).get());
+		CustomEvent {
+			event: Event::new(&kind, init.event),
+			detail: Heap::boxed(detail),
+		}
+	}
+
+	#[ion(get)]
+	pub fn get_detail(&self) -> JSVal {
+		self.detail.get()
+	}
+}
@@ -0,0 +1,269 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::ptr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use mozjs::jsapi::{Heap, JSObject};
+
+use ion::{ClassDefinition, Context, Object};
+use ion::class::Reflector;
+pub use custom::CustomEvent;
+pub use rejection::PromiseRejectionEvent;
+pub use target::{define_global_target, EventTarget};
+
+mod custom;
+mod rejection;
+mod target;
+
+#[derive(Default, FromValue)]
+pub struct EventInit {
+	#[ion(default)]
+	bubbles: bool,
+	#[ion(default)]
+	cancelable: bool,
+	#[ion(default)]
+	composed: bool,
+}
+
+/// Represents the phase of an [Event] during dispatch.
+/// Events are only dispatched to their target, so the capturing and bubbling phases are never entered.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum EventPhase {
+	#[default]
+	None = 0,
+	AtTarget = 2,
+}
+
+#[js_class]
+pub struct Event {
+	reflector: Reflector,
+	#[ion(no_trace)]
+	kind: String,
+	#[ion(no_trace)]
+	bubbles: bool,
+	#[ion(no_trace)]
+	cancelable: bool,
+	#[ion(no_trace)]
+	composed: bool,
+	#[ion(no_trace)]
+	pub(crate) trusted: bool,
+	#[ion(no_trace)]
+	time_stamp: f64,
+
+	target: Box<Heap<*mut JSObject>>,
+	current_target: Box<Heap<*mut JSObject>>,
+	#[ion(no_trace)]
+	pub(crate) phase: EventPhase,
+
+	#[ion(no_trace)]
+	pub(crate) stop_propagation: bool,
+	#[ion(no_trace)]
+	pub(crate) stop_immediate_propagation: bool,
+	#[ion(no_trace)]
+	canceled: bool,
+	#[ion(no_trace)]
+	pub(crate) in_passive_listener: bool,
+	#[ion(no_trace)]
+	pub(crate) dispatching: bool,
+}
+
+impl Event {
+	/// Creates an [Event] which has not been dispatched.
+	pub fn new(kind: &str, init: EventInit) -> Event {
+		let time_stamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs_f64() * 1000.0;
+		Event {
+			reflector: Reflector::default(),
+			kind: String::from(kind),
+			bubbles: init.bubbles,
+			cancelable: init.cancelable,
+			composed: init.composed,
+			trusted: false,
+			time_stamp,
+			target: Heap::boxed(ptr::null_mut()),
+			current_target: Heap::boxed(ptr::null_mut()),
+			phase: EventPhase::None,
+			stop_propagation: false,
+			stop_immediate_propagation: false,
+			canceled: false,
+			in_passive_listener: false,
+			dispatching: false,
+		}
+	}
+
+	/// Creates a trusted event object, which is dispatched by the runtime rather than by scripts.
+	pub fn new_trusted<'cx>(cx: &'cx Context, kind: &str) -> Object<'cx> {
+		let mut event = Event::new(kind, EventInit::default());
+		event.trusted = true;
+		cx.root_object(Event::new_object(cx, Box::new(event))).into()
+	}
+
+	pub fn kind(&self) -> &str {
+		&self.kind
+	}
+
+	pub fn canceled(&self) -> bool {
+		self.canceled
+	}
+
+	pub(crate) fn set_target(&mut self, target: *mut JSObject) {
+		self.target.set(target);
+		self.current_target.set(target);
+		self.phase = EventPhase::AtTarget;
+		self.dispatching = true;
+	}
+
+	/// Resets the state of the event after it has been dispatched, so that it can be dispatched again.
+	pub(crate) fn finish_dispatch(&mut self) {
+		self.current_target.set(ptr::null_mut());
+		self.phase = EventPhase::None;
+		self.stop_propagation = false;
+		self.stop_immediate_propagation = false;
+		self.dispatching = false;
+	}
+}
+
+#[js_class]
+impl Event {
+	#[ion(constructor)]
+	pub fn constructor(kind: String, init: Option<EventInit>) -> Event {
+		Event::new(&kind, init.unwrap_or_default())
+	}
+
+	#[ion(get)]
+	pub fn get_type(&self) -> String {
+		self.kind.clone()
+	}
+
+	#[ion(get)]
+	pub fn get_target(&self) -> *mut JSObject {
+		self.target.get()
+	}
+
+	#[ion(get)]
+	pub fn get_current_target(&self) -> *mut JSObject {
+		self.current_target.get()
+	}
+
+	#[ion(get)]
+	pub fn get_src_element(&self) -> *mut JSObject {
+		self.target.get()
+	}
+
+	#[ion(name = "composedPath")]
+	pub fn composed_path(&self) -> Vec<*mut JSObject> {
+		let target = self.current_target.get();
+		if target.is_null() {
+			Vec::new()
+		} else {
+			vec![target]
+		}
+	}
+
+	#[ion(get)]
+	pub fn get_event_phase(&self) -> u16 {
+		self.phase as u16
+	}
+
+	#[ion(name = "stopPropagation")]
+	pub fn stop_propagation(&mut self) {
+		self.stop_propagation = true;
+	}
+
+	#[ion(get)]
+	pub fn get_cancel_bubble(&self) -> bool {
+		self.stop_propagation
+	}
+
+	#[ion(set)]
+	pub fn set_cancel_bubble(&mut self, cancel: bool) {
+		self.stop_propagation |= cancel;
+	}
+
+	#[ion(name = "stopImmediatePropagation")]
+	pub fn stop_immediate_propagation(&mut self) {
+		self.stop_propagation = true;
+		self.stop_immediate_propagation = true;
+	}
+
+	#[ion(get)]
+	pub fn get_bubbles(&self) -> bool {
+		self.bubbles
+	}
+
+	#[ion(get)]
+	pub fn get_cancelable(&self) -> bool {
+		self.cancelable
+	}
+
+	#[ion(get)]
+	pub fn get_return_value(&self) -> bool {
+		!self.canceled
+	}
+
+	#[ion(set)]
+	pub fn set_return_value(&mut self, value: bool) {
+		if !value {
+			self.prevent_default();
+		}
+	}
+
+	/// Cancels the event if it is cancelable.
+	/// Calls from listeners added with `passive` are ignored.
+	#[ion(name = "preventDefault")]
+	pub fn prevent_default(&mut self) {
+		if self.cancelable && !self.in_passive_listener {
+			self.canceled = true;
+		}
+	}
+
+	#[ion(get)]
+	pub fn get_default_prevented(&self) -> bool {
+		self.canceled
+	}
+
+	#[ion(get)]
+	pub fn get_composed(&self) -> bool {
+		self.composed
+	}
+
+	#[ion(get)]
+	pub fn get_is_trusted(&self) -> bool {
+		self.trusted
+	}
+
+	#[ion(get)]
+	pub fn get_time_stamp(&self) -> f64 {
+		self.time_stamp
+	}
+
+	#[ion(get, name = "NONE")]
+	pub fn none() -> u16 {
+		0
+	}
+
+	#[ion(get, name = "CAPTURING_PHASE")]
+	pub fn capturing_phase() -> u16 {
+		1
+	}
+
+	#[ion(get, name = "AT_TARGET")]
+	pub fn at_target() -> u16 {
+		2
+	}
+
+	#[ion(get, name = "BUBBLING_PHASE")]
+	pub fn bubbling_phase() -> u16 {
+		3
+	}
+}
+
+pub fn define(cx: &Context, global: &mut Object) -> bool {
+	Event::init_class(cx, global).0
+		&& CustomEvent::init_class(cx, global).0
+		&& PromiseRejectionEvent::init_class(cx, global).0
+		&& EventTarget::init_class(cx, global).0
+}
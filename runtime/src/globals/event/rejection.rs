@@ -0,0 +1,73 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use mozjs::jsapi::{Heap, JSObject};
+use mozjs::jsval::JSVal;
+
+use ion::{ClassDefinition, Context, Object, Promise, Value};
+
+use crate::globals::event::{Event, EventInit};
+
+#[derive(FromValue)]
+pub struct PromiseRejectionEventInit {
+	#[ion(inherit)]
+	event: EventInit,
+	promise: *mut JSObject,
+	#[ion(default)]
+	reason: Option<JSVal>,
+}
+
+/// Event fired at the global object when a promise is rejected without a handler (`unhandledrejection`),
+/// or when a handler is attached to such a promise afterwards (`rejectionhandled`).
+#[js_class]
+pub struct PromiseRejectionEvent {
+	event: Event,
+	promise: Box<Heap<*mut JSObject>>,
+	reason: Box<Heap<JSVal>>,
+}
+
+impl PromiseRejectionEvent {
+	/// Creates a trusted event for a rejected promise.
+	/// `unhandledrejection` events are cancelable, and cancelling them stops the rejection from being reported.
+	pub fn new_trusted<'cx>(cx: &'cx Context, kind: &str, promise: &Promise, reason: &Value) -> Object<'cx> {
+		let init = EventInit {
+			cancelable: kind == "unhandledrejection",
+			..EventInit::default()
+		};
+		let mut event = Event::new(kind, init);
+		event.trusted = true;
+
+		let event = PromiseRejectionEvent {
+			event,
+			promise: Heap::boxed(promise.handle().get()),
+			reason: Heap::boxed(reason.get()),
+		};
+		cx.root_object(PromiseRejectionEvent::new_object(cx, Box::new(event))).into()
+	}
+}
+
+#[js_class]
+impl PromiseRejectionEvent {
+	#[ion(constructor)]
+	pub fn constructor(cx: &Context, kind: String, init: PromiseRejectionEventInit) -> PromiseRejectionEvent {
+		let reason = init.reason.unwrap_or_else(|| Value::undefined(cx).get());
+		PromiseRejectionEvent {
+			event: Event::new(&kind, init.event),
+			promise: Heap::boxed(init.promise),
+			reason: Heap::boxed(reason),
+		}
+	}
+
+	#[ion(get)]
+	pub fn get_promise(&self) -> *mut JSObject {
+		self.promise.get()
+	}
+
+	#[ion(get)]
+	pub fn get_reason(&self) -> JSVal {
+		self.reason.get()
+	}
+}
@@ -0,0 +1,281 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use mozjs::jsapi::{Heap, JSFunctionSpec, JSObject};
+
+use ion::{ClassDefinition, Context, Error, ErrorKind, ErrorReport, Function, Object, PersistentRooted, Result, Value};
+use ion::class::Reflector;
+use ion::conversions::FromValue;
+
+use crate::ContextExt;
+use crate::globals::event::Event;
+
+#[derive(Traceable)]
+struct Listener {
+	#[ion(no_trace)]
+	kind: String,
+	callback: Box<Heap<*mut JSObject>>,
+	#[ion(no_trace)]
+	capture: bool,
+	#[ion(no_trace)]
+	once: bool,
+	#[ion(no_trace)]
+	passive: bool,
+}
+
+impl Listener {
+	fn matches(&self, kind: &str, callback: *mut JSObject, capture: bool) -> bool {
+		self.kind == kind && self.callback.get() == callback && self.capture == capture
+	}
+}
+
+#[derive(Default)]
+struct ListenerOptions {
+	capture: bool,
+	once: bool,
+	passive: bool,
+}
+
+impl ListenerOptions {
+	/// Reads the options of `addEventListener` and `removeEventListener`, which are either a boolean for `capture` or an object.
+	fn from_value(cx: &Context, options: Option<Value>) -> Result<ListenerOptions> {
+		let Some(options) = options else {
+			return Ok(ListenerOptions::default());
+		};
+		if !options.handle().is_object() {
+			return Ok(ListenerOptions {
+				capture: bool::from_value(cx, &options, false, ())?,
+				..ListenerOptions::default()
+			});
+		}
+
+		let options = options.to_object(cx);
+		let flag = |name: &str| options.get_as::<_, bool>(cx, name, false, ()).unwrap_or_default();
+		Ok(ListenerOptions {
+			capture: flag("capture"),
+			once: flag("once"),
+			passive: flag("passive"),
+		})
+	}
+}
+
+/// Objects which receive events, and dispatch them to their listeners.
+///
+/// Native classes can inherit from [EventTarget] by using it as their first field, and dispatch events with [EventTarget::dispatch].
+#[js_class]
+#[derive(Default)]
+pub struct EventTarget {
+	reflector: Reflector,
+	listeners: Vec<Listener>,
+}
+
+impl EventTarget {
+	/// Checks if the target has listeners for events of the given type.
+	pub fn has_listeners(&self, kind: &str) -> bool {
+		self.listeners.iter().any(|listener| listener.kind == kind)
+	}
+
+	/// Dispatches an event to the listeners of a target, returning `false` if it was cancelled.
+	///
+	/// Events are only dispatched at the target, as there is no tree of targets to capture or bubble through.
+	/// Exceptions thrown by listeners are reported, and do not prevent the remaining listeners from being called.
+	pub fn dispatch(cx: &Context, target: &Object, event: &mut Object) -> Result<bool> {
+		EventTarget::dispatch_inner(cx, target, event, false)
+	}
+
+	/// Fires an event created by the runtime at a target, calling its `on{type}` event handler before its listeners.
+	/// Targets which are not an [EventTarget], such as the global object of a worker, only have their event handler called.
+	pub fn fire(cx: &Context, target: &Object, event: &mut Object) -> Result<bool> {
+		EventTarget::dispatch_inner(cx, target, event, true)
+	}
+
+	/// Checks if an object has an `on{type}` event handler or listeners for events of the given type.
+	pub fn is_listening(cx: &Context, target: &Object, kind: &str) -> bool {
+		event_handler(cx, target, kind).is_some()
+			|| listener_target(cx, target).is_some_and(|listeners| EventTarget::get_private(&listeners).has_listeners(kind))
+	}
+
+	fn dispatch_inner(cx: &Context, target: &Object, event: &mut Object, handler: bool) -> Result<bool> {
+		if !Event::instance_of_derived(cx, event) {
+			return Err(Error::new("Expected Event", ErrorKind::Type));
+		}
+		if Event::get_private(event).dispatching {
+			return Err(Error::new("Event is already being dispatched.", None).with_name("InvalidStateError"));
+		}
+		Event::get_mut_private(event).set_target(target.handle().get());
+
+		let handler = handler.then(|| event_handler(cx, target, Event::get_private(event).kind())).flatten();
+		if let Some(handler) = handler {
+			if let Err(Some(report)) = handler.call(cx, target, &[event.as_value(cx)]) {
+				eprintln!("{}", report.format(cx));
+			}
+		}
+
+		let listener_target = listener_target(cx, target).filter(|_| !Event::get_private(event).stop_immediate_propagation);
+		if let Some(listener_target) = listener_target {
+			let kind = String::from(Event::get_private(event).kind());
+			// Listeners added during dispatch are not called, and listeners removed during dispatch are skipped.
+			let listeners: Vec<_> = EventTarget::get_private(&listener_target)
+				.listeners
+				.iter()
+				.filter(|listener| listener.kind == kind)
+				.map(|listener| (listener.callback.get(), listener.capture))
+				.collect();
+
+			for (callback, capture) in listeners {
+				let mut listener_target = Object::from(cx.root_object(listener_target.handle().get()));
+				let listeners = &mut EventTarget::get_mut_private(&mut listener_target).listeners;
+				let Some(index) = listeners.iter().position(|listener| listener.matches(&kind, callback, capture)) else {
+					continue;
+				};
+				let passive = listeners[index].passive;
+				if listeners[index].once {
+					listeners.remove(index);
+				}
+
+				Event::get_mut_private(event).in_passive_listener = passive;
+				if let Err(Some(report)) = invoke(cx, callback, target, event) {
+					eprintln!("{}", report.format(cx));
+				}
+
+				let native = Event::get_mut_private(event);
+				native.in_passive_listener = false;
+				if native.stop_immediate_propagation {
+					break;
+				}
+			}
+		}
+
+		let native = Event::get_mut_private(event);
+		native.finish_dispatch();
+		Ok(!native.canceled())
+	}
+}
+
+/// Returns the object which holds the listeners of a target.
+/// The global object is not an [EventTarget], so its listeners are held by a separate [EventTarget] instead.
+fn listener_target<'cx>(cx: &'cx Context, target: &Object) -> Option<Object<'cx>> {
+	if EventTarget::instance_of_derived(cx, target) {
+		return Some(Object::from(cx.root_object(target.handle().get())));
+	}
+	if target.handle().get() != Object::global(cx).handle().get() {
+		return None;
+	}
+	let global_target = unsafe { &(*cx.get_private().as_ptr()).global_target };
+	global_target
+		.as_ref()
+		.map(|global_target| Object::from(cx.root_object(global_target.get())))
+}
+
+fn global_listener_target(cx: &Context) -> Result<Object> {
+	listener_target(cx, &Object::global(cx)).ok_or_else(|| Error::new("Global EventTarget has not been initialised.", None))
+}
+
+fn event_handler<'cx>(cx: &'cx Context, target: &Object, kind: &str) -> Option<Function<'cx>> {
+	let handler = target.get(cx, format!("on{}", kind).as_str())?;
+	handler
+		.handle()
+		.is_object()
+		.then(|| Function::from_object(cx, &handler.to_object(cx).into_local()))
+		.flatten()
+}
+
+/// Calls a listener, which is either a function or an object with a `handleEvent` method.
+fn invoke(cx: &Context, callback: *mut JSObject, target: &Object, event: &Object) -> std::result::Result<(), Option<ErrorReport>> {
+	let args = [event.as_value(cx)];
+	if let Some(function) = Function::from_object(cx, &cx.root_object(callback)) {
+		return function.call(cx, target, &args).map(|_| ());
+	}
+
+	let callback = Object::from(cx.root_object(callback));
+	let handle_event = callback.get(cx, "handleEvent").filter(|handle_event| handle_event.handle().is_object());
+	match handle_event.and_then(|handle_event| Function::from_object(cx, &handle_event.to_object(cx).into_local())) {
+		Some(function) => function.call(cx, &callback, &args).map(|_| ()),
+		None => Ok(()),
+	}
+}
+
+#[js_class]
+impl EventTarget {
+	#[ion(constructor)]
+	pub fn constructor() -> EventTarget {
+		EventTarget::default()
+	}
+
+	#[ion(name = "addEventListener")]
+	pub fn add_event_listener(&mut self, cx: &Context, kind: String, callback: Option<Object>, options: Option<Value>) -> Result<()> {
+		let options = ListenerOptions::from_value(cx, options)?;
+		let Some(callback) = callback else {
+			return Ok(());
+		};
+		let callback = callback.handle().get();
+		if self.listeners.iter().any(|listener| listener.matches(&kind, callback, options.capture)) {
+			return Ok(());
+		}
+
+		self.listeners.push(Listener {
+			kind,
+			callback: Heap::boxed(callback),
+			capture: options.capture,
+			once: options.once,
+			passive: options.passive,
+		});
+		Ok(())
+	}
+
+	#[ion(name = "removeEventListener")]
+	pub fn remove_event_listener(&mut self, cx: &Context, kind: String, callback: Option<Object>, options: Option<Value>) -> Result<()> {
+		let options = ListenerOptions::from_value(cx, options)?;
+		if let Some(callback) = callback {
+			let callback = callback.handle().get();
+			self.listeners.retain(|listener| !listener.matches(&kind, callback, options.capture));
+		}
+		Ok(())
+	}
+
+	#[ion(name = "dispatchEvent")]
+	pub fn dispatch_event(#[ion(this)] this: &Object, cx: &Context, event: Object) -> Result<bool> {
+		let mut event = event;
+		if Event::instance_of_derived(cx, &event) {
+			Event::get_mut_private(&mut event).trusted = false;
+		}
+		EventTarget::dispatch(cx, this, &mut event)
+	}
+}
+
+#[js_fn]
+fn addEventListener(cx: &Context, kind: String, callback: Option<Object>, options: Option<Value>) -> Result<()> {
+	let mut target = global_listener_target(cx)?;
+	EventTarget::get_mut_private(&mut target).add_event_listener(cx, kind, callback, options)
+}
+
+#[js_fn]
+fn removeEventListener(cx: &Context, kind: String, callback: Option<Object>, options: Option<Value>) -> Result<()> {
+	let mut target = global_listener_target(cx)?;
+	EventTarget::get_mut_private(&mut target).remove_event_listener(cx, kind, callback, options)
+}
+
+#[js_fn]
+fn dispatchEvent(cx: &Context, event: Object) -> Result<bool> {
+	EventTarget::dispatch_event(&Object::global(cx), cx, event)
+}
+
+const GLOBAL_FUNCTIONS: &[JSFunctionSpec] = &[
+	function_spec!(addEventListener, 2),
+	function_spec!(removeEventListener, 2),
+	function_spec!(dispatchEvent, 1),
+	JSFunctionSpec::ZERO,
+];
+
+/// Makes the global object behave as an [EventTarget], with listeners held by a separate [EventTarget].
+/// This must be called after the private data of the context has been set.
+pub fn define_global_target(cx: &Context, global: &mut Object) -> bool {
+	let target = EventTarget::new_object(cx, Box::new(EventTarget::default()));
+	unsafe {
+		(*cx.get_private().as_ptr()).global_target = Some(PersistentRooted::new(target));
+	}
+	unsafe { global.define_methods(cx, GLOBAL_FUNCTIONS) }
+}
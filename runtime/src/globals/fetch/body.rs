@@ -6,20 +6,46 @@
 
 use std::fmt;
 use std::fmt::{Display, Formatter};
+use std::io;
+use std::rc::Rc;
 
+use async_compression::tokio::bufread::{BrotliDecoder, GzipDecoder, ZlibDecoder};
 use bytes::Bytes;
+use futures::future::LocalBoxFuture;
+use futures::lock::Mutex;
+use futures::TryStreamExt;
+use http::header::CONTENT_ENCODING;
 use hyper::Body;
-use mozjs::jsapi::{ESClass, Heap};
+use hyper::body::HttpBody;
+use mozjs::jsapi::{ESClass, Heap, JSObject};
 use mozjs::jsval::JSVal;
+use tokio_util::io::{ReaderStream, StreamReader as ByteStreamReader};
 
-use ion::{Context, Error, ErrorKind, Result, Value};
+use ion::{Context, Error, ErrorKind, Local, Object, Result, ResultExc, Value};
 use ion::conversions::FromValue;
 
-#[derive(Debug, Clone, Traceable)]
+use crate::event_loop::handles::{ActiveHandle, HandleKind};
+use crate::globals::streams::{is_readable_stream, NativeSource, readable_stream, StreamReader, tee_stream};
+use crate::promise::future_to_promise_with_handle;
+
+#[derive(Debug, Traceable)]
 #[non_exhaustive]
 enum FetchBodyInner {
 	None,
 	Bytes(#[ion(no_trace)] Bytes),
+	Stream(Box<Heap<*mut JSObject>>),
+}
+
+/// Clones share stream bodies, as they take over the body from the original.
+/// Bodies which are read by both are teed with [FetchBody::tee] instead.
+impl Clone for FetchBodyInner {
+	fn clone(&self) -> FetchBodyInner {
+		match self {
+			FetchBodyInner::None => FetchBodyInner::None,
+			FetchBodyInner::Bytes(bytes) => FetchBodyInner::Bytes(bytes.clone()),
+			FetchBodyInner::Stream(stream) => FetchBodyInner::Stream(Heap::boxed(stream.get())),
+		}
+	}
 }
 
 #[derive(Copy, Clone, Debug, Traceable)]
@@ -44,6 +70,15 @@ pub struct FetchBody {
 }
 
 impl FetchBody {
+	/// Creates a body which is read from a [ReadableStream](crate::globals::streams).
+	pub(crate) fn from_stream(stream: *mut JSObject) -> FetchBody {
+		FetchBody {
+			body: FetchBodyInner::Stream(Heap::boxed(stream)),
+			source: None,
+			kind: None,
+		}
+	}
+
 	pub fn is_none(&self) -> bool {
 		matches!(&self.body, FetchBodyInner::None)
 	}
@@ -52,6 +87,7 @@ impl FetchBody {
 		match &self.body {
 			FetchBodyInner::None => true,
 			FetchBodyInner::Bytes(bytes) => bytes.is_empty(),
+			FetchBodyInner::Stream(_) => false,
 		}
 	}
 
@@ -59,6 +95,7 @@ impl FetchBody {
 		match &self.body {
 			FetchBodyInner::None => None,
 			FetchBodyInner::Bytes(bytes) => Some(bytes.len()),
+			FetchBodyInner::Stream(_) => None,
 		}
 	}
 
@@ -66,10 +103,95 @@ impl FetchBody {
 		matches!(&self.body, FetchBodyInner::None | FetchBodyInner::Bytes(_))
 	}
 
-	pub fn to_http_body(&self) -> Body {
+	/// Returns the [ReadableStream](crate::globals::streams) of the body, if it is a stream.
+	pub fn stream(&self) -> Option<*mut JSObject> {
+		match &self.body {
+			FetchBodyInner::Stream(stream) => Some(stream.get()),
+			_ => None,
+		}
+	}
+
+	/// Converts the body to a [ReadableStream](crate::globals::streams), if it is not one already.
+	/// Bodies which are [None](FetchBodyInner::None) have no stream.
+	pub(crate) fn to_stream(&mut self, cx: &Context) -> ResultExc<Option<*mut JSObject>> {
+		let bytes = match &self.body {
+			FetchBodyInner::None => return Ok(None),
+			FetchBodyInner::Bytes(bytes) => bytes.clone(),
+			FetchBodyInner::Stream(stream) => return Ok(Some(stream.get())),
+		};
+		let stream = readable_stream(cx, BytesSource(Some(bytes)))?;
+		self.body = FetchBodyInner::Stream(Heap::boxed(stream.handle().get()));
+		Ok(Some(stream.handle().get()))
+	}
+
+	/// Returns a copy of the body, which can be read independently of this body.
+	/// Stream bodies are teed, with this body reading from one branch and the copy reading from the other.
+	pub(crate) fn tee(&mut self, cx: &Context) -> ResultExc<FetchBody> {
+		let body = match &self.body {
+			FetchBodyInner::None => FetchBodyInner::None,
+			FetchBodyInner::Bytes(bytes) => FetchBodyInner::Bytes(bytes.clone()),
+			FetchBodyInner::Stream(stream) => {
+				let stream = Object::from(unsafe { Local::from_heap(stream) });
+				let (first, second) = tee_stream(cx, &stream)?;
+				self.body = FetchBodyInner::Stream(Heap::boxed(first.handle().get()));
+				FetchBodyInner::Stream(Heap::boxed(second.handle().get()))
+			}
+		};
+		Ok(FetchBody {
+			body,
+			source: self.source.as_ref().map(|source| Heap::boxed(source.get())),
+			kind: self.kind,
+		})
+	}
+
+	/// Reads the body to its end. Streams are locked while they are read.
+	pub(crate) async fn read_to_end(&self, cx: &Context) -> ResultExc<Vec<u8>> {
+		match &self.body {
+			FetchBodyInner::None => Ok(Vec::new()),
+			FetchBodyInner::Bytes(bytes) => Ok(bytes.to_vec()),
+			FetchBodyInner::Stream(stream) => {
+				let stream = Object::from(unsafe { Local::from_heap(stream) });
+				let reader = StreamReader::new(cx, &stream).map_err(|_| Error::new("Body stream is locked", ErrorKind::Type))?;
+				reader.read_to_end(cx).await
+			}
+		}
+	}
+
+	/// Converts the body to a [Body], which can be sent by the client.
+	/// Streams are locked, and their chunks are sent as they are read by a future in the event loop.
+	pub fn to_http_body(&self, cx: &Context) -> Result<Body> {
 		match &self.body {
-			FetchBodyInner::None => Body::empty(),
-			FetchBodyInner::Bytes(bytes) => Body::from(bytes.clone()),
+			FetchBodyInner::None => Ok(Body::empty()),
+			FetchBodyInner::Bytes(bytes) => Ok(Body::from(bytes.clone())),
+			FetchBodyInner::Stream(stream) => {
+				let stream = Object::from(unsafe { Local::from_heap(stream) });
+				let reader = StreamReader::new(cx, &stream).map_err(|_| Error::new("Body stream is locked", ErrorKind::Type))?;
+				let (mut sender, body) = Body::channel();
+
+				let cx_ptr = cx.as_ptr();
+				let handle = ActiveHandle::new(cx, HandleKind::Promise, "Body Stream");
+				let promise = future_to_promise_with_handle::<_, _, Error>(cx, handle, async move {
+					let cx = unsafe { Context::new_unchecked(cx_ptr) };
+					loop {
+						match reader.read(&cx).await {
+							Ok(Some(chunk)) => {
+								if sender.send_data(Bytes::from(chunk)).await.is_err() {
+									break;
+								}
+							}
+							Ok(None) => break,
+							Err(_) => {
+								sender.abort();
+								break;
+							}
+						}
+					}
+					Ok(())
+				});
+				promise
+					.map(|_| body)
+					.ok_or_else(|| Error::new("Future Queue has not been initialised.", None))
+			}
 		}
 	}
 }
@@ -94,6 +216,65 @@ impl Default for FetchBody {
 	}
 }
 
+/// Decodes the body of a response with the `gzip`, `deflate` or `br` content codings as it is read.
+/// Bodies with other or multiple codings are left as they are.
+pub(crate) fn decode_response(response: hyper::Response<Body>) -> hyper::Response<Body> {
+	let encoding = response.headers().get(CONTENT_ENCODING).and_then(|encoding| encoding.to_str().ok());
+	let encoding = encoding.map(|encoding| encoding.trim().to_ascii_lowercase()).unwrap_or_default();
+	if !matches!(encoding.as_str(), "gzip" | "x-gzip" | "deflate" | "br") {
+		return response;
+	}
+
+	response.map(|body| {
+		let reader = ByteStreamReader::new(TryStreamExt::map_err(body, |error| io::Error::new(io::ErrorKind::Other, error)));
+		match encoding.as_str() {
+			"deflate" => Body::wrap_stream(ReaderStream::new(ZlibDecoder::new(reader))),
+			"br" => Body::wrap_stream(ReaderStream::new(BrotliDecoder::new(reader))),
+			_ => Body::wrap_stream(ReaderStream::new(GzipDecoder::new(reader))),
+		}
+	})
+}
+
+/// Pulls the chunks of a [Body] into a [ReadableStream](crate::globals::streams).
+pub(crate) struct HyperBodySource {
+	body: Rc<Mutex<Body>>,
+}
+
+impl HyperBodySource {
+	pub(crate) fn new(body: Body) -> HyperBodySource {
+		HyperBodySource { body: Rc::new(Mutex::new(body)) }
+	}
+}
+
+impl NativeSource for HyperBodySource {
+	fn pull(&mut self) -> LocalBoxFuture<'static, Result<Option<Vec<u8>>>> {
+		let body = Rc::clone(&self.body);
+		Box::pin(async move {
+			match body.lock().await.data().await {
+				Some(Ok(chunk)) => Ok(Some(chunk.to_vec())),
+				Some(Err(error)) => Err(Error::new(&error.to_string(), ErrorKind::Type)),
+				None => Ok(None),
+			}
+		})
+	}
+
+	fn cancel(&mut self) {
+		if let Some(mut body) = self.body.try_lock() {
+			*body = Body::empty();
+		}
+	}
+}
+
+/// Yields the bytes of a body as a single chunk.
+struct BytesSource(Option<Bytes>);
+
+impl NativeSource for BytesSource {
+	fn pull(&mut self) -> LocalBoxFuture<'static, Result<Option<Vec<u8>>>> {
+		let bytes = self.0.take().filter(|bytes| !bytes.is_empty()).map(|bytes| bytes.to_vec());
+		Box::pin(async move { Ok(bytes) })
+	}
+}
+
 macro_rules! typedarray_to_bytes {
 	($body:expr) => {
 		Err(Error::new("Expected TypedArray or ArrayBuffer", ErrorKind::Type))
@@ -135,6 +316,13 @@ impl<'cx> FromValue<'cx> for FetchBody {
 			})
 		} else if value.handle().is_object() {
 			let object = value.to_object(cx);
+			if is_readable_stream(cx, value) {
+				return Ok(FetchBody {
+					body: FetchBodyInner::Stream(Heap::boxed(object.handle().get())),
+					source: Some(Heap::boxed(value.get())),
+					kind: None,
+				});
+			}
 
 			let class = object.get_builtin_class(cx);
 			if class == ESClass::String {
@@ -0,0 +1,376 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::cell::Cell;
+use std::mem::take;
+use std::rc::Rc;
+use std::time::Duration;
+
+use futures::future::{Either, select};
+use http::{HeaderValue, StatusCode};
+use http::header::{ACCEPT, CACHE_CONTROL, CONTENT_TYPE};
+use hyper::Body;
+use hyper::body::HttpBody;
+use mime::{Mime, TEXT_EVENT_STREAM};
+use mozjs::rust::IntoHandle;
+use tokio::sync::Notify;
+use tokio::time::sleep;
+use url::Url;
+
+use ion::{ClassDefinition, Context, Error, Local, Object, Result, Value};
+use ion::flags::PropertyFlags;
+
+use crate::event_loop::handles::{ActiveHandle, HandleKind};
+use crate::globals::event::{Event, EventTarget};
+use crate::globals::fetch::{fetch_internal, GLOBAL_CLIENT, Headers, Request, RequestInfo, Response};
+use crate::globals::fetch::request::{RequestCache, RequestCredentials, RequestMode};
+use crate::globals::url::parse_url;
+use crate::promise::future_to_promise_with_handle;
+
+/// Time to wait before reconnecting, until the server sends a `retry` field.
+const DEFAULT_RECONNECTION_TIME: u64 = 3000;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum ReadyState {
+	#[default]
+	Connecting = 0,
+	Open = 1,
+	Closed = 2,
+}
+
+#[derive(Default, FromValue)]
+pub struct EventSourceInit {
+	#[ion(default)]
+	with_credentials: bool,
+}
+
+/// Represents an event parsed from an event stream, before it is dispatched.
+#[derive(Debug)]
+struct ServerEvent {
+	kind: String,
+	data: String,
+	last_event_id: String,
+}
+
+/// Parses the [event stream format](https://html.spec.whatwg.org/multipage/server-sent-events.html#parsing-an-event-stream),
+/// which may be split across chunks at any byte.
+#[derive(Debug, Default)]
+struct EventStreamParser {
+	buffer: Vec<u8>,
+	started: bool,
+	trailing_cr: bool,
+
+	kind: String,
+	data: String,
+	last_event_id: String,
+	retry: Option<u64>,
+}
+
+impl EventStreamParser {
+	/// Creates a parser for a new connection, which keeps the last event ID and reconnection time of the previous connections.
+	fn resume(last_event_id: &str, retry: Option<u64>) -> EventStreamParser {
+		EventStreamParser {
+			last_event_id: String::from(last_event_id),
+			retry,
+			..EventStreamParser::default()
+		}
+	}
+
+	fn feed(&mut self, mut chunk: &[u8]) -> Vec<ServerEvent> {
+		if self.trailing_cr && chunk.first() == Some(&b'\n') {
+			chunk = &chunk[1..];
+		}
+		self.trailing_cr = false;
+
+		let mut events = Vec::new();
+		for (index, &byte) in chunk.iter().enumerate() {
+			match byte {
+				b'\r' | b'\n' => {
+					if byte == b'\r' && chunk.get(index + 1) == Some(&b'\n') {
+						continue;
+					}
+					self.trailing_cr = byte == b'\r' && index == chunk.len() - 1;
+					let line = take(&mut self.buffer);
+					events.extend(self.process_line(&line));
+				}
+				_ => self.buffer.push(byte),
+			}
+		}
+		events
+	}
+
+	fn process_line(&mut self, line: &[u8]) -> Option<ServerEvent> {
+		let mut line = String::from_utf8_lossy(line).into_owned();
+		if !self.started {
+			self.started = true;
+			if line.starts_with('\u{FEFF}') {
+				line.remove(0);
+			}
+		}
+
+		if line.is_empty() {
+			return self.dispatch();
+		}
+		if line.starts_with(':') {
+			return None;
+		}
+
+		let (field, value) = match line.split_once(':') {
+			Some((field, value)) => (field, value.strip_prefix(' ').unwrap_or(value)),
+			None => (line.as_str(), ""),
+		};
+		match field {
+			"event" => self.kind = String::from(value),
+			"data" => {
+				self.data.push_str(value);
+				self.data.push('\n');
+			}
+			"id" if !value.contains('\0') => self.last_event_id = String::from(value),
+			"retry" if !value.is_empty() && value.bytes().all(|byte| byte.is_ascii_digit()) => self.retry = value.parse().ok(),
+			_ => {}
+		}
+		None
+	}
+
+	fn dispatch(&mut self) -> Option<ServerEvent> {
+		let kind = take(&mut self.kind);
+		if self.data.is_empty() {
+			return None;
+		}
+
+		let mut data = take(&mut self.data);
+		data.pop();
+		Some(ServerEvent {
+			kind: if kind.is_empty() { String::from("message") } else { kind },
+			data,
+			last_event_id: self.last_event_id.clone(),
+		})
+	}
+}
+
+/// Describes how a connection to the server ended.
+enum Disconnect {
+	/// The connection should be re-established after the reconnection time.
+	Reconnect,
+	/// The connection failed, and should not be re-established.
+	Fail,
+	/// The [EventSource] was closed by the script.
+	Closed,
+}
+
+#[js_class]
+pub struct EventSource {
+	event_target: EventTarget,
+	#[ion(no_trace)]
+	url: Url,
+	#[ion(no_trace)]
+	with_credentials: bool,
+	#[ion(no_trace)]
+	state: Rc<Cell<ReadyState>>,
+	#[ion(no_trace)]
+	close: Rc<Notify>,
+}
+
+#[js_class]
+impl EventSource {
+	#[ion(constructor)]
+	pub fn constructor(#[ion(this)] this: &Object, cx: &Context, url: String, init: Option<EventSourceInit>) -> Result<EventSource> {
+		let url = parse_url(&url, None).map_err(|_| Error::new("Invalid URL", None).with_name("SyntaxError"))?;
+		let with_credentials = init.unwrap_or_default().with_credentials;
+		let state = Rc::new(Cell::new(ReadyState::Connecting));
+		let close = Rc::new(Notify::new());
+
+		let this = cx.root_persistent_object(this.handle().get());
+		let cx2 = unsafe { Context::new_unchecked(cx.as_ptr()) };
+		let this = this.handle().into_handle();
+		let connection = {
+			let url = url.clone();
+			let state = Rc::clone(&state);
+			let close = Rc::clone(&close);
+			async move {
+				let target = Object::from(unsafe { Local::from_raw_handle(this) });
+				run(&cx2, &target, url, with_credentials, &state, &close).await;
+				cx2.unroot_persistent_object(this.get());
+				Ok::<_, Error>(())
+			}
+		};
+		let handle = ActiveHandle::new(cx, HandleKind::Fetch, format!("EventSource {}", url));
+		future_to_promise_with_handle(cx, handle, connection).ok_or_else(|| Error::new("EventSource requires a future queue", None))?;
+
+		Ok(EventSource {
+			event_target: EventTarget::default(),
+			url,
+			with_credentials,
+			state,
+			close,
+		})
+	}
+
+	#[ion(get)]
+	pub fn get_url(&self) -> String {
+		String::from(self.url.as_str())
+	}
+
+	#[ion(get)]
+	pub fn get_with_credentials(&self) -> bool {
+		self.with_credentials
+	}
+
+	#[ion(get)]
+	pub fn get_ready_state(&self) -> u16 {
+		self.state.get() as u16
+	}
+
+	/// Closes the connection to the server, which is not re-established.
+	pub fn close(&self) {
+		self.state.set(ReadyState::Closed);
+		self.close.notify_one();
+	}
+
+	#[ion(get, name = "CONNECTING")]
+	pub fn connecting() -> u16 {
+		ReadyState::Connecting as u16
+	}
+
+	#[ion(get, name = "OPEN")]
+	pub fn open() -> u16 {
+		ReadyState::Open as u16
+	}
+
+	#[ion(get, name = "CLOSED")]
+	pub fn closed() -> u16 {
+		ReadyState::Closed as u16
+	}
+}
+
+/// Connects to the server, and re-establishes the connection until it fails or the [EventSource] is closed.
+async fn run(cx: &Context, target: &Object, url: Url, with_credentials: bool, state: &Cell<ReadyState>, close: &Notify) {
+	let mut last_event_id = String::new();
+	let mut retry = None;
+
+	loop {
+		let connection = Box::pin(connect(cx, target, &url, with_credentials, state, &mut last_event_id, &mut retry));
+		let disconnect = match select(connection, Box::pin(close.notified())).await {
+			Either::Left((disconnect, _)) => disconnect,
+			Either::Right(_) => Disconnect::Closed,
+		};
+
+		match disconnect {
+			Disconnect::Reconnect if state.get() != ReadyState::Closed => {
+				state.set(ReadyState::Connecting);
+				fire(cx, target, Event::new_trusted(cx, "error"));
+
+				let wait = Box::pin(sleep(Duration::from_millis(retry.unwrap_or(DEFAULT_RECONNECTION_TIME))));
+				if let Either::Right(_) = select(wait, Box::pin(close.notified())).await {
+					break;
+				}
+				if state.get() == ReadyState::Closed {
+					break;
+				}
+			}
+			Disconnect::Fail if state.get() != ReadyState::Closed => {
+				state.set(ReadyState::Closed);
+				fire(cx, target, Event::new_trusted(cx, "error"));
+				break;
+			}
+			_ => break,
+		}
+	}
+}
+
+/// Fetches the event stream, and dispatches its events until the connection ends.
+/// Each connection is parsed from the start of a line, so partial lines and events of the previous connection are discarded.
+async fn connect(
+	cx: &Context, target: &Object, url: &Url, with_credentials: bool, state: &Cell<ReadyState>, last_event_id: &mut String, retry: &mut Option<u64>,
+) -> Disconnect {
+	let mut body = match request_stream(cx, url, with_credentials, last_event_id).await {
+		Ok(Some(body)) => body,
+		Ok(None) => return Disconnect::Fail,
+		Err(()) => return Disconnect::Reconnect,
+	};
+	if state.get() == ReadyState::Closed {
+		return Disconnect::Closed;
+	}
+
+	state.set(ReadyState::Open);
+	fire(cx, target, Event::new_trusted(cx, "open"));
+
+	let mut parser = EventStreamParser::resume(last_event_id, *retry);
+	let origin = url.origin().ascii_serialization();
+	while let Some(chunk) = body.data().await {
+		let Ok(chunk) = chunk else {
+			break;
+		};
+		let events = parser.feed(&chunk);
+		last_event_id.clone_from(&parser.last_event_id);
+		*retry = parser.retry;
+
+		for event in events {
+			if state.get() == ReadyState::Closed {
+				return Disconnect::Closed;
+			}
+			let mut message = Event::new_trusted(cx, &event.kind);
+			message.define(cx, "data", &Value::string(cx, &event.data), PropertyFlags::ENUMERATE);
+			message.define(cx, "origin", &Value::string(cx, &origin), PropertyFlags::ENUMERATE);
+			message.define(cx, "lastEventId", &Value::string(cx, &event.last_event_id), PropertyFlags::ENUMERATE);
+			fire(cx, target, message);
+		}
+	}
+	Disconnect::Reconnect
+}
+
+/// Sends the request for the event stream.
+/// Returns an error if the request failed, or [None] if the response is not an event stream.
+async fn request_stream(cx: &Context, url: &Url, with_credentials: bool, last_event_id: &str) -> std::result::Result<Option<Body>, ()> {
+	let mut request = Request::constructor(cx, RequestInfo::String(String::from(url.as_str())), None).map_err(|_| ())?;
+	request.mode = RequestMode::Cors;
+	request.credentials = if with_credentials {
+		RequestCredentials::Include
+	} else {
+		RequestCredentials::SameOrigin
+	};
+	request.cache = RequestCache::NoStore;
+
+	let mut headers = Object::from(unsafe { Local::from_heap(&request.headers) });
+	let headers = &mut Headers::get_mut_private(&mut headers).headers;
+	headers.insert(ACCEPT, HeaderValue::from_static("text/event-stream"));
+	headers.insert(CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+	if !last_event_id.is_empty() {
+		if let Ok(last_event_id) = HeaderValue::from_str(last_event_id) {
+			headers.insert("Last-Event-ID", last_event_id);
+		}
+	}
+
+	let mut request = Object::from(cx.root_object(Request::new_object(cx, Box::new(request))));
+	let response = fetch_internal(cx, &mut request, GLOBAL_CLIENT.get().unwrap().clone())
+		.await
+		.map_err(|_| ())?;
+	let mut response = Object::from(cx.root_object(response));
+	let response = Response::get_mut_private(&mut response);
+
+	let headers = Object::from(unsafe { Local::from_heap(&response.headers) });
+	let content_type = Headers::get_private(&headers)
+		.headers
+		.get(CONTENT_TYPE)
+		.and_then(|value| value.to_str().ok());
+	let content_type = content_type.and_then(|value| value.parse::<Mime>().ok());
+	let is_event_stream = content_type.is_some_and(|mime| mime.essence_str() == TEXT_EVENT_STREAM.essence_str());
+
+	if response.status != Some(StatusCode::OK) || !is_event_stream {
+		return Ok(None);
+	}
+	Ok(response.response.as_mut().map(|response| take(response.body_mut())))
+}
+
+fn fire(cx: &Context, target: &Object, mut event: Object) {
+	if let Err(error) = EventTarget::fire(cx, target, &mut event) {
+		eprintln!("{}", error.format());
+	}
+}
+
+pub fn define(cx: &Context, global: &mut Object) -> bool {
+	EventSource::init_class(cx, global).0
+}
@@ -8,6 +8,7 @@ use std::iter::once;
 use std::mem::take;
 use std::str;
 use std::str::FromStr;
+use std::time::Instant;
 
 use async_recursion::async_recursion;
 use bytes::Bytes;
@@ -24,31 +25,37 @@ use mozjs::jsapi::JSObject;
 use mozjs::rust::IntoHandle;
 use sys_locale::get_locales;
 use tokio::fs::read;
+use tracing::debug;
 use url::Url;
 
 pub use client::{default_client, GLOBAL_CLIENT};
 pub use header::Headers;
-use ion::{ClassDefinition, Context, Error, ErrorKind, Exception, Local, Object, Promise, ResultExc};
+use ion::{ClassDefinition, Context, Error, ErrorKind, Exception, Local, Object, Promise, Result, ResultExc};
 use ion::class::Reflector;
 use ion::conversions::ToValue;
 use ion::flags::PropertyFlags;
 pub use request::{Request, RequestInfo, RequestInit};
 pub use response::Response;
 
+use crate::event_loop::handles::{ActiveHandle, HandleKind};
 use crate::globals::abort::AbortSignal;
-use crate::globals::fetch::body::FetchBody;
+use crate::globals::fetch::body::{decode_response, FetchBody};
 use crate::globals::fetch::client::Client;
 use crate::globals::fetch::header::{FORBIDDEN_RESPONSE_HEADERS, HeadersKind, remove_all_header_entries};
 use crate::globals::fetch::request::{Referrer, ReferrerPolicy, RequestCache, RequestCredentials, RequestMode, RequestRedirect};
 use crate::globals::fetch::response::{network_error, ResponseKind, ResponseTaint};
-use crate::promise::future_to_promise;
+use crate::globals::url::parse_url;
+use crate::permissions::check_url;
+use crate::promise::future_to_promise_with_handle;
 use crate::VERSION;
 
 mod body;
 mod client;
+mod event_source;
 mod header;
 mod request;
 mod response;
+mod wasm;
 
 const DEFAULT_USER_AGENT: &str = concatcp!("Spiderfire/", VERSION);
 
@@ -89,10 +96,11 @@ fn fetch<'cx>(cx: &'cx Context, resource: RequestInfo, init: Option<RequestInit>
 		headers.headers.append(ACCEPT_LANGUAGE, HeaderValue::from_str(&locale_string).unwrap());
 	}
 
+	let handle = ActiveHandle::new(cx, HandleKind::Fetch, format!("{} {}", request.request.method(), request.url));
 	let request = cx.root_persistent_object(Request::new_object(cx, Box::new(request)));
 	let cx2 = unsafe { Context::new_unchecked(cx.as_ptr()) };
 	let request = request.handle().into_handle();
-	future_to_promise(cx, async move {
+	future_to_promise_with_handle(cx, handle, async move {
 		let mut request = Object::from(unsafe { Local::from_raw_handle(request) });
 		let res = fetch_internal(&cx2, &mut request, GLOBAL_CLIENT.get().unwrap().clone()).await;
 		cx2.unroot_persistent_object(request.handle().get());
@@ -102,13 +110,28 @@ fn fetch<'cx>(cx: &'cx Context, resource: RequestInfo, init: Option<RequestInit>
 
 async fn fetch_internal<'o>(cx: &Context, request: &mut Object<'o>, client: Client) -> ResultExc<*mut JSObject> {
 	let request = Request::get_mut_private(request);
+	check_url(&request.url)?;
 	let signal = Object::from(unsafe { Local::from_heap(&request.signal_object) });
-	let signal = AbortSignal::get_private(&signal).signal.clone().poll();
+	let signal = AbortSignal::get_private(&signal).signal().poll();
+	let start = Instant::now();
+	let url = request.url.clone();
+	debug!(method = %request.request.method(), url = %url, "Fetch started");
 	let send = Box::pin(main_fetch(cx, request, client, 0));
 	let response = match select(send, signal).await {
 		Either::Left((response, _)) => Ok(response),
-		Either::Right((exception, _)) => Err(Exception::Other(exception)),
+		Either::Right((exception, _)) => {
+			debug!(url = %url, "Fetch aborted");
+			Err(Exception::Other(exception))
+		}
 	};
+	if let Ok(response) = &response {
+		debug!(
+			url = %url,
+			status = response.status.map(|status| status.as_u16()),
+			elapsed_ms = start.elapsed().as_secs_f64() * 1000.0,
+			"Fetch finished"
+		);
+	}
 	response.and_then(|response| {
 		if response.kind == ResponseKind::Error {
 			Err(Exception::Error(Error::new(
@@ -456,6 +479,8 @@ async fn http_network_fetch(cx: &Context, req: &Request, client: Client, is_new:
 
 	if headers.contains_key(RANGE) {
 		headers.append(ACCEPT_ENCODING, HeaderValue::from_static("identity"));
+	} else if !headers.contains_key(ACCEPT_ENCODING) {
+		headers.append(ACCEPT_ENCODING, HeaderValue::from_static("gzip, deflate, br"));
 	}
 
 	if !headers.contains_key(HOST) {
@@ -479,9 +504,14 @@ async fn http_network_fetch(cx: &Context, req: &Request, client: Client, is_new:
 
 	let range_requested = headers.contains_key(RANGE);
 
+	*request.request.body_mut() = match request.body.to_http_body(cx) {
+		Ok(body) => body,
+		Err(_) => return network_error(),
+	};
+
 	let mut response = match client.request(request.request).await {
 		Ok(response) => {
-			let mut response = Response::new(response, req.url.clone());
+			let mut response = Response::new(decode_response(response), req.url.clone());
 
 			let headers = Headers {
 				reflector: Reflector::default(),
@@ -491,7 +521,10 @@ async fn http_network_fetch(cx: &Context, req: &Request, client: Client, is_new:
 			response.headers.set(Headers::new_object(cx, Box::new(headers)));
 			response
 		}
-		Err(_) => return network_error(),
+		Err(error) => {
+			debug!(url = %req.url, error = %error, "Network request failed");
+			return network_error();
+		}
 	};
 
 	response.range_requested = range_requested;
@@ -518,10 +551,7 @@ async fn http_redirect_fetch(
 		None => return network_error(),
 		_ => {
 			let location = location.next().unwrap();
-			match Url::options()
-				.base_url(response.url.as_ref())
-				.parse(str::from_utf8(location.as_bytes()).unwrap())
-			{
+			match parse_url(str::from_utf8(location.as_bytes()).unwrap(), response.url.as_ref()) {
 				Ok(mut url) => {
 					if url.fragment().is_none() {
 						url.set_fragment(response.url.as_ref().and_then(Url::fragment));
@@ -541,6 +571,11 @@ async fn http_redirect_fetch(
 		return network_error();
 	}
 
+	if let Err(error) = check_url(&location) {
+		debug!(url = %location, error = %error, "Redirect denied");
+		return network_error();
+	}
+
 	if taint == ResponseTaint::Cors && (location.username() != "" || location.password().is_some()) {
 		return network_error();
 	}
@@ -580,5 +615,9 @@ async fn http_redirect_fetch(
 pub fn define(cx: &Context, global: &mut Object) -> bool {
 	let _ = GLOBAL_CLIENT.set(default_client());
 	global.define_method(cx, "fetch", fetch, 1, PropertyFlags::CONSTANT_ENUMERATED);
-	Headers::init_class(cx, global).0 && Request::init_class(cx, global).0 && Response::init_class(cx, global).0
+	Headers::init_class(cx, global).0
+		&& Request::init_class(cx, global).0
+		&& Response::init_class(cx, global).0
+		&& event_source::define(cx, global)
+		&& wasm::define(cx)
 }
@@ -6,21 +6,29 @@
 
 use std::str::FromStr;
 
+use std::ptr;
+
 use http::{HeaderMap, HeaderValue};
 use http::header::CONTENT_TYPE;
 use hyper::{Body, Method, Uri};
+use hyper::body::HttpBody;
 use mozjs::jsapi::{Heap, JSObject};
+use mozjs::rust::IntoHandle;
 use url::Url;
 
-use ion::{ClassDefinition, Context, Error, ErrorKind, Object, Result, Value};
-use ion::class::Reflector;
+use ion::{ClassDefinition, Context, Error, ErrorKind, Exception, Local, Object, Promise, Result, ResultExc, Value};
+use ion::class::{NativeObject, Reflector};
 use ion::conversions::FromValue;
+use ion::typedarray::ArrayBuffer;
 pub use options::*;
 
 use crate::globals::abort::AbortSignal;
-use crate::globals::fetch::body::FetchBody;
+use crate::globals::fetch::body::{FetchBody, HyperBodySource};
 use crate::globals::fetch::header::HeadersKind;
 use crate::globals::fetch::Headers;
+use crate::globals::streams::readable_stream;
+use crate::globals::url::parse_url;
+use crate::promise::future_to_promise;
 
 mod options;
 
@@ -74,8 +82,8 @@ impl Request {
 		let mut request = match info {
 			RequestInfo::Request(request) => request.clone(),
 			RequestInfo::String(url) => {
-				let uri = Uri::from_str(&url)?;
-				let url = Url::from_str(&url)?;
+				let url = parse_url(&url, None)?;
+				let uri = Uri::from_str(url.as_str())?;
 				if url.username() != "" || url.password().is_some() {
 					return Err(Error::new("Received URL with embedded credentials", ErrorKind::Type));
 				}
@@ -221,6 +229,60 @@ impl Request {
 		Ok(request)
 	}
 
+	/// Creates a request which was received by a server, with the given absolute URL.
+	/// Its body is streamed from the connection as it is read.
+	#[ion(skip)]
+	pub fn new_incoming(cx: &Context, request: hyper::Request<Body>, url: Url) -> ResultExc<Request> {
+		let (mut parts, body) = request.into_parts();
+		parts.uri = Uri::from_str(url.as_str())?;
+
+		let headers = Headers {
+			reflector: Reflector::default(),
+			headers: parts.headers.clone(),
+			kind: HeadersKind::Immutable,
+		};
+		let body = if body.is_end_stream() {
+			FetchBody::default()
+		} else {
+			let stream = readable_stream(cx, HyperBodySource::new(body))?;
+			FetchBody::from_stream(stream.handle().get())
+		};
+
+		Ok(Request {
+			reflector: Reflector::default(),
+
+			request: hyper::Request::from_parts(parts, Body::empty()),
+			headers: Heap::boxed(Headers::new_object(cx, Box::new(headers))),
+			body,
+			body_used: false,
+
+			url: url.clone(),
+			locations: vec![url],
+
+			referrer: Referrer::default(),
+			referrer_policy: ReferrerPolicy::default(),
+
+			mode: RequestMode::default(),
+			credentials: RequestCredentials::default(),
+			cache: RequestCache::default(),
+			redirect: RequestRedirect::default(),
+
+			integrity: String::new(),
+
+			unsafe_request: false,
+			keepalive: false,
+
+			client_window: false,
+			signal_object: Heap::boxed(AbortSignal::new_object(cx, Box::default())),
+		})
+	}
+
+	/// Returns the underlying request. The extensions of requests received by a server include their protocol upgrade.
+	#[ion(skip)]
+	pub fn http_request_mut(&mut self) -> &mut hyper::Request<Body> {
+		&mut self.request
+	}
+
 	#[ion(get)]
 	pub fn get_method(&self) -> String {
 		self.request.method().to_string()
@@ -236,6 +298,52 @@ impl Request {
 		self.headers.get()
 	}
 
+	/// Returns the body of the request as a [ReadableStream](crate::globals::streams), or null if it has no body.
+	#[ion(get)]
+	pub fn get_body(&mut self, cx: &Context) -> ResultExc<*mut JSObject> {
+		Ok(self.body.to_stream(cx)?.unwrap_or_else(ptr::null_mut))
+	}
+
+	#[ion(get)]
+	pub fn get_body_used(&self) -> bool {
+		self.body_used
+	}
+
+	async fn read_to_bytes(&mut self, cx: &Context) -> ResultExc<Vec<u8>> {
+		if self.body_used {
+			return Err(Error::new("Request body has already been used.", None).into());
+		}
+		self.body_used = true;
+		self.body.read_to_end(cx).await
+	}
+
+	#[ion(name = "arrayBuffer")]
+	pub fn array_buffer<'cx>(&mut self, cx: &'cx Context) -> Option<Promise<'cx>> {
+		let this = cx.root_persistent_object(self.reflector().get());
+		let cx2 = unsafe { Context::new_unchecked(cx.as_ptr()) };
+		let this = this.handle().into_handle();
+		future_to_promise::<_, _, Exception>(cx, async move {
+			let mut request = Object::from(unsafe { Local::from_raw_handle(this) });
+			let request = Request::get_mut_private(&mut request);
+			let bytes = request.read_to_bytes(&cx2).await?;
+			cx2.unroot_persistent_object(this.get());
+			Ok(ArrayBuffer::from(bytes))
+		})
+	}
+
+	pub fn text<'cx>(&mut self, cx: &'cx Context) -> Option<Promise<'cx>> {
+		let this = cx.root_persistent_object(self.reflector().get());
+		let cx2 = unsafe { Context::new_unchecked(cx.as_ptr()) };
+		let this = this.handle().into_handle();
+		future_to_promise::<_, _, Exception>(cx, async move {
+			let mut request = Object::from(unsafe { Local::from_raw_handle(this) });
+			let request = Request::get_mut_private(&mut request);
+			let bytes = request.read_to_bytes(&cx2).await?;
+			cx2.unroot_persistent_object(this.get());
+			String::from_utf8(bytes).map_err(|e| Error::new(&format!("Invalid UTF-8 sequence: {}", e), None).into())
+		})
+	}
+
 	#[ion(get)]
 	pub fn get_destination(&self) -> String {
 		String::new()
@@ -301,6 +409,28 @@ impl Request {
 		String::from("half")
 	}
 
+	/// Returns a copy of the request, with a copy of its headers and body.
+	/// Stream bodies are teed, so that both requests can read them.
+	#[ion(name = "clone")]
+	pub fn duplicate(&mut self, cx: &Context) -> ResultExc<*mut JSObject> {
+		if self.body_used {
+			return Err(Error::new("Request body has already been used.", ErrorKind::Type).into());
+		}
+		let body = self.body.tee(cx)?;
+
+		let mut request = self.clone();
+		request.body = body;
+		let headers = Object::from(unsafe { Local::from_heap(&self.headers) });
+		let headers = Headers::get_private(&headers);
+		let headers = Headers {
+			reflector: Reflector::default(),
+			headers: headers.headers.clone(),
+			kind: headers.kind,
+		};
+		request.headers.set(Headers::new_object(cx, Box::new(headers)));
+		Ok(Request::new_object(cx, Box::new(request)))
+	}
+
 	#[allow(clippy::should_implement_trait)]
 	#[ion(skip)]
 	pub fn clone(&self) -> Request {
@@ -17,6 +17,7 @@ use ion::conversions::FromValue;
 
 use crate::globals::fetch::body::FetchBody;
 use crate::globals::fetch::header::HeadersInit;
+use crate::globals::url::parse_url;
 
 #[derive(Clone, Default, Debug, Traceable)]
 pub enum Referrer {
@@ -34,7 +35,7 @@ impl FromStr for Referrer {
 		if referrer.is_empty() {
 			Ok(Referrer::NoReferrer)
 		} else {
-			let url = Url::parse(referrer).map_err(|e| Error::new(&e.to_string(), ErrorKind::Type))?;
+			let url = parse_url(referrer, None)?;
 
 			if url.scheme() == "about" && url.path() == "client" {
 				Ok(Referrer::Client)
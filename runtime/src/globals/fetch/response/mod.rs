@@ -4,6 +4,9 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/.
  */
 
+use std::mem::take;
+use std::ptr;
+
 use bytes::{Buf, BufMut, Bytes};
 use http::{HeaderMap, HeaderValue};
 use http::header::CONTENT_TYPE;
@@ -14,14 +17,15 @@ use mozjs::jsapi::{Heap, JSObject};
 use mozjs::rust::IntoHandle;
 use url::Url;
 
-use ion::{ClassDefinition, Context, Error, ErrorKind, Local, Object, Promise, Result};
+use ion::{ClassDefinition, Context, Error, ErrorKind, Exception, Local, Object, Promise, Result, ResultExc};
 use ion::class::{NativeObject, Reflector};
 use ion::typedarray::ArrayBuffer;
 pub use options::*;
 
-use crate::globals::fetch::body::FetchBody;
+use crate::globals::fetch::body::{FetchBody, HyperBodySource};
 use crate::globals::fetch::header::HeadersKind;
 use crate::globals::fetch::Headers;
+use crate::globals::streams::{readable_stream, StreamReader, tee_stream};
 use crate::promise::future_to_promise;
 
 mod options;
@@ -35,6 +39,7 @@ pub struct Response {
 	pub(crate) headers: Box<Heap<*mut JSObject>>,
 	pub(crate) body: Option<FetchBody>,
 	pub(crate) body_used: bool,
+	pub(crate) stream: Box<Heap<*mut JSObject>>,
 
 	pub(crate) kind: ResponseKind,
 	#[ion(no_trace)]
@@ -62,6 +67,7 @@ impl Response {
 			headers: Box::default(),
 			body: None,
 			body_used: false,
+			stream: Box::default(),
 
 			kind: ResponseKind::default(),
 			url: None,
@@ -85,6 +91,10 @@ impl Response {
 					headers.headers.append(CONTENT_TYPE, HeaderValue::from_str(&kind.to_string()).unwrap());
 				}
 			}
+			match body.stream() {
+				Some(stream) => response.stream.set(stream),
+				None => *response.response.as_mut().unwrap().body_mut() = body.to_http_body(cx)?,
+			}
 			response.body = Some(body);
 		}
 
@@ -108,6 +118,7 @@ impl Response {
 			headers: Box::default(),
 			body: None,
 			body_used: false,
+			stream: Box::default(),
 
 			kind: ResponseKind::default(),
 			url: Some(url),
@@ -129,6 +140,7 @@ impl Response {
 			headers: Box::default(),
 			body: None,
 			body_used: false,
+			stream: Box::default(),
 
 			kind: ResponseKind::Basic,
 			url: Some(url),
@@ -141,6 +153,60 @@ impl Response {
 		}
 	}
 
+	/// Creates a response from its status and headers, such as the response to a protocol upgrade.
+	/// Its headers can be modified before it is sent by a server.
+	#[ion(skip)]
+	pub fn new_outgoing(cx: &Context, response: hyper::Response<Body>) -> Response {
+		let status = response.status();
+		let headers = Headers {
+			reflector: Reflector::default(),
+			headers: response.headers().clone(),
+			kind: HeadersKind::Response,
+		};
+
+		Response {
+			reflector: Reflector::default(),
+
+			response: Some(response),
+			headers: Heap::boxed(Headers::new_object(cx, Box::new(headers))),
+			body: None,
+			body_used: false,
+			stream: Box::default(),
+
+			kind: ResponseKind::default(),
+			url: None,
+			redirected: false,
+
+			status: Some(status),
+			status_text: status.canonical_reason().map(String::from),
+
+			range_requested: false,
+		}
+	}
+
+	/// Takes the response to be sent by a server, along with its headers.
+	/// Bodies created from streams, or read through [body](Response::get_body), are sent as their chunks are read.
+	#[ion(skip)]
+	pub fn take_http_response(&mut self, cx: &Context) -> Result<hyper::Response<Body>> {
+		if self.body_used {
+			return Err(Error::new("Response body has already been used.", ErrorKind::Type));
+		}
+		let mut response = self
+			.response
+			.take()
+			.ok_or_else(|| Error::new("Response is a network error and cannot be sent.", ErrorKind::Type))?;
+		self.body_used = true;
+
+		if !self.headers.get().is_null() {
+			let headers = Object::from(unsafe { Local::from_heap(&self.headers) });
+			*response.headers_mut() = Headers::get_private(&headers).headers.clone();
+		}
+		if !self.stream.get().is_null() {
+			*response.body_mut() = FetchBody::from_stream(self.stream.get()).to_http_body(cx)?;
+		}
+		Ok(response)
+	}
+
 	#[ion(get)]
 	pub fn get_type(&self) -> String {
 		self.kind.to_string()
@@ -181,14 +247,101 @@ impl Response {
 		self.body_used
 	}
 
-	async fn read_to_bytes(&mut self) -> Result<Vec<u8>> {
+	/// Returns a copy of the response, with a copy of its headers and body.
+	/// Bodies are teed, so that both responses can read them.
+	#[ion(name = "clone")]
+	pub fn duplicate(&mut self, cx: &Context) -> ResultExc<*mut JSObject> {
+		if self.body_used {
+			return Err(Error::new("Response body has already been used.", ErrorKind::Type).into());
+		}
+
+		let stream = self.get_body(cx)?;
+		let branch = if stream.is_null() {
+			ptr::null_mut()
+		} else {
+			let (first, second) = tee_stream(cx, &Object::from(cx.root_object(stream)))?;
+			self.stream.set(first.handle().get());
+			second.handle().get()
+		};
+
+		let response = self.response.as_ref().map(|response| {
+			let mut clone = hyper::Response::new(Body::empty());
+			*clone.status_mut() = response.status();
+			*clone.version_mut() = response.version();
+			*clone.headers_mut() = response.headers().clone();
+			clone
+		});
+		let headers = if self.headers.get().is_null() {
+			Box::default()
+		} else {
+			let headers = Object::from(unsafe { Local::from_heap(&self.headers) });
+			let headers = Headers::get_private(&headers);
+			let headers = Headers {
+				reflector: Reflector::default(),
+				headers: headers.headers.clone(),
+				kind: headers.kind,
+			};
+			Heap::boxed(Headers::new_object(cx, Box::new(headers)))
+		};
+
+		let response = Response {
+			reflector: Reflector::default(),
+
+			response,
+			headers,
+			body: self.body.clone(),
+			body_used: false,
+			stream: Heap::boxed(branch),
+
+			kind: self.kind,
+			url: self.url.clone(),
+			redirected: self.redirected,
+
+			status: self.status,
+			status_text: self.status_text.clone(),
+
+			range_requested: self.range_requested,
+		};
+		Ok(Response::new_object(cx, Box::new(response)))
+	}
+
+	/// Returns the body of the response as a [ReadableStream](crate::globals::streams), or null if it has no body.
+	/// The stream is created when the body is first accessed, and reads from the response as it is pulled.
+	#[ion(get)]
+	pub fn get_body(&mut self, cx: &Context) -> ResultExc<*mut JSObject> {
+		if !self.stream.get().is_null() {
+			return Ok(self.stream.get());
+		}
+		if self.has_null_body() || self.body_used {
+			return Ok(ptr::null_mut());
+		}
+
+		let body = take(self.response.as_mut().unwrap().body_mut());
+		let stream = readable_stream(cx, HyperBodySource::new(body))?;
+		self.stream.set(stream.handle().get());
+		Ok(self.stream.get())
+	}
+
+	/// Checks if the response has a null body, which is the case for network errors, filtered responses without bodies,
+	/// and responses constructed without one.
+	fn has_null_body(&self) -> bool {
+		self.response.is_none() || (self.body.is_none() && self.url.is_none())
+	}
+
+	async fn read_to_bytes(&mut self, cx: &Context) -> ResultExc<Vec<u8>> {
 		if self.body_used {
-			return Err(Error::new("Response body has already been used.", None));
+			return Err(Error::new("Response body has already been used.", None).into());
 		}
 		self.body_used = true;
 
+		if !self.stream.get().is_null() {
+			let stream = Object::from(unsafe { Local::from_heap(&self.stream) });
+			let reader = StreamReader::new(cx, &stream).map_err(|_| Error::new("Response body is locked.", ErrorKind::Type))?;
+			return reader.read_to_end(cx).await;
+		}
+
 		match &mut self.response {
-			None => Err(Error::new("Response is a network error and cannot be read.", None)),
+			None => Err(Error::new("Response is a network error and cannot be read.", None).into()),
 			Some(response) => {
 				let body = response.body_mut();
 
@@ -223,10 +376,10 @@ impl Response {
 		let this = cx.root_persistent_object(self.reflector().get());
 		let cx2 = unsafe { Context::new_unchecked(cx.as_ptr()) };
 		let this = this.handle().into_handle();
-		future_to_promise::<_, _, Error>(cx, async move {
+		future_to_promise::<_, _, Exception>(cx, async move {
 			let mut response = Object::from(unsafe { Local::from_raw_handle(this) });
 			let response = Response::get_mut_private(&mut response);
-			let bytes = response.read_to_bytes().await?;
+			let bytes = response.read_to_bytes(&cx2).await?;
 			cx2.unroot_persistent_object(this.get());
 			Ok(ArrayBuffer::from(bytes))
 		})
@@ -236,12 +389,12 @@ impl Response {
 		let this = cx.root_persistent_object(self.reflector().get());
 		let cx2 = unsafe { Context::new_unchecked(cx.as_ptr()) };
 		let this = this.handle().into_handle();
-		future_to_promise::<_, _, Error>(cx, async move {
+		future_to_promise::<_, _, Exception>(cx, async move {
 			let mut response = Object::from(unsafe { Local::from_raw_handle(this) });
 			let response = Response::get_mut_private(&mut response);
-			let bytes = response.read_to_bytes().await?;
+			let bytes = response.read_to_bytes(&cx2).await?;
 			cx2.unroot_persistent_object(this.get());
-			String::from_utf8(bytes).map_err(|e| Error::new(&format!("Invalid UTF-8 sequence: {}", e), None))
+			String::from_utf8(bytes).map_err(|e| Error::new(&format!("Invalid UTF-8 sequence: {}", e), None).into())
 		})
 	}
 }
@@ -254,6 +407,7 @@ pub fn network_error() -> Response {
 		headers: Box::default(),
 		body: None,
 		body_used: false,
+		stream: Box::default(),
 
 		kind: ResponseKind::Error,
 		url: None,
@@ -0,0 +1,45 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+// Implements `WebAssembly.compileStreaming` and `WebAssembly.instantiateStreaming` (https://webassembly.github.io/spec/web-api/).
+// SpiderMonkey only provides them when an embedder consumes streams natively, so responses are read into a buffer and compiled instead.
+
+(function () {
+	"use strict";
+
+	if (typeof WebAssembly !== "object" || typeof WebAssembly.compileStreaming === "function") {
+		return;
+	}
+
+	async function bytes(source) {
+		const response = await source;
+		if (!(response instanceof Response)) {
+			throw new TypeError("Expected Response or Promise resolving to Response");
+		}
+
+		const type = response.headers.get("Content-Type");
+		if (type === null || type.split(";")[0].trim().toLowerCase() !== "application/wasm") {
+			throw new TypeError("Response must have a Content-Type of application/wasm");
+		}
+		if (!response.ok) {
+			throw new TypeError(`Response has a status of ${response.status}`);
+		}
+		return response.arrayBuffer();
+	}
+
+	const methods = {
+		async compileStreaming(source) {
+			return WebAssembly.compile(await bytes(source));
+		},
+		async instantiateStreaming(source, imports) {
+			return WebAssembly.instantiate(await bytes(source), imports);
+		},
+	};
+
+	for (const name of Object.keys(methods)) {
+		Object.defineProperty(WebAssembly, name, { value: methods[name], writable: true, configurable: true });
+	}
+});
@@ -0,0 +1,27 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::path::Path;
+
+use ion::{Context, Function, Object};
+use ion::script::Script;
+
+const WASM_SOURCE: &str = include_str!("wasm.js");
+
+/// Defines `WebAssembly.compileStreaming` and `WebAssembly.instantiateStreaming`, which compile the body of a [Response](super::Response).
+/// Nothing is defined if `WebAssembly` has been disabled.
+pub fn define(cx: &Context) -> bool {
+	let Ok(script) = Script::compile_and_evaluate(cx, Path::new("wasm.js"), WASM_SOURCE) else {
+		return false;
+	};
+	if !script.handle().is_object() {
+		return false;
+	}
+	let Some(initialise) = Function::from_object(cx, &script.to_object(cx).into_local()) else {
+		return false;
+	};
+	initialise.call(cx, &Object::global(cx), &[]).is_ok()
+}
@@ -0,0 +1,23 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use mozjs::jsapi::{GCReason, JS_GC, JSFunctionSpec};
+
+use ion::{Context, Function, Object};
+use ion::flags::PropertyFlags;
+
+#[js_fn]
+fn gc(cx: &Context) {
+	unsafe {
+		JS_GC(cx.as_ptr(), GCReason::API);
+	}
+}
+
+const FUNCTION: JSFunctionSpec = function_spec!(gc, 0);
+
+pub fn define(cx: &Context, global: &mut Object) -> bool {
+	global.define_as(cx, "gc", &Function::from_spec(cx, &FUNCTION), PropertyFlags::CONSTANT_ENUMERATED)
+}
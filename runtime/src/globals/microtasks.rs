@@ -6,7 +6,7 @@
 
 use mozjs::jsapi::JSFunctionSpec;
 
-use ion::{Context, Error, Function, Object, Result};
+use ion::{Context, Error, Function, Object, PersistentRooted, Result};
 use ion::flags::PropertyFlags;
 
 use crate::ContextExt;
@@ -14,10 +14,9 @@ use crate::event_loop::microtasks::Microtask;
 
 #[js_fn]
 fn queueMicrotask(cx: &Context, callback: Function) -> Result<()> {
-	let event_loop = unsafe { &mut (*cx.get_private().as_ptr()).event_loop };
-	if let Some(queue) = &mut event_loop.microtasks {
-		queue.enqueue(cx, Microtask::User(callback.get()));
-		Ok(())
+	let event_loop = unsafe { &(*cx.get_private().as_ptr()).event_loop };
+	if let Some(queue) = &event_loop.microtasks {
+		queue.enqueue(cx, Microtask::User(PersistentRooted::new(callback.get())))
 	} else {
 		Err(Error::new("Microtask Queue has not been initialised.", None))
 	}
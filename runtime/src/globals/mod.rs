@@ -4,24 +4,43 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/.
  */
 
-use ion::{ClassDefinition, Context, Iterator, Object};
+use ion::{AsyncIterator, ClassDefinition, Context, Iterator, Object};
 
 pub mod abort;
 pub mod base64;
+pub mod broadcast;
+pub mod clone;
+pub mod compression;
 pub mod console;
+pub mod crypto;
 pub mod encoding;
+pub mod event;
 #[cfg(feature = "fetch")]
 pub mod fetch;
+pub mod gc;
 pub mod microtasks;
+pub mod performance;
+pub mod storage;
+pub mod streams;
 pub mod timers;
 pub mod url;
+pub mod worker;
 
 pub fn init_globals(cx: &Context, global: &mut Object) -> bool {
-	let result = base64::define(cx, global)
+	let result = event::define(cx, global)
+		&& base64::define(cx, global)
+		&& broadcast::define(cx, global)
+		&& clone::define(cx, global)
+		&& compression::define(cx, global)
 		&& console::define(cx, global)
+		&& crypto::define(cx, global)
 		&& encoding::define(cx, global)
+		&& performance::define(cx, global)
+		&& storage::define(cx, global)
+		&& streams::define(cx, global)
 		&& url::define(cx, global)
-		&& Iterator::init_class(cx, global).0;
+		&& Iterator::init_class(cx, global).0
+		&& AsyncIterator::init_class(cx, global).0;
 	#[cfg(feature = "fetch")]
 	{
 		result && fetch::define(cx, global)
@@ -39,3 +58,11 @@ pub fn init_timers(cx: &Context, global: &mut Object) -> bool {
 pub fn init_microtasks(cx: &Context, global: &mut Object) -> bool {
 	microtasks::define(cx, global)
 }
+
+pub fn init_workers(cx: &Context, global: &mut Object) -> bool {
+	worker::define(cx, global)
+}
+
+pub fn init_gc(cx: &Context, global: &mut Object) -> bool {
+	gc::define(cx, global)
+}
@@ -0,0 +1,167 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use mozjs::jsapi::{Heap, JSObject};
+use mozjs::jsval::JSVal;
+
+use ion::{ClassDefinition, Context, Error, ErrorKind, Object, Result, ResultExc, Value};
+use ion::class::Reflector;
+
+use crate::clone::StructuredClone;
+use crate::ContextExt;
+
+pub const MARK: &str = "mark";
+pub const MEASURE: &str = "measure";
+
+#[js_class]
+pub struct PerformanceEntry {
+	reflector: Reflector,
+	#[ion(no_trace)]
+	pub(crate) name: String,
+	#[ion(no_trace)]
+	pub(crate) entry_type: &'static str,
+	#[ion(no_trace)]
+	pub(crate) start_time: f64,
+	#[ion(no_trace)]
+	pub(crate) duration: f64,
+}
+
+impl PerformanceEntry {
+	fn new(name: String, entry_type: &'static str, start_time: f64, duration: f64) -> PerformanceEntry {
+		PerformanceEntry {
+			reflector: Reflector::default(),
+			name,
+			entry_type,
+			start_time,
+			duration,
+		}
+	}
+
+	/// Returns the entry of an object in a performance timeline.
+	pub(crate) fn from_raw(cx: &Context, entry: *mut JSObject) -> &PerformanceEntry {
+		PerformanceEntry::get_private(&Object::from(cx.root_object(entry)))
+	}
+
+	/// Checks if the entry matches the filters of `getEntriesByName` and `getEntriesByType`.
+	pub(crate) fn matches(&self, name: Option<&str>, entry_type: Option<&str>) -> bool {
+		name.map_or(true, |name| self.name == name) && entry_type.map_or(true, |entry_type| self.entry_type == entry_type)
+	}
+}
+
+#[js_class]
+impl PerformanceEntry {
+	#[ion(constructor)]
+	pub fn constructor() -> Result<PerformanceEntry> {
+		Err(Error::new("PerformanceEntry has no constructor.", ErrorKind::Type))
+	}
+
+	#[ion(get)]
+	pub fn get_name(&self) -> String {
+		self.name.clone()
+	}
+
+	#[ion(get)]
+	pub fn get_entry_type(&self) -> String {
+		String::from(self.entry_type)
+	}
+
+	#[ion(get)]
+	pub fn get_start_time(&self) -> f64 {
+		self.start_time
+	}
+
+	#[ion(get)]
+	pub fn get_duration(&self) -> f64 {
+		self.duration
+	}
+
+	#[ion(name = "toJSON")]
+	pub fn to_json<'cx>(&self, cx: &'cx Context) -> Object<'cx> {
+		let mut object = Object::new(cx);
+		object.set_as(cx, "name", &self.name);
+		object.set_as(cx, "entryType", self.entry_type);
+		object.set_as(cx, "startTime", &self.start_time);
+		object.set_as(cx, "duration", &self.duration);
+		object
+	}
+}
+
+#[derive(Default, FromValue)]
+pub struct PerformanceMarkOptions {
+	#[ion(default)]
+	detail: Option<JSVal>,
+	start_time: Option<f64>,
+}
+
+/// Copies the detail of a mark or measure with the structured clone algorithm, so later changes to it are not observed.
+pub(crate) fn clone_detail(cx: &Context, detail: Option<JSVal>) -> ResultExc<JSVal> {
+	match detail {
+		Some(detail) if !detail.is_null_or_undefined() => {
+			let detail = Value::from(cx.root_value(detail));
+			Ok(StructuredClone::serialise(cx, &detail, &[])?.deserialise(cx)?.get())
+		}
+		_ => Ok(Value::null(cx).get()),
+	}
+}
+
+#[js_class]
+pub struct PerformanceMark {
+	entry: PerformanceEntry,
+	detail: Box<Heap<JSVal>>,
+}
+
+#[js_class]
+impl PerformanceMark {
+	#[ion(constructor)]
+	pub fn constructor(cx: &Context, name: String, options: Option<PerformanceMarkOptions>) -> ResultExc<PerformanceMark> {
+		let options = options.unwrap_or_default();
+		let start_time = match options.start_time {
+			Some(start_time) if start_time < 0.0 => {
+				return Err(Error::new("Mark start time cannot be negative", ErrorKind::Type).into());
+			}
+			Some(start_time) => start_time,
+			None => unsafe { &(*cx.get_private().as_ptr()).performance }.now(),
+		};
+
+		Ok(PerformanceMark {
+			entry: PerformanceEntry::new(name, MARK, start_time, 0.0),
+			detail: Heap::boxed(clone_detail(cx, options.detail)?),
+		})
+	}
+
+	#[ion(get)]
+	pub fn get_detail(&self) -> JSVal {
+		self.detail.get()
+	}
+}
+
+#[js_class]
+pub struct PerformanceMeasure {
+	entry: PerformanceEntry,
+	detail: Box<Heap<JSVal>>,
+}
+
+impl PerformanceMeasure {
+	pub(crate) fn new(name: String, start_time: f64, end_time: f64, detail: JSVal) -> PerformanceMeasure {
+		PerformanceMeasure {
+			entry: PerformanceEntry::new(name, MEASURE, start_time, end_time - start_time),
+			detail: Heap::boxed(detail),
+		}
+	}
+}
+
+#[js_class]
+impl PerformanceMeasure {
+	#[ion(constructor)]
+	pub fn constructor() -> Result<PerformanceMeasure> {
+		Err(Error::new("PerformanceMeasure has no constructor.", ErrorKind::Type))
+	}
+
+	#[ion(get)]
+	pub fn get_detail(&self) -> JSVal {
+		self.detail.get()
+	}
+}
@@ -0,0 +1,308 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use chrono::Duration;
+use mozjs::jsapi::JSObject;
+use mozjs::jsval::JSVal;
+
+use ion::{ClassDefinition, Context, Error, ErrorKind, Object, PersistentRooted, Result, ResultExc, Value};
+use ion::class::Reflector;
+use ion::conversions::FromValue;
+use ion::flags::PropertyFlags;
+
+use crate::ContextExt;
+use crate::event_loop::macrotasks::{Macrotask, SignalMacrotask};
+use crate::permissions::{is_granted, PermissionName};
+pub use entry::{PerformanceEntry, PerformanceMark, PerformanceMarkOptions, PerformanceMeasure};
+pub use observer::{PerformanceObserver, PerformanceObserverEntryList};
+
+mod entry;
+mod observer;
+
+/// Resolution of times in milliseconds, when high resolution time has not been granted.
+const COARSE_RESOLUTION: f64 = 2.0;
+
+const SUPPORTED_ENTRY_TYPES: [&str; 2] = [entry::MARK, entry::MEASURE];
+
+/// Holds the performance timeline of a runtime, which contains the marks and measures it has recorded.
+/// Times are measured in milliseconds from the time origin, which is when the runtime was created.
+pub struct Timeline {
+	origin: Instant,
+	origin_epoch: f64,
+	entries: Vec<PersistentRooted<*mut JSObject>>,
+	observers: Vec<PersistentRooted<*mut JSObject>>,
+	delivery_scheduled: bool,
+}
+
+impl Default for Timeline {
+	fn default() -> Timeline {
+		let epoch = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+		Timeline {
+			origin: Instant::now(),
+			origin_epoch: epoch.as_secs_f64() * 1000.0,
+			entries: Vec::new(),
+			observers: Vec::new(),
+			delivery_scheduled: false,
+		}
+	}
+}
+
+impl Timeline {
+	/// Returns the time elapsed since the time origin, using a monotonic clock.
+	/// Unless the `hrtime` permission is granted, the time is coarsened to [COARSE_RESOLUTION] to mitigate timing attacks.
+	pub fn now(&self) -> f64 {
+		let now = self.origin.elapsed().as_secs_f64() * 1000.0;
+		if is_granted(PermissionName::Hrtime) {
+			now
+		} else {
+			(now / COARSE_RESOLUTION).floor() * COARSE_RESOLUTION
+		}
+	}
+
+	/// Returns the time origin as the number of milliseconds since the Unix epoch.
+	pub fn time_origin(&self) -> f64 {
+		self.origin_epoch
+	}
+
+	fn entries(&self) -> impl Iterator<Item = *mut JSObject> + '_ {
+		self.entries.iter().map(PersistentRooted::get)
+	}
+
+	/// Adds an entry to the timeline, and buffers it for the observers of its type.
+	fn queue(&mut self, cx: &Context, entry: *mut JSObject) {
+		self.entries.push(PersistentRooted::new(entry));
+
+		let entry_type = PerformanceEntry::from_raw(cx, entry).entry_type;
+		let mut observed = false;
+		for observer in &self.observers {
+			let mut observer = Object::from(cx.root_object(observer.get()));
+			let observer = PerformanceObserver::get_mut_private(&mut observer);
+			if observer.observes(entry_type) {
+				observer.push(entry);
+				observed = true;
+			}
+		}
+		if observed {
+			self.schedule_delivery(cx);
+		}
+	}
+
+	/// Schedules a macrotask to call the observers with their buffered entries.
+	/// Observers are only called while the event loop is running.
+	fn schedule_delivery(&mut self, cx: &Context) {
+		let event_loop = unsafe { &mut (*cx.get_private().as_ptr()).event_loop };
+		let Some(queue) = &mut event_loop.macrotasks else {
+			return;
+		};
+		if self.delivery_scheduled {
+			return;
+		}
+
+		self.delivery_scheduled = true;
+		let callback = Box::new(|cx: &Context| {
+			let timeline = unsafe { &mut (*cx.get_private().as_ptr()).performance };
+			timeline.delivery_scheduled = false;
+			let observers: Vec<_> = timeline.observers.iter().map(PersistentRooted::get).collect();
+			for observer in observers {
+				PerformanceObserver::notify(cx, &mut Object::from(cx.root_object(observer)));
+			}
+		});
+		queue.enqueue(Macrotask::Signal(SignalMacrotask::new(callback, Duration::zero())), None);
+	}
+
+	/// Returns the start time of the most recent mark with the given name.
+	fn mark_time(&self, cx: &Context, mark: &MarkReference) -> Result<f64> {
+		match mark {
+			MarkReference::Name(name) => self
+				.entries()
+				.map(|entry| PerformanceEntry::from_raw(cx, entry))
+				.filter(|entry| entry.matches(Some(name), Some(entry::MARK)))
+				.last()
+				.map(|entry| entry.start_time)
+				.ok_or_else(|| Error::new(&format!("No mark named '{}' exists", name), None).with_name("SyntaxError")),
+			MarkReference::Time(time) if *time < 0.0 => Err(Error::new("Timestamp cannot be negative", ErrorKind::Type)),
+			MarkReference::Time(time) => Ok(*time),
+		}
+	}
+}
+
+/// Returns the entries which match the filters, in chronological order.
+fn filter_entries<I: IntoIterator<Item = *mut JSObject>>(
+	cx: &Context, entries: I, name: Option<&str>, entry_type: Option<&str>,
+) -> Vec<*mut JSObject> {
+	let mut entries: Vec<_> = entries
+		.into_iter()
+		.map(|entry| (entry, PerformanceEntry::from_raw(cx, entry)))
+		.filter(|(_, native)| native.matches(name, entry_type))
+		.collect();
+	entries.sort_by(|(_, a), (_, b)| a.start_time.total_cmp(&b.start_time));
+	entries.into_iter().map(|(entry, _)| entry).collect()
+}
+
+/// Refers to a point in the timeline, either by the name of a mark or by its time.
+pub enum MarkReference {
+	Name(String),
+	Time(f64),
+}
+
+impl<'cx> FromValue<'cx> for MarkReference {
+	type Config = ();
+
+	fn from_value(cx: &'cx Context, value: &Value, strict: bool, _: ()) -> Result<MarkReference> {
+		if value.handle().is_number() {
+			f64::from_value(cx, value, strict, ()).map(MarkReference::Time)
+		} else {
+			String::from_value(cx, value, strict, ()).map(MarkReference::Name)
+		}
+	}
+}
+
+#[derive(Default, FromValue)]
+pub struct PerformanceMeasureOptions {
+	#[ion(default)]
+	detail: Option<JSVal>,
+	start: Option<MarkReference>,
+	duration: Option<f64>,
+	end: Option<MarkReference>,
+}
+
+#[js_class]
+#[derive(Default)]
+pub struct Performance {
+	reflector: Reflector,
+}
+
+#[js_class]
+impl Performance {
+	#[ion(constructor)]
+	pub fn constructor() -> Result<Performance> {
+		Err(Error::new("Performance has no constructor.", ErrorKind::Type))
+	}
+
+	#[ion(get)]
+	pub fn get_time_origin(&self, cx: &Context) -> f64 {
+		unsafe { &(*cx.get_private().as_ptr()).performance }.time_origin()
+	}
+
+	pub fn now(&self, cx: &Context) -> f64 {
+		unsafe { &(*cx.get_private().as_ptr()).performance }.now()
+	}
+
+	/// Records a mark with the given name in the timeline.
+	pub fn mark(&self, cx: &Context, name: String, options: Option<PerformanceMarkOptions>) -> ResultExc<*mut JSObject> {
+		let mark = PerformanceMark::constructor(cx, name, options)?;
+		let mark = PerformanceMark::new_object(cx, Box::new(mark));
+		unsafe { &mut (*cx.get_private().as_ptr()).performance }.queue(cx, mark);
+		Ok(mark)
+	}
+
+	/// Records a measure with the given name in the timeline, which spans between two marks or times.
+	/// The start defaults to the time origin, and the end defaults to the current time.
+	pub fn measure<'cx>(
+		&self, cx: &'cx Context, name: String, start_or_options: Option<Value<'cx>>, end_mark: Option<String>,
+	) -> ResultExc<*mut JSObject> {
+		let timeline = unsafe { &mut (*cx.get_private().as_ptr()).performance };
+
+		let (start_mark, options) = match start_or_options {
+			Some(value) if value.handle().is_object() => (None, PerformanceMeasureOptions::from_value(cx, &value, false, ())?),
+			Some(value) if !value.handle().is_undefined() => (
+				Some(MarkReference::Name(String::from_value(cx, &value, false, ())?)),
+				PerformanceMeasureOptions::default(),
+			),
+			_ => (None, PerformanceMeasureOptions::default()),
+		};
+
+		let has_options = options.start.is_some() || options.end.is_some() || options.duration.is_some() || options.detail.is_some();
+		if has_options {
+			if end_mark.is_some() {
+				return Err(Error::new("End mark cannot be specified with measure options", ErrorKind::Type).into());
+			}
+			if options.start.is_none() && options.end.is_none() {
+				return Err(Error::new("Measure options must specify a start or an end", ErrorKind::Type).into());
+			}
+			if options.start.is_some() && options.duration.is_some() && options.end.is_some() {
+				return Err(Error::new("Measure options cannot specify a start, duration and end", ErrorKind::Type).into());
+			}
+		}
+
+		let end_time = match (end_mark, &options.end, &options.start, options.duration) {
+			(Some(end_mark), _, _, _) => timeline.mark_time(cx, &MarkReference::Name(end_mark))?,
+			(None, Some(end), _, _) => timeline.mark_time(cx, end)?,
+			(None, None, Some(start), Some(duration)) => timeline.mark_time(cx, start)? + duration,
+			_ => timeline.now(),
+		};
+		let start_time = match (&options.start, options.duration, &options.end, start_mark) {
+			(Some(start), _, _, _) => timeline.mark_time(cx, start)?,
+			(None, Some(duration), Some(end), _) => timeline.mark_time(cx, end)? - duration,
+			(None, _, _, Some(start_mark)) => timeline.mark_time(cx, &start_mark)?,
+			_ => 0.0,
+		};
+
+		let detail = entry::clone_detail(cx, options.detail)?;
+		let measure = PerformanceMeasure::new_object(cx, Box::new(PerformanceMeasure::new(name, start_time, end_time, detail)));
+		timeline.queue(cx, measure);
+		Ok(measure)
+	}
+
+	#[ion(name = "getEntries")]
+	pub fn get_entries(&self, cx: &Context) -> Vec<*mut JSObject> {
+		let timeline = unsafe { &(*cx.get_private().as_ptr()).performance };
+		filter_entries(cx, timeline.entries(), None, None)
+	}
+
+	#[ion(name = "getEntriesByType")]
+	pub fn get_entries_by_type(&self, cx: &Context, kind: String) -> Vec<*mut JSObject> {
+		let timeline = unsafe { &(*cx.get_private().as_ptr()).performance };
+		filter_entries(cx, timeline.entries(), None, Some(&kind))
+	}
+
+	#[ion(name = "getEntriesByName")]
+	pub fn get_entries_by_name(&self, cx: &Context, name: String, kind: Option<String>) -> Vec<*mut JSObject> {
+		let timeline = unsafe { &(*cx.get_private().as_ptr()).performance };
+		filter_entries(cx, timeline.entries(), Some(&name), kind.as_deref())
+	}
+
+	#[ion(name = "clearMarks")]
+	pub fn clear_marks(&self, cx: &Context, name: Option<String>) {
+		clear_entries(cx, name.as_deref(), entry::MARK);
+	}
+
+	#[ion(name = "clearMeasures")]
+	pub fn clear_measures(&self, cx: &Context, name: Option<String>) {
+		clear_entries(cx, name.as_deref(), entry::MEASURE);
+	}
+
+	#[ion(name = "toJSON")]
+	pub fn to_json<'cx>(&self, cx: &'cx Context) -> Object<'cx> {
+		let mut object = Object::new(cx);
+		object.set_as(cx, "timeOrigin", &self.get_time_origin(cx));
+		object
+	}
+}
+
+fn clear_entries(cx: &Context, name: Option<&str>, entry_type: &str) {
+	let timeline = unsafe { &mut (*cx.get_private().as_ptr()).performance };
+	timeline
+		.entries
+		.retain(|entry| !PerformanceEntry::from_raw(cx, entry.get()).matches(name, Some(entry_type)));
+}
+
+pub fn define(cx: &Context, global: &mut Object) -> bool {
+	Performance::init_class(cx, global).0
+		&& PerformanceEntry::init_class(cx, global).0
+		&& PerformanceMark::init_class(cx, global).0
+		&& PerformanceMeasure::init_class(cx, global).0
+		&& PerformanceObserver::init_class(cx, global).0
+		&& PerformanceObserverEntryList::init_class(cx, global).0
+		&& global.define_as(
+			cx,
+			"performance",
+			&Performance::new_object(cx, Box::default()),
+			PropertyFlags::CONSTANT_ENUMERATED,
+		)
+}
@@ -0,0 +1,164 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use mozjs::jsapi::{Heap, JSObject};
+
+use ion::{ClassDefinition, Context, Error, ErrorKind, Function, Object, PersistentRooted, Result};
+use ion::class::{NativeObject, Reflector};
+
+use crate::ContextExt;
+use crate::globals::performance::{filter_entries, SUPPORTED_ENTRY_TYPES};
+
+#[derive(FromValue)]
+pub struct PerformanceObserverInit {
+	entry_types: Option<Vec<String>>,
+	#[ion(name = "type")]
+	kind: Option<String>,
+	buffered: Option<bool>,
+}
+
+#[js_class]
+pub struct PerformanceObserver {
+	reflector: Reflector,
+	callback: Box<Heap<*mut JSObject>>,
+	#[ion(no_trace)]
+	entry_types: Vec<String>,
+	buffer: Vec<Box<Heap<*mut JSObject>>>,
+}
+
+impl PerformanceObserver {
+	pub(crate) fn observes(&self, entry_type: &str) -> bool {
+		self.entry_types.iter().any(|observed| observed == entry_type)
+	}
+
+	pub(crate) fn push(&mut self, entry: *mut JSObject) {
+		self.buffer.push(Heap::boxed(entry));
+	}
+
+	fn take_buffer(&mut self) -> Vec<*mut JSObject> {
+		self.buffer.drain(..).map(|entry| entry.get()).collect()
+	}
+
+	/// Calls the callback of an observer with the entries buffered since it was last called.
+	pub(crate) fn notify(cx: &Context, observer: &mut Object) {
+		let native = PerformanceObserver::get_mut_private(observer);
+		let entries = native.take_buffer();
+		if entries.is_empty() {
+			return;
+		}
+
+		let list = PerformanceObserverEntryList::new_object(
+			cx,
+			Box::new(PerformanceObserverEntryList {
+				reflector: Reflector::default(),
+				entries: entries.into_iter().map(Heap::boxed).collect(),
+			}),
+		);
+		let list = Object::from(cx.root_object(list));
+		let callback = Function::from_object(cx, &cx.root_object(native.callback.get())).unwrap();
+		if let Err(Some(report)) = callback.call(cx, observer, &[list.as_value(cx), observer.as_value(cx)]) {
+			eprintln!("{}", report.format(cx));
+		}
+	}
+}
+
+#[js_class]
+impl PerformanceObserver {
+	#[ion(constructor)]
+	pub fn constructor(cx: &Context, callback: Function) -> PerformanceObserver {
+		PerformanceObserver {
+			reflector: Reflector::default(),
+			callback: Heap::boxed(callback.to_object(cx).handle().get()),
+			entry_types: Vec::new(),
+			buffer: Vec::new(),
+		}
+	}
+
+	/// Starts observing entries of the given types.
+	/// `entryTypes` replaces the observed types, while `type` adds to them, and can also deliver entries already in the timeline.
+	pub fn observe(&mut self, cx: &Context, options: PerformanceObserverInit) -> Result<()> {
+		let timeline = unsafe { &mut (*cx.get_private().as_ptr()).performance };
+		match (options.entry_types, options.kind) {
+			(Some(entry_types), None) => {
+				if options.buffered.is_some() {
+					return Err(Error::new("buffered cannot be used with entryTypes", ErrorKind::Type));
+				}
+				self.entry_types = entry_types
+					.into_iter()
+					.filter(|kind| SUPPORTED_ENTRY_TYPES.contains(&kind.as_str()))
+					.collect();
+			}
+			(None, Some(kind)) => {
+				if !SUPPORTED_ENTRY_TYPES.contains(&kind.as_str()) {
+					return Ok(());
+				}
+				if options.buffered.unwrap_or_default() {
+					for entry in filter_entries(cx, timeline.entries(), None, Some(&kind)) {
+						self.push(entry);
+					}
+					timeline.schedule_delivery(cx);
+				}
+				if !self.observes(&kind) {
+					self.entry_types.push(kind);
+				}
+			}
+			_ => return Err(Error::new("Either entryTypes or type must be specified", ErrorKind::Type)),
+		}
+
+		let this = self.reflector().get();
+		if !self.entry_types.is_empty() && !timeline.observers.iter().any(|observer| observer.get() == this) {
+			timeline.observers.push(PersistentRooted::new(this));
+		}
+		Ok(())
+	}
+
+	pub fn disconnect(&mut self, cx: &Context) {
+		let timeline = unsafe { &mut (*cx.get_private().as_ptr()).performance };
+		let this = self.reflector().get();
+		timeline.observers.retain(|observer| observer.get() != this);
+		self.entry_types.clear();
+		self.buffer.clear();
+	}
+
+	#[ion(name = "takeRecords")]
+	pub fn take_records(&mut self) -> Vec<*mut JSObject> {
+		self.take_buffer()
+	}
+
+	#[ion(get, name = "supportedEntryTypes")]
+	pub fn supported_entry_types() -> Vec<String> {
+		SUPPORTED_ENTRY_TYPES.iter().copied().map(String::from).collect()
+	}
+}
+
+#[js_class]
+pub struct PerformanceObserverEntryList {
+	reflector: Reflector,
+	entries: Vec<Box<Heap<*mut JSObject>>>,
+}
+
+#[js_class]
+impl PerformanceObserverEntryList {
+	#[ion(constructor)]
+	pub fn constructor() -> Result<PerformanceObserverEntryList> {
+		Err(Error::new("PerformanceObserverEntryList has no constructor.", ErrorKind::Type))
+	}
+
+	#[ion(name = "getEntries")]
+	pub fn get_entries(&self, cx: &Context) -> Vec<*mut JSObject> {
+		filter_entries(cx, self.entries.iter().map(|entry| entry.get()), None, None)
+	}
+
+	#[ion(name = "getEntriesByType")]
+	pub fn get_entries_by_type(&self, cx: &Context, kind: String) -> Vec<*mut JSObject> {
+		filter_entries(cx, self.entries.iter().map(|entry| entry.get()), None, Some(&kind))
+	}
+
+	#[ion(name = "getEntriesByName")]
+	pub fn get_entries_by_name(&self, cx: &Context, name: String, kind: Option<String>) -> Vec<*mut JSObject> {
+		filter_entries(cx, self.entries.iter().map(|entry| entry.get()), Some(&name), kind.as_deref())
+	}
+}
@@ -0,0 +1,115 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::path::Path;
+
+use indexmap::IndexMap;
+use rusqlite::{Connection, params};
+
+use ion::{Error, Result};
+
+/// Maximum size of a storage area, counted in UTF-16 code units of its keys and values, which matches the quota of browsers.
+pub const STORAGE_QUOTA: usize = 5 * 1024 * 1024;
+
+fn storage_error(error: rusqlite::Error) -> Error {
+	Error::new(&format!("Could not access storage: {}", error), None)
+}
+
+fn length(string: &str) -> usize {
+	string.encode_utf16().count()
+}
+
+/// Represents the items of `localStorage` or `sessionStorage`, in the order they were first set.
+///
+/// Items are held in memory, and areas opened from a database also write every change through to it,
+/// so that they persist across runs of the same script.
+#[derive(Default)]
+pub struct StorageArea {
+	items: IndexMap<String, String>,
+	size: usize,
+	connection: Option<Connection>,
+}
+
+impl StorageArea {
+	/// Opens the area stored in the database at the given path, creating it if it does not exist.
+	pub fn open(path: &Path) -> rusqlite::Result<StorageArea> {
+		let connection = Connection::open(path)?;
+		connection.execute_batch("CREATE TABLE IF NOT EXISTS items (key TEXT PRIMARY KEY, value TEXT NOT NULL)")?;
+
+		let items = {
+			let mut statement = connection.prepare("SELECT key, value FROM items ORDER BY rowid")?;
+			let rows = statement.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+			rows.collect::<rusqlite::Result<IndexMap<String, String>>>()?
+		};
+		let size = items.iter().map(|(key, value)| length(key) + length(value)).sum();
+		Ok(StorageArea {
+			items,
+			size,
+			connection: Some(connection),
+		})
+	}
+
+	pub fn len(&self) -> usize {
+		self.items.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.items.is_empty()
+	}
+
+	pub fn key(&self, index: usize) -> Option<&str> {
+		self.items.get_index(index).map(|(key, _)| key.as_str())
+	}
+
+	pub fn keys(&self) -> impl Iterator<Item = &str> {
+		self.items.keys().map(String::as_str)
+	}
+
+	pub fn get(&self, key: &str) -> Option<&str> {
+		self.items.get(key).map(String::as_str)
+	}
+
+	/// Sets the value of an item.
+	/// Returns [Err] with a `QuotaExceededError` if the area would exceed [STORAGE_QUOTA].
+	pub fn set(&mut self, key: String, value: String) -> Result<()> {
+		let previous = self.items.get(&key).map(|previous| length(&key) + length(previous)).unwrap_or_default();
+		let size = self.size - previous + length(&key) + length(&value);
+		if size > STORAGE_QUOTA {
+			return Err(Error::new("Storage quota has been exceeded", None).with_name("QuotaExceededError"));
+		}
+
+		if let Some(connection) = &self.connection {
+			connection
+				.execute(
+					"INSERT INTO items (key, value) VALUES (?1, ?2) ON CONFLICT (key) DO UPDATE SET value = excluded.value",
+					params![key, value],
+				)
+				.map_err(storage_error)?;
+		}
+		self.items.insert(key, value);
+		self.size = size;
+		Ok(())
+	}
+
+	pub fn remove(&mut self, key: &str) -> Result<()> {
+		if let Some(connection) = &self.connection {
+			connection.execute("DELETE FROM items WHERE key = ?1", [key]).map_err(storage_error)?;
+		}
+		if let Some(value) = self.items.shift_remove(key) {
+			self.size -= length(key) + length(&value);
+		}
+		Ok(())
+	}
+
+	pub fn clear(&mut self) -> Result<()> {
+		if let Some(connection) = &self.connection {
+			connection.execute("DELETE FROM items", []).map_err(storage_error)?;
+		}
+		self.items.clear();
+		self.size = 0;
+		Ok(())
+	}
+}
@@ -0,0 +1,127 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::cell::RefCell;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use dunce::canonicalize;
+use url::Url;
+
+use ion::{Arguments, Context, Error, Function, Object, Result, Value};
+use ion::conversions::ToValue;
+use ion::script::Script;
+
+pub use area::{STORAGE_QUOTA, StorageArea};
+
+use crate::cache::Cache;
+use crate::config::CONFIG;
+
+mod area;
+
+const STORAGE_SOURCE: &str = include_str!("storage.js");
+
+/// Returns the origin which persistent storage is partitioned by.
+/// This is the origin of the `--location` given to the runtime if there is one, and otherwise the path of the main script.
+pub fn storage_origin() -> Option<String> {
+	let config = CONFIG.get()?;
+	if let Some(location) = &config.location {
+		let origin = Url::parse(location).map(|url| url.origin().ascii_serialization());
+		return Some(origin.unwrap_or_else(|_| location.clone()));
+	}
+	let main = canonicalize(config.main.as_ref()?).ok()?;
+	main.to_str().map(String::from)
+}
+
+/// Returns the path of a database of persistent storage for the current origin, within the cache.
+pub fn storage_file(name: &str) -> Option<PathBuf> {
+	let origin = storage_origin()?;
+	Cache::new()?.storage_file(&origin, name).ok()
+}
+
+/// Opens the area of `localStorage`, which is kept in memory if there is no origin or cache to persist it in.
+/// Returns [Err] if the database of the area cannot be created or opened.
+fn open_local_area() -> Result<StorageArea> {
+	let Some(origin) = storage_origin() else {
+		return Ok(StorageArea::default());
+	};
+	let Some(cache) = Cache::new() else {
+		return Ok(StorageArea::default());
+	};
+	let file = cache
+		.storage_file(&origin, "localStorage")
+		.map_err(|error| Error::new(&format!("Could not open localStorage: {}", error), None))?;
+	StorageArea::open(&file).map_err(|error| Error::new(&format!("Could not open localStorage: {}", error), None))
+}
+
+fn define_method<F>(cx: &Context, object: &mut Object, area: &Rc<RefCell<StorageArea>>, name: &str, method: F) -> bool
+where
+	F: for<'cx> Fn(&'cx Context, &Arguments<'cx>, &RefCell<StorageArea>) -> Result<Value<'cx>> + 'static,
+{
+	let area = Rc::clone(area);
+	let function = Function::new_closure(cx, name, move |cx, args| method(cx, args, &area));
+	object.set_as(cx, name, &function)
+}
+
+/// Creates the object of native functions which a `Storage` reads and writes its area with.
+/// Keys and values are converted to strings by the storage script before they are passed to these functions.
+fn area_object(cx: &Context, area: StorageArea) -> Object {
+	let area = Rc::new(RefCell::new(area));
+	let mut object = Object::new(cx);
+
+	define_method(cx, &mut object, &area, "length", |cx, _, area| {
+		Ok((area.borrow().len() as f64).as_value(cx))
+	});
+	define_method(cx, &mut object, &area, "key", |cx, args, area| {
+		let index = args.get::<f64>(0)?;
+		Ok(area.borrow().key(index as usize).as_value(cx))
+	});
+	define_method(cx, &mut object, &area, "keys", |cx, _, area| {
+		let keys: Vec<_> = area.borrow().keys().map(String::from).collect();
+		Ok(keys.as_value(cx))
+	});
+	define_method(cx, &mut object, &area, "get", |cx, args, area| {
+		let key = args.get::<String>(0)?;
+		Ok(area.borrow().get(&key).as_value(cx))
+	});
+	define_method(cx, &mut object, &area, "set", |cx, args, area| {
+		area.borrow_mut().set(args.get(0)?, args.get(1)?)?;
+		Ok(Value::undefined(cx))
+	});
+	define_method(cx, &mut object, &area, "remove", |cx, args, area| {
+		area.borrow_mut().remove(&args.get::<String>(0)?)?;
+		Ok(Value::undefined(cx))
+	});
+	define_method(cx, &mut object, &area, "clear", |cx, _, area| {
+		area.borrow_mut().clear()?;
+		Ok(Value::undefined(cx))
+	});
+	object
+}
+
+/// Defines `Storage`, `localStorage` and `sessionStorage`.
+/// The areas are only created once `localStorage` or `sessionStorage` is first accessed, so scripts which do not use them do not open a database.
+pub fn define(cx: &Context, _: &mut Object) -> bool {
+	let Ok(script) = Script::compile_and_evaluate(cx, Path::new("storage.js"), STORAGE_SOURCE) else {
+		return false;
+	};
+	if !script.handle().is_object() {
+		return false;
+	}
+	let Some(initialise) = Function::from_object(cx, &script.to_object(cx).into_local()) else {
+		return false;
+	};
+
+	let open = Function::new_closure(cx, "openArea", |cx, args| {
+		let area = if args.get::<bool>(0)? {
+			open_local_area()?
+		} else {
+			StorageArea::default()
+		};
+		Ok(area_object(cx, area).as_value(cx))
+	});
+	initialise.call(cx, &Object::global(cx), &[open.as_value(cx)]).is_ok()
+}
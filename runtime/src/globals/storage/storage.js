@@ -0,0 +1,138 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+// Implements the Web Storage API (https://html.spec.whatwg.org/multipage/webstorage.html).
+// Storage objects are proxies, so that items can also be read and written as properties, as in `localStorage.key = "value"`.
+// Properties of Storage.prototype take precedence over items when read, but writing a property always sets an item.
+
+(function (openArea) {
+	"use strict";
+
+	const areas = new WeakMap();
+	const token = Symbol("internal");
+
+	function area(storage) {
+		const area = typeof storage === "object" && storage !== null ? areas.get(storage) : undefined;
+		if (area === undefined) {
+			throw new TypeError("Expected Storage");
+		}
+		return area;
+	}
+
+	function checkArguments(length, required, name) {
+		if (length < required) {
+			throw new TypeError(`Storage.${name} requires at least ${required} argument${required === 1 ? "" : "s"}`);
+		}
+	}
+
+	class Storage {
+		constructor(key) {
+			if (key !== token) {
+				throw new TypeError("Illegal constructor");
+			}
+		}
+
+		get length() {
+			return area(this).length();
+		}
+
+		key(index) {
+			checkArguments(arguments.length, 1, "key");
+			return area(this).key(index >>> 0);
+		}
+
+		getItem(key) {
+			checkArguments(arguments.length, 1, "getItem");
+			return area(this).get(String(key));
+		}
+
+		setItem(key, value) {
+			checkArguments(arguments.length, 2, "setItem");
+			area(this).set(String(key), String(value));
+		}
+
+		removeItem(key) {
+			checkArguments(arguments.length, 1, "removeItem");
+			area(this).remove(String(key));
+		}
+
+		clear() {
+			area(this).clear();
+		}
+
+		get [Symbol.toStringTag]() {
+			return "Storage";
+		}
+	}
+
+	function isItem(area, target, key) {
+		return typeof key === "string" && !(key in target) && area.get(key) !== null;
+	}
+
+	function createStorage(area) {
+		const target = new Storage(token);
+		const storage = new Proxy(target, {
+			get(target, key, receiver) {
+				if (isItem(area, target, key)) {
+					return area.get(key);
+				}
+				return Reflect.get(target, key, receiver);
+			},
+			set(target, key, value, receiver) {
+				if (typeof key === "symbol") {
+					return Reflect.set(target, key, value, receiver);
+				}
+				area.set(key, String(value));
+				return true;
+			},
+			has(target, key) {
+				return isItem(area, target, key) || Reflect.has(target, key);
+			},
+			deleteProperty(target, key) {
+				if (isItem(area, target, key)) {
+					area.remove(key);
+					return true;
+				}
+				return Reflect.deleteProperty(target, key);
+			},
+			defineProperty(target, key, descriptor) {
+				if (typeof key === "symbol" || !("value" in descriptor)) {
+					return Reflect.defineProperty(target, key, descriptor);
+				}
+				area.set(key, String(descriptor.value));
+				return true;
+			},
+			ownKeys(target) {
+				return [...area.keys().filter(key => !(key in target)), ...Reflect.ownKeys(target)];
+			},
+			getOwnPropertyDescriptor(target, key) {
+				if (isItem(area, target, key)) {
+					return { value: area.get(key), writable: true, enumerable: true, configurable: true };
+				}
+				return Reflect.getOwnPropertyDescriptor(target, key);
+			},
+		});
+		areas.set(storage, area);
+		return storage;
+	}
+
+	Object.defineProperty(globalThis, "Storage", { value: Storage, writable: true, configurable: true });
+
+	const storages = {};
+	for (const [name, persistent] of [
+		["localStorage", true],
+		["sessionStorage", false],
+	]) {
+		Object.defineProperty(globalThis, name, {
+			get() {
+				storages[name] ??= createStorage(openArea(persistent));
+				return storages[name];
+			},
+			enumerable: true,
+			configurable: true,
+		});
+	}
+});
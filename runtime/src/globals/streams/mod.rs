@@ -0,0 +1,40 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::path::Path;
+
+use ion::{Context, Object, PersistentRooted};
+use ion::script::Script;
+
+use crate::ContextExt;
+
+pub use native::{
+	is_readable_stream, NativeSink, NativeSource, NativeTransform, readable_stream, StreamReader, tee_stream, transform_stream, writable_stream,
+};
+
+mod native;
+
+const STREAMS_SOURCE: &str = include_str!("streams.js");
+
+/// Returns the object of internal functions returned by the streams script, which native streams are created with.
+fn internals<'cx>(cx: &'cx Context) -> Option<Object<'cx>> {
+	let internals = unsafe { (*cx.get_private().as_ptr()).stream_internals.as_ref().map(PersistentRooted::get)? };
+	Some(Object::from(cx.root_object(internals)))
+}
+
+pub fn define(cx: &Context, _: &mut Object) -> bool {
+	let internals = Script::compile_and_evaluate(cx, Path::new("streams.js"), STREAMS_SOURCE);
+	match internals {
+		Ok(internals) if internals.handle().is_object() => {
+			let internals = PersistentRooted::new(internals.handle().to_object());
+			unsafe {
+				(*cx.get_private().as_ptr()).stream_internals = Some(internals);
+			}
+			true
+		}
+		_ => false,
+	}
+}
@@ -0,0 +1,215 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use futures::future::LocalBoxFuture;
+use mozjs::jsapi::JSObject;
+use mozjs::jsval::JSVal;
+use mozjs::typedarray::Uint8;
+
+use ion::{Context, Error, ErrorKind, ErrorReport, Exception, Function, Object, PersistentRooted, Promise, PromiseFuture, ResultExc, Value};
+use ion::conversions::ToValue;
+use ion::typedarray::{TypedArrayView, Uint8Array};
+
+use crate::event_loop::handles::{ActiveHandle, HandleKind};
+use crate::globals::streams::internals;
+use crate::promise::future_to_promise_with_handle;
+
+/// Represents a native source of bytes, which backs a [ReadableStream](https://streams.spec.whatwg.org/#rs-class).
+pub trait NativeSource: 'static {
+	/// Reads the next chunk of bytes, or [None] once the source is exhausted.
+	fn pull(&mut self) -> LocalBoxFuture<'static, Result<Option<Vec<u8>>, Error>>;
+
+	/// Called when the stream is cancelled. No further chunks are pulled afterwards.
+	fn cancel(&mut self) {}
+}
+
+/// Represents a native sink of bytes, which backs a [WritableStream](https://streams.spec.whatwg.org/#ws-class).
+pub trait NativeSink: 'static {
+	fn write(&mut self, bytes: Vec<u8>) -> LocalBoxFuture<'static, Result<(), Error>>;
+
+	fn close(&mut self) -> LocalBoxFuture<'static, Result<(), Error>> {
+		Box::pin(async { Ok(()) })
+	}
+
+	/// Called when the stream is aborted. No further chunks are written afterwards.
+	fn abort(&mut self) {}
+}
+
+/// Represents a native transformation of bytes, which backs a [TransformStream](https://streams.spec.whatwg.org/#ts-class).
+pub trait NativeTransform: 'static {
+	/// Transforms a chunk of bytes, returning the bytes which are produced so far.
+	fn transform(&mut self, bytes: Vec<u8>) -> Result<Vec<u8>, Error>;
+
+	/// Called when the writable side is closed, returning the remaining bytes.
+	fn flush(&mut self) -> Result<Vec<u8>, Error> {
+		Ok(Vec::new())
+	}
+}
+
+/// Creates a readable byte stream which pulls its chunks from `source`.
+pub fn readable_stream<'cx, S: NativeSource>(cx: &'cx Context, source: S) -> ResultExc<Object<'cx>> {
+	let source = Rc::new(RefCell::new(source));
+
+	let pull = {
+		let source = Rc::clone(&source);
+		Function::new_closure(cx, "pull", move |cx, _| {
+			let chunk = source.borrow_mut().pull();
+			let handle = ActiveHandle::new(cx, HandleKind::Stream, "Read from Native Stream");
+			let chunk = async move { Ok(chunk.await?.map(Uint8Array::from)) };
+			let promise = future_to_promise_with_handle::<_, _, Error>(cx, handle, chunk);
+			promise.map(|promise| promise.as_value(cx)).ok_or_else(no_future_queue)
+		})
+	};
+	let cancel = Function::new_closure(cx, "cancel", move |cx, _| {
+		source.borrow_mut().cancel();
+		Ok(Value::undefined(cx))
+	});
+
+	create(cx, "readableFromNative", &[pull, cancel])
+}
+
+/// Creates a writable stream which writes its chunks to `sink`.
+/// Chunks written to the stream must be [ArrayBuffers](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/ArrayBuffer) or views over them.
+pub fn writable_stream<'cx, S: NativeSink>(cx: &'cx Context, sink: S) -> ResultExc<Object<'cx>> {
+	let sink = Rc::new(RefCell::new(sink));
+
+	let write = {
+		let sink = Rc::clone(&sink);
+		Function::new_closure(cx, "write", move |cx, args| {
+			let bytes = args.value(0).map(|chunk| chunk.to_object(cx).into_local());
+			let bytes = bytes
+				.and_then(TypedArrayView::<Uint8>::from)
+				.ok_or_else(|| Error::new("Expected Uint8Array", ErrorKind::Type))?
+				.to_vec();
+
+			let write = sink.borrow_mut().write(bytes);
+			let handle = ActiveHandle::new(cx, HandleKind::Stream, "Write to Native Stream");
+			let promise = future_to_promise_with_handle::<_, _, Error>(cx, handle, write);
+			promise.map(|promise| promise.as_value(cx)).ok_or_else(no_future_queue)
+		})
+	};
+	let close = {
+		let sink = Rc::clone(&sink);
+		Function::new_closure(cx, "close", move |cx, _| {
+			let close = sink.borrow_mut().close();
+			let handle = ActiveHandle::new(cx, HandleKind::Stream, "Close Native Stream");
+			let promise = future_to_promise_with_handle::<_, _, Error>(cx, handle, close);
+			promise.map(|promise| promise.as_value(cx)).ok_or_else(no_future_queue)
+		})
+	};
+	let abort = Function::new_closure(cx, "abort", move |cx, _| {
+		sink.borrow_mut().abort();
+		Ok(Value::undefined(cx))
+	});
+
+	create(cx, "writableFromNative", &[write, close, abort])
+}
+
+/// Creates a transform stream whose chunks are transformed by `transform`.
+/// Chunks written to the stream must be [ArrayBuffers](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/ArrayBuffer) or views over them.
+pub fn transform_stream<'cx, T: NativeTransform>(cx: &'cx Context, transform: T) -> ResultExc<Object<'cx>> {
+	let transform = Rc::new(RefCell::new(transform));
+
+	let transform_chunk = {
+		let transform = Rc::clone(&transform);
+		Function::new_closure(cx, "transform", move |cx, args| {
+			let bytes = args.value(0).map(|chunk| chunk.to_object(cx).into_local());
+			let bytes = bytes
+				.and_then(TypedArrayView::<Uint8>::from)
+				.ok_or_else(|| Error::new("Expected Uint8Array", ErrorKind::Type))?
+				.to_vec();
+
+			let output = transform.borrow_mut().transform(bytes)?;
+			Ok(Uint8Array::from(output).as_value(cx))
+		})
+	};
+	let flush = Function::new_closure(cx, "flush", move |cx, _| {
+		let output = transform.borrow_mut().flush()?;
+		Ok(Uint8Array::from(output).as_value(cx))
+	});
+
+	create(cx, "transformFromNative", &[transform_chunk, flush])
+}
+
+/// Checks if a value is a [ReadableStream](https://streams.spec.whatwg.org/#rs-class).
+pub fn is_readable_stream(cx: &Context, value: &Value) -> bool {
+	call_internal(cx, "isReadableStream", &[value.get()]).is_ok_and(|result| result.handle().is_true())
+}
+
+/// Tees a [ReadableStream](https://streams.spec.whatwg.org/#rs-class), locking it and returning its two branches.
+pub fn tee_stream<'cx>(cx: &'cx Context, stream: &Object) -> ResultExc<(Object<'cx>, Object<'cx>)> {
+	let branches = call_internal(cx, "teeStream", &[stream.as_value(cx).get()])?.to_object(cx);
+	let first = branches.get(cx, 0).map(|branch| branch.to_object(cx));
+	let second = branches.get(cx, 1).map(|branch| branch.to_object(cx));
+	first.zip(second).ok_or_else(|| Error::new("Failed to Tee Stream", None).into())
+}
+
+/// Reads the chunks of a [ReadableStream](https://streams.spec.whatwg.org/#rs-class) of bytes from native code.
+/// The stream is locked to the reader while it exists.
+pub struct StreamReader {
+	read: PersistentRooted<*mut JSObject>,
+}
+
+impl StreamReader {
+	pub fn new(cx: &Context, stream: &Object) -> ResultExc<StreamReader> {
+		let read = call_internal(cx, "readerFromStream", &[stream.as_value(cx).get()])?;
+		Ok(StreamReader {
+			read: PersistentRooted::new(read.handle().to_object()),
+		})
+	}
+
+	/// Reads the next chunk of the stream, or [None] once it is closed.
+	pub async fn read(&self, cx: &Context) -> ResultExc<Option<Vec<u8>>> {
+		let read = Function::from_object(cx, &cx.root_object(self.read.get())).unwrap();
+		let promise = read.call(cx, &Object::null(cx), &[]).map_err(report_to_exception)?;
+		let promise = Promise::from(promise.to_object(cx).into_local()).unwrap();
+
+		match PromiseFuture::new(cx, &promise).await {
+			Ok(chunk) if chunk.handle().is_null() => Ok(None),
+			Ok(chunk) => Ok(TypedArrayView::<Uint8>::from(chunk.to_object(cx).into_local()).map(|chunk| chunk.to_vec())),
+			Err(error) => Err(Exception::Other(error.get())),
+		}
+	}
+
+	/// Reads the remaining chunks of the stream.
+	pub async fn read_to_end(&self, cx: &Context) -> ResultExc<Vec<u8>> {
+		let mut bytes = Vec::new();
+		while let Some(chunk) = self.read(cx).await? {
+			bytes.extend_from_slice(&chunk);
+		}
+		Ok(bytes)
+	}
+}
+
+fn create<'cx>(cx: &'cx Context, name: &str, functions: &[Function]) -> ResultExc<Object<'cx>> {
+	let args: Vec<_> = functions.iter().map(|function| function.as_value(cx).get()).collect();
+	let stream = call_internal(cx, name, &args)?;
+	Ok(stream.to_object(cx))
+}
+
+fn call_internal<'cx>(cx: &'cx Context, name: &str, args: &[JSVal]) -> ResultExc<Value<'cx>> {
+	let internals = internals(cx).ok_or_else(|| Error::new("Streams have not been initialised", None))?;
+	let function = internals
+		.get(cx, name)
+		.and_then(|function| Function::from_object(cx, &function.to_object(cx).into_local()));
+	let function = function.ok_or_else(|| Error::new("Streams have not been initialised", None))?;
+
+	let args: Vec<_> = args.iter().map(|arg| Value::from(cx.root_value(*arg))).collect();
+	function.call(cx, &internals, &args).map_err(report_to_exception)
+}
+
+fn report_to_exception(report: Option<ErrorReport>) -> Exception {
+	report
+		.map(|report| report.exception)
+		.unwrap_or_else(|| Exception::Error(Error::new("Stream threw an uncatchable exception", None)))
+}
+
+fn no_future_queue() -> Error {
+	Error::new("Native streams require a future queue", None)
+}
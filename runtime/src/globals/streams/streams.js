@@ -0,0 +1,2612 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+// Implements the Streams Standard (https://streams.spec.whatwg.org/).
+// The internal slots of streams, readers, writers and controllers are kept in a WeakMap, so they cannot be observed by scripts.
+// Chunks are copied rather than transferred, so the buffers of enqueued views remain usable by the source.
+
+(function () {
+	"use strict";
+
+	// Intrinsics are captured when the script is evaluated, before other scripts run, so that they cannot be tampered with.
+	const { Boolean, DataView, Number, Promise, RangeError, Reflect, String, Symbol, TypeError, Uint8Array, WeakMap, queueMicrotask } = globalThis;
+	const { ArrayBuffer, Object } = globalThis;
+	const { apply: reflectApply } = Reflect;
+	const { create: objectCreate, defineProperty: objectDefineProperty, entries: objectEntries, getPrototypeOf: objectGetPrototypeOf } = Object;
+	const { isFinite: numberIsFinite, isInteger: numberIsInteger, isNaN: numberIsNaN } = Number;
+	const { max: mathMax, min: mathMin } = Math;
+	const isArrayBufferView = ArrayBuffer.isView;
+
+	const uncurryThis = method => (receiver, ...args) => reflectApply(method, receiver, args);
+	const promiseThen = uncurryThis(Promise.prototype.then);
+	const { all: promiseAllStatic, reject: promiseRejectStatic, resolve: promiseResolveStatic } = Promise;
+	const promiseResolve = value => reflectApply(promiseResolveStatic, Promise, [value]);
+	const promiseReject = reason => reflectApply(promiseRejectStatic, Promise, [reason]);
+	const promiseAll = values => reflectApply(promiseAllStatic, Promise, [values]);
+	const weakMapGet = uncurryThis(WeakMap.prototype.get);
+	const weakMapSet = uncurryThis(WeakMap.prototype.set);
+
+	const slots = new WeakMap();
+	const token = Symbol("internal");
+	const noop = () => {};
+
+	function slot(object, brand) {
+		const internal = typeof object === "object" && object !== null ? weakMapGet(slots, object) : undefined;
+		if (internal === undefined || internal.brand !== brand) {
+			throw new TypeError(`Expected ${brand}`);
+		}
+		return internal;
+	}
+
+	function checkConstructor(key) {
+		if (key !== token) {
+			throw new TypeError("Illegal constructor");
+		}
+	}
+
+	function defer() {
+		const deferred = { settled: false };
+		deferred.promise = new Promise((resolve, reject) => {
+			deferred.resolve = value => {
+				deferred.settled = true;
+				resolve(value);
+			};
+			deferred.reject = reason => {
+				deferred.settled = true;
+				reject(reason);
+			};
+		});
+		return deferred;
+	}
+
+	function markHandled(promise) {
+		promiseThen(promise, undefined, noop);
+		return promise;
+	}
+
+	function promiseCall(method, receiver, ...args) {
+		if (method === undefined) {
+			return promiseResolve();
+		}
+		try {
+			return promiseResolve(reflectApply(method, receiver, args));
+		} catch (error) {
+			return promiseReject(error);
+		}
+	}
+
+	function getMethod(object, name) {
+		const method = object[name];
+		if (method === undefined || method === null) {
+			return undefined;
+		}
+		if (typeof method !== "function") {
+			throw new TypeError(`${name} must be a function`);
+		}
+		return method;
+	}
+
+	// Queuing Strategies
+
+	function extractHighWaterMark(strategy, defaultHighWaterMark) {
+		const highWaterMark = strategy?.highWaterMark;
+		if (highWaterMark === undefined) {
+			return defaultHighWaterMark;
+		}
+		const number = Number(highWaterMark);
+		if (numberIsNaN(number) || number < 0) {
+			throw new RangeError("highWaterMark must be a non-negative number");
+		}
+		return number;
+	}
+
+	function extractSizeAlgorithm(strategy) {
+		const size = strategy?.size;
+		if (size === undefined) {
+			return () => 1;
+		}
+		if (typeof size !== "function") {
+			throw new TypeError("size must be a function");
+		}
+		return chunk => size(chunk);
+	}
+
+	const byteLengthSize = function size(chunk) {
+		return chunk.byteLength;
+	};
+	const countSize = function size() {
+		return 1;
+	};
+
+	class ByteLengthQueuingStrategy {
+		constructor(init) {
+			if (init?.highWaterMark === undefined) {
+				throw new TypeError("highWaterMark is required");
+			}
+			weakMapSet(slots, this, { brand: "ByteLengthQueuingStrategy", highWaterMark: Number(init.highWaterMark) });
+		}
+
+		get highWaterMark() {
+			return slot(this, "ByteLengthQueuingStrategy").highWaterMark;
+		}
+
+		get size() {
+			slot(this, "ByteLengthQueuingStrategy");
+			return byteLengthSize;
+		}
+	}
+
+	class CountQueuingStrategy {
+		constructor(init) {
+			if (init?.highWaterMark === undefined) {
+				throw new TypeError("highWaterMark is required");
+			}
+			weakMapSet(slots, this, { brand: "CountQueuingStrategy", highWaterMark: Number(init.highWaterMark) });
+		}
+
+		get highWaterMark() {
+			return slot(this, "CountQueuingStrategy").highWaterMark;
+		}
+
+		get size() {
+			slot(this, "CountQueuingStrategy");
+			return countSize;
+		}
+	}
+
+	// Queues with Sizes
+
+	function enqueueValueWithSize(container, value, size) {
+		size = Number(size);
+		if (!numberIsFinite(size) || size < 0) {
+			throw new RangeError("Size of chunk must be a finite, non-negative number");
+		}
+		container.queue.push({ value, size });
+		container.queueTotalSize += size;
+	}
+
+	function dequeueValue(container) {
+		const { value, size } = container.queue.shift();
+		container.queueTotalSize = mathMax(0, container.queueTotalSize - size);
+		return value;
+	}
+
+	function resetQueue(container) {
+		container.queue = [];
+		container.queueTotalSize = 0;
+	}
+
+	function copyBytes(view) {
+		return new Uint8Array(view.buffer, view.byteOffset, view.byteLength).slice();
+	}
+
+	// Readable Streams
+
+	class ReadableStream {
+		constructor(underlyingSource = undefined, strategy = {}) {
+			if (underlyingSource === null) {
+				throw new TypeError("underlyingSource must be an object");
+			}
+			const source = underlyingSource ?? {};
+			const stream = initialiseReadableStream(this);
+
+			const type = source.type === undefined ? undefined : String(source.type);
+			if (type === "bytes") {
+				if (strategy?.size !== undefined) {
+					throw new RangeError("size must be undefined for byte streams");
+				}
+				setUpByteControllerFromSource(stream, source, extractHighWaterMark(strategy, 0));
+			} else if (type === undefined) {
+				const size = extractSizeAlgorithm(strategy);
+				setUpDefaultControllerFromSource(stream, source, extractHighWaterMark(strategy, 1), size);
+			} else {
+				throw new TypeError(`Invalid type: ${type}`);
+			}
+		}
+
+		static from(asyncIterable) {
+			const iterator = getAsyncIterator(asyncIterable);
+			const stream = createReadableStream(
+				noop,
+				() => {
+					let result;
+					try {
+						result = iterator.next();
+					} catch (error) {
+						return promiseReject(error);
+					}
+					return promiseThen(promiseResolve(result), result => {
+						if (typeof result !== "object" || result === null) {
+							throw new TypeError("Iterator result must be an object");
+						}
+						if (result.done) {
+							defaultControllerClose(stream.controller);
+						} else {
+							defaultControllerEnqueue(stream.controller, result.value);
+						}
+					});
+				},
+				reason => {
+					let result;
+					try {
+						const method = iterator.return;
+						if (method === undefined) {
+							return promiseResolve();
+						}
+						result = method.call(iterator, reason);
+					} catch (error) {
+						return promiseReject(error);
+					}
+					return promiseThen(promiseResolve(result), result => {
+						if (typeof result !== "object" || result === null) {
+							throw new TypeError("Iterator result must be an object");
+						}
+					});
+				},
+				0,
+			);
+			return stream.object;
+		}
+
+		get locked() {
+			return isLocked(slot(this, "ReadableStream"));
+		}
+
+		cancel(reason = undefined) {
+			let stream;
+			try {
+				stream = slot(this, "ReadableStream");
+			} catch (error) {
+				return promiseReject(error);
+			}
+			if (isLocked(stream)) {
+				return promiseReject(new TypeError("Cannot cancel a locked ReadableStream"));
+			}
+			return readableStreamCancel(stream, reason);
+		}
+
+		getReader(options = {}) {
+			slot(this, "ReadableStream");
+			const mode = options?.mode;
+			if (mode === undefined) {
+				return new ReadableStreamDefaultReader(this);
+			}
+			if (String(mode) === "byob") {
+				return new ReadableStreamBYOBReader(this);
+			}
+			throw new TypeError(`Invalid mode: ${mode}`);
+		}
+
+		pipeThrough(transform, options = {}) {
+			const stream = slot(this, "ReadableStream");
+			const { readable, writable } = transform;
+			const destination = slot(writable, "WritableStream");
+			if (isLocked(stream)) {
+				throw new TypeError("Cannot pipe a locked ReadableStream");
+			}
+			if (destination.writer !== undefined) {
+				throw new TypeError("Cannot pipe to a locked WritableStream");
+			}
+			const { preventClose, preventAbort, preventCancel, signal } = pipeOptions(options);
+			markHandled(readableStreamPipeTo(stream, destination, preventClose, preventAbort, preventCancel, signal));
+			return readable;
+		}
+
+		pipeTo(destination, options = {}) {
+			let source, dest, pipe;
+			try {
+				source = slot(this, "ReadableStream");
+				dest = slot(destination, "WritableStream");
+				pipe = pipeOptions(options);
+			} catch (error) {
+				return promiseReject(error);
+			}
+			if (isLocked(source)) {
+				return promiseReject(new TypeError("Cannot pipe a locked ReadableStream"));
+			}
+			if (dest.writer !== undefined) {
+				return promiseReject(new TypeError("Cannot pipe to a locked WritableStream"));
+			}
+			return readableStreamPipeTo(source, dest, pipe.preventClose, pipe.preventAbort, pipe.preventCancel, pipe.signal);
+		}
+
+		tee() {
+			const stream = slot(this, "ReadableStream");
+			return readableStreamTee(stream, stream.controller.brand === "ReadableByteStreamController");
+		}
+
+		values(options = {}) {
+			const stream = slot(this, "ReadableStream");
+			const iterator = objectCreate(asyncIteratorPrototype);
+			weakMapSet(slots, iterator, {
+				brand: "ReadableStreamAsyncIterator",
+				reader: acquireDefaultReader(stream),
+				preventCancel: Boolean(options?.preventCancel),
+				finished: false,
+				ongoing: undefined,
+			});
+			return iterator;
+		}
+
+		[Symbol.asyncIterator](options = {}) {
+			return this.values(options);
+		}
+	}
+
+	function initialiseReadableStream(object) {
+		const stream = {
+			brand: "ReadableStream",
+			object,
+			state: "readable",
+			reader: undefined,
+			storedError: undefined,
+			disturbed: false,
+			controller: undefined,
+		};
+		weakMapSet(slots, object, stream);
+		return stream;
+	}
+
+	function createReadableStream(startAlgorithm, pullAlgorithm, cancelAlgorithm, highWaterMark = 1, sizeAlgorithm = () => 1) {
+		const stream = initialiseReadableStream(objectCreate(ReadableStream.prototype));
+		setUpDefaultController(stream, startAlgorithm, pullAlgorithm, cancelAlgorithm, highWaterMark, sizeAlgorithm);
+		return stream;
+	}
+
+	function createReadableByteStream(startAlgorithm, pullAlgorithm, cancelAlgorithm) {
+		const stream = initialiseReadableStream(objectCreate(ReadableStream.prototype));
+		setUpByteController(stream, startAlgorithm, pullAlgorithm, cancelAlgorithm, 0, undefined);
+		return stream;
+	}
+
+	function isLocked(stream) {
+		return stream.reader !== undefined;
+	}
+
+	function readableStreamCancel(stream, reason) {
+		stream.disturbed = true;
+		if (stream.state === "closed") {
+			return promiseResolve();
+		}
+		if (stream.state === "errored") {
+			return promiseReject(stream.storedError);
+		}
+		readableStreamClose(stream);
+
+		const reader = stream.reader;
+		if (reader !== undefined && reader.brand === "ReadableStreamBYOBReader") {
+			const requests = reader.readIntoRequests;
+			reader.readIntoRequests = [];
+			for (const request of requests) {
+				request.close(undefined);
+			}
+		}
+		return promiseThen(controllerCancelSteps(stream.controller, reason), noop);
+	}
+
+	function readableStreamClose(stream) {
+		stream.state = "closed";
+		const reader = stream.reader;
+		if (reader === undefined) {
+			return;
+		}
+		reader.closed.resolve();
+		if (reader.brand === "ReadableStreamDefaultReader") {
+			const requests = reader.readRequests;
+			reader.readRequests = [];
+			for (const request of requests) {
+				request.close();
+			}
+		}
+	}
+
+	function readableStreamError(stream, error) {
+		stream.state = "errored";
+		stream.storedError = error;
+		const reader = stream.reader;
+		if (reader === undefined) {
+			return;
+		}
+		reader.closed.reject(error);
+		markHandled(reader.closed.promise);
+
+		const requests = reader.brand === "ReadableStreamDefaultReader" ? reader.readRequests : reader.readIntoRequests;
+		if (reader.brand === "ReadableStreamDefaultReader") {
+			reader.readRequests = [];
+		} else {
+			reader.readIntoRequests = [];
+		}
+		for (const request of requests) {
+			request.error(error);
+		}
+	}
+
+	function hasDefaultReader(stream) {
+		return stream.reader !== undefined && stream.reader.brand === "ReadableStreamDefaultReader";
+	}
+
+	function hasBYOBReader(stream) {
+		return stream.reader !== undefined && stream.reader.brand === "ReadableStreamBYOBReader";
+	}
+
+	function numReadRequests(stream) {
+		return hasDefaultReader(stream) ? stream.reader.readRequests.length : 0;
+	}
+
+	function numReadIntoRequests(stream) {
+		return hasBYOBReader(stream) ? stream.reader.readIntoRequests.length : 0;
+	}
+
+	function fulfillReadRequest(stream, chunk, done) {
+		const request = stream.reader.readRequests.shift();
+		if (done) {
+			request.close();
+		} else {
+			request.chunk(chunk);
+		}
+	}
+
+	function fulfillReadIntoRequest(stream, chunk, done) {
+		const request = stream.reader.readIntoRequests.shift();
+		if (done) {
+			request.close(chunk);
+		} else {
+			request.chunk(chunk);
+		}
+	}
+
+	function controllerCancelSteps(controller, reason) {
+		if (controller.brand === "ReadableByteStreamController") {
+			clearPendingPullIntos(controller);
+		}
+		resetQueue(controller);
+		const result = controller.cancelAlgorithm(reason);
+		clearReadableAlgorithms(controller);
+		return result;
+	}
+
+	function controllerPullSteps(controller, request) {
+		if (controller.brand === "ReadableByteStreamController") {
+			byteControllerPullSteps(controller, request);
+		} else {
+			defaultControllerPullSteps(controller, request);
+		}
+	}
+
+	function controllerReleaseSteps(controller) {
+		if (controller.brand === "ReadableByteStreamController" && controller.pendingPullIntos.length > 0) {
+			const first = controller.pendingPullIntos[0];
+			first.readerType = "none";
+			controller.pendingPullIntos = [first];
+		}
+	}
+
+	function clearReadableAlgorithms(controller) {
+		controller.pullAlgorithm = undefined;
+		controller.cancelAlgorithm = undefined;
+		controller.strategySize = undefined;
+	}
+
+	// Readers
+
+	class ReadableStreamDefaultReader {
+		constructor(stream) {
+			const internal = slot(stream, "ReadableStream");
+			if (isLocked(internal)) {
+				throw new TypeError("ReadableStream is locked");
+			}
+			const reader = { brand: "ReadableStreamDefaultReader", object: this, stream: undefined, closed: undefined, readRequests: [] };
+			weakMapSet(slots, this, reader);
+			readerGenericInitialise(reader, internal);
+		}
+
+		get closed() {
+			return slot(this, "ReadableStreamDefaultReader").closed.promise;
+		}
+
+		cancel(reason = undefined) {
+			const reader = slot(this, "ReadableStreamDefaultReader");
+			if (reader.stream === undefined) {
+				return promiseReject(new TypeError("Reader has been released"));
+			}
+			return readableStreamCancel(reader.stream, reason);
+		}
+
+		read() {
+			const reader = slot(this, "ReadableStreamDefaultReader");
+			if (reader.stream === undefined) {
+				return promiseReject(new TypeError("Reader has been released"));
+			}
+			const deferred = defer();
+			defaultReaderRead(reader, {
+				chunk: value => deferred.resolve({ value, done: false }),
+				close: () => deferred.resolve({ value: undefined, done: true }),
+				error: error => deferred.reject(error),
+			});
+			return deferred.promise;
+		}
+
+		releaseLock() {
+			const reader = slot(this, "ReadableStreamDefaultReader");
+			if (reader.stream !== undefined) {
+				defaultReaderRelease(reader);
+			}
+		}
+	}
+
+	class ReadableStreamBYOBReader {
+		constructor(stream) {
+			const internal = slot(stream, "ReadableStream");
+			if (isLocked(internal)) {
+				throw new TypeError("ReadableStream is locked");
+			}
+			if (internal.controller.brand !== "ReadableByteStreamController") {
+				throw new TypeError("BYOB readers can only be used with byte streams");
+			}
+			const reader = { brand: "ReadableStreamBYOBReader", object: this, stream: undefined, closed: undefined, readIntoRequests: [] };
+			weakMapSet(slots, this, reader);
+			readerGenericInitialise(reader, internal);
+		}
+
+		get closed() {
+			return slot(this, "ReadableStreamBYOBReader").closed.promise;
+		}
+
+		cancel(reason = undefined) {
+			const reader = slot(this, "ReadableStreamBYOBReader");
+			if (reader.stream === undefined) {
+				return promiseReject(new TypeError("Reader has been released"));
+			}
+			return readableStreamCancel(reader.stream, reason);
+		}
+
+		read(view, options = {}) {
+			const reader = slot(this, "ReadableStreamBYOBReader");
+			if (!isArrayBufferView(view)) {
+				return promiseReject(new TypeError("view must be an ArrayBufferView"));
+			}
+			if (view.byteLength === 0 || view.buffer.byteLength === 0) {
+				return promiseReject(new TypeError("view must not be empty"));
+			}
+			const min = options?.min === undefined ? 1 : Number(options.min);
+			const length = view instanceof DataView ? view.byteLength : view.length;
+			if (!numberIsInteger(min) || min <= 0 || min > length) {
+				return promiseReject(new RangeError("min must be a positive integer within the length of view"));
+			}
+			if (reader.stream === undefined) {
+				return promiseReject(new TypeError("Reader has been released"));
+			}
+
+			const deferred = defer();
+			const stream = reader.stream;
+			stream.disturbed = true;
+			const request = {
+				chunk: value => deferred.resolve({ value, done: false }),
+				close: value => deferred.resolve({ value, done: true }),
+				error: error => deferred.reject(error),
+			};
+			if (stream.state === "errored") {
+				request.error(stream.storedError);
+			} else {
+				byteControllerPullInto(stream.controller, view, min, request);
+			}
+			return deferred.promise;
+		}
+
+		releaseLock() {
+			const reader = slot(this, "ReadableStreamBYOBReader");
+			if (reader.stream === undefined) {
+				return;
+			}
+			readerGenericRelease(reader);
+			const requests = reader.readIntoRequests;
+			reader.readIntoRequests = [];
+			const error = new TypeError("Reader has been released");
+			for (const request of requests) {
+				request.error(error);
+			}
+		}
+	}
+
+	function readerGenericInitialise(reader, stream) {
+		reader.stream = stream;
+		stream.reader = reader;
+		reader.closed = defer();
+		if (stream.state === "closed") {
+			reader.closed.resolve();
+		} else if (stream.state === "errored") {
+			reader.closed.reject(stream.storedError);
+			markHandled(reader.closed.promise);
+		}
+	}
+
+	function readerGenericRelease(reader) {
+		const stream = reader.stream;
+		const error = new TypeError("Reader has been released");
+		if (stream.state !== "readable") {
+			reader.closed = defer();
+		}
+		reader.closed.reject(error);
+		markHandled(reader.closed.promise);
+
+		controllerReleaseSteps(stream.controller);
+		stream.reader = undefined;
+		reader.stream = undefined;
+	}
+
+	function acquireDefaultReader(stream) {
+		return weakMapGet(slots, new ReadableStreamDefaultReader(stream.object));
+	}
+
+	function defaultReaderRead(reader, request) {
+		const stream = reader.stream;
+		stream.disturbed = true;
+		if (stream.state === "closed") {
+			request.close();
+		} else if (stream.state === "errored") {
+			request.error(stream.storedError);
+		} else {
+			controllerPullSteps(stream.controller, request);
+		}
+	}
+
+	function defaultReaderRelease(reader) {
+		readerGenericRelease(reader);
+		const requests = reader.readRequests;
+		reader.readRequests = [];
+		const error = new TypeError("Reader has been released");
+		for (const request of requests) {
+			request.error(error);
+		}
+	}
+
+	// Default Controllers
+
+	class ReadableStreamDefaultController {
+		constructor(key) {
+			checkConstructor(key);
+		}
+
+		get desiredSize() {
+			return defaultControllerGetDesiredSize(slot(this, "ReadableStreamDefaultController"));
+		}
+
+		close() {
+			const controller = slot(this, "ReadableStreamDefaultController");
+			if (!defaultControllerCanCloseOrEnqueue(controller)) {
+				throw new TypeError("The stream cannot be closed");
+			}
+			defaultControllerClose(controller);
+		}
+
+		enqueue(chunk = undefined) {
+			const controller = slot(this, "ReadableStreamDefaultController");
+			if (!defaultControllerCanCloseOrEnqueue(controller)) {
+				throw new TypeError("The stream cannot be enqueued to");
+			}
+			defaultControllerEnqueue(controller, chunk);
+		}
+
+		error(error = undefined) {
+			defaultControllerError(slot(this, "ReadableStreamDefaultController"), error);
+		}
+	}
+
+	function setUpDefaultControllerFromSource(stream, source, highWaterMark, sizeAlgorithm) {
+		const start = getMethod(source, "start");
+		const pull = getMethod(source, "pull");
+		const cancel = getMethod(source, "cancel");
+		setUpDefaultController(
+			stream,
+			controller => start?.call(source, controller),
+			controller => promiseCall(pull, source, controller),
+			reason => promiseCall(cancel, source, reason),
+			highWaterMark,
+			sizeAlgorithm,
+		);
+	}
+
+	function setUpDefaultController(stream, startAlgorithm, pullAlgorithm, cancelAlgorithm, highWaterMark, sizeAlgorithm) {
+		const object = new ReadableStreamDefaultController(token);
+		const controller = {
+			brand: "ReadableStreamDefaultController",
+			object,
+			stream,
+			queue: [],
+			queueTotalSize: 0,
+			started: false,
+			closeRequested: false,
+			pullAgain: false,
+			pulling: false,
+			strategySize: sizeAlgorithm,
+			strategyHighWaterMark: highWaterMark,
+			pullAlgorithm,
+			cancelAlgorithm,
+		};
+		weakMapSet(slots, object, controller);
+		stream.controller = controller;
+
+		promiseThen(
+			promiseResolve(startAlgorithm(object)),
+			() => {
+				controller.started = true;
+				defaultControllerCallPullIfNeeded(controller);
+			},
+			error => defaultControllerError(controller, error),
+		);
+	}
+
+	function defaultControllerCallPullIfNeeded(controller) {
+		if (!defaultControllerShouldCallPull(controller)) {
+			return;
+		}
+		if (controller.pulling) {
+			controller.pullAgain = true;
+			return;
+		}
+		controller.pulling = true;
+		promiseThen(
+			controller.pullAlgorithm(controller.object),
+			() => {
+				controller.pulling = false;
+				if (controller.pullAgain) {
+					controller.pullAgain = false;
+					defaultControllerCallPullIfNeeded(controller);
+				}
+			},
+			error => defaultControllerError(controller, error),
+		);
+	}
+
+	function defaultControllerShouldCallPull(controller) {
+		if (!defaultControllerCanCloseOrEnqueue(controller) || !controller.started) {
+			return false;
+		}
+		if (isLocked(controller.stream) && numReadRequests(controller.stream) > 0) {
+			return true;
+		}
+		return defaultControllerGetDesiredSize(controller) > 0;
+	}
+
+	function defaultControllerCanCloseOrEnqueue(controller) {
+		return !controller.closeRequested && controller.stream.state === "readable";
+	}
+
+	function defaultControllerHasBackpressure(controller) {
+		return !defaultControllerShouldCallPull(controller);
+	}
+
+	function defaultControllerGetDesiredSize(controller) {
+		const state = controller.stream.state;
+		if (state === "errored") {
+			return null;
+		}
+		if (state === "closed") {
+			return 0;
+		}
+		return controller.strategyHighWaterMark - controller.queueTotalSize;
+	}
+
+	function defaultControllerClose(controller) {
+		if (!defaultControllerCanCloseOrEnqueue(controller)) {
+			return;
+		}
+		controller.closeRequested = true;
+		if (controller.queue.length === 0) {
+			clearReadableAlgorithms(controller);
+			readableStreamClose(controller.stream);
+		}
+	}
+
+	function defaultControllerEnqueue(controller, chunk) {
+		if (!defaultControllerCanCloseOrEnqueue(controller)) {
+			return;
+		}
+		const stream = controller.stream;
+		if (isLocked(stream) && numReadRequests(stream) > 0) {
+			fulfillReadRequest(stream, chunk, false);
+		} else {
+			try {
+				enqueueValueWithSize(controller, chunk, controller.strategySize(chunk));
+			} catch (error) {
+				defaultControllerError(controller, error);
+				throw error;
+			}
+		}
+		defaultControllerCallPullIfNeeded(controller);
+	}
+
+	function defaultControllerError(controller, error) {
+		if (controller.stream.state !== "readable") {
+			return;
+		}
+		resetQueue(controller);
+		clearReadableAlgorithms(controller);
+		readableStreamError(controller.stream, error);
+	}
+
+	function defaultControllerPullSteps(controller, request) {
+		const stream = controller.stream;
+		if (controller.queue.length > 0) {
+			const chunk = dequeueValue(controller);
+			if (controller.closeRequested && controller.queue.length === 0) {
+				clearReadableAlgorithms(controller);
+				readableStreamClose(stream);
+			} else {
+				defaultControllerCallPullIfNeeded(controller);
+			}
+			request.chunk(chunk);
+		} else {
+			stream.reader.readRequests.push(request);
+			defaultControllerCallPullIfNeeded(controller);
+		}
+	}
+
+	// Byte Stream Controllers
+
+	class ReadableByteStreamController {
+		constructor(key) {
+			checkConstructor(key);
+		}
+
+		get byobRequest() {
+			const controller = slot(this, "ReadableByteStreamController");
+			if (controller.byobRequest === undefined && controller.pendingPullIntos.length > 0) {
+				const first = controller.pendingPullIntos[0];
+				const view = new Uint8Array(first.buffer, first.byteOffset + first.bytesFilled, first.byteLength - first.bytesFilled);
+				const request = new ReadableStreamBYOBRequest(token);
+				weakMapSet(slots, request, { brand: "ReadableStreamBYOBRequest", controller, view });
+				controller.byobRequest = request;
+			}
+			return controller.byobRequest ?? null;
+		}
+
+		get desiredSize() {
+			return byteControllerGetDesiredSize(slot(this, "ReadableByteStreamController"));
+		}
+
+		close() {
+			const controller = slot(this, "ReadableByteStreamController");
+			if (controller.closeRequested) {
+				throw new TypeError("The stream is already closing");
+			}
+			if (controller.stream.state !== "readable") {
+				throw new TypeError("The stream cannot be closed");
+			}
+			byteControllerClose(controller);
+		}
+
+		enqueue(chunk) {
+			const controller = slot(this, "ReadableByteStreamController");
+			if (!isArrayBufferView(chunk)) {
+				throw new TypeError("chunk must be an ArrayBufferView");
+			}
+			if (chunk.byteLength === 0 || chunk.buffer.byteLength === 0) {
+				throw new TypeError("chunk must not be empty");
+			}
+			if (controller.closeRequested) {
+				throw new TypeError("The stream is closing");
+			}
+			if (controller.stream.state !== "readable") {
+				throw new TypeError("The stream cannot be enqueued to");
+			}
+			byteControllerEnqueue(controller, chunk);
+		}
+
+		error(error = undefined) {
+			byteControllerError(slot(this, "ReadableByteStreamController"), error);
+		}
+	}
+
+	class ReadableStreamBYOBRequest {
+		constructor(key) {
+			checkConstructor(key);
+		}
+
+		get view() {
+			return slot(this, "ReadableStreamBYOBRequest").view;
+		}
+
+		respond(bytesWritten) {
+			const request = slot(this, "ReadableStreamBYOBRequest");
+			if (request.controller === undefined) {
+				throw new TypeError("This request has already been responded to");
+			}
+			byteControllerRespond(request.controller, Number(bytesWritten));
+		}
+
+		respondWithNewView(view) {
+			const request = slot(this, "ReadableStreamBYOBRequest");
+			if (!isArrayBufferView(view)) {
+				throw new TypeError("view must be an ArrayBufferView");
+			}
+			if (request.controller === undefined) {
+				throw new TypeError("This request has already been responded to");
+			}
+			const controller = request.controller;
+			const first = controller.pendingPullIntos[0];
+			if (view.byteOffset !== first.byteOffset + first.bytesFilled) {
+				throw new RangeError("view must start at the offset of the request");
+			}
+			first.buffer = view.buffer;
+			byteControllerRespond(controller, view.byteLength);
+		}
+	}
+
+	function setUpByteControllerFromSource(stream, source, highWaterMark) {
+		const start = getMethod(source, "start");
+		const pull = getMethod(source, "pull");
+		const cancel = getMethod(source, "cancel");
+		let autoAllocateChunkSize = source.autoAllocateChunkSize;
+		if (autoAllocateChunkSize !== undefined) {
+			autoAllocateChunkSize = Number(autoAllocateChunkSize);
+			if (!numberIsInteger(autoAllocateChunkSize) || autoAllocateChunkSize <= 0) {
+				throw new TypeError("autoAllocateChunkSize must be a positive integer");
+			}
+		}
+		setUpByteController(
+			stream,
+			controller => start?.call(source, controller),
+			controller => promiseCall(pull, source, controller),
+			reason => promiseCall(cancel, source, reason),
+			highWaterMark,
+			autoAllocateChunkSize,
+		);
+	}
+
+	function setUpByteController(stream, startAlgorithm, pullAlgorithm, cancelAlgorithm, highWaterMark, autoAllocateChunkSize) {
+		const object = new ReadableByteStreamController(token);
+		const controller = {
+			brand: "ReadableByteStreamController",
+			object,
+			stream,
+			queue: [],
+			queueTotalSize: 0,
+			started: false,
+			closeRequested: false,
+			pullAgain: false,
+			pulling: false,
+			strategyHighWaterMark: highWaterMark,
+			pullAlgorithm,
+			cancelAlgorithm,
+			autoAllocateChunkSize,
+			pendingPullIntos: [],
+			byobRequest: undefined,
+		};
+		weakMapSet(slots, object, controller);
+		stream.controller = controller;
+
+		promiseThen(
+			promiseResolve(startAlgorithm(object)),
+			() => {
+				controller.started = true;
+				byteControllerCallPullIfNeeded(controller);
+			},
+			error => byteControllerError(controller, error),
+		);
+	}
+
+	function byteControllerCallPullIfNeeded(controller) {
+		if (!byteControllerShouldCallPull(controller)) {
+			return;
+		}
+		if (controller.pulling) {
+			controller.pullAgain = true;
+			return;
+		}
+		controller.pulling = true;
+		promiseThen(
+			controller.pullAlgorithm(controller.object),
+			() => {
+				controller.pulling = false;
+				if (controller.pullAgain) {
+					controller.pullAgain = false;
+					byteControllerCallPullIfNeeded(controller);
+				}
+			},
+			error => byteControllerError(controller, error),
+		);
+	}
+
+	function byteControllerShouldCallPull(controller) {
+		const stream = controller.stream;
+		if (stream.state !== "readable" || controller.closeRequested || !controller.started) {
+			return false;
+		}
+		if (numReadRequests(stream) > 0 || numReadIntoRequests(stream) > 0) {
+			return true;
+		}
+		return byteControllerGetDesiredSize(controller) > 0;
+	}
+
+	function byteControllerGetDesiredSize(controller) {
+		const state = controller.stream.state;
+		if (state === "errored") {
+			return null;
+		}
+		if (state === "closed") {
+			return 0;
+		}
+		return controller.strategyHighWaterMark - controller.queueTotalSize;
+	}
+
+	function invalidateBYOBRequest(controller) {
+		if (controller.byobRequest !== undefined) {
+			const request = weakMapGet(slots, controller.byobRequest);
+			request.controller = undefined;
+			request.view = null;
+			controller.byobRequest = undefined;
+		}
+	}
+
+	function clearPendingPullIntos(controller) {
+		invalidateBYOBRequest(controller);
+		controller.pendingPullIntos = [];
+	}
+
+	function byteControllerEnqueueChunk(controller, buffer, byteOffset, byteLength) {
+		controller.queue.push({ buffer, byteOffset, byteLength });
+		controller.queueTotalSize += byteLength;
+	}
+
+	function byteControllerEnqueue(controller, chunk) {
+		const stream = controller.stream;
+		if (controller.closeRequested || stream.state !== "readable") {
+			return;
+		}
+		const bytes = copyBytes(chunk);
+
+		if (controller.pendingPullIntos.length > 0) {
+			invalidateBYOBRequest(controller);
+			const first = controller.pendingPullIntos[0];
+			if (first.readerType === "none") {
+				if (first.bytesFilled > 0) {
+					byteControllerEnqueueChunk(controller, first.buffer.slice(first.byteOffset, first.byteOffset + first.bytesFilled), 0, first.bytesFilled);
+				}
+				controller.pendingPullIntos.shift();
+			}
+		}
+
+		if (hasDefaultReader(stream) && numReadRequests(stream) > 0) {
+			if (controller.pendingPullIntos.length > 0) {
+				controller.pendingPullIntos.shift();
+			}
+			fulfillReadRequest(stream, bytes, false);
+		} else {
+			byteControllerEnqueueChunk(controller, bytes.buffer, 0, bytes.byteLength);
+			if (hasBYOBReader(stream)) {
+				processPullIntosUsingQueue(controller);
+			}
+		}
+		byteControllerCallPullIfNeeded(controller);
+	}
+
+	function byteControllerClose(controller) {
+		const stream = controller.stream;
+		if (controller.closeRequested || stream.state !== "readable") {
+			return;
+		}
+		if (controller.queueTotalSize > 0) {
+			controller.closeRequested = true;
+			return;
+		}
+		if (controller.pendingPullIntos.length > 0) {
+			const first = controller.pendingPullIntos[0];
+			if (first.bytesFilled % first.elementSize !== 0) {
+				const error = new TypeError("Insufficient bytes to fill elements in the given buffer");
+				byteControllerError(controller, error);
+				throw error;
+			}
+		}
+		clearReadableAlgorithms(controller);
+		readableStreamClose(stream);
+	}
+
+	function byteControllerError(controller, error) {
+		if (controller.stream.state !== "readable") {
+			return;
+		}
+		clearPendingPullIntos(controller);
+		resetQueue(controller);
+		clearReadableAlgorithms(controller);
+		readableStreamError(controller.stream, error);
+	}
+
+	function byteControllerHandleQueueDrain(controller) {
+		if (controller.queueTotalSize === 0 && controller.closeRequested) {
+			clearReadableAlgorithms(controller);
+			readableStreamClose(controller.stream);
+		} else {
+			byteControllerCallPullIfNeeded(controller);
+		}
+	}
+
+	function byteControllerPullSteps(controller, request) {
+		const stream = controller.stream;
+		if (controller.queueTotalSize > 0) {
+			const entry = controller.queue.shift();
+			controller.queueTotalSize -= entry.byteLength;
+			byteControllerHandleQueueDrain(controller);
+			request.chunk(new Uint8Array(entry.buffer, entry.byteOffset, entry.byteLength));
+			return;
+		}
+		if (controller.autoAllocateChunkSize !== undefined) {
+			const size = controller.autoAllocateChunkSize;
+			controller.pendingPullIntos.push({
+				buffer: new ArrayBuffer(size),
+				byteOffset: 0,
+				byteLength: size,
+				bytesFilled: 0,
+				minimumFill: 1,
+				elementSize: 1,
+				viewConstructor: Uint8Array,
+				readerType: "default",
+			});
+		}
+		stream.reader.readRequests.push(request);
+		byteControllerCallPullIfNeeded(controller);
+	}
+
+	function byteControllerPullInto(controller, view, min, request) {
+		const stream = controller.stream;
+		const viewConstructor = view instanceof DataView ? DataView : view.constructor;
+		const elementSize = viewConstructor.BYTES_PER_ELEMENT ?? 1;
+		const pullInto = {
+			buffer: view.buffer,
+			byteOffset: view.byteOffset,
+			byteLength: view.byteLength,
+			bytesFilled: 0,
+			minimumFill: min * elementSize,
+			elementSize,
+			viewConstructor,
+			readerType: "byob",
+		};
+
+		if (controller.pendingPullIntos.length > 0) {
+			controller.pendingPullIntos.push(pullInto);
+			stream.reader.readIntoRequests.push(request);
+			return;
+		}
+		if (stream.state === "closed") {
+			request.close(new viewConstructor(pullInto.buffer, pullInto.byteOffset, 0));
+			return;
+		}
+		if (controller.queueTotalSize > 0) {
+			if (fillPullIntoFromQueue(controller, pullInto)) {
+				const filled = convertPullInto(pullInto);
+				byteControllerHandleQueueDrain(controller);
+				request.chunk(filled);
+				return;
+			}
+			if (controller.closeRequested) {
+				const error = new TypeError("Insufficient bytes to fill elements in the given buffer");
+				byteControllerError(controller, error);
+				request.error(error);
+				return;
+			}
+		}
+
+		controller.pendingPullIntos.push(pullInto);
+		stream.reader.readIntoRequests.push(request);
+		byteControllerCallPullIfNeeded(controller);
+	}
+
+	function fillPullIntoFromQueue(controller, pullInto) {
+		const maxBytesToCopy = mathMin(controller.queueTotalSize, pullInto.byteLength - pullInto.bytesFilled);
+		const maxBytesFilled = pullInto.bytesFilled + maxBytesToCopy;
+		const maxAlignedBytes = maxBytesFilled - (maxBytesFilled % pullInto.elementSize);
+		let remaining = mathMax(0, maxAlignedBytes - pullInto.bytesFilled);
+		const ready = maxAlignedBytes >= pullInto.minimumFill;
+
+		const destination = new Uint8Array(pullInto.buffer);
+		while (remaining > 0) {
+			const entry = controller.queue[0];
+			const count = mathMin(remaining, entry.byteLength);
+			destination.set(new Uint8Array(entry.buffer, entry.byteOffset, count), pullInto.byteOffset + pullInto.bytesFilled);
+			if (entry.byteLength === count) {
+				controller.queue.shift();
+			} else {
+				entry.byteOffset += count;
+				entry.byteLength -= count;
+			}
+			controller.queueTotalSize -= count;
+			pullInto.bytesFilled += count;
+			remaining -= count;
+		}
+		return ready;
+	}
+
+	function convertPullInto(pullInto) {
+		return new pullInto.viewConstructor(pullInto.buffer, pullInto.byteOffset, pullInto.bytesFilled / pullInto.elementSize);
+	}
+
+	function commitPullInto(stream, pullInto) {
+		const done = stream.state === "closed";
+		const filled = convertPullInto(pullInto);
+		if (pullInto.readerType === "default") {
+			fulfillReadRequest(stream, filled, done);
+		} else {
+			fulfillReadIntoRequest(stream, filled, done);
+		}
+	}
+
+	function processPullIntosUsingQueue(controller) {
+		while (controller.pendingPullIntos.length > 0 && controller.queueTotalSize > 0) {
+			const first = controller.pendingPullIntos[0];
+			if (!fillPullIntoFromQueue(controller, first)) {
+				break;
+			}
+			controller.pendingPullIntos.shift();
+			commitPullInto(controller.stream, first);
+		}
+	}
+
+	function byteControllerRespond(controller, bytesWritten) {
+		const stream = controller.stream;
+		const first = controller.pendingPullIntos[0];
+		if (stream.state === "closed") {
+			if (bytesWritten !== 0) {
+				throw new TypeError("bytesWritten must be 0 when the stream is closed");
+			}
+			invalidateBYOBRequest(controller);
+			if (first.readerType === "none") {
+				controller.pendingPullIntos.shift();
+			}
+			if (hasBYOBReader(stream)) {
+				while (numReadIntoRequests(stream) > 0) {
+					commitPullInto(stream, controller.pendingPullIntos.shift());
+				}
+			}
+		} else {
+			if (!numberIsInteger(bytesWritten) || bytesWritten <= 0) {
+				throw new TypeError("bytesWritten must be a positive integer");
+			}
+			if (first.bytesFilled + bytesWritten > first.byteLength) {
+				throw new RangeError("bytesWritten is out of range");
+			}
+			invalidateBYOBRequest(controller);
+			first.bytesFilled += bytesWritten;
+
+			if (first.readerType === "none") {
+				controller.pendingPullIntos.shift();
+				byteControllerEnqueueChunk(controller, first.buffer.slice(first.byteOffset, first.byteOffset + first.bytesFilled), 0, first.bytesFilled);
+				processPullIntosUsingQueue(controller);
+			} else if (first.bytesFilled >= first.minimumFill) {
+				controller.pendingPullIntos.shift();
+				const remainder = first.bytesFilled % first.elementSize;
+				if (remainder > 0) {
+					const end = first.byteOffset + first.bytesFilled;
+					byteControllerEnqueueChunk(controller, first.buffer.slice(end - remainder, end), 0, remainder);
+				}
+				first.bytesFilled -= remainder;
+				commitPullInto(stream, first);
+				processPullIntosUsingQueue(controller);
+			}
+		}
+		byteControllerCallPullIfNeeded(controller);
+	}
+
+	// Piping
+
+	function pipeOptions(options) {
+		const preventClose = Boolean(options?.preventClose);
+		const preventAbort = Boolean(options?.preventAbort);
+		const preventCancel = Boolean(options?.preventCancel);
+		const signal = options?.signal;
+		if (signal !== undefined && (typeof AbortSignal !== "function" || !(signal instanceof AbortSignal))) {
+			throw new TypeError("signal must be an AbortSignal");
+		}
+		return { preventClose, preventAbort, preventCancel, signal };
+	}
+
+	function readableStreamPipeTo(source, dest, preventClose, preventAbort, preventCancel, signal) {
+		const reader = acquireDefaultReader(source);
+		const writer = acquireWriter(dest);
+		source.disturbed = true;
+
+		let shuttingDown = false;
+		let currentWrite = promiseResolve();
+		const result = defer();
+		let abortAlgorithm;
+
+		const finalise = (isError, error) => {
+			writerRelease(writer);
+			defaultReaderRelease(reader);
+			if (signal !== undefined) {
+				signal.removeEventListener("abort", abortAlgorithm);
+			}
+			if (isError) {
+				result.reject(error);
+			} else {
+				result.resolve();
+			}
+		};
+		const waitForWrites = () => {
+			const write = currentWrite;
+			return promiseThen(write, () => (write !== currentWrite ? waitForWrites() : undefined));
+		};
+		const isWritable = () => dest.state === "writable" && !closeQueuedOrInFlight(dest);
+		const shutdownWithAction = (action, isError = false, error = undefined) => {
+			if (shuttingDown) {
+				return;
+			}
+			shuttingDown = true;
+			const perform = () => {
+				let promise;
+				try {
+					promise = promiseResolve(action());
+				} catch (error) {
+					promise = promiseReject(error);
+				}
+				promiseThen(
+					promise,
+					() => finalise(isError, error),
+					error => finalise(true, error),
+				);
+			};
+			if (isWritable()) {
+				promiseThen(waitForWrites(), perform);
+			} else {
+				perform();
+			}
+		};
+		const shutdown = (isError = false, error = undefined) => {
+			if (shuttingDown) {
+				return;
+			}
+			shuttingDown = true;
+			if (isWritable()) {
+				promiseThen(waitForWrites(), () => finalise(isError, error));
+			} else {
+				finalise(isError, error);
+			}
+		};
+
+		if (signal !== undefined) {
+			abortAlgorithm = () => {
+				const error = signal.reason;
+				const actions = [];
+				if (!preventAbort) {
+					actions.push(() => (dest.state === "writable" ? writableStreamAbort(dest, error) : promiseResolve()));
+				}
+				if (!preventCancel) {
+					actions.push(() => (source.state === "readable" ? readableStreamCancel(source, error) : promiseResolve()));
+				}
+				shutdownWithAction(() => promiseAll(actions.map(action => action())), true, error);
+			};
+			if (signal.aborted) {
+				abortAlgorithm();
+				return result.promise;
+			}
+			signal.addEventListener("abort", abortAlgorithm);
+		}
+
+		const onSourceErrored = error => {
+			if (!preventAbort) {
+				shutdownWithAction(() => writableStreamAbort(dest, error), true, error);
+			} else {
+				shutdown(true, error);
+			}
+		};
+		const onDestinationErrored = error => {
+			if (!preventCancel) {
+				shutdownWithAction(() => readableStreamCancel(source, error), true, error);
+			} else {
+				shutdown(true, error);
+			}
+		};
+		const onSourceClosed = () => {
+			if (!preventClose) {
+				shutdownWithAction(() => writerCloseWithErrorPropagation(writer));
+			} else {
+				shutdown();
+			}
+		};
+		const onDestinationClosed = () => {
+			const error = new TypeError("The destination stream has been closed");
+			if (!preventCancel) {
+				shutdownWithAction(() => readableStreamCancel(source, error), true, error);
+			} else {
+				shutdown(true, error);
+			}
+		};
+
+		if (source.state === "errored") {
+			onSourceErrored(source.storedError);
+		} else if (dest.state === "erroring" || dest.state === "errored") {
+			onDestinationErrored(dest.storedError);
+		} else if (source.state === "closed") {
+			onSourceClosed();
+		} else if (closeQueuedOrInFlight(dest) || dest.state === "closed") {
+			onDestinationClosed();
+		}
+
+		promiseThen(
+			reader.closed.promise,
+			() => !shuttingDown && onSourceClosed(),
+			error => !shuttingDown && onSourceErrored(error),
+		);
+		promiseThen(writer.closed.promise, undefined, error => !shuttingDown && onDestinationErrored(error));
+
+		const step = () => {
+			if (shuttingDown) {
+				return promiseResolve(true);
+			}
+			return promiseThen(writer.ready.promise, () => {
+				if (shuttingDown) {
+					return true;
+				}
+				const read = defer();
+				defaultReaderRead(reader, {
+					chunk: chunk => {
+						currentWrite = promiseThen(writerWrite(writer, chunk), noop, noop);
+						read.resolve(false);
+					},
+					close: () => read.resolve(true),
+					error: () => read.resolve(true),
+				});
+				return read.promise;
+			});
+		};
+		const loop = () => {
+			promiseThen(step(), done => !done && loop(), noop);
+		};
+		loop();
+
+		return result.promise;
+	}
+
+	// Teeing
+
+	function readableStreamTee(stream, cloneChunks) {
+		const reader = acquireDefaultReader(stream);
+		let reading = false;
+		let readAgain = false;
+		let canceled1 = false;
+		let canceled2 = false;
+		let reason1, reason2, branch1, branch2;
+		const cancelled = defer();
+
+		const pullAlgorithm = () => {
+			if (reading) {
+				readAgain = true;
+				return promiseResolve();
+			}
+			reading = true;
+			defaultReaderRead(reader, {
+				chunk: chunk => {
+					queueMicrotask(() => {
+						readAgain = false;
+						if (!canceled1) {
+							defaultControllerEnqueue(branch1.controller, chunk);
+						}
+						if (!canceled2) {
+							defaultControllerEnqueue(branch2.controller, cloneChunks ? copyBytes(chunk) : chunk);
+						}
+						reading = false;
+						if (readAgain) {
+							pullAlgorithm();
+						}
+					});
+				},
+				close: () => {
+					reading = false;
+					if (!canceled1) {
+						defaultControllerClose(branch1.controller);
+					}
+					if (!canceled2) {
+						defaultControllerClose(branch2.controller);
+					}
+					if (!canceled1 || !canceled2) {
+						cancelled.resolve();
+					}
+				},
+				error: () => {
+					reading = false;
+				},
+			});
+			return promiseResolve();
+		};
+
+		const cancelAlgorithm = branch => reason => {
+			if (branch === 1) {
+				canceled1 = true;
+				reason1 = reason;
+			} else {
+				canceled2 = true;
+				reason2 = reason;
+			}
+			if (canceled1 && canceled2) {
+				cancelled.resolve(readableStreamCancel(stream, [reason1, reason2]));
+			}
+			return cancelled.promise;
+		};
+
+		branch1 = createReadableStream(noop, pullAlgorithm, cancelAlgorithm(1));
+		branch2 = createReadableStream(noop, pullAlgorithm, cancelAlgorithm(2));
+
+		promiseThen(reader.closed.promise, undefined, error => {
+			defaultControllerError(branch1.controller, error);
+			defaultControllerError(branch2.controller, error);
+			if (!canceled1 || !canceled2) {
+				cancelled.resolve();
+			}
+		});
+
+		return [branch1.object, branch2.object];
+	}
+
+	// Async Iteration
+
+	function getAsyncIterator(object) {
+		if (object === null || object === undefined) {
+			throw new TypeError("Object is not iterable");
+		}
+		const asyncMethod = object[Symbol.asyncIterator];
+		if (asyncMethod !== undefined && asyncMethod !== null) {
+			return asyncMethod.call(object);
+		}
+		const method = object[Symbol.iterator];
+		if (method === undefined || method === null) {
+			throw new TypeError("Object is not iterable");
+		}
+		const iterator = method.call(object);
+		return {
+			next: () => {
+				const result = iterator.next();
+				return promiseThen(promiseResolve(result.value), value => ({ value, done: Boolean(result.done) }));
+			},
+			return: iterator.return === undefined ? undefined : value => promiseResolve(iterator.return(value)),
+		};
+	}
+
+	const asyncIteratorPrototype = objectCreate(objectGetPrototypeOf(objectGetPrototypeOf(async function* () {}).prototype), {
+		next: {
+			value: function next() {
+				const iterator = slot(this, "ReadableStreamAsyncIterator");
+				const step = () => asyncIteratorNext(iterator);
+				iterator.ongoing = iterator.ongoing === undefined ? step() : promiseThen(iterator.ongoing, step, step);
+				return iterator.ongoing;
+			},
+			writable: true,
+			configurable: true,
+		},
+		return: {
+			value: function (value) {
+				const iterator = slot(this, "ReadableStreamAsyncIterator");
+				const step = () => asyncIteratorReturn(iterator, value);
+				iterator.ongoing = iterator.ongoing === undefined ? step() : promiseThen(iterator.ongoing, step, step);
+				return iterator.ongoing;
+			},
+			writable: true,
+			configurable: true,
+		},
+		[Symbol.toStringTag]: { value: "ReadableStream AsyncIterator", configurable: true },
+	});
+
+	function asyncIteratorNext(iterator) {
+		if (iterator.finished) {
+			return promiseResolve({ value: undefined, done: true });
+		}
+		const reader = iterator.reader;
+		const deferred = defer();
+		defaultReaderRead(reader, {
+			chunk: value => deferred.resolve({ value, done: false }),
+			close: () => {
+				iterator.finished = true;
+				defaultReaderRelease(reader);
+				deferred.resolve({ value: undefined, done: true });
+			},
+			error: error => {
+				iterator.finished = true;
+				defaultReaderRelease(reader);
+				deferred.reject(error);
+			},
+		});
+		return deferred.promise;
+	}
+
+	function asyncIteratorReturn(iterator, value) {
+		if (iterator.finished) {
+			return promiseResolve({ value, done: true });
+		}
+		iterator.finished = true;
+		const reader = iterator.reader;
+		if (!iterator.preventCancel) {
+			const result = readableStreamCancel(reader.stream, value);
+			defaultReaderRelease(reader);
+			return promiseThen(result, () => ({ value, done: true }));
+		}
+		defaultReaderRelease(reader);
+		return promiseResolve({ value, done: true });
+	}
+
+	// Writable Streams
+
+	const closeSentinel = {};
+
+	class WritableStream {
+		constructor(underlyingSink = undefined, strategy = {}) {
+			if (underlyingSink === null) {
+				throw new TypeError("underlyingSink must be an object");
+			}
+			const sink = underlyingSink ?? {};
+			if (sink.type !== undefined) {
+				throw new RangeError("Invalid type");
+			}
+			const stream = initialiseWritableStream(this);
+			const size = extractSizeAlgorithm(strategy);
+			const highWaterMark = extractHighWaterMark(strategy, 1);
+
+			const start = getMethod(sink, "start");
+			const write = getMethod(sink, "write");
+			const close = getMethod(sink, "close");
+			const abort = getMethod(sink, "abort");
+			setUpWritableController(
+				stream,
+				controller => start?.call(sink, controller),
+				(chunk, controller) => promiseCall(write, sink, chunk, controller),
+				() => promiseCall(close, sink),
+				reason => promiseCall(abort, sink, reason),
+				highWaterMark,
+				size,
+			);
+		}
+
+		get locked() {
+			return slot(this, "WritableStream").writer !== undefined;
+		}
+
+		abort(reason = undefined) {
+			let stream;
+			try {
+				stream = slot(this, "WritableStream");
+			} catch (error) {
+				return promiseReject(error);
+			}
+			if (stream.writer !== undefined) {
+				return promiseReject(new TypeError("Cannot abort a locked WritableStream"));
+			}
+			return writableStreamAbort(stream, reason);
+		}
+
+		close() {
+			let stream;
+			try {
+				stream = slot(this, "WritableStream");
+			} catch (error) {
+				return promiseReject(error);
+			}
+			if (stream.writer !== undefined) {
+				return promiseReject(new TypeError("Cannot close a locked WritableStream"));
+			}
+			if (closeQueuedOrInFlight(stream)) {
+				return promiseReject(new TypeError("The stream is already closing"));
+			}
+			return writableStreamClose(stream);
+		}
+
+		getWriter() {
+			return new WritableStreamDefaultWriter(this);
+		}
+	}
+
+	function initialiseWritableStream(object) {
+		const stream = {
+			brand: "WritableStream",
+			object,
+			state: "writable",
+			storedError: undefined,
+			writer: undefined,
+			controller: undefined,
+			inFlightWriteRequest: undefined,
+			closeRequest: undefined,
+			inFlightCloseRequest: undefined,
+			pendingAbortRequest: undefined,
+			writeRequests: [],
+			backpressure: false,
+		};
+		weakMapSet(slots, object, stream);
+		return stream;
+	}
+
+	function createWritableStream(startAlgorithm, writeAlgorithm, closeAlgorithm, abortAlgorithm, highWaterMark = 1, sizeAlgorithm = () => 1) {
+		const stream = initialiseWritableStream(objectCreate(WritableStream.prototype));
+		setUpWritableController(stream, startAlgorithm, writeAlgorithm, closeAlgorithm, abortAlgorithm, highWaterMark, sizeAlgorithm);
+		return stream;
+	}
+
+	function writableStreamAbort(stream, reason) {
+		if (stream.state === "closed" || stream.state === "errored") {
+			return promiseResolve();
+		}
+		stream.controller.abortController?.abort(reason);
+		const state = stream.state;
+		if (state === "closed" || state === "errored") {
+			return promiseResolve();
+		}
+		if (stream.pendingAbortRequest !== undefined) {
+			return stream.pendingAbortRequest.deferred.promise;
+		}
+
+		const wasAlreadyErroring = state === "erroring";
+		const deferred = defer();
+		stream.pendingAbortRequest = { deferred, reason: wasAlreadyErroring ? undefined : reason, wasAlreadyErroring };
+		if (!wasAlreadyErroring) {
+			writableStreamStartErroring(stream, reason);
+		}
+		return deferred.promise;
+	}
+
+	function writableStreamClose(stream) {
+		const state = stream.state;
+		if (state === "closed" || state === "errored") {
+			return promiseReject(new TypeError("The stream is closed or errored"));
+		}
+		const deferred = defer();
+		stream.closeRequest = deferred;
+		const writer = stream.writer;
+		if (writer !== undefined && stream.backpressure && state === "writable") {
+			writer.ready.resolve();
+		}
+		writableControllerClose(stream.controller);
+		return deferred.promise;
+	}
+
+	function writableStreamDealWithRejection(stream, error) {
+		if (stream.state === "writable") {
+			writableStreamStartErroring(stream, error);
+		} else {
+			writableStreamFinishErroring(stream);
+		}
+	}
+
+	function writableStreamStartErroring(stream, reason) {
+		stream.state = "erroring";
+		stream.storedError = reason;
+		const writer = stream.writer;
+		if (writer !== undefined) {
+			writerEnsureReadyPromiseRejected(writer, reason);
+		}
+		if (!hasOperationMarkedInFlight(stream) && stream.controller.started) {
+			writableStreamFinishErroring(stream);
+		}
+	}
+
+	function writableStreamFinishErroring(stream) {
+		stream.state = "errored";
+		resetQueue(stream.controller);
+		const storedError = stream.storedError;
+		for (const request of stream.writeRequests) {
+			request.reject(storedError);
+		}
+		stream.writeRequests = [];
+
+		const abortRequest = stream.pendingAbortRequest;
+		if (abortRequest === undefined) {
+			rejectCloseAndClosedPromiseIfNeeded(stream);
+			return;
+		}
+		stream.pendingAbortRequest = undefined;
+		if (abortRequest.wasAlreadyErroring) {
+			abortRequest.deferred.reject(storedError);
+			rejectCloseAndClosedPromiseIfNeeded(stream);
+			return;
+		}
+
+		const controller = stream.controller;
+		const promise = controller.abortAlgorithm !== undefined ? controller.abortAlgorithm(abortRequest.reason) : promiseResolve();
+		clearWritableAlgorithms(controller);
+		promiseThen(
+			promise,
+			() => {
+				abortRequest.deferred.resolve();
+				rejectCloseAndClosedPromiseIfNeeded(stream);
+			},
+			reason => {
+				abortRequest.deferred.reject(reason);
+				rejectCloseAndClosedPromiseIfNeeded(stream);
+			},
+		);
+	}
+
+	function finishInFlightWrite(stream) {
+		stream.inFlightWriteRequest.resolve();
+		stream.inFlightWriteRequest = undefined;
+	}
+
+	function finishInFlightWriteWithError(stream, error) {
+		stream.inFlightWriteRequest.reject(error);
+		stream.inFlightWriteRequest = undefined;
+		writableStreamDealWithRejection(stream, error);
+	}
+
+	function finishInFlightClose(stream) {
+		stream.inFlightCloseRequest.resolve();
+		stream.inFlightCloseRequest = undefined;
+		if (stream.state === "erroring") {
+			stream.storedError = undefined;
+			if (stream.pendingAbortRequest !== undefined) {
+				stream.pendingAbortRequest.deferred.resolve();
+				stream.pendingAbortRequest = undefined;
+			}
+		}
+		stream.state = "closed";
+		if (stream.writer !== undefined) {
+			stream.writer.closed.resolve();
+		}
+	}
+
+	function finishInFlightCloseWithError(stream, error) {
+		stream.inFlightCloseRequest.reject(error);
+		stream.inFlightCloseRequest = undefined;
+		if (stream.pendingAbortRequest !== undefined) {
+			stream.pendingAbortRequest.deferred.reject(error);
+			stream.pendingAbortRequest = undefined;
+		}
+		writableStreamDealWithRejection(stream, error);
+	}
+
+	function closeQueuedOrInFlight(stream) {
+		return stream.closeRequest !== undefined || stream.inFlightCloseRequest !== undefined;
+	}
+
+	function hasOperationMarkedInFlight(stream) {
+		return stream.inFlightWriteRequest !== undefined || stream.inFlightCloseRequest !== undefined;
+	}
+
+	function rejectCloseAndClosedPromiseIfNeeded(stream) {
+		if (stream.closeRequest !== undefined) {
+			stream.closeRequest.reject(stream.storedError);
+			stream.closeRequest = undefined;
+		}
+		if (stream.writer !== undefined) {
+			stream.writer.closed.reject(stream.storedError);
+			markHandled(stream.writer.closed.promise);
+		}
+	}
+
+	function writableStreamUpdateBackpressure(stream, backpressure) {
+		const writer = stream.writer;
+		if (writer !== undefined && backpressure !== stream.backpressure) {
+			if (backpressure) {
+				writer.ready = defer();
+			} else {
+				writer.ready.resolve();
+			}
+		}
+		stream.backpressure = backpressure;
+	}
+
+	// Writers
+
+	class WritableStreamDefaultWriter {
+		constructor(stream) {
+			const internal = slot(stream, "WritableStream");
+			if (internal.writer !== undefined) {
+				throw new TypeError("WritableStream is locked");
+			}
+			const writer = { brand: "WritableStreamDefaultWriter", object: this, stream: internal, ready: defer(), closed: defer() };
+			weakMapSet(slots, this, writer);
+			internal.writer = writer;
+
+			const state = internal.state;
+			if (state === "writable") {
+				if (closeQueuedOrInFlight(internal) || !internal.backpressure) {
+					writer.ready.resolve();
+				}
+			} else if (state === "erroring") {
+				writer.ready.reject(internal.storedError);
+				markHandled(writer.ready.promise);
+			} else if (state === "closed") {
+				writer.ready.resolve();
+				writer.closed.resolve();
+			} else {
+				writer.ready.reject(internal.storedError);
+				markHandled(writer.ready.promise);
+				writer.closed.reject(internal.storedError);
+				markHandled(writer.closed.promise);
+			}
+		}
+
+		get closed() {
+			return slot(this, "WritableStreamDefaultWriter").closed.promise;
+		}
+
+		get desiredSize() {
+			const writer = slot(this, "WritableStreamDefaultWriter");
+			if (writer.stream === undefined) {
+				throw new TypeError("Writer has been released");
+			}
+			const state = writer.stream.state;
+			if (state === "errored" || state === "erroring") {
+				return null;
+			}
+			if (state === "closed") {
+				return 0;
+			}
+			return writableControllerGetDesiredSize(writer.stream.controller);
+		}
+
+		get ready() {
+			return slot(this, "WritableStreamDefaultWriter").ready.promise;
+		}
+
+		abort(reason = undefined) {
+			const writer = slot(this, "WritableStreamDefaultWriter");
+			if (writer.stream === undefined) {
+				return promiseReject(new TypeError("Writer has been released"));
+			}
+			return writableStreamAbort(writer.stream, reason);
+		}
+
+		close() {
+			const writer = slot(this, "WritableStreamDefaultWriter");
+			const stream = writer.stream;
+			if (stream === undefined) {
+				return promiseReject(new TypeError("Writer has been released"));
+			}
+			if (closeQueuedOrInFlight(stream)) {
+				return promiseReject(new TypeError("The stream is already closing"));
+			}
+			return writableStreamClose(stream);
+		}
+
+		releaseLock() {
+			const writer = slot(this, "WritableStreamDefaultWriter");
+			if (writer.stream !== undefined) {
+				writerRelease(writer);
+			}
+		}
+
+		write(chunk = undefined) {
+			const writer = slot(this, "WritableStreamDefaultWriter");
+			if (writer.stream === undefined) {
+				return promiseReject(new TypeError("Writer has been released"));
+			}
+			return writerWrite(writer, chunk);
+		}
+	}
+
+	function acquireWriter(stream) {
+		return weakMapGet(slots, new WritableStreamDefaultWriter(stream.object));
+	}
+
+	function writerEnsureReadyPromiseRejected(writer, error) {
+		if (writer.ready.settled) {
+			writer.ready = defer();
+		}
+		writer.ready.reject(error);
+		markHandled(writer.ready.promise);
+	}
+
+	function writerEnsureClosedPromiseRejected(writer, error) {
+		if (writer.closed.settled) {
+			writer.closed = defer();
+		}
+		writer.closed.reject(error);
+		markHandled(writer.closed.promise);
+	}
+
+	function writerRelease(writer) {
+		const error = new TypeError("Writer has been released");
+		writerEnsureReadyPromiseRejected(writer, error);
+		writerEnsureClosedPromiseRejected(writer, error);
+		writer.stream.writer = undefined;
+		writer.stream = undefined;
+	}
+
+	function writerWrite(writer, chunk) {
+		const stream = writer.stream;
+		const controller = stream.controller;
+		const chunkSize = writableControllerGetChunkSize(controller, chunk);
+		if (stream !== writer.stream) {
+			return promiseReject(new TypeError("Writer has been released"));
+		}
+		const state = stream.state;
+		if (state === "errored" || state === "erroring") {
+			return promiseReject(stream.storedError);
+		}
+		if (closeQueuedOrInFlight(stream) || state === "closed") {
+			return promiseReject(new TypeError("The stream is closing or closed"));
+		}
+
+		const deferred = defer();
+		stream.writeRequests.push(deferred);
+		writableControllerWrite(controller, chunk, chunkSize);
+		return deferred.promise;
+	}
+
+	function writerCloseWithErrorPropagation(writer) {
+		const stream = writer.stream;
+		const state = stream.state;
+		if (closeQueuedOrInFlight(stream) || state === "closed") {
+			return promiseResolve();
+		}
+		if (state === "errored") {
+			return promiseReject(stream.storedError);
+		}
+		return writableStreamClose(stream);
+	}
+
+	// Writable Stream Controllers
+
+	class WritableStreamDefaultController {
+		constructor(key) {
+			checkConstructor(key);
+		}
+
+		get signal() {
+			return slot(this, "WritableStreamDefaultController").abortController?.signal;
+		}
+
+		error(error = undefined) {
+			const controller = slot(this, "WritableStreamDefaultController");
+			if (controller.stream.state === "writable") {
+				writableControllerError(controller, error);
+			}
+		}
+	}
+
+	function setUpWritableController(stream, startAlgorithm, writeAlgorithm, closeAlgorithm, abortAlgorithm, highWaterMark, sizeAlgorithm) {
+		const object = new WritableStreamDefaultController(token);
+		const controller = {
+			brand: "WritableStreamDefaultController",
+			object,
+			stream,
+			queue: [],
+			queueTotalSize: 0,
+			started: false,
+			strategySize: sizeAlgorithm,
+			strategyHighWaterMark: highWaterMark,
+			writeAlgorithm,
+			closeAlgorithm,
+			abortAlgorithm,
+			abortController: typeof AbortController === "function" ? new AbortController() : undefined,
+		};
+		weakMapSet(slots, object, controller);
+		stream.controller = controller;
+		writableStreamUpdateBackpressure(stream, writableControllerGetDesiredSize(controller) <= 0);
+
+		promiseThen(
+			promiseResolve(startAlgorithm(object)),
+			() => {
+				controller.started = true;
+				writableControllerAdvanceQueueIfNeeded(controller);
+			},
+			error => {
+				controller.started = true;
+				writableStreamDealWithRejection(stream, error);
+			},
+		);
+	}
+
+	function clearWritableAlgorithms(controller) {
+		controller.writeAlgorithm = undefined;
+		controller.closeAlgorithm = undefined;
+		controller.abortAlgorithm = undefined;
+		controller.strategySize = undefined;
+	}
+
+	function writableControllerGetDesiredSize(controller) {
+		return controller.strategyHighWaterMark - controller.queueTotalSize;
+	}
+
+	function writableControllerGetChunkSize(controller, chunk) {
+		if (controller.strategySize === undefined) {
+			return 1;
+		}
+		try {
+			return controller.strategySize(chunk);
+		} catch (error) {
+			writableControllerErrorIfNeeded(controller, error);
+			return 1;
+		}
+	}
+
+	function writableControllerWrite(controller, chunk, chunkSize) {
+		try {
+			enqueueValueWithSize(controller, chunk, chunkSize);
+		} catch (error) {
+			writableControllerErrorIfNeeded(controller, error);
+			return;
+		}
+		const stream = controller.stream;
+		if (!closeQueuedOrInFlight(stream) && stream.state === "writable") {
+			writableStreamUpdateBackpressure(stream, writableControllerGetDesiredSize(controller) <= 0);
+		}
+		writableControllerAdvanceQueueIfNeeded(controller);
+	}
+
+	function writableControllerClose(controller) {
+		enqueueValueWithSize(controller, closeSentinel, 0);
+		writableControllerAdvanceQueueIfNeeded(controller);
+	}
+
+	function writableControllerAdvanceQueueIfNeeded(controller) {
+		const stream = controller.stream;
+		if (!controller.started || stream.inFlightWriteRequest !== undefined) {
+			return;
+		}
+		if (stream.state === "erroring") {
+			writableStreamFinishErroring(stream);
+			return;
+		}
+		if (controller.queue.length === 0) {
+			return;
+		}
+
+		const value = controller.queue[0].value;
+		if (value === closeSentinel) {
+			stream.inFlightCloseRequest = stream.closeRequest;
+			stream.closeRequest = undefined;
+			dequeueValue(controller);
+			const promise = controller.closeAlgorithm();
+			clearWritableAlgorithms(controller);
+			promiseThen(
+				promise,
+				() => finishInFlightClose(stream),
+				error => finishInFlightCloseWithError(stream, error),
+			);
+		} else {
+			stream.inFlightWriteRequest = stream.writeRequests.shift();
+			promiseThen(
+				controller.writeAlgorithm(value, controller.object),
+				() => {
+					finishInFlightWrite(stream);
+					dequeueValue(controller);
+					if (!closeQueuedOrInFlight(stream) && stream.state === "writable") {
+						writableStreamUpdateBackpressure(stream, writableControllerGetDesiredSize(controller) <= 0);
+					}
+					writableControllerAdvanceQueueIfNeeded(controller);
+				},
+				error => {
+					if (stream.state === "writable") {
+						clearWritableAlgorithms(controller);
+					}
+					finishInFlightWriteWithError(stream, error);
+				},
+			);
+		}
+	}
+
+	function writableControllerErrorIfNeeded(controller, error) {
+		if (controller.stream.state === "writable") {
+			writableControllerError(controller, error);
+		}
+	}
+
+	function writableControllerError(controller, error) {
+		clearWritableAlgorithms(controller);
+		writableStreamStartErroring(controller.stream, error);
+	}
+
+	// Transform Streams
+
+	class TransformStream {
+		constructor(transformer = undefined, writableStrategy = {}, readableStrategy = {}) {
+			if (transformer === null) {
+				throw new TypeError("transformer must be an object");
+			}
+			const transform = transformer ?? {};
+			if (transform.readableType !== undefined) {
+				throw new RangeError("Invalid readableType");
+			}
+			if (transform.writableType !== undefined) {
+				throw new RangeError("Invalid writableType");
+			}
+
+			const readableHighWaterMark = extractHighWaterMark(readableStrategy, 0);
+			const readableSize = extractSizeAlgorithm(readableStrategy);
+			const writableHighWaterMark = extractHighWaterMark(writableStrategy, 1);
+			const writableSize = extractSizeAlgorithm(writableStrategy);
+
+			const start = defer();
+			const stream = initialiseTransformStream(this, start.promise, writableHighWaterMark, writableSize, readableHighWaterMark, readableSize);
+			setUpTransformControllerFromTransformer(stream, transform);
+			const startMethod = getMethod(transform, "start");
+			start.resolve(startMethod === undefined ? undefined : startMethod.call(transform, stream.controller.object));
+		}
+
+		get readable() {
+			return slot(this, "TransformStream").readable.object;
+		}
+
+		get writable() {
+			return slot(this, "TransformStream").writable.object;
+		}
+	}
+
+	function initialiseTransformStream(object, startPromise, writableHighWaterMark, writableSize, readableHighWaterMark, readableSize) {
+		const stream = {
+			brand: "TransformStream",
+			object,
+			readable: undefined,
+			writable: undefined,
+			backpressure: undefined,
+			backpressureChange: undefined,
+			controller: undefined,
+		};
+		weakMapSet(slots, object, stream);
+
+		const startAlgorithm = () => startPromise;
+		stream.writable = createWritableStream(
+			startAlgorithm,
+			chunk => transformSinkWrite(stream, chunk),
+			() => transformSinkClose(stream),
+			reason => transformSinkAbort(stream, reason),
+			writableHighWaterMark,
+			writableSize,
+		);
+		stream.readable = createReadableStream(
+			startAlgorithm,
+			() => transformSourcePull(stream),
+			reason => transformSourceCancel(stream, reason),
+			readableHighWaterMark,
+			readableSize,
+		);
+		transformSetBackpressure(stream, true);
+		return stream;
+	}
+
+	class TransformStreamDefaultController {
+		constructor(key) {
+			checkConstructor(key);
+		}
+
+		get desiredSize() {
+			const controller = slot(this, "TransformStreamDefaultController");
+			return defaultControllerGetDesiredSize(controller.stream.readable.controller);
+		}
+
+		enqueue(chunk = undefined) {
+			transformControllerEnqueue(slot(this, "TransformStreamDefaultController"), chunk);
+		}
+
+		error(reason = undefined) {
+			transformStreamError(slot(this, "TransformStreamDefaultController").stream, reason);
+		}
+
+		terminate() {
+			const stream = slot(this, "TransformStreamDefaultController").stream;
+			defaultControllerClose(stream.readable.controller);
+			transformErrorWritableAndUnblockWrite(stream, new TypeError("The TransformStream has been terminated"));
+		}
+	}
+
+	function setUpTransformControllerFromTransformer(stream, transformer) {
+		const object = new TransformStreamDefaultController(token);
+		const transform = getMethod(transformer, "transform");
+		const flush = getMethod(transformer, "flush");
+		const cancel = getMethod(transformer, "cancel");
+
+		const controller = {
+			brand: "TransformStreamDefaultController",
+			object,
+			stream,
+			transformAlgorithm:
+				transform !== undefined
+					? chunk => promiseCall(transform, transformer, chunk, object)
+					: chunk => {
+							try {
+								transformControllerEnqueue(controller, chunk);
+								return promiseResolve();
+							} catch (error) {
+								return promiseReject(error);
+							}
+						},
+			flushAlgorithm: () => promiseCall(flush, transformer, object),
+			cancelAlgorithm: reason => promiseCall(cancel, transformer, reason),
+			finishPromise: undefined,
+		};
+		weakMapSet(slots, object, controller);
+		stream.controller = controller;
+	}
+
+	function clearTransformAlgorithms(controller) {
+		controller.transformAlgorithm = undefined;
+		controller.flushAlgorithm = undefined;
+		controller.cancelAlgorithm = undefined;
+	}
+
+	function transformControllerEnqueue(controller, chunk) {
+		const stream = controller.stream;
+		const readableController = stream.readable.controller;
+		if (!defaultControllerCanCloseOrEnqueue(readableController)) {
+			throw new TypeError("The readable side of the TransformStream cannot be enqueued to");
+		}
+		try {
+			defaultControllerEnqueue(readableController, chunk);
+		} catch (error) {
+			transformErrorWritableAndUnblockWrite(stream, error);
+			throw stream.readable.storedError;
+		}
+		if (defaultControllerHasBackpressure(readableController) !== stream.backpressure) {
+			transformSetBackpressure(stream, true);
+		}
+	}
+
+	function transformStreamError(stream, error) {
+		defaultControllerError(stream.readable.controller, error);
+		transformErrorWritableAndUnblockWrite(stream, error);
+	}
+
+	function transformErrorWritableAndUnblockWrite(stream, error) {
+		clearTransformAlgorithms(stream.controller);
+		writableControllerErrorIfNeeded(stream.writable.controller, error);
+		if (stream.backpressure) {
+			transformSetBackpressure(stream, false);
+		}
+	}
+
+	function transformSetBackpressure(stream, backpressure) {
+		if (stream.backpressureChange !== undefined) {
+			stream.backpressureChange.resolve();
+		}
+		stream.backpressureChange = defer();
+		stream.backpressure = backpressure;
+	}
+
+	function transformPerformTransform(controller, chunk) {
+		return promiseThen(controller.transformAlgorithm(chunk), undefined, error => {
+			transformStreamError(controller.stream, error);
+			throw error;
+		});
+	}
+
+	function transformSinkWrite(stream, chunk) {
+		const controller = stream.controller;
+		if (stream.backpressure) {
+			return promiseThen(stream.backpressureChange.promise, () => {
+				if (stream.writable.state === "erroring") {
+					throw stream.writable.storedError;
+				}
+				return transformPerformTransform(controller, chunk);
+			});
+		}
+		return transformPerformTransform(controller, chunk);
+	}
+
+	function transformFinish(controller, algorithm, onFulfilled, onRejected) {
+		if (controller.finishPromise !== undefined) {
+			return controller.finishPromise.promise;
+		}
+		const finish = defer();
+		controller.finishPromise = finish;
+		const promise = algorithm();
+		clearTransformAlgorithms(controller);
+		promiseThen(
+			promise,
+			() => onFulfilled(finish),
+			error => {
+				onRejected(error);
+				finish.reject(error);
+			},
+		);
+		return finish.promise;
+	}
+
+	function transformSinkAbort(stream, reason) {
+		const controller = stream.controller;
+		const readable = stream.readable;
+		const cancel = controller.cancelAlgorithm;
+		return transformFinish(
+			controller,
+			() => cancel(reason),
+			finish => {
+				if (readable.state === "errored") {
+					finish.reject(readable.storedError);
+				} else {
+					defaultControllerError(readable.controller, reason);
+					finish.resolve();
+				}
+			},
+			error => defaultControllerError(readable.controller, error),
+		);
+	}
+
+	function transformSinkClose(stream) {
+		const controller = stream.controller;
+		const readable = stream.readable;
+		const flush = controller.flushAlgorithm;
+		return transformFinish(
+			controller,
+			() => flush(),
+			finish => {
+				if (readable.state === "errored") {
+					finish.reject(readable.storedError);
+				} else {
+					defaultControllerClose(readable.controller);
+					finish.resolve();
+				}
+			},
+			error => defaultControllerError(readable.controller, error),
+		);
+	}
+
+	function transformSourcePull(stream) {
+		transformSetBackpressure(stream, false);
+		return stream.backpressureChange.promise;
+	}
+
+	function transformSourceCancel(stream, reason) {
+		const controller = stream.controller;
+		const writable = stream.writable;
+		const cancel = controller.cancelAlgorithm;
+		return transformFinish(
+			controller,
+			() => cancel(reason),
+			finish => {
+				if (writable.state === "errored") {
+					finish.reject(writable.storedError);
+				} else {
+					writableControllerErrorIfNeeded(writable.controller, reason);
+					if (stream.backpressure) {
+						transformSetBackpressure(stream, false);
+					}
+					finish.resolve();
+				}
+			},
+			error => {
+				writableControllerErrorIfNeeded(writable.controller, error);
+				if (stream.backpressure) {
+					transformSetBackpressure(stream, false);
+				}
+			},
+		);
+	}
+
+	// Globals
+
+	const globals = {
+		ReadableStream,
+		ReadableStreamDefaultReader,
+		ReadableStreamBYOBReader,
+		ReadableStreamDefaultController,
+		ReadableByteStreamController,
+		ReadableStreamBYOBRequest,
+		WritableStream,
+		WritableStreamDefaultWriter,
+		WritableStreamDefaultController,
+		TransformStream,
+		TransformStreamDefaultController,
+		ByteLengthQueuingStrategy,
+		CountQueuingStrategy,
+	};
+	for (const [name, value] of objectEntries(globals)) {
+		objectDefineProperty(globalThis, name, { value, writable: true, configurable: true });
+	}
+
+	// Native sources and sinks are implemented in Rust.
+	// `pull` returns a promise which resolves to a Uint8Array, or null once the source is exhausted.
+	return {
+		isReadableStream(value) {
+			return weakMapGet(slots, value)?.brand === "ReadableStream";
+		},
+
+		// Locks the stream, and returns its two branches, which each read its chunks.
+		teeStream(stream) {
+			const inner = slot(stream, "ReadableStream");
+			if (isLocked(inner)) {
+				throw new TypeError("Cannot tee a locked ReadableStream");
+			}
+			return readableStreamTee(inner, false);
+		},
+
+		// Locks the stream, and returns a function which reads its next chunk of bytes, or null once it is closed.
+		readerFromStream(stream) {
+			const reader = acquireDefaultReader(slot(stream, "ReadableStream"));
+			return () => {
+				const deferred = defer();
+				defaultReaderRead(reader, {
+					chunk: chunk => {
+						if (isArrayBufferView(chunk)) {
+							deferred.resolve(new Uint8Array(chunk.buffer, chunk.byteOffset, chunk.byteLength));
+						} else if (chunk instanceof ArrayBuffer) {
+							deferred.resolve(new Uint8Array(chunk));
+						} else {
+							const error = new TypeError("Chunk must be an ArrayBuffer or ArrayBufferView");
+							readableStreamCancel(reader.stream, error);
+							deferred.reject(error);
+						}
+					},
+					close: () => deferred.resolve(null),
+					error: error => deferred.reject(error),
+				});
+				return deferred.promise;
+			};
+		},
+
+		readableFromNative(pull, cancel) {
+			const stream = createReadableByteStream(
+				noop,
+				() =>
+					promiseThen(pull(), chunk => {
+						const controller = stream.controller;
+						if (chunk === null || chunk === undefined) {
+							byteControllerClose(controller);
+							if (controller.pendingPullIntos.length > 0) {
+								byteControllerRespond(controller, 0);
+							}
+						} else if (chunk.byteLength > 0) {
+							byteControllerEnqueue(controller, chunk);
+						}
+					}),
+				reason => {
+					cancel(reason);
+					return promiseResolve();
+				},
+			);
+			return stream.object;
+		},
+
+		writableFromNative(write, close, abort) {
+			const stream = createWritableStream(
+				noop,
+				chunk => {
+					let bytes;
+					if (isArrayBufferView(chunk)) {
+						bytes = new Uint8Array(chunk.buffer, chunk.byteOffset, chunk.byteLength);
+					} else if (chunk instanceof ArrayBuffer) {
+						bytes = new Uint8Array(chunk);
+					} else {
+						return promiseReject(new TypeError("Chunk must be an ArrayBuffer or ArrayBufferView"));
+					}
+					return write(bytes);
+				},
+				() => close(),
+				reason => {
+					abort(reason);
+					return promiseResolve();
+				},
+			);
+			return stream.object;
+		},
+
+		// `transform` and `flush` return the bytes which are produced, which are enqueued unless they are empty.
+		transformFromNative(transform, flush) {
+			const enqueue = (controller, bytes) => {
+				if (bytes.byteLength > 0) {
+					transformControllerEnqueue(controller, bytes);
+				}
+			};
+			const object = objectCreate(TransformStream.prototype);
+			const stream = initialiseTransformStream(object, promiseResolve(), 1, () => 1, 0, () => 1);
+			const controller = {
+				brand: "TransformStreamDefaultController",
+				object: new TransformStreamDefaultController(token),
+				stream,
+				transformAlgorithm: chunk => {
+					try {
+						if (isArrayBufferView(chunk)) {
+							enqueue(controller, transform(new Uint8Array(chunk.buffer, chunk.byteOffset, chunk.byteLength)));
+						} else if (chunk instanceof ArrayBuffer) {
+							enqueue(controller, transform(new Uint8Array(chunk)));
+						} else {
+							throw new TypeError("Chunk must be an ArrayBuffer or ArrayBufferView");
+						}
+						return promiseResolve();
+					} catch (error) {
+						return promiseReject(error);
+					}
+				},
+				flushAlgorithm: () => {
+					try {
+						enqueue(controller, flush());
+						return promiseResolve();
+					} catch (error) {
+						return promiseReject(error);
+					}
+				},
+				cancelAlgorithm: () => promiseResolve(),
+				finishPromise: undefined,
+			};
+			weakMapSet(slots, controller.object, controller);
+			stream.controller = controller;
+			return object;
+		},
+	};
+})();
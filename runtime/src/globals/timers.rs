@@ -10,20 +10,18 @@ use mozjs::jsapi::JSFunctionSpec;
 use mozjs::jsval::JSVal;
 
 use ion::{Context, Error, Function, Object, Result};
+use ion::functions::Rest;
 
 use crate::ContextExt;
-use crate::event_loop::macrotasks::{Macrotask, TimerMacrotask, UserMacrotask};
+use crate::event_loop::macrotasks::{ImmediateMacrotask, Macrotask, TimerMacrotask, UserMacrotask};
 
 const MINIMUM_DELAY: i32 = 1;
-const MINIMUM_DELAY_NESTED: i32 = 4;
 
-fn set_timer(cx: &Context, callback: Function, duration: Option<i32>, arguments: Vec<JSVal>, repeat: bool) -> Result<u32> {
+fn set_timer(cx: &Context, callback: Function, duration: i32, arguments: Vec<JSVal>, repeat: bool) -> Result<u32> {
 	let event_loop = unsafe { &mut (*cx.get_private().as_ptr()).event_loop };
 	if let Some(queue) = &mut event_loop.macrotasks {
-		let minimum = if queue.nesting > 5 { MINIMUM_DELAY_NESTED } else { MINIMUM_DELAY };
-
-		let duration = duration.map(|t| t.max(minimum)).unwrap_or(minimum);
-		let timer = TimerMacrotask::new(callback, arguments, repeat, Duration::milliseconds(duration as i64));
+		let duration = duration.max(MINIMUM_DELAY);
+		let timer = TimerMacrotask::new(cx, callback, arguments, repeat, Duration::milliseconds(duration as i64));
 		Ok(queue.enqueue(Macrotask::Timer(timer), None))
 	} else {
 		Err(Error::new("Macrotask Queue has not been initialised.", None))
@@ -34,7 +32,7 @@ fn clear_timer(cx: &Context, id: Option<u32>) -> Result<()> {
 	if let Some(id) = id {
 		let event_loop = unsafe { &mut (*cx.get_private().as_ptr()).event_loop };
 		if let Some(queue) = &mut event_loop.macrotasks {
-			queue.remove(id);
+			queue.clear_timer(id);
 			Ok(())
 		} else {
 			Err(Error::new("Macrotask Queue has not been initialised.", None))
@@ -45,15 +43,13 @@ fn clear_timer(cx: &Context, id: Option<u32>) -> Result<()> {
 }
 
 #[js_fn]
-fn setTimeout(cx: &Context, callback: Function, #[ion(convert = Clamp)] duration: Option<i32>, #[ion(varargs)] arguments: Vec<JSVal>) -> Result<u32> {
-	set_timer(cx, callback, duration, arguments, false)
+fn setTimeout(cx: &Context, callback: Function, #[ion(convert = Clamp, default = 0)] duration: i32, arguments: Rest<JSVal>) -> Result<u32> {
+	set_timer(cx, callback, duration, arguments.into_inner(), false)
 }
 
 #[js_fn]
-fn setInterval(
-	cx: &Context, callback: Function, #[ion(convert = Clamp)] duration: Option<i32>, #[ion(varargs)] arguments: Vec<JSVal>,
-) -> Result<u32> {
-	set_timer(cx, callback, duration, arguments, true)
+fn setInterval(cx: &Context, callback: Function, #[ion(convert = Clamp, default = 0)] duration: i32, arguments: Rest<JSVal>) -> Result<u32> {
+	set_timer(cx, callback, duration, arguments.into_inner(), true)
 }
 
 #[js_fn]
@@ -66,11 +62,57 @@ fn clearInterval(cx: &Context, #[ion(convert = EnforceRange)] id: Option<u32>) -
 	clear_timer(cx, id)
 }
 
+/// Queues a callback to run after the expired timers of the next turn of the event loop.
+#[js_fn]
+fn setImmediate(cx: &Context, callback: Function, arguments: Rest<JSVal>) -> Result<u32> {
+	let event_loop = unsafe { &mut (*cx.get_private().as_ptr()).event_loop };
+	if let Some(queue) = &mut event_loop.macrotasks {
+		Ok(queue.enqueue_immediate(ImmediateMacrotask::new(cx, callback, arguments.into_inner())))
+	} else {
+		Err(Error::new("Macrotask Queue has not been initialised.", None))
+	}
+}
+
+#[js_fn]
+fn clearImmediate(cx: &Context, #[ion(convert = EnforceRange)] id: Option<u32>) -> Result<()> {
+	let event_loop = unsafe { &mut (*cx.get_private().as_ptr()).event_loop };
+	if let Some(queue) = &mut event_loop.macrotasks {
+		if let Some(id) = id {
+			queue.clear_immediate(id);
+		}
+		Ok(())
+	} else {
+		Err(Error::new("Macrotask Queue has not been initialised.", None))
+	}
+}
+
+fn ref_timer(cx: &Context, id: u32, refed: bool) -> Result<()> {
+	let event_loop = unsafe { &mut (*cx.get_private().as_ptr()).event_loop };
+	if let Some(queue) = &mut event_loop.macrotasks {
+		queue.ref_timer(id, refed);
+		Ok(())
+	} else {
+		Err(Error::new("Macrotask Queue has not been initialised.", None))
+	}
+}
+
+/// Makes a timer keep the event loop alive while it is pending, which is the default.
+#[js_fn]
+fn refTimer(cx: &Context, #[ion(convert = EnforceRange)] id: u32) -> Result<()> {
+	ref_timer(cx, id, true)
+}
+
+/// Stops a timer from keeping the event loop alive, so that the runtime can exit while it is pending.
+#[js_fn]
+fn unrefTimer(cx: &Context, #[ion(convert = EnforceRange)] id: u32) -> Result<()> {
+	ref_timer(cx, id, false)
+}
+
 #[js_fn]
 fn queueMacrotask(cx: &Context, callback: Function) -> Result<()> {
 	let event_loop = unsafe { &mut (*cx.get_private().as_ptr()).event_loop };
 	if let Some(queue) = &mut event_loop.macrotasks {
-		queue.enqueue(Macrotask::User(UserMacrotask::new(callback)), None);
+		queue.enqueue(Macrotask::User(UserMacrotask::new(cx, callback)), None);
 		Ok(())
 	} else {
 		Err(Error::new("Macrotask Queue has not been initialised.", None))
@@ -82,6 +124,10 @@ const FUNCTIONS: &[JSFunctionSpec] = &[
 	function_spec!(setInterval, 1),
 	function_spec!(clearTimeout, 0),
 	function_spec!(clearInterval, 0),
+	function_spec!(setImmediate, 1),
+	function_spec!(clearImmediate, 0),
+	function_spec!(refTimer, 1),
+	function_spec!(unrefTimer, 1),
 	function_spec!(queueMacrotask, 1),
 	JSFunctionSpec::ZERO,
 ];
@@ -4,13 +4,10 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/.
  */
 
-use std::cmp::Ordering;
-
-use mozjs::conversions::ConversionBehavior::EnforceRange;
 use mozjs::jsapi::{Heap, JSObject};
-use url::Url;
+use url::{quirks, Url};
 
-use ion::{ClassDefinition, Context, Error, Local, Object, Result};
+use ion::{ClassDefinition, Context, Error, ErrorKind, Local, Object, Result};
 use ion::class::Reflector;
 pub use search_params::URLSearchParams;
 
@@ -34,14 +31,26 @@ pub struct URL {
 	search_params: Box<Heap<*mut JSObject>>,
 }
 
+/// Parses a URL relative to an optional base, as the [URL] constructor does.
+///
+/// Used wherever URLs are resolved within the runtime, so that fetch and the module loader agree with scripts.
+pub fn parse_url(input: &str, base: Option<&Url>) -> Result<Url> {
+	Url::options()
+		.base_url(base)
+		.parse(input)
+		.map_err(|error| Error::new(&format!("Invalid URL \"{}\": {}", input, error), ErrorKind::Type))
+}
+
+fn parse_with_base(input: &str, base: Option<&str>) -> Result<Url> {
+	let base = base.map(|base| parse_url(base, None)).transpose()?;
+	parse_url(input, base.as_ref())
+}
+
 #[js_class]
 impl URL {
 	#[ion(constructor)]
 	pub fn constructor(#[ion(this)] this: &Object, cx: &Context, input: String, base: Option<String>) -> Result<URL> {
-		let options = Url::options();
-		let base = base.as_ref().and_then(|base| Url::parse(base).ok());
-		options.base_url(base.as_ref());
-		let url = options.parse(&input).map_err(|error| Error::new(&error.to_string(), None))?;
+		let url = parse_with_base(&input, base.as_deref())?;
 
 		let search_params = Box::new(URLSearchParams::new(url.query_pairs().into_owned().collect()));
 		search_params.url.as_ref().unwrap().set(this.handle().get());
@@ -56,10 +65,25 @@ impl URL {
 
 	#[ion(name = "canParse")]
 	pub fn can_parse(input: String, base: Option<String>) -> bool {
-		let options = Url::options();
-		let base = base.as_ref().and_then(|base| Url::parse(base).ok());
-		options.base_url(base.as_ref());
-		options.parse(&input).is_ok()
+		parse_with_base(&input, base.as_deref()).is_ok()
+	}
+
+	pub fn parse(cx: &Context, input: String, base: Option<String>) -> Option<*mut JSObject> {
+		let url = parse_with_base(&input, base.as_deref()).ok()?;
+		let search_params = Box::new(URLSearchParams::new(url.query_pairs().into_owned().collect()));
+		let search_params = cx.root_object(URLSearchParams::new_object(cx, search_params));
+
+		let object = URL::new_object(
+			cx,
+			Box::new(URL {
+				reflector: Reflector::default(),
+				url,
+				search_params: Heap::boxed(search_params.get()),
+			}),
+		);
+		let search_params = URLSearchParams::get_mut_private(&mut Object::from(search_params));
+		search_params.url.as_ref().unwrap().set(object);
+		Some(object)
 	}
 
 	pub fn format(&self, options: Option<FormatOptions>) -> Result<String> {
@@ -87,152 +111,122 @@ impl URL {
 
 	#[ion(get)]
 	pub fn get_href(&self) -> String {
-		self.url.to_string()
+		String::from(quirks::href(&self.url))
 	}
 
 	#[ion(set)]
 	pub fn set_href(&mut self, input: String) -> Result<()> {
-		match Url::parse(&input) {
-			Ok(url) => {
-				let mut search_params = Object::from(unsafe { Local::from_heap(&self.search_params) });
-				let search_params = URLSearchParams::get_mut_private(&mut search_params);
-				search_params.set_pairs(url.query_pairs().into_owned().collect());
-				self.url = url;
-				Ok(())
-			}
-			Err(error) => Err(Error::new(&error.to_string(), None)),
-		}
+		self.url = parse_url(&input, None)?;
+		self.update_search_params();
+		Ok(())
+	}
+
+	#[ion(get)]
+	pub fn get_origin(&self) -> String {
+		quirks::origin(&self.url)
 	}
 
 	#[ion(get)]
 	pub fn get_protocol(&self) -> String {
-		String::from(self.url.scheme())
+		String::from(quirks::protocol(&self.url))
 	}
 
 	#[ion(set)]
-	pub fn set_protocol(&mut self, protocol: String) -> Result<()> {
-		self.url.set_scheme(&protocol).map_err(|_| Error::new("Invalid Protocol", None))
+	pub fn set_protocol(&mut self, protocol: String) {
+		let _ = quirks::set_protocol(&mut self.url, &protocol);
 	}
 
 	#[ion(get)]
-	pub fn get_host(&self) -> Option<String> {
-		self.url.host_str().map(|host| {
-			if let Some(port) = self.url.port() {
-				format!("{}:{}", host, port)
-			} else {
-				String::from(host)
-			}
-		})
+	pub fn get_username(&self) -> String {
+		String::from(quirks::username(&self.url))
 	}
 
 	#[ion(set)]
-	pub fn set_host(&mut self, host: Option<String>) -> Result<()> {
-		if let Some(host) = host {
-			let segments: Vec<&str> = host.split(':').collect();
-			let (host, port) = match segments.len().cmp(&2) {
-				Ordering::Less => Ok((segments[0], None)),
-				Ordering::Greater => Err(Error::new("Invalid Host", None)),
-				Ordering::Equal => {
-					let port = match segments[1].parse::<u16>() {
-						Ok(port) => Ok(port),
-						Err(error) => Err(Error::new(&error.to_string(), None)),
-					}?;
-					Ok((segments[0], Some(port)))
-				}
-			}?;
-
-			self.url.set_host(Some(host))?;
-
-			self.url.set_port(port).map_err(|_| Error::new("Invalid Url", None))?;
-		} else {
-			self.url.set_host(None)?;
-			self.url.set_port(None).map_err(|_| Error::new("Invalid Url", None))?;
-		}
-		Ok(())
+	pub fn set_username(&mut self, username: String) {
+		let _ = quirks::set_username(&mut self.url, &username);
 	}
 
 	#[ion(get)]
-	pub fn get_hostname(&self) -> Option<String> {
-		self.url.host_str().map(String::from)
+	pub fn get_password(&self) -> String {
+		String::from(quirks::password(&self.url))
 	}
 
 	#[ion(set)]
-	pub fn set_hostname(&mut self, hostname: Option<String>) -> Result<()> {
-		self.url
-			.set_host(hostname.as_deref())
-			.map_err(|error| Error::new(&error.to_string(), None))
+	pub fn set_password(&mut self, password: String) {
+		let _ = quirks::set_password(&mut self.url, &password);
 	}
 
 	#[ion(get)]
-	pub fn get_origin(&self) -> String {
-		self.url.origin().ascii_serialization()
-	}
-
-	#[ion(get)]
-	pub fn get_port(&self) -> Option<u16> {
-		self.url.port_or_known_default()
+	pub fn get_host(&self) -> String {
+		String::from(quirks::host(&self.url))
 	}
 
 	#[ion(set)]
-	pub fn set_port(&mut self, #[ion(convert = EnforceRange)] port: Option<u16>) -> Result<()> {
-		self.url.set_port(port).map_err(|_| Error::new("Invalid Port", None))
+	pub fn set_host(&mut self, host: String) {
+		let _ = quirks::set_host(&mut self.url, &host);
 	}
 
 	#[ion(get)]
-	pub fn get_pathname(&self) -> String {
-		String::from(self.url.path())
+	pub fn get_hostname(&self) -> String {
+		String::from(quirks::hostname(&self.url))
 	}
 
 	#[ion(set)]
-	pub fn set_pathname(&mut self, path: String) -> Result<()> {
-		self.url.set_path(&path);
-		Ok(())
+	pub fn set_hostname(&mut self, hostname: String) {
+		let _ = quirks::set_hostname(&mut self.url, &hostname);
 	}
 
 	#[ion(get)]
-	pub fn get_username(&self) -> String {
-		String::from(self.url.username())
+	pub fn get_port(&self) -> String {
+		String::from(quirks::port(&self.url))
 	}
 
 	#[ion(set)]
-	pub fn set_username(&mut self, username: String) -> Result<()> {
-		self.url.set_username(&username).map_err(|_| Error::new("Invalid Url", None))
+	pub fn set_port(&mut self, port: String) {
+		let _ = quirks::set_port(&mut self.url, &port);
 	}
 
 	#[ion(get)]
-	pub fn get_password(&self) -> Option<String> {
-		self.url.password().map(String::from)
+	pub fn get_pathname(&self) -> String {
+		String::from(quirks::pathname(&self.url))
 	}
 
 	#[ion(set)]
-	pub fn set_password(&mut self, password: Option<String>) -> Result<()> {
-		self.url.set_password(password.as_deref()).map_err(|_| Error::new("Invalid Url", None))
+	pub fn set_pathname(&mut self, path: String) {
+		quirks::set_pathname(&mut self.url, &path);
 	}
 
 	#[ion(get)]
-	pub fn get_search(&self) -> Option<String> {
-		self.url.query().map(String::from)
+	pub fn get_search(&self) -> String {
+		String::from(quirks::search(&self.url))
 	}
 
 	#[ion(set)]
-	pub fn set_search(&mut self, search: Option<String>) {
-		self.url.set_query(search.as_deref());
+	pub fn set_search(&mut self, search: String) {
+		quirks::set_search(&mut self.url, &search);
+		self.update_search_params();
 	}
 
 	#[ion(get)]
-	pub fn get_hash(&self) -> Option<String> {
-		self.url.fragment().map(String::from)
+	pub fn get_hash(&self) -> String {
+		String::from(quirks::hash(&self.url))
 	}
 
 	#[ion(set)]
-	pub fn set_hash(&mut self, hash: Option<String>) {
-		self.url.set_fragment(hash.as_deref());
+	pub fn set_hash(&mut self, hash: String) {
+		quirks::set_hash(&mut self.url, &hash);
 	}
 
 	#[ion(get)]
 	pub fn get_search_params(&self) -> *mut JSObject {
 		self.search_params.get()
 	}
+
+	fn update_search_params(&mut self) {
+		let mut search_params = Object::from(unsafe { Local::from_heap(&self.search_params) });
+		let search_params = URLSearchParams::get_mut_private(&mut search_params);
+		search_params.set_pairs(self.url.query_pairs().into_owned().collect());
+	}
 }
 
 pub fn define(cx: &Context, global: &mut Object) -> bool {
@@ -6,7 +6,7 @@
 use form_urlencoded::{parse, Serializer};
 use mozjs::jsapi::{Heap, JSObject};
 
-use ion::{ClassDefinition, Context, Error, ErrorKind, JSIterator, Local, Object, OwnedKey, Result, Value};
+use ion::{ClassDefinition, Context, Error, ErrorKind, Function, JSIterator, Local, Object, OwnedKey, Result, ResultExc, Value};
 use ion::class::Reflector;
 use ion::conversions::{FromValue, ToValue};
 use ion::symbol::WellKnownSymbolCode;
@@ -95,7 +95,7 @@ impl URLSearchParams {
 
 	pub fn delete(&mut self, name: String, value: Option<String>) {
 		if let Some(value) = value {
-			self.pairs.retain(|(k, v)| k != &name && v != &value)
+			self.pairs.retain(|(k, v)| k != &name || v != &value)
 		} else {
 			self.pairs.retain(|(k, _)| k != &name)
 		}
@@ -106,7 +106,7 @@ impl URLSearchParams {
 		self.pairs.iter().find(|(k, _)| k == &key).map(|(_, v)| v.clone())
 	}
 
-	#[ion(name = "get_all")]
+	#[ion(name = "getAll")]
 	pub fn get_all(&self, key: String) -> Vec<String> {
 		self.pairs.iter().filter(|(k, _)| k == &key).map(|(_, v)| v.clone()).collect()
 	}
@@ -167,24 +167,66 @@ impl URLSearchParams {
 		}
 	}
 
-	#[ion(name = WellKnownSymbolCode::Iterator)]
+	#[ion(name = "forEach")]
+	pub fn for_each(cx: &Context, #[ion(this)] this: &Object, callback: Function, this_arg: Option<Object>) -> ResultExc<()> {
+		let this_arg = this_arg.unwrap_or_else(|| Object::null(cx));
+		let mut index = 0;
+		// Pairs are read by index on each iteration, as the callback may modify them.
+		while let Some((key, value)) = URLSearchParams::get_private(this).pairs.get(index).cloned() {
+			let args = [Value::string(cx, &value), Value::string(cx, &key), this.as_value(cx)];
+			callback.call(cx, &this_arg, &args).map_err(|report| {
+				report
+					.map(|report| report.exception)
+					.unwrap_or_else(|| Error::new("Callback threw an uncatchable exception", None).into())
+			})?;
+			index += 1;
+		}
+		Ok(())
+	}
+
+	pub fn keys(cx: &Context, #[ion(this)] this: &Object) -> ion::Iterator {
+		SearchParamsIterator::new(cx, this, IteratorKind::Keys)
+	}
+
+	pub fn values(cx: &Context, #[ion(this)] this: &Object) -> ion::Iterator {
+		SearchParamsIterator::new(cx, this, IteratorKind::Values)
+	}
+
+	#[ion(name = WellKnownSymbolCode::Iterator, alias = ["entries"])]
 	pub fn iterator(cx: &Context, #[ion(this)] this: &Object) -> ion::Iterator {
-		let thisv = this.as_value(cx);
-		ion::Iterator::new(SearchParamsIterator::default(), &thisv)
+		SearchParamsIterator::new(cx, this, IteratorKind::Entries)
 	}
 }
 
-#[derive(Default)]
-pub struct SearchParamsIterator(usize);
+#[derive(Clone, Copy)]
+enum IteratorKind {
+	Keys,
+	Values,
+	Entries,
+}
+
+pub struct SearchParamsIterator {
+	index: usize,
+	kind: IteratorKind,
+}
+
+impl SearchParamsIterator {
+	fn new(cx: &Context, search_params: &Object, kind: IteratorKind) -> ion::Iterator {
+		let thisv = search_params.as_value(cx);
+		ion::Iterator::new(SearchParamsIterator { index: 0, kind }, &thisv)
+	}
+}
 
 impl JSIterator for SearchParamsIterator {
 	fn next_value<'cx>(&mut self, cx: &'cx Context, private: &Value<'cx>) -> Option<Value<'cx>> {
 		let object = private.to_object(cx);
 		let search_params = URLSearchParams::get_private(&object);
-		let pair = search_params.pairs.get(self.0);
-		pair.map(move |(k, v)| {
-			self.0 += 1;
-			[k, v].as_value(cx)
+		let (key, value) = search_params.pairs.get(self.index)?;
+		self.index += 1;
+		Some(match self.kind {
+			IteratorKind::Keys => key.as_value(cx),
+			IteratorKind::Values => value.as_value(cx),
+			IteratorKind::Entries => [key, value].as_value(cx),
 		})
 	}
 }
@@ -0,0 +1,257 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::cell::RefCell;
+use std::fs::read_to_string;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+
+use dunce::canonicalize;
+use futures::future::{Either, select};
+use mozjs::jsapi::{JS_AddInterruptCallback, JS_RequestInterruptCallback, JSContext, JSFunctionSpec};
+use mozjs::rust::{JSEngineHandle, Runtime as RustRuntime};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+use tokio::sync::Notify;
+use tokio::task::LocalSet;
+use url::Url;
+
+use ion::{ClassDefinition, Context, Error, ErrorReport, Object, Result, ResultExc, ThrowException, Value};
+use ion::module::Module;
+
+use crate::{ContextExt, RuntimeBuilder};
+use crate::clone::StructuredClone;
+use crate::event_loop::messages::{Message, Port};
+use crate::globals::event::EventTarget;
+use crate::modules::Loader;
+use crate::options::ContextOptions;
+use crate::permissions::check_read;
+
+thread_local! {
+	static PARENT: RefCell<Option<(UnboundedSender<Message>, Arc<AtomicBool>, Arc<Notify>)>> = RefCell::new(None);
+}
+
+/// Options used to create the runtimes of workers, inherited from the runtime which creates them.
+#[derive(Clone)]
+pub(crate) struct WorkerOptions {
+	pub(crate) engine: JSEngineHandle,
+	pub(crate) options: ContextOptions,
+	pub(crate) standard_modules: Option<fn(&Context, &mut Object) -> bool>,
+}
+
+/// Checks if the current thread is running a worker.
+pub fn is_worker() -> bool {
+	PARENT.with(|parent| parent.borrow().is_some())
+}
+
+#[js_class]
+pub struct Worker {
+	event_target: EventTarget,
+	#[ion(no_trace)]
+	sender: UnboundedSender<Message>,
+	#[ion(no_trace)]
+	closed: Arc<AtomicBool>,
+	#[ion(no_trace)]
+	notify: Arc<Notify>,
+	#[ion(no_trace)]
+	context: Arc<Mutex<Option<usize>>>,
+}
+
+#[js_class]
+impl Worker {
+	#[ion(constructor)]
+	pub fn constructor(#[ion(this)] this: &Object, cx: &Context, specifier: String) -> Result<Worker> {
+		let private = unsafe { &mut *cx.get_private().as_ptr() };
+		let Some(options) = private.workers.clone() else {
+			return Err(Error::new("Workers are not enabled in this runtime", None));
+		};
+
+		let path = match Url::parse(&specifier) {
+			Ok(url) => url
+				.to_file_path()
+				.map_err(|_| Error::new(&format!("Worker module must be a local file: {}", specifier), None))?,
+			Err(_) => PathBuf::from(&specifier),
+		};
+		let path = canonicalize(&path).map_err(|_| Error::new(&format!("Unable to find worker module: {}", specifier), None))?;
+		check_read(&path.to_string_lossy())?;
+
+		let (sender, worker_receiver) = unbounded_channel();
+		let (worker_sender, receiver) = unbounded_channel();
+		let closed = Arc::new(AtomicBool::new(false));
+		let notify = Arc::new(Notify::new());
+		let context = Arc::new(Mutex::new(None));
+
+		let (worker_closed, worker_notify, worker_context) = (closed.clone(), notify.clone(), context.clone());
+		thread::Builder::new()
+			.name(format!("Worker {}", path.display()))
+			.spawn(move || {
+				run_worker(
+					options,
+					path,
+					worker_receiver,
+					worker_sender,
+					worker_closed,
+					worker_notify,
+					worker_context,
+				)
+			})
+			.map_err(|error| Error::new(&format!("Unable to start worker: {}", error), None))?;
+
+		private.event_loop.messages.enqueue(Port::new(cx, this, receiver, closed.clone(), false));
+		Ok(Worker {
+			event_target: EventTarget::default(),
+			sender,
+			closed,
+			notify,
+			context,
+		})
+	}
+
+	#[ion(name = "postMessage")]
+	pub fn post_message(&self, cx: &Context, message: Value, transfer: Option<Vec<Object>>) -> ResultExc<()> {
+		let data = StructuredClone::serialise(cx, &message, &transfer.unwrap_or_default())?;
+		let _ = self.sender.send(Message::Data(data));
+		Ok(())
+	}
+
+	/// Terminates the worker immediately, interrupting any script it is running.
+	pub fn terminate(&self) {
+		terminate(&self.closed, &self.notify, &self.context);
+	}
+}
+
+fn terminate(closed: &AtomicBool, notify: &Notify, context: &Mutex<Option<usize>>) {
+	closed.store(true, Ordering::SeqCst);
+	notify.notify_one();
+	if let Some(cx) = *context.lock().unwrap() {
+		unsafe {
+			JS_RequestInterruptCallback(cx as *mut JSContext);
+		}
+	}
+}
+
+#[js_fn]
+fn postMessage(cx: &Context, message: Value, transfer: Option<Vec<Object>>) -> ResultExc<()> {
+	let data = StructuredClone::serialise(cx, &message, &transfer.unwrap_or_default())?;
+	PARENT.with(|parent| {
+		if let Some((sender, _, _)) = &*parent.borrow() {
+			let _ = sender.send(Message::Data(data));
+		}
+	});
+	Ok(())
+}
+
+#[js_fn]
+fn close() {
+	PARENT.with(|parent| {
+		if let Some((_, closed, notify)) = &*parent.borrow() {
+			closed.store(true, Ordering::SeqCst);
+			notify.notify_one();
+		}
+	});
+}
+
+const FUNCTIONS: &[JSFunctionSpec] = &[function_spec!(postMessage, 1), function_spec!(close, 0), JSFunctionSpec::ZERO];
+
+unsafe extern "C" fn interrupt_callback(_: *mut JSContext) -> bool {
+	PARENT.with(|parent| {
+		parent
+			.borrow()
+			.as_ref()
+			.map(|(_, closed, _)| !closed.load(Ordering::SeqCst))
+			.unwrap_or(true)
+	})
+}
+
+fn run_worker(
+	options: WorkerOptions, path: PathBuf, receiver: UnboundedReceiver<Message>, sender: UnboundedSender<Message>, closed: Arc<AtomicBool>,
+	notify: Arc<Notify>, context: Arc<Mutex<Option<usize>>>,
+) {
+	let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+	let local = LocalSet::new();
+
+	local.block_on(&runtime, async {
+		let rt = RustRuntime::new(options.engine.clone());
+		let cx = &mut Context::from_runtime(&rt);
+		let rt = RuntimeBuilder::<_, ()>::new()
+			.microtask_queue()
+			.macrotask_queue()
+			.modules(Loader::default())
+			.options(options.options)
+			.build(cx);
+		let cx = rt.cx();
+
+		PARENT.with(|parent| *parent.borrow_mut() = Some((sender.clone(), closed.clone(), notify.clone())));
+		unsafe {
+			(*cx.get_private().as_ptr()).workers = Some(options.clone());
+			JS_AddInterruptCallback(cx.as_ptr(), Some(interrupt_callback));
+		}
+		*context.lock().unwrap() = Some(cx.as_ptr() as usize);
+
+		let mut global = Object::global(cx);
+		let event_loop = unsafe { &mut (*cx.get_private().as_ptr()).event_loop };
+		event_loop.messages.enqueue(Port::new(cx, &global, receiver, closed.clone(), true));
+		let initialised = unsafe { global.define_methods(cx, FUNCTIONS) }
+			&& Worker::init_class(cx, &mut global).0
+			&& options.standard_modules.map(|init| init(cx, &mut global)).unwrap_or(true);
+
+		let result = if initialised {
+			evaluate(cx, &path)
+		} else {
+			Err(ErrorReport::new_with_exception_stack(cx))
+		};
+		let result = match result {
+			Ok(()) => {
+				// The worker is notified when it is closed, which stores a permit if it is not yet waiting.
+				let terminated = async {
+					while !closed.load(Ordering::SeqCst) {
+						notify.notified().await;
+					}
+				};
+				match select(Box::pin(rt.run_event_loop()), Box::pin(terminated)).await {
+					Either::Left((result, _)) => result,
+					Either::Right(_) => Ok(()),
+				}
+			}
+			Err(report) => Err(report),
+		};
+
+		// Errors caused by termination are not reported.
+		if let Err(report) = result {
+			if !closed.load(Ordering::SeqCst) {
+				let error = report
+					.map(|report| report.format(cx))
+					.unwrap_or_else(|| String::from("Uncatchable Error"));
+				let _ = sender.send(Message::Error(error));
+			}
+		}
+
+		*context.lock().unwrap() = None;
+		PARENT.with(|parent| *parent.borrow_mut() = None);
+	});
+
+	// The parent waits for the worker to drop its sender, so the engine handle must be released first.
+	drop(options);
+	drop(sender);
+}
+
+fn evaluate(cx: &Context, path: &Path) -> std::result::Result<(), Option<ErrorReport>> {
+	let script = read_to_string(path).map_err(|error| {
+		Error::new(&format!("Unable to read worker module: {}", path.display()), None)
+			.with_cause(error)
+			.throw(cx);
+		ErrorReport::new(cx)
+	})?;
+	let filename = path.file_name().and_then(|name| name.to_str()).unwrap_or_default();
+	Module::compile(cx, filename, Some(path), &script)
+		.map(|_| ())
+		.map_err(|error| error.report)
+}
+
+pub fn define(cx: &Context, global: &mut Object) -> bool {
+	Worker::init_class(cx, global).0
+}
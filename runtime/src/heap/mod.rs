@@ -0,0 +1,83 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::fs::write;
+use std::io;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use mozjs::jsapi::{GCReason, JS_DefineDebuggerObject, JS_GC, JSAutoRealm, OnNewGlobalHookOption};
+use mozjs::rust::{RealmOptions, SIMPLE_GLOBAL_CLASS};
+
+use ion::{Context, ErrorReport, Function, Object};
+use ion::conversions::{FromValue, ToValue};
+use ion::objects::new_global;
+use ion::script::Script;
+
+use crate::ContextExt;
+
+const SNAPSHOT_SOURCE: &str = include_str!("snapshot.js");
+
+/// Creates a global with the [Debugger API](https://firefox-source-docs.mozilla.org/js/Debugger/) defined.
+/// The global is invisible to debuggers, so that it cannot debug itself.
+pub(crate) fn debugger_global<'cx>(cx: &'cx Context) -> Option<Object<'cx>> {
+	let mut realm_options = RealmOptions::default();
+	realm_options.creationOptions_.invisibleToDebugger_ = true;
+	let global = new_global(
+		cx,
+		&SIMPLE_GLOBAL_CLASS,
+		None,
+		OnNewGlobalHookOption::DontFireOnNewGlobalHook,
+		realm_options,
+	);
+
+	let _realm = JSAutoRealm::new(cx.as_ptr(), global.handle().get());
+	unsafe { JS_DefineDebuggerObject(cx.as_ptr(), global.handle().into()) }.then_some(global)
+}
+
+/// Takes a snapshot of the objects reachable from the current global, and those kept alive by the runtime.
+/// Returns the snapshot in the `.heapsnapshot` format, which can be loaded in the Memory panel of Chrome DevTools.
+///
+/// A garbage collection is performed first, so that the snapshot only contains live objects.
+pub fn take_heap_snapshot(cx: &Context) -> Result<String, Option<ErrorReport>> {
+	unsafe {
+		JS_GC(cx.as_ptr(), GCReason::API);
+	}
+
+	let debuggee = Object::global(cx);
+	let global = debugger_global(cx).ok_or(None)?;
+	let _realm = JSAutoRealm::new(cx.as_ptr(), global.handle().get());
+
+	let function = Script::compile_and_evaluate(cx, Path::new("snapshot.js"), SNAPSHOT_SOURCE)?;
+	let function = Function::from_object(cx, &function.to_object(cx).into_local()).ok_or(None)?;
+	let snapshot = function.call(cx, &Object::null(cx), &[debuggee.as_value(cx)])?;
+	String::from_value(cx, &snapshot, true, ()).map_err(|_| None)
+}
+
+/// Takes a heap snapshot, as with [take_heap_snapshot], and writes it to `path`.
+pub fn write_heap_snapshot(cx: &Context, path: &Path) -> io::Result<()> {
+	match take_heap_snapshot(cx) {
+		Ok(snapshot) => write(path, snapshot),
+		Err(Some(report)) => Err(io::Error::new(ErrorKind::Other, report.format(cx))),
+		Err(None) => Err(io::Error::new(ErrorKind::Other, "Failed to Take Heap Snapshot")),
+	}
+}
+
+/// Writes a heap snapshot to `path` when the runtime of the context shuts down.
+pub fn snapshot_heap_on_shutdown(cx: &Context, path: PathBuf) {
+	unsafe {
+		(*cx.get_private().as_ptr()).heap_snapshot = Some(path);
+	}
+}
+
+pub(crate) fn write_on_shutdown(cx: &Context) {
+	let path = unsafe { (*cx.get_private().as_ptr()).heap_snapshot.take() };
+	if let Some(path) = path {
+		if let Err(error) = write_heap_snapshot(cx, &path) {
+			eprintln!("Failed to Write Heap Snapshot to {}: {}", path.display(), error);
+		}
+	}
+}
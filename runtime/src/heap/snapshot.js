@@ -0,0 +1,321 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+// Builds a heap snapshot in the format of V8 (https://v8.dev/docs/memory-leaks), which is loaded by the Memory panel of Chrome DevTools.
+// This runs in a global which is invisible to the debugger, and walks the objects of the runtime's global through the Debugger API,
+// so that getters and proxy traps are never invoked. The variables captured by closures are walked through their environments.
+// SpiderMonkey does not expose the size of individual objects, so objects are sized by the average size of their class in a census.
+
+(function (debuggee) {
+	"use strict";
+
+	const NODE_FIELDS = ["type", "name", "id", "self_size", "edge_count", "trace_node_id", "detachedness"];
+	const NODE_TYPES = [
+		"hidden",
+		"array",
+		"string",
+		"object",
+		"code",
+		"closure",
+		"regexp",
+		"number",
+		"native",
+		"synthetic",
+		"concatenated string",
+		"sliced string",
+		"symbol",
+		"bigint",
+		"object shape",
+	];
+	const EDGE_FIELDS = ["type", "name_or_index", "to_node"];
+	const EDGE_TYPES = ["context", "element", "property", "internal", "hidden", "shortcut", "weak"];
+
+	const [HIDDEN, ARRAY, STRING, OBJECT, , CLOSURE, REGEXP, , , SYNTHETIC] = NODE_TYPES.keys();
+	const [CONTEXT, ELEMENT, PROPERTY, INTERNAL] = EDGE_TYPES.keys();
+
+	const MAX_STRING_NAME_LENGTH = 1024;
+	const DEFAULT_OBJECT_SIZE = 32;
+
+	const dbg = new Debugger();
+	const global = dbg.addDebuggee(debuggee);
+
+	const sizes = new Map();
+	try {
+		const count = { by: "count", count: true, bytes: true };
+		const census = dbg.memory.takeCensus({ breakdown: { by: "objectClass", then: count, other: count } });
+		for (const [name, { count, bytes }] of Object.entries(census)) {
+			sizes.set(name, count === 0 ? DEFAULT_OBJECT_SIZE : Math.round(bytes / count));
+		}
+	} catch {
+		// Sizes fall back to the default if a census cannot be taken.
+	}
+
+	const strings = [""];
+	const stringIndices = new Map([["", 0]]);
+
+	function string(value) {
+		let index = stringIndices.get(value);
+		if (index === undefined) {
+			index = strings.push(value) - 1;
+			stringIndices.set(value, index);
+		}
+		return index;
+	}
+
+	const nodes = [];
+	const queue = [];
+	const objectNodes = new Map();
+	const environmentNodes = new Map();
+	const stringNodes = new Map();
+
+	function createNode(type, name, size) {
+		const node = { type, name: string(name), id: nodes.length * 2 + 1, size, edges: [], index: nodes.length };
+		nodes.push(node);
+		return node;
+	}
+
+	function addEdge(from, type, name, to) {
+		if (to !== undefined) {
+			const nameOrIndex = type === ELEMENT ? name : string(String(name));
+			from.edges.push([type, nameOrIndex, to]);
+		}
+	}
+
+	function constructorName(object) {
+		try {
+			const descriptor = object.proto?.getOwnPropertyDescriptor("constructor");
+			const constructor = descriptor?.value;
+			if (constructor instanceof Debugger.Object && constructor.callable && constructor.name) {
+				return constructor.name;
+			}
+		} catch {
+			// Objects from other compartments cannot always be inspected.
+		}
+		return object.class;
+	}
+
+	function objectNode(object) {
+		let node = objectNodes.get(object);
+		if (node === undefined) {
+			let type = OBJECT;
+			let name;
+			if (object.callable) {
+				type = CLOSURE;
+				name = object.displayName || object.name || "(anonymous function)";
+			} else if (object.class === "Array") {
+				type = ARRAY;
+				name = "Array";
+			} else if (object.class === "RegExp") {
+				type = REGEXP;
+				name = "RegExp";
+			} else {
+				name = constructorName(object);
+			}
+			if (object === global) {
+				name = "global";
+			}
+
+			node = createNode(type, name, sizes.get(object.class) ?? DEFAULT_OBJECT_SIZE);
+			objectNodes.set(object, node);
+			queue.push(() => walkObject(object, node));
+		}
+		return node;
+	}
+
+	function stringNode(value) {
+		let node = stringNodes.get(value);
+		if (node === undefined) {
+			node = createNode(STRING, value.slice(0, MAX_STRING_NAME_LENGTH), 16 + value.length * 2);
+			stringNodes.set(value, node);
+		}
+		return node;
+	}
+
+	function environmentNode(environment) {
+		if (environment.type !== "declarative") {
+			return environment.object ? objectNode(environment.object) : undefined;
+		}
+		let node = environmentNodes.get(environment);
+		if (node === undefined) {
+			node = createNode(HIDDEN, "system / Context", 0);
+			environmentNodes.set(environment, node);
+			queue.push(() => walkEnvironment(environment, node));
+		}
+		return node;
+	}
+
+	// Returns the node of a value, or undefined for primitives other than strings, which are not tracked.
+	function valueNode(value) {
+		if (value instanceof Debugger.Object) {
+			return objectNode(value);
+		} else if (typeof value === "string") {
+			return stringNode(value);
+		}
+		return undefined;
+	}
+
+	function isIndex(name) {
+		return /^(?:0|[1-9]\d*)$/.test(name) && Number(name) < 2 ** 32 - 1;
+	}
+
+	function walkProperty(node, object, key, name) {
+		let descriptor;
+		try {
+			descriptor = object.getOwnPropertyDescriptor(key);
+		} catch {
+			return;
+		}
+		if (descriptor === undefined) {
+			return;
+		}
+		if ("value" in descriptor) {
+			if (isIndex(name)) {
+				addEdge(node, ELEMENT, Number(name), valueNode(descriptor.value));
+			} else {
+				addEdge(node, PROPERTY, name, valueNode(descriptor.value));
+			}
+		} else {
+			addEdge(node, PROPERTY, `get ${name}`, valueNode(descriptor.get));
+			addEdge(node, PROPERTY, `set ${name}`, valueNode(descriptor.set));
+		}
+	}
+
+	function walkEntries(node, object) {
+		const target = object.unsafeDereference();
+		try {
+			if (object.class === "Map") {
+				let index = 0;
+				Map.prototype.forEach.call(target, (value, key) => {
+					addEdge(node, INTERNAL, `key ${index}`, valueNode(global.makeDebuggeeValue(key)));
+					addEdge(node, INTERNAL, `value ${index}`, valueNode(global.makeDebuggeeValue(value)));
+					index++;
+				});
+			} else if (object.class === "Set") {
+				let index = 0;
+				Set.prototype.forEach.call(target, value => {
+					addEdge(node, INTERNAL, `value ${index++}`, valueNode(global.makeDebuggeeValue(value)));
+				});
+			}
+		} catch {
+			// Entries are omitted if the collection cannot be iterated.
+		}
+	}
+
+	function walkObject(object, node) {
+		if (object.isProxy) {
+			addEdge(node, INTERNAL, "target", valueNode(object.proxyTarget));
+			addEdge(node, INTERNAL, "handler", valueNode(object.proxyHandler));
+			return;
+		}
+
+		let names = [];
+		let symbols = [];
+		try {
+			names = object.getOwnPropertyNames();
+			symbols = object.getOwnPropertySymbols();
+		} catch {
+			// Properties are omitted if they cannot be listed.
+		}
+		for (const name of names) {
+			walkProperty(node, object, name, name);
+		}
+		for (const symbol of symbols) {
+			walkProperty(node, object, symbol, symbol.toString());
+		}
+
+		addEdge(node, INTERNAL, "__proto__", valueNode(object.proto));
+
+		if (object.callable) {
+			if (object.isBoundFunction) {
+				addEdge(node, INTERNAL, "bound_function", valueNode(object.boundTargetFunction));
+				addEdge(node, INTERNAL, "bound_this", valueNode(object.boundThis));
+				object.boundArguments.forEach((argument, index) => addEdge(node, INTERNAL, `bound_argument ${index}`, valueNode(argument)));
+			} else if (object.environment) {
+				addEdge(node, INTERNAL, "context", environmentNode(object.environment));
+			}
+		}
+
+		if (object.isPromise) {
+			if (object.promiseState === "fulfilled") {
+				addEdge(node, INTERNAL, "value", valueNode(object.promiseValue));
+			} else if (object.promiseState === "rejected") {
+				addEdge(node, INTERNAL, "reason", valueNode(object.promiseReason));
+			}
+		}
+
+		walkEntries(node, object);
+	}
+
+	function walkEnvironment(environment, node) {
+		for (const name of environment.names()) {
+			let value;
+			try {
+				value = environment.getVariable(name);
+			} catch {
+				continue;
+			}
+			addEdge(node, CONTEXT, name, valueNode(value));
+		}
+		if (environment.parent) {
+			addEdge(node, INTERNAL, "previous", environmentNode(environment.parent));
+		}
+	}
+
+	function drain() {
+		while (queue.length > 0) {
+			queue.pop()();
+		}
+	}
+
+	const root = createNode(SYNTHETIC, "", 0);
+	addEdge(root, ELEMENT, 1, objectNode(global));
+	drain();
+
+	// Objects which are alive, but not reachable from the global, are held by the event loop or the engine, such as timer callbacks.
+	const others = createNode(SYNTHETIC, "(Other Roots)", 0);
+	addEdge(root, ELEMENT, 2, others);
+	let index = 0;
+	for (const object of dbg.findObjects()) {
+		if (!objectNodes.has(object)) {
+			addEdge(others, ELEMENT, index++, objectNode(object));
+			drain();
+		}
+	}
+
+	const nodeFields = [];
+	const edgeFields = [];
+	for (const node of nodes) {
+		nodeFields.push(node.type, node.name, node.id, node.size, node.edges.length, 0, 0);
+		for (const [type, nameOrIndex, to] of node.edges) {
+			edgeFields.push(type, nameOrIndex, to.index * NODE_FIELDS.length);
+		}
+	}
+
+	return JSON.stringify({
+		snapshot: {
+			meta: {
+				node_fields: NODE_FIELDS,
+				node_types: [NODE_TYPES, "string", "number", "number", "number", "number", "number"],
+				edge_fields: EDGE_FIELDS,
+				edge_types: [EDGE_TYPES, "string_or_number", "node"],
+				trace_function_info_fields: ["function_id", "name", "script_name", "script_id", "line", "column"],
+				trace_node_fields: ["id", "function_info_index", "count", "size", "children"],
+				sample_fields: ["timestamp_us", "last_assigned_id"],
+				location_fields: ["object_index", "script_id", "line", "column"],
+			},
+			node_count: nodes.length,
+			edge_count: edgeFields.length / EDGE_FIELDS.length,
+			trace_function_count: 0,
+		},
+		nodes: nodeFields,
+		edges: edgeFields,
+		trace_function_infos: [],
+		trace_tree: [],
+		samples: [],
+		locations: [],
+		strings,
+	});
+});
@@ -0,0 +1,817 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+// Implements the Debugger, Runtime, Profiler and HeapProfiler domains of the Chrome DevTools Protocol (https://chromedevtools.github.io/devtools-protocol/).
+// This runs in a global which is invisible to the debugger, and inspects the runtime's global through the Debugger API.
+// Script IDs identify sources, and line numbers are converted from the 1-based lines of the Debugger API to the 0-based lines of the protocol.
+
+(function (host, debuggee) {
+	"use strict";
+
+	const CONTEXT_ID = 1;
+	const BACKTRACE = "backtrace";
+	const SNAPSHOT_CHUNK_LENGTH = 64 * 1024;
+
+	class ProtocolError extends Error {}
+
+	const dbg = new Debugger();
+	const global = dbg.addDebuggee(debuggee);
+
+	let enabled = false;
+	let paused = false;
+	let resumed = false;
+	let waiting = false;
+	let breakpointsActive = true;
+	let skipPauses = false;
+	let pauseOnExceptions = "none";
+	let lastException = undefined;
+
+	let pausedFrames = [];
+	let steppingFrames = [];
+
+	const sources = new Map();
+	const sourceIds = new WeakMap();
+	let nextSourceId = 1;
+
+	const breakpoints = new Map();
+
+	const objects = new Map();
+	let nextObjectId = 1;
+	let nextExceptionId = 1;
+
+	// Interval between samples of the profiler, in microseconds.
+	let samplingInterval = 1000;
+
+	function send(message) {
+		host.send(JSON.stringify(message));
+	}
+
+	function event(method, params) {
+		send({ method, params });
+	}
+
+	function sourceUrl(source) {
+		const url = source.url ?? "";
+		if (url.startsWith("/")) {
+			return `file://${encodeURI(url)}`;
+		}
+		if (/^[A-Za-z]:[\\/]/.test(url)) {
+			return `file:///${encodeURI(url.replaceAll("\\", "/"))}`;
+		}
+		return url;
+	}
+
+	function lineCount(source) {
+		return source.text.split(/\r\n|\r|\n/).length;
+	}
+
+	function register(source) {
+		let id = sourceIds.get(source);
+		if (id !== undefined) {
+			return id;
+		}
+
+		id = String(nextSourceId++);
+		sourceIds.set(source, id);
+		sources.set(id, source);
+		if (enabled) {
+			scriptParsed(id, source);
+		}
+		for (const breakpoint of breakpoints.values()) {
+			const location = applyBreakpoint(breakpoint, id, source);
+			if (location) {
+				event("Debugger.breakpointResolved", { breakpointId: breakpoint.id, location });
+			}
+		}
+		return id;
+	}
+
+	function scriptParsed(id, source) {
+		const lines = source.text.split(/\r\n|\r|\n/);
+		event("Debugger.scriptParsed", {
+			scriptId: id,
+			url: sourceUrl(source),
+			startLine: 0,
+			startColumn: 0,
+			endLine: lines.length - 1,
+			endColumn: lines[lines.length - 1].length,
+			executionContextId: CONTEXT_ID,
+			hash: "",
+			sourceMapURL: source.sourceMapURL ?? "",
+			length: source.text.length,
+		});
+	}
+
+	dbg.onNewScript = script => {
+		register(script.source);
+	};
+
+	// Breakpoints
+
+	function matches(breakpoint, id, source) {
+		if (breakpoint.scriptId !== undefined) {
+			return breakpoint.scriptId === id;
+		}
+		if (breakpoint.urlRegex) {
+			return breakpoint.urlRegex.test(sourceUrl(source));
+		}
+		return breakpoint.url === sourceUrl(source) || breakpoint.url === source.url;
+	}
+
+	// Finds the first breakable position at or after a location, on the same line or the lines after it.
+	function findLocation(source, lineNumber, columnNumber) {
+		const lines = lineCount(source);
+		for (let line = lineNumber; line < lines; line++) {
+			let location = null;
+			for (const script of dbg.findScripts({ source, line: line + 1 })) {
+				for (const position of script.getPossibleBreakpoints({ line: line + 1 })) {
+					if (line === lineNumber && position.columnNumber < columnNumber) {
+						continue;
+					}
+					if (!location || position.columnNumber < location.columnNumber) {
+						location = { script, offset: position.offset, lineNumber: line, columnNumber: position.columnNumber };
+					}
+				}
+			}
+			if (location) {
+				return location;
+			}
+		}
+		return null;
+	}
+
+	function applyBreakpoint(breakpoint, id, source) {
+		if (!matches(breakpoint, id, source)) {
+			return null;
+		}
+		const location = findLocation(source, breakpoint.lineNumber, breakpoint.columnNumber);
+		if (!location) {
+			return null;
+		}
+
+		const handler = { hit: frame => hitBreakpoint(frame, breakpoint) };
+		location.script.setBreakpoint(location.offset, handler);
+		breakpoint.handlers.push({ script: location.script, handler });
+
+		const resolved = { scriptId: id, lineNumber: location.lineNumber, columnNumber: location.columnNumber };
+		breakpoint.locations.push(resolved);
+		return resolved;
+	}
+
+	function removeBreakpoint(breakpoint) {
+		for (const { script, handler } of breakpoint.handlers) {
+			script.clearBreakpoint(handler);
+		}
+		breakpoints.delete(breakpoint.id);
+	}
+
+	function createBreakpoint(id, properties) {
+		if (breakpoints.has(id)) {
+			throw new ProtocolError("Breakpoint at specified location already exists.");
+		}
+		const breakpoint = { id, ...properties, handlers: [], locations: [] };
+		breakpoints.set(id, breakpoint);
+		for (const [scriptId, source] of sources) {
+			applyBreakpoint(breakpoint, scriptId, source);
+		}
+		return breakpoint;
+	}
+
+	function isTruthy(value) {
+		return value instanceof Debugger.Object || Boolean(value);
+	}
+
+	function hitBreakpoint(frame, breakpoint) {
+		if (!breakpointsActive || skipPauses) {
+			return undefined;
+		}
+		if (breakpoint.condition) {
+			const completion = frame.eval(breakpoint.condition);
+			if (!completion || "throw" in completion || !isTruthy(completion.return)) {
+				return undefined;
+			}
+		}
+		return pause(frame, "other", [breakpoint.id]);
+	}
+
+	// Pausing and Stepping
+
+	function location(frame) {
+		const { lineNumber, columnNumber } = frame.script.getOffsetMetadata(frame.offset);
+		return { scriptId: register(frame.script.source), lineNumber: lineNumber - 1, columnNumber };
+	}
+
+	function scopeChain(frame) {
+		const scopes = [];
+		let local = true;
+		for (let environment = frame.environment; environment; environment = environment.parent) {
+			let type;
+			if (!environment.parent) {
+				type = "global";
+			} else if (environment.type === "with") {
+				type = "with";
+			} else if (environment.calleeScript) {
+				type = local ? "local" : "closure";
+				local = false;
+			} else if (environment.scopeKind === "module") {
+				type = "module";
+			} else if (!environment.parent.parent) {
+				type = "script";
+			} else {
+				type = "block";
+			}
+
+			const description = type[0].toUpperCase() + type.slice(1);
+			const objectId = store({ environment }, BACKTRACE);
+			scopes.push({ type, object: { type: "object", className: "Object", description, objectId } });
+		}
+		return scopes;
+	}
+
+	function callFrame(frame, index) {
+		return {
+			callFrameId: String(index),
+			functionName: frame.callee?.displayName ?? "",
+			location: location(frame),
+			url: sourceUrl(frame.script.source),
+			scopeChain: scopeChain(frame),
+			this: remote(frame.this, BACKTRACE),
+		};
+	}
+
+	function clearStepping() {
+		for (const frame of steppingFrames) {
+			if (frame.onStack) {
+				frame.onStep = undefined;
+				frame.onPop = undefined;
+			}
+		}
+		steppingFrames = [];
+		dbg.onEnterFrame = undefined;
+	}
+
+	// Pauses at the next breakable position of a frame which is not on the line it started on.
+	function pauseOnStep(frame, startLine, reason = "other") {
+		frame.onStep = function () {
+			const metadata = this.script.getOffsetMetadata(this.offset);
+			if (!metadata.isBreakpoint || metadata.lineNumber === startLine) {
+				return undefined;
+			}
+			return pause(this, reason);
+		};
+		steppingFrames.push(frame);
+	}
+
+	// Pauses in the caller of a frame, once the frame returns to it.
+	function pauseOnPop(frame) {
+		frame.onPop = function () {
+			if (this.older) {
+				pauseOnStep(this.older);
+			}
+			return undefined;
+		};
+		steppingFrames.push(frame);
+	}
+
+	function step(kind) {
+		if (!paused) {
+			throw new ProtocolError("Can only perform operation while paused.");
+		}
+		const frame = pausedFrames[0];
+		if (kind !== "out") {
+			pauseOnStep(frame, frame.script.getOffsetMetadata(frame.offset).lineNumber);
+		}
+		if (kind === "into") {
+			dbg.onEnterFrame = entered => pauseOnStep(entered);
+		}
+		pauseOnPop(frame);
+		resumed = true;
+	}
+
+	// Pauses the debuggee, dispatching messages until it is resumed or the debugger disconnects.
+	// Pauses while already paused, such as from breakpoints hit by evaluations, are ignored.
+	function pause(frame, reason, hitBreakpoints = [], data = undefined) {
+		if (paused) {
+			return undefined;
+		}
+		clearStepping();
+
+		paused = true;
+		resumed = false;
+		pausedFrames = [];
+		for (let current = frame; current; current = current.older) {
+			pausedFrames.push(current);
+		}
+		event("Debugger.paused", { callFrames: pausedFrames.map(callFrame), reason, hitBreakpoints, data });
+
+		while (!resumed) {
+			const message = host.receive();
+			if (message === null) {
+				disconnect();
+				break;
+			}
+			dispatch(message);
+		}
+
+		paused = false;
+		pausedFrames = [];
+		releaseGroup(BACKTRACE);
+		event("Debugger.resumed", {});
+		return undefined;
+	}
+
+	// Exceptions are reported once, where they are first thrown.
+	// Uncaught exceptions are approximated by exceptions which unwind the oldest frame, as catch blocks are not known in advance.
+	function onExceptionUnwind(frame, value) {
+		if (value === lastException || pauseOnExceptions === "none" || skipPauses) {
+			return undefined;
+		}
+		if (pauseOnExceptions === "uncaught" && frame.older) {
+			return undefined;
+		}
+		lastException = value;
+		return pause(frame, "exception", [], remote(value, BACKTRACE));
+	}
+
+	// Remote Objects
+
+	function store(value, group) {
+		const id = String(nextObjectId++);
+		objects.set(id, { value, group });
+		return id;
+	}
+
+	function releaseGroup(group) {
+		for (const [id, object] of objects) {
+			if (object.group === group) {
+				objects.delete(id);
+			}
+		}
+	}
+
+	function lookup(objectId) {
+		const object = objects.get(objectId);
+		if (!object) {
+			throw new ProtocolError("Could not find object with given id");
+		}
+		return object;
+	}
+
+	function evaluateString(object, expression) {
+		const completion = global.executeInGlobalWithBindings(expression, { object });
+		return completion && typeof completion.return === "string" ? completion.return : undefined;
+	}
+
+	function toJSON(object) {
+		const json = evaluateString(object, "JSON.stringify(object)");
+		return json === undefined ? undefined : JSON.parse(json);
+	}
+
+	const SUBTYPES = {
+		Array: "array",
+		Date: "date",
+		RegExp: "regexp",
+		Map: "map",
+		Set: "set",
+		WeakMap: "weakmap",
+		WeakSet: "weakset",
+		Promise: "promise",
+		Generator: "generator",
+		ArrayBuffer: "arraybuffer",
+		SharedArrayBuffer: "arraybuffer",
+		DataView: "dataview",
+		Error: "error",
+	};
+
+	function describe(object, group) {
+		const objectId = store(object, group);
+		if (object.isProxy) {
+			return { type: "object", subtype: "proxy", className: "Object", description: "Proxy", objectId };
+		}
+		if (object.callable) {
+			const name = object.displayName ?? object.name ?? "";
+			return { type: "function", className: "Function", description: `function ${name}()`, objectId };
+		}
+
+		const className = object.class;
+		let subtype = SUBTYPES[className];
+		if (!subtype && /^(?:Big)?(?:Int|Uint|Float)(?:8|16|32|64)(?:Clamped)?Array$/.test(className)) {
+			subtype = "typedarray";
+		}
+
+		let description = object.proto?.getOwnPropertyDescriptor("constructor")?.value?.displayName ?? className;
+		switch (subtype) {
+			case "array":
+			case "typedarray":
+				description = `${description}(${object.getOwnPropertyDescriptor("length")?.value ?? 0})`;
+				break;
+			case "date":
+			case "regexp":
+				description = evaluateString(object, "String(object)") ?? description;
+				break;
+			case "error":
+				description = evaluateString(object, "`${object.name}: ${object.message}\n${object.stack}`") ?? description;
+				break;
+		}
+		return { type: "object", subtype, className, description, objectId };
+	}
+
+	function remote(value, group, byValue = false) {
+		switch (typeof value) {
+			case "undefined":
+				return { type: "undefined" };
+			case "boolean":
+			case "string":
+				return { type: typeof value, value };
+			case "number":
+				if (!Number.isFinite(value) || Object.is(value, -0)) {
+					const unserializableValue = Object.is(value, -0) ? "-0" : String(value);
+					return { type: "number", unserializableValue, description: unserializableValue };
+				}
+				return { type: "number", value, description: String(value) };
+			case "bigint":
+				return { type: "bigint", unserializableValue: `${value}n`, description: `${value}n` };
+			case "symbol":
+				return { type: "symbol", description: value.toString(), objectId: store(value, group) };
+		}
+		if (value === null) {
+			return { type: "object", subtype: "null", value: null };
+		}
+		if (!(value instanceof Debugger.Object)) {
+			// Variables which are optimised out or uninitialised are represented by plain objects.
+			const description = value.optimizedOut ? "<optimized out>" : value.uninitialized ? "<uninitialized>" : "<unavailable>";
+			return { type: "undefined", description };
+		}
+		if (byValue) {
+			const json = toJSON(value);
+			if (json !== undefined) {
+				return { type: "object", value: json };
+			}
+		}
+		return describe(value, group);
+	}
+
+	function propertyKeys(object) {
+		return [...object.getOwnPropertyNames(), ...object.getOwnPropertySymbols()];
+	}
+
+	function properties(object, group, accessorsOnly) {
+		const result = [];
+		for (const key of propertyKeys(object)) {
+			const descriptor = object.getOwnPropertyDescriptor(key);
+			const accessor = "get" in descriptor || "set" in descriptor;
+			if (accessorsOnly && !accessor) {
+				continue;
+			}
+
+			const property = {
+				name: typeof key === "symbol" ? key.toString() : key,
+				configurable: descriptor.configurable,
+				enumerable: descriptor.enumerable,
+				isOwn: true,
+			};
+			if (typeof key === "symbol") {
+				property.symbol = remote(key, group);
+			}
+			if (accessor) {
+				property.get = remote(descriptor.get, group);
+				property.set = remote(descriptor.set, group);
+			} else {
+				property.value = remote(descriptor.value, group);
+				property.writable = descriptor.writable;
+			}
+			result.push(property);
+		}
+		return result;
+	}
+
+	function internalProperties(object, group) {
+		const internal = [];
+		if (object.isPromise) {
+			internal.push({ name: "[[PromiseState]]", value: remote(object.promiseState, group) });
+			if (object.promiseState === "fulfilled") {
+				internal.push({ name: "[[PromiseResult]]", value: remote(object.promiseValue, group) });
+			} else if (object.promiseState === "rejected") {
+				internal.push({ name: "[[PromiseResult]]", value: remote(object.promiseReason, group) });
+			}
+		}
+		if (object.proto) {
+			internal.push({ name: "[[Prototype]]", value: remote(object.proto, group) });
+		}
+		return internal;
+	}
+
+	function callArgument(argument) {
+		if (argument.objectId !== undefined) {
+			return lookup(argument.objectId).value;
+		}
+		if (argument.unserializableValue !== undefined) {
+			return global.executeInGlobal(argument.unserializableValue)?.return;
+		}
+		if (typeof argument.value === "object" && argument.value !== null) {
+			return global.executeInGlobal(`(${JSON.stringify(argument.value)})`)?.return;
+		}
+		return argument.value;
+	}
+
+	function completionResult(completion, group, byValue) {
+		if (completion === null) {
+			const exceptionDetails = { exceptionId: nextExceptionId++, text: "Execution was terminated", lineNumber: 0, columnNumber: 0 };
+			return { result: { type: "undefined" }, exceptionDetails };
+		}
+		if ("throw" in completion) {
+			const exception = remote(completion.throw, group);
+			const exceptionDetails = { exceptionId: nextExceptionId++, text: "Uncaught", lineNumber: 0, columnNumber: 0, exception };
+			return { result: exception, exceptionDetails };
+		}
+		return { result: remote(completion.return, group, byValue) };
+	}
+
+	// Domains
+
+	function enable() {
+		if (enabled) {
+			return;
+		}
+		enabled = true;
+		dbg.onDebuggerStatement = frame => (skipPauses || !breakpointsActive ? undefined : pause(frame, "other"));
+		dbg.onExceptionUnwind = onExceptionUnwind;
+
+		for (const script of dbg.findScripts()) {
+			register(script.source);
+		}
+		for (const [id, source] of sources) {
+			scriptParsed(id, source);
+		}
+	}
+
+	function reset() {
+		for (const breakpoint of [...breakpoints.values()]) {
+			removeBreakpoint(breakpoint);
+		}
+		clearStepping();
+		objects.clear();
+		enabled = false;
+		breakpointsActive = true;
+		skipPauses = false;
+		pauseOnExceptions = "none";
+		dbg.onDebuggerStatement = undefined;
+		dbg.onExceptionUnwind = undefined;
+	}
+
+	const methods = {
+		"Debugger.enable"() {
+			enable();
+			return { debuggerId: "spiderfire" };
+		},
+		"Debugger.disable"() {
+			reset();
+		},
+		"Debugger.setBreakpointsActive"({ active }) {
+			breakpointsActive = active;
+		},
+		"Debugger.setSkipAllPauses"({ skip }) {
+			skipPauses = skip;
+		},
+		"Debugger.setPauseOnExceptions"({ state }) {
+			if (!["none", "uncaught", "all"].includes(state)) {
+				throw new ProtocolError(`Unknown pause on exceptions mode: ${state}`);
+			}
+			pauseOnExceptions = state;
+		},
+		"Debugger.setAsyncCallStackDepth"() {},
+		"Debugger.getScriptSource"({ scriptId }) {
+			const source = sources.get(scriptId);
+			if (!source) {
+				throw new ProtocolError("No script for id");
+			}
+			return { scriptSource: source.text };
+		},
+		"Debugger.setBreakpointByUrl"({ lineNumber, url, urlRegex, columnNumber = 0, condition }) {
+			if (url === undefined && urlRegex === undefined) {
+				throw new ProtocolError("Either url or urlRegex must be specified.");
+			}
+			const id = `${lineNumber}:${columnNumber}:${url ?? urlRegex}`;
+			const breakpoint = createBreakpoint(id, {
+				url,
+				urlRegex: urlRegex === undefined ? undefined : new RegExp(urlRegex),
+				lineNumber,
+				columnNumber,
+				condition,
+			});
+			return { breakpointId: id, locations: breakpoint.locations };
+		},
+		"Debugger.setBreakpoint"({ location, condition }) {
+			if (!sources.has(location.scriptId)) {
+				throw new ProtocolError("No script for id");
+			}
+			const { scriptId, lineNumber, columnNumber = 0 } = location;
+			const id = `${scriptId}:${lineNumber}:${columnNumber}`;
+			const breakpoint = createBreakpoint(id, { scriptId, lineNumber, columnNumber, condition });
+			if (breakpoint.locations.length === 0) {
+				breakpoints.delete(id);
+				throw new ProtocolError("Could not resolve breakpoint");
+			}
+			return { breakpointId: id, actualLocation: breakpoint.locations[0] };
+		},
+		"Debugger.removeBreakpoint"({ breakpointId }) {
+			const breakpoint = breakpoints.get(breakpointId);
+			if (breakpoint) {
+				removeBreakpoint(breakpoint);
+			}
+		},
+		"Debugger.getPossibleBreakpoints"({ start, end }) {
+			const source = sources.get(start.scriptId);
+			if (!source) {
+				throw new ProtocolError("No script for id");
+			}
+			const endLine = end?.lineNumber ?? lineCount(source);
+			const endColumn = end?.columnNumber ?? 0;
+
+			const locations = new Map();
+			for (const script of dbg.findScripts({ source })) {
+				for (const { lineNumber, columnNumber } of script.getPossibleBreakpoints({ minLine: start.lineNumber + 1, maxLine: endLine + 2 })) {
+					const line = lineNumber - 1;
+					const afterStart = line > start.lineNumber || columnNumber >= (start.columnNumber ?? 0);
+					const beforeEnd = line < endLine || (line === endLine && columnNumber < endColumn);
+					if (afterStart && beforeEnd) {
+						locations.set(`${line}:${columnNumber}`, { scriptId: start.scriptId, lineNumber: line, columnNumber });
+					}
+				}
+			}
+			const sorted = [...locations.values()].sort((a, b) => a.lineNumber - b.lineNumber || a.columnNumber - b.columnNumber);
+			return { locations: sorted };
+		},
+		"Debugger.pause"() {
+			if (!paused) {
+				dbg.onEnterFrame = frame => pauseOnStep(frame);
+			}
+		},
+		"Debugger.resume"() {
+			resumed = true;
+		},
+		"Debugger.stepOver"() {
+			step("over");
+		},
+		"Debugger.stepInto"() {
+			step("into");
+		},
+		"Debugger.stepOut"() {
+			step("out");
+		},
+		"Debugger.evaluateOnCallFrame"({ callFrameId, expression, objectGroup, returnByValue }) {
+			const frame = pausedFrames[Number(callFrameId)];
+			if (!frame) {
+				throw new ProtocolError("Could not find call frame with given id");
+			}
+			return completionResult(frame.eval(expression), objectGroup ?? BACKTRACE, returnByValue);
+		},
+
+		"Runtime.enable"() {
+			event("Runtime.executionContextCreated", {
+				context: { id: CONTEXT_ID, origin: "", name: "Spiderfire", uniqueId: String(CONTEXT_ID), auxData: { isDefault: true } },
+			});
+		},
+		"Runtime.disable"() {},
+		"Runtime.runIfWaitingForDebugger"() {
+			waiting = false;
+		},
+		"Runtime.evaluate"({ expression, objectGroup, returnByValue }) {
+			return completionResult(global.executeInGlobal(expression), objectGroup, returnByValue);
+		},
+		"Runtime.callFunctionOn"({ functionDeclaration, objectId, arguments: parameters = [], objectGroup, returnByValue }) {
+			const target = objectId === undefined ? undefined : lookup(objectId);
+			if (target && !(target.value instanceof Debugger.Object)) {
+				throw new ProtocolError("Functions can only be called on objects");
+			}
+			const completion = global.executeInGlobal(`(${functionDeclaration})`);
+			if (!completion || "throw" in completion) {
+				return completionResult(completion, objectGroup);
+			}
+			if (!(completion.return instanceof Debugger.Object) || !completion.return.callable) {
+				throw new ProtocolError("Given expression does not evaluate to a function");
+			}
+			const args = parameters.map(callArgument);
+			return completionResult(completion.return.apply(target?.value, args), objectGroup ?? target?.group, returnByValue);
+		},
+		"Runtime.getProperties"({ objectId, ownProperties, accessorPropertiesOnly }) {
+			const { value, group } = lookup(objectId);
+			if (value.environment) {
+				const environment = value.environment;
+				const result = environment.names().map(name => ({
+					name,
+					value: remote(environment.getVariable(name), group),
+					writable: true,
+					configurable: false,
+					enumerable: true,
+					isOwn: true,
+				}));
+				return { result };
+			}
+			if (!(value instanceof Debugger.Object)) {
+				return { result: [] };
+			}
+
+			const result = properties(value, group, accessorPropertiesOnly);
+			if (!ownProperties && !accessorPropertiesOnly) {
+				const names = new Set(result.map(property => property.name));
+				for (let proto = value.proto; proto; proto = proto.proto) {
+					for (const property of properties(proto, group, true)) {
+						if (!names.has(property.name)) {
+							names.add(property.name);
+							result.push({ ...property, isOwn: false });
+						}
+					}
+				}
+			}
+			return { result, internalProperties: internalProperties(value, group) };
+		},
+		"Runtime.releaseObject"({ objectId }) {
+			objects.delete(objectId);
+		},
+		"Runtime.releaseObjectGroup"({ objectGroup }) {
+			releaseGroup(objectGroup);
+		},
+
+		"Profiler.enable"() {},
+		"Profiler.disable"() {
+			host.stopProfiling();
+		},
+		"Profiler.setSamplingInterval"({ interval }) {
+			samplingInterval = interval;
+		},
+		"Profiler.start"() {
+			if (!host.startProfiling(samplingInterval)) {
+				throw new ProtocolError("Profiler is already started.");
+			}
+		},
+		"Profiler.stop"() {
+			const profile = host.stopProfiling();
+			if (profile === null) {
+				throw new ProtocolError("Profiler is not started.");
+			}
+			return { profile: JSON.parse(profile) };
+		},
+
+		"HeapProfiler.enable"() {},
+		"HeapProfiler.disable"() {},
+		"HeapProfiler.collectGarbage"() {
+			host.gc();
+		},
+		"HeapProfiler.takeHeapSnapshot"({ reportProgress = false }) {
+			const snapshot = host.takeHeapSnapshot();
+			for (let i = 0; i < snapshot.length; i += SNAPSHOT_CHUNK_LENGTH) {
+				event("HeapProfiler.addHeapSnapshotChunk", { chunk: snapshot.slice(i, i + SNAPSHOT_CHUNK_LENGTH) });
+			}
+			if (reportProgress) {
+				event("HeapProfiler.reportHeapSnapshotProgress", { done: 1, total: 1, finished: true });
+			}
+		},
+	};
+
+	function dispatch(message) {
+		let request;
+		try {
+			request = JSON.parse(message);
+		} catch {
+			return;
+		}
+
+		const { id, method, params = {} } = request;
+		if (!Object.hasOwn(methods, method)) {
+			send({ id, error: { code: -32601, message: `'${method}' wasn't found` } });
+			return;
+		}
+		try {
+			send({ id, result: methods[method](params) ?? {} });
+		} catch (error) {
+			const code = error instanceof ProtocolError ? -32000 : -32603;
+			send({ id, error: { code, message: String(error?.message ?? error) } });
+		}
+	}
+
+	// Clears the state of the session, and resumes the debuggee if it is paused.
+	function disconnect() {
+		reset();
+		resumed = true;
+	}
+
+	// Dispatches messages until a debugger asks the runtime to run, then pauses at the first statement.
+	function waitForDebugger() {
+		waiting = true;
+		while (waiting) {
+			const message = host.receive();
+			if (message === null) {
+				disconnect();
+			} else {
+				dispatch(message);
+			}
+		}
+		dbg.onEnterFrame = frame => pauseOnStep(frame, undefined, "Break on start");
+	}
+
+	return { dispatch, disconnect, waitForDebugger };
+});
@@ -0,0 +1,252 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::io;
+use std::io::ErrorKind;
+use std::net::{SocketAddr, TcpListener};
+use std::path::Path;
+use std::rc::Rc;
+use std::sync::{Arc, mpsc, Mutex};
+use std::task;
+use std::task::Waker;
+use std::time::Duration;
+
+use mozjs::jsapi::{GCReason, JS_GC, JSAutoRealm, JSObject};
+use tokio::sync::mpsc::UnboundedSender;
+
+use ion::{Context, Error, ErrorReport, Function, Object, PersistentRooted, Value};
+use ion::conversions::ToValue;
+use ion::script::Script;
+
+use crate::ContextExt;
+use crate::heap::{debugger_global, take_heap_snapshot};
+use crate::inspector::server::{spawn, Target};
+use crate::profiler::Profiler;
+
+mod server;
+
+const INSPECTOR_SOURCE: &str = include_str!("inspector.js");
+
+pub(crate) enum Incoming {
+	Message(String),
+	Disconnected,
+}
+
+struct Session {
+	id: u64,
+	sender: UnboundedSender<String>,
+}
+
+/// State shared between the runtime and the thread of the server.
+pub(crate) struct Shared {
+	incoming: Mutex<mpsc::Sender<Incoming>>,
+	waker: Mutex<Option<Waker>>,
+	session: Mutex<Option<Session>>,
+	sessions: Mutex<u64>,
+}
+
+impl Shared {
+	/// Passes an incoming message to the runtime, and wakes its event loop.
+	pub(crate) fn receive(&self, incoming: Incoming) {
+		let _ = self.incoming.lock().unwrap().send(incoming);
+		if let Some(waker) = self.waker.lock().unwrap().take() {
+			waker.wake();
+		}
+	}
+
+	/// Attaches a debugger, returning the ID of its session, or [None] if another debugger is attached.
+	pub(crate) fn attach(&self, sender: UnboundedSender<String>) -> Option<u64> {
+		let mut session = self.session.lock().unwrap();
+		if session.is_some() {
+			return None;
+		}
+		let mut sessions = self.sessions.lock().unwrap();
+		*sessions += 1;
+		*session = Some(Session { id: *sessions, sender });
+		Some(*sessions)
+	}
+
+	pub(crate) fn detach(&self, id: u64) {
+		let mut session = self.session.lock().unwrap();
+		if session.as_ref().is_some_and(|session| session.id == id) {
+			*session = None;
+		}
+	}
+
+	fn send(&self, message: String) {
+		if let Some(session) = &*self.session.lock().unwrap() {
+			let _ = session.sender.send(message);
+		}
+	}
+}
+
+struct Channel {
+	receiver: mpsc::Receiver<Incoming>,
+	shared: Arc<Shared>,
+}
+
+/// Implements the Chrome DevTools Protocol for the global of a runtime, so that it can be debugged from Chrome DevTools or VS Code.
+///
+/// Protocol messages are handled by the inspector script, which runs in a separate global with the [Debugger API](https://firefox-source-docs.mozilla.org/js/Debugger/).
+/// Messages are dispatched between turns of the event loop, and while the runtime is paused, the inspector blocks until it is resumed.
+pub struct Inspector {
+	global: PersistentRooted<*mut JSObject>,
+	internals: PersistentRooted<*mut JSObject>,
+	channel: Rc<Channel>,
+}
+
+impl Inspector {
+	/// Starts the inspector of the current global, serving it at `address` until the process exits.
+	/// Returns the URL of the WebSocket which debuggers connect to.
+	pub fn start(cx: &Context, address: SocketAddr, title: &str, url: &str) -> io::Result<String> {
+		let listener = TcpListener::bind(address)?;
+		let target = Target {
+			id: random_id(),
+			title: String::from(title),
+			url: String::from(url),
+			address: listener.local_addr()?,
+		};
+		let websocket_url = target.websocket_url();
+
+		let (sender, receiver) = mpsc::channel();
+		let shared = Arc::new(Shared {
+			incoming: Mutex::new(sender),
+			waker: Mutex::new(None),
+			session: Mutex::new(None),
+			sessions: Mutex::new(0),
+		});
+		let channel = Rc::new(Channel { receiver, shared: Arc::clone(&shared) });
+
+		let debuggee = Object::global(cx);
+		let Some(global) = debugger_global(cx) else {
+			return Err(io::Error::new(ErrorKind::Other, "Failed to Define the Debugger API"));
+		};
+
+		let internals = {
+			let _realm = JSAutoRealm::new(cx.as_ptr(), global.handle().get());
+			let host = host_object(cx, &channel, &debuggee);
+			let internals = Script::compile_and_evaluate(cx, Path::new("inspector.js"), INSPECTOR_SOURCE).and_then(|function| {
+				let function = Function::from_object(cx, &function.to_object(cx).into_local()).unwrap();
+				let args = [host.as_value(cx), debuggee.as_value(cx)];
+				function.call(cx, &Object::null(cx), &args)
+			});
+			match internals {
+				Ok(internals) if internals.handle().is_object() => PersistentRooted::new(internals.handle().to_object()),
+				Err(Some(report)) => return Err(io::Error::new(ErrorKind::Other, report.format(cx))),
+				_ => return Err(io::Error::new(ErrorKind::Other, "Failed to Initialise the Inspector")),
+			}
+		};
+
+		spawn(listener, target, shared)?;
+
+		let event_loop = unsafe { &mut (*cx.get_private().as_ptr()).event_loop };
+		event_loop.inspector = Some(Inspector {
+			global: PersistentRooted::new(global.handle().get()),
+			internals,
+			channel,
+		});
+		Ok(websocket_url)
+	}
+
+	/// Blocks until a debugger has connected and asked the runtime to run, then pauses at the next statement.
+	/// Returns [Err] if the inspector script throws.
+	pub fn wait_for_debugger(cx: &Context) -> Result<(), Option<ErrorReport>> {
+		let event_loop = unsafe { &(*cx.get_private().as_ptr()).event_loop };
+		match &event_loop.inspector {
+			Some(inspector) => inspector.call(cx, "waitForDebugger", None),
+			None => Ok(()),
+		}
+	}
+
+	/// Dispatches the messages which have been received since the last turn of the event loop.
+	pub(crate) fn poll(&self, cx: &Context, wcx: &mut task::Context) -> Result<(), Option<ErrorReport>> {
+		*self.channel.shared.waker.lock().unwrap() = Some(wcx.waker().clone());
+		while let Ok(incoming) = self.channel.receiver.try_recv() {
+			match incoming {
+				Incoming::Message(message) => self.call(cx, "dispatch", Some(&message))?,
+				Incoming::Disconnected => self.call(cx, "disconnect", None)?,
+			}
+		}
+		Ok(())
+	}
+
+	fn call(&self, cx: &Context, name: &str, message: Option<&str>) -> Result<(), Option<ErrorReport>> {
+		let _realm = JSAutoRealm::new(cx.as_ptr(), self.global.get());
+		let internals = Object::from(cx.root_object(self.internals.get()));
+		let Some(function) = internals.get(cx, name) else {
+			return Ok(());
+		};
+		let function = Function::from_object(cx, &function.to_object(cx).into_local()).unwrap();
+		let args: Vec<_> = message.map(|message| Value::string(cx, message)).into_iter().collect();
+		function.call(cx, &internals, &args).map(|_| ())
+	}
+}
+
+/// Creates the object of native functions which the inspector script communicates with the debugger through.
+/// `receive` blocks until a message is received, and returns `null` once the debugger disconnects.
+/// Profiles and heap snapshots are returned as JSON strings, and heap snapshots are taken of the debuggee global.
+fn host_object<'cx>(cx: &'cx Context, channel: &Rc<Channel>, debuggee: &Object) -> Object<'cx> {
+	let mut host = Object::new(cx);
+
+	let sender = Rc::clone(channel);
+	let send = Function::new_closure(cx, "send", move |cx, args| {
+		sender.shared.send(args.get::<String>(0)?);
+		Ok(Value::undefined(cx))
+	});
+	host.set_as(cx, "send", &send);
+
+	let receiver = Rc::clone(channel);
+	let receive = Function::new_closure(cx, "receive", move |cx, _| match receiver.receiver.recv() {
+		Ok(Incoming::Message(message)) => Ok(Value::string(cx, &message)),
+		Ok(Incoming::Disconnected) | Err(_) => Ok(Value::null(cx)),
+	});
+	host.set_as(cx, "receive", &receive);
+
+	let gc = Function::new_closure(cx, "gc", |cx, _| {
+		unsafe {
+			JS_GC(cx.as_ptr(), GCReason::API);
+		}
+		Ok(Value::undefined(cx))
+	});
+	host.set_as(cx, "gc", &gc);
+
+	let start_profiling = Function::new_closure(cx, "startProfiling", |cx, args| {
+		let interval = Duration::from_micros(args.get::<f64>(0)?.max(1.0) as u64);
+		Ok(Profiler::start(cx, interval, None).as_value(cx))
+	});
+	host.set_as(cx, "startProfiling", &start_profiling);
+
+	let stop_profiling = Function::new_closure(cx, "stopProfiling", |cx, _| match Profiler::stop(cx) {
+		Some(profile) => Ok(Value::string(cx, &profile.to_cpu_profile())),
+		None => Ok(Value::null(cx)),
+	});
+	host.set_as(cx, "stopProfiling", &stop_profiling);
+
+	let debuggee = PersistentRooted::new(debuggee.handle().get());
+	let take_snapshot = Function::new_closure(cx, "takeHeapSnapshot", move |cx, _| {
+		let snapshot = {
+			let _realm = JSAutoRealm::new(cx.as_ptr(), debuggee.get());
+			take_heap_snapshot(cx)
+		};
+		match snapshot {
+			Ok(snapshot) => Ok(Value::string(cx, &snapshot)),
+			Err(Some(report)) => Err(Error::new(&report.format(cx), None)),
+			Err(None) => Err(Error::new("Failed to Take Heap Snapshot", None)),
+		}
+	});
+	host.set_as(cx, "takeHeapSnapshot", &take_snapshot);
+
+	host
+}
+
+/// Generates a random version 4 UUID, which identifies the target to debuggers.
+fn random_id() -> String {
+	let mut bytes: [u8; 16] = rand::random();
+	bytes[6] = (bytes[6] & 0x0F) | 0x40;
+	bytes[8] = (bytes[8] & 0x3F) | 0x80;
+	let hex: String = bytes.iter().map(|byte| format!("{:02x}", byte)).collect();
+	format!("{}-{}-{}-{}-{}", &hex[..8], &hex[8..12], &hex[12..16], &hex[16..20], &hex[20..])
+}
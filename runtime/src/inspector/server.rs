@@ -0,0 +1,222 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::collections::HashMap;
+use std::io;
+use std::net::{IpAddr, SocketAddr, TcpListener as StdTcpListener};
+use std::sync::Arc;
+use std::thread;
+
+use futures::{SinkExt, StreamExt};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::runtime::Builder;
+use tokio::select;
+use tokio::sync::mpsc::unbounded_channel;
+use tokio_tungstenite::tungstenite::handshake::derive_accept_key;
+use tokio_tungstenite::tungstenite::protocol::Role;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::WebSocketStream;
+use url::{Host, Url};
+
+use crate::inspector::{Incoming, Shared};
+use crate::json::json_string;
+use crate::VERSION;
+
+const MAX_REQUEST_LENGTH: usize = 16 * 1024;
+
+/// Describes the runtime to debuggers, which list it as a target they can attach to.
+pub(crate) struct Target {
+	pub(crate) id: String,
+	pub(crate) title: String,
+	pub(crate) url: String,
+	pub(crate) address: SocketAddr,
+}
+
+impl Target {
+	pub(crate) fn websocket_url(&self) -> String {
+		format!("ws://{}/{}", self.address, self.id)
+	}
+
+	fn list(&self) -> String {
+		format!(
+			r#"[{{"description":"Spiderfire","devtoolsFrontendUrl":"devtools://devtools/bundled/js_app.html?experiments=true&v8only=true&ws={address}/{id}","id":"{id}","title":{title},"type":"node","url":{url},"webSocketDebuggerUrl":"{websocket}"}}]"#,
+			address = self.address,
+			id = self.id,
+			title = json_string(&self.title),
+			url = json_string(&self.url),
+			websocket = self.websocket_url(),
+		)
+	}
+}
+
+/// Serves the target on a separate thread, for as long as the process runs.
+/// Only one debugger can be attached at a time.
+pub(crate) fn spawn(listener: StdTcpListener, target: Target, shared: Arc<Shared>) -> io::Result<()> {
+	listener.set_nonblocking(true)?;
+	let runtime = Builder::new_current_thread().enable_all().build()?;
+	thread::Builder::new().name(String::from("inspector")).spawn(move || {
+		runtime.block_on(async move {
+			let Ok(listener) = TcpListener::from_std(listener) else {
+				return;
+			};
+			let target = Arc::new(target);
+			loop {
+				if let Ok((stream, _)) = listener.accept().await {
+					tokio::spawn(handle(stream, Arc::clone(&target), Arc::clone(&shared)));
+				}
+			}
+		})
+	})?;
+	Ok(())
+}
+
+struct Request {
+	path: String,
+	headers: HashMap<String, String>,
+}
+
+async fn handle(mut stream: TcpStream, target: Arc<Target>, shared: Arc<Shared>) -> io::Result<()> {
+	let Some(request) = read_request(&mut stream).await? else {
+		return Ok(());
+	};
+	// Web pages can reach the server by rebinding their domain to its address, so only requests addressed to it by IP or as localhost are served.
+	if !request.headers.get("host").is_some_and(|host| is_allowed_host(host)) {
+		return respond(&mut stream, "403 Forbidden", r#"{"error":"Host must be localhost or an IP Address"}"#).await;
+	}
+
+	match request.path.as_str() {
+		"/json" | "/json/list" => respond(&mut stream, "200 OK", &target.list()).await,
+		"/json/version" => {
+			let version = format!(r#"{{"Browser":"Spiderfire/{}","Protocol-Version":"1.3"}}"#, VERSION);
+			respond(&mut stream, "200 OK", &version).await
+		}
+		path if path.strip_prefix('/') == Some(target.id.as_str()) => {
+			let upgrade = request
+				.headers
+				.get("upgrade")
+				.is_some_and(|upgrade| upgrade.eq_ignore_ascii_case("websocket"));
+			let Some(key) = request.headers.get("sec-websocket-key").filter(|_| upgrade) else {
+				return respond(&mut stream, "400 Bad Request", r#"{"error":"Expected a WebSocket Upgrade"}"#).await;
+			};
+			if let Some(origin) = request.headers.get("origin") {
+				if !is_allowed_origin(origin) {
+					return respond(&mut stream, "403 Forbidden", r#"{"error":"Origin is not Allowed"}"#).await;
+				}
+			}
+
+			let (sender, mut outgoing) = unbounded_channel();
+			let Some(session) = shared.attach(sender) else {
+				return respond(&mut stream, "400 Bad Request", r#"{"error":"A Debugger is already attached"}"#).await;
+			};
+
+			let response = format!(
+				"HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Accept: {}\r\n\r\n",
+				derive_accept_key(key.as_bytes())
+			);
+			if let Err(error) = stream.write_all(response.as_bytes()).await {
+				shared.detach(session);
+				return Err(error);
+			}
+
+			let socket = WebSocketStream::from_raw_socket(stream, Role::Server, None).await;
+			let (mut sink, mut source) = socket.split();
+			loop {
+				select! {
+					message = source.next() => match message {
+						Some(Ok(Message::Text(message))) => shared.receive(Incoming::Message(message)),
+						Some(Ok(Message::Close(_)) | Err(_)) | None => break,
+						Some(Ok(_)) => {}
+					},
+					message = outgoing.recv() => match message {
+						Some(message) => {
+							if sink.send(Message::Text(message)).await.is_err() {
+								break;
+							}
+						}
+						None => break,
+					},
+				}
+			}
+
+			shared.detach(session);
+			shared.receive(Incoming::Disconnected);
+			Ok(())
+		}
+		_ => respond(&mut stream, "404 Not Found", r#"{"error":"Unknown Path"}"#).await,
+	}
+}
+
+/// Reads the request line and headers of a HTTP request.
+async fn read_request(stream: &mut TcpStream) -> io::Result<Option<Request>> {
+	let mut buffer = Vec::new();
+	let mut chunk = [0; 1024];
+	let end = loop {
+		let read = stream.read(&mut chunk).await?;
+		if read == 0 {
+			return Ok(None);
+		}
+		buffer.extend_from_slice(&chunk[..read]);
+		if let Some(end) = buffer.windows(4).position(|window| window == b"\r\n\r\n") {
+			break end;
+		}
+		if buffer.len() > MAX_REQUEST_LENGTH {
+			return Ok(None);
+		}
+	};
+
+	let head = String::from_utf8_lossy(&buffer[..end]);
+	let mut lines = head.split("\r\n");
+	let Some(path) = lines.next().and_then(|line| line.split(' ').nth(1)) else {
+		return Ok(None);
+	};
+	let path = path.split('?').next().unwrap_or(path).trim_end_matches('/');
+	let headers = lines
+		.filter_map(|line| line.split_once(':'))
+		.map(|(name, value)| (name.trim().to_ascii_lowercase(), String::from(value.trim())))
+		.collect();
+	Ok(Some(Request { path: String::from(path), headers }))
+}
+
+/// Checks if a `Host` header is `localhost` or an IP address, with an optional port.
+fn is_allowed_host(host: &str) -> bool {
+	let (hostname, port) = match host.strip_prefix('[') {
+		Some(host) => match host.split_once(']') {
+			Some((address, port)) => (address, port),
+			None => return false,
+		},
+		None => match host.find(':') {
+			Some(index) => host.split_at(index),
+			None => (host, ""),
+		},
+	};
+	let valid_port = port.is_empty() || port.strip_prefix(':').is_some_and(|port| port.parse::<u16>().is_ok());
+	valid_port && (hostname.eq_ignore_ascii_case("localhost") || hostname.parse::<IpAddr>().is_ok())
+}
+
+/// Checks if the `Origin` of a WebSocket upgrade is DevTools or a page served from the loopback address.
+/// Other clients, such as VS Code, do not send an `Origin`.
+fn is_allowed_origin(origin: &str) -> bool {
+	if origin.starts_with("devtools://") || origin.starts_with("chrome-devtools://") {
+		return true;
+	}
+	match Url::parse(origin).ok().as_ref().and_then(Url::host) {
+		Some(Host::Domain(domain)) => domain.eq_ignore_ascii_case("localhost"),
+		Some(Host::Ipv4(address)) => address.is_loopback(),
+		Some(Host::Ipv6(address)) => address.is_loopback(),
+		None => false,
+	}
+}
+
+async fn respond(stream: &mut TcpStream, status: &str, body: &str) -> io::Result<()> {
+	let response = format!(
+		"HTTP/1.1 {}\r\nContent-Type: application/json; charset=UTF-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+		status,
+		body.len(),
+		body
+	);
+	stream.write_all(response.as_bytes()).await
+}
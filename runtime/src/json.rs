@@ -0,0 +1,24 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+/// Escapes a string as a JSON string literal, including its quotes.
+pub(crate) fn json_string(string: &str) -> String {
+	let mut json = String::with_capacity(string.len() + 2);
+	json.push('"');
+	for char in string.chars() {
+		match char {
+			'"' => json.push_str("\\\""),
+			'\\' => json.push_str("\\\\"),
+			'\n' => json.push_str("\\n"),
+			'\r' => json.push_str("\\r"),
+			'\t' => json.push_str("\\t"),
+			char if char.is_control() => json.push_str(&format!("\\u{:04x}", char as u32)),
+			char => json.push(char),
+		}
+	}
+	json.push('"');
+	json
+}
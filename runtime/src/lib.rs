@@ -14,13 +14,26 @@ extern crate ion;
 
 pub use crate::runtime::*;
 
+pub mod bundler;
 pub mod cache;
+pub mod clone;
 pub mod config;
+pub mod diagnostics;
 pub mod event_loop;
 pub mod globals;
+pub mod heap;
+#[cfg(feature = "inspector")]
+pub mod inspector;
+mod json;
 pub mod modules;
+pub mod options;
+pub mod permissions;
+pub mod profiler;
 pub mod promise;
 pub mod runtime;
+pub mod snapshot;
+pub mod standalone;
 pub mod typescript;
+pub mod watchdog;
 
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
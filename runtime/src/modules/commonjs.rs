@@ -0,0 +1,195 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::collections::HashMap;
+use std::ffi::OsStr;
+use std::fs::read_to_string;
+use std::path::{Path, PathBuf};
+
+use dunce::canonicalize;
+use mozjs::jsapi::{JS_ParseJSON, JSObject};
+
+use ion::{Context, Error, ErrorKind, Exception, Function, Object, PersistentRooted, ResultExc, Value};
+use ion::conversions::FromValue;
+use ion::flags::PropertyFlags;
+use ion::script::Script;
+
+use crate::ContextExt;
+use crate::modules::package::{is_bare, read_manifest, resolve_file, resolve_package, ResolutionKind};
+
+/// Checks if a file is a CommonJS module.
+///
+/// `.cjs` files are always CommonJS, and `.mjs` files never are.
+/// Otherwise, the `type` field of the nearest `package.json` is used.
+/// Files in `node_modules` without a `type` are CommonJS unless they contain `import` or `export` declarations.
+pub fn is_commonjs(cx: &Context, path: &Path, source: &str) -> bool {
+	match path.extension().and_then(OsStr::to_str) {
+		Some("cjs") => return true,
+		Some("js") => {}
+		_ => return false,
+	}
+
+	let path = canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+	match package_type(cx, &path).as_deref() {
+		Some("commonjs") => true,
+		Some("module") => false,
+		_ => path.components().any(|component| component.as_os_str() == "node_modules") && !has_module_syntax(source),
+	}
+}
+
+/// Checks if a file required by a CommonJS module is an ES module, which cannot be required.
+/// Unlike imports, required `.js` files without a package `type` are CommonJS unless they contain module syntax.
+fn is_es_module(cx: &Context, path: &Path, source: &str) -> bool {
+	match path.extension().and_then(OsStr::to_str) {
+		Some("mjs") => true,
+		Some("js") => package_type(cx, path).as_deref() == Some("module") || has_module_syntax(source),
+		_ => false,
+	}
+}
+
+/// Returns the `type` field of the nearest `package.json`.
+fn package_type(cx: &Context, path: &Path) -> Option<String> {
+	let manifest = path
+		.ancestors()
+		.skip(1)
+		.find_map(|directory| read_manifest(cx, &directory.join("package.json")))?;
+	manifest.get_as::<_, String>(cx, "type", true, ())
+}
+
+fn has_module_syntax(source: &str) -> bool {
+	source.lines().map(str::trim_start).any(|line| {
+		let declaration = |keyword: &str| {
+			line.strip_prefix(keyword)
+				.and_then(|rest| rest.chars().next())
+				.is_some_and(|next| next.is_whitespace() || next == '{' || next == '*' || next == '"' || next == '\'')
+		};
+		declaration("import") || declaration("export")
+	})
+}
+
+/// Calls `f` with the CommonJS modules loaded by the runtime of a context.
+fn with_modules<R, F: FnOnce(&mut HashMap<PathBuf, PersistentRooted<*mut JSObject>>) -> R>(cx: &Context, f: F) -> R {
+	f(unsafe { &mut (*cx.get_private().as_ptr()).commonjs_modules })
+}
+
+/// Loads a CommonJS module, returning its `module.exports`.
+/// Modules are cached by path, and cyclic requires receive the exports of the partially evaluated module.
+pub fn require<'cx>(cx: &'cx Context, path: &Path) -> ResultExc<Value<'cx>> {
+	let path = canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+	if let Some(module) = with_modules(cx, |modules| modules.get(&path).map(PersistentRooted::get)) {
+		return Ok(Object::from(cx.root_object(module))
+			.get(cx, "exports")
+			.unwrap_or_else(|| Value::undefined(cx)));
+	}
+
+	let source = read_to_string(&path).map_err(|error| Error::new(&format!("Unable to read module: {}", path.display()), None).with_cause(error))?;
+
+	let mut module = Object::new(cx);
+	let exports = Object::new(cx);
+	module.set_as(cx, "id", &*path.to_string_lossy());
+	module.set_as(cx, "exports", &exports);
+	with_modules(cx, |modules| modules.insert(path.clone(), PersistentRooted::new(module.handle().get())));
+
+	let result = if path.extension() == Some(OsStr::new("json")) {
+		parse_json(cx, &source).map(|value| {
+			module.set(cx, "exports", &value);
+		})
+	} else {
+		evaluate(cx, &path, &source, &module, &exports)
+	};
+	if let Err(exception) = result {
+		with_modules(cx, |modules| modules.remove(&path));
+		return Err(exception);
+	}
+
+	Ok(module.get(cx, "exports").unwrap_or_else(|| Value::undefined(cx)))
+}
+
+/// Returns the `module.exports` of a CommonJS module which has been loaded.
+pub fn exports<'cx>(cx: &'cx Context, path: &Path) -> Option<Value<'cx>> {
+	let path = canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+	let module = with_modules(cx, |modules| modules.get(&path).map(PersistentRooted::get))?;
+	Object::from(cx.root_object(module)).get(cx, "exports")
+}
+
+fn evaluate(cx: &Context, path: &Path, source: &str, module: &Object, exports: &Object) -> ResultExc<()> {
+	// The wrapper is kept on the first line, so that line numbers in stack traces match the file.
+	let wrapper = format!("(function (exports, require, module, __filename, __dirname) {{ {}\n}})", source);
+	let function = Script::compile(cx, path, &wrapper)
+		.map_err(Some)
+		.and_then(|script| script.evaluate(cx))
+		.map_err(|report| {
+			report
+				.map(|report| report.exception)
+				.unwrap_or_else(|| Error::new("Module threw an uncatchable exception", None).into())
+		})?;
+	let function = Function::from_object(cx, &function.to_object(cx).into_local()).unwrap();
+
+	let filename = path.to_string_lossy();
+	let dirname = path.parent().map(Path::to_string_lossy).unwrap_or_default();
+	let args = [
+		exports.as_value(cx),
+		require_function(cx, path).to_object(cx).as_value(cx),
+		module.as_value(cx),
+		Value::string(cx, &filename),
+		Value::string(cx, &dirname),
+	];
+
+	function.call(cx, exports, &args).map(|_| ()).map_err(|report| {
+		report
+			.map(|report| report.exception)
+			.unwrap_or_else(|| Error::new("Module threw an uncatchable exception", None).into())
+	})
+}
+
+fn require_function<'cx>(cx: &'cx Context, path: &Path) -> Function<'cx> {
+	let path = path.to_path_buf();
+	Function::from_closure(
+		cx,
+		"require",
+		Box::new(move |args| {
+			let cx = args.cx();
+			let specifier = args.value(0).map(|specifier| String::from_value(cx, specifier, true, ())).transpose()?;
+			let Some(specifier) = specifier else {
+				return Err(Error::new("require() expects a specifier", ErrorKind::Type).into());
+			};
+
+			let resolved = resolve_require(cx, &specifier, &path)
+				.ok_or_else(|| Error::new(&format!("Cannot find module '{}'", specifier), ErrorKind::Normal))?;
+			let source = read_to_string(&resolved).unwrap_or_default();
+			if is_es_module(cx, &resolved, &source) {
+				return Err(Error::new(&format!("require() of ES module '{}' is not supported", specifier), ErrorKind::Normal).into());
+			}
+			require(cx, &resolved)
+		}),
+		1,
+		PropertyFlags::empty(),
+	)
+}
+
+fn resolve_require(cx: &Context, specifier: &str, parent: &Path) -> Option<PathBuf> {
+	if is_bare(specifier) {
+		return resolve_package(cx, specifier, Some(parent), ResolutionKind::Require);
+	}
+
+	let path = Path::new(specifier);
+	let path = if path.is_absolute() {
+		path.to_path_buf()
+	} else {
+		parent.parent().unwrap_or(Path::new("")).join(path)
+	};
+	resolve_file(&path, ResolutionKind::Require)
+}
+
+fn parse_json<'cx>(cx: &'cx Context, json: &str) -> ResultExc<Value<'cx>> {
+	let chars: Vec<u16> = json.encode_utf16().collect();
+	let mut value = Value::undefined(cx);
+	if unsafe { JS_ParseJSON(cx.as_ptr(), chars.as_ptr(), chars.len() as u32, value.handle_mut().into()) } {
+		Ok(value)
+	} else {
+		Err(Exception::new(cx).unwrap())
+	}
+}
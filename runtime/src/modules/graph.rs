@@ -0,0 +1,73 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::collections::HashSet;
+use std::ffi::OsStr;
+use std::fs::read_to_string;
+use std::path::{Path, PathBuf};
+
+use dunce::canonicalize;
+
+use ion::{Context, Exception};
+use ion::module::Module;
+use ion::stencil::Stencil;
+
+use crate::modules::commonjs::is_commonjs;
+use crate::modules::Loader;
+use crate::modules::remote::remote_url;
+use crate::typescript::{compile_typescript, is_typescript};
+
+/// Returns the local files in the graph of static imports of a module, including the module itself.
+///
+/// Modules are compiled without being cached, and remote modules are neither fetched nor followed, as they cannot import local modules.
+/// Modules which cannot be read, compiled or resolved are skipped, so the graph is returned as far as it could be walked.
+pub fn local_module_graph(cx: &Context, loader: &mut Loader, entry: &Path) -> HashSet<PathBuf> {
+	let mut files = HashSet::new();
+	let mut queue = vec![entry.to_path_buf()];
+	while let Some(path) = queue.pop() {
+		let path = canonicalize(&path).unwrap_or(path);
+		if !files.insert(path.clone()) {
+			continue;
+		}
+
+		for specifier in requested_specifiers(cx, &path).unwrap_or_default() {
+			match loader.resolve_specifier(cx, &specifier, Some(&path)) {
+				Some(dependency) if dependency.is_file() && remote_url(cx, &dependency).is_none() => queue.push(dependency),
+				Some(_) => {}
+				// Remote modules which have not been fetched cannot be resolved.
+				None => Exception::clear(cx),
+			}
+		}
+	}
+	files
+}
+
+/// Returns the specifiers of the static imports of a module, without linking or caching it.
+/// Returns [None] for JSON and CommonJS modules, and modules which cannot be read or compiled.
+pub(crate) fn requested_specifiers(cx: &Context, path: &Path) -> Option<Vec<String>> {
+	if path.extension() == Some(OsStr::new("json")) {
+		return None;
+	}
+	let source = read_to_string(path).ok()?;
+	if is_commonjs(cx, path, &source) {
+		return None;
+	}
+	let source = if is_typescript(path) {
+		compile_typescript(&path.to_string_lossy(), &source).ok()?.0
+	} else {
+		source
+	};
+
+	// Modules which fail to compile here are reported when the loader compiles them.
+	let module = Stencil::compile_module(cx, path, &source).and_then(|stencil| stencil.to_module(cx));
+	match module {
+		Ok(module) => Some(Module(module).requested_specifiers(cx)),
+		Err(_) => {
+			Exception::clear(cx);
+			None
+		}
+	}
+}
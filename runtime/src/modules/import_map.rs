@@ -0,0 +1,180 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::fs::read_to_string;
+use std::path::Path;
+
+use dunce::canonicalize;
+use mozjs::jsapi::JS_ParseJSON;
+use url::Url;
+
+use ion::{Context, Error, ErrorKind, ErrorReport, Object, OwnedKey, Value};
+use ion::conversions::FromValue;
+
+use crate::globals::url::parse_url;
+
+type SpecifierMap = Vec<(String, Option<Url>)>;
+
+/// Represents an import map, which remaps the specifiers of imports before they are resolved.
+///
+/// Refer to the [HTML Standard](https://html.spec.whatwg.org/multipage/webappapis.html#import-maps) for more details.
+#[derive(Clone, Debug, Default)]
+pub struct ImportMap {
+	imports: SpecifierMap,
+	scopes: Vec<(String, SpecifierMap)>,
+}
+
+impl ImportMap {
+	/// Reads and parses an import map from a file.
+	/// Addresses in the import map are resolved relative to the file.
+	pub fn from_file(cx: &Context, path: &Path) -> Result<ImportMap, Error> {
+		let json =
+			read_to_string(path).map_err(|error| Error::new(&format!("Unable to read import map: {}", path.display()), None).with_cause(error))?;
+		let path = canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+		let base = Url::from_file_path(&path).map_err(|_| Error::new(&format!("Invalid import map path: {}", path.display()), None))?;
+		ImportMap::parse(cx, &json, &base)
+	}
+
+	/// Parses an import map, resolving its addresses against `base`.
+	/// Invalid entries are ignored, as specified.
+	pub fn parse(cx: &Context, json: &str, base: &Url) -> Result<ImportMap, Error> {
+		let chars: Vec<u16> = json.encode_utf16().collect();
+		let mut value = Value::undefined(cx);
+		if !unsafe { JS_ParseJSON(cx.as_ptr(), chars.as_ptr(), chars.len() as u32, value.handle_mut().into()) } {
+			let report = ErrorReport::new(cx).unwrap();
+			return Err(Error::new("Unable to parse import map", ErrorKind::Syntax).with_cause(report.exception));
+		}
+		if !value.handle().is_object() {
+			return Err(Error::new("Import map must be an object", ErrorKind::Type));
+		}
+		let map = value.to_object(cx);
+
+		let imports = match map.get(cx, "imports").filter(|imports| !imports.handle().is_undefined()) {
+			Some(imports) if imports.handle().is_object() => sort_and_normalise_specifier_map(cx, &imports.to_object(cx), base),
+			Some(_) => return Err(Error::new("Import map \"imports\" must be an object", ErrorKind::Type)),
+			None => Vec::new(),
+		};
+
+		let scopes = match map.get(cx, "scopes").filter(|scopes| !scopes.handle().is_undefined()) {
+			Some(scopes) if scopes.handle().is_object() => sort_and_normalise_scopes(cx, &scopes.to_object(cx), base)?,
+			Some(_) => return Err(Error::new("Import map \"scopes\" must be an object", ErrorKind::Type)),
+			None => Vec::new(),
+		};
+
+		Ok(ImportMap { imports, scopes })
+	}
+
+	/// Resolves a specifier imported from `referrer` with the import map.
+	/// Returns [None] if no entry of the import map applies, or [Err] if resolution is blocked by the import map.
+	pub fn resolve(&self, specifier: &str, referrer: &Url) -> Result<Option<Url>, Error> {
+		let as_url = parse_url_like_specifier(specifier, referrer);
+		let normalised = as_url.as_ref().map(Url::to_string).unwrap_or_else(|| String::from(specifier));
+
+		let referrer = referrer.as_str();
+		for (prefix, map) in &self.scopes {
+			if prefix == referrer || (prefix.ends_with('/') && referrer.starts_with(prefix.as_str())) {
+				if let Some(url) = resolve_imports_match(&normalised, as_url.as_ref(), map)? {
+					return Ok(Some(url));
+				}
+			}
+		}
+
+		resolve_imports_match(&normalised, as_url.as_ref(), &self.imports)
+	}
+}
+
+fn parse_url_like_specifier(specifier: &str, base: &Url) -> Option<Url> {
+	if specifier.starts_with('/') || specifier.starts_with("./") || specifier.starts_with("../") {
+		parse_url(specifier, Some(base)).ok()
+	} else {
+		parse_url(specifier, None).ok()
+	}
+}
+
+fn string_keys(cx: &Context, object: &Object) -> Vec<String> {
+	object
+		.keys(cx, None)
+		.filter_map(|key| match key.to_owned_key(cx) {
+			OwnedKey::String(key) => Some(key),
+			OwnedKey::Int(key) => Some(key.to_string()),
+			_ => None,
+		})
+		.collect()
+}
+
+fn sort_and_normalise_specifier_map(cx: &Context, object: &Object, base: &Url) -> SpecifierMap {
+	let mut map = SpecifierMap::new();
+	for key in string_keys(cx, object) {
+		if key.is_empty() {
+			continue;
+		}
+		let normalised = parse_url_like_specifier(&key, base).map(String::from).unwrap_or_else(|| key.clone());
+
+		let address = object
+			.get(cx, key.as_str())
+			.and_then(|address| String::from_value(cx, &address, true, ()).ok())
+			.and_then(|address| parse_url_like_specifier(&address, base))
+			.filter(|address| !key.ends_with('/') || address.as_str().ends_with('/'));
+
+		map.retain(|(existing, _)| *existing != normalised);
+		map.push((normalised, address));
+	}
+
+	// Sorting in descending order places longer prefixes before the shorter prefixes they start with.
+	map.sort_by(|(a, _), (b, _)| b.cmp(a));
+	map
+}
+
+fn sort_and_normalise_scopes(cx: &Context, object: &Object, base: &Url) -> Result<Vec<(String, SpecifierMap)>, Error> {
+	let mut scopes = Vec::new();
+	for prefix in string_keys(cx, object) {
+		let map = object.get(cx, prefix.as_str()).filter(|map| map.handle().is_object());
+		let Some(map) = map else {
+			return Err(Error::new(&format!("Import map scope \"{}\" must be an object", prefix), ErrorKind::Type));
+		};
+		let Ok(prefix) = base.join(&prefix) else {
+			continue;
+		};
+		scopes.push((String::from(prefix), sort_and_normalise_specifier_map(cx, &map.to_object(cx), base)));
+	}
+
+	scopes.sort_by(|(a, _), (b, _)| b.cmp(a));
+	Ok(scopes)
+}
+
+fn resolve_imports_match(normalised: &str, as_url: Option<&Url>, map: &SpecifierMap) -> Result<Option<Url>, Error> {
+	for (key, address) in map {
+		if key == normalised {
+			return match address {
+				Some(address) => Ok(Some(address.clone())),
+				None => Err(blocked(normalised)),
+			};
+		}
+
+		let is_special = as_url
+			.map(|url| matches!(url.scheme(), "file" | "ftp" | "http" | "https" | "ws" | "wss"))
+			.unwrap_or(true);
+		if key.ends_with('/') && normalised.starts_with(key.as_str()) && is_special {
+			let Some(address) = address else {
+				return Err(blocked(normalised));
+			};
+			let after_prefix = &normalised[key.len()..];
+			let url = address.join(after_prefix).map_err(|_| blocked(normalised))?;
+			if !url.as_str().starts_with(address.as_str()) {
+				return Err(Error::new(
+					&format!("Specifier \"{}\" backtracks above its prefix \"{}\"", normalised, key),
+					ErrorKind::Type,
+				));
+			}
+			return Ok(Some(url));
+		}
+	}
+	Ok(None)
+}
+
+fn blocked(specifier: &str) -> Error {
+	Error::new(&format!("Specifier \"{}\" was blocked by the import map", specifier), ErrorKind::Type)
+}
@@ -5,79 +5,254 @@
  */
 
 use std::collections::hash_map::{Entry, HashMap};
-use std::ffi::OsStr;
+use std::env::current_dir;
 use std::fs::read_to_string;
-use std::path::Path;
+use std::path::{Component, Path, PathBuf};
 use std::ptr;
+use std::time::Instant;
 
 use dunce::canonicalize;
 use mozjs::jsapi::JSObject;
+use tracing::{debug, trace};
 use url::Url;
 
-use ion::{Context, Error, Object, Value};
+use ion::{Context, Error, ErrorKind, Object, PersistentRooted, Value};
 use ion::exception::ThrowException;
-use ion::module::{Module, ModuleData, ModuleLoader, ModuleRequest};
+use ion::module::{DynamicImport, Module, ModuleData, ModuleError, ModuleErrorKind, ModuleLoader, ModuleRequest};
+use ion::stencil::Stencil;
 
-use crate::cache::locate_in_cache;
-use crate::cache::map::save_sourcemap;
+use crate::cache::{locate_in_cache, locate_stencil};
+use crate::cache::map::{register_sourcemap_from_source, save_sourcemap};
 use crate::config::Config;
+#[cfg(feature = "fetch")]
+use crate::event_loop::handles::{ActiveHandle, HandleKind};
+use crate::modules::commonjs;
+use crate::modules::commonjs::is_commonjs;
+use crate::modules::import_map::ImportMap;
+use crate::modules::native::namespace_source;
+use crate::modules::package::{is_bare, resolve_package, ResolutionKind};
+use crate::modules::remote::{check_import, is_remote, locate_remote, remote_url, resolve_url};
+#[cfg(feature = "fetch")]
+use crate::modules::remote::fetch_imports;
+#[cfg(feature = "fetch")]
+use crate::promise::future_to_promise_with_handle;
+use crate::typescript::is_typescript;
+use crate::ContextExt;
 
 #[derive(Default)]
 pub struct Loader {
-	registry: HashMap<String, *mut JSObject>,
+	registry: HashMap<ModuleKey, PersistentRooted<*mut JSObject>>,
+	import_map: Option<ImportMap>,
 }
 
-impl ModuleLoader for Loader {
-	fn resolve(&mut self, cx: &Context, private: &Value, request: &ModuleRequest) -> *mut JSObject {
-		let specifier = request.specifier(cx).to_owned(cx);
-		let data = ModuleData::from_private(cx, private);
+impl Loader {
+	fn load(&mut self, cx: &Context, specifier: &str, path: &Path, module_type: Option<&str>) -> Option<*mut JSObject> {
+		if let Some(module_type) = module_type.filter(|module_type| *module_type != "json") {
+			Error::new(
+				&format!("Unsupported module type \"{}\" of module: {}", module_type, specifier),
+				ErrorKind::Type,
+			)
+			.throw(cx);
+			return None;
+		}
 
-		let path = if specifier.starts_with("./") || specifier.starts_with("../") {
-			Path::new(data.as_ref().and_then(|d| d.path.as_ref()).unwrap())
-				.parent()
-				.unwrap()
-				.join(&specifier)
-		} else {
-			Path::new(&specifier).to_path_buf()
+		let start = Instant::now();
+		let script = match read_to_string(path) {
+			Ok(script) => script,
+			Err(error) => {
+				Error::new(&format!("Unable to read module: {}", specifier), None)
+					.with_cause(error)
+					.throw(cx);
+				return None;
+			}
 		};
 
-		let str = String::from(path.to_str().unwrap());
-		self.registry
-			.get(&str)
-			.copied()
-			.or_else(|| {
-				if let Ok(script) = read_to_string(&path) {
-					let is_typescript = Config::global().typescript && path.extension() == Some(OsStr::new("ts"));
-					let (script, sourcemap) = is_typescript
-						.then(|| locate_in_cache(&path, &script))
-						.flatten()
-						.map(|(s, sm)| (s, Some(sm)))
-						.unwrap_or_else(|| (script, None));
-					if let Some(sourcemap) = sourcemap {
-						save_sourcemap(&path, sourcemap);
-					}
+		let module = if module_type == Some("json") {
+			Module::load_json(cx, Some(path), &script)
+		} else if is_commonjs(cx, path, &script) {
+			// CommonJS modules are evaluated when they are resolved, as their exports determine the names the namespace exports.
+			let exports = match commonjs::require(cx, path) {
+				Ok(exports) => exports,
+				Err(exception) => {
+					exception.throw(cx);
+					return None;
+				}
+			};
+			let source = namespace_source(cx, "import.meta.commonjs", &exports);
+			Stencil::compile_module(cx, path, &source)
+				.map_err(|report| ModuleError {
+					kind: ModuleErrorKind::Compilation,
+					report: Some(report),
+				})
+				.and_then(|stencil| Module::load(cx, Some(path), &stencil))
+		} else {
+			Loader::compile(cx, path, script)
+		};
 
-					let module = Module::compile(cx, &specifier, Some(path.as_path()), &script);
+		match module {
+			Ok(module) => {
+				debug!(
+					specifier,
+					path = %path.display(),
+					elapsed_ms = start.elapsed().as_secs_f64() * 1000.0,
+					"Loaded module"
+				);
+				let module = module.0.handle().get();
+				self.registry.insert(module_key(path, module_type), PersistentRooted::new(module));
+				Some(module)
+			}
+			Err(error) => {
+				debug!(specifier, path = %path.display(), "Failed to compile module");
+				// Uncatchable errors leave no exception to throw, and are propagated as they are.
+				if let Some(report) = error.report {
+					Error::new(&format!("Unable to compile module: {}", specifier), None)
+						.with_cause(report.exception)
+						.throw(cx);
+				}
+				None
+			}
+		}
+	}
 
-					if let Ok((module, _)) = module {
-						let request = ModuleRequest::new(cx, path.to_str().unwrap());
-						Some(self.register(cx, module.0.handle().get(), &request))
-					} else {
-						Error::new(&format!("Unable to compile module: {}\0", specifier), None).throw(cx);
-						None
-					}
-				} else {
-					Error::new(&format!("Unable to read module: {}", specifier), None).throw(cx);
+	/// Resolves a specifier to a path.
+	/// The import map is applied first, then `node_modules` is used for bare specifiers which are not registered standard modules.
+	/// Remote modules resolve to their downloaded source in the cache.
+	/// Returns [None] and throws an exception if the specifier cannot be resolved.
+	pub fn resolve_specifier(&mut self, cx: &Context, specifier: &str, importer: Option<&Path>) -> Option<PathBuf> {
+		if let Some(url) = self.resolve_url(cx, specifier, importer)? {
+			let path = if is_remote(&url) {
+				locate_remote(cx, &url).ok_or(format!("Remote module has not been fetched: {}", url))
+			} else {
+				url.to_file_path().map_err(|_| format!("Unsupported module URL: {}", url))
+			};
+			return match path {
+				Ok(path) => Some(path),
+				Err(message) => {
+					Error::new(&message, ErrorKind::Type).throw(cx);
 					None
 				}
-			})
-			.unwrap_or_else(ptr::null_mut)
+			};
+		}
+
+		let registered = self.registry.contains_key(&module_key(Path::new(specifier), None));
+		// Remote modules may only import registered modules by bare specifiers, as packages and paths are local.
+		let referrer = referrer_url(cx, importer).filter(is_remote);
+		if let (Some(referrer), false) = (referrer, registered) {
+			Error::new(
+				&format!("Remote module {} cannot import local module {}", referrer, specifier),
+				ErrorKind::Type,
+			)
+			.throw(cx);
+			return None;
+		}
+
+		if is_bare(specifier) && !registered {
+			if let Some(path) = resolve_package(cx, specifier, importer, ResolutionKind::Import) {
+				return Some(path);
+			}
+		}
+		Some(resolve_path(specifier, importer))
+	}
+
+	/// Resolves a specifier to a URL, if it is mapped by the import map, or refers to or is imported by a remote module.
+	/// Returns [Some] of [None] for other specifiers, which are resolved as paths or packages.
+	/// Returns [None] and throws an exception if the import map cannot be loaded, or a remote module imports a local module.
+	pub fn resolve_url(&mut self, cx: &Context, specifier: &str, importer: Option<&Path>) -> Option<Option<Url>> {
+		let referrer = referrer_url(cx, importer);
+		let url = match self.resolve_import_map(cx, specifier, referrer.as_ref())? {
+			Some(url) => Some(url),
+			// Specifiers of remote modules, and specifiers imported by them, are resolved as URLs.
+			None => referrer
+				.as_ref()
+				.filter(|referrer| is_remote(referrer))
+				.and_then(|referrer| resolve_url(specifier, referrer))
+				.or_else(|| Url::parse(specifier).ok().filter(is_remote)),
+		};
+
+		if let (Some(referrer), Some(url)) = (referrer.as_ref().filter(|referrer| is_remote(referrer)), &url) {
+			if let Err(error) = check_import(referrer, url) {
+				error.throw(cx);
+				return None;
+			}
+		}
+		Some(url)
+	}
+
+	/// Applies the import map from the configuration, which is loaded on first use.
+	fn resolve_import_map(&mut self, cx: &Context, specifier: &str, referrer: Option<&Url>) -> Option<Option<Url>> {
+		if self.import_map.is_none() {
+			let Some(path) = &Config::global().import_map else {
+				return Some(None);
+			};
+			match ImportMap::from_file(cx, path) {
+				Ok(import_map) => self.import_map = Some(import_map),
+				Err(error) => {
+					error.throw(cx);
+					return None;
+				}
+			}
+		}
+
+		let Some(referrer) = referrer else {
+			return Some(None);
+		};
+
+		match self.import_map.as_ref().unwrap().resolve(specifier, referrer) {
+			Ok(url) => Some(url),
+			Err(error) => {
+				error.throw(cx);
+				None
+			}
+		}
+	}
+
+	#[allow(clippy::result_large_err)]
+	fn compile<'cx>(cx: &'cx Context, path: &Path, script: String) -> Result<Module<'cx>, ModuleError> {
+		let is_typescript = is_typescript(path);
+		let (script, sourcemap) = is_typescript
+			.then(|| locate_in_cache(path, &script))
+			.flatten()
+			.map(|(s, sm)| (s, Some(sm)))
+			.unwrap_or_else(|| (script, None));
+		if let Some(sourcemap) = sourcemap {
+			save_sourcemap(path, sourcemap);
+		} else {
+			register_sourcemap_from_source(path, &script);
+		}
+
+		match locate_stencil(cx, path, &script, true) {
+			Ok(stencil) => Module::load(cx, Some(path), &stencil),
+			Err(report) => Err(ModuleError {
+				kind: ModuleErrorKind::Compilation,
+				report: Some(report),
+			}),
+		}
+	}
+}
+
+impl ModuleLoader for Loader {
+	fn resolve(&mut self, cx: &Context, private: &Value, request: &ModuleRequest) -> *mut JSObject {
+		let specifier = request.specifier(cx).to_owned(cx);
+		let data = ModuleData::from_private(cx, private);
+		let importer = data.as_ref().and_then(|data| data.path.as_deref()).map(Path::new);
+
+		let Some(path) = self.resolve_specifier(cx, &specifier, importer) else {
+			debug!(specifier = %specifier, importer = ?importer, "Failed to resolve module");
+			return ptr::null_mut();
+		};
+		trace!(specifier = %specifier, importer = ?importer, path = %path.display(), "Resolved module");
+		let module_type = request.assertion(cx, "type");
+		// Modules are registered before they are linked or evaluated, so cyclic imports resolve to the same module record.
+		match self.registry.get(&module_key(&path, module_type.as_deref())) {
+			Some(module) => module.get(),
+			None => self.load(cx, &specifier, &path, module_type.as_deref()).unwrap_or_else(ptr::null_mut),
+		}
 	}
 
 	fn register(&mut self, cx: &Context, module: *mut JSObject, request: &ModuleRequest) -> *mut JSObject {
 		let specifier = request.specifier(cx).to_owned(cx);
-		match self.registry.entry(specifier) {
-			Entry::Vacant(v) => *v.insert(module),
+		match self.registry.entry(module_key(&resolve_path(&specifier, None), None)) {
+			Entry::Vacant(v) => v.insert(PersistentRooted::new(module)).get(),
 			Entry::Occupied(_) => ptr::null_mut(),
 		}
 	}
@@ -87,12 +262,116 @@ impl ModuleLoader for Loader {
 
 		if let Some(data) = data {
 			if let Some(path) = data.path.as_ref() {
-				let url = Url::from_file_path(canonicalize(path).unwrap()).unwrap();
+				let path = Path::new(path);
+				let url = remote_url(cx, path).unwrap_or_else(|| Url::from_file_path(canonicalize(path).unwrap()).unwrap());
 				if !meta.set_as(cx, "url", url.as_str()) {
 					return false;
 				}
+				if let Some(exports) = commonjs::exports(cx, path) {
+					if !meta.set(cx, "commonjs", &exports) {
+						return false;
+					}
+				}
 			}
 		}
 		true
 	}
+
+	fn dynamic_import(&mut self, cx: &Context, import: DynamicImport) -> Option<DynamicImport> {
+		#[cfg(feature = "fetch")]
+		{
+			let specifier = import.specifier(cx);
+			let data = ModuleData::from_private(cx, &import.private(cx));
+			let referrer = referrer_url(cx, data.as_ref().and_then(|data| data.path.as_deref()).map(Path::new));
+			if let Some(referrer) = referrer {
+				let url = resolve_url(&specifier, &referrer).filter(is_remote);
+				if url.is_some_and(|url| locate_remote(cx, &url).is_none()) {
+					fetch_dynamic_import(cx, import, referrer, specifier);
+					return None;
+				}
+			}
+		}
+
+		// Imports are finished on the next turn of the event loop, as the hook runs while the importer is still executing.
+		let event_loop = unsafe { &mut (*cx.get_private().as_ptr()).event_loop };
+		event_loop.dynamic_imports.push_back(import);
+		None
+	}
+}
+
+/// Fetches the graph of a remote module imported dynamically, then queues the import to be finished.
+#[cfg(feature = "fetch")]
+fn fetch_dynamic_import(cx: &Context, import: DynamicImport, referrer: Url, specifier: String) {
+	let cx2 = unsafe { Context::new_unchecked(cx.as_ptr()) };
+	let handle = ActiveHandle::new(cx, HandleKind::Module, format!("import(\"{}\") from {}", specifier, referrer));
+	future_to_promise_with_handle(cx, handle, async move {
+		match fetch_imports(&cx2, &referrer, vec![specifier]).await {
+			Ok(()) => {
+				let event_loop = unsafe { &mut (*cx2.get_private().as_ptr()).event_loop };
+				event_loop.dynamic_imports.push_back(import);
+			}
+			Err(error) => {
+				error.throw(&cx2);
+				import.reject(&cx2);
+			}
+		}
+		Ok::<_, ()>(())
+	});
+}
+
+/// Resolves a module specifier to a path.
+///
+/// Relative specifiers (`./` and `../`) are resolved against the directory of the importing module, or the current directory if there is none.
+/// `file:` URLs are converted to paths, and other specifiers are treated as paths.
+pub fn resolve_path(specifier: &str, importer: Option<&Path>) -> PathBuf {
+	if specifier.starts_with("file:") {
+		if let Some(path) = Url::parse(specifier).ok().and_then(|url| url.to_file_path().ok()) {
+			return path;
+		}
+	}
+
+	if specifier.starts_with("./") || specifier.starts_with("../") {
+		let base = importer.and_then(Path::parent).map(Path::to_path_buf).unwrap_or_default();
+		base.join(specifier)
+	} else {
+		PathBuf::from(specifier)
+	}
+}
+
+/// Returns the URL of the importing module, or of the current directory if there is none.
+fn referrer_url(cx: &Context, importer: Option<&Path>) -> Option<Url> {
+	match importer {
+		Some(importer) => {
+			remote_url(cx, importer).or_else(|| Url::from_file_path(canonicalize(importer).unwrap_or_else(|_| normalise(importer))).ok())
+		}
+		None => current_dir().ok().and_then(|dir| Url::from_directory_path(dir).ok()),
+	}
+}
+
+/// Identifies a module in the registry by its path and its type, such as `"json"`.
+type ModuleKey = (PathBuf, Option<String>);
+
+/// Returns the key of a module in the registry, so that different paths to the same file share a single module record.
+/// Importing a file with a different type creates a separate module record.
+fn module_key(path: &Path, module_type: Option<&str>) -> ModuleKey {
+	let path = canonicalize(path).unwrap_or_else(|_| normalise(path));
+	(path, module_type.map(String::from))
+}
+
+fn normalise(path: &Path) -> PathBuf {
+	let mut normalised = PathBuf::new();
+	for component in path.components() {
+		match component {
+			Component::CurDir => {}
+			Component::ParentDir => {
+				if matches!(normalised.components().next_back(), Some(Component::Normal(_))) {
+					normalised.pop();
+				} else {
+					normalised.push(component);
+				}
+			}
+			component => normalised.push(component),
+		}
+	}
+	normalised
 }
@@ -4,8 +4,18 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/.
  */
 
+pub use import_map::ImportMap;
 pub use loader::*;
+pub use native::{CustomModule, init_custom_module};
+pub use package::{is_bare, resolve_package, ResolutionKind};
+pub use remote::{is_remote, locate_remote, remote_url};
 pub use standard::*;
 
+pub mod commonjs;
+pub mod graph;
+pub mod import_map;
 pub mod loader;
+pub mod native;
+pub mod package;
+pub mod remote;
 pub mod standard;
@@ -0,0 +1,156 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use ion::{Context, Object, OwnedKey, Value};
+use ion::flags::PropertyFlags;
+use ion::module::{Module, ModuleRequest};
+
+/// Represents a module implemented in Rust, which an application embedding the runtime registers with
+/// [RuntimeBuilder::register_module](crate::RuntimeBuilder::register_module).
+///
+/// Unlike [NativeModule](crate::modules::NativeModule), the name and exports of the module are determined at runtime,
+/// and the module can hold state.
+pub trait CustomModule {
+	/// Initialises the module. Called once when the runtime is built, before [CustomModule::exports].
+	/// Returning `false` prevents the module from being registered.
+	fn init(&mut self, _: &Context) -> bool {
+		true
+	}
+
+	/// Creates the exports of the module.
+	/// Its own enumerable properties become named exports, and the object itself is the default export.
+	fn exports<'cx>(&mut self, cx: &'cx Context) -> Option<Object<'cx>>;
+}
+
+/// Initialises a custom module, and registers it with the module loader under `name`.
+/// Without a module loader, the exports are defined as a global instead.
+pub fn init_custom_module(cx: &Context, global: &mut Object, name: &str, module: &mut dyn CustomModule, has_loader: bool) -> bool {
+	if !module.init(cx) {
+		return false;
+	}
+	let Some(exports) = module.exports(cx) else {
+		return false;
+	};
+
+	if !has_loader {
+		return global.define_as(cx, name, &exports, PropertyFlags::CONSTANT_ENUMERATED);
+	}
+
+	let internal = format!("______{}Internal______", name);
+	if !global.define_as(cx, &internal, &exports, PropertyFlags::CONSTANT) {
+		return false;
+	}
+	// The name of the module may not be an identifier, such as `@scope/module`, so the global is accessed with a string literal.
+	let source = namespace_source(cx, &format!("globalThis[{}]", string_literal(&internal)), &exports.as_value(cx));
+	let Ok((module, _)) = Module::compile(cx, name, None, &source) else {
+		return false;
+	};
+
+	let loader = unsafe { &mut (*cx.get_inner_data().as_ptr()).module_loader };
+	loader.as_mut().is_some_and(|loader| {
+		let request = ModuleRequest::new(cx, name);
+		loader.register(cx, module.0.handle().get(), &request);
+		true
+	})
+}
+
+/// Creates the source of an ES module which re-exports the value of `expression`.
+///
+/// The value is the default export, and its own enumerable properties which are valid identifiers, and are not reserved words, are named exports.
+pub fn namespace_source(cx: &Context, expression: &str, exports: &Value) -> String {
+	let mut source = format!("export default {};\n", expression);
+
+	if exports.handle().is_object() {
+		let names: Vec<String> = exports
+			.to_object(cx)
+			.keys(cx, None)
+			.filter_map(|key| match key.to_owned_key(cx) {
+				OwnedKey::String(name) => Some(name),
+				_ => None,
+			})
+			.filter(|name| name != "default" && is_identifier(name))
+			.collect();
+		if !names.is_empty() {
+			source.push_str(&format!("export const {{ {} }} = {};\n", names.join(", "), expression));
+		}
+	}
+	source
+}
+
+/// Quotes a string as a JavaScript string literal.
+fn string_literal(string: &str) -> String {
+	let mut literal = String::with_capacity(string.len() + 2);
+	literal.push('"');
+	for char in string.chars() {
+		match char {
+			'"' => literal.push_str("\\\""),
+			'\\' => literal.push_str("\\\\"),
+			char if char.is_control() || char == '\u{2028}' || char == '\u{2029}' => literal.push_str(&format!("\\u{{{:x}}}", char as u32)),
+			char => literal.push(char),
+		}
+	}
+	literal.push('"');
+	literal
+}
+
+/// Reserved words, and names which cannot be bound in strict mode code, which cannot be the names of exports declared with `const`.
+const RESERVED_WORDS: &[&str] = &[
+	"arguments",
+	"await",
+	"break",
+	"case",
+	"catch",
+	"class",
+	"const",
+	"continue",
+	"debugger",
+	"default",
+	"delete",
+	"do",
+	"else",
+	"enum",
+	"eval",
+	"export",
+	"extends",
+	"false",
+	"finally",
+	"for",
+	"function",
+	"if",
+	"implements",
+	"import",
+	"in",
+	"instanceof",
+	"interface",
+	"let",
+	"new",
+	"null",
+	"package",
+	"private",
+	"protected",
+	"public",
+	"return",
+	"static",
+	"super",
+	"switch",
+	"this",
+	"throw",
+	"true",
+	"try",
+	"typeof",
+	"var",
+	"void",
+	"while",
+	"with",
+	"yield",
+];
+
+fn is_identifier(name: &str) -> bool {
+	let mut chars = name.chars();
+	chars.next().is_some_and(|first| first.is_alphabetic() || first == '_' || first == '$')
+		&& chars.all(|char| char.is_alphanumeric() || char == '_' || char == '$')
+		&& !RESERVED_WORDS.contains(&name)
+}
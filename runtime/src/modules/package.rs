@@ -0,0 +1,233 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::env::current_dir;
+use std::fs::read_to_string;
+use std::path::{Path, PathBuf};
+
+use dunce::canonicalize;
+use mozjs::jsapi::JS_ParseJSON;
+use url::Url;
+
+use ion::{Array, Context, Exception, Object, OwnedKey, Value};
+use ion::conversions::FromValue;
+
+/// Represents how a package is being loaded, which determines the conditional `exports` it matches and the file extensions that are tried.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ResolutionKind {
+	Import,
+	Require,
+}
+
+impl ResolutionKind {
+	/// Returns the conditions matched against conditional `exports` of packages, in addition to `default`.
+	pub fn conditions(self) -> &'static [&'static str] {
+		match self {
+			ResolutionKind::Import => &["spiderfire", "import", "module"],
+			ResolutionKind::Require => &["spiderfire", "require", "node"],
+		}
+	}
+
+	/// Returns the extensions tried when a path does not refer to a file.
+	pub fn extensions(self) -> &'static [&'static str] {
+		match self {
+			ResolutionKind::Import => &["js", "mjs"],
+			ResolutionKind::Require => &["js", "cjs", "json"],
+		}
+	}
+}
+
+/// Checks if a specifier is bare, such as `lodash` or `@scope/package/subpath`.
+/// Relative specifiers, absolute paths and URLs are not bare.
+pub fn is_bare(specifier: &str) -> bool {
+	!(specifier.is_empty()
+		|| specifier.starts_with("./")
+		|| specifier.starts_with("../")
+		|| specifier.starts_with('/')
+		|| Path::new(specifier).is_absolute()
+		|| Url::parse(specifier).is_ok())
+}
+
+/// Resolves a bare specifier to a file within a package.
+///
+/// `node_modules` directories are searched from the directory of the importing module, or the current directory, up to the root.
+/// The `exports` field of the package's `package.json` is used if present, otherwise `module`, `main` and `index.js` are tried.
+pub fn resolve_package(cx: &Context, specifier: &str, importer: Option<&Path>, kind: ResolutionKind) -> Option<PathBuf> {
+	let (name, subpath) = split_specifier(specifier)?;
+
+	let base = match importer.and_then(Path::parent) {
+		Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+		_ => current_dir().ok()?,
+	};
+	let base = canonicalize(&base).unwrap_or(base);
+
+	base.ancestors()
+		.map(|directory| directory.join("node_modules").join(name))
+		.filter(|package| package.is_dir())
+		.find_map(|package| resolve_in_package(cx, &package, &subpath, kind))
+}
+
+/// Splits a bare specifier into the package name and the subpath within the package, which is `.` for the package itself.
+fn split_specifier(specifier: &str) -> Option<(&str, String)> {
+	let mut separators = specifier.match_indices('/').map(|(index, _)| index);
+	let end = if specifier.starts_with('@') {
+		separators.nth(1)
+	} else {
+		separators.next()
+	};
+
+	match end {
+		Some(end) => Some((&specifier[..end], format!(".{}", &specifier[end..]))),
+		None if specifier.starts_with('@') && !specifier.contains('/') => None,
+		None => Some((specifier, String::from("."))),
+	}
+}
+
+fn resolve_in_package(cx: &Context, package: &Path, subpath: &str, kind: ResolutionKind) -> Option<PathBuf> {
+	let manifest = read_manifest(cx, &package.join("package.json"));
+
+	if let Some(manifest) = &manifest {
+		if let Some(exports) = manifest.get(cx, "exports").filter(|exports| !exports.handle().is_null_or_undefined()) {
+			// Packages with `exports` cannot be imported through any other subpaths.
+			return resolve_exports(cx, &exports, subpath, kind)
+				.map(|target| package.join(target))
+				.filter(|path| path.is_file())
+				.and_then(|path| within_package(package, path));
+		}
+	}
+
+	let path = if subpath == "." {
+		let entry = manifest.and_then(|manifest| {
+			["module", "main"]
+				.into_iter()
+				.find_map(|field| manifest.get_as::<_, String>(cx, field, true, ()))
+		});
+		entry
+			.and_then(|entry| resolve_file(&package.join(entry), kind))
+			.or_else(|| resolve_file(&package.join("index.js"), kind))
+	} else {
+		resolve_file(&package.join(subpath), kind)
+	};
+	path.and_then(|path| within_package(package, path))
+}
+
+/// Returns the path if it is within the package, after symbolic links and `..` segments are resolved.
+fn within_package(package: &Path, path: PathBuf) -> Option<PathBuf> {
+	let root = canonicalize(package).ok()?;
+	let resolved = canonicalize(&path).ok()?;
+	resolved.starts_with(&root).then_some(path)
+}
+
+pub(crate) fn read_manifest<'cx>(cx: &'cx Context, path: &Path) -> Option<Object<'cx>> {
+	let manifest = read_to_string(path).ok()?;
+	let chars: Vec<u16> = manifest.encode_utf16().collect();
+
+	let mut value = Value::undefined(cx);
+	if !unsafe { JS_ParseJSON(cx.as_ptr(), chars.as_ptr(), chars.len() as u32, value.handle_mut().into()) } {
+		Exception::clear(cx);
+		return None;
+	}
+	value.handle().is_object().then(|| value.to_object(cx))
+}
+
+/// Resolves a subpath of a package with its `exports` field.
+/// Supports subpath exports, subpath patterns with a single `*`, and nested conditions.
+fn resolve_exports(cx: &Context, exports: &Value, subpath: &str, kind: ResolutionKind) -> Option<String> {
+	if !exports.handle().is_object() || Array::from(cx, exports.to_object(cx).into_local()).is_some() {
+		return (subpath == ".").then(|| resolve_target(cx, exports, None, kind)).flatten();
+	}
+
+	let exports = exports.to_object(cx);
+	let keys: Vec<String> = exports
+		.keys(cx, None)
+		.filter_map(|key| match key.to_owned_key(cx) {
+			OwnedKey::String(key) => Some(key),
+			_ => None,
+		})
+		.collect();
+
+	// Objects whose keys are conditions rather than subpaths only export the package itself.
+	if !keys.iter().any(|key| key.starts_with('.')) {
+		return (subpath == ".").then(|| resolve_target(cx, &exports.as_value(cx), None, kind)).flatten();
+	}
+
+	if let Some(target) = keys.iter().find(|key| *key == subpath).and_then(|key| exports.get(cx, key.as_str())) {
+		return resolve_target(cx, &target, None, kind);
+	}
+
+	let (key, replacement) = keys
+		.iter()
+		.filter_map(|key| {
+			let (prefix, suffix) = key.split_once('*')?;
+			let replacement = subpath.strip_prefix(prefix)?.strip_suffix(suffix)?;
+			Some((key, prefix.len(), replacement))
+		})
+		.max_by_key(|(_, prefix, _)| *prefix)
+		.map(|(key, _, replacement)| (key, replacement))?;
+	let target = exports.get(cx, key.as_str())?;
+	resolve_target(cx, &target, Some(replacement), kind)
+}
+
+fn resolve_target(cx: &Context, target: &Value, replacement: Option<&str>, kind: ResolutionKind) -> Option<String> {
+	let handle = target.handle();
+	if handle.is_string() {
+		let target = String::from_value(cx, target, true, ()).ok()?;
+		if !target.starts_with("./") || has_invalid_segments(&target[2..]) {
+			return None;
+		}
+		return match replacement {
+			Some(replacement) if has_invalid_segments(replacement) => None,
+			Some(replacement) => Some(target.replace('*', replacement)),
+			None => Some(target),
+		};
+	}
+	if !handle.is_object() {
+		return None;
+	}
+
+	let object = target.to_object(cx);
+	if let Some(array) = Array::from(cx, target.to_object(cx).into_local()) {
+		return (0..array.len(cx)).find_map(|index| {
+			let target = array.get(cx, index)?;
+			resolve_target(cx, &target, replacement, kind)
+		});
+	}
+
+	object.keys(cx, None).find_map(|key| {
+		let OwnedKey::String(condition) = key.to_owned_key(cx) else {
+			return None;
+		};
+		if condition != "default" && !kind.conditions().contains(&condition.as_str()) {
+			return None;
+		}
+		let target = object.get(cx, condition.as_str())?;
+		resolve_target(cx, &target, replacement, kind)
+	})
+}
+
+/// Checks if a path within a package has `.`, `..` or `node_modules` segments, which are not allowed in targets of `exports`.
+fn has_invalid_segments(path: &str) -> bool {
+	path.split(['/', '\\'])
+		.any(|segment| segment == "." || segment == ".." || segment.eq_ignore_ascii_case("node_modules"))
+}
+
+/// Resolves a path to a file, trying the extensions of the [ResolutionKind], then `index.js` for directories.
+pub(crate) fn resolve_file(path: &Path, kind: ResolutionKind) -> Option<PathBuf> {
+	if path.is_file() {
+		return Some(path.to_path_buf());
+	}
+	for extension in kind.extensions() {
+		let mut file = path.as_os_str().to_owned();
+		file.push(".");
+		file.push(extension);
+		let file = PathBuf::from(file);
+		if file.is_file() {
+			return Some(file);
+		}
+	}
+	let index = path.join("index.js");
+	index.is_file().then_some(index)
+}
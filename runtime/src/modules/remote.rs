@@ -0,0 +1,239 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::path::{Path, PathBuf};
+
+use url::Url;
+
+use ion::{Context, Error, ErrorKind};
+
+use crate::cache::Cache;
+use crate::config::Config;
+use crate::ContextExt;
+use crate::globals::url::parse_url;
+
+/// Checks if a URL refers to a remote module, which has to be fetched before it can be imported.
+pub fn is_remote(url: &Url) -> bool {
+	matches!(url.scheme(), "http" | "https")
+}
+
+/// Resolves a specifier imported from `referrer` to a URL.
+/// Returns [None] for bare specifiers.
+pub fn resolve_url(specifier: &str, referrer: &Url) -> Option<Url> {
+	if specifier.starts_with('/') || specifier.starts_with("./") || specifier.starts_with("../") {
+		parse_url(specifier, Some(referrer)).ok()
+	} else {
+		parse_url(specifier, None).ok()
+	}
+}
+
+/// Returns the path of a remote module in the cache.
+///
+/// Modules fetched by the runtime are always found.
+/// Otherwise, modules downloaded by a previous run are reused unless reloading was requested.
+pub fn locate_remote(cx: &Context, url: &Url) -> Option<PathBuf> {
+	let modules = unsafe { &mut (*cx.get_private().as_ptr()).remote_modules };
+	if let Some(path) = modules.get(url) {
+		return Some(path.clone());
+	}
+	if Config::global().reload {
+		return None;
+	}
+
+	let (response_url, path) = Cache::new()?.check_remote(url)?;
+	register_remote(cx, url, response_url, &path);
+	Some(path)
+}
+
+/// Returns the URL of a remote module after redirects from its path in the cache.
+pub fn remote_url(cx: &Context, path: &Path) -> Option<Url> {
+	let urls = unsafe { &(*cx.get_private().as_ptr()).remote_urls };
+	urls.get(path).cloned()
+}
+
+/// Records the path of a remote module requested from `url`, and the URL it was redirected to.
+fn register_remote(cx: &Context, url: &Url, response_url: Url, path: &Path) {
+	let private = unsafe { &mut *cx.get_private().as_ptr() };
+	private.remote_modules.insert(url.clone(), path.to_path_buf());
+	private.remote_modules.insert(response_url.clone(), path.to_path_buf());
+	private.remote_urls.insert(path.to_path_buf(), response_url);
+}
+
+/// Checks that a module imported by `referrer` may be imported.
+/// Remote modules cannot import local modules, as they could read arbitrary files.
+pub fn check_import(referrer: &Url, url: &Url) -> Result<(), Error> {
+	if is_remote(referrer) && !is_remote(url) {
+		return Err(Error::new(
+			&format!("Remote module {} cannot import local module {}", referrer, url),
+			ErrorKind::Type,
+		));
+	}
+	Ok(())
+}
+
+#[cfg(feature = "fetch")]
+pub use fetch::{fetch_imports, fetch_module_imports, fetch_remote};
+
+#[cfg(feature = "fetch")]
+mod fetch {
+	use std::collections::HashSet;
+	use std::path::{Path, PathBuf};
+	use std::time::Instant;
+
+	use dunce::canonicalize;
+	use hyper::{body, Uri};
+	use hyper::header::{CONTENT_TYPE, LOCATION};
+	use mime::Mime;
+	use tracing::debug;
+	use url::Url;
+
+	use ion::{Context, Error};
+	use ion::module::Module;
+	use ion::stencil::Stencil;
+
+	use crate::cache::Cache;
+	use crate::config::Config;
+	use crate::globals::fetch::{default_client, GLOBAL_CLIENT};
+	use crate::ContextExt;
+	use crate::modules::graph::requested_specifiers;
+	use crate::modules::remote::{check_import, is_remote, locate_remote, register_remote, remote_url, resolve_url};
+	use crate::permissions::check_url;
+
+	const MAX_REDIRECTS: usize = 20;
+
+	/// Fetches a remote module, or returns its path if it is already cached.
+	/// Modules which are not cached cannot be fetched if only cached modules are allowed.
+	///
+	/// Importing a remote module requires network access to its host, even if it is cached.
+	pub async fn fetch_remote(cx: &Context, url: &Url) -> Result<PathBuf, Error> {
+		check_url(url)?;
+		if let Some(path) = locate_remote(cx, url) {
+			return Ok(path);
+		}
+		if Config::global().cached_only {
+			return Err(Error::new(
+				&format!("Remote module {} is not cached, and cannot be fetched with --cached-only", url),
+				None,
+			));
+		}
+
+		let start = Instant::now();
+		let (response_url, source) = download(url).await?;
+		debug!(url = %url, elapsed_ms = start.elapsed().as_secs_f64() * 1000.0, "Fetched remote module");
+		let cache = Cache::new().ok_or_else(|| Error::new("Unable to locate the cache for remote modules", None))?;
+		let path = cache
+			.save_remote(url, &response_url, &source)
+			.map_err(|error| Error::new(&format!("Unable to cache module {}: {}", url, error), None))?;
+
+		register_remote(cx, url, response_url, &path);
+		Ok(path)
+	}
+
+	/// Fetches the remote modules in the graph of static imports of a module, so that they can be resolved synchronously when it is linked.
+	///
+	/// Local modules in the graph are followed, as they may import remote modules. Bare specifiers are not followed.
+	/// Imports of remote modules are resolved against their URL after redirects, and cannot refer to local modules.
+	pub async fn fetch_imports(cx: &Context, referrer: &Url, specifiers: Vec<String>) -> Result<(), Error> {
+		let mut visited = HashSet::new();
+		let mut queue = vec![(referrer.clone(), specifiers)];
+
+		while let Some((referrer, specifiers)) = queue.pop() {
+			for specifier in specifiers {
+				let Some(url) = resolve_url(&specifier, &referrer) else {
+					continue;
+				};
+				check_import(&referrer, &url)?;
+				if !visited.insert(url.clone()) {
+					continue;
+				}
+
+				let (url, path) = if is_remote(&url) {
+					let path = fetch_remote(cx, &url).await?;
+					(remote_url(cx, &path).unwrap_or(url), path)
+				} else if let Ok(path) = url.to_file_path() {
+					(url, path)
+				} else {
+					continue;
+				};
+
+				if let Some(specifiers) = requested_specifiers(cx, &path) {
+					queue.push((url, specifiers));
+				}
+			}
+		}
+		Ok(())
+	}
+
+	/// Fetches the remote modules in the graph of a local module, which has been compiled but not linked.
+	pub async fn fetch_module_imports(cx: &Context, path: &Path, stencil: &Stencil) -> Result<(), Error> {
+		let path = canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+		let referrer = Url::from_file_path(&path).map_err(|_| Error::new(&format!("Invalid module path: {}", path.display()), None))?;
+		let module = stencil
+			.to_module(cx)
+			.map_err(|report| Error::new(&format!("Unable to instantiate module: {}", path.display()), None).with_cause(report.exception))?;
+
+		let specifiers = Module(module).requested_specifiers(cx);
+		fetch_imports(cx, &referrer, specifiers).await
+	}
+
+	/// Downloads a remote module, following redirects to hosts which network access is granted to.
+	/// Returns the URL after redirects, and the source of the module.
+	async fn download(url: &Url) -> Result<(Url, String), Error> {
+		let client = GLOBAL_CLIENT.get().cloned().unwrap_or_else(default_client);
+		let mut url = url.clone();
+
+		for _ in 0..MAX_REDIRECTS {
+			check_url(&url)?;
+			let uri: Uri = url
+				.as_str()
+				.parse()
+				.map_err(|_| Error::new(&format!("Invalid module URL: {}", url), None))?;
+			let response = client
+				.get(uri)
+				.await
+				.map_err(|error| Error::new(&format!("Unable to fetch module {}: {}", url, error), None))?;
+
+			let status = response.status();
+			if status.is_redirection() {
+				let location = response.headers().get(LOCATION).and_then(|location| location.to_str().ok());
+				url = location
+					.and_then(|location| url.join(location).ok())
+					.ok_or_else(|| Error::new(&format!("Invalid redirect while fetching module: {}", url), None))?;
+				continue;
+			}
+			if !status.is_success() {
+				return Err(Error::new(&format!("Unable to fetch module {}: {}", url, status), None));
+			}
+
+			let content_type = response.headers().get(CONTENT_TYPE).and_then(|content_type| content_type.to_str().ok());
+			if !content_type.map(is_module_content_type).unwrap_or(false) {
+				return Err(Error::new(
+					&format!("Unsupported content type of module {}: {}", url, content_type.unwrap_or("none")),
+					None,
+				));
+			}
+
+			let bytes = body::to_bytes(response.into_body())
+				.await
+				.map_err(|error| Error::new(&format!("Unable to fetch module {}: {}", url, error), None))?;
+			let source = String::from_utf8(bytes.to_vec()).map_err(|_| Error::new(&format!("Module {} is not valid UTF-8", url), None))?;
+			return Ok((url, source));
+		}
+
+		Err(Error::new(&format!("Too many redirects while fetching module: {}", url), None))
+	}
+
+	fn is_module_content_type(content_type: &str) -> bool {
+		let Ok(mime) = content_type.parse::<Mime>() else {
+			return false;
+		};
+		matches!(mime.type_().as_str(), "application" | "text")
+			&& matches!(
+				mime.subtype().as_str(),
+				"javascript" | "x-javascript" | "ecmascript" | "typescript" | "x-typescript" | "json"
+			)
+	}
+}
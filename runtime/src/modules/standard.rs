@@ -8,10 +8,17 @@ use ion::{Context, Object};
 use ion::flags::PropertyFlags;
 use ion::module::{Module, ModuleRequest};
 
+use crate::snapshot::Snapshot;
+
 pub trait StandardModules {
 	fn init(self, cx: &Context, global: &mut Object) -> bool;
 
 	fn init_globals(self, cx: &Context, global: &mut Object) -> bool;
+
+	/// Adds the sources of the modules to a [Snapshot], so that they can be loaded without compilation.
+	fn snapshot(&self, _: &Context, _: &mut Snapshot) -> bool {
+		true
+	}
 }
 
 impl StandardModules for () {
@@ -39,6 +46,10 @@ impl<M: NativeModule> StandardModules for M {
 	fn init_globals(self, cx: &Context, global: &mut Object) -> bool {
 		init_global_module::<M>(cx, global)
 	}
+
+	fn snapshot(&self, cx: &Context, snapshot: &mut Snapshot) -> bool {
+		snapshot_module::<M>(cx, snapshot)
+	}
 }
 
 // TODO: Remove JS Wrapper, Stop Global Scope Pollution, Use CreateEmptyModule and AddModuleExport
@@ -49,7 +60,11 @@ pub fn init_module<M: NativeModule>(cx: &Context, global: &mut Object) -> bool {
 
 	if let Some(module) = module {
 		if global.define_as(cx, &internal, &module, PropertyFlags::CONSTANT) {
-			let (module, _) = Module::compile(cx, M::NAME, None, M::SOURCE).unwrap();
+			let stencil = Snapshot::global().and_then(|snapshot| snapshot.stencil(cx, M::NAME, M::SOURCE, true));
+			let (module, _) = match stencil {
+				Some(stencil) => Module::from_stencil(cx, None, &stencil).unwrap(),
+				None => Module::compile(cx, M::NAME, None, M::SOURCE).unwrap(),
+			};
 			let loader = unsafe { &mut (*cx.get_inner_data().as_ptr()).module_loader };
 			return loader.as_mut().is_some_and(|loader| {
 				let request = ModuleRequest::new(cx, M::NAME);
@@ -61,6 +76,10 @@ pub fn init_module<M: NativeModule>(cx: &Context, global: &mut Object) -> bool {
 	false
 }
 
+pub fn snapshot_module<M: NativeModule>(cx: &Context, snapshot: &mut Snapshot) -> bool {
+	snapshot.add_module(cx, M::NAME, M::SOURCE).is_ok()
+}
+
 pub fn init_global_module<M: NativeModule>(cx: &Context, global: &mut Object) -> bool {
 	let module = M::module(cx);
 
@@ -0,0 +1,133 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+#[cfg(feature = "debugmozjs")]
+use mozjs::jsapi::JS_SetGCZeal;
+use mozjs::jsapi::{ContextOptionsRef, JS_SetGlobalJitCompilerOption, JSJitCompilerOption, WeakRefSpecifier};
+use mozjs::rust::RealmOptions;
+
+use ion::Context;
+
+/// Toggles for engine features, applied when a [Runtime](crate::Runtime) is built.
+#[derive(Clone, Copy, Debug)]
+pub struct ContextOptions {
+	pub weak_refs: bool,
+	pub shared_memory: bool,
+	pub import_assertions: bool,
+	pub wasm: bool,
+	pub baseline_interpreter: bool,
+	pub baseline_jit: bool,
+	pub optimizing_jit: bool,
+	/// GC Zeal level and frequency, only available in debug builds of SpiderMonkey.
+	pub gc_zeal: Option<(u8, u32)>,
+	/// Defines `globalThis.gc()` to force a garbage collection.
+	pub expose_gc: bool,
+}
+
+impl ContextOptions {
+	pub fn weak_refs(self, weak_refs: bool) -> ContextOptions {
+		ContextOptions { weak_refs, ..self }
+	}
+
+	pub fn shared_memory(self, shared_memory: bool) -> ContextOptions {
+		ContextOptions { shared_memory, ..self }
+	}
+
+	pub fn import_assertions(self, import_assertions: bool) -> ContextOptions {
+		ContextOptions { import_assertions, ..self }
+	}
+
+	pub fn wasm(self, wasm: bool) -> ContextOptions {
+		ContextOptions { wasm, ..self }
+	}
+
+	pub fn baseline_interpreter(self, baseline_interpreter: bool) -> ContextOptions {
+		ContextOptions { baseline_interpreter, ..self }
+	}
+
+	pub fn baseline_jit(self, baseline_jit: bool) -> ContextOptions {
+		ContextOptions { baseline_jit, ..self }
+	}
+
+	pub fn optimizing_jit(self, optimizing_jit: bool) -> ContextOptions {
+		ContextOptions { optimizing_jit, ..self }
+	}
+
+	/// Disables all JIT tiers, so that scripts only run in the interpreter.
+	pub fn no_jit(self) -> ContextOptions {
+		ContextOptions {
+			baseline_interpreter: false,
+			baseline_jit: false,
+			optimizing_jit: false,
+			..self
+		}
+	}
+
+	pub fn gc_zeal(self, level: u8, frequency: u32) -> ContextOptions {
+		ContextOptions {
+			gc_zeal: Some((level, frequency)),
+			..self
+		}
+	}
+
+	pub fn expose_gc(self, expose_gc: bool) -> ContextOptions {
+		ContextOptions { expose_gc, ..self }
+	}
+
+	pub(crate) fn realm_options(&self) -> RealmOptions {
+		let mut realm_options = RealmOptions::default();
+		let creation_options = &mut realm_options.creationOptions_;
+		creation_options.sharedMemoryAndAtomics_ = self.shared_memory;
+		creation_options.weakRefs_ = if self.weak_refs {
+			WeakRefSpecifier::EnabledWithoutCleanupSome
+		} else {
+			WeakRefSpecifier::Disabled
+		};
+		creation_options.importAssertions_ = self.import_assertions;
+		realm_options
+	}
+
+	pub(crate) fn apply(&self, cx: &Context) {
+		let options = unsafe { &mut *ContextOptionsRef(cx.as_ptr()) };
+		options.set_wasm_(self.wasm);
+		options.set_wasmBaseline_(self.wasm);
+		options.set_wasmIon_(self.wasm);
+
+		let jit_options = [
+			(JSJitCompilerOption::JSJITCOMPILER_BASELINE_INTERPRETER_ENABLE, self.baseline_interpreter),
+			(JSJitCompilerOption::JSJITCOMPILER_BASELINE_ENABLE, self.baseline_jit),
+			(JSJitCompilerOption::JSJITCOMPILER_ION_ENABLE, self.optimizing_jit),
+		];
+		for (option, enabled) in jit_options {
+			unsafe {
+				JS_SetGlobalJitCompilerOption(cx.as_ptr(), option, enabled as u32);
+			}
+		}
+
+		#[cfg(feature = "debugmozjs")]
+		if let Some((level, frequency)) = self.gc_zeal {
+			unsafe {
+				JS_SetGCZeal(cx.as_ptr(), level, frequency);
+			}
+		}
+	}
+}
+
+impl Default for ContextOptions {
+	fn default() -> ContextOptions {
+		ContextOptions {
+			weak_refs: true,
+			shared_memory: true,
+			import_assertions: false,
+			wasm: true,
+			baseline_interpreter: true,
+			baseline_jit: true,
+			optimizing_jit: true,
+			gc_zeal: None,
+			expose_gc: false,
+		}
+	}
+}
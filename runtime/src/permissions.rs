@@ -0,0 +1,426 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::env::current_dir;
+use std::fmt::{Display, Formatter};
+use std::io::{BufRead, IsTerminal, stderr, stdin, Write};
+use std::net::IpAddr;
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Mutex, MutexGuard, OnceLock};
+use std::{fmt, io};
+
+use dunce::canonicalize;
+use url::Url;
+
+use ion::{Error, ErrorKind, Result};
+
+use crate::config::CONFIG;
+
+static PERMISSIONS: OnceLock<Mutex<Permissions>> = OnceLock::new();
+/// Held while the user is prompted, so that only one prompt is shown at a time.
+static PROMPT: Mutex<()> = Mutex::new(());
+
+/// Represents a kind of access which scripts must be granted before they can use it.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum PermissionName {
+	/// Reading files and directories, whose resources are paths.
+	Read,
+	/// Writing files and directories, whose resources are paths.
+	Write,
+	/// Connecting to and listening on hosts, whose resources are hostnames optionally followed by a port.
+	Net,
+	/// Reading and writing environment variables, whose resources are their names.
+	Env,
+	/// Running subprocesses, whose resources are the names or paths of programs.
+	Run,
+	/// Loading native libraries with the `ffi` module.
+	Ffi,
+	/// Measuring time with high resolution. Without it, `performance.now()` is coarsened.
+	Hrtime,
+}
+
+impl PermissionName {
+	pub const ALL: [PermissionName; 7] = [
+		PermissionName::Read,
+		PermissionName::Write,
+		PermissionName::Net,
+		PermissionName::Env,
+		PermissionName::Run,
+		PermissionName::Ffi,
+		PermissionName::Hrtime,
+	];
+
+	pub fn parse(name: &str) -> Option<PermissionName> {
+		PermissionName::ALL.into_iter().find(|permission| permission.as_str() == name)
+	}
+
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			PermissionName::Read => "read",
+			PermissionName::Write => "write",
+			PermissionName::Net => "net",
+			PermissionName::Env => "env",
+			PermissionName::Run => "run",
+			PermissionName::Ffi => "ffi",
+			PermissionName::Hrtime => "hrtime",
+		}
+	}
+
+	/// Checks if the permission can be granted for individual resources.
+	pub fn has_resources(&self) -> bool {
+		!matches!(self, PermissionName::Ffi | PermissionName::Hrtime)
+	}
+
+	/// Normalises a resource, so that equivalent resources are compared equal.
+	/// Relative paths are resolved against the current directory, and symbolic links are resolved where the path exists.
+	fn normalise(&self, resource: &str) -> String {
+		match self {
+			PermissionName::Read | PermissionName::Write => {
+				let path = Path::new(resource);
+				let path = if path.is_relative() {
+					current_dir().map(|dir| dir.join(path)).unwrap_or_else(|_| path.to_path_buf())
+				} else {
+					path.to_path_buf()
+				};
+				resolve_path(&path).to_string_lossy().into_owned()
+			}
+			PermissionName::Net => resource.to_ascii_lowercase(),
+			_ => String::from(resource),
+		}
+	}
+
+	/// Checks if a granted or denied resource covers the given resource.
+	/// Paths cover every path within them, and hosts without a port cover every port.
+	fn covers(&self, entry: &str, resource: &str) -> bool {
+		match self {
+			PermissionName::Read | PermissionName::Write => Path::new(resource).starts_with(entry),
+			PermissionName::Net => {
+				let (entry_host, entry_port) = split_host_port(entry);
+				let (host, port) = split_host_port(resource);
+				entry_host == host && (entry_port.is_none() || entry_port == port)
+			}
+			_ => entry == resource,
+		}
+	}
+}
+
+/// Splits a network resource into its host and optional port.
+/// IPv6 addresses are given in brackets when followed by a port, as in `[::1]:8000`, and may be given without brackets otherwise.
+/// IP addresses are normalised, so that different notations of the same address are compared equal.
+fn split_host_port(resource: &str) -> (String, Option<u16>) {
+	let (host, port) = if let Some(rest) = resource.strip_prefix('[') {
+		match rest.split_once(']') {
+			Some((host, port)) => (host, port.strip_prefix(':')),
+			None => (rest, None),
+		}
+	} else if resource.matches(':').count() == 1 {
+		match resource.split_once(':') {
+			Some((host, port)) => (host, Some(port)),
+			None => (resource, None),
+		}
+	} else {
+		(resource, None)
+	};
+
+	let host = host.parse::<IpAddr>().map(|ip| ip.to_string()).unwrap_or_else(|_| String::from(host));
+	match port.map(str::parse::<u16>) {
+		Some(Ok(port)) => (host, Some(port)),
+		Some(Err(_)) => (String::from(resource), None),
+		None => (host, None),
+	}
+}
+
+impl Display for PermissionName {
+	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+		f.write_str(self.as_str())
+	}
+}
+
+/// Resolves symbolic links in the longest prefix of an absolute path which exists, then normalises the rest of it.
+/// Otherwise, a link within a granted directory, such as to `/`, would be covered by the grant.
+fn resolve_path(path: &Path) -> PathBuf {
+	let components: Vec<_> = path.components().collect();
+	for end in (1..=components.len()).rev() {
+		let prefix: PathBuf = components[..end].iter().collect();
+		if let Ok(canonical) = canonicalize(&prefix) {
+			let resolved = components[end..].iter().fold(canonical, |path, component| path.join(component));
+			return normalise_path(&resolved);
+		}
+	}
+	normalise_path(path)
+}
+
+fn normalise_path(path: &Path) -> PathBuf {
+	let mut normalised = PathBuf::new();
+	for component in path.components() {
+		match component {
+			Component::CurDir => {}
+			Component::ParentDir => {
+				normalised.pop();
+			}
+			component => normalised.push(component),
+		}
+	}
+	normalised
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PermissionState {
+	Granted,
+	/// The permission has not been granted or denied, so the user is prompted when it is requested.
+	Prompt,
+	Denied,
+}
+
+impl PermissionState {
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			PermissionState::Granted => "granted",
+			PermissionState::Prompt => "prompt",
+			PermissionState::Denied => "denied",
+		}
+	}
+}
+
+/// Represents the resources a permission has been granted and denied for.
+/// Denials take precedence over grants.
+#[derive(Clone, Debug, Default)]
+struct PermissionEntry {
+	granted_all: bool,
+	granted: Vec<String>,
+	denied_all: bool,
+	denied: Vec<String>,
+}
+
+/// Represents the permissions of the runtime, which are shared by every realm and worker.
+///
+/// The default permissions grant everything, so that embedders are not restricted unless they opt in.
+#[derive(Clone, Debug)]
+pub struct Permissions {
+	entries: [PermissionEntry; 7],
+	prompt: bool,
+}
+
+impl Permissions {
+	/// Creates permissions which grant nothing, and prompt the user for each request if `prompt` is set.
+	pub fn none(prompt: bool) -> Permissions {
+		Permissions { entries: Default::default(), prompt }
+	}
+
+	pub fn allow_all() -> Permissions {
+		let mut permissions = Permissions::none(false);
+		for name in PermissionName::ALL {
+			permissions.entry_mut(name).granted_all = true;
+		}
+		permissions
+	}
+
+	/// Grants a permission for the given resources, or for all resources if there are none.
+	pub fn allow(mut self, name: PermissionName, resources: &[String]) -> Permissions {
+		self.grant(name, resources);
+		self
+	}
+
+	fn entry(&self, name: PermissionName) -> &PermissionEntry {
+		&self.entries[name as usize]
+	}
+
+	fn entry_mut(&mut self, name: PermissionName) -> &mut PermissionEntry {
+		&mut self.entries[name as usize]
+	}
+
+	fn grant(&mut self, name: PermissionName, resources: &[String]) {
+		let entry = &mut self.entries[name as usize];
+		if resources.is_empty() || !name.has_resources() {
+			entry.granted_all = true;
+		} else {
+			entry.granted.extend(resources.iter().map(|resource| name.normalise(resource)));
+		}
+	}
+
+	/// Returns the state of a permission for a resource, or for every resource if there is none.
+	pub fn query(&self, name: PermissionName, resource: Option<&str>) -> PermissionState {
+		let entry = self.entry(name);
+		let resource = resource.filter(|_| name.has_resources()).map(|resource| name.normalise(resource));
+		let covered = |entries: &[String]| match &resource {
+			Some(resource) => entries.iter().any(|entry| name.covers(entry, resource)),
+			None => false,
+		};
+
+		if entry.denied_all || covered(&entry.denied) {
+			PermissionState::Denied
+		} else if entry.granted_all || covered(&entry.granted) {
+			PermissionState::Granted
+		} else if self.prompt {
+			PermissionState::Prompt
+		} else {
+			PermissionState::Denied
+		}
+	}
+
+	/// Revokes a permission for a resource, or for every resource if there is none, so that it must be requested again.
+	pub fn revoke(&mut self, name: PermissionName, resource: Option<&str>) -> PermissionState {
+		let entry = self.entry_mut(name);
+		match resource.filter(|_| name.has_resources()) {
+			Some(resource) => {
+				let resource = name.normalise(resource);
+				entry
+					.granted
+					.retain(|granted| !name.covers(&resource, granted) && !name.covers(granted, &resource));
+				entry.granted_all = false;
+			}
+			None => {
+				entry.granted_all = false;
+				entry.granted.clear();
+			}
+		}
+		self.query(name, resource)
+	}
+
+	/// Remembers the answer to a prompt for the resource, or for every resource if there is none.
+	fn remember(&mut self, name: PermissionName, resource: Option<&str>, granted: bool) {
+		let entry = self.entry_mut(name);
+		match (resource.filter(|_| name.has_resources()), granted) {
+			(Some(resource), true) => entry.granted.push(name.normalise(resource)),
+			(Some(resource), false) => entry.denied.push(name.normalise(resource)),
+			(None, true) => entry.granted_all = true,
+			(None, false) => entry.denied_all = true,
+		}
+	}
+}
+
+impl Default for Permissions {
+	fn default() -> Permissions {
+		Permissions::allow_all()
+	}
+}
+
+/// Asks the user whether to grant a permission, if both standard input and standard error are terminals.
+/// Returns `false` if the user cannot be asked.
+fn prompt(name: PermissionName, resource: Option<&str>) -> io::Result<bool> {
+	if !stdin().is_terminal() || !stderr().is_terminal() {
+		return Ok(false);
+	}
+
+	let target = match resource {
+		Some(resource) => format!("{} access to \"{}\"", name, resource),
+		None => format!("{} access", name),
+	};
+	let mut stderr = stderr().lock();
+	let mut stdin = stdin().lock();
+	loop {
+		write!(stderr, "Spiderfire requests {}. Allow? [y/n] ", target)?;
+		stderr.flush()?;
+
+		let mut answer = String::new();
+		if stdin.read_line(&mut answer)? == 0 {
+			return Ok(false);
+		}
+		match answer.trim().to_ascii_lowercase().as_str() {
+			"y" | "yes" => return Ok(true),
+			"n" | "no" => return Ok(false),
+			_ => writeln!(stderr, "Please answer y or n.")?,
+		}
+	}
+}
+
+/// Returns the permissions of the runtime, which are initialised from the [configuration](crate::config::Config).
+pub fn permissions() -> MutexGuard<'static, Permissions> {
+	let permissions = PERMISSIONS.get_or_init(|| {
+		let permissions = CONFIG.get().map(|config| config.permissions.clone()).unwrap_or_default();
+		Mutex::new(permissions)
+	});
+	permissions.lock().unwrap_or_else(|error| error.into_inner())
+}
+
+/// Requests a permission, prompting the user if it has neither been granted nor denied.
+/// The answer is remembered for the resource, or for every resource if there is none.
+///
+/// The permissions are not locked while the user is prompted, so other threads can still check permissions.
+pub fn request(name: PermissionName, resource: Option<&str>) -> PermissionState {
+	let state = permissions().query(name, resource);
+	if state != PermissionState::Prompt {
+		return state;
+	}
+
+	// The permission is queried again, as it may have been answered while another prompt was shown.
+	let _prompt = PROMPT.lock().unwrap_or_else(|error| error.into_inner());
+	let state = permissions().query(name, resource);
+	if state != PermissionState::Prompt {
+		return state;
+	}
+
+	let granted = prompt(name, resource.filter(|_| name.has_resources())).unwrap_or(false);
+	let mut permissions = permissions();
+	permissions.remember(name, resource, granted);
+	permissions.query(name, resource)
+}
+
+/// Checks that a permission has been granted for a resource, prompting the user if needed.
+/// Returns [Err] with a `PermissionDenied` error if it has been denied.
+pub fn check(name: PermissionName, resource: Option<&str>) -> Result<()> {
+	match request(name, resource) {
+		PermissionState::Granted => Ok(()),
+		_ => {
+			let message = match resource.filter(|_| name.has_resources()) {
+				Some(resource) => format!("Requires {} access to \"{}\", run again with --allow-{}", name, resource, name),
+				None => format!("Requires {} access, run again with --allow-{}", name, name),
+			};
+			Err(Error::new(&message, None).with_name("PermissionDenied"))
+		}
+	}
+}
+
+/// Checks if a permission has been granted for every resource, without prompting the user.
+pub fn is_granted(name: PermissionName) -> bool {
+	permissions().query(name, None) == PermissionState::Granted
+}
+
+pub fn check_read(path: &str) -> Result<()> {
+	check(PermissionName::Read, Some(path))
+}
+
+pub fn check_write(path: &str) -> Result<()> {
+	check(PermissionName::Write, Some(path))
+}
+
+/// Checks that connecting to or listening on a host is allowed, where the port is only checked if it is given.
+/// IPv6 addresses may be given with or without brackets.
+pub fn check_net(hostname: &str, port: Option<u16>) -> Result<()> {
+	match port {
+		Some(port) if hostname.contains(':') && !hostname.starts_with('[') => check(PermissionName::Net, Some(&format!("[{}]:{}", hostname, port))),
+		Some(port) => check(PermissionName::Net, Some(&format!("{}:{}", hostname, port))),
+		None => check(PermissionName::Net, Some(hostname)),
+	}
+}
+
+/// Checks that loading a URL is allowed, where `file:` URLs require read access and HTTP URLs require network access.
+pub fn check_url(url: &Url) -> Result<()> {
+	match url.scheme() {
+		"file" => match url.to_file_path() {
+			Ok(path) => check_read(&path.to_string_lossy()),
+			Err(_) => Ok(()),
+		},
+		"http" | "https" => match url.host_str() {
+			Some(host) => check_net(host, url.port_or_known_default()),
+			None => Ok(()),
+		},
+		_ => Ok(()),
+	}
+}
+
+pub fn check_env(name: &str) -> Result<()> {
+	check(PermissionName::Env, Some(name))
+}
+
+pub fn check_run(program: &str) -> Result<()> {
+	check(PermissionName::Run, Some(program))
+}
+
+/// Parses the name of a permission, as given by scripts.
+pub fn parse_permission(name: &str) -> Result<PermissionName> {
+	PermissionName::parse(name).ok_or_else(|| Error::new(&format!("Unknown Permission: {}", name), ErrorKind::Type))
+}
@@ -0,0 +1,382 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::fs::write;
+use std::mem;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use mozjs::jsapi::{JS_RequestInterruptCallback, JSContext};
+
+use ion::Context;
+use ion::stack::{Stack, StackRecord};
+
+use crate::cache::map::find_sourcemap;
+use crate::config::CONFIG;
+use crate::ContextExt;
+use crate::json::json_string;
+use crate::VERSION;
+
+/// Default interval between samples, which is fine enough for short scripts without slowing them down significantly.
+pub const DEFAULT_INTERVAL: Duration = Duration::from_millis(1);
+const MAX_FRAMES: u32 = 128;
+
+/// Output formats of a [Profile].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ProfileFormat {
+	/// [Speedscope](https://www.speedscope.app) JSON, which can also be opened with the Firefox Profiler.
+	Speedscope,
+	/// Folded stacks, with one stack and its count on each line, as read by `flamegraph.pl`, `inferno` and `pprof`.
+	Folded,
+}
+
+impl ProfileFormat {
+	pub fn from_name(format: &str) -> Option<ProfileFormat> {
+		match format {
+			"speedscope" => Some(ProfileFormat::Speedscope),
+			"folded" => Some(ProfileFormat::Folded),
+			_ => None,
+		}
+	}
+
+	/// Returns the format of a profile written to `path`, which is folded stacks for `.folded` files, and speedscope otherwise.
+	pub fn from_path(path: &Path) -> ProfileFormat {
+		match path.extension().and_then(|extension| extension.to_str()) {
+			Some("folded") => ProfileFormat::Folded,
+			_ => ProfileFormat::Speedscope,
+		}
+	}
+}
+
+/// Represents the stacks sampled by a [Profiler], with their locations mapped to the original sources.
+#[derive(Clone, Debug)]
+pub struct Profile {
+	pub name: String,
+	/// Unique frames of the sampled stacks.
+	pub frames: Vec<StackRecord>,
+	/// Sampled stacks, as indices into the frames, from the outermost frame to the innermost.
+	pub samples: Vec<Vec<usize>>,
+	pub interval: Duration,
+	/// Time between the start and end of profiling, including time spent waiting in the event loop.
+	pub duration: Duration,
+}
+
+impl Profile {
+	pub fn format(&self, format: ProfileFormat) -> String {
+		match format {
+			ProfileFormat::Speedscope => self.to_speedscope(),
+			ProfileFormat::Folded => self.to_folded(),
+		}
+	}
+
+	/// Formats the profile as a sampled speedscope profile, weighting each sample by the interval.
+	pub fn to_speedscope(&self) -> String {
+		let mut json = String::from(r#"{"$schema":"https://www.speedscope.app/file-format-schema.json","#);
+		let _ = write!(
+			json,
+			r#""exporter":"spiderfire@{}","name":{},"activeProfileIndex":0,"#,
+			VERSION,
+			json_string(&self.name)
+		);
+
+		json.push_str(r#""shared":{"frames":["#);
+		for (i, frame) in self.frames.iter().enumerate() {
+			if i != 0 {
+				json.push(',');
+			}
+			let _ = write!(
+				json,
+				r#"{{"name":{},"file":{},"line":{},"col":{}}}"#,
+				json_string(frame_name(frame)),
+				json_string(&frame.location.file),
+				frame.location.lineno,
+				frame.location.column
+			);
+		}
+		json.push_str("]},");
+
+		let interval = self.interval.as_secs_f64() * 1000.0;
+		let _ = write!(
+			json,
+			r#""profiles":[{{"type":"sampled","name":{},"unit":"milliseconds","startValue":0,"endValue":{},"samples":["#,
+			json_string(&self.name),
+			interval * self.samples.len() as f64
+		);
+		for (i, sample) in self.samples.iter().enumerate() {
+			if i != 0 {
+				json.push(',');
+			}
+			let indices: Vec<_> = sample.iter().map(usize::to_string).collect();
+			let _ = write!(json, "[{}]", indices.join(","));
+		}
+		json.push_str(r#"],"weights":["#);
+		let weights = vec![interval.to_string(); self.samples.len()];
+		json.push_str(&weights.join(","));
+		json.push_str("]}]}");
+		json
+	}
+
+	/// Formats the profile as a CPU profile of the Chrome DevTools Protocol, which is also the format of `.cpuprofile` files.
+	/// Each sample is a node of the call tree, identified by the stack leading to it.
+	pub fn to_cpu_profile(&self) -> String {
+		let mut nodes = vec![CallNode::default()];
+		let mut indices = HashMap::new();
+		let mut samples = Vec::with_capacity(self.samples.len());
+		for sample in &self.samples {
+			let mut node = 0;
+			for &frame in sample {
+				node = *indices.entry((node, frame)).or_insert_with(|| {
+					nodes.push(CallNode {
+						frame: Some(frame),
+						..CallNode::default()
+					});
+					nodes[node].children.push(nodes.len() - 1);
+					nodes.len() - 1
+				});
+			}
+			nodes[node].hits += 1;
+			samples.push(node + 1);
+		}
+
+		let mut json = String::from(r#"{"nodes":["#);
+		for (i, node) in nodes.iter().enumerate() {
+			if i != 0 {
+				json.push(',');
+			}
+			let (name, url, line, column) = match node.frame {
+				Some(frame) => {
+					let frame = &self.frames[frame];
+					let location = &frame.location;
+					(
+						frame_name(frame),
+						location.file.as_str(),
+						location.lineno.saturating_sub(1),
+						location.column.saturating_sub(1),
+					)
+				}
+				None => ("(root)", "", 0, 0),
+			};
+			let children: Vec<_> = node.children.iter().map(|child| (child + 1).to_string()).collect();
+			let _ = write!(
+				json,
+				r#"{{"id":{},"callFrame":{{"functionName":{},"scriptId":"0","url":{},"lineNumber":{},"columnNumber":{}}},"hitCount":{},"children":[{}]}}"#,
+				i + 1,
+				json_string(name),
+				json_string(url),
+				line,
+				column,
+				node.hits,
+				children.join(",")
+			);
+		}
+
+		let interval = self.interval.as_micros();
+		let samples: Vec<_> = samples.iter().map(usize::to_string).collect();
+		let deltas = vec![interval.to_string(); samples.len()];
+		let _ = write!(
+			json,
+			r#"],"startTime":0,"endTime":{},"samples":[{}],"timeDeltas":[{}]}}"#,
+			self.duration.as_micros(),
+			samples.join(","),
+			deltas.join(",")
+		);
+		json
+	}
+
+	/// Formats the profile as folded stacks, counting identical stacks together.
+	pub fn to_folded(&self) -> String {
+		let mut counts = HashMap::new();
+		for sample in &self.samples {
+			*counts.entry(sample).or_insert(0) += 1;
+		}
+
+		let mut lines: Vec<_> = counts
+			.into_iter()
+			.map(|(sample, count)| {
+				let stack: Vec<_> = sample
+					.iter()
+					.map(|&index| {
+						let frame = &self.frames[index];
+						let label = format!("{} ({}:{})", frame_name(frame), frame.location.file, frame.location.lineno);
+						label.replace(';', ":")
+					})
+					.collect();
+				format!("{} {}", stack.join(";"), count)
+			})
+			.collect();
+		lines.sort();
+
+		let mut folded = lines.join("\n");
+		folded.push('\n');
+		folded
+	}
+}
+
+/// Node of the call tree of a CPU profile, whose ID is its index plus one.
+#[derive(Default)]
+struct CallNode {
+	frame: Option<usize>,
+	children: Vec<usize>,
+	hits: u32,
+}
+
+fn frame_name(frame: &StackRecord) -> &str {
+	frame.function.as_deref().filter(|function| !function.is_empty()).unwrap_or("(anonymous)")
+}
+
+/// Samples the stack of a runtime at a fixed interval.
+///
+/// A thread requests an interrupt of the runtime at each interval, and the stack is captured by the interrupt callback.
+/// As interrupts are only handled while JavaScript is running, time spent idle in the event loop is not sampled.
+pub struct Profiler {
+	interval: Duration,
+	output: Option<PathBuf>,
+	start: Instant,
+	running: Arc<AtomicBool>,
+	sampler: Option<JoinHandle<()>>,
+	frames: Vec<StackRecord>,
+	indices: HashMap<(Option<String>, String, u32, u32), usize>,
+	samples: Vec<Vec<usize>>,
+}
+
+impl Profiler {
+	/// Starts profiling the runtime of the context, sampling its stack every `interval`.
+	/// If `output` is given, the profile is written to it when the runtime shuts down.
+	/// Returns `false` if the runtime is already being profiled.
+	pub fn start(cx: &Context, interval: Duration, output: Option<PathBuf>) -> bool {
+		let private = unsafe { &mut *cx.get_private().as_ptr() };
+		if private.profiler.is_some() {
+			return false;
+		}
+
+		let running = Arc::new(AtomicBool::new(true));
+		let context = cx.as_ptr() as usize;
+		let sampler = {
+			let running = Arc::clone(&running);
+			thread::Builder::new().name(String::from("profiler")).spawn(move || {
+				while running.load(Ordering::SeqCst) {
+					thread::sleep(interval);
+					if running.load(Ordering::SeqCst) {
+						unsafe {
+							JS_RequestInterruptCallback(context as *mut JSContext);
+						}
+					}
+				}
+			})
+		};
+		let Ok(sampler) = sampler else {
+			return false;
+		};
+
+		private.profiler = Some(Profiler {
+			interval,
+			output,
+			start: Instant::now(),
+			running,
+			sampler: Some(sampler),
+			frames: Vec::new(),
+			indices: HashMap::new(),
+			samples: Vec::new(),
+		});
+		true
+	}
+
+	/// Stops profiling the runtime of the context, and returns its profile.
+	/// Returns [None] if it is not being profiled.
+	pub fn stop(cx: &Context) -> Option<Profile> {
+		let private = unsafe { &mut *cx.get_private().as_ptr() };
+		private.profiler.take().map(Profiler::finish)
+	}
+
+	pub fn is_running(cx: &Context) -> bool {
+		unsafe { (*cx.get_private().as_ptr()).profiler.is_some() }
+	}
+
+	/// Captures the current stack as a sample, which is called from the interrupt callback of the runtime.
+	pub(crate) fn sample(&mut self, cx: &Context) {
+		let Some(stack) = Stack::from_capture_with_max_frames(cx, MAX_FRAMES) else {
+			return;
+		};
+		if stack.is_empty() {
+			return;
+		}
+
+		let sample = stack
+			.records
+			.into_iter()
+			.rev()
+			.map(|record| {
+				let key = (
+					record.function.clone(),
+					record.location.file.clone(),
+					record.location.lineno,
+					record.location.column,
+				);
+				*self.indices.entry(key).or_insert_with(|| {
+					self.frames.push(record);
+					self.frames.len() - 1
+				})
+			})
+			.collect();
+		self.samples.push(sample);
+	}
+
+	fn finish(mut self) -> Profile {
+		self.halt();
+
+		let mut frames = mem::take(&mut self.frames);
+		for frame in &mut frames {
+			if let Some(sourcemap) = find_sourcemap(&frame.location.file) {
+				frame.transform_with_sourcemap(&sourcemap);
+			}
+		}
+
+		let name = CONFIG
+			.get()
+			.and_then(|config| config.main.as_ref())
+			.map(|main| main.display().to_string())
+			.unwrap_or_else(|| String::from("Spiderfire"));
+		Profile {
+			name,
+			frames,
+			samples: mem::take(&mut self.samples),
+			interval: self.interval,
+			duration: self.start.elapsed(),
+		}
+	}
+
+	fn halt(&mut self) {
+		self.running.store(false, Ordering::SeqCst);
+		if let Some(sampler) = self.sampler.take() {
+			let _ = sampler.join();
+		}
+	}
+}
+
+impl Drop for Profiler {
+	fn drop(&mut self) {
+		self.halt();
+	}
+}
+
+/// Stops the profiler when the runtime shuts down, and writes its profile to its output, if it has one.
+pub(crate) fn write_on_shutdown(cx: &Context) {
+	let private = unsafe { &mut *cx.get_private().as_ptr() };
+	if private.profiler.as_ref().is_some_and(|profiler| profiler.output.is_some()) {
+		let mut profiler = private.profiler.take().unwrap();
+		let output = profiler.output.take().unwrap();
+		let profile = profiler.finish();
+		if let Err(error) = write(&output, profile.format(ProfileFormat::from_path(&output))) {
+			eprintln!("Failed to Write Profile to {}: {}", output.display(), error);
+		}
+	}
+}
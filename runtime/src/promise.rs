@@ -8,13 +8,27 @@ use std::future::Future;
 
 use tokio::task::spawn_local;
 
-use ion::{Context, Promise};
+use ion::{Context, Promise, Value};
 use ion::conversions::{BoxedIntoValue, IntoValue};
+use ion::objects::SpawnedFuture;
 
 use crate::ContextExt;
+use crate::event_loop::handles::{ActiveHandle, HandleKind};
 
 /// Returns None if no future queue has been initialised.
 pub fn future_to_promise<'cx, F, O, E>(cx: &'cx Context, future: F) -> Option<Promise<'cx>>
+where
+	F: Future<Output = Result<O, E>> + 'static,
+	O: for<'cx2> IntoValue<'cx2> + 'static,
+	E: for<'cx2> IntoValue<'cx2> + 'static,
+{
+	let handle = ActiveHandle::new(cx, HandleKind::Promise, "Native Operation");
+	future_to_promise_with_handle(cx, handle, future)
+}
+
+/// Converts a future to a promise, as with [future_to_promise],
+/// which is described by `handle` in the [active handles](crate::event_loop::handles::active_handles) of the runtime while it is pending.
+pub fn future_to_promise_with_handle<'cx, F, O, E>(cx: &'cx Context, handle: ActiveHandle, future: F) -> Option<Promise<'cx>>
 where
 	F: Future<Output = Result<O, E>> + 'static,
 	O: for<'cx2> IntoValue<'cx2> + 'static,
@@ -23,7 +37,7 @@ where
 	let promise = Promise::new(cx);
 	let object = promise.handle().get();
 
-	let handle = spawn_local(async move {
+	let task = spawn_local(async move {
 		let result: Result<BoxedIntoValue, BoxedIntoValue> = match future.await {
 			Ok(o) => Ok(Box::new(o)),
 			Err(e) => Err(Box::new(e)),
@@ -33,7 +47,21 @@ where
 
 	let event_loop = unsafe { &(*cx.get_private().as_ptr()).event_loop };
 	event_loop.futures.as_ref().map(|futures| {
-		futures.enqueue(handle);
+		futures.enqueue(task, handle);
 		promise
 	})
 }
+
+struct Spawned(BoxedIntoValue);
+
+impl<'cx> IntoValue<'cx> for Spawned {
+	fn into_value(self: Box<Self>, cx: &'cx Context, value: &mut Value) {
+		self.0.into_value(cx, value)
+	}
+}
+
+/// Converts the futures of native objects defined by ion, such as [AsyncIterator](ion::AsyncIterator), to promises with [future_to_promise].
+/// This is set as the [FutureSpawner](ion::objects::FutureSpawner) of runtimes with a microtask queue.
+pub(crate) fn spawn_future(cx: &Context, future: SpawnedFuture) -> Option<Promise> {
+	future_to_promise(cx, async move { future.await.map(Spawned).map_err(Spawned) })
+}
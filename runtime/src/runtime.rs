@@ -4,26 +4,106 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/.
  */
 
+use std::any::{Any, TypeId};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::PathBuf;
 use std::ptr;
 use std::ptr::NonNull;
+use std::rc::Rc;
 
 use mozjs::glue::CreateJobQueue;
-use mozjs::jsapi::{ContextOptionsRef, JSAutoRealm, SetJobQueue, SetPromiseRejectionTrackerCallback};
+use mozjs::jsapi::{
+	GCOptions, GCReason, JS_AddInterruptCallback, JS_GC, JS_GetGCParameter, JS_SetGCCallback, JSAutoRealm, JSContext, JSGCParamKey, JSObject,
+	NonIncrementalGC, OnNewGlobalHookOption, PrepareForFullGC, SetHostCleanupFinalizationRegistryCallback, SetJobQueue,
+	SetPromiseRejectionTrackerCallback,
+};
+use mozjs::rust::{JSEngineHandle, SIMPLE_GLOBAL_CLASS};
+use url::Url;
 
-use ion::{Context, ErrorReport, Object};
+use ion::{Context, Error, ErrorReport, Exception, Object, PersistentRooted, Promise, Value};
 use ion::module::{init_module_loader, ModuleLoader};
-use ion::objects::default_new_global;
+use ion::objects::new_global;
 
-use crate::event_loop::{EventLoop, promise_rejection_tracker_callback};
+use crate::diagnostics::{Diagnostics, gc_callback};
+use crate::event_loop::{cleanup_finalization_registry_callback, EventLoop, promise_rejection_tracker_callback, RejectionCallback};
 use crate::event_loop::future::FutureQueue;
+use crate::event_loop::handles::report_on_exit;
 use crate::event_loop::macrotasks::MacrotaskQueue;
 use crate::event_loop::microtasks::{JOB_QUEUE_TRAPS, MicrotaskQueue};
-use crate::globals::{init_globals, init_microtasks, init_timers};
-use crate::modules::StandardModules;
+use crate::globals::{init_gc, init_globals, init_microtasks, init_timers, init_workers};
+use crate::globals::console::{ConsoleBackend, ConsoleState, set_backend};
+use crate::globals::event::define_global_target;
+use crate::globals::performance::Timeline;
+use crate::globals::worker::WorkerOptions;
+use crate::heap;
+use crate::modules::{CustomModule, init_custom_module, StandardModules};
+use crate::options::ContextOptions;
+use crate::profiler;
+use crate::profiler::Profiler;
+use crate::promise::spawn_future;
+use crate::watchdog::Interrupt;
 
 #[derive(Default)]
 pub struct ContextPrivate {
 	pub(crate) event_loop: EventLoop,
+	pub(crate) performance: Timeline,
+	pub(crate) workers: Option<WorkerOptions>,
+	/// Holds the event listeners of the global object.
+	pub(crate) global_target: Option<PersistentRooted<*mut JSObject>>,
+	pub(crate) exit_code: i32,
+	/// Whether a script has requested the runtime to exit, which stops its event loop.
+	pub(crate) exited: bool,
+	pub(crate) profiler: Option<Profiler>,
+	pub(crate) diagnostics: Diagnostics,
+	/// Path which a heap snapshot is written to when the runtime shuts down.
+	pub(crate) heap_snapshot: Option<PathBuf>,
+	/// Holds the `module` objects of the CommonJS modules which have been loaded, by path.
+	pub(crate) commonjs_modules: HashMap<PathBuf, PersistentRooted<*mut JSObject>>,
+	/// Holds the internal functions returned by the streams script, which native streams are created with.
+	pub(crate) stream_internals: Option<PersistentRooted<*mut JSObject>>,
+	pub(crate) interrupt: Interrupt,
+	pub(crate) console: ConsoleState,
+	/// Holds the paths of the remote modules which have been located in the cache, by URL.
+	pub(crate) remote_modules: HashMap<Url, PathBuf>,
+	/// Holds the URLs of remote modules after redirects, by their path in the cache, which their imports are resolved against.
+	pub(crate) remote_urls: HashMap<PathBuf, Url>,
+	/// Holds the state of native modules and embedders, by type.
+	extensions: HashMap<TypeId, Box<dyn Any>>,
+}
+
+impl ContextPrivate {
+	/// Returns the exit code set by scripts, which is 0 unless it has been set.
+	pub fn exit_code(&self) -> i32 {
+		self.exit_code
+	}
+
+	pub fn set_exit_code(&mut self, code: i32) {
+		self.exit_code = code;
+	}
+
+	/// Sets the exit code, and stops the event loop once the running script has been terminated.
+	/// The runtime still has to be [shut down](Runtime::shutdown) by its embedder.
+	pub fn exit(&mut self, code: i32) {
+		self.exit_code = code;
+		self.exited = true;
+	}
+
+	/// Checks if a script has requested the runtime to exit.
+	pub fn has_exited(&self) -> bool {
+		self.exited
+	}
+
+	/// Returns the performance timeline, whose clock is used by `performance.now()`.
+	pub fn performance(&self) -> &Timeline {
+		&self.performance
+	}
+
+	/// Returns the state of type `T` held by the runtime, which is created with its default value when it is first accessed.
+	pub fn extension<T: Any + Default>(&mut self) -> &mut T {
+		let extension = self.extensions.entry(TypeId::of::<T>()).or_insert_with(|| Box::new(T::default()));
+		extension.downcast_mut().unwrap()
+	}
 }
 
 pub trait ContextExt {
@@ -36,6 +116,36 @@ impl ContextExt for Context {
 	}
 }
 
+/// Memory statistics of the garbage-collected heap.
+#[derive(Clone, Copy, Debug)]
+pub struct MemoryUsage {
+	/// Bytes currently allocated in the GC heap.
+	pub gc_bytes: u32,
+	/// Maximum size of the GC heap in bytes.
+	pub max_gc_bytes: u32,
+	/// Number of chunks reserved for the GC heap, including those which are unused.
+	pub total_chunks: u32,
+	/// Number of garbage collections, including minor collections.
+	pub gc_number: u32,
+	pub major_gc_number: u32,
+	pub minor_gc_number: u32,
+}
+
+impl MemoryUsage {
+	/// Size of the chunks which the GC heap is allocated in.
+	pub const CHUNK_BYTES: u64 = 1 << 20;
+
+	/// Returns the bytes reserved for the GC heap, which is at least the bytes allocated in it.
+	pub fn reserved_bytes(&self) -> u64 {
+		(self.total_chunks as u64 * MemoryUsage::CHUNK_BYTES).max(self.gc_bytes as u64)
+	}
+}
+
+/// Runtime built on a [Context], whose state, such as its event loop, is held by the context.
+///
+/// Any number of runtimes can be created in a process, and each is independent of the others.
+/// SpiderMonkey allows a single context on each thread at a time, so concurrent runtimes must run on separate threads,
+/// while runtimes on the same thread must be created one after another.
 pub struct Runtime<'cx> {
 	global: Object<'cx>,
 	cx: &'cx Context,
@@ -56,14 +166,80 @@ impl<'cx> Runtime<'cx> {
 		&mut self.global
 	}
 
+	/// Performs a full, non-incremental garbage collection.
+	pub fn gc(&self) {
+		unsafe {
+			JS_GC(self.cx.as_ptr(), GCReason::API);
+		}
+	}
+
+	/// Notifies the engine that the system is under memory pressure.
+	/// Performs a shrinking garbage collection, which also releases unused memory back to the system.
+	pub fn memory_pressure(&self) {
+		unsafe {
+			PrepareForFullGC(self.cx.as_ptr());
+			NonIncrementalGC(self.cx.as_ptr(), GCOptions::Shrink, GCReason::MEM_PRESSURE);
+		}
+	}
+
+	pub fn memory_usage(&self) -> MemoryUsage {
+		memory_usage(self.cx)
+	}
+
 	pub async fn run_event_loop(&self) -> Result<(), Option<ErrorReport>> {
 		let event_loop = unsafe { &mut (*self.cx.get_private().as_ptr()).event_loop };
 		event_loop.run_event_loop(self.cx).await
 	}
+
+	/// Fires `unload` at the global object, and cancels all pending timers, futures and messages.
+	/// This should be called once the runtime is no longer used, before it is dropped.
+	pub fn shutdown(&self) {
+		shutdown(self.cx);
+	}
+
+	pub fn exit_code(&self) -> i32 {
+		unsafe { (*self.cx.get_private().as_ptr()).exit_code }
+	}
+
+	/// Checks if a script has requested the runtime to exit, which stops the event loop.
+	pub fn has_exited(&self) -> bool {
+		unsafe { (*self.cx.get_private().as_ptr()).exited }
+	}
+
+	/// Checks if any promise rejection was left unhandled, and was not cancelled by an `unhandledrejection` listener.
+	pub fn has_unhandled_rejections(&self) -> bool {
+		let event_loop = unsafe { &(*self.cx.get_private().as_ptr()).event_loop };
+		event_loop.rejected
+	}
+}
+
+/// Returns the memory statistics of the runtime of a context, as with [Runtime::memory_usage].
+pub fn memory_usage(cx: &Context) -> MemoryUsage {
+	let parameter = |key| unsafe { JS_GetGCParameter(cx.as_ptr(), key) };
+	MemoryUsage {
+		gc_bytes: parameter(JSGCParamKey::JSGC_BYTES),
+		max_gc_bytes: parameter(JSGCParamKey::JSGC_MAX_BYTES),
+		total_chunks: parameter(JSGCParamKey::JSGC_TOTAL_CHUNKS),
+		gc_number: parameter(JSGCParamKey::JSGC_NUMBER),
+		major_gc_number: parameter(JSGCParamKey::JSGC_MAJOR_GC_NUMBER),
+		minor_gc_number: parameter(JSGCParamKey::JSGC_MINOR_GC_NUMBER),
+	}
+}
+
+/// Shuts down the runtime of a context, as with [Runtime::shutdown].
+pub fn shutdown(cx: &Context) {
+	report_on_exit(cx);
+	profiler::write_on_shutdown(cx);
+	heap::write_on_shutdown(cx);
+	let event_loop = unsafe { &mut (*cx.get_private().as_ptr()).event_loop };
+	event_loop.shutdown(cx);
 }
 
 impl Drop for Runtime<'_> {
 	fn drop(&mut self) {
+		unsafe {
+			JS_SetGCCallback(self.cx.as_ptr(), None, ptr::null_mut());
+		}
 		let private = self.cx.get_private();
 		let _ = unsafe { Box::from_raw(private.as_ptr()) };
 		let inner_private = self.cx.get_inner_data();
@@ -71,12 +247,22 @@ impl Drop for Runtime<'_> {
 	}
 }
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Derivative)]
+#[derivative(Clone, Debug)]
 pub struct RuntimeBuilder<ML: ModuleLoader + 'static = (), Std: StandardModules + 'static = ()> {
 	microtask_queue: bool,
 	macrotask_queue: bool,
 	modules: Option<ML>,
 	standard_modules: Option<Std>,
+	#[derivative(Debug = "ignore")]
+	custom_modules: Vec<(String, Rc<RefCell<dyn CustomModule>>)>,
+	#[derivative(Debug = "ignore")]
+	workers: Option<(JSEngineHandle, fn(&Context, &mut Object) -> bool)>,
+	#[derivative(Debug = "ignore")]
+	console: Option<Rc<dyn ConsoleBackend>>,
+	#[derivative(Debug = "ignore")]
+	rejection_callback: Option<RejectionCallback>,
+	options: ContextOptions,
 }
 
 impl<ML: ModuleLoader + 'static, Std: StandardModules + 'static> RuntimeBuilder<ML, Std> {
@@ -104,20 +290,67 @@ impl<ML: ModuleLoader + 'static, Std: StandardModules + 'static> RuntimeBuilder<
 		self
 	}
 
+	/// Registers a module implemented in Rust, which can be imported as `name`.
+	/// Without a module loader, its exports are defined as the global `name` instead.
+	pub fn register_module<M: CustomModule + 'static>(mut self, name: &str, module: M) -> RuntimeBuilder<ML, Std> {
+		self.custom_modules.push((String::from(name), Rc::new(RefCell::new(module))));
+		self
+	}
+
+	/// Sets the backend which receives the output of the `console` global, instead of stdout and stderr.
+	pub fn console<B: ConsoleBackend + 'static>(mut self, backend: B) -> RuntimeBuilder<ML, Std> {
+		self.console = Some(Rc::new(backend));
+		self
+	}
+
+	/// Sets a callback which is called for unhandled promise rejections, instead of printing them.
+	pub fn on_unhandled_rejection<F: Fn(&Context, &Promise, &Value) + 'static>(mut self, callback: F) -> RuntimeBuilder<ML, Std> {
+		self.rejection_callback = Some(Rc::new(callback));
+		self
+	}
+
+	pub fn options(mut self, options: ContextOptions) -> RuntimeBuilder<ML, Std> {
+		self.options = options;
+		self
+	}
+
+	/// Builds the runtime.
+	///
+	/// ### Panics
+	/// Panics if a module registered with [register_module](RuntimeBuilder::register_module) fails to initialise.
+	/// [try_build](RuntimeBuilder::try_build) returns an error instead.
 	pub fn build(self, cx: &mut Context) -> Runtime {
-		let mut global = default_new_global(cx);
+		match self.try_build(cx) {
+			Ok(runtime) => runtime,
+			Err(error) => panic!("{}", error.format()),
+		}
+	}
+
+	/// Builds the runtime, returning [Err] if a module registered with [register_module](RuntimeBuilder::register_module) fails to initialise.
+	pub fn try_build(self, cx: &mut Context) -> Result<Runtime, Error> {
+		self.options.apply(cx);
+		let realm_options = self.options.realm_options();
+		let mut global = new_global(cx, &SIMPLE_GLOBAL_CLASS, None, OnNewGlobalHookOption::FireOnNewGlobalHook, realm_options);
 		let realm = JSAutoRealm::new(cx.as_ptr(), global.handle().get());
 
+		// State of the runtime is held by the context, so that globals can store their state while they are defined.
+		cx.set_private(Box::<ContextPrivate>::default());
+		let private = unsafe { &mut *cx.get_private().as_ptr() };
+		private.interrupt.attach(cx);
+		private.event_loop.rejection_callback = self.rejection_callback;
+
 		let global_obj = global.handle().get();
 		global.set_as(cx, "global", &global_obj);
 		init_globals(cx, &mut global);
-
-		let mut private = Box::<ContextPrivate>::default();
+		if let Some(backend) = self.console {
+			set_backend(cx, backend);
+		}
 
 		if self.microtask_queue {
 			private.event_loop.microtasks = Some(MicrotaskQueue::default());
 			init_microtasks(cx, &mut global);
 			private.event_loop.futures = Some(FutureQueue::default());
+			unsafe { (*cx.get_inner_data().as_ptr()).future_spawner = Some(spawn_future) };
 
 			unsafe {
 				SetJobQueue(
@@ -131,10 +364,24 @@ impl<ML: ModuleLoader + 'static, Std: StandardModules + 'static> RuntimeBuilder<
 			private.event_loop.macrotasks = Some(MacrotaskQueue::default());
 			init_timers(cx, &mut global);
 		}
+		if self.options.expose_gc {
+			init_gc(cx, &mut global);
+		}
+		if let Some((engine, init_standard_modules)) = self.workers {
+			private.workers = Some(WorkerOptions {
+				engine,
+				options: self.options,
+				standard_modules: self.standard_modules.is_some().then_some(init_standard_modules),
+			});
+			init_workers(cx, &mut global);
+		}
 
-		let _options = unsafe { &mut *ContextOptionsRef(cx.as_ptr()) };
-
-		cx.set_private(private);
+		define_global_target(cx, &mut global);
+		unsafe {
+			SetHostCleanupFinalizationRegistryCallback(cx.as_ptr(), Some(cleanup_finalization_registry_callback), cx.as_ptr().cast());
+			JS_AddInterruptCallback(cx.as_ptr(), Some(interrupt_callback));
+			JS_SetGCCallback(cx.as_ptr(), Some(gc_callback), ptr::null_mut());
+		}
 
 		let has_loader = self.modules.is_some();
 		if let Some(loader) = self.modules {
@@ -149,10 +396,46 @@ impl<ML: ModuleLoader + 'static, Std: StandardModules + 'static> RuntimeBuilder<
 			}
 		}
 
-		Runtime { global, cx, realm }
+		for (name, module) in self.custom_modules {
+			if !init_custom_module(cx, &mut global, &name, &mut *module.borrow_mut(), has_loader) {
+				// The exception is formatted before the runtime is dropped, as it may refer to objects of the runtime.
+				let mut message = format!("Failed to Initialise Module: {}", name);
+				if let Some(exception) = Exception::new(cx) {
+					message.push_str(&format!("\n{}", exception.format(cx)));
+				}
+				drop(Runtime { global, cx, realm });
+				return Err(Error::new(&message, None));
+			}
+		}
+
+		Ok(Runtime { global, cx, realm })
 	}
 }
 
+/// Handles interrupts requested by the runtime, such as those of the [Profiler], which samples the stack when interrupted.
+/// Returning `false` terminates the running script, when requested by an [InterruptHandle](crate::watchdog::InterruptHandle).
+unsafe extern "C" fn interrupt_callback(cx: *mut JSContext) -> bool {
+	let cx = unsafe { Context::new_unchecked(cx) };
+	let private = unsafe { &mut *cx.get_private().as_ptr() };
+	if let Some(profiler) = &mut private.profiler {
+		profiler.sample(&cx);
+	}
+	private.interrupt.check()
+}
+
+impl<ML: ModuleLoader + 'static, Std: StandardModules + Default + 'static> RuntimeBuilder<ML, Std> {
+	/// Enables the `Worker` global, which runs modules in runtimes on other threads created from `engine`.
+	/// Workers have the same options and standard modules as this runtime, and always have a module loader.
+	pub fn workers(mut self, engine: JSEngineHandle) -> RuntimeBuilder<ML, Std> {
+		self.workers = Some((engine, init_worker_standard_modules::<Std>));
+		self
+	}
+}
+
+fn init_worker_standard_modules<Std: StandardModules + Default>(cx: &Context, global: &mut Object) -> bool {
+	Std::default().init(cx, global)
+}
+
 impl<ML: ModuleLoader + 'static, Std: StandardModules + 'static> Default for RuntimeBuilder<ML, Std> {
 	fn default() -> RuntimeBuilder<ML, Std> {
 		RuntimeBuilder {
@@ -160,6 +443,11 @@ impl<ML: ModuleLoader + 'static, Std: StandardModules + 'static> Default for Run
 			macrotask_queue: false,
 			modules: None,
 			standard_modules: None,
+			custom_modules: Vec::new(),
+			workers: None,
+			console: None,
+			rejection_callback: None,
+			options: ContextOptions::default(),
 		}
 	}
 }
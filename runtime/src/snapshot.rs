@@ -0,0 +1,193 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::collections::HashMap;
+use std::fs::{read, write};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use ion::{Context, ErrorReport};
+use ion::stencil::Stencil;
+
+use crate::cache::{Cache, hash};
+use crate::config::CONFIG;
+use crate::VERSION;
+
+const MAGIC: &[u8] = b"SPIDERFIRE-SNAPSHOT";
+
+static SNAPSHOT: OnceLock<Option<Snapshot>> = OnceLock::new();
+
+#[derive(Clone, Debug)]
+struct Entry {
+	module: bool,
+	hash: String,
+	stencil: Vec<u8>,
+}
+
+/// Represents a bundle of pre-compiled stencils for the standard modules and user code, which are decoded at startup instead of
+/// compiling their sources.
+///
+/// Entries are keyed by the name of a standard module, or the canonical path of a file, and are only used if their source is unchanged.
+/// As with the code cache, snapshots are only valid for the engine build that produced them.
+#[derive(Clone, Debug, Default)]
+pub struct Snapshot {
+	entries: HashMap<String, Entry>,
+}
+
+impl Snapshot {
+	/// Returns the snapshot used by the runtime, which is loaded on first use.
+	///
+	/// The snapshot configured with [Config::snapshot](crate::config::Config::snapshot) is used if present,
+	/// otherwise the default snapshot in the cache is used unless the code cache is disabled.
+	pub fn global() -> Option<&'static Snapshot> {
+		SNAPSHOT
+			.get_or_init(|| {
+				let config = CONFIG.get()?;
+				let path = match &config.snapshot {
+					Some(path) => path.clone(),
+					None => config.code_cache.then(Snapshot::default_path).flatten()?,
+				};
+				Snapshot::from_file(&path).ok()
+			})
+			.as_ref()
+	}
+
+	/// Sets the snapshot used by the runtime, instead of loading it from a file.
+	/// Returns `false` if the snapshot has already been loaded.
+	pub fn set_global(snapshot: Snapshot) -> bool {
+		SNAPSHOT.set(Some(snapshot)).is_ok()
+	}
+
+	/// Returns the path of the default snapshot in the cache.
+	pub fn default_path() -> Option<PathBuf> {
+		Cache::new().map(|cache| cache.dir().join("snapshot.bin"))
+	}
+
+	/// Reads a snapshot from a file.
+	pub fn from_file(path: &Path) -> io::Result<Snapshot> {
+		let bytes = read(path)?;
+		Snapshot::from_bytes(&bytes).ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "Invalid or incompatible snapshot"))
+	}
+
+	/// Decodes a snapshot from bytes produced by [Snapshot::to_bytes].
+	/// Returns [None] if the bytes are malformed or were produced by a different version.
+	pub fn from_bytes(bytes: &[u8]) -> Option<Snapshot> {
+		let mut reader = Reader { bytes };
+		if reader.read(MAGIC.len())? != MAGIC || reader.read_string()? != VERSION {
+			return None;
+		}
+
+		let count = reader.read_u32()?;
+		let mut entries = HashMap::with_capacity(count as usize);
+		for _ in 0..count {
+			let name = reader.read_string()?;
+			let module = reader.read(1)?[0] != 0;
+			let hash = reader.read_string()?;
+			let stencil = reader.read_bytes()?.to_vec();
+			entries.insert(name, Entry { module, hash, stencil });
+		}
+		reader.bytes.is_empty().then_some(Snapshot { entries })
+	}
+
+	/// Encodes the snapshot into bytes.
+	pub fn to_bytes(&self) -> Vec<u8> {
+		let mut bytes = Vec::from(MAGIC);
+		write_bytes(&mut bytes, VERSION.as_bytes());
+		bytes.extend_from_slice(&(self.entries.len() as u32).to_le_bytes());
+		for (name, entry) in &self.entries {
+			write_bytes(&mut bytes, name.as_bytes());
+			bytes.push(entry.module as u8);
+			write_bytes(&mut bytes, entry.hash.as_bytes());
+			write_bytes(&mut bytes, &entry.stencil);
+		}
+		bytes
+	}
+
+	/// Writes the snapshot to a file.
+	pub fn save(&self, path: &Path) -> io::Result<()> {
+		write(path, self.to_bytes())
+	}
+
+	/// Compiles a module and adds it to the snapshot under `name`.
+	pub fn add_module(&mut self, cx: &Context, name: &str, source: &str) -> Result<(), ErrorReport> {
+		let stencil = Stencil::compile_module(cx, Path::new(name), source)?;
+		self.add_stencil(cx, name, source, &stencil, true);
+		Ok(())
+	}
+
+	/// Compiles a script and adds it to the snapshot under `name`.
+	pub fn add_script(&mut self, cx: &Context, name: &str, source: &str) -> Result<(), ErrorReport> {
+		let stencil = Stencil::compile_script(cx, Path::new(name), source)?;
+		self.add_stencil(cx, name, source, &stencil, false);
+		Ok(())
+	}
+
+	/// Adds a compiled [Stencil] of `source` to the snapshot under `name`.
+	/// Returns `false` if the stencil could not be encoded.
+	pub fn add_stencil(&mut self, cx: &Context, name: &str, source: &str, stencil: &Stencil, module: bool) -> bool {
+		let Some(stencil) = stencil.encode(cx) else {
+			return false;
+		};
+		let entry = Entry {
+			module,
+			hash: hash(source, None),
+			stencil,
+		};
+		self.entries.insert(String::from(name), entry);
+		true
+	}
+
+	/// Returns the [Stencil] stored under `name`, if it was compiled from `source` as a module or script.
+	pub fn stencil(&self, cx: &Context, name: &str, source: &str, module: bool) -> Option<Stencil> {
+		let entry = self.entries.get(name)?;
+		if entry.module != module || entry.hash != hash(source, None) {
+			return None;
+		}
+		Stencil::decode(cx, &entry.stencil)
+	}
+
+	pub fn len(&self) -> usize {
+		self.entries.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.entries.is_empty()
+	}
+}
+
+pub(crate) struct Reader<'b> {
+	pub(crate) bytes: &'b [u8],
+}
+
+impl<'b> Reader<'b> {
+	pub(crate) fn read(&mut self, len: usize) -> Option<&'b [u8]> {
+		if self.bytes.len() < len {
+			return None;
+		}
+		let (read, rest) = self.bytes.split_at(len);
+		self.bytes = rest;
+		Some(read)
+	}
+
+	pub(crate) fn read_u32(&mut self) -> Option<u32> {
+		self.read(4).map(|bytes| u32::from_le_bytes(bytes.try_into().unwrap()))
+	}
+
+	pub(crate) fn read_bytes(&mut self) -> Option<&'b [u8]> {
+		let len = self.read_u32()?;
+		self.read(len as usize)
+	}
+
+	pub(crate) fn read_string(&mut self) -> Option<String> {
+		self.read_bytes().and_then(|bytes| String::from_utf8(bytes.to_vec()).ok())
+	}
+}
+
+pub(crate) fn write_bytes(buffer: &mut Vec<u8>, bytes: &[u8]) {
+	buffer.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+	buffer.extend_from_slice(bytes);
+}
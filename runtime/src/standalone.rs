@@ -0,0 +1,140 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::env::current_exe;
+use std::fs::{File, read, write};
+use std::io;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+use crate::snapshot::{Reader, write_bytes};
+use crate::VERSION;
+
+const MAGIC: &[u8] = b"SPIDERFIRE-STANDALONE";
+/// Length of the trailer at the end of a standalone executable, which holds the length of the application and the magic bytes.
+const TRAILER_LENGTH: usize = 8 + MAGIC.len();
+
+/// Represents an application embedded into a copy of the runtime's executable, which runs it instead of parsing its arguments.
+///
+/// The application is appended to the executable, followed by a trailer, so that it can be found without reading the whole executable.
+/// Applications are only valid for the version of the runtime that embedded them.
+#[derive(Clone, Debug)]
+pub struct Standalone {
+	/// Name of the entry module, which is used in stack traces.
+	pub name: String,
+	/// Source of the bundled module.
+	pub source: String,
+	/// Encoded [Snapshot](crate::snapshot::Snapshot) of the standard modules and the bundled module.
+	pub snapshot: Option<Vec<u8>>,
+	/// Runtime flags which the application was compiled with, such as permissions.
+	pub flags: Vec<String>,
+}
+
+impl Standalone {
+	/// Returns the application embedded in the current executable, if it has one.
+	pub fn current() -> Option<Standalone> {
+		let path = current_exe().ok()?;
+		Standalone::from_executable(&path).ok().flatten()
+	}
+
+	/// Reads the application embedded in an executable.
+	/// Returns [None] if the executable does not have one, or it was embedded by a different version.
+	pub fn from_executable(path: &Path) -> io::Result<Option<Standalone>> {
+		let mut file = File::open(path)?;
+		let length = file.seek(SeekFrom::End(0))?;
+		if length < TRAILER_LENGTH as u64 {
+			return Ok(None);
+		}
+
+		let mut trailer = [0; TRAILER_LENGTH];
+		file.seek(SeekFrom::End(-(TRAILER_LENGTH as i64)))?;
+		file.read_exact(&mut trailer)?;
+		if &trailer[8..] != MAGIC {
+			return Ok(None);
+		}
+
+		let size = u64::from_le_bytes(trailer[..8].try_into().unwrap());
+		if size > length - TRAILER_LENGTH as u64 {
+			return Ok(None);
+		}
+		let mut bytes = vec![0; size as usize];
+		file.seek(SeekFrom::End(-((size as usize + TRAILER_LENGTH) as i64)))?;
+		file.read_exact(&mut bytes)?;
+		Ok(Standalone::from_bytes(&bytes))
+	}
+
+	/// Decodes an application from bytes produced by [Standalone::to_bytes].
+	pub fn from_bytes(bytes: &[u8]) -> Option<Standalone> {
+		let mut reader = Reader { bytes };
+		if reader.read_string()? != VERSION {
+			return None;
+		}
+
+		let name = reader.read_string()?;
+		let source = reader.read_string()?;
+		let snapshot = match reader.read(1)?[0] {
+			0 => None,
+			_ => Some(reader.read_bytes()?.to_vec()),
+		};
+		let count = reader.read_u32()?;
+		let flags = (0..count).map(|_| reader.read_string()).collect::<Option<_>>()?;
+		reader.bytes.is_empty().then_some(Standalone { name, source, snapshot, flags })
+	}
+
+	/// Encodes the application into bytes.
+	pub fn to_bytes(&self) -> Vec<u8> {
+		let mut bytes = Vec::new();
+		write_bytes(&mut bytes, VERSION.as_bytes());
+		write_bytes(&mut bytes, self.name.as_bytes());
+		write_bytes(&mut bytes, self.source.as_bytes());
+		match &self.snapshot {
+			Some(snapshot) => {
+				bytes.push(1);
+				write_bytes(&mut bytes, snapshot);
+			}
+			None => bytes.push(0),
+		}
+		bytes.extend_from_slice(&(self.flags.len() as u32).to_le_bytes());
+		for flag in &self.flags {
+			write_bytes(&mut bytes, flag.as_bytes());
+		}
+		bytes
+	}
+
+	/// Writes a copy of the executable at `binary` with the application embedded to `output`, and makes it executable.
+	/// An application already embedded in the executable is replaced.
+	pub fn save(&self, binary: &Path, output: &Path) -> io::Result<()> {
+		let mut executable = read(binary)?;
+		executable.truncate(executable_length(&executable));
+
+		let bytes = self.to_bytes();
+		executable.extend_from_slice(&bytes);
+		executable.extend_from_slice(&(bytes.len() as u64).to_le_bytes());
+		executable.extend_from_slice(MAGIC);
+		write(output, executable)?;
+
+		#[cfg(unix)]
+		{
+			use std::fs::{metadata, set_permissions};
+			use std::os::unix::fs::PermissionsExt;
+
+			let mut permissions = metadata(output)?.permissions();
+			permissions.set_mode(permissions.mode() | 0o111);
+			set_permissions(output, permissions)?;
+		}
+		Ok(())
+	}
+}
+
+/// Returns the length of an executable without the application embedded in it.
+fn executable_length(executable: &[u8]) -> usize {
+	let length = executable.len();
+	if length < TRAILER_LENGTH || &executable[length - MAGIC.len()..] != MAGIC {
+		return length;
+	}
+	let size = u64::from_le_bytes(executable[length - TRAILER_LENGTH..length - MAGIC.len()].try_into().unwrap()) as usize;
+	length.checked_sub(size + TRAILER_LENGTH).unwrap_or(length)
+}
@@ -6,7 +6,8 @@
 
 use std::fmt;
 use std::fmt::{Display, Formatter};
-use std::path::PathBuf;
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
 use std::string::FromUtf8Error;
 
 use sourcemap::SourceMap;
@@ -18,34 +19,47 @@ use swc_core::common::sync::Lrc;
 use swc_core::ecma::ast::EsVersion;
 use swc_core::ecma::codegen::{Config as CodegenConfig, Emitter};
 use swc_core::ecma::codegen::text_writer::JsWriter;
-use swc_core::ecma::parser::{Capturing, Parser, Syntax};
+use swc_core::ecma::parser::{Capturing, Parser, Syntax, TsConfig};
 use swc_core::ecma::parser::lexer::Lexer;
 use swc_core::ecma::transforms::base::fixer::fixer;
 use swc_core::ecma::transforms::base::hygiene::hygiene;
 use swc_core::ecma::transforms::base::resolver;
+use swc_core::ecma::transforms::react::{Options as ReactOptions, react};
 use swc_core::ecma::transforms::typescript::strip;
 use swc_core::ecma::visit::FoldWith;
 
 use crate::config::Config;
 
+/// File extensions which are transpiled as TypeScript.
+pub const EXTENSIONS: [&str; 4] = ["ts", "tsx", "mts", "cts"];
+
+/// Checks if a file should be transpiled as TypeScript, based on its extension.
+pub fn is_typescript(path: &Path) -> bool {
+	Config::global().typescript && path.extension().and_then(OsStr::to_str).map_or(false, |ext| EXTENSIONS.contains(&ext))
+}
+
+/// Strips types from TypeScript source, returning the emitted JavaScript and its source map.
+/// JSX in `.tsx` files is transformed into `React.createElement` calls.
 pub fn compile_typescript(filename: &str, source: &str) -> Result<(String, SourceMap), Error> {
-	let name = FileName::Real(PathBuf::from(filename));
+	let path = PathBuf::from(filename);
+	let tsx = path.extension() == Some(OsStr::new("tsx"));
+	let name = FileName::Real(path);
 
 	let source_map: Lrc<SwcSourceMap> = Default::default();
 	let file = source_map.new_source_file(name, String::from(source));
 	let input = StringInput::from(&*file);
 
 	let comments = SingleThreadedComments::default();
-	let (handler, mut parser) = initialise_parser(source_map.clone(), &comments, input);
+	let (handler, mut parser) = initialise_parser(source_map.clone(), &comments, input, tsx);
 
 	let mut buffer = Vec::new();
 	let mut mappings = Vec::new();
 	let mut emitter = initialise_emitter(source_map.clone(), &comments, &mut buffer, &mut mappings);
 
 	if Config::global().script {
-		handle_script(&handler, &mut parser, &mut emitter)?;
+		handle_script(&handler, &mut parser, &mut emitter, tsx)?;
 	} else {
-		handle_module(&handler, &mut parser, &mut emitter)?;
+		handle_module(&handler, &mut parser, &mut emitter, tsx)?;
 	}
 
 	let source_map = source_map.build_source_map(&mappings);
@@ -53,7 +67,7 @@ pub fn compile_typescript(filename: &str, source: &str) -> Result<(String, Sourc
 }
 
 pub fn handle_script(
-	handler: &Handler, parser: &mut Parser<Capturing<Lexer>>, emitter: &mut Emitter<JsWriter<&mut Vec<u8>>, SwcSourceMap>,
+	handler: &Handler, parser: &mut Parser<Capturing<Lexer>>, emitter: &mut Emitter<JsWriter<&mut Vec<u8>>, SwcSourceMap>, tsx: bool,
 ) -> Result<(), Error> {
 	let script = parser.parse_script().map_err(|e| {
 		e.into_diagnostic(handler).emit();
@@ -68,6 +82,17 @@ pub fn handle_script(
 
 		let script = script.fold_with(&mut resolver(unresolved_mark, top_level_mark, true));
 		let script = script.fold_with(&mut strip(top_level_mark));
+		let script = if tsx {
+			script.fold_with(&mut react(
+				emitter.cm.clone(),
+				comments,
+				ReactOptions::default(),
+				top_level_mark,
+				unresolved_mark,
+			))
+		} else {
+			script
+		};
 		let script = script.fold_with(&mut hygiene());
 		script.fold_with(&mut fixer(comments))
 	});
@@ -76,7 +101,7 @@ pub fn handle_script(
 }
 
 pub fn handle_module(
-	handler: &Handler, parser: &mut Parser<Capturing<Lexer>>, emitter: &mut Emitter<JsWriter<&mut Vec<u8>>, SwcSourceMap>,
+	handler: &Handler, parser: &mut Parser<Capturing<Lexer>>, emitter: &mut Emitter<JsWriter<&mut Vec<u8>>, SwcSourceMap>, tsx: bool,
 ) -> Result<(), Error> {
 	let module = parser.parse_module().map_err(|e| {
 		e.into_diagnostic(handler).emit();
@@ -91,6 +116,17 @@ pub fn handle_module(
 
 		let module = module.fold_with(&mut resolver(unresolved_mark, top_level_mark, true));
 		let module = module.fold_with(&mut strip(top_level_mark));
+		let module = if tsx {
+			module.fold_with(&mut react(
+				emitter.cm.clone(),
+				comments,
+				ReactOptions::default(),
+				top_level_mark,
+				unresolved_mark,
+			))
+		} else {
+			module
+		};
 		let module = module.fold_with(&mut hygiene());
 		module.fold_with(&mut fixer(comments))
 	});
@@ -99,10 +135,11 @@ pub fn handle_module(
 }
 
 fn initialise_parser<'a>(
-	source_map: Lrc<SwcSourceMap>, comments: &'a dyn Comments, input: StringInput<'a>,
+	source_map: Lrc<SwcSourceMap>, comments: &'a dyn Comments, input: StringInput<'a>, tsx: bool,
 ) -> (Handler, Parser<Capturing<Lexer<'a>>>) {
 	let handler = Handler::with_tty_emitter(ColorConfig::Auto, true, false, Some(source_map));
-	let lexer = Lexer::new(Syntax::Typescript(Default::default()), EsVersion::Es2022, input, Some(comments));
+	let syntax = Syntax::Typescript(TsConfig { tsx, ..TsConfig::default() });
+	let lexer = Lexer::new(syntax, EsVersion::Es2022, input, Some(comments));
 	let capturing = Capturing::new(lexer);
 	let mut parser = Parser::new_from(capturing);
 
@@ -0,0 +1,200 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::sync::{Arc, Condvar, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use mozjs::jsapi::{JS_RequestInterruptCallback, JSContext};
+
+use ion::Context;
+
+use crate::ContextExt;
+
+#[derive(Debug)]
+struct Shared {
+	/// Address of the context, which is cleared when the runtime is dropped.
+	context: Mutex<Option<usize>>,
+	terminating: AtomicBool,
+}
+
+/// Terminates the script running in a runtime, and can be sent to other threads.
+///
+/// Termination is uncatchable, so `try` and `finally` blocks of the script are not run.
+/// The runtime can still be used afterwards, such as to evaluate another script.
+#[derive(Clone, Debug)]
+pub struct InterruptHandle {
+	shared: Arc<Shared>,
+}
+
+impl InterruptHandle {
+	/// Terminates the script which is running, or the next script to run if the runtime is idle.
+	/// Returns `false` if the runtime has been dropped.
+	pub fn terminate(&self) -> bool {
+		let context = self.shared.context.lock().unwrap();
+		if let Some(context) = *context {
+			self.shared.terminating.store(true, Ordering::SeqCst);
+			unsafe {
+				JS_RequestInterruptCallback(context as *mut JSContext);
+			}
+			true
+		} else {
+			false
+		}
+	}
+}
+
+/// Holds the termination state of a runtime, which is checked by its interrupt callback.
+#[derive(Debug, Default)]
+pub(crate) struct Interrupt {
+	handle: Option<InterruptHandle>,
+	watchdog: Option<Watchdog>,
+	terminated: bool,
+}
+
+impl Interrupt {
+	pub(crate) fn attach(&mut self, cx: &Context) {
+		let shared = Shared {
+			context: Mutex::new(Some(cx.as_ptr() as usize)),
+			terminating: AtomicBool::new(false),
+		};
+		self.handle = Some(InterruptHandle { shared: Arc::new(shared) });
+	}
+
+	/// Checks if termination was requested, which is called from the interrupt callback of the runtime.
+	/// Returns `false` if the running script should be terminated.
+	pub(crate) fn check(&mut self) -> bool {
+		let terminating = self
+			.handle
+			.as_ref()
+			.is_some_and(|handle| handle.shared.terminating.swap(false, Ordering::SeqCst));
+		if terminating {
+			self.terminated = true;
+		}
+		!terminating
+	}
+}
+
+impl Drop for Interrupt {
+	fn drop(&mut self) {
+		// The watchdog is stopped first, as it can request an interrupt until the context is cleared.
+		self.watchdog = None;
+		if let Some(handle) = &self.handle {
+			*handle.shared.context.lock().unwrap() = None;
+		}
+	}
+}
+
+#[derive(Debug, Default)]
+struct Deadline {
+	deadline: Option<Instant>,
+	stopped: bool,
+}
+
+/// Thread which terminates the running script of a runtime once a deadline has passed.
+#[derive(Debug)]
+struct Watchdog {
+	state: Arc<(Mutex<Deadline>, Condvar)>,
+	thread: Option<JoinHandle<()>>,
+}
+
+impl Watchdog {
+	fn start(handle: InterruptHandle) -> Option<Watchdog> {
+		let state = Arc::new((Mutex::new(Deadline::default()), Condvar::new()));
+		let thread = {
+			let state = Arc::clone(&state);
+			thread::Builder::new().name(String::from("watchdog")).spawn(move || {
+				let (lock, condvar) = &*state;
+				let mut state = lock.lock().unwrap();
+				while !state.stopped {
+					match state.deadline {
+						Some(deadline) => {
+							let now = Instant::now();
+							if now >= deadline {
+								state.deadline = None;
+								handle.terminate();
+							} else {
+								state = condvar.wait_timeout(state, deadline - now).unwrap().0;
+							}
+						}
+						None => state = condvar.wait(state).unwrap(),
+					}
+				}
+			})
+		};
+		Some(Watchdog { state, thread: Some(thread.ok()?) })
+	}
+
+	fn set_deadline(&self, deadline: Option<Instant>) {
+		let (lock, condvar) = &*self.state;
+		lock.lock().unwrap().deadline = deadline;
+		condvar.notify_one();
+	}
+
+	fn deadline(&self) -> Option<Instant> {
+		self.state.0.lock().unwrap().deadline
+	}
+}
+
+impl Drop for Watchdog {
+	fn drop(&mut self) {
+		let (lock, condvar) = &*self.state;
+		lock.lock().unwrap().stopped = true;
+		condvar.notify_one();
+		if let Some(thread) = self.thread.take() {
+			let _ = thread.join();
+		}
+	}
+}
+
+/// Returns a handle which terminates the script running in the runtime of the context, from any thread.
+pub fn interrupt_handle(cx: &Context) -> InterruptHandle {
+	let interrupt = unsafe { &mut (*cx.get_private().as_ptr()).interrupt };
+	interrupt.handle.clone().expect("Runtime should have been built")
+}
+
+/// Sets the instant at which the running script of the runtime is terminated, or clears it with [None].
+///
+/// The deadline is cleared once it has passed, so each deadline only terminates one script.
+/// Returns `false` if the thread which waits for the deadline could not be started.
+pub fn set_deadline(cx: &Context, deadline: Option<Instant>) -> bool {
+	let interrupt = unsafe { &mut (*cx.get_private().as_ptr()).interrupt };
+	if interrupt.watchdog.is_none() {
+		if deadline.is_none() {
+			return true;
+		}
+		let Some(handle) = interrupt.handle.clone() else {
+			return false;
+		};
+		interrupt.watchdog = Watchdog::start(handle);
+	}
+	match &interrupt.watchdog {
+		Some(watchdog) => {
+			watchdog.set_deadline(deadline);
+			true
+		}
+		None => false,
+	}
+}
+
+/// Sets the deadline of the runtime to `limit` from now, as with [set_deadline].
+pub fn set_time_limit(cx: &Context, limit: Duration) -> bool {
+	set_deadline(cx, Some(Instant::now() + limit))
+}
+
+/// Returns the deadline of the runtime, which has not yet passed.
+pub fn deadline(cx: &Context) -> Option<Instant> {
+	let interrupt = unsafe { &(*cx.get_private().as_ptr()).interrupt };
+	interrupt.watchdog.as_ref().and_then(Watchdog::deadline)
+}
+
+/// Checks if a script of the runtime has been terminated since this was last called.
+pub fn take_terminated(cx: &Context) -> bool {
+	let interrupt = unsafe { &mut (*cx.get_private().as_ptr()).interrupt };
+	std::mem::take(&mut interrupt.terminated)
+}
@@ -0,0 +1,43 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::path::Path;
+
+use futures::executor::block_on;
+use mozjs::jsapi::PromiseState;
+use mozjs::rust::{JSEngine, Runtime};
+
+use ion::Context;
+use ion::module::Module;
+use runtime::config::{Config, CONFIG, LogLevel};
+use runtime::modules::Loader;
+use runtime::RuntimeBuilder;
+
+const FILE_NAME: &str = "abort.js";
+const SCRIPT: &str = include_str!("scripts/abort.js");
+
+#[test]
+fn abort() {
+	CONFIG.set(Config::default().log_level(LogLevel::Debug).code_cache(false)).unwrap();
+
+	let engine = JSEngine::init().unwrap();
+	let rt = Runtime::new(engine.handle());
+
+	let cx = &mut Context::from_runtime(&rt);
+	let rt = RuntimeBuilder::<_, ()>::new()
+		.microtask_queue()
+		.macrotask_queue()
+		.modules(Loader::default())
+		.build(cx);
+
+	let path = format!("./tests/scripts/{}", FILE_NAME);
+	let (_, promise) = Module::compile(rt.cx(), FILE_NAME, Some(Path::new(&path)), SCRIPT).unwrap();
+	let promise = promise.unwrap();
+	assert_eq!(PromiseState::Pending, promise.state());
+
+	block_on(rt.run_event_loop()).unwrap();
+	assert_eq!(PromiseState::Fulfilled, promise.state());
+}
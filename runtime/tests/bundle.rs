@@ -0,0 +1,45 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::path::Path;
+
+use mozjs::jsapi::PromiseState;
+use mozjs::rust::{JSEngine, Runtime};
+
+use ion::Context;
+use ion::module::Module;
+use runtime::bundler::{bundle, BundleOptions};
+use runtime::config::{Config, CONFIG, LogLevel};
+use runtime::modules::Loader;
+use runtime::RuntimeBuilder;
+
+const FILE_NAME: &str = "bundle.js";
+const ENTRY: &str = "./tests/scripts/bundle/main.js";
+
+#[test]
+fn bundle_graph() {
+	CONFIG.set(Config::default().log_level(LogLevel::Debug).code_cache(false)).unwrap();
+
+	let engine = JSEngine::init().unwrap();
+	let rt = Runtime::new(engine.handle());
+
+	let cx = &mut Context::from_runtime(&rt);
+	let rt = RuntimeBuilder::<_, ()>::new().microtask_queue().modules(Loader::default()).build(cx);
+
+	let bundle = bundle(rt.cx(), &mut Loader::default(), Path::new(ENTRY), BundleOptions::default()).unwrap();
+	assert_eq!(4, bundle.modules.len());
+	assert!(bundle.modules.last().unwrap().ends_with("main.js"));
+	assert!(!bundle.code.contains("unusedHelper"));
+	assert!(!bundle.code.contains("interface"));
+	assert!(bundle.code.contains("//# sourceMappingURL=data:application/json;base64,"));
+
+	let (_, promise) = Module::compile(rt.cx(), FILE_NAME, None, &bundle.code).unwrap();
+	let promise = promise.unwrap();
+	if let Some(Err(error)) = promise.settled_result(rt.cx()) {
+		panic!("Error: {:?}", ion::Exception::from_value(rt.cx(), &error));
+	}
+	assert_eq!(PromiseState::Fulfilled, promise.state());
+}
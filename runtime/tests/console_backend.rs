@@ -0,0 +1,60 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::cell::RefCell;
+use std::path::Path;
+use std::rc::Rc;
+
+use mozjs::rust::{JSEngine, Runtime};
+
+use ion::Context;
+use ion::script::Script;
+use runtime::config::{Config, CONFIG, LogLevel};
+use runtime::globals::console::ConsoleBackend;
+use runtime::RuntimeBuilder;
+
+const FILE_NAME: &str = "console-backend.js";
+const SCRIPT: &str = include_str!("scripts/console-backend.js");
+
+#[derive(Clone, Default)]
+struct CaptureBackend {
+	messages: Rc<RefCell<Vec<(LogLevel, String)>>>,
+}
+
+impl ConsoleBackend for CaptureBackend {
+	fn write(&self, level: LogLevel, message: &str) {
+		self.messages.borrow_mut().push((level, String::from(message)));
+	}
+}
+
+#[test]
+fn console_backend() {
+	CONFIG.set(Config::default().log_level(LogLevel::Debug).script(true)).unwrap();
+
+	let engine = JSEngine::init().unwrap();
+	let rt = Runtime::new(engine.handle());
+
+	let backend = CaptureBackend::default();
+	let cx = &mut Context::from_runtime(&rt);
+	let rt = RuntimeBuilder::<()>::new().console(backend.clone()).build(cx);
+
+	let result = Script::compile_and_evaluate(rt.cx(), Path::new(FILE_NAME), SCRIPT);
+	assert!(result.is_ok(), "Error: {:?}", result.unwrap_err());
+
+	let messages = backend.messages.borrow();
+	let expected = [
+		(LogLevel::Info, "Log Message"),
+		(LogLevel::Warn, "Warn"),
+		(LogLevel::Error, "Error"),
+		(LogLevel::Info, "Group"),
+		(LogLevel::Info, "  Indented"),
+	];
+	assert_eq!(messages.len(), expected.len());
+	for ((level, message), (expected_level, expected_message)) in messages.iter().zip(expected) {
+		assert_eq!(*level, expected_level);
+		assert_eq!(message, expected_message);
+	}
+}
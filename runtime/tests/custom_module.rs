@@ -0,0 +1,105 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::cell::Cell;
+use std::path::Path;
+use std::rc::Rc;
+
+use futures::executor::block_on;
+use mozjs::jsapi::PromiseState;
+use mozjs::rust::{JSEngine, JSEngineHandle, Runtime};
+
+use ion::{Context, Function, Object, Value};
+use ion::flags::PropertyFlags;
+use ion::module::Module;
+use runtime::config::{Config, CONFIG, LogLevel};
+use runtime::modules::{CustomModule, Loader};
+use runtime::RuntimeBuilder;
+
+const FILE_NAME: &str = "module-custom.js";
+const SCRIPT: &str = include_str!("scripts/module-custom.js");
+
+struct Counter {
+	count: Rc<Cell<i32>>,
+}
+
+impl CustomModule for Counter {
+	fn init(&mut self, _: &Context) -> bool {
+		self.count.set(5);
+		true
+	}
+
+	fn exports<'cx>(&mut self, cx: &'cx Context) -> Option<Object<'cx>> {
+		let count = Rc::clone(&self.count);
+		let next = Function::from_closure(
+			cx,
+			"next",
+			Box::new(move |args| {
+				count.set(count.get() + 1);
+				Ok(Value::i32(args.cx(), count.get()))
+			}),
+			0,
+			PropertyFlags::empty(),
+		);
+
+		let mut exports = Object::new(cx);
+		(exports.set_as(cx, "label", "counter") && exports.set_as(cx, "next", &next)).then_some(exports)
+	}
+}
+
+struct Failing;
+
+impl CustomModule for Failing {
+	fn init(&mut self, _: &Context) -> bool {
+		false
+	}
+
+	fn exports<'cx>(&mut self, _: &'cx Context) -> Option<Object<'cx>> {
+		None
+	}
+}
+
+#[test]
+fn custom_module() {
+	CONFIG.set(Config::default().log_level(LogLevel::Debug).code_cache(false)).unwrap();
+
+	let engine = JSEngine::init().unwrap();
+	run(engine.handle());
+	fail(engine.handle());
+}
+
+fn run(engine: JSEngineHandle) {
+	let rt = Runtime::new(engine);
+
+	let cx = &mut Context::from_runtime(&rt);
+	let rt = RuntimeBuilder::<_, ()>::new()
+		.microtask_queue()
+		.modules(Loader::default())
+		.register_module("counter", Counter { count: Rc::new(Cell::new(0)) })
+		.register_module("@scope/counter", Counter { count: Rc::new(Cell::new(0)) })
+		.build(cx);
+
+	let path = format!("./tests/scripts/{}", FILE_NAME);
+	let (_, promise) = Module::compile(rt.cx(), FILE_NAME, Some(Path::new(&path)), SCRIPT).unwrap();
+	let promise = promise.unwrap();
+
+	block_on(rt.run_event_loop()).unwrap();
+	assert_eq!(PromiseState::Fulfilled, promise.state());
+	rt.shutdown();
+}
+
+/// Modules which fail to initialise cause the runtime to fail to build.
+fn fail(engine: JSEngineHandle) {
+	let rt = Runtime::new(engine);
+
+	let cx = &mut Context::from_runtime(&rt);
+	let result = RuntimeBuilder::<_, ()>::new()
+		.microtask_queue()
+		.modules(Loader::default())
+		.register_module("failing", Failing)
+		.try_build(cx);
+	assert!(result.is_err());
+}
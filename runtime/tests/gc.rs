@@ -0,0 +1,33 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::path::Path;
+
+use mozjs::rust::{JSEngine, Runtime};
+
+use ion::Context;
+use ion::conversions::FromValue;
+use ion::script::Script;
+use runtime::options::ContextOptions;
+use runtime::RuntimeBuilder;
+
+#[test]
+fn gc() {
+	let engine = JSEngine::init().unwrap();
+	let rt = Runtime::new(engine.handle());
+
+	let cx = &mut Context::from_runtime(&rt);
+	let rt = RuntimeBuilder::<()>::new().options(ContextOptions::default().expose_gc(true)).build(cx);
+
+	let before = rt.memory_usage();
+	rt.gc();
+	let after = rt.memory_usage();
+	assert!(after.major_gc_number > before.major_gc_number);
+
+	let result = Script::compile_and_evaluate(rt.cx(), Path::new("gc.js"), "gc(); typeof gc").unwrap();
+	assert_eq!("function", String::from_value(rt.cx(), &result, true, ()).unwrap());
+	assert!(rt.memory_usage().major_gc_number > after.major_gc_number);
+}
@@ -0,0 +1,133 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+#![cfg(feature = "inspector")]
+
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::path::Path;
+use std::thread;
+
+use mozjs::rust::{JSEngine, Runtime};
+use tokio_tungstenite::tungstenite::{connect, Message, WebSocket};
+use tokio_tungstenite::tungstenite::stream::MaybeTlsStream;
+
+use ion::Context;
+use ion::conversions::{ConversionBehavior, FromValue};
+use ion::script::Script;
+use runtime::inspector::Inspector;
+use runtime::RuntimeBuilder;
+
+type Socket = WebSocket<MaybeTlsStream<TcpStream>>;
+
+fn request(address: &str, path: &str, headers: &str) -> String {
+	let mut stream = TcpStream::connect(address).unwrap();
+	write!(stream, "GET {} HTTP/1.1\r\n{}\r\n", path, headers).unwrap();
+	let mut response = String::new();
+	stream.read_to_string(&mut response).unwrap();
+	response
+}
+
+fn get(address: &str, path: &str) -> String {
+	request(address, path, &format!("Host: {}\r\n", address))
+}
+
+fn send(socket: &mut Socket, id: u32, method: &str, params: &str) {
+	let message = format!(r#"{{"id":{},"method":"{}","params":{}}}"#, id, method, params);
+	socket.send(Message::Text(message)).unwrap();
+}
+
+/// Reads messages until one contains `pattern`, and returns it.
+fn receive(socket: &mut Socket, pattern: &str) -> String {
+	loop {
+		if let Message::Text(message) = socket.read().unwrap() {
+			if message.contains(pattern) {
+				return message;
+			}
+		}
+	}
+}
+
+#[test]
+fn inspector() {
+	let engine = JSEngine::init().unwrap();
+	let rt = Runtime::new(engine.handle());
+
+	let cx = &mut Context::from_runtime(&rt);
+	let rt = RuntimeBuilder::<()>::new().build(cx);
+
+	let address = SocketAddr::from(([127, 0, 0, 1], 0));
+	let url = Inspector::start(rt.cx(), address, "inspector.js", "file:///inspector.js").unwrap();
+	let target = url.strip_prefix("ws://").unwrap();
+	let (address, id) = target.split_once('/').unwrap();
+
+	let list = get(address, "/json/list");
+	assert!(list.starts_with("HTTP/1.1 200 OK"));
+	assert!(list.contains(&format!(r#""id":"{}""#, id)));
+	assert!(list.contains(&format!(r#""webSocketDebuggerUrl":"{}""#, url)));
+	assert!(list.contains(r#""url":"file:///inspector.js""#));
+
+	let version = get(address, "/json/version");
+	assert!(version.contains(r#""Protocol-Version":"1.3""#));
+
+	let websocket = get(address, &format!("/{}", id));
+	assert!(websocket.starts_with("HTTP/1.1 400 Bad Request"));
+	assert!(get(address, "/unknown").starts_with("HTTP/1.1 404 Not Found"));
+
+	// Pages which rebind their domain to the loopback address cannot reach the inspector.
+	let port = address.rsplit_once(':').unwrap().1;
+	assert!(request(address, "/json/list", "Host: attacker.example\r\n").starts_with("HTTP/1.1 403 Forbidden"));
+	assert!(request(address, "/json/list", "").starts_with("HTTP/1.1 403 Forbidden"));
+	assert!(get_with_host(address, &format!("localhost:{}", port)).starts_with("HTTP/1.1 200 OK"));
+	assert!(get_with_host(address, &format!("[::1]:{}", port)).starts_with("HTTP/1.1 200 OK"));
+	let upgrade = format!(
+		"Host: {}\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\nSec-WebSocket-Version: 13\r\nOrigin: https://attacker.example\r\n",
+		address
+	);
+	assert!(request(address, &format!("/{}", id), &upgrade).starts_with("HTTP/1.1 403 Forbidden"));
+
+	// The inspector runs in its own global, which is not visible to the runtime.
+	let result = Script::compile_and_evaluate(rt.cx(), Path::new("inspector.js"), "typeof Debugger").unwrap();
+	assert_eq!("undefined", String::from_value(rt.cx(), &result, true, ()).unwrap());
+
+	// Pauses at a breakpoint set before the script is loaded, and evaluates in the paused runtime.
+	let client = thread::spawn(move || {
+		let (mut socket, _) = connect(url).unwrap();
+		send(&mut socket, 1, "Debugger.enable", "{}");
+		send(&mut socket, 2, "Runtime.enable", "{}");
+		send(&mut socket, 3, "Debugger.setBreakpointByUrl", r#"{"lineNumber":1,"url":"breakpoint.js"}"#);
+		let breakpoint = receive(&mut socket, r#""id":3"#);
+		send(&mut socket, 4, "Runtime.runIfWaitingForDebugger", "{}");
+
+		let start = receive(&mut socket, r#""method":"Debugger.paused""#);
+		send(&mut socket, 5, "Debugger.resume", "{}");
+		let paused = receive(&mut socket, r#""method":"Debugger.paused""#);
+		send(&mut socket, 6, "Runtime.evaluate", r#"{"expression":"value","returnByValue":true}"#);
+		let evaluated = receive(&mut socket, r#""id":6"#);
+		send(&mut socket, 7, "Profiler.start", "{}");
+		receive(&mut socket, r#""id":7"#);
+		send(&mut socket, 8, "Profiler.stop", "{}");
+		let profile = receive(&mut socket, r#""id":8"#);
+		send(&mut socket, 9, "Debugger.resume", "{}");
+		receive(&mut socket, r#""method":"Debugger.resumed""#);
+		(breakpoint, start, paused, evaluated, profile)
+	});
+
+	Inspector::wait_for_debugger(rt.cx()).unwrap();
+	let result = Script::compile_and_evaluate(rt.cx(), Path::new("breakpoint.js"), "let value = 42;\nvalue += 1;\nvalue").unwrap();
+	assert_eq!(43, i32::from_value(rt.cx(), &result, true, ConversionBehavior::Default).unwrap());
+
+	let (breakpoint, start, paused, evaluated, profile) = client.join().unwrap();
+	assert!(breakpoint.contains(r#""breakpointId":"1:0:breakpoint.js""#));
+	assert!(start.contains(r#""reason":"Break on start""#));
+	assert!(paused.contains(r#""hitBreakpoints":["1:0:breakpoint.js"]"#));
+	assert!(evaluated.contains(r#""value":42"#));
+	assert!(profile.contains(r#""profile":{"nodes":[{"id":1,"callFrame":{"functionName":"(root)""#));
+}
+
+fn get_with_host(address: &str, host: &str) -> String {
+	request(address, "/json/version", &format!("Host: {}\r\n", host))
+}
@@ -0,0 +1,62 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::path::Path;
+
+use futures::executor::block_on;
+use mozjs::conversions::ConversionBehavior;
+use mozjs::jsapi::PromiseState;
+use mozjs::rust::{JSEngine, Runtime};
+
+use ion::Context;
+use ion::module::Module;
+use ion::script::Script;
+use runtime::config::{Config, CONFIG, LogLevel};
+use runtime::modules::Loader;
+use runtime::RuntimeBuilder;
+
+const FILE_NAME: &str = "lifecycle.js";
+const SCRIPT: &str = include_str!("scripts/lifecycle.js");
+
+#[test]
+fn lifecycle() {
+	CONFIG.set(Config::default().log_level(LogLevel::Debug).code_cache(false)).unwrap();
+
+	let engine = JSEngine::init().unwrap();
+	let rt = Runtime::new(engine.handle());
+
+	let cx = &mut Context::from_runtime(&rt);
+	let rt = RuntimeBuilder::<_, ()>::new()
+		.microtask_queue()
+		.macrotask_queue()
+		.modules(Loader::default())
+		.build(cx);
+
+	let path = format!("./tests/scripts/{}", FILE_NAME);
+	let (_, promise) = Module::compile(rt.cx(), FILE_NAME, Some(Path::new(&path)), SCRIPT).unwrap();
+	let promise = promise.unwrap();
+	assert_eq!(PromiseState::Pending, promise.state());
+
+	block_on(rt.run_event_loop()).unwrap();
+	assert_eq!(PromiseState::Fulfilled, promise.state());
+
+	let before_unloads = rt.global().get_as::<_, i32>(rt.cx(), "beforeUnloads", true, ConversionBehavior::Default);
+	assert_eq!(before_unloads, Some(2));
+
+	let result = Script::compile_and_evaluate(rt.cx(), Path::new("shutdown.js"), "setTimeout(() => {}, 60_000);");
+	assert!(result.is_ok());
+	rt.shutdown();
+	block_on(rt.run_event_loop()).unwrap();
+
+	let unloaded = rt.global().get_as::<_, bool>(rt.cx(), "unloaded", true, ());
+	assert_eq!(unloaded, Some(true));
+
+	// Microtasks cannot be queued once the runtime has shut down, as they would never run.
+	let result = Script::compile_and_evaluate(rt.cx(), Path::new("queue.js"), "queueMicrotask(() => {});");
+	assert!(result.is_err());
+	let result = Script::compile_and_evaluate(rt.cx(), Path::new("promise.js"), "Promise.resolve().then(() => {});");
+	assert!(result.is_err());
+}
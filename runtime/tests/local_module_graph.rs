@@ -0,0 +1,48 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::collections::HashSet;
+use std::path::Path;
+
+use dunce::canonicalize;
+use mozjs::rust::{JSEngine, Runtime};
+use url::Url;
+
+use ion::Context;
+use runtime::cache::Cache;
+use runtime::config::{Config, CONFIG, LogLevel};
+use runtime::modules::graph::local_module_graph;
+use runtime::modules::Loader;
+use runtime::RuntimeBuilder;
+
+const DIRECTORY: &str = "./tests/scripts/local-graph";
+const REMOTE: &str = "https://example.invalid/remote.js";
+
+#[test]
+fn local_module_graph_files() {
+	CONFIG.set(Config::default().log_level(LogLevel::Debug)).unwrap();
+
+	let engine = JSEngine::init().unwrap();
+	let rt = Runtime::new(engine.handle());
+
+	let cx = &mut Context::from_runtime(&rt);
+	let rt = RuntimeBuilder::<(), ()>::new().build(cx);
+
+	let directory = canonicalize(DIRECTORY).unwrap();
+	let files = local_module_graph(rt.cx(), &mut Loader::default(), &directory.join("entry.js"));
+	let expected: HashSet<_> = ["entry.js", "typed.ts", "nested.js", "data.json"]
+		.iter()
+		.map(|file| directory.join(file))
+		.collect();
+	assert_eq!(files, expected, "Missing files and remote modules should be skipped");
+
+	// Modules are neither cached nor fetched.
+	if let Some(cache) = Cache::new() {
+		let folder = cache.find_folder(Path::new(DIRECTORY).join("typed.ts")).unwrap();
+		assert!(!folder.exists(), "TypeScript should not be compiled into the cache");
+		assert!(cache.check_remote(&Url::parse(REMOTE).unwrap()).is_none());
+	}
+}
@@ -0,0 +1,58 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+#![cfg(unix)]
+
+use std::os::unix::fs::symlink;
+use std::{env, fs, process};
+
+use runtime::permissions::{PermissionName, Permissions, PermissionState};
+
+#[test]
+fn symlinks() {
+	let directory = env::temp_dir().join(format!("spiderfire-permissions-{}", process::id()));
+	let granted = directory.join("granted");
+	fs::create_dir_all(&granted).unwrap();
+	fs::write(directory.join("secret"), "secret").unwrap();
+	symlink(&directory, granted.join("escape")).unwrap();
+	let granted_path = granted.to_str().unwrap();
+
+	let permissions = Permissions::none(false).allow(PermissionName::Read, &[String::from(granted_path)]);
+	let query = |path: String| permissions.query(PermissionName::Read, Some(&path));
+
+	let inside = query(format!("{}/file", granted_path));
+	let through_link = query(format!("{}/escape/secret", granted_path));
+	let parent_of_link = query(format!("{}/escape/granted/../secret", granted_path));
+	let created = query(format!("{}/missing/new", granted_path));
+
+	fs::remove_dir_all(&directory).unwrap();
+	assert_eq!(inside, PermissionState::Granted);
+	assert_eq!(through_link, PermissionState::Denied);
+	assert_eq!(parent_of_link, PermissionState::Denied);
+	assert_eq!(created, PermissionState::Granted);
+}
+
+#[test]
+fn net() {
+	let resources = [
+		String::from("example.com"),
+		String::from("localhost:8000"),
+		String::from("::1"),
+		String::from("[::2]:8000"),
+	];
+	let permissions = Permissions::none(false).allow(PermissionName::Net, &resources);
+	let query = |resource: &str| permissions.query(PermissionName::Net, Some(resource));
+
+	assert_eq!(query("example.com"), PermissionState::Granted);
+	assert_eq!(query("example.com:443"), PermissionState::Granted);
+	assert_eq!(query("localhost:8000"), PermissionState::Granted);
+	assert_eq!(query("localhost:8001"), PermissionState::Denied);
+	assert_eq!(query("[::1]:443"), PermissionState::Granted);
+	assert_eq!(query("[0:0:0:0:0:0:0:1]:80"), PermissionState::Granted);
+	assert_eq!(query("[::2]:8000"), PermissionState::Granted);
+	assert_eq!(query("[::2]:8001"), PermissionState::Denied);
+	assert_eq!(query("[::3]:8000"), PermissionState::Denied);
+}
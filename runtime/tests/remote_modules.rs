@@ -0,0 +1,104 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+#![cfg(feature = "fetch")]
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::Path;
+use std::thread;
+
+use dunce::canonicalize;
+use mozjs::jsapi::PromiseState;
+use mozjs::rust::{JSEngine, Runtime};
+use tokio::task::LocalSet;
+use url::Url;
+
+use ion::Context;
+use ion::module::Module;
+use runtime::config::{Config, CONFIG, LogLevel};
+use runtime::modules::Loader;
+use runtime::RuntimeBuilder;
+
+const FILE_NAME: &str = "module-remote.js";
+const SCRIPT: &str = include_str!("scripts/module-remote.js");
+
+#[test]
+fn remote_modules() {
+	CONFIG
+		.set(Config::default().log_level(LogLevel::Debug).code_cache(false).reload(true))
+		.unwrap();
+
+	let origin = serve();
+	let tokio = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+	LocalSet::new().block_on(&tokio, run(&origin));
+}
+
+async fn run(origin: &str) {
+	let engine = JSEngine::init().unwrap();
+	let rt = Runtime::new(engine.handle());
+
+	let cx = &mut Context::from_runtime(&rt);
+	let rt = RuntimeBuilder::<_, ()>::new()
+		.microtask_queue()
+		.macrotask_queue()
+		.modules(Loader::default())
+		.build(cx);
+
+	let path = format!("./tests/scripts/{}", FILE_NAME);
+	let script = SCRIPT.replace("{origin}", origin);
+	let (_, promise) = Module::compile(rt.cx(), FILE_NAME, Some(Path::new(&path)), &script).unwrap();
+	let promise = promise.unwrap();
+
+	rt.run_event_loop().await.unwrap();
+	assert_eq!(PromiseState::Fulfilled, promise.state());
+}
+
+/// Serves the remote modules of the test from a local server, returning its origin.
+fn serve() -> String {
+	let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+	let origin = format!("http://{}", listener.local_addr().unwrap());
+
+	let local = Url::from_file_path(canonicalize("./tests/scripts/remote/local.js").unwrap()).unwrap();
+	thread::spawn(move || {
+		for stream in listener.incoming().flatten() {
+			respond(stream, local.as_str());
+		}
+	});
+	origin
+}
+
+fn respond(mut stream: TcpStream, local: &str) {
+	let mut reader = BufReader::new(&stream);
+	let mut request_line = String::new();
+	if reader.read_line(&mut request_line).is_err() {
+		return;
+	}
+	let mut line = String::new();
+	while reader.read_line(&mut line).is_ok_and(|read| read > 2) {
+		line.clear();
+	}
+
+	let path = request_line.split_whitespace().nth(1).unwrap_or("/");
+	let (status, headers, body) = match path {
+		"/redirect/entry.js" => ("302 Found", "Location: /final/entry.js\r\n", String::new()),
+		"/final/entry.js" => ("200 OK", "", String::from(r#"export {value} from "./dep.js";"#)),
+		"/final/dep.js" => ("200 OK", "", String::from(r#"export const value = "redirected";"#)),
+		"/redirect/dep.js" => ("200 OK", "", String::from(r#"export const value = "requested";"#)),
+		"/local.js" => ("200 OK", "", format!(r#"export {{secret}} from "{}";"#, local)),
+		"/bare.js" => ("200 OK", "", String::from(r#"import "left-pad";"#)),
+		_ => ("404 Not Found", "", String::new()),
+	};
+
+	let response = format!(
+		"HTTP/1.1 {}\r\n{}Content-Type: text/javascript\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+		status,
+		headers,
+		body.len(),
+		body
+	);
+	let _ = stream.write_all(response.as_bytes());
+}
@@ -0,0 +1,61 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::path::Path;
+use std::thread;
+
+use futures::executor::block_on;
+use mozjs::jsapi::PromiseState;
+use mozjs::rust::{JSEngine, JSEngineHandle, Runtime};
+
+use ion::Context;
+use ion::module::Module;
+use runtime::config::{Config, CONFIG, LogLevel};
+use runtime::modules::Loader;
+use runtime::RuntimeBuilder;
+
+const FILE_NAME: &str = "runtimes.js";
+const SCRIPT: &str = include_str!("scripts/runtimes.js");
+
+#[test]
+fn runtimes() {
+	CONFIG.set(Config::default().log_level(LogLevel::Debug).code_cache(false)).unwrap();
+
+	let engine = JSEngine::init().unwrap();
+
+	// Runtimes created one after another on the same thread do not share state.
+	run(engine.handle());
+	run(engine.handle());
+
+	let threads: Vec<_> = (0..4)
+		.map(|_| {
+			let engine = engine.handle();
+			thread::spawn(move || run(engine))
+		})
+		.collect();
+	for thread in threads {
+		thread.join().unwrap();
+	}
+}
+
+fn run(engine: JSEngineHandle) {
+	let rt = Runtime::new(engine);
+
+	let cx = &mut Context::from_runtime(&rt);
+	let rt = RuntimeBuilder::<_, ()>::new()
+		.microtask_queue()
+		.macrotask_queue()
+		.modules(Loader::default())
+		.build(cx);
+
+	let path = format!("./tests/scripts/{}", FILE_NAME);
+	let (_, promise) = Module::compile(rt.cx(), FILE_NAME, Some(Path::new(&path)), SCRIPT).unwrap();
+	let promise = promise.unwrap();
+
+	block_on(rt.run_event_loop()).unwrap();
+	assert_eq!(PromiseState::Fulfilled, promise.state());
+	rt.shutdown();
+}
@@ -0,0 +1,49 @@
+const controller = new AbortController();
+const signal = controller.signal;
+if (controller.signal !== signal || !(signal instanceof EventTarget)) {
+	throw new Error("AbortController did not return the same signal");
+}
+
+let events = 0;
+signal.onabort = event => {
+	if (event.type !== "abort" || !event.isTrusted) {
+		throw new Error("Abort event is incorrect");
+	}
+	events++;
+};
+signal.addEventListener("abort", () => events++);
+
+const reason = new Error("Cancelled");
+controller.abort(reason);
+controller.abort(new Error("Ignored"));
+if (!signal.aborted || signal.reason !== reason || events !== 2) {
+	throw new Error("AbortController did not abort its signal once");
+}
+
+const aborted = AbortSignal.abort();
+if (!aborted.aborted || aborted.reason.name !== "AbortError") {
+	throw new Error("AbortSignal.abort did not create an aborted signal");
+}
+
+const first = new AbortController();
+const second = new AbortController();
+const any = AbortSignal.any([first.signal, second.signal]);
+second.abort("second");
+if (!any.aborted || any.reason !== "second") {
+	throw new Error("AbortSignal.any did not follow its signals");
+}
+if (AbortSignal.any([aborted, first.signal]).reason !== aborted.reason) {
+	throw new Error("AbortSignal.any did not use the reason of an aborted signal");
+}
+
+// AbortSignal.timeout does not keep the event loop running, so a timer waits for it.
+const keepAlive = setTimeout(() => {}, 1000);
+const timeout = AbortSignal.timeout(10);
+const error = await new Promise(resolve => timeout.addEventListener("abort", () => resolve(timeout.reason)));
+clearTimeout(keepAlive);
+if (error.name !== "TimeoutError") {
+	throw new Error("AbortSignal.timeout did not abort with a TimeoutError");
+}
+
+// The event loop completes without waiting for this timeout.
+AbortSignal.timeout(60000);
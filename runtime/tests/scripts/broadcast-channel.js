@@ -0,0 +1,38 @@
+const first = new BroadcastChannel("broadcast");
+const second = new BroadcastChannel("broadcast");
+const other = new BroadcastChannel("other");
+
+let received = 0;
+first.onmessage = () => {
+	throw new Error("Channel received its own message");
+};
+other.onmessage = () => {
+	throw new Error("Channel received a message for another name");
+};
+
+const local = await new Promise(resolve => {
+	second.onmessage = event => resolve(event.data);
+	first.postMessage({ value: 1 });
+});
+if (local.value !== 1) {
+	throw new Error("Message was not delivered to another channel");
+}
+other.close();
+
+const worker = new Worker("./tests/scripts/worker/broadcast.js");
+await new Promise(resolve => worker.onmessage = resolve);
+
+const doubled = await new Promise(resolve => {
+	second.onmessage = event => {
+		received++;
+		resolve(event.data);
+	};
+	first.onmessage = null;
+	second.postMessage(21);
+});
+if (doubled !== 42 || received !== 1) {
+	throw new Error("Message was not delivered across workers");
+}
+
+first.close();
+second.close();
@@ -0,0 +1,18 @@
+import {add, PI} from "./math.js";
+import * as math from "./math.js";
+import greeting, {shout} from "./text.ts";
+import config from "./config.json";
+
+export {add};
+export * from "./text.ts";
+export const result = add(config.value, PI);
+
+if (result !== 4.5) {
+	throw new Error(`Imported bindings did not resolve: ${result}`);
+}
+if (math[Symbol.toStringTag] !== "Module" || math.add !== add) {
+	throw new Error("Namespace object was not created");
+}
+if (greeting !== "hello" || shout(greeting) !== "HELLO!") {
+	throw new Error("TypeScript module was not bundled");
+}
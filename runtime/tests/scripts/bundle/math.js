@@ -0,0 +1,9 @@
+export const PI = 3.5;
+
+export function add(a, b) {
+	return a + b;
+}
+
+export function unusedHelper() {
+	return "unused";
+}
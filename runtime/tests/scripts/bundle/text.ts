@@ -0,0 +1,9 @@
+interface Options {
+	suffix: string;
+}
+
+export function shout(text: string, options: Options = {suffix: "!"}): string {
+	return text.toUpperCase() + options.suffix;
+}
+
+export default "hello";
@@ -0,0 +1,10 @@
+const { increment } = require("./helper.cjs");
+const config = require("./config.json");
+
+let count = config.start;
+
+exports.next = function () {
+	count = increment(count);
+	return count;
+};
+exports.name = config.name;
@@ -0,0 +1 @@
+exports.increment = value => value + 1;
@@ -0,0 +1,3 @@
+exports.class = "class";
+exports.await = "await";
+exports.value = "value";
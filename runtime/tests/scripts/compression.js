@@ -0,0 +1,67 @@
+function assert(condition, message) {
+	if (!condition) {
+		throw new Error(message);
+	}
+}
+
+const encoder = new TextEncoder();
+const decoder = new TextDecoder();
+
+async function collect(stream) {
+	const chunks = [];
+	for await (const chunk of stream) {
+		chunks.push(chunk);
+	}
+	const bytes = new Uint8Array(chunks.reduce((length, chunk) => length + chunk.length, 0));
+	let offset = 0;
+	for (const chunk of chunks) {
+		bytes.set(chunk, offset);
+		offset += chunk.length;
+	}
+	return bytes;
+}
+
+function source(...chunks) {
+	return ReadableStream.from(chunks.map(chunk => (typeof chunk === "string" ? encoder.encode(chunk) : chunk)));
+}
+
+const text = "spiderfire ".repeat(256);
+
+for (const format of ["gzip", "deflate", "deflate-raw", "brotli"]) {
+	const compressed = await collect(source(text.slice(0, 1000), text.slice(1000)).pipeThrough(new CompressionStream(format)));
+	assert(compressed.length > 0 && compressed.length < text.length, `${format} did not compress the data`);
+
+	const decompressed = await collect(source(compressed.slice(0, 10), compressed.slice(10)).pipeThrough(new DecompressionStream(format)));
+	assert(decoder.decode(decompressed) === text, `${format} did not round-trip the data`);
+}
+
+const gzip = await collect(source("hello").pipeThrough(new CompressionStream("gzip")));
+assert(gzip[0] === 0x1f && gzip[1] === 0x8b, "gzip output should begin with the gzip magic number");
+
+const stream = new CompressionStream("deflate");
+assert(stream.readable instanceof ReadableStream, "readable should be a ReadableStream");
+assert(stream.writable instanceof WritableStream, "writable should be a WritableStream");
+
+let invalidFormat = false;
+try {
+	new CompressionStream("zip");
+} catch (error) {
+	invalidFormat = error instanceof TypeError;
+}
+assert(invalidFormat, "Unknown formats should throw a TypeError");
+
+let invalidData = false;
+try {
+	await collect(source(new Uint8Array([1, 2, 3, 4])).pipeThrough(new DecompressionStream("gzip")));
+} catch (error) {
+	invalidData = error instanceof TypeError;
+}
+assert(invalidData, "Invalid data should error the stream with a TypeError");
+
+let invalidChunk = false;
+try {
+	await collect(ReadableStream.from(["text"]).pipeThrough(new CompressionStream("gzip")));
+} catch (error) {
+	invalidChunk = error instanceof TypeError;
+}
+assert(invalidChunk, "Chunks which are not buffers should error the stream");
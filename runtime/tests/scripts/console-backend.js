@@ -0,0 +1,6 @@
+console.log("Log", "Message");
+console.warn("Warn");
+console.error("Error");
+console.group("Group");
+console.info("Indented");
+console.groupEnd();
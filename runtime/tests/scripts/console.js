@@ -50,3 +50,18 @@ for (let i = 0; i < 576; i++) {
 }
 console.timeEnd();
 console.timeEnd("Timer");
+
+console.log("%s is %d years and %f months old", "Spiderfire", 3.5, 7.25);
+console.log("%o and %O", {hidden: [1, 2]}, {shown: true}, "extra");
+console.log("%c Styled", "color: red", "%i%%", 42.9);
+
+const cycle = {name: "Cycle"};
+cycle.self = cycle;
+console.log(cycle);
+
+const nested = {a: {b: {c: {d: {e: {f: "Deep"}}}}}};
+console.dir(nested);
+console.dir(nested, {depth: null, colors: false});
+console.dir(nested, {depth: 0});
+
+console.table([{a: 1, b: "Y"}, {a: "Z", b: 2}]);
@@ -0,0 +1,55 @@
+const bytes = crypto.getRandomValues(new Uint8Array(32));
+if (!(bytes instanceof Uint8Array) || bytes.every(byte => byte === 0)) {
+	throw new Error("crypto.getRandomValues did not fill the array");
+}
+try {
+	crypto.getRandomValues(new Float64Array(4));
+	throw new Error("crypto.getRandomValues accepted a Float64Array");
+} catch (error) {
+	if (error.name !== "TypeMismatchError") {
+		throw error;
+	}
+}
+
+if (!/^[0-9a-f]{8}-[0-9a-f]{4}-4[0-9a-f]{3}-[89ab][0-9a-f]{3}-[0-9a-f]{12}$/.test(crypto.randomUUID())) {
+	throw new Error("crypto.randomUUID did not return a version 4 UUID");
+}
+
+const hex = buffer => Array.from(new Uint8Array(buffer), byte => byte.toString(16).padStart(2, "0")).join("");
+const encoder = new TextEncoder();
+
+const digest = await crypto.subtle.digest("SHA-256", encoder.encode("abc"));
+if (hex(digest) !== "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad") {
+	throw new Error("SHA-256 digest is incorrect");
+}
+
+const hmac = await crypto.subtle.generateKey({ name: "HMAC", hash: "SHA-256" }, true, ["sign", "verify"]);
+const signature = await crypto.subtle.sign("HMAC", hmac, encoder.encode("message"));
+if (!await crypto.subtle.verify("HMAC", hmac, signature, encoder.encode("message"))) {
+	throw new Error("HMAC signature was not verified");
+}
+
+const aes = await crypto.subtle.generateKey({ name: "AES-GCM", length: 256 }, true, ["encrypt", "decrypt"]);
+const iv = crypto.getRandomValues(new Uint8Array(12));
+const ciphertext = await crypto.subtle.encrypt({ name: "AES-GCM", iv }, aes, encoder.encode("plaintext"));
+const plaintext = await crypto.subtle.decrypt({ name: "AES-GCM", iv }, aes, ciphertext);
+if (new TextDecoder().decode(plaintext) !== "plaintext") {
+	throw new Error("AES-GCM did not round-trip");
+}
+
+const exported = await crypto.subtle.exportKey("jwk", aes);
+if (exported.kty !== "oct" || exported.alg !== "A256GCM") {
+	throw new Error("AES key was not exported as a JSON Web Key");
+}
+
+const ecdsa = await crypto.subtle.generateKey({ name: "ECDSA", namedCurve: "P-256" }, false, ["sign", "verify"]);
+const ecdsaSignature = await crypto.subtle.sign({ name: "ECDSA", hash: "SHA-256" }, ecdsa.privateKey, encoder.encode("message"));
+if (!await crypto.subtle.verify({ name: "ECDSA", hash: "SHA-256" }, ecdsa.publicKey, ecdsaSignature, encoder.encode("message"))) {
+	throw new Error("ECDSA signature was not verified");
+}
+
+const password = await crypto.subtle.importKey("raw", encoder.encode("password"), "PBKDF2", false, ["deriveBits"]);
+const derived = await crypto.subtle.deriveBits({ name: "PBKDF2", salt: encoder.encode("salt"), iterations: 1, hash: "SHA-1" }, password, 160);
+if (hex(derived) !== "0c60c80f961f0e71f3a9b524af6012062fe037a6") {
+	throw new Error("PBKDF2 derived bits are incorrect");
+}
@@ -0,0 +1,88 @@
+// Each section waits for the callback which runs last, so that the assertions do not depend on how long timers take to expire.
+function check(actual, expected, message) {
+	if (actual.join(", ") !== expected.join(", ")) {
+		throw new Error(`${message}: ${actual.join(", ")}`);
+	}
+}
+
+// Immediates run in the order they were queued in, and those queued by immediates run in the next turn.
+const order = [];
+await new Promise(resolve => {
+	setImmediate(() => {
+		order.push("immediate 1");
+		setImmediate(() => {
+			order.push("nested immediate");
+			resolve();
+		});
+		queueMicrotask(() => order.push("immediate 1 microtask"));
+	});
+	clearImmediate(setImmediate(() => order.push("cleared immediate")));
+	setImmediate(value => order.push(`immediate ${value}`), 2);
+
+	queueMicrotask(() => order.push("microtask"));
+	order.push("script");
+});
+check(
+	order,
+	["script", "microtask", "immediate 1", "immediate 1 microtask", "immediate 2", "nested immediate"],
+	"Event loop ran immediates in the wrong order"
+);
+
+// Timers with the same delay run in the order they were queued in, and microtasks are drained after each timer.
+const timers = [];
+await new Promise(resolve => {
+	setTimeout(() => {
+		timers.push("timeout 1");
+		Promise.resolve().then(() => timers.push("timeout 1 microtask"));
+	}, 1);
+	setTimeout(() => {
+		timers.push("timeout 2");
+		resolve();
+	}, 1);
+});
+check(timers, ["timeout 1", "timeout 1 microtask", "timeout 2"], "Event loop ran timers in the wrong order");
+
+// The immediate phase follows the timer phase, so immediates queued by a timer run before timers queued by it.
+const phases = [];
+await new Promise(resolve => {
+	setTimeout(() => {
+		setTimeout(() => {
+			phases.push("timeout");
+			resolve();
+		}, 0);
+		setImmediate(() => phases.push("immediate"));
+	}, 0);
+});
+check(phases, ["immediate", "timeout"], "Event loop ran phases in the wrong order");
+
+// Microtasks queued by microtasks run in the same checkpoint, before the next macrotask.
+const MICROTASK_DEPTH = 100_000;
+let depth = 0;
+let ranBeforeTimeout = false;
+function nest() {
+	if (++depth < MICROTASK_DEPTH) {
+		queueMicrotask(nest);
+	}
+}
+const timeout = new Promise(resolve => {
+	setTimeout(() => {
+		ranBeforeTimeout = depth === MICROTASK_DEPTH;
+		resolve();
+	}, 0);
+});
+queueMicrotask(nest);
+await timeout;
+if (!ranBeforeTimeout) {
+	throw new Error(`Nested microtasks did not run to completion before the next timer: ${depth}`);
+}
+
+// Promises settled by the event loop are followed by a checkpoint, before any other promise is settled.
+const settled = [];
+const callbacks = [1, 2].map(i =>
+	new Promise(resolve => setTimeout(resolve, 1)).then(() => {
+		settled.push(`timer ${i}`);
+		queueMicrotask(() => settled.push(`microtask ${i}`));
+	})
+);
+await Promise.all(callbacks);
+check(settled, ["timer 1", "microtask 1", "timer 2", "microtask 2"], "Microtasks did not run after each callback");
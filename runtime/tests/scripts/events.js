@@ -0,0 +1,81 @@
+const target = new EventTarget();
+const calls = [];
+
+function listener(event) {
+	calls.push(["listener", this === target, event.currentTarget === target, event.eventPhase]);
+}
+
+target.addEventListener("ping", listener);
+target.addEventListener("ping", listener);
+target.addEventListener("ping", { handleEvent: event => calls.push(["object", event.type]) });
+target.addEventListener("ping", () => calls.push(["once"]), { once: true });
+
+if (!target.dispatchEvent(new Event("ping"))) {
+	throw new Error("Event without listeners calling preventDefault was cancelled");
+}
+target.dispatchEvent(new Event("ping"));
+
+const expected = JSON.stringify([
+	["listener", true, true, Event.AT_TARGET],
+	["object", "ping"],
+	["once"],
+	["listener", true, true, Event.AT_TARGET],
+	["object", "ping"],
+]);
+if (JSON.stringify(calls) !== expected) {
+	throw new Error(`Listeners were not called in order: ${JSON.stringify(calls)}`);
+}
+
+target.removeEventListener("ping", listener);
+calls.length = 0;
+target.dispatchEvent(new Event("ping"));
+if (calls.length !== 1) {
+	throw new Error("Listener was not removed");
+}
+
+const stopped = new EventTarget();
+let reached = false;
+stopped.addEventListener("stop", event => event.stopImmediatePropagation());
+stopped.addEventListener("stop", () => reached = true);
+stopped.dispatchEvent(new Event("stop"));
+if (reached) {
+	throw new Error("stopImmediatePropagation did not stop later listeners");
+}
+
+const cancellable = new EventTarget();
+cancellable.addEventListener("cancel", event => event.preventDefault(), { passive: true });
+if (!cancellable.dispatchEvent(new Event("cancel", { cancelable: true }))) {
+	throw new Error("preventDefault was not ignored in a passive listener");
+}
+cancellable.addEventListener("cancel", event => event.preventDefault());
+const event = new Event("cancel", { cancelable: true });
+if (cancellable.dispatchEvent(event) || !event.defaultPrevented) {
+	throw new Error("Event was not cancelled");
+}
+if (event.isTrusted || event.target !== cancellable || event.currentTarget !== null) {
+	throw new Error("Event has incorrect state after dispatch");
+}
+
+const custom = new CustomEvent("custom", { detail: { value: 1 } });
+if (!(custom instanceof Event) || custom.detail.value !== 1 || custom.type !== "custom") {
+	throw new Error("CustomEvent does not inherit from Event");
+}
+let detail = null;
+target.addEventListener("custom", event => detail = event.detail);
+target.dispatchEvent(custom);
+if (detail?.value !== 1) {
+	throw new Error("CustomEvent was not dispatched");
+}
+
+const worker = new Worker("./tests/scripts/worker/echo.js");
+if (!(worker instanceof EventTarget)) {
+	throw new Error("Worker does not inherit from EventTarget");
+}
+const reply = await new Promise(resolve => {
+	worker.addEventListener("message", event => resolve(event.data), { once: true });
+	worker.postMessage({ map: new Map([["count", 0]]), buffer: new ArrayBuffer(1) });
+});
+worker.terminate();
+if (reply.map.get("count") !== 1) {
+	throw new Error("Message was not dispatched to event listeners");
+}
@@ -0,0 +1,3 @@
+globalThis.evaluations = (globalThis.evaluations ?? 0) + 1;
+
+export const base = "base";
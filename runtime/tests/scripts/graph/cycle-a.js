@@ -0,0 +1,11 @@
+import {b, getA} from "./cycle-b.js";
+
+export const a = "a";
+
+export function getB() {
+	return b;
+}
+
+if (getA() !== "a") {
+	throw new Error("Cyclic import did not resolve to the same module");
+}
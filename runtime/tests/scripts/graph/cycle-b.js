@@ -0,0 +1,11 @@
+import {a, getB} from "./cycle-a.js";
+
+export const b = "b";
+
+export function getA() {
+	return a;
+}
+
+export function getBFromA() {
+	return getB();
+}
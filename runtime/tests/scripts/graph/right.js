@@ -0,0 +1,3 @@
+import {base} from "../graph/base.js";
+
+export const right = `${base}-right`;
@@ -0,0 +1,17 @@
+globalThis.beforeUnloads = 0;
+addEventListener("beforeunload", () => {
+	beforeUnloads++;
+	// Queuing work from a listener keeps the event loop alive.
+	if (beforeUnloads === 1) {
+		setTimeout(() => {}, 1);
+	}
+});
+
+globalThis.unloaded = false;
+addEventListener("unload", event => {
+	if (event.type !== "unload" || !event.isTrusted) {
+		throw new Error("unload event is incorrect");
+	}
+	// Microtasks queued from a listener run before the runtime shuts down.
+	queueMicrotask(() => (unloaded = true));
+});
@@ -0,0 +1,6 @@
+import { greeting } from "./typed.ts";
+import config from "./data.json" assert { type: "json" };
+import "https://example.invalid/remote.js";
+import "./missing.js";
+
+console.log(greeting, config.name);
@@ -0,0 +1,4 @@
+import { greeting } from "./typed.ts";
+
+export const name = "spiderfire";
+export const echo = () => greeting;
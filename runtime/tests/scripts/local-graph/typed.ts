@@ -0,0 +1,3 @@
+import { name } from "./nested.js";
+
+export const greeting: string = `Hello, ${name}`;
@@ -0,0 +1 @@
+export const value = await new Promise(resolve => queueMicrotask(() => resolve(1)));
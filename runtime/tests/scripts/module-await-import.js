@@ -0,0 +1,7 @@
+import {value} from "../scripts/module-await-export.js";
+
+if (value !== 1) {
+	throw new Error("Imported value was not awaited");
+}
+
+await Promise.resolve();
@@ -0,0 +1,14 @@
+import counter, { next, name } from "./commonjs/counter.cjs";
+import legacy, { join } from "legacy";
+
+if (name !== "counter" || next() !== 11 || counter.next() !== 12) {
+	throw new Error("CommonJS exports were not imported");
+}
+if (legacy() !== "legacy" || join("a", "b") !== "a/b") {
+	throw new Error("CommonJS package was not imported");
+}
+
+const reserved = await import("./commonjs/reserved.cjs");
+if (reserved.value !== "value" || reserved.default.class !== "class" || "class" in reserved) {
+	throw new Error("CommonJS exports named by reserved words were not skipped");
+}
@@ -0,0 +1,10 @@
+import counter, { next, label } from "counter";
+import scoped from "@scope/counter";
+
+if (label !== "counter" || next() !== 6 || counter.next() !== 7) {
+	throw new Error("Custom module exports were not imported");
+}
+
+if (scoped.label !== "counter" || scoped.next() !== 6) {
+	throw new Error("Custom modules named by non-identifiers were not imported");
+}
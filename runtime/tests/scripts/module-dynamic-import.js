@@ -0,0 +1,15 @@
+const {value} = await import("./module-await-export.js");
+if (value !== 1) {
+	throw new Error("Dynamically imported module was not evaluated");
+}
+
+const again = await import("./module-await-export.js");
+if (again.value !== value) {
+	throw new Error("Dynamically imported module was evaluated twice");
+}
+
+let rejected = false;
+await import("./missing-module.js").catch(() => rejected = true);
+if (!rejected) {
+	throw new Error("Importing a missing module did not reject");
+}
@@ -0,0 +1,14 @@
+import {left} from "./graph/left.js";
+import {right} from "./graph/right.js";
+import {getBFromA} from "./graph/cycle-b.js";
+import {getB} from "./graph/cycle-a.js";
+
+if (left !== "base-left" || right !== "base-right") {
+	throw new Error("Diamond imports did not resolve");
+}
+if (globalThis.evaluations !== 1) {
+	throw new Error(`Shared dependency was evaluated ${globalThis.evaluations} times`);
+}
+if (getB() !== "b" || getBFromA() !== "b") {
+	throw new Error("Cyclic imports did not resolve");
+}
@@ -0,0 +1,16 @@
+import {base} from "base";
+import {left} from "graph/left.js";
+import {scoped} from "./import-map/scoped/entry.js";
+
+if (base !== "base" || left !== "base-left") {
+	throw new Error("Import map did not remap specifiers");
+}
+if (scoped !== "scoped") {
+	throw new Error("Import map scope was not applied");
+}
+
+let rejected = false;
+await import("blocked").catch(() => rejected = true);
+if (!rejected) {
+	throw new Error("Blocked specifier was importable");
+}
@@ -0,0 +1,22 @@
+import config from "./json/config.json" assert { type: "json" };
+import proto from "./json/proto.json" assert { type: "json" };
+import array from "./json/array.json" assert { type: "json" };
+import * as script from "./json/array.json";
+
+if (config.name !== "spiderfire" || config.features.length !== 2 || !config.nested.enabled) {
+	throw new Error("JSON module was not parsed");
+}
+
+let rejected = false;
+await import("./json/invalid.json", { assert: { type: "json" } }).catch(() => rejected = true);
+if (!rejected) {
+	throw new Error("Importing invalid JSON did not reject");
+}
+
+if (Object.getPrototypeOf(proto) !== Object.prototype || !Object.hasOwn(proto, "__proto__") || proto.polluted !== undefined) {
+	throw new Error("JSON module did not define __proto__ as an own property");
+}
+
+if (!Array.isArray(array) || array.length !== 2 || script.default !== undefined) {
+	throw new Error("Importing a file as JSON and as JavaScript did not create separate modules");
+}
@@ -0,0 +1,23 @@
+import main from "main-only";
+import conditional from "conditional";
+import feature from "conditional/feature";
+import strings from "@scope/pattern/utils/strings";
+
+if (main !== "main" || conditional !== "import" || feature !== "feature" || strings !== "strings") {
+	throw new Error("Bare specifiers were not resolved to packages");
+}
+
+let rejected = false;
+await import("conditional/dist/index.js").catch(() => rejected = true);
+if (!rejected) {
+	throw new Error("Subpaths not in exports were importable");
+}
+
+const escapes = ["@scope/pattern/utils/../../secret", "escaping"];
+for (const specifier of escapes) {
+	let escaped = true;
+	await import(specifier).catch(() => escaped = false);
+	if (escaped) {
+		throw new Error(`Package resolved outside of its root: ${specifier}`);
+	}
+}
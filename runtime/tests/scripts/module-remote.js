@@ -0,0 +1,14 @@
+const origin = "{origin}";
+
+const {value} = await import(`${origin}/redirect/entry.js`);
+if (value !== "redirected") {
+	throw new Error("Imports of a redirected module were not resolved against the URL after redirects");
+}
+
+for (const name of ["local.js", "bare.js"]) {
+	let error = null;
+	await import(`${origin}/${name}`).catch(e => error = e);
+	if (!error || !String(error.message).includes("cannot import local module")) {
+		throw new Error(`Remote module ${name} should not be able to import local modules`);
+	}
+}
@@ -0,0 +1,17 @@
+import {greet} from "./typescript/greet.mts";
+import {View} from "./typescript/view.tsx";
+
+globalThis.React = {
+	createElement(type, props, ...children) {
+		return {type, props, children};
+	},
+};
+
+if (greet({name: "TypeScript"}) !== "Hello, TypeScript") {
+	throw new Error("TypeScript module was not transpiled");
+}
+
+const element = View({title: "JSX"});
+if (element.type !== "section" || element.props.id !== "view" || element.children[0] !== "JSX") {
+	throw new Error("TSX module was not transpiled");
+}
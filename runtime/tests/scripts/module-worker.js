@@ -0,0 +1,18 @@
+const worker = new Worker("./tests/scripts/worker/echo.js");
+
+const buffer = new ArrayBuffer(8);
+new Uint8Array(buffer)[0] = 42;
+
+const reply = await new Promise((resolve, reject) => {
+	worker.onmessage = event => resolve(event.data);
+	worker.onerror = event => reject(new Error(event.message));
+	worker.postMessage({ map: new Map([["count", 1]]), buffer }, [buffer]);
+});
+worker.terminate();
+
+if (buffer.byteLength !== 0) {
+	throw new Error("ArrayBuffer was not transferred");
+}
+if (!(reply.map instanceof Map) || reply.map.get("count") !== 2 || reply.first !== 42) {
+	throw new Error("Worker did not reply with a structured clone");
+}
@@ -0,0 +1,58 @@
+const before = performance.now();
+const after = performance.now();
+if (typeof before !== "number" || after < before) {
+	throw new Error("performance.now is not monotonic");
+}
+if (Math.abs(performance.timeOrigin + before - Date.now()) > 1000) {
+	throw new Error("performance.timeOrigin is not the time the runtime was created");
+}
+
+const observed = new Promise(resolve => {
+	const observer = new PerformanceObserver((list, observer) => {
+		observer.disconnect();
+		resolve(list.getEntries().map(entry => `${entry.entryType}:${entry.name}`));
+	});
+	observer.observe({ entryTypes: ["mark", "measure"] });
+});
+
+const start = performance.mark("start", { detail: { step: 1 } });
+if (!(start instanceof PerformanceMark) || !(start instanceof PerformanceEntry) || start.entryType !== "mark") {
+	throw new Error("performance.mark did not return a PerformanceMark");
+}
+if (start.detail.step !== 1 || start.duration !== 0) {
+	throw new Error("PerformanceMark has an incorrect detail or duration");
+}
+
+performance.mark("end", { startTime: start.startTime + 10 });
+const measure = performance.measure("between", "start", "end");
+if (!(measure instanceof PerformanceMeasure) || measure.startTime !== start.startTime || Math.abs(measure.duration - 10) > 1e-9) {
+	throw new Error("performance.measure did not measure between marks");
+}
+
+const options = performance.measure("options", { start: 5, duration: 20 });
+if (options.startTime !== 5 || options.duration !== 20) {
+	throw new Error("performance.measure did not use its options");
+}
+
+try {
+	performance.measure("missing", "missing");
+	throw new Error("performance.measure did not throw for a missing mark");
+} catch (error) {
+	if (error.name !== "SyntaxError") {
+		throw error;
+	}
+}
+
+if (performance.getEntriesByType("mark").length !== 2 || performance.getEntriesByName("between").length !== 1) {
+	throw new Error("Performance entries were not recorded");
+}
+performance.clearMarks("start");
+if (performance.getEntriesByType("mark").length !== 1) {
+	throw new Error("performance.clearMarks did not remove the mark");
+}
+
+const entries = await observed;
+const expected = ["mark:end", "mark:start", "measure:between", "measure:options"];
+if (JSON.stringify(entries.sort()) !== JSON.stringify(expected)) {
+	throw new Error(`PerformanceObserver did not observe all entries: ${JSON.stringify(entries)}`);
+}
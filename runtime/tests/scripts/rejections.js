@@ -0,0 +1,28 @@
+const unhandled = [];
+const handled = [];
+
+addEventListener("unhandledrejection", event => {
+	if (!(event instanceof PromiseRejectionEvent) || !event.cancelable || !(event.promise instanceof Promise)) {
+		throw new Error("unhandledrejection event is incorrect");
+	}
+	unhandled.push(event.reason);
+	if (event.reason === "cancelled") {
+		event.preventDefault();
+	}
+});
+globalThis.onrejectionhandled = event => handled.push(event.reason);
+
+Promise.reject("cancelled");
+const late = Promise.reject("late");
+Promise.reject("caught").catch(() => {});
+
+await new Promise(resolve => setTimeout(resolve, 5));
+late.catch(() => {});
+await new Promise(resolve => setTimeout(resolve, 5));
+
+if (unhandled.join(", ") !== "cancelled, late") {
+	throw new Error(`unhandledrejection was fired for ${unhandled.join(", ")}`);
+}
+if (handled.join(", ") !== "late") {
+	throw new Error(`rejectionhandled was fired for ${handled.join(", ")}`);
+}
@@ -0,0 +1,35 @@
+import { next } from "./commonjs/counter.cjs";
+
+// Each runtime loads its own instance of a CommonJS module.
+if (next() !== 11) {
+	throw new Error("CommonJS module was shared with another runtime");
+}
+
+const stream = new ReadableStream({
+	start(controller) {
+		controller.enqueue("chunk");
+		controller.close();
+	},
+});
+const { value } = await stream.getReader().read();
+if (value !== "chunk") {
+	throw new Error("Stream did not read its chunk");
+}
+
+// Compression streams are native streams, which are created with the internals of the streams of their own runtime.
+const compression = new CompressionStream("gzip");
+const decompressed = compression.readable.pipeThrough(new DecompressionStream("gzip"));
+const writer = compression.writable.getWriter();
+writer.write(new TextEncoder().encode("body"));
+writer.close();
+
+const decoder = new TextDecoder();
+let text = "";
+for await (const chunk of decompressed) {
+	text += decoder.decode(chunk, { stream: true });
+}
+if (text !== "body") {
+	throw new Error("Native stream did not read its chunks");
+}
+
+await new Promise(resolve => setTimeout(resolve, 1));
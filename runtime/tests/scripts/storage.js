@@ -0,0 +1,62 @@
+function assert(condition, message) {
+	if (!condition) {
+		throw new Error(message);
+	}
+}
+
+function throws(callback, name) {
+	try {
+		callback();
+	} catch (error) {
+		return name === undefined || error.name === name;
+	}
+	return false;
+}
+
+assert(localStorage instanceof Storage && sessionStorage instanceof Storage, "localStorage and sessionStorage should be Storage objects");
+assert(localStorage === localStorage, "localStorage should be the same object each time");
+assert(localStorage !== sessionStorage, "localStorage and sessionStorage should be separate");
+assert(throws(() => new Storage(), "TypeError"), "Storage should not be constructible");
+assert(Object.prototype.toString.call(localStorage) === "[object Storage]", "Storage should have a toStringTag");
+
+for (const storage of [localStorage, sessionStorage]) {
+	storage.clear();
+	assert(storage.length === 0, "clear should remove every item");
+
+	storage.setItem("first", "1");
+	storage.setItem("second", 2);
+	assert(storage.length === 2, "length should be the number of items");
+	assert(storage.getItem("first") === "1", "getItem should return the value of an item");
+	assert(storage.getItem("second") === "2", "Values should be converted to strings");
+	assert(storage.getItem("missing") === null, "getItem should return null for missing items");
+	assert(storage.key(0) === "first" && storage.key(1) === "second", "key should return the keys in the order they were set");
+	assert(storage.key(2) === null, "key should return null when the index is out of range");
+
+	storage.setItem("first", "one");
+	assert(storage.getItem("first") === "one" && storage.key(0) === "first", "Setting an item should keep its position");
+
+	storage.removeItem("first");
+	assert(storage.getItem("first") === null && storage.length === 1, "removeItem should remove an item");
+	storage.removeItem("missing");
+
+	storage.third = 3;
+	assert(storage.getItem("third") === "3", "Setting a property should set an item");
+	assert(storage.third === "3" && "third" in storage, "Items should be readable as properties");
+	assert(storage.missing === undefined && !("missing" in storage), "Missing items should not be properties");
+	assert(Object.keys(storage).join() === "second,third", "Items should be the enumerable own properties");
+	delete storage.third;
+	assert(storage.getItem("third") === null, "Deleting a property should remove the item");
+
+	storage.setItem("getItem", "shadowed");
+	assert(typeof storage.getItem === "function", "Methods should take precedence over items");
+	assert(storage.getItem("getItem") === "shadowed", "Items with the names of methods should still be readable");
+
+	assert(throws(() => storage.setItem("key")), "setItem should require two arguments");
+	assert(throws(() => storage.setItem("large", "x".repeat(5 * 1024 * 1024)), "QuotaExceededError"), "Exceeding the quota should throw");
+	assert(storage.getItem("large") === null, "Items exceeding the quota should not be set");
+
+	storage.clear();
+}
+
+sessionStorage.setItem("session", "value");
+assert(localStorage.getItem("session") === null, "Items in sessionStorage should not be visible in localStorage");
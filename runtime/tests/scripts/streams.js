@@ -0,0 +1,111 @@
+function assert(condition, message) {
+	if (!condition) {
+		throw new Error(message);
+	}
+}
+
+async function collect(stream) {
+	const chunks = [];
+	for await (const chunk of stream) {
+		chunks.push(chunk);
+	}
+	return chunks;
+}
+
+const source = new ReadableStream({
+	start(controller) {
+		controller.enqueue("a");
+		controller.enqueue("b");
+		controller.close();
+	},
+});
+const reader = source.getReader();
+assert(source.locked, "ReadableStream was not locked by its reader");
+const first = await reader.read();
+assert(first.value === "a" && !first.done, "ReadableStream did not read the first chunk");
+assert((await reader.read()).value === "b", "ReadableStream did not read the second chunk");
+assert((await reader.read()).done, "ReadableStream did not close");
+reader.releaseLock();
+assert(!source.locked, "ReadableStream was not unlocked");
+
+let pulls = 0;
+const pulled = new ReadableStream(
+	{
+		pull(controller) {
+			pulls++;
+			controller.enqueue(pulls);
+			if (pulls === 3) {
+				controller.close();
+			}
+		},
+	},
+	new CountQueuingStrategy({ highWaterMark: 1 }),
+);
+const upper = new TransformStream({
+	transform(chunk, controller) {
+		controller.enqueue(chunk * 10);
+	},
+});
+const transformed = await collect(pulled.pipeThrough(upper));
+assert(transformed.join() === "10,20,30", "TransformStream did not transform piped chunks");
+
+const [left, right] = ReadableStream.from(["x", "y"]).tee();
+const [leftChunks, rightChunks] = await Promise.all([collect(left), collect(right)]);
+assert(leftChunks.join() === "x,y" && rightChunks.join() === "x,y", "ReadableStream was not teed");
+
+let offset = 0;
+const bytes = new ReadableStream({
+	type: "bytes",
+	pull(controller) {
+		const request = controller.byobRequest;
+		const view = request.view;
+		for (let i = 0; i < 4; i++) {
+			view[i] = offset + i;
+		}
+		offset += 4;
+		request.respond(4);
+		if (offset === 8) {
+			controller.close();
+		}
+	},
+});
+const byob = bytes.getReader({ mode: "byob" });
+const read = await byob.read(new Uint8Array(8), { min: 8 });
+assert(read.value.length === 8 && read.value[7] === 7, "ReadableStreamBYOBReader did not fill the view");
+assert((await byob.read(new Uint8Array(1))).done, "Byte stream did not close");
+
+const written = [];
+let resolveWrite;
+const sink = new WritableStream(
+	{
+		write(chunk) {
+			written.push(chunk);
+			return new Promise(resolve => (resolveWrite = resolve));
+		},
+	},
+	{ highWaterMark: 1 },
+);
+const writer = sink.getWriter();
+assert(writer.desiredSize === 1, "WritableStream has an incorrect desired size");
+const write = writer.write("first");
+writer.write("second");
+assert(writer.desiredSize === -1, "WritableStream did not apply backpressure");
+await new Promise(resolve => setTimeout(resolve, 0));
+resolveWrite();
+await write;
+await new Promise(resolve => setTimeout(resolve, 0));
+resolveWrite();
+const closed = writer.close();
+await new Promise(resolve => setTimeout(resolve, 0));
+await closed;
+assert(written.join() === "first,second", "WritableStream did not write its chunks in order");
+
+const controller = new AbortController();
+const piped = new ReadableStream({ pull() {} }).pipeTo(new WritableStream(), { signal: controller.signal });
+controller.abort(new Error("Aborted"));
+try {
+	await piped;
+	assert(false, "pipeTo did not reject when aborted");
+} catch (error) {
+	assert(error.message === "Aborted", "pipeTo rejected with the wrong reason");
+}
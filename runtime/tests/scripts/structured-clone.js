@@ -0,0 +1,37 @@
+const date = new Date(0);
+const original = {
+	map: new Map([["key", { nested: true }]]),
+	set: new Set([1, 2, 3]),
+	date,
+	regex: /clone/gi,
+	typed: new Uint16Array([1, 2, 3]),
+	bigint: 1n,
+};
+original.self = original;
+
+const clone = structuredClone(original);
+if (clone === original || clone.self !== clone) {
+	throw new Error("Circular references were not preserved");
+}
+if (!clone.map.get("key").nested || !clone.set.has(3) || clone.date.getTime() !== date.getTime() || clone.date === date) {
+	throw new Error("Map, Set or Date were not cloned");
+}
+if (clone.regex.source !== "clone" || clone.regex.flags !== "gi" || clone.typed[2] !== 3 || clone.bigint !== 1n) {
+	throw new Error("RegExp, typed arrays or BigInts were not cloned");
+}
+
+const buffer = new ArrayBuffer(4);
+const transferred = structuredClone(buffer, { transfer: [buffer] });
+if (buffer.byteLength !== 0 || transferred.byteLength !== 4) {
+	throw new Error("ArrayBuffer was not transferred");
+}
+
+let threw = false;
+try {
+	structuredClone(() => {});
+} catch {
+	threw = true;
+}
+if (!threw) {
+	throw new Error("Functions were cloned");
+}
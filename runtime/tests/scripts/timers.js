@@ -0,0 +1,82 @@
+function delay(duration) {
+	return new Promise(resolve => setTimeout(resolve, duration));
+}
+
+const args = await new Promise(resolve => setTimeout((...args) => resolve(args), 1, "first", 2));
+if (args.length !== 2 || args[0] !== "first" || args[1] !== 2) {
+	throw new Error("setTimeout did not pass its arguments to the callback");
+}
+
+const cleared = setTimeout(() => {
+	throw new Error("Cleared timeout was called");
+}, 5);
+if (typeof cleared !== "number" || cleared <= 0) {
+	throw new Error("Timer IDs are not positive numbers");
+}
+clearTimeout(cleared);
+
+let ticks = 0;
+await new Promise(resolve => {
+	const id = setInterval(() => {
+		ticks++;
+		if (ticks === 3) {
+			clearInterval(id);
+			resolve();
+		}
+	}, 1);
+});
+await delay(10);
+if (ticks !== 3) {
+	throw new Error(`Interval cleared in its callback was called ${ticks} times`);
+}
+
+const interval = setInterval(() => {
+	throw new Error("Interval cleared with clearTimeout was called");
+}, 5);
+clearTimeout(interval);
+clearInterval();
+clearTimeout(undefined);
+
+const order = [];
+await new Promise(resolve => {
+	setTimeout(() => order.push(3), 3);
+	setTimeout(() => order.push(1), 1);
+	setTimeout(() => order.push(2), 1);
+	setTimeout(resolve, 5);
+});
+if (order.join() !== "1,2,3") {
+	throw new Error(`Timers ran in the order ${order.join()}, instead of by their deadlines`);
+}
+
+const many = [];
+for (let i = 0; i < 10_000; i++) {
+	many.push(setTimeout(() => {
+		throw new Error("Cleared timeout of many was called");
+	}, 60_000));
+}
+many.forEach(clearTimeout);
+await delay(1);
+
+let immediates = 0;
+const ranAfter = await new Promise(resolve => {
+	setTimeout(() => resolve(immediates), 1);
+	const start = Date.now();
+	for (let i = 0; i < 5000; i++) {
+		setImmediate(() => {
+			while (i === 0 && Date.now() - start < 5) {}
+			immediates++;
+		});
+	}
+});
+if (ranAfter === 5000) {
+	throw new Error("Expired timer was starved by immediates");
+}
+await new Promise(resolve => setImmediate(resolve));
+if (immediates !== 5000) {
+	throw new Error(`Only ${immediates} of 5000 immediates ran`);
+}
+
+unrefTimer(setInterval(() => {}, 1));
+unrefTimer(setTimeout(() => {
+	throw new Error("Unreferenced timeout kept the event loop alive");
+}, 60_000));
@@ -0,0 +1,7 @@
+type Props = {
+	title: string;
+};
+
+export function View({title}: Props) {
+	return <section id="view">{title}</section>;
+}
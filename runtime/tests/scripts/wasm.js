@@ -0,0 +1,48 @@
+function assert(condition, message) {
+	if (!condition) {
+		throw new Error(message);
+	}
+}
+
+async function rejects(promise, type) {
+	try {
+		await promise;
+	} catch (error) {
+		return error instanceof type;
+	}
+	return false;
+}
+
+// (module
+//   (memory (export "memory") 1)
+//   (func (export "add") (param i32 i32) (result i32) local.get 0 local.get 1 i32.add))
+const bytes = new Uint8Array([
+	0x00, 0x61, 0x73, 0x6D, 0x01, 0x00, 0x00, 0x00, 0x01, 0x07, 0x01, 0x60, 0x02, 0x7F, 0x7F, 0x01, 0x7F, 0x03, 0x02, 0x01, 0x00, 0x05, 0x03, 0x01,
+	0x00, 0x01, 0x07, 0x10, 0x02, 0x03, 0x61, 0x64, 0x64, 0x00, 0x00, 0x06, 0x6D, 0x65, 0x6D, 0x6F, 0x72, 0x79, 0x02, 0x00, 0x0A, 0x09, 0x01, 0x07,
+	0x00, 0x20, 0x00, 0x20, 0x01, 0x6A, 0x0B,
+]);
+
+function response(type = "application/wasm", status = 200) {
+	return new Response(bytes, { status, headers: { "Content-Type": type } });
+}
+
+assert(typeof WebAssembly === "object", "WebAssembly is defined");
+assert(WebAssembly.validate(bytes), "Module is valid");
+
+const { instance } = await WebAssembly.instantiate(bytes);
+assert(instance.exports.add(2, 3) === 5, "Instantiated module can be called");
+
+const streamed = await WebAssembly.instantiateStreaming(response("application/wasm; charset=binary"));
+assert(streamed.module instanceof WebAssembly.Module, "instantiateStreaming resolves with a module");
+assert(streamed.instance.exports.add(40, 2) === 42, "instantiateStreaming resolves with an instance");
+
+const module = await WebAssembly.compileStreaming(Promise.resolve(response()));
+assert(WebAssembly.Module.exports(module).length === 2, "compileStreaming accepts a promise of a response");
+
+const memory = new WebAssembly.Instance(module).exports.memory;
+new Uint8Array(memory.buffer).set([1, 2, 3], 8);
+assert(new Uint8Array(memory.buffer)[9] === 2, "Memory can be shared with typed arrays");
+
+assert(await rejects(WebAssembly.compileStreaming(response("application/octet-stream")), TypeError), "Content-Type must be application/wasm");
+assert(await rejects(WebAssembly.compileStreaming(response("application/wasm", 404)), TypeError), "Status must be ok");
+assert(await rejects(WebAssembly.compileStreaming(bytes), TypeError), "Source must be a response");
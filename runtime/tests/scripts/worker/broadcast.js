@@ -0,0 +1,7 @@
+const channel = new BroadcastChannel("broadcast");
+channel.onmessage = event => {
+	channel.postMessage(event.data * 2);
+	channel.close();
+	close();
+};
+postMessage("ready");
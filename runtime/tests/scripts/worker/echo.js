@@ -0,0 +1,4 @@
+globalThis.onmessage = event => {
+	const { map, buffer } = event.data;
+	postMessage({ map: new Map([["count", map.get("count") + 1]]), first: new Uint8Array(buffer)[0] });
+};
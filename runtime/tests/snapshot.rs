@@ -0,0 +1,45 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use futures::executor::block_on;
+use mozjs::jsapi::PromiseState;
+use mozjs::rust::{JSEngine, Runtime};
+
+use ion::Context;
+use ion::module::Module;
+use runtime::config::{Config, CONFIG, LogLevel};
+use runtime::modules::Loader;
+use runtime::RuntimeBuilder;
+use runtime::snapshot::Snapshot;
+
+const NAME: &str = "snapshot";
+const SOURCE: &str = "export const value = 1 + 1;";
+
+#[test]
+fn snapshot() {
+	CONFIG.set(Config::default().log_level(LogLevel::Debug).code_cache(false)).unwrap();
+
+	let engine = JSEngine::init().unwrap();
+	let rt = Runtime::new(engine.handle());
+
+	let cx = &mut Context::from_runtime(&rt);
+	let rt = RuntimeBuilder::<_, ()>::new().microtask_queue().modules(Loader::default()).build(cx);
+
+	let mut snapshot = Snapshot::default();
+	snapshot.add_module(rt.cx(), NAME, SOURCE).unwrap();
+
+	let snapshot = Snapshot::from_bytes(&snapshot.to_bytes()).unwrap();
+	assert_eq!(1, snapshot.len());
+	assert!(snapshot.stencil(rt.cx(), NAME, "export const value = 3;", true).is_none());
+	assert!(snapshot.stencil(rt.cx(), NAME, SOURCE, false).is_none());
+
+	let stencil = snapshot.stencil(rt.cx(), NAME, SOURCE, true).unwrap();
+	let (_, promise) = Module::from_stencil(rt.cx(), None, &stencil).unwrap();
+	block_on(rt.run_event_loop()).unwrap();
+	assert_eq!(PromiseState::Fulfilled, promise.unwrap().state());
+
+	assert!(Snapshot::from_bytes(&[]).is_none());
+}
@@ -0,0 +1,57 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::{env, process};
+use std::fs::{create_dir_all, read, remove_dir_all, write};
+
+use runtime::standalone::Standalone;
+
+const BINARY: &[u8] = b"\x7fELF not really an executable";
+
+#[test]
+fn standalone() {
+	let directory = env::temp_dir().join(format!("spiderfire-standalone-{}", process::id()));
+	create_dir_all(&directory).unwrap();
+	let binary = directory.join("spiderfire");
+	let output = directory.join("app");
+	write(&binary, BINARY).unwrap();
+
+	assert!(Standalone::from_executable(&binary).unwrap().is_none());
+
+	let app = Standalone {
+		name: String::from("app.js"),
+		source: String::from("console.log(1);"),
+		snapshot: Some(vec![1, 2, 3]),
+		flags: vec![String::from("--allow-net=example.com")],
+	};
+	app.save(&binary, &output).unwrap();
+	assert!(read(&output).unwrap().starts_with(BINARY));
+
+	let embedded = Standalone::from_executable(&output).unwrap().unwrap();
+	assert_eq!(app.name, embedded.name);
+	assert_eq!(app.source, embedded.source);
+	assert_eq!(app.snapshot, embedded.snapshot);
+	assert_eq!(app.flags, embedded.flags);
+
+	// Compiling from a standalone executable replaces the embedded application.
+	let replacement = Standalone {
+		source: String::from("console.log(2);"),
+		snapshot: None,
+		..app
+	};
+	let copy = directory.join("copy");
+	replacement.save(&output, &copy).unwrap();
+	let bytes = read(&copy).unwrap();
+	assert_eq!(
+		BINARY.len() + replacement.to_bytes().len() + 8 + b"SPIDERFIRE-STANDALONE".len(),
+		bytes.len()
+	);
+	let embedded = Standalone::from_executable(&copy).unwrap().unwrap();
+	assert_eq!(replacement.source, embedded.source);
+	assert!(embedded.snapshot.is_none());
+
+	remove_dir_all(&directory).unwrap();
+}
@@ -0,0 +1,70 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::path::Path;
+use std::process;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use futures::executor::block_on;
+use mozjs::jsapi::PromiseState;
+use mozjs::rust::{JSEngine, JSEngineHandle, Runtime};
+
+use ion::Context;
+use ion::module::Module;
+use runtime::config::{Config, CONFIG, LogLevel};
+use runtime::modules::Loader;
+use runtime::RuntimeBuilder;
+
+const FILE_NAME: &str = "storage.js";
+const SCRIPT: &str = include_str!("scripts/storage.js");
+
+const WRITE_SCRIPT: &str = r#"localStorage.setItem("persisted", "value"); sessionStorage.setItem("session", "value");"#;
+const READ_SCRIPT: &str = r#"
+if (localStorage.getItem("persisted") !== "value") {
+	throw new Error("localStorage should persist across runtimes");
+}
+if (sessionStorage.getItem("session") !== null) {
+	throw new Error("sessionStorage should not persist across runtimes");
+}
+localStorage.clear();
+"#;
+
+#[test]
+fn storage() {
+	// Each run uses a separate origin, so that localStorage is not shared with earlier runs of the test.
+	let nanos = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos();
+	let location = format!("http://storage-{}-{}.test/", process::id(), nanos);
+	CONFIG
+		.set(Config::default().log_level(LogLevel::Debug).code_cache(false).location(Some(location)))
+		.unwrap();
+
+	let engine = JSEngine::init().unwrap();
+
+	run(engine.handle(), FILE_NAME, SCRIPT);
+
+	// Items of localStorage are written to the database of the origin, and are read by later runtimes.
+	run(engine.handle(), "write.js", WRITE_SCRIPT);
+	run(engine.handle(), "read.js", READ_SCRIPT);
+}
+
+fn run(engine: JSEngineHandle, file_name: &str, script: &str) {
+	let rt = Runtime::new(engine);
+
+	let cx = &mut Context::from_runtime(&rt);
+	let rt = RuntimeBuilder::<_, ()>::new()
+		.microtask_queue()
+		.macrotask_queue()
+		.modules(Loader::default())
+		.build(cx);
+
+	let path = format!("./tests/scripts/{}", file_name);
+	let (_, promise) = Module::compile(rt.cx(), file_name, Some(Path::new(&path)), script).unwrap();
+	let promise = promise.unwrap();
+
+	block_on(rt.run_event_loop()).unwrap();
+	assert_eq!(PromiseState::Fulfilled, promise.state());
+	rt.shutdown();
+}
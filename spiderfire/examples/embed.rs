@@ -0,0 +1,18 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use spiderfire::{JSEngine, Runtime};
+use spiderfire::mozjs::conversions::ConversionBehavior;
+
+fn main() {
+	let engine = JSEngine::init().unwrap();
+	let rt = Runtime::builder(engine.handle()).build().unwrap();
+
+	let value = rt.eval_script("1 + 2", "sum.js").unwrap();
+	let sum: i32 = rt.from_value(&value, ConversionBehavior::Default).unwrap();
+	println!("1 + 2 = {}", sum);
+	rt.run_event_loop_until_idle().unwrap();
+}
@@ -0,0 +1,88 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::{error, io};
+use std::fmt;
+use std::fmt::{Display, Formatter};
+
+use ion::{Context, ErrorReport, Exception, Value};
+use ion::module::ModuleError;
+use runtime::cache::map::transform_error_report_with_sourcemaps;
+use runtime::watchdog::take_terminated;
+
+/// Represents an error which occurred while running JavaScript in a [Runtime](crate::Runtime).
+#[derive(Debug)]
+pub enum Error {
+	/// Exception thrown by JavaScript, formatted with its stack.
+	Exception(String),
+	/// Error which cannot be caught by JavaScript, such as running out of memory.
+	Uncatchable,
+	/// Script which was terminated by an [InterruptHandle](runtime::watchdog::InterruptHandle), or for exceeding its time limit.
+	Terminated,
+	/// Value which could not be converted to the requested type.
+	Conversion(String),
+	/// Runtime which could not be built, such as when its thread already has a runtime.
+	Build(String),
+	Io(io::Error),
+}
+
+impl Error {
+	pub(crate) fn from_report(cx: &Context, report: Option<ErrorReport>) -> Error {
+		if take_terminated(cx) {
+			return Error::Terminated;
+		}
+		match report {
+			Some(mut report) => {
+				transform_error_report_with_sourcemaps(&mut report);
+				Error::Exception(report.format(cx))
+			}
+			None => Error::Uncatchable,
+		}
+	}
+
+	pub(crate) fn from_module_error(cx: &Context, mut error: ModuleError) -> Error {
+		if take_terminated(cx) {
+			return Error::Terminated;
+		}
+		if let Some(report) = &mut error.report {
+			transform_error_report_with_sourcemaps(report);
+		}
+		Error::Exception(error.format(cx))
+	}
+
+	/// Creates an error from the reason a promise was rejected with.
+	pub(crate) fn from_rejection(cx: &Context, reason: &Value) -> Error {
+		let exception = Exception::from_value(cx, reason);
+		Error::from_report(cx, Some(ErrorReport::from_exception_with_error_stack(cx, exception)))
+	}
+}
+
+impl Display for Error {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		match self {
+			Error::Exception(exception) => f.write_str(exception),
+			Error::Uncatchable => f.write_str("Uncatchable Error"),
+			Error::Terminated => f.write_str("Script Terminated"),
+			Error::Conversion(message) => write!(f, "Conversion Failed: {}", message),
+			Error::Build(message) => write!(f, "Runtime Build Failed: {}", message),
+			Error::Io(error) => write!(f, "{}", error),
+		}
+	}
+}
+
+impl error::Error for Error {}
+
+impl From<io::Error> for Error {
+	fn from(error: io::Error) -> Error {
+		Error::Io(error)
+	}
+}
+
+impl From<ion::Error> for Error {
+	fn from(error: ion::Error) -> Error {
+		Error::Conversion(error.format())
+	}
+}
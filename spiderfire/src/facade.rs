@@ -0,0 +1,380 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::cell::Cell;
+use std::fs::read_to_string;
+use std::path::Path;
+use std::pin::pin;
+use std::time::Duration;
+
+use futures::future::{Either, select};
+use mozjs::rust::{JSEngineHandle, Runtime as RustRuntime};
+use tokio::runtime::{EnterGuard, Runtime as TokioRuntime};
+use tokio::task::{LocalEnterGuard, LocalSet};
+
+use ion::{Arguments, Context, ErrorReport, Function, Object, Promise, PromiseFuture, Value};
+use ion::conversions::{FromValue, ToValue};
+use ion::flags::PropertyFlags;
+use ion::module::Module;
+use ion::script::{CompileOptions, Script};
+use modules::Modules;
+use runtime::{Runtime as InnerRuntime, RuntimeBuilder as InnerBuilder};
+use runtime::config::{Config, CONFIG};
+use runtime::globals::console::ConsoleBackend;
+use runtime::modules::{CustomModule, Loader};
+use runtime::options::ContextOptions;
+use runtime::permissions::Permissions;
+use runtime::watchdog;
+use runtime::watchdog::InterruptHandle;
+
+use crate::Error;
+
+thread_local! {
+	static HAS_RUNTIME: Cell<bool> = Cell::new(false);
+}
+
+/// JavaScript runtime for embedding, which owns its engine runtime, its context and the executor of its event loop.
+///
+/// Runtimes are created with a [RuntimeBuilder], and are shut down when dropped.
+/// The engine only supports one context per thread, so each thread can only have one runtime at a time.
+/// A runtime cannot be moved between threads.
+pub struct Runtime {
+	// Fields are dropped in order, so the runtime is dropped before its pending tasks, its context and the engine runtime.
+	runtime: InnerRuntime<'static>,
+	local: LocalSet,
+	cx: Box<Context>,
+	time_limit: Cell<Option<Duration>>,
+	_rt: RustRuntime,
+	tokio: TokioRuntime,
+}
+
+impl Runtime {
+	/// Creates a builder of a runtime, which runs on the given engine.
+	pub fn builder(engine: JSEngineHandle) -> RuntimeBuilder {
+		RuntimeBuilder::new(engine)
+	}
+
+	pub fn cx(&self) -> &Context {
+		&self.cx
+	}
+
+	pub fn global(&self) -> &Object {
+		self.runtime.global()
+	}
+
+	/// Returns the underlying runtime, for APIs which are not exposed by this facade.
+	pub fn inner(&self) -> &InnerRuntime<'static> {
+		&self.runtime
+	}
+
+	/// Sets the time limit of each script evaluation, function call and run of the event loop, or removes it with [None].
+	///
+	/// Scripts which exceed the limit, such as those stuck in an infinite loop, are terminated with [Error::Terminated].
+	/// Time spent idle in the event loop counts towards the limit, but only a running script can be terminated.
+	pub fn set_time_limit(&self, limit: Option<Duration>) {
+		self.time_limit.set(limit);
+	}
+
+	/// Returns a handle which terminates the running script from another thread, with [Error::Terminated].
+	pub fn interrupt_handle(&self) -> InterruptHandle {
+		watchdog::interrupt_handle(self.cx())
+	}
+
+	/// Defines a global function, which calls `closure` with its arguments.
+	///
+	/// The closure can capture state from the host application. Errors returned by it are thrown as exceptions.
+	/// The global can be redefined, such as by calling this again with the same name.
+	pub fn set_global_fn<F>(&self, name: &str, closure: F) -> Result<(), Error>
+	where
+		F: for<'cx> FnMut(&'cx Context, &Arguments<'cx>) -> ion::Result<Value<'cx>> + 'static,
+	{
+		let _guards = self.enter();
+		let function = Function::new_closure(self.cx(), name, closure);
+		self.set_global_value(name, &function)
+	}
+
+	/// Defines a global property with the given value, which is converted to a JavaScript value.
+	pub fn set_global_value<'cx, T: ToValue<'cx> + ?Sized>(&'cx self, name: &str, value: &T) -> Result<(), Error> {
+		let cx = self.cx();
+		let mut global = Object::global(cx);
+		if global.define_as(cx, name, value, PropertyFlags::ENUMERATE) {
+			Ok(())
+		} else {
+			Err(Error::from_report(cx, ErrorReport::new_with_exception_stack(cx)))
+		}
+	}
+
+	/// Evaluates a classic script, returning its completion value.
+	/// Promises created by the script are only settled once the event loop is run.
+	pub fn eval_script(&self, source: &str, filename: &str) -> Result<Value, Error> {
+		let _guards = self.enter();
+		Script::compile_and_evaluate(self.cx(), Path::new(filename), source).map_err(|report| Error::from_report(self.cx(), report))
+	}
+
+	/// Evaluates a classic script, converting its completion value to `T`.
+	///
+	/// This is convenient for reading the result of a configuration script.
+	/// Types whose conversion takes a configuration without a default, such as integers, are converted with [Runtime::eval_with_options].
+	pub fn eval<'cx, T: FromValue<'cx>>(&'cx self, source: &str, filename: &str) -> Result<T, Error>
+	where
+		T::Config: Default,
+	{
+		self.eval_with_options(source, &CompileOptions::new(filename), T::Config::default())
+	}
+
+	/// Evaluates a classic script compiled with `options`, converting its completion value to `T` with `config`.
+	pub fn eval_with_options<'cx, T: FromValue<'cx>>(&'cx self, source: &str, options: &CompileOptions, config: T::Config) -> Result<T, Error> {
+		let _guards = self.enter();
+		let cx = self.cx();
+		let value = Script::compile_with_options(cx, options, source)
+			.map_err(Some)
+			.and_then(|script| script.evaluate(cx))
+			.map_err(|report| Error::from_report(cx, report))?;
+		self.from_value(&value, config)
+	}
+
+	/// Evaluates a module compiled with `options`, and returns its namespace object.
+	/// Its imports are resolved relative to the filename of `options`.
+	/// If the module uses top-level await, the event loop is run until its evaluation has finished.
+	pub fn eval_module(&self, source: &str, options: &CompileOptions) -> Result<Object, Error> {
+		let _guards = self.enter();
+		let cx = self.cx();
+		let path = Path::new(&options.filename);
+		let (module, promise) = Module::compile_with_options(cx, options, Some(path), source).map_err(|error| Error::from_module_error(cx, error))?;
+		if let Some(promise) = promise {
+			self.run_until_settled(&promise)?;
+		}
+		Ok(module.namespace(cx))
+	}
+
+	/// Loads the module at `path`, with its imports, and returns its namespace object.
+	/// If the module uses top-level await, the event loop is run until its evaluation has finished.
+	pub fn load_module(&self, path: &Path) -> Result<Object, Error> {
+		let source = read_to_string(path)?;
+		let filename = path
+			.to_str()
+			.ok_or_else(|| Error::Conversion(format!("Path {} is not valid UTF-8", path.display())))?;
+		self.eval_module(&source, &CompileOptions::new(filename))
+	}
+
+	/// Calls the global function `name` with the global object as `this`, returning its result.
+	pub fn call_function(&self, name: &str, args: &[Value]) -> Result<Value, Error> {
+		let _guards = self.enter();
+		let cx = self.cx();
+		let function = self
+			.global()
+			.get(cx, name)
+			.filter(|function| function.handle().is_object())
+			.and_then(|function| Function::from_object(cx, &function.to_object(cx).into_local()))
+			.ok_or_else(|| Error::Conversion(format!("Global {} is not a Function", name)))?;
+		function.call(cx, self.global(), args).map_err(|report| Error::from_report(cx, report))
+	}
+
+	/// Runs the event loop until there is no more pending work, such as timers, promises and I/O.
+	pub fn run_event_loop_until_idle(&self) -> Result<(), Error> {
+		let _limit = self.limit();
+		self.local
+			.block_on(&self.tokio, self.runtime.run_event_loop())
+			.map_err(|report| Error::from_report(self.cx(), report))
+	}
+
+	/// Runs the event loop until `promise` has settled, returning the value it was fulfilled with.
+	/// Returns [Err] if it was rejected, or if the event loop finished before it settled.
+	pub fn run_until_settled(&self, promise: &Promise) -> Result<Value, Error> {
+		let _limit = self.limit();
+		let cx = self.cx();
+		let settled = PromiseFuture::new(cx, promise);
+		let result = self.local.block_on(&self.tokio, async {
+			match select(pin!(self.runtime.run_event_loop()), settled).await {
+				Either::Left((result, _)) => Err(result),
+				Either::Right((settled, _)) => Ok(settled),
+			}
+		});
+		match result {
+			Ok(Ok(value)) => Ok(value),
+			Ok(Err(reason)) => Err(Error::from_rejection(cx, &reason)),
+			Err(Err(report)) => Err(Error::from_report(cx, report)),
+			Err(Ok(())) => match promise.settled_result(cx) {
+				Some(Ok(value)) => Ok(value),
+				Some(Err(reason)) => Err(Error::from_rejection(cx, &reason)),
+				None => Err(Error::Exception(String::from("Promise did not settle before the event loop finished"))),
+			},
+		}
+	}
+
+	/// Converts a Rust value to a JavaScript value.
+	pub fn to_value<'cx, T: ToValue<'cx> + ?Sized>(&'cx self, value: &T) -> Value<'cx> {
+		value.as_value(self.cx())
+	}
+
+	/// Converts a JavaScript value to a Rust value, without coercing it to a different type.
+	pub fn from_value<'cx, T: FromValue<'cx>>(&'cx self, value: &Value, config: T::Config) -> Result<T, Error> {
+		Ok(T::from_value(self.cx(), value, true, config)?)
+	}
+
+	/// Returns the exit code set by scripts, such as with `process.exit()`.
+	pub fn exit_code(&self) -> i32 {
+		self.runtime.exit_code()
+	}
+
+	/// Enters the executor of the event loop, so that native functions called from JavaScript can spawn tasks.
+	/// The time limit is also started, if it is set.
+	fn enter(&self) -> (EnterGuard, LocalEnterGuard, Option<TimeLimit>) {
+		(self.tokio.enter(), self.local.enter(), self.limit())
+	}
+
+	/// Starts the time limit, unless it is not set or has already been started by an outer call.
+	fn limit(&self) -> Option<TimeLimit> {
+		let limit = self.time_limit.get()?;
+		let cx = self.cx();
+		(watchdog::deadline(cx).is_none() && watchdog::set_time_limit(cx, limit)).then_some(TimeLimit { cx })
+	}
+}
+
+/// Clears the deadline of the runtime when the call which started it returns.
+struct TimeLimit<'cx> {
+	cx: &'cx Context,
+}
+
+impl Drop for TimeLimit<'_> {
+	fn drop(&mut self) {
+		watchdog::set_deadline(self.cx, None);
+	}
+}
+
+impl Drop for Runtime {
+	fn drop(&mut self) {
+		{
+			let _guards = self.enter();
+			self.runtime.shutdown();
+		}
+		HAS_RUNTIME.set(false);
+	}
+}
+
+/// Builder of a [Runtime], which configures its globals, modules and permissions.
+///
+/// By default, the runtime can import modules, including the standard modules, and create workers.
+pub struct RuntimeBuilder {
+	engine: JSEngineHandle,
+	builder: InnerBuilder<Loader, Modules>,
+	config: Config,
+	options: ContextOptions,
+	modules: bool,
+	standard_modules: bool,
+	workers: bool,
+}
+
+impl RuntimeBuilder {
+	pub fn new(engine: JSEngineHandle) -> RuntimeBuilder {
+		RuntimeBuilder {
+			engine,
+			builder: InnerBuilder::new().microtask_queue().macrotask_queue(),
+			config: Config::default(),
+			options: ContextOptions::default(),
+			modules: true,
+			standard_modules: true,
+			workers: true,
+		}
+	}
+
+	/// Sets the configuration of the runtime.
+	///
+	/// The configuration is shared by every runtime in the process, so only the configuration of the first runtime is used.
+	pub fn config(mut self, config: Config) -> RuntimeBuilder {
+		self.config = config;
+		self
+	}
+
+	/// Sets the permissions granted to scripts, which are part of the [configuration](RuntimeBuilder::config).
+	pub fn permissions(mut self, permissions: Permissions) -> RuntimeBuilder {
+		self.config = self.config.permissions(permissions);
+		self
+	}
+
+	pub fn options(mut self, options: ContextOptions) -> RuntimeBuilder {
+		self.options = options;
+		self
+	}
+
+	/// Sets whether modules can be imported. Without modules, the standard modules are defined as globals instead.
+	pub fn modules(mut self, modules: bool) -> RuntimeBuilder {
+		self.modules = modules;
+		self
+	}
+
+	/// Sets whether the standard modules, such as `fs` and `path`, are available.
+	pub fn standard_modules(mut self, standard_modules: bool) -> RuntimeBuilder {
+		self.standard_modules = standard_modules;
+		self
+	}
+
+	pub fn workers(mut self, workers: bool) -> RuntimeBuilder {
+		self.workers = workers;
+		self
+	}
+
+	/// Registers a module implemented in Rust, which can be imported as `name`.
+	pub fn register_module<M: CustomModule + 'static>(mut self, name: &str, module: M) -> RuntimeBuilder {
+		self.builder = self.builder.register_module(name, module);
+		self
+	}
+
+	/// Sets the backend which receives the output of the `console` global.
+	pub fn console<B: ConsoleBackend + 'static>(mut self, backend: B) -> RuntimeBuilder {
+		self.builder = self.builder.console(backend);
+		self
+	}
+
+	/// Sets a callback which is called for unhandled promise rejections, instead of printing them.
+	pub fn on_unhandled_rejection<F: Fn(&Context, &Promise, &Value) + 'static>(mut self, callback: F) -> RuntimeBuilder {
+		self.builder = self.builder.on_unhandled_rejection(callback);
+		self
+	}
+
+	/// Builds the runtime on the current thread.
+	/// Returns [Err] if the thread already has a runtime, or if the executor of its event loop could not be created.
+	pub fn build(self) -> Result<Runtime, Error> {
+		if HAS_RUNTIME.get() {
+			return Err(Error::Build(String::from("Thread already has a runtime")));
+		}
+		let _ = CONFIG.set(self.config);
+
+		let tokio = tokio::runtime::Builder::new_current_thread().enable_all().build()?;
+		let local = LocalSet::new();
+		let rt = RustRuntime::new(self.engine.clone());
+		let mut cx = Box::new(Context::from_runtime(&rt));
+
+		let mut builder = self.builder.options(self.options);
+		if self.modules {
+			builder = builder.modules(Loader::default());
+		}
+		if self.standard_modules {
+			builder = builder.standard_modules(Modules);
+		}
+		if self.workers {
+			builder = builder.workers(self.engine);
+		}
+
+		// The context is boxed, so that it is not moved while the runtime refers to it, and is dropped after the runtime.
+		let runtime = {
+			let _tokio = tokio.enter();
+			let _local = local.enter();
+			let cx: *mut Context = &mut *cx;
+			builder.try_build(unsafe { &mut *cx }).map_err(|error| Error::Build(error.format()))?
+		};
+
+		HAS_RUNTIME.set(true);
+		Ok(Runtime {
+			runtime,
+			local,
+			cx,
+			time_limit: Cell::new(None),
+			_rt: rt,
+			tokio,
+		})
+	}
+}
@@ -0,0 +1,32 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+//! High-level API for embedding Spiderfire in Rust applications.
+//!
+//! The engine is initialised once per process, and each [Runtime] is built on a handle to it.
+//! The engine must outlive every runtime built on it.
+//!
+//! The `embed` example evaluates a script, converts its result and runs the event loop.
+//!
+//! The lower-level [ion] and [runtime] crates are re-exported for APIs which are not covered by this crate.
+
+pub use ion;
+pub use ion::{Arguments, Context, Function, Object, Promise, Value};
+pub use ion::conversions::{FromValue, ToValue};
+pub use ion::script::CompileOptions;
+pub use mozjs;
+pub use mozjs::rust::{JSEngine, JSEngineHandle};
+pub use runtime;
+pub use runtime::config::Config;
+pub use runtime::options::ContextOptions;
+pub use runtime::permissions::{PermissionName, Permissions};
+pub use runtime::watchdog::InterruptHandle;
+
+pub use crate::error::Error;
+pub use crate::facade::{Runtime, RuntimeBuilder};
+
+mod error;
+mod facade;
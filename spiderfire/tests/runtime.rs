@@ -0,0 +1,108 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::cell::Cell;
+use std::path::Path;
+use std::rc::Rc;
+use std::thread;
+use std::time::Duration;
+
+use mozjs::conversions::ConversionBehavior;
+
+use runtime::config::LogLevel;
+use spiderfire::{CompileOptions, Config, Error, FromValue, JSEngine, Runtime, ToValue};
+
+#[test]
+fn runtime() {
+	let engine = JSEngine::init().unwrap();
+	let rt = Runtime::builder(engine.handle())
+		.config(Config::default().log_level(LogLevel::Debug).code_cache(false))
+		.build()
+		.unwrap();
+	assert!(matches!(Runtime::builder(engine.handle()).build(), Err(Error::Build(_))));
+
+	let value = rt.eval_script("function add(a, b) { return a + b; } 40 + 2;", "script.js").unwrap();
+	assert_eq!(rt.from_value::<i32>(&value, ConversionBehavior::Default).unwrap(), 42);
+
+	let args = [rt.to_value(&1), rt.to_value(&2)];
+	let sum = rt.call_function("add", &args).unwrap();
+	assert_eq!(rt.from_value::<i32>(&sum, ConversionBehavior::Default).unwrap(), 3);
+	assert!(matches!(rt.call_function("missing", &[]), Err(Error::Conversion(_))));
+
+	let error = rt.eval_script("throw new Error('Expected Failure');", "throw.js").unwrap_err();
+	assert!(matches!(error, Error::Exception(message) if message.contains("Expected Failure")));
+
+	assert_eq!(rt.eval::<String>("['a', 'b'].join('')", "string.js").unwrap(), "ab");
+	assert_eq!(rt.eval::<f64>("Math.PI / 2", "number.js").unwrap(), std::f64::consts::FRAC_PI_2);
+	assert!(rt.eval::<bool>("typeof add === 'function'", "boolean.js").unwrap());
+	assert!(matches!(rt.eval::<bool>("({})", "object.js"), Err(Error::Conversion(_))));
+
+	let sloppy = CompileOptions::new("sloppy.js");
+	rt.eval_with_options::<f64>("undeclared = 1;", &sloppy, ()).unwrap();
+	let strict = CompileOptions::new("strict.js").strict(true);
+	let error = rt.eval_with_options::<f64>("alsoUndeclared = 1;", &strict, ()).unwrap_err();
+	assert!(matches!(error, Error::Exception(message) if message.contains("alsoUndeclared")));
+
+	let offset = CompileOptions::new("embedded.js").line_offset(9);
+	let error = rt.eval_with_options::<f64>("1;\nthrow new Error('Offset');", &offset, ()).unwrap_err();
+	assert!(matches!(error, Error::Exception(message) if message.contains("embedded.js:11")));
+
+	let calls = Rc::new(Cell::new(0));
+	let counter = Rc::clone(&calls);
+	rt.set_global_fn("hostDouble", move |cx, args| {
+		counter.set(counter.get() + 1);
+		let value = f64::from_value(cx, args.value(0).unwrap(), true, ())?;
+		Ok((value * 2.0).as_value(cx))
+	})
+	.unwrap();
+	rt.set_global_value("hostName", "embedder").unwrap();
+	rt.set_global_value("hostList", &vec![1.0, 2.0]).unwrap();
+	let result = rt
+		.eval::<String>("`${hostName}:${hostList.map(hostDouble).join(',')}`", "host.js")
+		.unwrap();
+	assert_eq!(result, "embedder:2,4");
+	assert_eq!(calls.get(), 2);
+	assert!(rt.eval_script("hostDouble('two')", "host.js").is_err());
+
+	let namespace = rt
+		.eval_module(
+			"export const answer = await Promise.resolve(42);",
+			&CompileOptions::new("./tests/scripts/inline.js"),
+		)
+		.unwrap();
+	let answer = namespace.get_as::<_, i32>(rt.cx(), "answer", true, ConversionBehavior::Default);
+	assert_eq!(answer, Some(42));
+
+	let namespace = rt.load_module(Path::new("./tests/scripts/module.js")).unwrap();
+	let joined = namespace.get_as::<_, String>(rt.cx(), "joined", true, ());
+	assert!(joined.is_some_and(|joined| joined.ends_with('b') && joined.starts_with('a')));
+	let value = namespace.get_as::<_, String>(rt.cx(), "value", true, ());
+	assert_eq!(value.as_deref(), Some("loaded"));
+
+	rt.set_time_limit(Some(Duration::from_millis(100)));
+	let error = rt
+		.eval_script("try { while (true) {} } finally { globalThis.cleaned = true; }", "loop.js")
+		.unwrap_err();
+	assert!(matches!(error, Error::Terminated));
+	assert!(rt.eval::<bool>("typeof cleaned === 'undefined'", "cleaned.js").unwrap());
+	rt.set_time_limit(None);
+
+	let handle = rt.interrupt_handle();
+	let interrupter = thread::spawn(move || {
+		thread::sleep(Duration::from_millis(50));
+		handle.terminate()
+	});
+	let error = rt.eval_script("for (;;) {}", "spin.js").unwrap_err();
+	assert!(interrupter.join().unwrap());
+	assert!(matches!(error, Error::Terminated));
+	assert_eq!(rt.eval::<f64>("1 + 1", "after.js").unwrap(), 2.0);
+
+	rt.eval_script("globalThis.done = false; setTimeout(() => (done = true), 1);", "timer.js")
+		.unwrap();
+	rt.run_event_loop_until_idle().unwrap();
+	let done = rt.global().get_as::<_, bool>(rt.cx(), "done", true, ());
+	assert_eq!(done, Some(true));
+}
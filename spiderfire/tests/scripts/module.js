@@ -0,0 +1,4 @@
+import { join } from "path";
+
+export const joined = join("a", "b");
+export const value = await new Promise(resolve => setTimeout(() => resolve("loaded"), 1));